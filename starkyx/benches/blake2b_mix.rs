@@ -0,0 +1,85 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::util::timing::TimingTree;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use starkyx::chip::register::array::ArrayRegister;
+use starkyx::chip::trace::writer::{InnerWriterData, TraceWriter};
+use starkyx::chip::uint::operations::instruction::UintInstruction;
+use starkyx::chip::uint::register::U64Register;
+use starkyx::chip::uint::util::u64_to_le_field_bytes;
+use starkyx::machine::hash::blake::blake2::BLAKE2;
+use starkyx::machine::hash::blake::blake2b::BLAKE2B;
+use starkyx::math::goldilocks::cubic::GoldilocksCubicParameters;
+use starkyx::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+use starkyx::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MixBench;
+
+/// One mix per row: the clock, the six input words and the columns of `BLAKE2B::mix_cost`.
+impl AirParameters for MixBench {
+    type Field = GoldilocksField;
+    type CubicParams = GoldilocksCubicParameters;
+    type Instruction = UintInstruction;
+
+    const NUM_FREE_COLUMNS: usize = 237;
+    const EXTENDED_COLUMNS: usize = 510;
+}
+
+fn build_mix() -> (
+    ByteStark<MixBench, CurtaPoseidonGoldilocksConfig, 2>,
+    ArrayRegister<U64Register>,
+) {
+    let mut builder = BytesBuilder::<MixBench>::new();
+    let values = builder.alloc_array::<U64Register>(6);
+    <BLAKE2B as BLAKE2<BytesBuilder<MixBench>>>::mix(
+        &mut builder,
+        &values.get(0),
+        &values.get(1),
+        &values.get(2),
+        &values.get(3),
+        &values.get(4),
+        &values.get(5),
+    );
+    (
+        builder.build::<CurtaPoseidonGoldilocksConfig, 2>(NUM_ROWS),
+        values,
+    )
+}
+
+const NUM_ROWS: usize = 1 << 10;
+
+fn bench_blake2b_mix(c: &mut Criterion) {
+    let (stark, values) = build_mix();
+
+    let mut rng = rand::thread_rng();
+    let inputs = (0..NUM_ROWS)
+        .map(|_| (0..6).map(|_| rng.gen::<u64>()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    let write_trace = || {
+        let writer = TraceWriter::new(&stark.air_data, NUM_ROWS);
+        for (i, row) in inputs.iter().enumerate() {
+            writer.write_array(&values, row.iter().map(|x| u64_to_le_field_bytes(*x)), i);
+            writer.write_row_instructions(&stark.air_data, i);
+        }
+        writer.into_inner().unwrap()
+    };
+
+    let mut group = c.benchmark_group("blake2b_mix");
+    group.sample_size(10);
+    group.bench_function("build", |b| b.iter(build_mix));
+    group.bench_function("write_trace", |b| b.iter(write_trace));
+    group.bench_function("prove", |b| {
+        let InnerWriterData { trace, public, .. } = write_trace();
+        b.iter(|| {
+            let mut timing = TimingTree::default();
+            stark.prove(&trace, &public, &mut timing).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_blake2b_mix);
+criterion_main!(benches);
@@ -0,0 +1,202 @@
+//! Serial and parallel trace generation for BLAKE2b.
+//!
+//! The `blake2b_trace` group writes the trace of the BLAKE2b machine hashing 8 one-block
+//! messages. The machine carries its compress state through memory and its compress counter
+//! through transitions from row to row, so its trace must be written serially, see
+//! `TraceWriter::write_trace_rows_parallel`. The parallel benchmark splits the messages instead,
+//! and writes the traces of 8 machines hashing one message each on the thread pool, for the same
+//! total number of rows.
+//!
+//! The `blake2b_mixes_trace` group writes a trace of BLAKE2b mixes, one mix with random inputs
+//! per row, with as many rows as the 768 mixes of the 8 messages padded to a power of two. The
+//! mixes are the bulk of the trace of the machine and only depend on their row, so their rows can
+//! be written in parallel.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use plonky2::field::goldilocks_field::GoldilocksField;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use starkyx::chip::register::array::ArrayRegister;
+use starkyx::chip::trace::writer::TraceWriter;
+use starkyx::chip::uint::operations::instruction::UintInstruction;
+use starkyx::chip::uint::register::U64Register;
+use starkyx::chip::uint::util::u64_to_le_field_bytes;
+use starkyx::machine::hash::blake::blake2::BLAKE2;
+use starkyx::machine::hash::blake::blake2b::builder::BlakeBuilder;
+use starkyx::machine::hash::blake::blake2b::padding::PaddedMessages;
+use starkyx::machine::hash::blake::blake2b::register::BLAKE2BDigestRegister;
+use starkyx::machine::hash::blake::blake2b::BLAKE2B;
+use starkyx::math::goldilocks::cubic::GoldilocksCubicParameters;
+use starkyx::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+use starkyx::prelude::*;
+
+type F = GoldilocksField;
+type C = CurtaPoseidonGoldilocksConfig;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BLAKE2BTraceBench;
+
+/// The columns of the BLAKE2b machine, see `BLAKE2B::required_columns`.
+impl AirParameters for BLAKE2BTraceBench {
+    type Field = GoldilocksField;
+    type CubicParams = GoldilocksCubicParameters;
+    type Instruction = UintInstruction;
+
+    const NUM_FREE_COLUMNS: usize = 1270;
+    const EXTENDED_COLUMNS: usize = 1464;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MixTraceBench;
+
+/// One mix per row: the clock, the six input words and the columns of `BLAKE2B::mix_cost`.
+impl AirParameters for MixTraceBench {
+    type Field = GoldilocksField;
+    type CubicParams = GoldilocksCubicParameters;
+    type Instruction = UintInstruction;
+
+    const NUM_FREE_COLUMNS: usize = 237;
+    const EXTENDED_COLUMNS: usize = 510;
+}
+
+const NUM_MESSAGES: usize = 8;
+
+/// The length of every message, which fits in one block.
+const MESSAGE_LENGTH: usize = 100;
+
+/// The 8 compresses of 12 rounds of 8 mixes of the messages, padded to a power of two.
+const NUM_MIX_ROWS: usize = (NUM_MESSAGES * 12 * 8).next_power_of_two();
+
+const CHUNK_SIZE: usize = 1 << 6;
+
+/// The BLAKE2b machine hashing a batch of messages.
+struct BLAKE2BMachine {
+    stark: ByteStark<BLAKE2BTraceBench, C, 2>,
+    padded: PaddedMessages,
+    digests: Vec<BLAKE2BDigestRegister>,
+    num_rows: usize,
+}
+
+impl BLAKE2BMachine {
+    fn new(messages: &[Vec<u8>]) -> Self {
+        let mut builder = BytesBuilder::<BLAKE2BTraceBench>::new();
+        let padded = BLAKE2B::pad_messages(&mut builder, messages);
+        let digests = builder.blake2b::<BLAKE2B>(
+            &padded.padded_chunks,
+            &padded.t_values,
+            &padded.end_bits,
+            &padded.digest_bits,
+            &padded.digest_indices,
+            &padded.num_messages,
+        );
+        let num_rows = BLAKE2B::rows_for(padded.num_compresses());
+        let stark = builder.build::<C, 2>(num_rows);
+
+        Self {
+            stark,
+            padded,
+            digests,
+            num_rows,
+        }
+    }
+
+    /// Writes the public inputs and the trace of the machine hashing `messages`, row by row.
+    fn write_trace(&self, messages: &[Vec<u8>]) -> AirWriterData<F> {
+        let air_data = &self.stark.air_data;
+        let mut writer_data = AirWriterData::new(air_data, self.num_rows);
+        let mut writer = writer_data.public_writer();
+        self.padded.write(&mut writer);
+        for (digest, message) in self.digests.iter().zip(messages) {
+            writer.write(digest, &BLAKE2B::hash(message).map(F::from_canonical_u8));
+        }
+
+        air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(self.num_rows) {
+            for i in 0..self.num_rows {
+                let mut writer = chunk.window_writer(i);
+                air_data.write_trace_instructions(&mut writer);
+            }
+        }
+        writer_data
+    }
+}
+
+fn bench_blake2b_trace(c: &mut Criterion) {
+    let mut rng = rand::thread_rng();
+    let messages = (0..NUM_MESSAGES)
+        .map(|_| {
+            (0..MESSAGE_LENGTH)
+                .map(|_| rng.gen::<u8>())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let machine = BLAKE2BMachine::new(&messages);
+    let message_machines = messages
+        .iter()
+        .map(|message| BLAKE2BMachine::new(core::slice::from_ref(message)))
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("blake2b_trace");
+    group.sample_size(10);
+    group.bench_function("serial", |b| b.iter(|| machine.write_trace(&messages)));
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            (0..NUM_MESSAGES)
+                .into_par_iter()
+                .map(|i| message_machines[i].write_trace(&messages[i..i + 1]))
+                .collect::<Vec<_>>()
+        })
+    });
+    group.finish();
+}
+
+fn bench_mixes_trace(c: &mut Criterion) {
+    let mut builder = BytesBuilder::<MixTraceBench>::new();
+    let values = builder.alloc_array::<U64Register>(6);
+    <BLAKE2B as BLAKE2<BytesBuilder<MixTraceBench>>>::mix(
+        &mut builder,
+        &values.get(0),
+        &values.get(1),
+        &values.get(2),
+        &values.get(3),
+        &values.get(4),
+        &values.get(5),
+    );
+    let stark = builder.build::<C, 2>(NUM_MIX_ROWS);
+
+    let mut rng = rand::thread_rng();
+    let inputs = (0..NUM_MIX_ROWS)
+        .map(|_| (0..6).map(|_| rng.gen::<u64>()).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let write_inputs = |values: &ArrayRegister<U64Register>| {
+        let writer = TraceWriter::new(&stark.air_data, NUM_MIX_ROWS);
+        for (i, row) in inputs.iter().enumerate() {
+            writer.write_array(values, row.iter().map(|x| u64_to_le_field_bytes(*x)), i);
+        }
+        writer
+    };
+
+    let mut group = c.benchmark_group("blake2b_mixes_trace");
+    group.sample_size(10);
+    group.bench_function("serial", |b| {
+        b.iter(|| {
+            let writer = write_inputs(&values);
+            for i in 0..NUM_MIX_ROWS {
+                writer.write_row_instructions(&stark.air_data, i);
+            }
+            writer
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            let writer = write_inputs(&values);
+            writer.write_trace_rows_parallel(&stark.air_data, CHUNK_SIZE);
+            writer
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_blake2b_trace, bench_mixes_trace);
+criterion_main!(benches);
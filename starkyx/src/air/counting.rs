@@ -0,0 +1,248 @@
+use alloc::collections::BTreeMap;
+use core::marker::PhantomData;
+
+use super::extension::cubic::CubicParser;
+use super::extension::quintic::QuinticParser;
+use super::parser::AirParser;
+use super::RAirData;
+use crate::math::prelude::*;
+use crate::polynomial::parser::PolynomialParser;
+
+/// A variable of the `CountingParser`: the degree of a value as a polynomial in the trace
+/// columns, and the latest round of the columns, challenges and global values it depends on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DegreeVar {
+    pub degree: usize,
+    pub round: usize,
+}
+
+impl DegreeVar {
+    const CONSTANT: Self = Self {
+        degree: 0,
+        round: 0,
+    };
+
+    fn combine(self, other: Self, degree: usize) -> Self {
+        Self {
+            degree,
+            round: self.round.max(other.round),
+        }
+    }
+}
+
+/// A parser counting the constraints of an AIR and their degrees, without evaluating them.
+///
+/// Evaluating an AIR with the parser gives the number of its constraints and the largest degree
+/// among them, to be compared with `RAirData::constraint_degree` before proving. Every constraint
+/// is attributed to the latest round it depends on.
+#[derive(Debug, Clone)]
+pub struct CountingParser<F> {
+    columns: Vec<DegreeVar>,
+    challenges: Vec<DegreeVar>,
+    global: Vec<DegreeVar>,
+    public: Vec<DegreeVar>,
+    histogram: Vec<BTreeMap<usize, usize>>,
+    _marker: PhantomData<F>,
+}
+
+impl<F> CountingParser<F> {
+    /// Creates a parser for the trace layout of `air`.
+    pub fn new<A: RAirData + ?Sized>(air: &A) -> Self {
+        let phases = air.commitment_phases();
+        let mut columns = vec![DegreeVar::CONSTANT; air.num_columns()];
+        let mut challenges = Vec::new();
+        let mut global = vec![DegreeVar::CONSTANT; air.num_global_values()];
+        for phase in phases.iter() {
+            let round = phase.round;
+            let var = DegreeVar { degree: 1, round };
+            columns[phase.columns_range.0..phase.columns_range.1].fill(var);
+            global[phase.global_values_range.0..phase.global_values_range.1]
+                .fill(DegreeVar { degree: 0, round });
+            // The challenges drawn after a round are used from the next round on.
+            let num_challenges = phase.challenges_range.1 - phase.challenges_range.0;
+            challenges.extend((0..num_challenges).map(|_| DegreeVar {
+                degree: 0,
+                round: round + 1,
+            }));
+        }
+
+        Self {
+            columns,
+            challenges,
+            global,
+            public: vec![DegreeVar::CONSTANT; air.num_public_inputs()],
+            histogram: vec![BTreeMap::new(); phases.len().max(1)],
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of constraints counted.
+    pub fn num_constraints(&self) -> usize {
+        self.histogram.iter().flat_map(|h| h.values()).sum()
+    }
+
+    /// The largest degree of the constraints counted, or zero if there are none.
+    pub fn max_degree(&self) -> usize {
+        self.histogram
+            .iter()
+            .filter_map(|h| h.keys().next_back())
+            .copied()
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The number of constraints of every degree, for each round.
+    pub fn round_histogram(&self) -> &[BTreeMap<usize, usize>] {
+        &self.histogram
+    }
+
+    fn count(&mut self, constraint: DegreeVar) {
+        if constraint.round >= self.histogram.len() {
+            self.histogram.resize(constraint.round + 1, BTreeMap::new());
+        }
+        *self.histogram[constraint.round]
+            .entry(constraint.degree)
+            .or_insert(0) += 1;
+    }
+}
+
+impl<F: Field> AirParser for CountingParser<F> {
+    type Field = F;
+
+    type Var = DegreeVar;
+
+    fn local_slice(&self) -> &[Self::Var] {
+        &self.columns
+    }
+
+    fn next_slice(&self) -> &[Self::Var] {
+        &self.columns
+    }
+
+    fn challenge_slice(&self) -> &[Self::Var] {
+        &self.challenges
+    }
+
+    fn global_slice(&self) -> &[Self::Var] {
+        &self.global
+    }
+
+    fn public_slice(&self) -> &[Self::Var] {
+        &self.public
+    }
+
+    fn constraint(&mut self, constraint: Self::Var) {
+        self.count(constraint)
+    }
+
+    fn constraint_transition(&mut self, constraint: Self::Var) {
+        self.count(constraint)
+    }
+
+    fn constraint_first_row(&mut self, constraint: Self::Var) {
+        self.count(constraint)
+    }
+
+    fn constraint_last_row(&mut self, constraint: Self::Var) {
+        self.count(constraint)
+    }
+
+    fn constant(&mut self, _value: Self::Field) -> Self::Var {
+        DegreeVar::CONSTANT
+    }
+
+    fn add(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        a.combine(b, a.degree.max(b.degree))
+    }
+
+    fn sub(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        a.combine(b, a.degree.max(b.degree))
+    }
+
+    fn neg(&mut self, a: Self::Var) -> Self::Var {
+        a
+    }
+
+    fn mul(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        a.combine(b, a.degree + b.degree)
+    }
+}
+
+impl<F: Field> PolynomialParser for CountingParser<F> {}
+
+impl<F: Field, E: CubicParameters<F>> CubicParser<E> for CountingParser<F> {}
+
+impl<F: Field, E: QuinticParameters<F>> QuinticParser<E> for CountingParser<F> {}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::air::fibonacci::FibonacciAir;
+    use crate::air::RAir;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::memory::time::Time;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::chip::AirParameters;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+    #[test]
+    fn test_counting_fibonacci() {
+        let air = FibonacciAir::new();
+        let mut parser = CountingParser::<GoldilocksField>::new(&air);
+        air.eval(&mut parser);
+        air.eval_global(&mut parser);
+
+        assert_eq!(parser.num_constraints(), 4);
+        assert_eq!(parser.max_degree(), 1);
+        assert_eq!(parser.round_histogram(), &[BTreeMap::from([(1, 4)])]);
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct CountingTest;
+
+    impl AirParameters for CountingTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_counting_chip() {
+        type L = CountingTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let x = builder.alloc::<ElementRegister>();
+        let y = builder.alloc::<ElementRegister>();
+        builder.assert_expressions_equal(x.expr() * x.expr() * y.expr(), y.expr());
+        let ptr = builder.uninit::<ElementRegister>();
+        builder.set(&ptr, x, &Time::zero(), None, None, None);
+
+        let (air, _) = builder.build();
+        let mut parser = CountingParser::<GoldilocksField>::new(&air);
+        air.eval(&mut parser);
+        air.eval_global(&mut parser);
+
+        assert_eq!(parser.max_degree(), 3);
+        assert!(parser.max_degree() <= air.constraint_degree());
+
+        // The cubic constraint is in the execution round, and the constraints of the memory bus
+        // depend on the challenges and are in the extended round.
+        let histogram = parser.round_histogram();
+        assert_eq!(histogram.len(), 2);
+        assert_eq!(histogram[0].get(&3), Some(&1));
+        assert!(!histogram[1].is_empty());
+        assert_eq!(
+            parser.num_constraints(),
+            histogram.iter().flat_map(|h| h.values()).sum::<usize>()
+        );
+    }
+}
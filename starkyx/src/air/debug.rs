@@ -0,0 +1,262 @@
+use super::extension::cubic::CubicParser;
+use super::extension::quintic::QuinticParser;
+use super::parser::AirParser;
+use crate::math::prelude::*;
+use crate::polynomial::parser::PolynomialParser;
+use crate::trace::window::TraceWindow;
+
+/// A constraint that does not vanish on the witness.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintFailure<F> {
+    /// The label of the constraint, as set by `DebugParser::set_label`.
+    pub label: String,
+    /// The row the constraint fails in, or `None` for a global constraint.
+    pub row: Option<usize>,
+    /// The value of the constraint.
+    pub value: F,
+}
+
+/// A parser evaluating constraints on a window of a filled trace, recording the constraints that
+/// do not vanish instead of panicking like `TraceWindowParser`.
+///
+/// The failures are recorded under the current label, which is set between constraints. See
+/// `Chip::debug_constraints` for the labels of the tags of a chip.
+#[derive(Debug, Clone)]
+pub struct DebugParser<'a, F> {
+    window: TraceWindow<'a, F>,
+    challenge_slice: &'a [F],
+    global_slice: &'a [F],
+    public_slice: &'a [F],
+    is_global: bool,
+    label: String,
+    failures: Vec<ConstraintFailure<F>>,
+}
+
+impl<'a, F> DebugParser<'a, F> {
+    /// A parser for the constraints evaluated on `window`.
+    pub fn new(
+        window: TraceWindow<'a, F>,
+        challenge_slice: &'a [F],
+        global_slice: &'a [F],
+        public_slice: &'a [F],
+    ) -> Self {
+        Self {
+            window,
+            challenge_slice,
+            global_slice,
+            public_slice,
+            is_global: false,
+            label: String::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    /// A parser for the global constraints, evaluated once over the public and global values.
+    pub fn global(challenge_slice: &'a [F], global_slice: &'a [F], public_slice: &'a [F]) -> Self {
+        Self {
+            is_global: true,
+            ..Self::new(
+                TraceWindow::empty(),
+                challenge_slice,
+                global_slice,
+                public_slice,
+            )
+        }
+    }
+
+    /// Sets the label of the failures recorded from now on.
+    pub fn set_label(&mut self, label: &str) {
+        self.label.clear();
+        self.label.push_str(label);
+    }
+
+    /// The failures recorded, in the order they were evaluated.
+    pub fn failures(&self) -> &[ConstraintFailure<F>] {
+        &self.failures
+    }
+
+    pub fn into_failures(self) -> Vec<ConstraintFailure<F>> {
+        self.failures
+    }
+
+    /// Drops the failures recorded after the first `len`.
+    pub fn truncate_failures(&mut self, len: usize) {
+        self.failures.truncate(len);
+    }
+}
+
+impl<'a, F: Field> DebugParser<'a, F> {
+    fn check(&mut self, constraint: F) {
+        if constraint != F::ZERO {
+            self.failures.push(ConstraintFailure {
+                label: self.label.clone(),
+                row: (!self.is_global).then_some(self.window.row),
+                value: constraint,
+            });
+        }
+    }
+}
+
+impl<'a, F: Field> AirParser for DebugParser<'a, F> {
+    type Field = F;
+
+    type Var = F;
+
+    fn local_slice(&self) -> &[Self::Var] {
+        self.window.local_slice
+    }
+
+    fn next_slice(&self) -> &[Self::Var] {
+        self.window.next_slice
+    }
+
+    fn challenge_slice(&self) -> &[Self::Var] {
+        self.challenge_slice
+    }
+
+    fn global_slice(&self) -> &[Self::Var] {
+        self.global_slice
+    }
+
+    fn public_slice(&self) -> &[Self::Var] {
+        self.public_slice
+    }
+
+    fn constraint(&mut self, constraint: Self::Var) {
+        self.check(constraint);
+    }
+
+    fn constraint_transition(&mut self, constraint: Self::Var) {
+        if !self.window.is_last_row {
+            self.check(constraint);
+        }
+    }
+
+    fn constraint_first_row(&mut self, constraint: Self::Var) {
+        if self.window.is_first_row {
+            self.check(constraint);
+        }
+    }
+
+    fn constraint_last_row(&mut self, constraint: Self::Var) {
+        if self.window.is_last_row {
+            self.check(constraint);
+        }
+    }
+
+    fn constant(&mut self, value: Self::Field) -> Self::Var {
+        value
+    }
+
+    fn add(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        a + b
+    }
+
+    fn sub(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        a - b
+    }
+
+    fn neg(&mut self, a: Self::Var) -> Self::Var {
+        -a
+    }
+
+    fn mul(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        a * b
+    }
+}
+
+impl<'a, F: Field> PolynomialParser for DebugParser<'a, F> {}
+
+impl<'a, F: Field, E: CubicParameters<F>> CubicParser<E> for DebugParser<'a, F> {}
+
+impl<'a, F: Field, E: QuinticParameters<F>> QuinticParser<E> for DebugParser<'a, F> {}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::air::RAir;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::chip::trace::generator::ArithmeticGenerator;
+    use crate::chip::AirParameters;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct DebugTest;
+
+    impl AirParameters for DebugTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 3;
+    }
+
+    /// Evaluates `a + b = c` and `a * b = a` with `b = 1`, tagged `sum` and `unit`, with `c` off
+    /// by one in the rows of `wrong_rows`.
+    fn debug_sum_unit(wrong_rows: &[usize]) -> Vec<ConstraintFailure<GoldilocksField>> {
+        type F = GoldilocksField;
+        type L = DebugTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let c = builder.alloc::<ElementRegister>();
+        builder.push_tag("sum");
+        builder.assert_expressions_equal(a.expr() + b.expr(), c.expr());
+        builder.pop_tag();
+        builder.push_tag("unit");
+        builder.assert_expressions_equal(a.expr() * b.expr(), a.expr());
+        builder.pop_tag();
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 4;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let (a_val, b_val) = (i, 1);
+            let c_val = a_val + b_val + wrong_rows.contains(&i) as usize;
+            writer.write(&a, &F::from_canonical_usize(a_val), i);
+            writer.write(&b, &F::from_canonical_usize(b_val), i);
+            writer.write(&c, &F::from_canonical_usize(c_val), i);
+        }
+
+        let trace = generator.trace_clone();
+        let failures = air.debug_constraints(&trace, &[], &[], &[]);
+
+        // Evaluating the whole AIR with the parser records every failing row, without labels.
+        let mut num_failures = 0;
+        for window in trace.windows() {
+            let mut parser = DebugParser::new(window, &[], &[], &[]);
+            air.eval(&mut parser);
+            num_failures += parser.failures().len();
+        }
+        assert_eq!(num_failures, wrong_rows.len());
+
+        failures
+    }
+
+    #[test]
+    fn test_debug_parser() {
+        type F = GoldilocksField;
+
+        assert!(debug_sum_unit(&[]).is_empty());
+
+        // Only the first failing row of the sum is reported.
+        let failures = debug_sum_unit(&[3, 7]);
+        assert_eq!(
+            failures,
+            vec![ConstraintFailure {
+                label: "sum".to_string(),
+                row: Some(3),
+                value: -F::ONE,
+            }]
+        );
+    }
+}
@@ -1 +1,153 @@
+use core::fmt::Debug;
+
+use self::cubic::CubicParser;
+use self::quintic::QuinticParser;
+use super::parser::AirParser;
+use crate::chip::register::cubic::CubicRegister;
+use crate::chip::register::quintic::QuinticRegister;
+use crate::chip::register::Register;
+use crate::math::extension::cubic::element::CubicElement;
+use crate::math::extension::cubic::extension::CubicExtension;
+use crate::math::extension::quintic::element::QuinticElement;
+use crate::math::extension::quintic::extension::QuinticExtension;
+use crate::math::extension::{CubicParameters, QuinticParameters};
+
 pub mod cubic;
+pub mod quintic;
+
+/// A parser for constraints over the extension field `X`, such as the cubic extension of a
+/// `CubicParser` or the quintic extension of a `QuinticParser`.
+///
+/// Arguments drawing their challenges from an extension, such as the log-derivative lookup, are
+/// generic over this trait so that the degree of the extension can be chosen by the user.
+pub trait ExtensionParser<X>: AirParser {
+    /// The coordinates of an element of the extension.
+    type Element: Debug + Copy;
+
+    /// The register holding an element of the extension.
+    type Register: Register<Value<Self::Var> = Self::Element>;
+
+    fn element_from_base_field(&mut self, value: Self::Var) -> Self::Element;
+
+    fn element_from_base_slice(&self, values: &[Self::Var]) -> Self::Element;
+
+    fn zero_extension(&mut self) -> Self::Element;
+
+    fn add_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element;
+
+    fn sub_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element;
+
+    fn mul_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element;
+
+    fn scalar_mul_extension(&mut self, a: Self::Element, scalar: Self::Var) -> Self::Element;
+
+    fn constraint_extension(&mut self, a: Self::Element);
+
+    fn constraint_extension_transition(&mut self, a: Self::Element);
+
+    fn constraint_extension_first_row(&mut self, a: Self::Element);
+
+    fn constraint_extension_last_row(&mut self, a: Self::Element);
+}
+
+impl<E: CubicParameters<AP::Field>, AP: CubicParser<E>>
+    ExtensionParser<CubicExtension<AP::Field, E>> for AP
+{
+    type Element = CubicElement<AP::Var>;
+    type Register = CubicRegister;
+
+    fn element_from_base_field(&mut self, value: Self::Var) -> Self::Element {
+        CubicParser::<E>::element_from_base_field(self, value)
+    }
+
+    fn element_from_base_slice(&self, values: &[Self::Var]) -> Self::Element {
+        CubicParser::<E>::element_from_base_slice(self, values)
+    }
+
+    fn zero_extension(&mut self) -> Self::Element {
+        CubicParser::<E>::zero_extension(self)
+    }
+
+    fn add_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element {
+        CubicParser::<E>::add_extension(self, a, b)
+    }
+
+    fn sub_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element {
+        CubicParser::<E>::sub_extension(self, a, b)
+    }
+
+    fn mul_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element {
+        CubicParser::<E>::mul_extension(self, a, b)
+    }
+
+    fn scalar_mul_extension(&mut self, a: Self::Element, scalar: Self::Var) -> Self::Element {
+        CubicParser::<E>::scalar_mul_extension(self, a, scalar)
+    }
+
+    fn constraint_extension(&mut self, a: Self::Element) {
+        CubicParser::<E>::constraint_extension(self, a)
+    }
+
+    fn constraint_extension_transition(&mut self, a: Self::Element) {
+        CubicParser::<E>::constraint_extension_transition(self, a)
+    }
+
+    fn constraint_extension_first_row(&mut self, a: Self::Element) {
+        CubicParser::<E>::constraint_extension_first_row(self, a)
+    }
+
+    fn constraint_extension_last_row(&mut self, a: Self::Element) {
+        CubicParser::<E>::constraint_extension_last_row(self, a)
+    }
+}
+
+impl<E: QuinticParameters<AP::Field>, AP: QuinticParser<E>>
+    ExtensionParser<QuinticExtension<AP::Field, E>> for AP
+{
+    type Element = QuinticElement<AP::Var>;
+    type Register = QuinticRegister;
+
+    fn element_from_base_field(&mut self, value: Self::Var) -> Self::Element {
+        QuinticParser::<E>::element_from_base_field(self, value)
+    }
+
+    fn element_from_base_slice(&self, values: &[Self::Var]) -> Self::Element {
+        QuinticParser::<E>::element_from_base_slice(self, values)
+    }
+
+    fn zero_extension(&mut self) -> Self::Element {
+        QuinticParser::<E>::zero_extension(self)
+    }
+
+    fn add_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element {
+        QuinticParser::<E>::add_extension(self, a, b)
+    }
+
+    fn sub_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element {
+        QuinticParser::<E>::sub_extension(self, a, b)
+    }
+
+    fn mul_extension(&mut self, a: Self::Element, b: Self::Element) -> Self::Element {
+        QuinticParser::<E>::mul_extension(self, a, b)
+    }
+
+    fn scalar_mul_extension(&mut self, a: Self::Element, scalar: Self::Var) -> Self::Element {
+        QuinticParser::<E>::scalar_mul_extension(self, a, scalar)
+    }
+
+    fn constraint_extension(&mut self, a: Self::Element) {
+        QuinticParser::<E>::constraint_extension(self, a)
+    }
+
+    fn constraint_extension_transition(&mut self, a: Self::Element) {
+        QuinticParser::<E>::constraint_extension_transition(self, a)
+    }
+
+    fn constraint_extension_first_row(&mut self, a: Self::Element) {
+        QuinticParser::<E>::constraint_extension_first_row(self, a)
+    }
+
+    fn constraint_extension_last_row(&mut self, a: Self::Element) {
+        QuinticParser::<E>::constraint_extension_last_row(self, a)
+    }
+}
@@ -0,0 +1,266 @@
+use crate::air::parser::AirParser;
+use crate::math::extension::quintic::element::QuinticElement;
+use crate::math::extension::quintic::extension::QuinticExtension;
+use crate::math::extension::quintic::parameters::QuinticParameters;
+use crate::math::prelude::*;
+
+pub trait QuinticParser<E: QuinticParameters<Self::Field>>: AirParser {
+    fn element_from_base_field(&mut self, value: Self::Var) -> QuinticElement<Self::Var> {
+        let zero = self.zero();
+        QuinticElement::from_base(value, zero)
+    }
+
+    fn element_from_base_slice(&self, values: &[Self::Var]) -> QuinticElement<Self::Var> {
+        QuinticElement::from_slice(values)
+    }
+
+    fn as_base_array(&self, value: QuinticElement<Self::Var>) -> [Self::Var; 5] {
+        value.0
+    }
+
+    fn one_extension(&mut self) -> QuinticElement<Self::Var> {
+        let one = self.one();
+        self.element_from_base_field(one)
+    }
+
+    fn zero_extension(&mut self) -> QuinticElement<Self::Var> {
+        let zero = self.zero();
+        QuinticElement([zero; 5])
+    }
+
+    fn constant_extension(
+        &mut self,
+        value: QuinticExtension<Self::Field, E>,
+    ) -> QuinticElement<Self::Var> {
+        QuinticElement(value.base_field_array().map(|x| self.constant(x)))
+    }
+
+    fn add_extension(
+        &mut self,
+        a: QuinticElement<Self::Var>,
+        b: QuinticElement<Self::Var>,
+    ) -> QuinticElement<Self::Var> {
+        QuinticElement(core::array::from_fn(|i| self.add(a.0[i], b.0[i])))
+    }
+
+    fn add_many_extension(
+        &mut self,
+        elements: &[QuinticElement<Self::Var>],
+    ) -> QuinticElement<Self::Var> {
+        let mut sum = self.zero_extension();
+        for element in elements {
+            sum = self.add_extension(sum, *element);
+        }
+        sum
+    }
+
+    fn sub_extension(
+        &mut self,
+        a: QuinticElement<Self::Var>,
+        b: QuinticElement<Self::Var>,
+    ) -> QuinticElement<Self::Var> {
+        QuinticElement(core::array::from_fn(|i| self.sub(a.0[i], b.0[i])))
+    }
+
+    fn mul_extension(
+        &mut self,
+        a: QuinticElement<Self::Var>,
+        b: QuinticElement<Self::Var>,
+    ) -> QuinticElement<Self::Var> {
+        let (x, y) = (a.0, b.0);
+
+        // Using X^5 = W, the coefficient of X^k is the sum of the products `x_i y_j` with
+        // `i + j = k`, plus `W` times the sum of those with `i + j = k + 5`.
+        QuinticElement(core::array::from_fn(|k| {
+            let mut low = self.zero();
+            for (x_i, y_j) in x[..=k].iter().zip(y[..=k].iter().rev()) {
+                let x_iy_j = self.mul(*x_i, *y_j);
+                low = self.add(low, x_iy_j);
+            }
+            let mut high = self.zero();
+            for (x_i, y_j) in x[k + 1..].iter().zip(y[k + 1..].iter().rev()) {
+                let x_iy_j = self.mul(*x_i, *y_j);
+                high = self.add(high, x_iy_j);
+            }
+            let w_high = self.mul_const(high, E::W);
+            self.add(low, w_high)
+        }))
+    }
+
+    fn square_extension(&mut self, a: QuinticElement<Self::Var>) -> QuinticElement<Self::Var> {
+        let x = a.0;
+        let two = Self::Field::from_canonical_u8(2);
+        let double_w = E::W * two;
+
+        let x_0x_0 = self.mul(x[0], x[0]);
+        let x_0x_1 = self.mul(x[0], x[1]);
+        let x_0x_2 = self.mul(x[0], x[2]);
+        let x_0x_3 = self.mul(x[0], x[3]);
+        let x_0x_4 = self.mul(x[0], x[4]);
+        let x_1x_1 = self.mul(x[1], x[1]);
+        let x_1x_2 = self.mul(x[1], x[2]);
+        let x_1x_3 = self.mul(x[1], x[3]);
+        let x_1x_4 = self.mul(x[1], x[4]);
+        let x_2x_2 = self.mul(x[2], x[2]);
+        let x_2x_3 = self.mul(x[2], x[3]);
+        let x_2x_4 = self.mul(x[2], x[4]);
+        let x_3x_3 = self.mul(x[3], x[3]);
+        let x_3x_4 = self.mul(x[3], x[4]);
+        let x_4x_4 = self.mul(x[4], x[4]);
+
+        let mut z_0 = self.add(x_1x_4, x_2x_3);
+        z_0 = self.mul_const(z_0, double_w);
+        z_0 = self.add(x_0x_0, z_0);
+
+        let mut z_1 = self.mul_const(x_2x_4, two);
+        z_1 = self.add(z_1, x_3x_3);
+        z_1 = self.mul_const(z_1, E::W);
+        let x_0x_1_2 = self.mul_const(x_0x_1, two);
+        z_1 = self.add(x_0x_1_2, z_1);
+
+        let mut z_2 = self.mul_const(x_3x_4, double_w);
+        let x_0x_2_2 = self.mul_const(x_0x_2, two);
+        z_2 = self.add(z_2, x_0x_2_2);
+        z_2 = self.add(z_2, x_1x_1);
+
+        let mut z_3 = self.add(x_0x_3, x_1x_2);
+        z_3 = self.mul_const(z_3, two);
+        let w_x_4x_4 = self.mul_const(x_4x_4, E::W);
+        z_3 = self.add(z_3, w_x_4x_4);
+
+        let mut z_4 = self.add(x_0x_4, x_1x_3);
+        z_4 = self.mul_const(z_4, two);
+        z_4 = self.add(z_4, x_2x_2);
+
+        QuinticElement([z_0, z_1, z_2, z_3, z_4])
+    }
+
+    fn scalar_mul_extension(
+        &mut self,
+        a: QuinticElement<Self::Var>,
+        scalar: Self::Var,
+    ) -> QuinticElement<Self::Var> {
+        QuinticElement(a.0.map(|x| self.mul(x, scalar)))
+    }
+
+    fn neg_extension(&mut self, a: QuinticElement<Self::Var>) -> QuinticElement<Self::Var> {
+        QuinticElement(a.0.map(|x| self.neg(x)))
+    }
+
+    fn constraint_extension(&mut self, a: QuinticElement<Self::Var>) {
+        let a_arr = self.as_base_array(a);
+        for a in a_arr {
+            self.constraint(a);
+        }
+    }
+
+    fn constraint_extension_transition(&mut self, a: QuinticElement<Self::Var>) {
+        let a_arr = self.as_base_array(a);
+        for a in a_arr {
+            self.constraint_transition(a);
+        }
+    }
+
+    fn constraint_extension_first_row(&mut self, a: QuinticElement<Self::Var>) {
+        let a_arr = self.as_base_array(a);
+        for a in a_arr {
+            self.constraint_first_row(a);
+        }
+    }
+
+    fn constraint_extension_last_row(&mut self, a: QuinticElement<Self::Var>) {
+        let a_arr = self.as_base_array(a);
+        for a in a_arr {
+            self.constraint_last_row(a);
+        }
+    }
+
+    fn assert_eq_extension(&mut self, a: QuinticElement<Self::Var>, b: QuinticElement<Self::Var>) {
+        let c = self.sub_extension(a, b);
+        self.constraint_extension(c);
+    }
+
+    fn assert_eq_extension_first_row(
+        &mut self,
+        a: QuinticElement<Self::Var>,
+        b: QuinticElement<Self::Var>,
+    ) {
+        let c = self.sub_extension(a, b);
+        self.constraint_extension_first_row(c);
+    }
+
+    fn assert_eq_extension_last_row(
+        &mut self,
+        a: QuinticElement<Self::Var>,
+        b: QuinticElement<Self::Var>,
+    ) {
+        let c = self.sub_extension(a, b);
+        self.constraint_extension_last_row(c);
+    }
+
+    fn assert_eq_extension_transition(
+        &mut self,
+        a: QuinticElement<Self::Var>,
+        b: QuinticElement<Self::Var>,
+    ) {
+        let c = self.sub_extension(a, b);
+        self.constraint_extension_transition(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::math::goldilocks::quintic::{GoldilocksQuinticParameters, GF5};
+    use crate::trace::window::TraceWindow;
+    use crate::trace::window_parser::TraceWindowParser;
+
+    type F = GoldilocksField;
+    type E = GoldilocksQuinticParameters;
+
+    #[test]
+    fn test_quintic_parser_arithmetic() {
+        let window = TraceWindow {
+            local_slice: &[],
+            next_slice: &[],
+            row: 0,
+            is_first_row: true,
+            is_last_row: true,
+        };
+        let mut parser = TraceWindowParser::<F>::new(window, &[], &[], &[]);
+
+        for _ in 0..100 {
+            let a = GF5::rand();
+            let b = GF5::rand();
+            let s = F::rand();
+            let (a_el, b_el) = (a.0, b.0);
+
+            let check = |value: QuinticElement<F>, expected: GF5| {
+                assert_eq!(value, expected.0);
+            };
+            check(
+                QuinticParser::<E>::add_extension(&mut parser, a_el, b_el),
+                a + b,
+            );
+            check(
+                QuinticParser::<E>::sub_extension(&mut parser, a_el, b_el),
+                a - b,
+            );
+            check(
+                QuinticParser::<E>::mul_extension(&mut parser, a_el, b_el),
+                a * b,
+            );
+            check(
+                QuinticParser::<E>::square_extension(&mut parser, a_el),
+                a.square(),
+            );
+            check(
+                QuinticParser::<E>::scalar_mul_extension(&mut parser, a_el, s),
+                a * s,
+            );
+            check(QuinticParser::<E>::neg_extension(&mut parser, a_el), -a);
+        }
+    }
+}
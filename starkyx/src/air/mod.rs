@@ -1,14 +1,20 @@
+pub mod counting;
 pub mod curta_air;
+pub mod debug;
 pub mod extension;
 pub mod opening;
 pub mod parser;
+pub mod plonk;
+pub mod serializable;
 
 #[cfg(test)]
 pub mod fibonacci;
 
+use anyhow::{ensure, Result};
 use parser::AirParser;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct RoundDatum {
     /// The number of columns generated in this round
     pub num_columns: usize,
@@ -18,6 +24,38 @@ pub struct RoundDatum {
     pub num_challenges: usize,
 }
 
+/// A phase of the commit-and-challenge loop of the prover.
+///
+/// In every phase, the columns and global values generated in a round are committed to, and the
+/// challenges of the following rounds are then drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CommitmentPhase {
+    /// The round committed in this phase
+    pub round: usize,
+    /// The range of the trace columns committed in this phase
+    pub columns_range: (usize, usize),
+    /// The range of global values observed in this phase
+    pub global_values_range: (usize, usize),
+    /// The range of the challenges drawn after the commitment
+    pub challenges_range: (usize, usize),
+}
+
+/// The layout data of an AIR, which can be shipped alongside a proof so that a verifier can
+/// reconstruct the trace layout without the builder.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AirMetadata {
+    /// The version of the metadata format
+    pub version: u32,
+    /// The data needed for each round
+    pub round_data: Vec<RoundDatum>,
+    /// The number of public inputs
+    pub num_public_inputs: usize,
+    /// The maximal constraint degree
+    pub constraint_degree: usize,
+    /// The width of the trace
+    pub width: usize,
+}
+
 pub trait AirConstraint<AP: AirParser> {
     /// Evaluation of the vanishing polynomials.
     fn eval(&self, parser: &mut AP);
@@ -49,6 +87,41 @@ pub trait RAirData {
             .sum()
     }
 
+    /// The ordered list of commitment phases, one for each round.
+    fn commitment_phases(&self) -> Vec<CommitmentPhase> {
+        let mut num_columns = 0;
+        let mut num_challenges = 0;
+        self.round_data()
+            .iter()
+            .enumerate()
+            .map(|(round, datum)| {
+                let phase = CommitmentPhase {
+                    round,
+                    columns_range: (num_columns, num_columns + datum.num_columns),
+                    global_values_range: datum.global_values_range,
+                    challenges_range: (num_challenges, num_challenges + datum.num_challenges),
+                };
+                num_columns += datum.num_columns;
+                num_challenges += datum.num_challenges;
+                phase
+            })
+            .collect()
+    }
+
+    /// The label of every constraint entry evaluated by `RAir::eval`, in order, to map an entry
+    /// back to where it was registered. An entry can emit several constraints to the parser, so
+    /// the labels are indexed by entry and not by emitted constraint. An AIR without labels
+    /// returns no entries.
+    fn constraint_entry_labels(&self) -> Vec<Option<&str>> {
+        Vec::new()
+    }
+
+    /// The label of every constraint entry evaluated by `RAir::eval_global`, like
+    /// `constraint_entry_labels`.
+    fn global_constraint_entry_labels(&self) -> Vec<Option<&str>> {
+        Vec::new()
+    }
+
     fn quotient_degree_factor(&self) -> usize {
         1.max(self.constraint_degree() - 1)
     }
@@ -75,3 +148,180 @@ impl RoundDatum {
         }
     }
 }
+
+impl AirMetadata {
+    /// The current version of the metadata format.
+    pub const VERSION: u32 = 1;
+
+    pub fn new<A: RAirData>(air: &A) -> Self {
+        Self {
+            version: Self::VERSION,
+            round_data: air.round_data(),
+            num_public_inputs: air.num_public_inputs(),
+            constraint_degree: air.constraint_degree(),
+            width: air.width(),
+        }
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Deserializes the metadata, checking that it was serialized with a supported version.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let metadata: Self = bincode::deserialize(bytes)?;
+        metadata.check_version()?;
+        Ok(metadata)
+    }
+
+    pub fn check_version(&self) -> Result<()> {
+        ensure!(
+            self.version == Self::VERSION,
+            "Unsupported AIR metadata version {}, expected {}",
+            self.version,
+            Self::VERSION
+        );
+        Ok(())
+    }
+}
+
+impl RAirData for AirMetadata {
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    fn constraint_degree(&self) -> usize {
+        self.constraint_degree
+    }
+
+    fn round_data(&self) -> Vec<RoundDatum> {
+        self.round_data.clone()
+    }
+
+    fn num_public_inputs(&self) -> usize {
+        self.num_public_inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::memory::time::Time;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::chip::AirParameters;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct CommitmentPhasesTest;
+
+    impl AirParameters for CommitmentPhasesTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_commitment_phases_of_chip() {
+        type L = CommitmentPhasesTest;
+
+        // A chip whose memory bus draws challenges after the execution trace.
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let x = builder.alloc::<ElementRegister>();
+        let y = builder.alloc::<ElementRegister>();
+        builder.assert_expressions_equal(x.expr() * y.expr(), y.expr());
+        let ptr = builder.uninit::<ElementRegister>();
+        builder.set(&ptr, x, &Time::zero(), None, None, None);
+        let (air, _) = builder.build();
+        assert!(air.num_challenges > 0);
+
+        // The metadata shipped with a proof gives the phases of the AIR.
+        let metadata =
+            AirMetadata::from_bytes(&AirMetadata::new(&air).to_bytes().unwrap()).unwrap();
+        let phases = metadata.commitment_phases();
+        assert_eq!(phases, air.commitment_phases());
+
+        // The execution trace is committed before the challenges are drawn, and the extended
+        // trace, which holds the global values of the bus, after.
+        let execution_trace_length = air.execution_trace_length;
+        assert_eq!(
+            phases,
+            vec![
+                CommitmentPhase {
+                    round: 0,
+                    columns_range: (0, execution_trace_length),
+                    global_values_range: (0, 0),
+                    challenges_range: (0, air.num_challenges),
+                },
+                CommitmentPhase {
+                    round: 1,
+                    columns_range: (execution_trace_length, L::num_columns()),
+                    global_values_range: (0, air.num_global_values),
+                    challenges_range: (air.num_challenges, air.num_challenges),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_commitment_phases() {
+        // An AIR with two challenge rounds followed by a final round. The chips of the builder
+        // draw challenges once, so the layout is given directly.
+        let metadata = AirMetadata {
+            version: AirMetadata::VERSION,
+            round_data: vec![
+                RoundDatum::new(4, (0, 0), 2),
+                RoundDatum::new(3, (0, 2), 1),
+                RoundDatum::new(6, (2, 3), 0),
+            ],
+            num_public_inputs: 1,
+            constraint_degree: 3,
+            width: 13,
+        };
+
+        let phases = metadata.commitment_phases();
+        assert_eq!(
+            phases,
+            vec![
+                CommitmentPhase {
+                    round: 0,
+                    columns_range: (0, 4),
+                    global_values_range: (0, 0),
+                    challenges_range: (0, 2),
+                },
+                CommitmentPhase {
+                    round: 1,
+                    columns_range: (4, 7),
+                    global_values_range: (0, 2),
+                    challenges_range: (2, 3),
+                },
+                CommitmentPhase {
+                    round: 2,
+                    columns_range: (7, 13),
+                    global_values_range: (2, 3),
+                    challenges_range: (3, 3),
+                },
+            ]
+        );
+
+        // The phases cover all the columns, and only the challenge rounds draw challenges.
+        assert_eq!(
+            phases.last().unwrap().columns_range.1,
+            metadata.num_columns()
+        );
+        let challenge_rounds = phases
+            .iter()
+            .filter(|phase| phase.challenges_range.1 > phase.challenges_range.0)
+            .count();
+        assert_eq!(challenge_rounds, 2);
+    }
+}
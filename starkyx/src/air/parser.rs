@@ -1,6 +1,7 @@
 use core::fmt::Debug;
 
 use super::extension::cubic::CubicParser;
+use super::extension::quintic::QuinticParser;
 use crate::math::prelude::*;
 
 pub trait AirParser: Sized {
@@ -164,3 +165,8 @@ impl<'a, AP: AirParser> AirParser for MulParser<'a, AP> {
 
 // TODO: implement parser specific functions
 impl<'a, AP: CubicParser<E>, E: CubicParameters<AP::Field>> CubicParser<E> for MulParser<'a, AP> {}
+
+impl<'a, AP: QuinticParser<E>, E: QuinticParameters<AP::Field>> QuinticParser<E>
+    for MulParser<'a, AP>
+{
+}
@@ -0,0 +1,514 @@
+//! Lowering of AIR constraints to a PLONKish arithmetization.
+//!
+//! The constraints of an AIR are evaluated symbolically, and every arithmetic operation becomes a
+//! gate of the standard form
+//!
+//! `q_l * a + q_r * b + q_o * c + q_m * a * b + q_c = 0`
+//!
+//! over three wires `a`, `b` and `c`. The gates of the per-row constraints form a template which
+//! is applied to every row of the trace: a `Local` wire refers to a column of the current row and a
+//! `Next` wire to the same column of the next row, i.e. a rotation by one. Intermediate values are
+//! assigned to `Internal` wires, which are fresh advice values of every row, and the positions of
+//! the gates sharing a wire are tied together by copy constraints.
+//!
+//! Arguments spanning several rows, such as the memory and lookup arguments of a `Chip`, are not
+//! lowered to gates but kept as multiset and lookup arguments over wires, which a PLONKish backend
+//! can prove with its own permutation and lookup arguments.
+
+use serde::{Deserialize, Serialize};
+
+use super::extension::cubic::CubicParser;
+use super::extension::quintic::QuinticParser;
+use super::parser::AirParser;
+use super::RAir;
+use crate::math::prelude::*;
+use crate::polynomial::parser::PolynomialParser;
+
+/// A wire of a PLONKish circuit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum PlonkWire {
+    /// A column of the current row.
+    Local(usize),
+    /// A column of the next row.
+    Next(usize),
+    /// A verifier challenge.
+    Challenge(usize),
+    /// A global value of the AIR.
+    Global(usize),
+    /// A public input.
+    Public(usize),
+    /// An intermediate value computed by a gate.
+    Internal(usize),
+}
+
+/// The rows on which a gate is enforced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PlonkDomain {
+    /// Every row of the trace.
+    EveryRow,
+    /// Every row of the trace but the last.
+    Transition,
+    /// The first row of the trace.
+    FirstRow,
+    /// The last row of the trace.
+    LastRow,
+    /// Enforced once, over the challenges, global values and public inputs.
+    Global,
+}
+
+/// A gate `q_l * a + q_r * b + q_o * c + q_m * a * b + q_c = 0`.
+///
+/// Unused wires are `None`, and their selectors are zero.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlonkGate<F> {
+    pub domain: PlonkDomain,
+    pub wires: [Option<PlonkWire>; 3],
+    pub q_l: F,
+    pub q_r: F,
+    pub q_o: F,
+    pub q_m: F,
+    pub q_c: F,
+}
+
+/// The position of a wire in the gate list, as a gate index and a slot in `0..3`.
+pub type WirePosition = (usize, usize);
+
+/// The multiplicity of an entry of a multiset or lookup argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PlonkMultiplicity {
+    One,
+    MinusOne,
+    Wire(PlonkWire),
+    /// The negation of the value of a wire.
+    NegWire(PlonkWire),
+}
+
+/// A tuple of wires counted with a multiplicity.
+///
+/// An entry whose wires are all public inputs, global values or challenges is counted once, and
+/// an entry over trace columns is counted in every row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PlonkEntry {
+    pub tuple: Vec<PlonkWire>,
+    pub multiplicity: PlonkMultiplicity,
+}
+
+/// A multiset argument, asserting that every tuple is counted with a total multiplicity of zero,
+/// i.e. that the entries with positive multiplicities are a permutation of the entries with
+/// negative multiplicities.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlonkMultiset {
+    pub entries: Vec<PlonkEntry>,
+}
+
+/// A lookup argument, asserting that the tuples of `values` appear in the table, with the table
+/// tuple `table[i]` looked up `multiplicities[i]` times in every row.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlonkLookup {
+    pub table: Vec<Vec<PlonkWire>>,
+    pub multiplicities: Vec<PlonkWire>,
+    pub values: Vec<PlonkEntry>,
+}
+
+/// A variable of the `PlonkParser`, either a wire or a constant folded into the selectors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlonkVar<F> {
+    Wire(PlonkWire),
+    Constant(F),
+}
+
+/// A parser lowering the constraints it evaluates into PLONK gates.
+#[derive(Debug, Clone)]
+pub struct PlonkParser<F> {
+    local: Vec<PlonkVar<F>>,
+    next: Vec<PlonkVar<F>>,
+    challenges: Vec<PlonkVar<F>>,
+    global: Vec<PlonkVar<F>>,
+    public: Vec<PlonkVar<F>>,
+    global_mode: bool,
+    num_internal_wires: usize,
+    gates: Vec<PlonkGate<F>>,
+}
+
+/// The gates, copy constraints and arguments of an AIR lowered to a PLONKish arithmetization.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlonkCircuit<F> {
+    pub num_columns: usize,
+    pub num_challenges: usize,
+    pub num_global_values: usize,
+    pub num_public_inputs: usize,
+    pub num_internal_wires: usize,
+    pub gates: Vec<PlonkGate<F>>,
+    /// Pairs of gate positions holding the same wire.
+    ///
+    /// The positions of every wire are chained, so the copy constraints of a wire appearing in `k`
+    /// positions are `k - 1` pairs of consecutive positions.
+    pub copy_constraints: Vec<(WirePosition, WirePosition)>,
+    pub multisets: Vec<PlonkMultiset>,
+    pub lookups: Vec<PlonkLookup>,
+}
+
+impl<F: Field> PlonkParser<F> {
+    pub fn new(
+        num_columns: usize,
+        num_challenges: usize,
+        num_global_values: usize,
+        num_public_inputs: usize,
+    ) -> Self {
+        let wires = |n: usize, wire: fn(usize) -> PlonkWire| {
+            (0..n).map(|i| PlonkVar::Wire(wire(i))).collect::<Vec<_>>()
+        };
+        Self {
+            local: wires(num_columns, PlonkWire::Local),
+            next: wires(num_columns, PlonkWire::Next),
+            challenges: wires(num_challenges, PlonkWire::Challenge),
+            global: wires(num_global_values, PlonkWire::Global),
+            public: wires(num_public_inputs, PlonkWire::Public),
+            global_mode: false,
+            num_internal_wires: 0,
+            gates: Vec::new(),
+        }
+    }
+
+    /// Lowers the per-row and global constraints of `air`.
+    pub fn eval_air<A: RAir<Self>>(&mut self, air: &A) {
+        air.eval(self);
+        self.set_global(true);
+        air.eval_global(self);
+        self.set_global(false);
+    }
+
+    /// Sets whether the constraints evaluated next are global constraints, enforced once.
+    pub fn set_global(&mut self, global: bool) {
+        self.global_mode = global;
+    }
+
+    /// Finishes the lowering, computing the copy constraints between the gates.
+    pub fn into_circuit(self) -> PlonkCircuit<F> {
+        let mut positions = Vec::<(PlonkWire, WirePosition)>::new();
+        for (i, gate) in self.gates.iter().enumerate() {
+            for (slot, wire) in gate.wires.iter().enumerate() {
+                if let Some(wire) = wire {
+                    positions.push((*wire, (i, slot)));
+                }
+            }
+        }
+        positions.sort();
+        let copy_constraints = positions
+            .windows(2)
+            .filter(|pair| pair[0].0 == pair[1].0)
+            .map(|pair| (pair[0].1, pair[1].1))
+            .collect();
+
+        PlonkCircuit {
+            num_columns: self.local.len(),
+            num_challenges: self.challenges.len(),
+            num_global_values: self.global.len(),
+            num_public_inputs: self.public.len(),
+            num_internal_wires: self.num_internal_wires,
+            gates: self.gates,
+            copy_constraints,
+            multisets: Vec::new(),
+            lookups: Vec::new(),
+        }
+    }
+
+    fn definition_domain(&self) -> PlonkDomain {
+        if self.global_mode {
+            PlonkDomain::Global
+        } else {
+            PlonkDomain::EveryRow
+        }
+    }
+
+    /// Adds the gate `q_l * a + q_r * b + q_m * a * b + q_c = c` for a new internal wire `c`.
+    fn define(
+        &mut self,
+        a: Option<PlonkWire>,
+        b: Option<PlonkWire>,
+        q_l: F,
+        q_r: F,
+        q_m: F,
+        q_c: F,
+    ) -> PlonkVar<F> {
+        let output = PlonkWire::Internal(self.num_internal_wires);
+        self.num_internal_wires += 1;
+        self.gates.push(PlonkGate {
+            domain: self.definition_domain(),
+            wires: [a, b, Some(output)],
+            q_l,
+            q_r,
+            q_o: -F::ONE,
+            q_m,
+            q_c,
+        });
+        PlonkVar::Wire(output)
+    }
+
+    /// Adds the gate `a = 0` on `domain`, or once if the constraint is a global one.
+    fn constrain(&mut self, var: PlonkVar<F>, domain: PlonkDomain) {
+        let domain = if self.global_mode {
+            PlonkDomain::Global
+        } else {
+            domain
+        };
+        let (wire, q_l, q_c) = match var {
+            PlonkVar::Wire(wire) => (Some(wire), F::ONE, F::ZERO),
+            // A nonzero constant constraint can't be satisfied, and is kept as is.
+            PlonkVar::Constant(value) if value != F::ZERO => (None, F::ZERO, value),
+            PlonkVar::Constant(_) => return,
+        };
+        self.gates.push(PlonkGate {
+            domain,
+            wires: [wire, None, None],
+            q_l,
+            q_r: F::ZERO,
+            q_o: F::ZERO,
+            q_m: F::ZERO,
+            q_c,
+        });
+    }
+
+    /// Adds `coeff_a * a + coeff_b * b`, folding constants.
+    fn linear(&mut self, a: PlonkVar<F>, coeff_a: F, b: PlonkVar<F>, coeff_b: F) -> PlonkVar<F> {
+        match (a, b) {
+            (PlonkVar::Constant(a), PlonkVar::Constant(b)) => {
+                PlonkVar::Constant(a * coeff_a + b * coeff_b)
+            }
+            (PlonkVar::Wire(a), PlonkVar::Constant(b)) => {
+                self.define(Some(a), None, coeff_a, F::ZERO, F::ZERO, b * coeff_b)
+            }
+            (PlonkVar::Constant(a), PlonkVar::Wire(b)) => {
+                self.define(Some(b), None, coeff_b, F::ZERO, F::ZERO, a * coeff_a)
+            }
+            (PlonkVar::Wire(a), PlonkVar::Wire(b)) => {
+                self.define(Some(a), Some(b), coeff_a, coeff_b, F::ZERO, F::ZERO)
+            }
+        }
+    }
+}
+
+impl<F: Field> AirParser for PlonkParser<F> {
+    type Field = F;
+
+    type Var = PlonkVar<F>;
+
+    fn local_slice(&self) -> &[Self::Var] {
+        &self.local
+    }
+
+    fn next_slice(&self) -> &[Self::Var] {
+        &self.next
+    }
+
+    fn challenge_slice(&self) -> &[Self::Var] {
+        &self.challenges
+    }
+
+    fn global_slice(&self) -> &[Self::Var] {
+        &self.global
+    }
+
+    fn public_slice(&self) -> &[Self::Var] {
+        &self.public
+    }
+
+    fn constraint(&mut self, constraint: Self::Var) {
+        self.constrain(constraint, PlonkDomain::EveryRow)
+    }
+
+    fn constraint_transition(&mut self, constraint: Self::Var) {
+        self.constrain(constraint, PlonkDomain::Transition)
+    }
+
+    fn constraint_first_row(&mut self, constraint: Self::Var) {
+        self.constrain(constraint, PlonkDomain::FirstRow)
+    }
+
+    fn constraint_last_row(&mut self, constraint: Self::Var) {
+        self.constrain(constraint, PlonkDomain::LastRow)
+    }
+
+    fn constant(&mut self, value: Self::Field) -> Self::Var {
+        PlonkVar::Constant(value)
+    }
+
+    fn add(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.linear(a, F::ONE, b, F::ONE)
+    }
+
+    fn sub(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.linear(a, F::ONE, b, -F::ONE)
+    }
+
+    fn neg(&mut self, a: Self::Var) -> Self::Var {
+        match a {
+            PlonkVar::Constant(a) => PlonkVar::Constant(-a),
+            PlonkVar::Wire(a) => self.define(Some(a), None, -F::ONE, F::ZERO, F::ZERO, F::ZERO),
+        }
+    }
+
+    fn mul(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        match (a, b) {
+            (PlonkVar::Constant(a), PlonkVar::Constant(b)) => PlonkVar::Constant(a * b),
+            (PlonkVar::Wire(a), PlonkVar::Constant(b))
+            | (PlonkVar::Constant(b), PlonkVar::Wire(a)) => {
+                self.define(Some(a), None, b, F::ZERO, F::ZERO, F::ZERO)
+            }
+            (PlonkVar::Wire(a), PlonkVar::Wire(b)) => {
+                self.define(Some(a), Some(b), F::ZERO, F::ZERO, F::ONE, F::ZERO)
+            }
+        }
+    }
+}
+
+impl<F: Field> PolynomialParser for PlonkParser<F> {}
+
+impl<F: Field, E: CubicParameters<F>> CubicParser<E> for PlonkParser<F> {}
+
+impl<F: Field, E: QuinticParameters<F>> QuinticParser<E> for PlonkParser<F> {}
+
+impl<F: Field> PlonkCircuit<F> {
+    /// Lowers the constraints of `air` to PLONK gates.
+    pub fn from_air<A: RAir<PlonkParser<F>>>(air: &A) -> Self {
+        let num_challenges = air.round_data().iter().map(|d| d.num_challenges).sum();
+        let mut parser = PlonkParser::new(
+            air.num_columns(),
+            num_challenges,
+            air.num_global_values(),
+            air.num_public_inputs(),
+        );
+        parser.eval_air(air);
+        parser.into_circuit()
+    }
+
+    /// The number of gates enforced on `domain`.
+    pub fn num_gates_in(&self, domain: PlonkDomain) -> usize {
+        self.gates.iter().filter(|g| g.domain == domain).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::air::fibonacci::FibonacciAir;
+    use crate::air::RAirData;
+
+    /// Evaluates the gates of `circuit` on the rows `row` and `next_row`, returning the gates which
+    /// do not vanish.
+    fn failing_gates<F: Field>(
+        circuit: &PlonkCircuit<F>,
+        row: &[F],
+        next_row: &[F],
+        public: &[F],
+        internal: &[F],
+        domains: &[PlonkDomain],
+    ) -> Vec<usize> {
+        let value = |wire: PlonkWire| match wire {
+            PlonkWire::Local(i) => row[i],
+            PlonkWire::Next(i) => next_row[i],
+            PlonkWire::Public(i) => public[i],
+            PlonkWire::Internal(i) => internal[i],
+            PlonkWire::Challenge(_) | PlonkWire::Global(_) => unreachable!(),
+        };
+        circuit
+            .gates
+            .iter()
+            .enumerate()
+            .filter(|(_, gate)| domains.contains(&gate.domain))
+            .filter(|(_, gate)| {
+                let [a, b, c] = gate.wires.map(|w| w.map(value).unwrap_or(F::ZERO));
+                gate.q_l * a + gate.q_r * b + gate.q_o * c + gate.q_m * a * b + gate.q_c != F::ZERO
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Computes the internal wires of `circuit` on the given rows, in the order of the gates.
+    fn internal_wires<F: Field>(
+        circuit: &PlonkCircuit<F>,
+        row: &[F],
+        next_row: &[F],
+        public: &[F],
+    ) -> Vec<F> {
+        let mut internal = vec![F::ZERO; circuit.num_internal_wires];
+        for gate in circuit.gates.iter() {
+            if let Some(PlonkWire::Internal(out)) = gate.wires[2] {
+                let value = |wire: Option<PlonkWire>| match wire {
+                    Some(PlonkWire::Local(i)) => row[i],
+                    Some(PlonkWire::Next(i)) => next_row[i],
+                    Some(PlonkWire::Public(i)) => public[i],
+                    Some(PlonkWire::Internal(i)) => internal[i],
+                    _ => F::ZERO,
+                };
+                let (a, b) = (value(gate.wires[0]), value(gate.wires[1]));
+                internal[out] = gate.q_l * a + gate.q_r * b + gate.q_m * a * b + gate.q_c;
+            }
+        }
+        internal
+    }
+
+    #[test]
+    fn test_fibonacci_to_plonk() {
+        type F = GoldilocksField;
+
+        let air = FibonacciAir::new();
+        let circuit = PlonkCircuit::<F>::from_air(&air);
+
+        // Two public input checks on the first row, with one gate for each difference and one for
+        // each constraint, and the two transitions with two and three gates respectively.
+        assert_eq!(circuit.gates.len(), 9);
+        assert_eq!(circuit.num_gates_in(PlonkDomain::FirstRow), 2);
+        assert_eq!(circuit.num_gates_in(PlonkDomain::Transition), 2);
+        assert_eq!(circuit.num_gates_in(PlonkDomain::EveryRow), 5);
+        assert_eq!(circuit.num_internal_wires, 5);
+        assert_eq!(circuit.num_columns, air.width());
+        assert_eq!(circuit.num_public_inputs, 3);
+        // Every internal wire is defined once and used once, `local_0` is used by two gates,
+        // `local_1` by three and the other trace and public wires by one.
+        assert_eq!(circuit.copy_constraints.len(), 5 + 1 + 2);
+        assert!(circuit.multisets.is_empty() && circuit.lookups.is_empty());
+
+        let num_rows = 1 << 4;
+        let trace = FibonacciAir::generate_trace(F::ZERO, F::ONE, num_rows);
+        let public = [
+            F::ZERO,
+            F::ONE,
+            FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE),
+        ];
+        for (i, window) in trace.windows().enumerate() {
+            let internal = internal_wires(&circuit, window.local_slice, window.next_slice, &public);
+            let mut domains = vec![PlonkDomain::EveryRow];
+            if i == 0 {
+                domains.push(PlonkDomain::FirstRow);
+            }
+            if !window.is_last_row {
+                domains.push(PlonkDomain::Transition);
+            }
+            let failing = failing_gates(
+                &circuit,
+                window.local_slice,
+                window.next_slice,
+                &public,
+                &internal,
+                &domains,
+            );
+            assert!(failing.is_empty(), "Gates {:?} fail on row {}", failing, i);
+        }
+
+        // A wrong transition is caught by a gate.
+        let (row, next_row) = ([F::ONE, F::ONE], [F::ONE, F::ONE + F::ONE + F::ONE]);
+        let internal = internal_wires(&circuit, &row, &next_row, &public);
+        let failing = failing_gates(
+            &circuit,
+            &row,
+            &next_row,
+            &public,
+            &internal,
+            &[PlonkDomain::EveryRow, PlonkDomain::Transition],
+        );
+        assert_eq!(failing.len(), 1);
+    }
+}
@@ -0,0 +1,181 @@
+use anyhow::{ensure, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::parser::AirParser;
+use super::{RAir, RAirData, RoundDatum};
+use crate::machine::builder::public::PublicInputs;
+
+/// A built AIR bundled with its round data and the layout of its public inputs, which can be
+/// shipped to a verifier so that it loads the AIR without running the builder.
+///
+/// The constraints are the ones serialized with `air`, e.g. all the constraints of a `Chip`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SerializableAir<A> {
+    /// The version of the serialization format
+    pub version: u32,
+    air: A,
+    round_data: Vec<RoundDatum>,
+    public_inputs: PublicInputs,
+}
+
+impl<A: RAirData + Serialize + DeserializeOwned> SerializableAir<A> {
+    /// The current version of the serialization format.
+    pub const VERSION: u32 = 1;
+
+    pub fn new(air: A, public_inputs: PublicInputs) -> Self {
+        Self {
+            version: Self::VERSION,
+            round_data: air.round_data(),
+            air,
+            public_inputs,
+        }
+    }
+
+    pub fn air(&self) -> &A {
+        &self.air
+    }
+
+    pub fn into_air(self) -> A {
+        self.air
+    }
+
+    /// The layout of the named public inputs.
+    pub fn public_inputs(&self) -> &PublicInputs {
+        &self.public_inputs
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Deserializes the AIR, checking the version of the format and that the round data and the
+    /// public inputs agree with the AIR.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let air: Self = bincode::deserialize(bytes)?;
+        ensure!(
+            air.version == Self::VERSION,
+            "Unsupported serialized AIR version {}, expected {}",
+            air.version,
+            Self::VERSION
+        );
+        ensure!(
+            air.round_data == air.air.round_data(),
+            "The round data does not match the AIR"
+        );
+        ensure!(
+            air.public_inputs.num_public_inputs() <= air.air.num_public_inputs(),
+            "The layout has {} public inputs, but the AIR only has {}",
+            air.public_inputs.num_public_inputs(),
+            air.air.num_public_inputs()
+        );
+        Ok(air)
+    }
+}
+
+impl<A: RAirData> RAirData for SerializableAir<A> {
+    fn width(&self) -> usize {
+        self.air.width()
+    }
+
+    fn constraint_degree(&self) -> usize {
+        self.air.constraint_degree()
+    }
+
+    fn round_data(&self) -> Vec<RoundDatum> {
+        self.round_data.clone()
+    }
+
+    fn num_public_inputs(&self) -> usize {
+        self.air.num_public_inputs()
+    }
+
+    fn constraint_entry_labels(&self) -> Vec<Option<&str>> {
+        self.air.constraint_entry_labels()
+    }
+
+    fn global_constraint_entry_labels(&self) -> Vec<Option<&str>> {
+        self.air.global_constraint_entry_labels()
+    }
+
+    fn quotient_degree_factor(&self) -> usize {
+        self.air.quotient_degree_factor()
+    }
+}
+
+impl<AP: AirParser, A: RAir<AP>> RAir<AP> for SerializableAir<A> {
+    fn eval(&self, parser: &mut AP) {
+        self.air.eval(parser)
+    }
+
+    fn eval_global(&self, parser: &mut AP) {
+        self.air.eval_global(parser)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::air::fibonacci::FibonacciAir;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::memory::MemorySlice;
+    use crate::chip::register::Register;
+    use crate::math::prelude::*;
+    use crate::trace::window_parser::TraceWindowParser;
+
+    fn fibonacci_public_inputs() -> PublicInputs {
+        let mut public_inputs = PublicInputs::new();
+        for (i, name) in ["x0", "x1", "result"].iter().enumerate() {
+            let register = ElementRegister::from_register(MemorySlice::Public(i, 1));
+            public_inputs.insert(name, &register);
+        }
+        public_inputs
+    }
+
+    #[test]
+    fn test_serializable_air_round_trip() {
+        type F = GoldilocksField;
+
+        let air = FibonacciAir::new();
+        let serializable = SerializableAir::new(air, fibonacci_public_inputs());
+        let bytes = serializable.to_bytes().unwrap();
+        let loaded = SerializableAir::<FibonacciAir>::from_bytes(&bytes).unwrap();
+
+        assert_eq!(loaded.width(), air.width());
+        assert_eq!(loaded.constraint_degree(), air.constraint_degree());
+        assert_eq!(loaded.num_rounds(), air.num_rounds());
+        assert_eq!(loaded.round_data(), air.round_data());
+        assert_eq!(loaded.num_public_inputs(), air.num_public_inputs());
+        assert_eq!(loaded.public_inputs(), &fibonacci_public_inputs());
+        assert_eq!(loaded.air(), &air);
+
+        // The loaded AIR evaluates the constraints of the original one.
+        let num_rows = 1 << 5usize;
+        let public_inputs = [
+            F::ZERO,
+            F::ONE,
+            FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE),
+        ];
+        let trace = FibonacciAir::generate_trace(F::ZERO, F::ONE, num_rows);
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &public_inputs);
+            loaded.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_serializable_air_checks() {
+        let air = FibonacciAir::new();
+        let mut serializable = SerializableAir::new(air, fibonacci_public_inputs());
+        serializable.version += 1;
+        let bytes = serializable.to_bytes().unwrap();
+        assert!(SerializableAir::<FibonacciAir>::from_bytes(&bytes).is_err());
+
+        let mut serializable = SerializableAir::new(air, fibonacci_public_inputs());
+        serializable.round_data.push(RoundDatum::new(1, (0, 0), 0));
+        let bytes = serializable.to_bytes().unwrap();
+        assert!(SerializableAir::<FibonacciAir>::from_bytes(&bytes).is_err());
+    }
+}
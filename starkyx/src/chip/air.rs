@@ -1,7 +1,9 @@
 use super::constraint::Constraint;
 use super::{AirParameters, Chip};
+use crate::air::debug::{ConstraintFailure, DebugParser};
 use crate::air::parser::AirParser;
 use crate::air::{AirConstraint, RAir, RAirData, RoundDatum};
+use crate::trace::AirTrace;
 
 impl<L: AirParameters> RAirData for Chip<L> {
     /// The maximal constraint degree
@@ -35,6 +37,20 @@ impl<L: AirParameters> RAirData for Chip<L> {
     fn width(&self) -> usize {
         L::NUM_ARITHMETIC_COLUMNS + L::NUM_FREE_COLUMNS + L::EXTENDED_COLUMNS
     }
+
+    /// The innermost tag of every entry of `Chip::constraints`.
+    fn constraint_entry_labels(&self) -> Vec<Option<&str>> {
+        (0..self.constraints.len())
+            .map(|i| self.tag_labels(i).last().copied())
+            .collect()
+    }
+
+    /// The innermost tag of every entry of `Chip::global_constraints`.
+    fn global_constraint_entry_labels(&self) -> Vec<Option<&str>> {
+        (0..self.global_constraints.len())
+            .map(|i| self.global_tag_labels(i).last().copied())
+            .collect()
+    }
 }
 
 impl<AP: AirParser, L: AirParameters<Field = AP::Field>> RAir<AP> for Chip<L>
@@ -53,3 +69,61 @@ where
         }
     }
 }
+
+impl<L: AirParameters> Chip<L> {
+    /// Evaluates the constraints on `trace` and returns the first failing row of every constraint
+    /// that does not vanish, followed by the global constraints that do not.
+    ///
+    /// A failure is labelled by the tags containing its constraint, joined by `/`, or by the index
+    /// of the constraint if it has no tag.
+    pub fn debug_constraints(
+        &self,
+        trace: &AirTrace<L::Field>,
+        challenges: &[L::Field],
+        global_values: &[L::Field],
+        public_values: &[L::Field],
+    ) -> Vec<ConstraintFailure<L::Field>>
+    where
+        Constraint<L>: for<'a> AirConstraint<DebugParser<'a, L::Field>>,
+    {
+        let labels = (0..self.constraints.len())
+            .map(|i| match self.tag_labels(i) {
+                labels if labels.is_empty() => format!("constraint {i}"),
+                labels => labels.join("/"),
+            })
+            .collect::<Vec<_>>();
+
+        let mut failures = Vec::new();
+        let mut failed = vec![false; self.constraints.len()];
+        for window in trace.windows() {
+            let mut parser = DebugParser::new(window, challenges, global_values, public_values);
+            for (i, constraint) in self.constraints.iter().enumerate() {
+                if failed[i] {
+                    continue;
+                }
+                let num_failures = parser.failures().len();
+                parser.set_label(&labels[i]);
+                constraint.eval(&mut parser);
+                if parser.failures().len() > num_failures {
+                    failed[i] = true;
+                    parser.truncate_failures(num_failures + 1);
+                }
+            }
+            failures.extend(parser.into_failures());
+        }
+
+        let mut parser = DebugParser::global(challenges, global_values, public_values);
+        for (i, constraint) in self.global_constraints.iter().enumerate() {
+            let num_failures = parser.failures().len();
+            match self.global_tag_labels(i) {
+                labels if labels.is_empty() => parser.set_label(&format!("global constraint {i}")),
+                labels => parser.set_label(&labels.join("/")),
+            }
+            constraint.eval(&mut parser);
+            parser.truncate_failures(num_failures + 1);
+        }
+        failures.extend(parser.into_failures());
+
+        failures
+    }
+}
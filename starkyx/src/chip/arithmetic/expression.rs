@@ -57,6 +57,17 @@ impl<F: Field> ArithmeticExpression<F> {
         self.expression.eval(parser)
     }
 
+    /// Returns an equivalent expression with its constant subexpressions folded.
+    ///
+    /// Constant additions and multiplications are evaluated, `+ 0`, `- 0` and `* 1` are removed,
+    /// and nested constants such as `(x + a) + b` or `a * (b * x)` are collapsed into one.
+    pub fn simplify(&self) -> Self {
+        Self {
+            expression: self.expression.simplify(self.size),
+            size: self.size,
+        }
+    }
+
     /// Returns the registers used in the expression.
     pub fn registers(&self) -> Vec<MemorySlice> {
         self.expression.registers()
@@ -209,3 +220,76 @@ impl<F: Field> Mul for ArithmeticExpression<F> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::{Register, RegisterSerializable};
+
+    type F = GoldilocksField;
+
+    fn register(index: usize) -> ElementRegister {
+        ElementRegister::from_register_unsafe(MemorySlice::Local(index, 1))
+    }
+
+    #[test]
+    fn test_simplify_identities() {
+        let x = register(0);
+        let row = [F::rand(), F::rand()];
+
+        let expr = x.expr() * F::ONE + F::ZERO;
+        let simplified = expr.simplify();
+        assert!(matches!(
+            simplified.expression,
+            ArithmeticExpressionSlice::Input(MemorySlice::Local(0, 1))
+        ));
+        assert_eq!(simplified.read_from_slice(&row), expr.read_from_slice(&row));
+
+        let expr = (x.expr() * ArithmeticExpression::one() - F::ZERO) * F::ZERO + x.expr();
+        let simplified = expr.simplify();
+        assert!(matches!(
+            simplified.expression,
+            ArithmeticExpressionSlice::Input(MemorySlice::Local(0, 1))
+        ));
+        assert_eq!(simplified.read_from_slice(&row), expr.read_from_slice(&row));
+    }
+
+    #[test]
+    fn test_simplify_constants() {
+        let x = register(0);
+        let y = register(1);
+        let row = [F::rand(), F::rand()];
+        let [a, b, c] = [F::rand(), F::rand(), F::rand()];
+        let two = F::from_canonical_u8(2);
+
+        let constant = (ArithmeticExpression::from(a) + b) * ArithmeticExpression::from(c) - a;
+        let simplified = constant.simplify();
+        assert!(matches!(
+            simplified.expression,
+            ArithmeticExpressionSlice::Const(_)
+        ));
+        assert_eq!(simplified.read_from_slice(&[]), vec![(a + b) * c - a]);
+
+        // Nested constant sums and products collapse into a single constant.
+        let expr = ((ArithmeticExpression::from(a) + x.expr()) + b) * two * c + y.expr() * c;
+        let simplified = expr.simplify();
+        assert_eq!(simplified.read_from_slice(&row), expr.read_from_slice(&row));
+        match &simplified.expression {
+            ArithmeticExpressionSlice::Add(left, _) => match left.as_ref() {
+                ArithmeticExpressionSlice::ConstMul(scalar, inner) => {
+                    assert_eq!(*scalar, two * c);
+                    assert!(matches!(
+                        inner.as_ref(),
+                        ArithmeticExpressionSlice::Add(_, constant)
+                            if constant.read_from_slice(&[]) == vec![a + b]
+                    ));
+                }
+                _ => panic!("The constant products were not collapsed"),
+            },
+            _ => panic!("The sum was not preserved"),
+        }
+    }
+}
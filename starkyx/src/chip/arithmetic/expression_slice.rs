@@ -42,6 +42,108 @@ impl<F: Field> ArithmeticExpressionSlice<F> {
         ArithmeticExpressionSlice::Input(input)
     }
 
+    /// Returns the values of the expression if it is a constant.
+    fn as_constant(&self) -> Option<&[F]> {
+        match self {
+            ArithmeticExpressionSlice::Const(constants) => Some(constants),
+            _ => None,
+        }
+    }
+
+    /// Folds the constant subexpressions of an expression of `size` coordinates.
+    ///
+    /// Operations on constants are replaced by their values, additions and subtractions of zero
+    /// and multiplications by one are removed, a multiplication by zero becomes zero, and nested
+    /// constant additions and multiplications are collapsed into one constant.
+    pub(crate) fn simplify(&self, size: usize) -> Self {
+        match self {
+            ArithmeticExpressionSlice::Input(_) | ArithmeticExpressionSlice::Const(_) => {
+                self.clone()
+            }
+            ArithmeticExpressionSlice::Add(left, right) => {
+                Self::simplify_add(left.simplify(size), right.simplify(size))
+            }
+            ArithmeticExpressionSlice::Sub(left, right) => {
+                let (left, right) = (left.simplify(size), right.simplify(size));
+                match (left.as_constant(), right.as_constant()) {
+                    (Some(l), Some(r)) => ArithmeticExpressionSlice::Const(
+                        l.iter().zip(r.iter()).map(|(l, r)| *l - *r).collect(),
+                    ),
+                    (_, Some(r)) if r.iter().all(|x| *x == F::ZERO) => left,
+                    _ => ArithmeticExpressionSlice::Sub(Arc::new(left), Arc::new(right)),
+                }
+            }
+            ArithmeticExpressionSlice::ConstMul(scalar, expr) => {
+                Self::simplify_const_mul(*scalar, expr.simplify(size), size)
+            }
+            ArithmeticExpressionSlice::ScalarMul(scalar, expr) => {
+                let (scalar, expr) = (scalar.simplify(1), expr.simplify(size));
+                match (scalar.as_constant(), expr.as_constant()) {
+                    (Some(s), _) => Self::simplify_const_mul(s[0], expr, size),
+                    (_, Some(e)) if e.iter().all(|x| *x == F::ZERO) => expr,
+                    (_, Some(e)) if size == 1 => Self::simplify_const_mul(e[0], scalar, size),
+                    _ => ArithmeticExpressionSlice::ScalarMul(Arc::new(scalar), Arc::new(expr)),
+                }
+            }
+            ArithmeticExpressionSlice::Mul(left, right) => {
+                let (left, right) = (left.simplify(size), right.simplify(size));
+                let is_uniform = |c: &[F]| c.iter().all(|x| *x == c[0]);
+                match (left.as_constant(), right.as_constant()) {
+                    (Some(l), Some(r)) => ArithmeticExpressionSlice::Const(
+                        l.iter().zip(r.iter()).map(|(l, r)| *l * *r).collect(),
+                    ),
+                    (_, Some(r)) if is_uniform(r) => Self::simplify_const_mul(r[0], left, size),
+                    (Some(l), _) if is_uniform(l) => Self::simplify_const_mul(l[0], right, size),
+                    _ => ArithmeticExpressionSlice::Mul(Arc::new(left), Arc::new(right)),
+                }
+            }
+        }
+    }
+
+    /// Folds the sum of two simplified expressions, with the constant term kept on the right.
+    fn simplify_add(left: Self, right: Self) -> Self {
+        match (left.as_constant(), right.as_constant()) {
+            (Some(l), Some(r)) => ArithmeticExpressionSlice::Const(
+                l.iter().zip(r.iter()).map(|(l, r)| *l + *r).collect(),
+            ),
+            (Some(_), None) => Self::simplify_add(right, left),
+            (None, Some(r)) if r.iter().all(|x| *x == F::ZERO) => left,
+            (None, Some(r)) => {
+                // `(x + a) + b` becomes `x + (a + b)`.
+                if let ArithmeticExpressionSlice::Add(inner, a) = &left {
+                    if let Some(a) = a.as_constant() {
+                        let constant = ArithmeticExpressionSlice::Const(
+                            a.iter().zip(r.iter()).map(|(a, b)| *a + *b).collect(),
+                        );
+                        return Self::simplify_add(inner.as_ref().clone(), constant);
+                    }
+                }
+                ArithmeticExpressionSlice::Add(Arc::new(left), Arc::new(right))
+            }
+            (None, None) => ArithmeticExpressionSlice::Add(Arc::new(left), Arc::new(right)),
+        }
+    }
+
+    /// Folds the product of a simplified expression of `size` coordinates by a constant.
+    fn simplify_const_mul(scalar: F, expr: Self, size: usize) -> Self {
+        if scalar == F::ZERO {
+            return ArithmeticExpressionSlice::Const(vec![F::ZERO; size]);
+        }
+        if scalar == F::ONE {
+            return expr;
+        }
+        match expr {
+            ArithmeticExpressionSlice::Const(constants) => {
+                ArithmeticExpressionSlice::Const(constants.iter().map(|x| *x * scalar).collect())
+            }
+            // `a * (b * x)` becomes `(a * b) * x`.
+            ArithmeticExpressionSlice::ConstMul(inner_scalar, inner) => {
+                Self::simplify_const_mul(scalar * inner_scalar, inner.as_ref().clone(), size)
+            }
+            expr => ArithmeticExpressionSlice::ConstMul(scalar, Arc::new(expr)),
+        }
+    }
+
     pub fn registers(&self) -> Vec<MemorySlice> {
         match self {
             ArithmeticExpressionSlice::Input(input) => vec![*input],
@@ -47,6 +47,39 @@ impl<L: AirParameters> AirBuilder<L> {
         result
     }
 
+    /// Returns the array equal to `a` if `bit` is one and to `b` otherwise, with a single select
+    /// instruction over all the elements of the arrays.
+    pub fn select_array<T: Register>(
+        &mut self,
+        bit: &BitRegister,
+        a: &ArrayRegister<T>,
+        b: &ArrayRegister<T>,
+    ) -> ArrayRegister<T> {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "Cannot select between arrays of different lengths"
+        );
+        let is_trace = a.is_trace() || b.is_trace() || bit.is_trace();
+        let result = if is_trace {
+            self.alloc_array::<T>(a.len())
+        } else {
+            self.alloc_array_public::<T>(a.len())
+        };
+        let instr = AirInstruction::Select(SelectInstruction {
+            bit: *bit,
+            true_value: *a.register(),
+            false_value: *b.register(),
+            result: *result.register(),
+        });
+        if is_trace {
+            self.register_air_instruction_internal(instr);
+        } else {
+            self.register_global_air_instruction_internal(instr);
+        }
+        result
+    }
+
     pub fn set_select<T: Register>(&mut self, bit: &BitRegister, a: &T, b: &T, result: &T) {
         let is_trace = a.is_trace() || b.is_trace() || bit.is_trace() || result.is_trace();
         let instr = SelectInstruction {
@@ -64,6 +97,13 @@ impl<L: AirParameters> AirBuilder<L> {
     }
 }
 
+impl SelectInstruction {
+    /// The bit selecting between the values, and the values selected if it is one and zero.
+    pub const fn selection(&self) -> (BitRegister, MemorySlice, MemorySlice) {
+        (self.bit, self.true_value, self.false_value)
+    }
+}
+
 impl<AP: AirParser> AirConstraint<AP> for SelectInstruction {
     fn eval(&self, parser: &mut AP) {
         let bit = self.bit.eval(parser);
@@ -3,12 +3,14 @@ use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::arithmetic::ArithmeticConstraint;
 use crate::chip::instruction::assign::{AssignInstruction, AssignType};
 use crate::chip::instruction::set::AirInstruction;
+use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::Register;
 use crate::chip::AirParameters;
 
 impl<L: AirParameters> AirBuilder<L> {
     #[inline]
     pub fn assert_expression_zero(&mut self, expression: ArithmeticExpression<L::Field>) {
+        let expression = expression.simplify();
         let flag = expression.is_trace();
         let constraint = ArithmeticConstraint::All(expression);
         if flag {
@@ -20,13 +22,13 @@ impl<L: AirParameters> AirBuilder<L> {
 
     #[inline]
     pub fn assert_expression_zero_first_row(&mut self, expression: ArithmeticExpression<L::Field>) {
-        let constraint = ArithmeticConstraint::First(expression);
+        let constraint = ArithmeticConstraint::First(expression.simplify());
         self.constraints.push(constraint.into());
     }
 
     #[inline]
     pub fn assert_expression_zero_last_row(&mut self, expression: ArithmeticExpression<L::Field>) {
-        let constraint = ArithmeticConstraint::Last(expression);
+        let constraint = ArithmeticConstraint::Last(expression.simplify());
         self.constraints.push(constraint.into());
     }
 
@@ -35,7 +37,7 @@ impl<L: AirParameters> AirBuilder<L> {
         &mut self,
         expression: ArithmeticExpression<L::Field>,
     ) {
-        let constraint = ArithmeticConstraint::Transition(expression);
+        let constraint = ArithmeticConstraint::Transition(expression.simplify());
         self.constraints.push(constraint.into());
     }
 
@@ -45,8 +47,7 @@ impl<L: AirParameters> AirBuilder<L> {
         a: ArithmeticExpression<L::Field>,
         b: ArithmeticExpression<L::Field>,
     ) {
-        let constraint = ArithmeticConstraint::All(a - b);
-        self.constraints.push(constraint.into());
+        self.assert_expression_zero(a - b);
     }
 
     #[inline]
@@ -55,7 +56,7 @@ impl<L: AirParameters> AirBuilder<L> {
         a: ArithmeticExpression<L::Field>,
         b: ArithmeticExpression<L::Field>,
     ) {
-        let constraint = ArithmeticConstraint::First(a - b);
+        let constraint = ArithmeticConstraint::First((a - b).simplify());
         self.constraints.push(constraint.into());
     }
 
@@ -65,7 +66,7 @@ impl<L: AirParameters> AirBuilder<L> {
         a: ArithmeticExpression<L::Field>,
         b: ArithmeticExpression<L::Field>,
     ) {
-        let constraint = ArithmeticConstraint::Last(a - b);
+        let constraint = ArithmeticConstraint::Last((a - b).simplify());
         self.constraints.push(constraint.into());
     }
 
@@ -75,7 +76,7 @@ impl<L: AirParameters> AirBuilder<L> {
         a: ArithmeticExpression<L::Field>,
         b: ArithmeticExpression<L::Field>,
     ) {
-        let constraint = ArithmeticConstraint::Transition(a - b);
+        let constraint = ArithmeticConstraint::Transition((a - b).simplify());
         self.constraints.push(constraint.into());
     }
 
@@ -84,6 +85,16 @@ impl<L: AirParameters> AirBuilder<L> {
         self.assert_expression_zero(a.expr() - b.expr());
     }
 
+    /// Asserts that the cubic extension elements `a` and `b` are equal in every coordinate.
+    ///
+    /// `CubicRegister` has no base field expression, so `assert_equal` does not apply to it.
+    #[inline]
+    pub fn assert_cubic_equal(&mut self, a: &CubicRegister, b: &CubicRegister) {
+        for (a, b) in a.as_base_array().iter().zip(b.as_base_array().iter()) {
+            self.assert_equal(a, b);
+        }
+    }
+
     #[inline]
     pub fn assert_equal_first_row<T: Register>(&mut self, a: &T, b: &T) {
         self.assert_expression_zero_first_row(a.expr() - b.expr());
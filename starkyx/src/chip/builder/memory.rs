@@ -3,6 +3,7 @@ use crate::chip::instruction::set::AirInstruction;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::cell::CellType;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::matrix::Matrix;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable};
 
@@ -87,6 +88,13 @@ impl<L: AirParameters> AirBuilder<L> {
         ArrayRegister::<T>::from_register_unsafe(register)
     }
 
+    /// Allocates a matrix of `num_rows` by `num_cols` trace registers of type `T`, in row-major
+    /// order in contiguous columns.
+    pub fn alloc_matrix<T: Register>(&mut self, num_rows: usize, num_cols: usize) -> Matrix<T> {
+        let array = self.alloc_array::<T>(num_rows * num_cols);
+        Matrix::from_array(array, num_rows, num_cols)
+    }
+
     pub fn alloc_array_extended<T: Register>(&mut self, length: usize) -> ArrayRegister<T> {
         let size_of = T::size_of() * length;
         let register = match T::CELL {
@@ -1,16 +1,25 @@
 pub mod arithmetic;
 pub mod memory;
+pub mod padding;
 pub mod range_check;
 pub mod shared_memory;
+pub mod sub;
+pub mod usage;
 
+use alloc::sync::Arc;
 use core::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 
+use self::padding::PaddingPolicy;
 use self::shared_memory::SharedMemory;
+use self::sub::ColumnLimits;
 use super::arithmetic::expression::ArithmeticExpression;
-use super::constraint::Constraint;
+use super::constraint::{Constraint, ConstraintTag};
 use super::instruction::clock::ClockInstruction;
+use super::instruction::hint::{HintInstruction, HintReader};
 use super::instruction::set::AirInstruction;
 use super::memory::pointer::accumulate::PointerAccumulator;
+use super::memory::reads::CountedStore;
 use super::register::array::ArrayRegister;
 use super::register::cubic::CubicRegister;
 use super::register::element::ElementRegister;
@@ -18,11 +27,13 @@ use super::register::Register;
 use super::table::accumulator::Accumulator;
 use super::table::bus::channel::BusChannel;
 use super::table::bus::global::Bus;
+use super::table::lookup::multi::MultiLookupTable;
+use super::table::lookup::range::RangeCheckTable;
 use super::table::lookup::table::LookupTable;
 use super::table::lookup::values::LookupValues;
 use super::table::powers::Powers;
 use super::trace::data::AirTraceData;
-use super::{AirParameters, Chip};
+use super::{AirParameters, Chip, ColumnBudget};
 use crate::chip::register::RegisterSerializable;
 
 #[derive(Debug, Clone)]
@@ -38,18 +49,31 @@ pub struct AirBuilder<L: AirParameters> {
     pub(crate) global_instructions: Vec<AirInstruction<L::Field, L::Instruction>>,
     pub(crate) constraints: Vec<Constraint<L>>,
     pub(crate) global_constraints: Vec<Constraint<L>>,
+    pub(crate) tags: Vec<ConstraintTag>,
+    tag_stack: Vec<usize>,
     pub(crate) powers: Vec<Powers<L::Field, L::CubicParams>>,
     pub(crate) accumulators: Vec<Accumulator<L::Field, L::CubicParams>>,
     pub(crate) pointer_row_accumulators: Vec<PointerAccumulator<L::Field, L::CubicParams>>,
     pub(crate) pointer_global_accumulators: Vec<PointerAccumulator<L::Field, L::CubicParams>>,
     pub(crate) bus_channels: Vec<BusChannel<CubicRegister, L::CubicParams>>,
     pub(crate) buses: Vec<Bus<CubicRegister, L::CubicParams>>,
-    pub(crate) lookup_values: Vec<LookupValues<L::Field, L::CubicParams>>,
-    pub(crate) lookup_tables: Vec<LookupTable<L::Field, L::CubicParams>>,
+    pub(crate) lookup_values: Vec<LookupValues<L::Field, L::CubicParams, L::LookupExtension>>,
+    pub(crate) lookup_tables: Vec<LookupTable<L::Field, L::CubicParams, L::LookupExtension>>,
     range_data: Option<(
-        LookupTable<L::Field, L::CubicParams>,
-        LookupValues<L::Field, L::CubicParams>,
+        LookupTable<L::Field, L::CubicParams, L::LookupExtension>,
+        LookupValues<L::Field, L::CubicParams, L::LookupExtension>,
     )>,
+    pub(crate) range_check_values: BTreeMap<usize, Vec<ElementRegister>>,
+    pub(crate) range_check_base: Option<usize>,
+    pub(crate) range_checks: Vec<RangeCheckTable<L::Field, L::CubicParams, L::LookupExtension>>,
+    pub(crate) multi_lookups: Vec<MultiLookupTable<L::Field, L::CubicParams>>,
+    pub(crate) constant_timestamps: BTreeSet<u64>,
+    pub(crate) reserved_timestamps: BTreeSet<u64>,
+    pub(crate) counted_stores: Vec<CountedStore<L::Field>>,
+    trace_length: Option<usize>,
+    padding_policy: PaddingPolicy,
+    column_limits: Option<ColumnLimits>,
+    pub(crate) debug: bool,
 }
 
 impl<L: AirParameters> AirBuilder<L> {
@@ -73,6 +97,8 @@ impl<L: AirParameters> AirBuilder<L> {
             global_instructions: Vec::new(),
             constraints: Vec::new(),
             global_constraints: Vec::new(),
+            tags: Vec::new(),
+            tag_stack: Vec::new(),
             powers: Vec::new(),
             accumulators: Vec::new(),
             pointer_row_accumulators: Vec::new(),
@@ -82,6 +108,17 @@ impl<L: AirParameters> AirBuilder<L> {
             lookup_values: Vec::new(),
             lookup_tables: Vec::new(),
             range_data: None,
+            range_check_values: BTreeMap::new(),
+            range_check_base: None,
+            range_checks: Vec::new(),
+            multi_lookups: Vec::new(),
+            constant_timestamps: BTreeSet::new(),
+            reserved_timestamps: BTreeSet::new(),
+            counted_stores: Vec::new(),
+            trace_length: None,
+            padding_policy: PaddingPolicy::default(),
+            column_limits: None,
+            debug: true,
         }
     }
 
@@ -113,7 +150,12 @@ impl<L: AirParameters> AirBuilder<L> {
     /// Prints out a log message (using the log::debug! macro) with the value of the register.
     ///
     /// The message will be presented with `RUST_LOG=debug` or `RUST_LOG=trace`.
+    ///
+    /// Does nothing if debugging is disabled by `set_debug`.
     pub fn watch(&mut self, data: &impl Register, name: &str) {
+        if !self.debug {
+            return;
+        }
         let register = ArrayRegister::from_register_unsafe(*data.register());
         let instruction = AirInstruction::Watch(name.to_string(), register);
         if data.is_trace() {
@@ -123,6 +165,37 @@ impl<L: AirParameters> AirBuilder<L> {
         }
     }
 
+    /// Enables or disables the debugging instructions registered from now on, such as `watch`
+    /// and `watch_memory`. Debugging is enabled by default.
+    ///
+    /// With debugging disabled, watches register no instruction, so they don't slow down trace
+    /// generation.
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+    }
+
+    /// Allocates a register whose value in every row is computed by `hint` during trace
+    /// generation.
+    ///
+    /// The function is given a reader of the values of the row, and the row index. It is called
+    /// in the order of the instructions, so it can read the registers written by the
+    /// instructions registered before it. No constraint is placed on the register, which has to
+    /// be constrained by the rest of the AIR. An AIR with a hint cannot be serialized.
+    pub fn alloc_with_hint<T: Register>(
+        &mut self,
+        hint: impl Fn(&HintReader<L::Field>, usize) -> T::Value<L::Field> + Send + Sync + 'static,
+    ) -> T {
+        let register = self.alloc::<T>();
+        let instruction = HintInstruction {
+            register: *register.register(),
+            hint: Arc::new(move |reader: &HintReader<L::Field>, row_index: usize| {
+                T::align(&hint(reader, row_index)).to_vec()
+            }),
+        };
+        self.instructions.push(AirInstruction::Hint(instruction));
+        register
+    }
+
     /// Registers an custom instruction with the builder.
     pub fn register_instruction<I>(&mut self, instruction: I)
     where
@@ -193,6 +266,94 @@ impl<L: AirParameters> AirBuilder<L> {
         self.global_constraints.push(constraint.into());
     }
 
+    /// Pushes `label` onto the tag stack. Every constraint registered until the matching call to
+    /// `pop_tag` is tagged with `label`.
+    pub fn push_tag(&mut self, label: &str) {
+        let start = self.constraints.len();
+        let global_start = self.global_constraints.len();
+        self.tag_stack.push(self.tags.len());
+        self.tags.push(ConstraintTag::new(
+            label.to_string(),
+            start..start,
+            global_start..global_start,
+        ));
+    }
+
+    /// Pops the most recently pushed tag from the tag stack.
+    pub fn pop_tag(&mut self) {
+        let index = self
+            .tag_stack
+            .pop()
+            .expect("Called `pop_tag` with an empty tag stack");
+        let tag = &mut self.tags[index];
+        tag.constraints.end = self.constraints.len();
+        tag.global_constraints.end = self.global_constraints.len();
+    }
+
+    /// The number of columns of each kind allocated so far.
+    ///
+    /// Column allocation is not bounded by the constants of `L`, so the budget can be measured
+    /// with any parameters before choosing the ones to build with. The columns added by `build`
+    /// are only counted once `register_build_constraints` is called.
+    pub fn column_budget(&self) -> ColumnBudget {
+        ColumnBudget {
+            num_arithmetic_columns: self.local_arithmetic_index,
+            num_free_columns: self.local_index - L::NUM_ARITHMETIC_COLUMNS,
+            extended_columns: self.extended_index - L::NUM_ARITHMETIC_COLUMNS - L::NUM_FREE_COLUMNS,
+        }
+    }
+
+    /// The number of rows of the trace, if it is fixed by one of the machines in the builder.
+    pub fn trace_length(&self) -> Option<usize> {
+        self.trace_length
+    }
+
+    /// The padding policy of the trace.
+    pub fn padding_policy(&self) -> PaddingPolicy {
+        self.padding_policy
+    }
+
+    /// Sets the padding policy of the trace, which defaults to `PaddingPolicy::NextPowerOfTwo`.
+    ///
+    /// Machines fixing the trace length pad their natural number of rows according to this
+    /// policy, so it should be set before registering them.
+    pub fn set_padding_policy(&mut self, policy: PaddingPolicy) {
+        if let Some(trace_length) = self.trace_length {
+            assert!(
+                policy.is_padded(trace_length),
+                "Trace length {} was already set and is not padded for {:?}",
+                trace_length,
+                policy
+            );
+        }
+        self.padding_policy = policy;
+    }
+
+    /// The number of rows of a trace with `num_rows` natural rows under the padding policy.
+    pub fn padded_trace_length(&self, num_rows: usize) -> usize {
+        self.padding_policy.padded_length(num_rows)
+    }
+
+    /// Fixes the number of rows of the trace to `num_rows`.
+    ///
+    /// Machines whose constraints depend on the trace length call this method so that the length
+    /// is known before generating the trace. The length must be padded for the padding policy.
+    pub fn set_trace_length(&mut self, num_rows: usize) {
+        assert!(
+            self.padding_policy.is_padded(num_rows),
+            "Trace length {} is not padded for {:?}",
+            num_rows,
+            self.padding_policy
+        );
+        if let Some(trace_length) = self.trace_length {
+            assert_eq!(
+                trace_length, num_rows,
+                "Trace length was already set to a different value"
+            );
+        }
+        self.trace_length = Some(num_rows);
+    }
+
     pub fn clock(&mut self) -> ElementRegister {
         let clk = self.alloc::<ElementRegister>();
 
@@ -201,8 +362,14 @@ impl<L: AirParameters> AirBuilder<L> {
         clk
     }
 
-    pub fn build(mut self) -> (Chip<L>, AirTraceData<L>) {
+    /// Registers the constraints and columns added when the AIR is built: the counted stores, the
+    /// buses and their channels, and the range checks.
+    pub(crate) fn register_build_constraints(&mut self) {
+        self.register_counted_stores();
+        self.check_timestamps();
+
         // Register all bus constraints.
+        self.push_tag("bus");
         for i in 0..self.buses.len() {
             self.register_bus_constraint(i);
         }
@@ -210,13 +377,37 @@ impl<L: AirParameters> AirBuilder<L> {
         for channel in self.bus_channels.iter() {
             self.constraints.push(channel.clone().into());
         }
+        self.pop_tag();
+
+        // Add the range check tables of the registered widths.
+        self.push_tag("range_check");
+        self.register_range_checks();
+        self.pop_tag();
 
         // Add the range checks
         if (L::NUM_ARITHMETIC_COLUMNS > 0 || !self.global_arithmetic.is_empty())
             && self.internal_range_check
         {
+            self.push_tag("arithmetic_range_check");
             self.arithmetic_range_checks();
+            self.pop_tag();
         }
+    }
+
+    pub fn build(mut self) -> (Chip<L>, AirTraceData<L>) {
+        assert!(
+            self.column_limits.is_none(),
+            "A sub-builder must be merged into its parent instead of built"
+        );
+        assert!(
+            self.tag_stack.is_empty(),
+            "Unclosed constraint tag: {}",
+            self.tag_stack
+                .last()
+                .map(|i| self.tags[*i].label.as_str())
+                .unwrap_or_default()
+        );
+        self.register_build_constraints();
 
         // Check the number of columns in comparison to config
         let num_free_columns = self.local_index - L::NUM_ARITHMETIC_COLUMNS;
@@ -276,8 +467,10 @@ impl<L: AirParameters> AirBuilder<L> {
             Chip {
                 constraints: self.constraints,
                 global_constraints: self.global_constraints,
+                tags: self.tags,
                 num_challenges: self.shared_memory.challenge_index(),
                 execution_trace_length,
+                num_extended_columns,
                 num_public_values: self.shared_memory.public_index(),
                 num_global_values: self.shared_memory.global_index(),
             },
@@ -297,6 +490,8 @@ impl<L: AirParameters> AirBuilder<L> {
                 lookup_values: self.lookup_values,
                 lookup_tables: self.lookup_tables,
                 range_data: self.range_data,
+                range_checks: self.range_checks,
+                multi_lookups: self.multi_lookups,
             },
         )
     }
@@ -312,7 +507,7 @@ pub(crate) mod tests {
     use super::*;
     use crate::air::fibonacci::FibonacciAir;
     pub use crate::air::parser::AirParser;
-    pub use crate::air::RAir;
+    pub use crate::air::{RAir, RAirData};
     pub use crate::chip::instruction::empty::EmptyInstruction;
     pub use crate::chip::register::u16::U16Register;
     pub use crate::chip::register::RegisterSerializable;
@@ -383,6 +578,138 @@ pub(crate) mod tests {
         }
     }
 
+    #[test]
+    fn test_builder_unpadded_trace() {
+        type F = GoldilocksField;
+        type L = FibonacciParameters;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.set_padding_policy(PaddingPolicy::None);
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+
+        let constr_1 = builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        let constr_2 = builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+
+        // The natural number of rows of three BLAKE2b compresses.
+        let num_rows = builder.padded_trace_length(3 * 96);
+        assert_eq!(num_rows, 288);
+        builder.set_trace_length(num_rows);
+
+        let (mut air, mut air_data) = builder.build();
+        air.num_public_values = 3;
+        air_data.num_public_inputs = 3;
+
+        let public_inputs = [
+            F::ZERO,
+            F::ONE,
+            FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE),
+        ];
+
+        let generator = ArithmeticGenerator::<L>::new(air_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write(&x_0, &F::ZERO, 0);
+        writer.write(&x_1, &F::ONE, 0);
+        for i in 0..num_rows {
+            writer.write_instruction(&constr_1, i);
+            writer.write_instruction(&constr_2, i);
+        }
+
+        // The transition constraints are not enforced from the true last row back to the first.
+        let trace = generator.trace_clone();
+        assert_eq!(trace.height(), num_rows);
+        for window in trace.windows() {
+            assert_eq!(window.is_last_row, window.row == num_rows - 1);
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &public_inputs);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "is not padded")]
+    fn test_builder_trace_length_not_padded() {
+        let mut builder = AirBuilder::<FibonacciParameters>::new();
+        builder.set_trace_length(288);
+    }
+
+    #[test]
+    fn test_builder_air_version_hash() {
+        type F = GoldilocksField;
+        type L = FibonacciParameters;
+
+        let fibonacci_chip = |coefficient: u64, tagged: bool| {
+            let mut builder = AirBuilder::<L>::new();
+            let x_0 = builder.alloc::<ElementRegister>();
+            let x_1 = builder.alloc::<ElementRegister>();
+            if tagged {
+                builder.push_tag("fibonacci");
+            }
+            builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+            builder.set_to_expression_transition(
+                &x_1.next(),
+                x_0.expr() * F::from_canonical_u64(coefficient) + x_1.expr(),
+            );
+            if tagged {
+                builder.pop_tag();
+            }
+            builder.build().0
+        };
+
+        // Rebuilding the same AIR reproduces the hash, which does not depend on the tags.
+        let hash = fibonacci_chip(1, false).air_version_hash();
+        assert_eq!(fibonacci_chip(1, false).air_version_hash(), hash);
+        assert_eq!(fibonacci_chip(1, true).air_version_hash(), hash);
+
+        // Changing a constraint changes the hash.
+        assert_ne!(fibonacci_chip(2, false).air_version_hash(), hash);
+
+        // Changing the layout of the public inputs changes the hash.
+        let mut chip = fibonacci_chip(1, false);
+        chip.num_public_values = 3;
+        assert_ne!(chip.air_version_hash(), hash);
+
+        let domain_hash = fibonacci_chip(1, false).air_version_hash_in_domain(b"v1");
+        assert_ne!(domain_hash, hash);
+        assert_eq!(
+            fibonacci_chip(1, false).air_version_hash_in_domain(b"v1"),
+            domain_hash
+        );
+    }
+
+    #[test]
+    fn test_builder_constraint_labels() {
+        type L = FibonacciParameters;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.push_tag("fibonacci");
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        let x_0_end = builder.constraints.len();
+        builder.push_tag("x_1");
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+        builder.pop_tag();
+        builder.pop_tag();
+        let x_1_end = builder.constraints.len();
+        builder.assert_expression_zero_first_row(x_0.expr());
+        let (air, _) = builder.build();
+
+        // Every constraint entry is labelled by its innermost tag.
+        let labels = air.constraint_entry_labels();
+        assert_eq!(labels.len(), air.constraints.len());
+        assert!(labels[..x_0_end]
+            .iter()
+            .all(|label| *label == Some("fibonacci")));
+        assert!(labels[x_0_end..x_1_end]
+            .iter()
+            .all(|label| *label == Some("x_1")));
+        assert!(labels[x_1_end..].iter().all(|label| label.is_none()));
+        assert!(air
+            .global_constraint_entry_labels()
+            .iter()
+            .all(|label| label.is_none()));
+    }
+
     #[test]
     fn test_builder_fibonacci_stark() {
         type F = GoldilocksField;
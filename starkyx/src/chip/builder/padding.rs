@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// How the natural number of rows of a trace is padded to the number of rows of the trace.
+///
+/// Provers committing to the trace with an FFT over a two-adic domain need a power-of-two trace
+/// height, which is the default. Provers without this restriction can keep the natural number of
+/// rows, or pad it to a multiple of some block size.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PaddingPolicy {
+    /// The trace has exactly the natural number of rows.
+    None,
+    /// The trace is padded to the next power of two.
+    #[default]
+    NextPowerOfTwo,
+    /// The trace is padded to the next multiple of the given number of rows.
+    Multiple(usize),
+}
+
+impl PaddingPolicy {
+    /// The number of rows of a trace with `num_rows` natural rows.
+    pub fn padded_length(&self, num_rows: usize) -> usize {
+        match self {
+            PaddingPolicy::None => num_rows,
+            PaddingPolicy::NextPowerOfTwo => num_rows.next_power_of_two(),
+            PaddingPolicy::Multiple(k) => {
+                assert!(*k > 0, "Cannot pad to a multiple of zero rows");
+                (num_rows + k - 1) / k * k
+            }
+        }
+    }
+
+    /// Returns `true` if a trace of `num_rows` rows needs no further padding.
+    pub fn is_padded(&self, num_rows: usize) -> bool {
+        num_rows > 0 && self.padded_length(num_rows) == num_rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_padded_length() {
+        assert_eq!(PaddingPolicy::None.padded_length(288), 288);
+        assert_eq!(PaddingPolicy::NextPowerOfTwo.padded_length(288), 512);
+        assert_eq!(PaddingPolicy::NextPowerOfTwo.padded_length(256), 256);
+        assert_eq!(PaddingPolicy::Multiple(100).padded_length(288), 300);
+        assert_eq!(PaddingPolicy::Multiple(96).padded_length(288), 288);
+
+        assert!(PaddingPolicy::None.is_padded(288));
+        assert!(!PaddingPolicy::NextPowerOfTwo.is_padded(288));
+        assert!(PaddingPolicy::Multiple(32).is_padded(288));
+        assert!(!PaddingPolicy::None.is_padded(0));
+    }
+}
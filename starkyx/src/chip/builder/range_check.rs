@@ -1,12 +1,18 @@
+use alloc::collections::BTreeMap;
+
 use super::AirBuilder;
 use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::element::ElementRegister;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::table::lookup::range::{
+    RangeCheckTable, RangeDecomposition, MAX_RANGE_CHECK_BITS,
+};
 use crate::chip::table::lookup::table::LookupTable;
 use crate::chip::table::lookup::values::LookupValues;
 use crate::chip::AirParameters;
+use crate::math::prelude::*;
 
 impl<L: AirParameters> AirBuilder<L> {
     pub(crate) fn arithmetic_range_checks(&mut self) {
@@ -37,4 +43,315 @@ impl<L: AirParameters> AirBuilder<L> {
             LookupValues::Element(lookup_values),
         ));
     }
+
+    /// Asserts that `value` lies in `0..2^bits` in every row of the trace, or once if `value` is
+    /// public.
+    ///
+    /// The values checked for the same width share a log-derivative lookup into a table of the
+    /// `2^bits` values, registered when the AIR is built. With a base width set by
+    /// `set_range_check_base`, trace values are checked against the table of the base width. See
+    /// `AirParameters` for the largest supported `bits` and the columns and rows it takes.
+    pub fn range_check(&mut self, value: &ElementRegister, bits: usize) {
+        assert!(
+            bits > 0 && bits <= MAX_RANGE_CHECK_BITS,
+            "Range checks of {} bits are not supported, the width must be in 1..={}",
+            bits,
+            MAX_RANGE_CHECK_BITS
+        );
+        assert!(
+            !matches!(value.register(), MemorySlice::Next(..)),
+            "Cannot range check a next row register"
+        );
+        self.range_check_values
+            .entry(bits)
+            .or_default()
+            .push(*value);
+    }
+
+    /// Checks the trace values of every width against the table of `base_bits` bits, instead of a
+    /// table per width.
+    ///
+    /// A value of `n` bits is split into `ceil(n / base_bits)` little-endian limbs, each checked
+    /// against the base table. If the top limb has `r < base_bits` bits, it is also checked
+    /// shifted by `base_bits - r` bits, which bounds it by `2^r`. The limbs take free columns in
+    /// exchange for the columns of the tables of the other widths, see `AirParameters`. Public
+    /// values are still checked against the table of their width.
+    pub fn set_range_check_base(&mut self, base_bits: usize) {
+        assert!(
+            base_bits > 0 && base_bits <= MAX_RANGE_CHECK_BITS,
+            "A range check base of {} bits is not supported, the width must be in 1..={}",
+            base_bits,
+            MAX_RANGE_CHECK_BITS
+        );
+        self.range_check_base = Some(base_bits);
+    }
+
+    /// Registers a lookup table for every width of the range checks, and the checked values into
+    /// it.
+    pub(crate) fn register_range_checks(&mut self) {
+        let range_check_values = core::mem::take(&mut self.range_check_values);
+
+        // Decompose the trace values of the widths other than the base width.
+        let mut table_values = BTreeMap::<usize, Vec<ElementRegister>>::new();
+        let mut decompositions = Vec::new();
+        for (bits, values) in range_check_values {
+            for value in values {
+                match self.range_check_base {
+                    Some(base_bits) if bits != base_bits && value.is_trace() => {
+                        let decomposition = self.range_decomposition(&value, bits, base_bits);
+                        let base_values = table_values.entry(base_bits).or_default();
+                        base_values.extend(decomposition.limbs.iter());
+                        base_values.extend(decomposition.shifted_top.map(|(shifted, _)| shifted));
+                        decompositions.push(decomposition);
+                    }
+                    _ => table_values.entry(bits).or_default().push(value),
+                }
+            }
+        }
+
+        for (bits, values) in table_values {
+            let table = self.alloc::<ElementRegister>();
+            let max_value = L::Field::from_canonical_u64((1 << bits) - 1);
+
+            // The table starts at zero, increases by zero or one in each row, and ends at the
+            // largest value of the width, so it takes every value of the range.
+            let step = table.next().expr() - table.expr();
+            self.assert_expressions_equal_first_row(table.expr(), ArithmeticExpression::zero());
+            self.assert_expression_zero_transition(
+                step.clone() * (step - ArithmeticExpression::one()),
+            );
+            self.assert_expressions_equal_last_row(table.expr(), max_value.into());
+
+            let multiplicities = self.alloc_array::<ElementRegister>(1);
+            let mut table_data = self.new_lookup(&[table], &multiplicities);
+            let lookup_values = table_data.register_lookup_values(self, &values);
+            self.constrain_element_lookup_table(table_data.clone());
+
+            let decompositions = if self.range_check_base == Some(bits) {
+                core::mem::take(&mut decompositions)
+            } else {
+                Vec::new()
+            };
+            self.range_checks.push(RangeCheckTable {
+                bits,
+                table: table_data,
+                values: lookup_values,
+                decompositions,
+            });
+        }
+    }
+
+    /// Allocates the limbs of base width `base_bits` of the `bits`-bit `value`, and constrains
+    /// `value` to be their recomposition.
+    fn range_decomposition(
+        &mut self,
+        value: &ElementRegister,
+        bits: usize,
+        base_bits: usize,
+    ) -> RangeDecomposition {
+        let num_limbs = bits.div_ceil(base_bits);
+        let limbs = self.alloc_array::<ElementRegister>(num_limbs);
+        let recomposition = limbs
+            .iter()
+            .enumerate()
+            .fold(ArithmeticExpression::zero(), |acc, (j, limb)| {
+                acc + limb.expr() * L::Field::from_canonical_u64(1 << (j * base_bits))
+            });
+        self.assert_expressions_equal(value.expr(), recomposition);
+
+        let top_bits = bits - (num_limbs - 1) * base_bits;
+        let shifted_top = (top_bits < base_bits).then(|| {
+            let shift = base_bits - top_bits;
+            let shifted = self.alloc::<ElementRegister>();
+            self.assert_expressions_equal(
+                shifted.expr(),
+                limbs.get(num_limbs - 1).expr() * L::Field::from_canonical_u64(1 << shift),
+            );
+            (shifted, shift)
+        });
+
+        RangeDecomposition {
+            value: *value,
+            limbs,
+            shifted_top,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::Chip;
+    use crate::math::goldilocks::quintic::GF5;
+    use crate::plonky2::Plonky2Air;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RangeCheckTest;
+
+    impl AirParameters for RangeCheckTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 7;
+        const EXTENDED_COLUMNS: usize = 21;
+    }
+
+    /// The range checks of `RangeCheckTest`, with the challenges of the lookups drawn from the
+    /// quintic extension.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct QuinticRangeCheckTest;
+
+    impl AirParameters for QuinticRangeCheckTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type LookupExtension = GF5;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 7;
+        const EXTENDED_COLUMNS: usize = 35;
+    }
+
+    fn prove_range_checks<L: AirParameters<Field = GoldilocksField>>(byte_offset: usize)
+    where
+        Chip<L>: Plonky2Air<GoldilocksField, 2>,
+    {
+        type F = GoldilocksField;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let c = builder.alloc::<ElementRegister>();
+        let p = builder.alloc_public::<ElementRegister>();
+        builder.range_check(&a, 8);
+        builder.range_check(&b, 8);
+        builder.range_check(&p, 8);
+        builder.range_check(&c, 4);
+
+        let (air, trace_data) = builder.build();
+        assert_eq!(trace_data.range_checks.len(), 2);
+        assert_eq!(trace_data.range_checks[0].bits(), 4);
+        assert_eq!(trace_data.range_checks[1].size(), 256);
+        assert_eq!(
+            trace_data.range_checks[0].table.challenge.register().len(),
+            <L::LookupExtension as Extension<F>>::D
+        );
+
+        // The trace is longer than the tables, whose last entries are repeated.
+        let num_rows = 1 << 9;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write(&p, &F::from_canonical_u8(200), 0);
+        for i in 0..num_rows {
+            writer.write(&a, &F::from_canonical_usize(i % 256), i);
+            writer.write(&b, &F::from_canonical_usize((7 * i) % 256 + byte_offset), i);
+            writer.write(&c, &F::from_canonical_usize(i % 16), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.public().unwrap().clone();
+        test_starky(&stark, &config, &generator, &public_inputs);
+    }
+
+    #[test]
+    fn test_range_check() {
+        prove_range_checks::<RangeCheckTest>(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_check_out_of_range() {
+        prove_range_checks::<RangeCheckTest>(1);
+    }
+
+    #[test]
+    fn test_range_check_quintic() {
+        prove_range_checks::<QuinticRangeCheckTest>(0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_check_quintic_out_of_range() {
+        prove_range_checks::<QuinticRangeCheckTest>(1);
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RangeCheckBaseTest;
+
+    impl AirParameters for RangeCheckBaseTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 10;
+        const EXTENDED_COLUMNS: usize = 18;
+    }
+
+    fn prove_base_range_checks(out_of_range_row: Option<usize>) {
+        type F = GoldilocksField;
+        type L = RangeCheckBaseTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.set_range_check_base(8);
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        let c = builder.alloc::<ElementRegister>();
+        builder.range_check(&a, 12);
+        builder.range_check(&b, 8);
+        builder.range_check(&c, 4);
+
+        // A single table of the base width, with the limbs of `a` and `c`.
+        let (air, trace_data) = builder.build();
+        assert_eq!(trace_data.range_checks.len(), 1);
+        assert_eq!(trace_data.range_checks[0].size(), 256);
+        assert_eq!(trace_data.range_checks[0].decompositions.len(), 2);
+
+        // The trace is shorter than a table of 12 bits.
+        let num_rows = 1 << 9;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let a_value = match out_of_range_row {
+                Some(row) if row == i => 1 << 12,
+                _ => (i * 37 + 4000) % 4096,
+            };
+            writer.write(&a, &F::from_canonical_usize(a_value), i);
+            writer.write(&b, &F::from_canonical_usize((7 * i) % 256), i);
+            writer.write(&c, &F::from_canonical_usize(i % 16), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.public().unwrap().clone();
+        test_starky(&stark, &config, &generator, &public_inputs);
+    }
+
+    #[test]
+    fn test_range_check_base() {
+        prove_base_range_checks(None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_range_check_base_out_of_range() {
+        // The value `2^12` has a top limb of 16, which fits in the base width but not in the 4
+        // remaining bits.
+        prove_base_range_checks(Some(100));
+    }
+
+    #[test]
+    #[should_panic(expected = "not supported")]
+    fn test_range_check_too_wide() {
+        let mut builder = AirBuilder::<RangeCheckTest>::new();
+        let a = builder.alloc::<ElementRegister>();
+        builder.range_check(&a, MAX_RANGE_CHECK_BITS + 1);
+    }
 }
@@ -0,0 +1,220 @@
+use super::{AirBuilder, AirParameters};
+use crate::chip::constraint::ConstraintTag;
+use crate::chip::ColumnBudget;
+
+/// The ends of the ranges of columns reserved for a sub-builder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ColumnLimits {
+    local_index: usize,
+    local_arithmetic_index: usize,
+    extended_index: usize,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Reserves the columns of `budget` and returns a builder allocating its trace columns in
+    /// them, to be merged back with `merge`.
+    ///
+    /// A sub-builder is `Send`, so independent gadgets can be built in their own sub-builders on
+    /// separate threads. The public, global and challenge registers are taken from the memory
+    /// shared with the parent, in the order they are allocated, which may differ between runs
+    /// when the sub-builders allocate them concurrently. The sub-builder has its own memory bus,
+    /// so a gadget using memory must initialize it in the sub-builder.
+    ///
+    /// The budget of a gadget can be measured with `column_budget` on a scratch builder.
+    pub fn sub_builder(&mut self, budget: ColumnBudget) -> Self {
+        let mut sub = Self::new_with_shared_memory(self.shared_memory.clone());
+        sub.local_index = self.local_index;
+        sub.local_arithmetic_index = self.local_arithmetic_index;
+        sub.extended_index = self.extended_index;
+        sub.internal_range_check = self.internal_range_check;
+        sub.range_check_base = self.range_check_base;
+        sub.reserved_timestamps = self.reserved_timestamps.clone();
+        sub.trace_length = self.trace_length;
+        sub.padding_policy = self.padding_policy;
+        sub.debug = self.debug;
+
+        self.local_index += budget.num_free_columns;
+        self.local_arithmetic_index += budget.num_arithmetic_columns;
+        self.extended_index += budget.extended_columns;
+        sub.column_limits = Some(ColumnLimits {
+            local_index: self.local_index,
+            local_arithmetic_index: self.local_arithmetic_index,
+            extended_index: self.extended_index,
+        });
+
+        sub
+    }
+
+    /// Merges the constraints, instructions and tables of the sub-builder `sub`, given by
+    /// `sub_builder`, into the builder.
+    ///
+    /// The columns of `sub` lie in the ranges reserved for it, so its registers are kept as they
+    /// are. Its constraints are appended after the constraints of the builder, and the ranges of
+    /// its tags are shifted accordingly, so merging the sub-builders in the order they were taken
+    /// gives the same AIR as building the gadgets one after the other.
+    pub fn merge(&mut self, mut sub: Self) {
+        let limits = sub.column_limits.expect("Only a sub-builder can be merged");
+        assert!(
+            sub.tag_stack.is_empty(),
+            "Unclosed constraint tag in sub-builder"
+        );
+        // The counted slots of `sub` are only loaded in `sub`.
+        sub.register_counted_stores();
+        assert!(
+            sub.local_index <= limits.local_index,
+            "The sub-builder allocated {} free columns more than reserved",
+            sub.local_index - limits.local_index
+        );
+        assert!(
+            sub.local_arithmetic_index <= limits.local_arithmetic_index,
+            "The sub-builder allocated {} arithmetic columns more than reserved",
+            sub.local_arithmetic_index - limits.local_arithmetic_index
+        );
+        assert!(
+            sub.extended_index <= limits.extended_index,
+            "The sub-builder allocated {} extended columns more than reserved",
+            sub.extended_index - limits.extended_index
+        );
+
+        if let Some(num_rows) = sub.trace_length {
+            self.set_trace_length(num_rows);
+        }
+        if let Some(base_bits) = sub.range_check_base {
+            if let Some(parent_bits) = self.range_check_base {
+                assert_eq!(
+                    parent_bits, base_bits,
+                    "The sub-builder set a different range check base"
+                );
+            }
+            self.range_check_base = Some(base_bits);
+        }
+
+        let constraints_offset = self.constraints.len();
+        let global_offset = self.global_constraints.len();
+        self.tags.extend(sub.tags.into_iter().map(|tag| {
+            ConstraintTag::new(
+                tag.label,
+                tag.constraints.start + constraints_offset
+                    ..tag.constraints.end + constraints_offset,
+                tag.global_constraints.start + global_offset
+                    ..tag.global_constraints.end + global_offset,
+            )
+        }));
+
+        self.instructions.extend(sub.instructions);
+        self.global_instructions.extend(sub.global_instructions);
+        self.constraints.extend(sub.constraints);
+        self.global_constraints.extend(sub.global_constraints);
+        self.global_arithmetic.extend(sub.global_arithmetic);
+        self.powers.extend(sub.powers);
+        self.accumulators.extend(sub.accumulators);
+        self.pointer_row_accumulators
+            .extend(sub.pointer_row_accumulators);
+        self.pointer_global_accumulators
+            .extend(sub.pointer_global_accumulators);
+        self.bus_channels.extend(sub.bus_channels);
+        self.buses.extend(sub.buses);
+        self.lookup_values.extend(sub.lookup_values);
+        self.lookup_tables.extend(sub.lookup_tables);
+        self.range_checks.extend(sub.range_checks);
+        for (bits, values) in sub.range_check_values {
+            self.range_check_values
+                .entry(bits)
+                .or_default()
+                .extend(values);
+        }
+        self.multi_lookups.extend(sub.multi_lookups);
+        self.constant_timestamps.extend(sub.constant_timestamps);
+        self.reserved_timestamps.extend(sub.reserved_timestamps);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::register::bit::BitRegister;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct SubBuilderTest;
+
+    impl AirParameters for SubBuilderTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 24;
+    }
+
+    /// Allocates `num_terms` terms and constrains their running products, selected by a bit.
+    fn products(builder: &mut AirBuilder<SubBuilderTest>, label: &str, num_terms: usize) {
+        builder.push_tag(label);
+        let terms = builder.alloc_array::<ElementRegister>(num_terms);
+        let bit = builder.alloc::<BitRegister>();
+        let mut product = terms.get(0);
+        for term in terms.iter().skip(1) {
+            let next = builder.alloc::<ElementRegister>();
+            builder.set_to_expression(&next, product.expr() * term.expr());
+            product = next;
+        }
+        builder.assert_expression_zero(bit.expr() * (product.expr() - terms.get(0).expr()));
+        builder.pop_tag();
+    }
+
+    #[test]
+    fn test_sub_builders_in_parallel() {
+        type L = SubBuilderTest;
+
+        fn assert_send<T: Send>() {}
+        assert_send::<AirBuilder<L>>();
+
+        let budget = |num_terms| {
+            let mut builder = AirBuilder::<L>::new();
+            products(&mut builder, "scratch", num_terms);
+            builder.column_budget()
+        };
+
+        let mut sequential = AirBuilder::<L>::new();
+        products(&mut sequential, "a", 5);
+        products(&mut sequential, "b", 7);
+        let (sequential_air, _) = sequential.build();
+
+        let mut builder = AirBuilder::<L>::new();
+        let mut sub_a = builder.sub_builder(budget(5));
+        let mut sub_b = builder.sub_builder(budget(7));
+        std::thread::scope(|s| {
+            s.spawn(|| products(&mut sub_a, "a", 5));
+            s.spawn(|| products(&mut sub_b, "b", 7));
+        });
+        builder.merge(sub_a);
+        builder.merge(sub_b);
+        let (air, _) = builder.build();
+
+        assert_eq!(
+            format!("{:?}", air.constraints()),
+            format!("{:?}", sequential_air.constraints())
+        );
+        assert_eq!(air.tags(), sequential_air.tags());
+    }
+
+    #[test]
+    #[should_panic(expected = "free columns more than reserved")]
+    fn test_sub_builder_over_budget() {
+        type L = SubBuilderTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let mut sub = builder.sub_builder(ColumnBudget {
+            num_arithmetic_columns: 0,
+            num_free_columns: 2,
+            extended_columns: 0,
+        });
+        sub.alloc_array::<ElementRegister>(3);
+        builder.merge(sub);
+    }
+}
@@ -0,0 +1,317 @@
+use core::marker::PhantomData;
+
+use super::AirBuilder;
+use crate::air::extension::cubic::CubicParser;
+use crate::air::extension::quintic::QuinticParser;
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::chip::constraint::Constraint;
+use crate::chip::instruction::set::AirInstruction;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+use crate::polynomial::parser::PolynomialParser;
+
+/// The allocated trace columns which the constraints of an AIR do not depend on.
+///
+/// Columns are reported as runs of consecutive columns, so a run may cover several registers
+/// allocated one after the other.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnUsage {
+    /// Columns which appear in no constraint and are not read by any memory instruction. These
+    /// are dead and can be removed.
+    pub unused: Vec<MemorySlice>,
+    /// Columns which appear in no constraint, but are read by memory instructions during trace
+    /// generation, e.g. as the index of a labeled memory access.
+    pub memory_only: Vec<MemorySlice>,
+}
+
+impl ColumnUsage {
+    /// Returns `true` if every allocated column appears in some constraint.
+    pub fn is_empty(&self) -> bool {
+        self.unused.is_empty() && self.memory_only.is_empty()
+    }
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Finds the allocated trace columns that appear in no constraint of the AIR.
+    ///
+    /// This is a diagnostic to be called before `build`, to find registers which are allocated
+    /// and computed but never constrained. Columns which are still read by memory instructions
+    /// are reported separately from the dead ones. The constraints of the bus channels, which are
+    /// only registered by `build`, are taken into account, but constraints that machine builders
+    /// add when building, such as the byte lookups of `BytesBuilder`, are not.
+    pub fn finalize(&self) -> ColumnUsage
+    where
+        Constraint<L>: AirConstraint<ColumnUsageParser<L::Field>>,
+    {
+        let num_columns = self.extended_index.max(L::num_columns());
+        let mut parser = ColumnUsageParser::new(
+            num_columns,
+            self.shared_memory.challenge_index(),
+            self.shared_memory.global_index(),
+            self.shared_memory.public_index(),
+        );
+        for constraint in self
+            .constraints
+            .iter()
+            .chain(self.global_constraints.iter())
+        {
+            constraint.eval(&mut parser);
+        }
+        for channel in self.bus_channels.iter() {
+            Constraint::<L>::from(channel.clone()).eval(&mut parser);
+        }
+
+        let mut memory_read = vec![false; num_columns];
+        for instruction in self.instructions.iter() {
+            for register in memory_reads(instruction) {
+                if let MemorySlice::Local(index, length) | MemorySlice::Next(index, length) =
+                    register
+                {
+                    memory_read[index..index + length].fill(true);
+                }
+            }
+        }
+
+        let allocated = (0..self.local_arithmetic_index)
+            .chain(L::NUM_ARITHMETIC_COLUMNS..self.local_index)
+            .chain(L::NUM_ARITHMETIC_COLUMNS + L::NUM_FREE_COLUMNS..self.extended_index);
+        let mut usage = ColumnUsage::default();
+        for column in allocated {
+            if parser.constrained[column] {
+                continue;
+            }
+            let runs = if memory_read[column] {
+                &mut usage.memory_only
+            } else {
+                &mut usage.unused
+            };
+            match runs.last_mut() {
+                Some(MemorySlice::Local(index, length)) if *index + *length == column => {
+                    *length += 1
+                }
+                _ => runs.push(MemorySlice::Local(column, 1)),
+            }
+        }
+        usage
+    }
+}
+
+/// The registers read by the memory instructions in `instruction`, including the filters of
+/// filtered memory instructions.
+fn memory_reads<F: Field, I>(instruction: &AirInstruction<F, I>) -> Vec<MemorySlice> {
+    match instruction {
+        AirInstruction::Mem(instr) => instr.read_registers(),
+        AirInstruction::Filtered(filter, instr) => {
+            let mut reads = memory_reads(instr);
+            if !reads.is_empty() || matches!(**instr, AirInstruction::Mem(_)) {
+                reads.extend(filter.registers());
+            }
+            reads
+        }
+        _ => Vec::new(),
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Node {
+    /// A value which does not depend on the trace, such as a constant or a challenge.
+    Leaf,
+    /// A column of the trace, in either the current or the next row.
+    Column(usize),
+    /// A value depending on two other values.
+    Binary(usize, usize),
+}
+
+/// A parser recording which trace columns the constraints depend on.
+///
+/// Every variable is a node of the graph of the operations performed by the constraints, and the
+/// columns reachable from a constraint are marked as constrained.
+#[derive(Debug, Clone)]
+pub struct ColumnUsageParser<F> {
+    nodes: Vec<Node>,
+    visited: Vec<bool>,
+    columns: Vec<usize>,
+    challenges: Vec<usize>,
+    global: Vec<usize>,
+    public: Vec<usize>,
+    constrained: Vec<bool>,
+    _marker: PhantomData<F>,
+}
+
+const LEAF: usize = 0;
+
+impl<F> ColumnUsageParser<F> {
+    fn new(
+        num_columns: usize,
+        num_challenges: usize,
+        num_global_values: usize,
+        num_public_values: usize,
+    ) -> Self {
+        let mut nodes = vec![Node::Leaf];
+        nodes.extend((0..num_columns).map(Node::Column));
+        Self {
+            visited: vec![false; nodes.len()],
+            nodes,
+            columns: (1..=num_columns).collect(),
+            challenges: vec![LEAF; num_challenges],
+            global: vec![LEAF; num_global_values],
+            public: vec![LEAF; num_public_values],
+            constrained: vec![false; num_columns],
+            _marker: PhantomData,
+        }
+    }
+
+    fn combine(&mut self, a: usize, b: usize) -> usize {
+        match (a, b) {
+            (LEAF, other) | (other, LEAF) => other,
+            _ if a == b => a,
+            _ => {
+                self.nodes.push(Node::Binary(a, b));
+                self.visited.push(false);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn mark(&mut self, var: usize) {
+        let mut stack = vec![var];
+        while let Some(node) = stack.pop() {
+            if self.visited[node] {
+                continue;
+            }
+            self.visited[node] = true;
+            match self.nodes[node] {
+                Node::Leaf => {}
+                Node::Column(column) => self.constrained[column] = true,
+                Node::Binary(a, b) => stack.extend([a, b]),
+            }
+        }
+    }
+}
+
+impl<F: Field> AirParser for ColumnUsageParser<F> {
+    type Field = F;
+
+    type Var = usize;
+
+    fn local_slice(&self) -> &[Self::Var] {
+        &self.columns
+    }
+
+    fn next_slice(&self) -> &[Self::Var] {
+        &self.columns
+    }
+
+    fn challenge_slice(&self) -> &[Self::Var] {
+        &self.challenges
+    }
+
+    fn global_slice(&self) -> &[Self::Var] {
+        &self.global
+    }
+
+    fn public_slice(&self) -> &[Self::Var] {
+        &self.public
+    }
+
+    fn constraint(&mut self, constraint: Self::Var) {
+        self.mark(constraint)
+    }
+
+    fn constraint_transition(&mut self, constraint: Self::Var) {
+        self.mark(constraint)
+    }
+
+    fn constraint_first_row(&mut self, constraint: Self::Var) {
+        self.mark(constraint)
+    }
+
+    fn constraint_last_row(&mut self, constraint: Self::Var) {
+        self.mark(constraint)
+    }
+
+    fn constant(&mut self, _value: Self::Field) -> Self::Var {
+        LEAF
+    }
+
+    fn add(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.combine(a, b)
+    }
+
+    fn sub(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.combine(a, b)
+    }
+
+    fn neg(&mut self, a: Self::Var) -> Self::Var {
+        a
+    }
+
+    fn mul(&mut self, a: Self::Var, b: Self::Var) -> Self::Var {
+        self.combine(a, b)
+    }
+}
+
+impl<F: Field> PolynomialParser for ColumnUsageParser<F> {}
+
+impl<F: Field, E: CubicParameters<F>> CubicParser<E> for ColumnUsageParser<F> {}
+
+impl<F: Field, E: QuinticParameters<F>> QuinticParser<E> for ColumnUsageParser<F> {}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::memory::instruction::MemorySliceIndex;
+    use crate::chip::memory::time::Time;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct ColumnUsageTest;
+
+    impl AirParameters for ColumnUsageTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 6;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_finalize_reports_unused_columns() {
+        type L = ColumnUsageTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let x = builder.alloc::<ElementRegister>();
+        let y = builder.alloc::<ElementRegister>();
+        builder.assert_expressions_equal_transition(x.expr() + y.expr(), x.next().expr());
+        assert!(builder.finalize().is_empty());
+
+        // A register which is allocated but never constrained is dead.
+        let dead = builder.alloc::<ElementRegister>();
+
+        // The index of a labeled memory access is only read by the memory instruction.
+        let index = builder.alloc::<ElementRegister>();
+        let value = builder.alloc::<ElementRegister>();
+        builder.assert_expressions_equal(value.expr(), y.expr());
+        let ptr = builder.uninit::<ElementRegister>();
+        builder.set(
+            &ptr,
+            value,
+            &Time::zero(),
+            None,
+            Some("value".to_string()),
+            Some(MemorySliceIndex::IndexElement(index)),
+        );
+
+        let usage = builder.finalize();
+        assert_eq!(usage.unused, vec![*dead.register()]);
+        assert_eq!(usage.memory_only, vec![*index.register()]);
+    }
+}
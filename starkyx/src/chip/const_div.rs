@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+
+use super::arithmetic::expression::ArithmeticExpression;
+use super::builder::AirBuilder;
+use super::instruction::Instruction;
+use super::register::array::ArrayRegister;
+use super::register::bit::BitRegister;
+use super::register::element::ElementRegister;
+use super::register::Register;
+use super::trace::writer::{AirWriter, TraceWriter};
+use super::AirParameters;
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::math::prelude::*;
+
+/// Writes the quotient and the remainder of the division of a value by a constant, together with
+/// the bits of the quotient and the complement of the remainder.
+///
+/// The quotient is decomposed into bits. If the divisor is not a power of two, the complement
+/// `divisor - 1 - remainder` is written as well.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DivRemConstInstruction {
+    value: ElementRegister,
+    divisor: u64,
+    quotient: ElementRegister,
+    remainder: ElementRegister,
+    quotient_bits: ArrayRegister<BitRegister>,
+    complement: Option<ElementRegister>,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Divides `value` by the constant `divisor`, returning the quotient and the remainder.
+    ///
+    /// The result is constrained by `value = quotient * divisor + remainder`, with the remainder
+    /// range checked to `[0, divisor)`. The remainder is checked to `num_bits` bits by
+    /// `range_check`, where `2^num_bits` is the smallest power of two not below the divisor, and
+    /// if the divisor is not a power of two, the complement `divisor - 1 - remainder` is checked
+    /// as well. The quotient is decomposed into the largest number of bits `k` such that
+    /// `2^k * divisor` does not exceed the order of the field, so that the relation can't wrap
+    /// around the field, which takes more bits than the widths of `range_check`. The value must be
+    /// smaller than `2^k * divisor`.
+    pub fn div_rem_const(
+        &mut self,
+        value: &ElementRegister,
+        divisor: u64,
+    ) -> (ElementRegister, ElementRegister)
+    where
+        L::Instruction: From<DivRemConstInstruction>,
+    {
+        assert!(divisor >= 2, "The divisor must be at least 2");
+        let num_quotient_bits = (L::Field::order() / divisor).ilog2() as usize;
+        let num_bits = (u64::BITS - (divisor - 1).leading_zeros()) as usize;
+
+        let quotient = self.alloc::<ElementRegister>();
+        let remainder = self.alloc::<ElementRegister>();
+        let quotient_bits = self.alloc_array::<BitRegister>(num_quotient_bits);
+        let quotient_recomposition = quotient_bits
+            .iter()
+            .enumerate()
+            .fold(ArithmeticExpression::zero(), |acc, (j, bit)| {
+                acc + bit.expr() * L::Field::from_canonical_u64(1 << j)
+            });
+        self.assert_expressions_equal(quotient.expr(), quotient_recomposition);
+
+        self.range_check(&remainder, num_bits);
+        let complement = (!divisor.is_power_of_two()).then(|| {
+            let complement = self.alloc::<ElementRegister>();
+            self.assert_expressions_equal(
+                remainder.expr() + complement.expr(),
+                ArithmeticExpression::from(L::Field::from_canonical_u64(divisor - 1)),
+            );
+            self.range_check(&complement, num_bits);
+            complement
+        });
+        self.assert_expressions_equal(
+            value.expr(),
+            quotient.expr() * L::Field::from_canonical_u64(divisor) + remainder.expr(),
+        );
+
+        self.register_instruction(DivRemConstInstruction {
+            value: *value,
+            divisor,
+            quotient,
+            remainder,
+            quotient_bits,
+            complement,
+        });
+
+        (quotient, remainder)
+    }
+}
+
+impl DivRemConstInstruction {
+    /// The quotient and remainder of `value` and the bits of the quotient.
+    fn div_rem_and_bits(&self, value: u64) -> (u64, u64, Vec<u64>) {
+        let quotient = value / self.divisor;
+        let remainder = value % self.divisor;
+        assert!(
+            quotient >> self.quotient_bits.len() == 0,
+            "Value {} is out of range for the division by {}",
+            value,
+            self.divisor
+        );
+
+        let quotient_bits = (0..self.quotient_bits.len())
+            .map(|j| (quotient >> j) & 1)
+            .collect();
+        (quotient, remainder, quotient_bits)
+    }
+}
+
+impl<AP: AirParser> AirConstraint<AP> for DivRemConstInstruction {
+    // The constraints of the division are registered by `div_rem_const`.
+    fn eval(&self, _parser: &mut AP) {}
+}
+
+impl<F: PrimeField64> Instruction<F> for DivRemConstInstruction {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let value = writer.read(&self.value, row_index).as_canonical_u64();
+        let (quotient, remainder, quotient_bits) = self.div_rem_and_bits(value);
+        writer.write(&self.quotient, &F::from_canonical_u64(quotient), row_index);
+        writer.write(
+            &self.remainder,
+            &F::from_canonical_u64(remainder),
+            row_index,
+        );
+        writer.write_array(
+            &self.quotient_bits,
+            quotient_bits.into_iter().map(F::from_canonical_u64),
+            row_index,
+        );
+        if let Some(complement) = &self.complement {
+            writer.write(
+                complement,
+                &F::from_canonical_u64(self.divisor - 1 - remainder),
+                row_index,
+            );
+        }
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let value = writer.read(&self.value).as_canonical_u64();
+        let (quotient, remainder, quotient_bits) = self.div_rem_and_bits(value);
+        writer.write(&self.quotient, &F::from_canonical_u64(quotient));
+        writer.write(&self.remainder, &F::from_canonical_u64(remainder));
+        writer.write_array(
+            &self.quotient_bits,
+            quotient_bits.into_iter().map(F::from_canonical_u64),
+        );
+        if let Some(complement) = &self.complement {
+            writer.write(
+                complement,
+                &F::from_canonical_u64(self.divisor - 1 - remainder),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::register::memory::MemorySlice;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct DivRemConstTest;
+
+    impl AirParameters for DivRemConstTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = DivRemConstInstruction;
+
+        // The value and, per division, the quotient, the remainder and the quotient bits: 2 + 57
+        // and the complement for the divisor 96 and 2 + 57 for the divisor 64, and the range
+        // check tables of 7 and 6 bits.
+        const NUM_FREE_COLUMNS: usize = 124;
+        const EXTENDED_COLUMNS: usize = 21;
+    }
+
+    /// Proves the divisions of `values` by 96 and 64, with the quotient and remainder by 96 of the
+    /// first row replaced by `forged` if set.
+    fn prove_div_rem_const(values: &[u64], forged: Option<(u64, u64)>) {
+        type F = GoldilocksField;
+        type L = DivRemConstTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let value = builder.alloc::<ElementRegister>();
+        let (quotient_96, remainder_96) = builder.div_rem_const(&value, 96);
+        let (quotient_64, remainder_64) = builder.div_rem_const(&value, 64);
+
+        let (air, trace_data) = builder.build();
+
+        // The trace holds the table of the 7-bit range checks.
+        let num_rows = 1 << 7;
+        assert_eq!(values.len(), num_rows);
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for (i, value_int) in values.iter().enumerate() {
+            writer.write(&value, &F::from_canonical_u64(*value_int), i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let read = |register: &ElementRegister| writer.read(register, i).as_canonical_u64();
+            assert_eq!(read(&quotient_96), value_int / 96);
+            assert_eq!(read(&remainder_96), value_int % 96);
+            assert_eq!(read(&quotient_64), value_int / 64);
+            assert_eq!(read(&remainder_64), value_int % 64);
+        }
+
+        // Overwrite the quotient by 96 and its bits, which are allocated right after the
+        // remainder, the remainder and its complement, which is allocated right after the bits.
+        if let Some((quotient, remainder)) = forged {
+            writer.write(&quotient_96, &F::from_canonical_u64(quotient), 0);
+            writer.write(&remainder_96, &F::from_canonical_u64(remainder), 0);
+            let quotient_bits = (0..57).map(|j| F::from_canonical_u64((quotient >> j) & 1));
+            writer.write_unsafe_raw(
+                MemorySlice::Local(3, 57),
+                &quotient_bits.collect::<Vec<_>>(),
+                0,
+            );
+            let complement = F::from_canonical_u8(95) - F::from_canonical_u64(remainder);
+            writer.write_unsafe_raw(MemorySlice::Local(60, 1), &[complement], 0);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.public().unwrap().clone();
+        test_starky(&stark, &config, &generator, &public_inputs);
+    }
+
+    #[test]
+    fn test_div_rem_const() {
+        let mut rng = rand::thread_rng();
+        let mut values = vec![0, 1, 63, 64, 95, 96, 191, 192, 96 * 64 - 1, u32::MAX as u64];
+        values.extend((values.len()..1 << 7).map(|_| rng.gen_range(0..1u64 << 40)));
+        prove_div_rem_const(&values, None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_div_rem_const_remainder_out_of_range() {
+        // Replace `200 = 2 * 96 + 8` by `200 = 1 * 96 + 104` in the first row. The remainder
+        // fits in 7 bits, but its complement `95 - 104` does not.
+        prove_div_rem_const(&[200; 1 << 7], Some((1, 104)));
+    }
+}
@@ -1,9 +1,13 @@
+use core::ops::Range;
+
 use serde::{Deserialize, Serialize};
 
 use super::arithmetic::ArithmeticConstraint;
 use super::instruction::set::AirInstruction;
 use super::memory::pointer::accumulate::PointerAccumulator;
 use super::register::cubic::CubicRegister;
+use super::register::element::ElementRegister;
+use super::register::extension::{ChallengeExtension, EvalExtension};
 use super::table::accumulator::Accumulator;
 use super::table::bus::channel::BusChannel;
 use super::table::bus::global::Bus;
@@ -11,6 +15,7 @@ use super::table::lookup::constraint::LookupChipConstraint;
 use super::table::powers::Powers;
 use super::AirParameters;
 use crate::air::extension::cubic::CubicParser;
+use crate::air::extension::ExtensionParser;
 use crate::air::parser::{AirParser, MulParser};
 use crate::air::AirConstraint;
 
@@ -23,7 +28,27 @@ pub enum Constraint<L: AirParameters> {
     Pointer(PointerAccumulator<L::Field, L::CubicParams>),
     BusChannel(BusChannel<CubicRegister, L::CubicParams>),
     Bus(Bus<CubicRegister, L::CubicParams>),
-    Lookup(LookupChipConstraint<L::Field, L::CubicParams>),
+    Lookup(LookupChipConstraint<L::Field, L::CubicParams, L::LookupExtension>),
+}
+
+/// A label attached to the constraints emitted while it was active on the builder's tag stack.
+///
+/// The ranges index into the chip's local and global constraint lists respectively.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintTag {
+    pub label: String,
+    pub constraints: Range<usize>,
+    pub global_constraints: Range<usize>,
+}
+
+impl ConstraintTag {
+    pub fn new(label: String, constraints: Range<usize>, global_constraints: Range<usize>) -> Self {
+        Self {
+            label,
+            constraints,
+            global_constraints,
+        }
+    }
 }
 
 impl<L: AirParameters> Constraint<L> {
@@ -40,7 +65,9 @@ impl<L: AirParameters> Constraint<L> {
         Self::Instruction(AirInstruction::CustomInstruction(instruction.into()))
     }
 
-    pub fn lookup(lookup: LookupChipConstraint<L::Field, L::CubicParams>) -> Self {
+    pub fn lookup(
+        lookup: LookupChipConstraint<L::Field, L::CubicParams, L::LookupExtension>,
+    ) -> Self {
         Self::Lookup(lookup)
     }
 }
@@ -49,6 +76,11 @@ impl<L: AirParameters, AP: AirParser<Field = L::Field>> AirConstraint<AP> for Co
 where
     L::Instruction: AirConstraint<AP> + for<'a> AirConstraint<MulParser<'a, AP>>,
     AP: CubicParser<<L as AirParameters>::CubicParams>,
+    AP: ExtensionParser<
+        L::LookupExtension,
+        Register = <L::LookupExtension as ChallengeExtension<L::Field>>::Register,
+    >,
+    ElementRegister: EvalExtension<L::LookupExtension>,
 {
     fn eval(&self, parser: &mut AP) {
         match self {
@@ -4,6 +4,7 @@ use core::marker::PhantomData;
 use serde::{Deserialize, Serialize};
 
 use super::parameters::FieldParameters;
+use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::builder::AirBuilder;
 use crate::chip::memory::pointer::raw::RawPointer;
 use crate::chip::memory::time::Time;
@@ -14,6 +15,7 @@ use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::u16::U16Register;
 use crate::chip::register::{Register, RegisterSerializable, RegisterSized};
+use crate::chip::uint::register::word_bytes;
 use crate::math::prelude::*;
 use crate::polynomial::Polynomial;
 
@@ -59,9 +61,10 @@ impl<P: FieldParameters> Register for FieldRegister<P> {
 }
 
 impl<P: FieldParameters> MemoryValue for FieldRegister<P> {
-    fn num_challenges() -> usize {
-        assert_eq!(P::NB_LIMBS % 2, 0);
-        P::NB_LIMBS / 2 + 1
+    fn num_challenges<F: PrimeField64>() -> usize {
+        let limbs_per_word = word_bytes::<F>() / 2;
+        assert_eq!(P::NB_LIMBS % limbs_per_word, 0);
+        P::NB_LIMBS / limbs_per_word + 1
     }
 
     fn compress<L: crate::chip::AirParameters>(
@@ -73,11 +76,18 @@ impl<P: FieldParameters> MemoryValue for FieldRegister<P> {
     ) -> CubicRegister {
         let limb_array = ArrayRegister::<U16Register>::from_register_unsafe(self.register);
         assert_eq!(limb_array.len(), P::NB_LIMBS);
+        // Pairs of limbs are packed into 32-bit words only if these fit in the field.
+        let limbs_per_word = word_bytes::<L::Field>() / 2;
         let expressions = (0..P::NB_LIMBS)
-            .step_by(2)
+            .step_by(limbs_per_word)
             .map(|i| {
-                let pair = limb_array.get_subarray(i..i + 2);
-                pair.get(0).expr() + pair.get(1).expr() * L::Field::from_canonical_u32(1 << 16)
+                limb_array
+                    .get_subarray(i..i + limbs_per_word)
+                    .iter()
+                    .enumerate()
+                    .fold(ArithmeticExpression::zero(), |acc, (j, limb)| {
+                        acc + limb.expr() * L::Field::from_canonical_u32(1 << (16 * j))
+                    })
             })
             .chain(once(time.expr()))
             .collect::<Vec<_>>();
@@ -4,7 +4,6 @@ use super::set::AirInstruction;
 use super::Instruction;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
-use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::builder::AirBuilder;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
@@ -12,6 +11,8 @@ use crate::chip::register::element::ElementRegister;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::AirParameters;
+use crate::machine::builder::flag::CyclicFlag;
+use crate::machine::builder::Builder;
 use crate::math::prelude::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,12 +32,20 @@ pub struct ProcessIdInstruction {
     end_bit: BitRegister,
 }
 
+/// A one-hot selector of the row modulo `num_iterations`: the register of iteration `i` is set
+/// in the rows whose index is `i` modulo `num_iterations`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Loop {
     num_iterations: usize,
     iterations_registers: ArrayRegister<BitRegister>,
 }
 
 impl Loop {
+    /// The number of iterations of the loop, one per row.
+    pub fn num_iterations(&self) -> usize {
+        self.num_iterations
+    }
+
     pub fn get_iteration_reg(&self, index: usize) -> BitRegister {
         assert!(
             index < self.num_iterations,
@@ -46,6 +55,29 @@ impl Loop {
     }
 }
 
+impl ProcessIdInstruction {
+    /// The number of rows of every process.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+impl<F: Field> Cycle<F> {
+    /// The number of rows of the cycle.
+    pub fn length(&self) -> usize {
+        self.group.len()
+    }
+
+    /// Allocates a register holding the position of the row within the cycle, that is the row
+    /// index modulo the length of the cycle.
+    ///
+    /// The position is zero in the first row, is incremented in every row, and wraps around to
+    /// zero after every row in which `end_bit` is set. Every call allocates a new register.
+    pub fn position<B: Builder<Field = F>>(&self, builder: &mut B) -> ElementRegister {
+        builder.cyclic_flag(&CyclicFlag::counter("cycle_position", self.end_bit))
+    }
+}
+
 impl<L: AirParameters> AirBuilder<L> {
     pub fn cycle(&mut self, length_log: usize) -> Cycle<L::Field> {
         let start_bit = self.alloc::<BitRegister>();
@@ -82,34 +114,14 @@ impl<L: AirParameters> AirBuilder<L> {
 
     pub fn loop_instr(&mut self, num_iterations: usize) -> Loop {
         let iterations_registers = self.alloc_array::<BitRegister>(num_iterations);
-
-        // Set the cycle 12 registers first row
-        self.set_to_expression_first_row(
-            &iterations_registers.get(0),
-            ArithmeticExpression::from_constant(L::Field::from_canonical_usize(1)),
-        );
-
-        for i in 1..num_iterations {
-            self.set_to_expression_first_row(
-                &iterations_registers.get(i),
-                ArithmeticExpression::from_constant(L::Field::from_canonical_usize(0)),
-            );
-        }
-
-        // Set transition constraint for the cycle_12_registers
-        for i in 0..num_iterations {
-            let next_i = (i + 1) % num_iterations;
-
-            self.set_to_expression_transition(
-                &iterations_registers.get(next_i).next(),
-                iterations_registers.get(i).expr(),
-            );
-        }
-
-        Loop {
+        let loop_instr = Loop {
             num_iterations,
             iterations_registers,
-        }
+        };
+
+        self.register_air_instruction_internal(AirInstruction::Loop(loop_instr.clone()));
+
+        loop_instr
     }
 }
 
@@ -234,6 +246,45 @@ impl<F: Field> Instruction<F> for Cycle<F> {
     }
 }
 
+impl<AP: AirParser<Field = F>, F: Field> AirConstraint<AP> for Loop {
+    fn eval(&self, parser: &mut AP) {
+        // The first iteration is selected in the first row, and every iteration is followed by
+        // the next one, the last one by the first one.
+        for i in 0..self.num_iterations {
+            let bit = self.iterations_registers.get(i).eval(parser);
+            let first_row_constraint = if i == 0 {
+                parser.sub_const(bit, F::ONE)
+            } else {
+                bit
+            };
+            parser.constraint_first_row(first_row_constraint);
+
+            let next_i = (i + 1) % self.num_iterations;
+            let next_bit = self.iterations_registers.get(next_i).next().eval(parser);
+            let transition_constraint = parser.sub(next_bit, bit);
+            parser.constraint_transition(transition_constraint);
+        }
+    }
+}
+
+impl<F: Field> Instruction<F> for Loop {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let iteration = row_index % self.num_iterations;
+        for i in 0..self.num_iterations {
+            let bit = F::from_canonical_usize((i == iteration) as usize);
+            writer.write(&self.iterations_registers.get(i), &bit, row_index);
+        }
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let iteration = writer.row_index().unwrap() % self.num_iterations;
+        for i in 0..self.num_iterations {
+            let bit = F::from_canonical_usize((i == iteration) as usize);
+            writer.write(&self.iterations_registers.get(i), &bit);
+        }
+    }
+}
+
 impl<AP: AirParser<Field = F>, F: Field> AirConstraint<AP> for ProcessIdInstruction {
     fn eval(&self, parser: &mut AP) {
         // Impose that `process_id` is the cumulative sum of end_bits.
@@ -316,4 +367,44 @@ mod tests {
             air.eval(&mut window_parser);
         }
     }
+
+    #[test]
+    fn test_cycle_position() {
+        type F = GoldilocksField;
+        type L = CycleTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let cycle = builder.cycle(3);
+        let position = cycle.position(&mut builder);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 6;
+        let period = 1 << 3;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in 0..num_rows {
+            assert_eq!(
+                writer.read(&position, i),
+                F::from_canonical_usize(i % period),
+                "position in row {i}"
+            );
+            if writer.read(&cycle.end_bit, i) == F::ONE {
+                assert_eq!(i % period, period - 1);
+                if i + 1 < num_rows {
+                    assert_eq!(writer.read(&position, i + 1), F::ZERO);
+                }
+            }
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
 }
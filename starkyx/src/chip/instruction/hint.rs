@@ -0,0 +1,166 @@
+use alloc::sync::Arc;
+use core::fmt::Debug;
+
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
+use crate::math::prelude::*;
+
+/// The function computing the values of a hint in a row of the trace.
+pub type HintFn<F> = Arc<dyn Fn(&HintReader<F>, usize) -> Vec<F> + Send + Sync>;
+
+/// Reads the values already written to the trace when a hint is computed.
+pub struct HintReader<'a, F> {
+    read_slice: &'a dyn Fn(&MemorySlice) -> Vec<F>,
+}
+
+impl<'a, F: Copy> HintReader<'a, F> {
+    /// Reads the value of `data` in the row of the hint, or its value if it is public.
+    pub fn read<T: Register>(&self, data: &T) -> T::Value<F> {
+        T::value_from_slice(&(self.read_slice)(data.register()))
+    }
+}
+
+/// Writes the values of a register computed by a host function during trace generation.
+///
+/// The instruction has no constraints, so the hinted values must be constrained by the rest of
+/// the AIR. Since the function cannot be serialized, neither can an AIR with a hint.
+#[derive(Clone)]
+pub struct HintInstruction<F> {
+    pub(crate) register: MemorySlice,
+    pub(crate) hint: HintFn<F>,
+}
+
+impl<F> Debug for HintInstruction<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HintInstruction")
+            .field("register", &self.register)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: Field> HintInstruction<F> {
+    fn values(&self) -> ArrayRegister<ElementRegister> {
+        ArrayRegister::from_register_unsafe(self.register)
+    }
+
+    pub(crate) fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let read_slice = |slice: &MemorySlice| {
+            writer.read_vec(
+                &ArrayRegister::<ElementRegister>::from_register_unsafe(*slice),
+                row_index,
+            )
+        };
+        let reader = HintReader {
+            read_slice: &read_slice,
+        };
+        let values = (self.hint)(&reader, row_index);
+        writer.write_array(&self.values(), values, row_index);
+    }
+
+    pub(crate) fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let row_index = writer.row_index().unwrap_or(0);
+        let values = {
+            let read_slice = |slice: &MemorySlice| writer.read_slice(slice).to_vec();
+            let reader = HintReader {
+                read_slice: &read_slice,
+            };
+            (self.hint)(&reader, row_index)
+        };
+        writer.write_array(&self.values(), values);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::AirParameters;
+    use crate::machine::builder::Builder;
+    use crate::machine::stark::builder::StarkBuilder;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::prelude::AirWriterData;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct HintTest;
+
+    impl AirParameters for HintTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 3;
+    }
+
+    #[test]
+    fn test_hint_inverse() {
+        type F = GoldilocksField;
+        type L = HintTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let clk = builder.clock();
+        // The inverse of `clk + 1` is hinted, and checked by a constraint.
+        let inverse = builder.alloc_with_hint::<ElementRegister>(move |reader, _| {
+            (reader.read(&clk) + F::ONE).inverse()
+        });
+        builder.assert_expressions_equal((clk.expr() + F::ONE) * inverse.expr(), F::ONE.into());
+
+        let (air, trace_data) = builder.build();
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+            assert_eq!(
+                writer.read(&inverse, i) * F::from_canonical_usize(i + 1),
+                F::ONE
+            );
+        }
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.public().unwrap().clone();
+        test_starky(&stark, &config, &generator, &public_inputs);
+    }
+
+    #[test]
+    fn test_hint_air_writer() {
+        type F = GoldilocksField;
+        type L = HintTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = StarkBuilder::<L>::new();
+        let clk = builder.clk();
+        let inverse = builder.alloc_with_hint::<ElementRegister>(move |reader, row_index| {
+            assert_eq!(reader.read(&clk), F::from_canonical_usize(row_index));
+            F::from_canonical_usize(row_index + 1).inverse()
+        });
+        builder.assert_expressions_equal((clk.expr() + F::ONE) * inverse.expr(), F::ONE.into());
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        stark.air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new("test_hint_air_writer", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::set::AirInstruction;
+use super::Instruction;
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::math::prelude::*;
+
+/// Writes the inverse of an expression to a register, or zero if the expression is zero.
+///
+/// The instruction has no constraints, so the inverse must be constrained by the rest of the AIR.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InverseInstruction<F> {
+    source: ArithmeticExpression<F>,
+    inverse: ElementRegister,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Returns a bit set in the rows where `expression` is zero.
+    pub fn is_zero(&mut self, expression: ArithmeticExpression<L::Field>) -> BitRegister {
+        let inverse = self.alloc::<ElementRegister>();
+        let instruction = InverseInstruction {
+            source: expression.clone(),
+            inverse,
+        };
+        self.register_air_instruction_internal(AirInstruction::Inverse(instruction));
+
+        // If the expression is not zero, the bit must vanish, and otherwise it is one whatever
+        // the inverse is.
+        let is_zero = self.expression::<BitRegister>(
+            ArithmeticExpression::one() - expression.clone() * inverse.expr(),
+        );
+        self.assert_expression_zero(expression * is_zero.expr());
+        is_zero
+    }
+}
+
+impl<AP: AirParser> AirConstraint<AP> for InverseInstruction<AP::Field> {
+    // No constraints for this instruction.
+    fn eval(&self, _parser: &mut AP) {}
+}
+
+impl<F: Field> Instruction<F> for InverseInstruction<F> {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let value = writer.read_expression(&self.source, row_index)[0];
+        writer.write(
+            &self.inverse,
+            &value.try_inverse().unwrap_or(F::ZERO),
+            row_index,
+        );
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let value = writer.read_expression(&self.source)[0];
+        writer.write(&self.inverse, &value.try_inverse().unwrap_or(F::ZERO));
+    }
+}
@@ -1,12 +1,14 @@
 use alloc::sync::Arc;
 
-use log::debug;
+use log::{debug, log_enabled, Level};
 use serde::{Deserialize, Serialize};
 
 use super::assign::AssignInstruction;
 use super::bit::BitConstraint;
 use super::clock::ClockInstruction;
-use super::cycle::{Cycle, ProcessIdInstruction};
+use super::cycle::{Cycle, Loop, ProcessIdInstruction};
+use super::hint::HintInstruction;
+use super::inverse::InverseInstruction;
 use super::Instruction;
 use crate::air::parser::{AirParser, MulParser};
 use crate::air::AirConstraint;
@@ -26,11 +28,15 @@ pub enum AirInstruction<F, I> {
     Assign(AssignInstruction<F>),
     Select(SelectInstruction),
     Cycle(Cycle<F>),
+    Loop(Loop),
     Clock(ClockInstruction),
     ProcessId(ProcessIdInstruction),
+    Inverse(InverseInstruction<F>),
     Filtered(ArithmeticExpression<F>, Arc<Self>),
     Mem(MemoryInstruction<F>),
     Watch(String, ArrayRegister<ElementRegister>),
+    #[serde(skip)]
+    Hint(HintInstruction<F>),
 }
 
 impl<F: Field, AP: AirParser<Field = F>, I> AirConstraint<AP> for AirInstruction<F, I>
@@ -44,8 +50,10 @@ where
             AirInstruction::Assign(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::Select(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::Cycle(i) => AirConstraint::<AP>::eval(i, parser),
+            AirInstruction::Loop(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::Clock(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::ProcessId(i) => AirConstraint::<AP>::eval(i, parser),
+            AirInstruction::Inverse(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::Filtered(expression, instr) => {
                 assert_eq!(
                     expression.size, 1,
@@ -58,11 +66,13 @@ where
                     AirInstruction::BitConstraint(i) => i.eval(&mut mul_parser),
                     AirInstruction::Assign(i) => i.eval(&mut mul_parser),
                     AirInstruction::Cycle(i) => i.eval(&mut mul_parser),
+                    AirInstruction::Mem(i) => i.eval(&mut mul_parser),
                     _ => unreachable!("Instructions cannot be filtered twice"),
                 }
             }
             AirInstruction::Mem(i) => AirConstraint::<AP>::eval(i, parser),
             AirInstruction::Watch(_, _) => {}
+            AirInstruction::Hint(_) => {}
         }
     }
 }
@@ -75,8 +85,10 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
             AirInstruction::Select(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::Assign(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::Cycle(i) => Instruction::<F>::write(i, writer, row_index),
+            AirInstruction::Loop(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::Clock(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::ProcessId(i) => Instruction::<F>::write(i, writer, row_index),
+            AirInstruction::Inverse(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::Filtered(expression, i) => {
                 let filter = writer.read_expression(expression, row_index)[0];
                 if filter == F::ONE {
@@ -85,9 +97,12 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
             }
             AirInstruction::Mem(i) => Instruction::<F>::write(i, writer, row_index),
             AirInstruction::Watch(name, register) => {
-                let value = writer.read_vec(register, row_index);
-                debug!("row {}: , {}: {:?}", row_index, name, value);
+                if log_enabled!(Level::Debug) {
+                    let value = writer.read_vec(register, row_index);
+                    debug!("row {}: , {}: {:?}", row_index, name, value);
+                }
             }
+            AirInstruction::Hint(i) => i.write(writer, row_index),
         }
     }
 
@@ -98,8 +113,10 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
             AirInstruction::Select(i) => i.write_to_air(writer),
             AirInstruction::Assign(i) => i.write_to_air(writer),
             AirInstruction::Cycle(i) => i.write_to_air(writer),
+            AirInstruction::Loop(i) => i.write_to_air(writer),
             AirInstruction::Clock(i) => i.write_to_air(writer),
             AirInstruction::ProcessId(i) => i.write_to_air(writer),
+            AirInstruction::Inverse(i) => i.write_to_air(writer),
             AirInstruction::Filtered(expression, i) => {
                 let filter = writer.read_expression(expression)[0];
                 if filter == F::ONE {
@@ -107,7 +124,7 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
                 }
             }
             AirInstruction::Mem(i) => i.write_to_air(writer),
-            AirInstruction::Watch(name, register) => {
+            AirInstruction::Watch(name, register) if log_enabled!(Level::Debug) => {
                 let value = writer.read_vec(register);
                 let row_index = writer.row_index();
                 if let Some(index) = row_index {
@@ -116,6 +133,8 @@ impl<F: Field, I: Instruction<F>> Instruction<F> for AirInstruction<F, I> {
                     debug!("{}: {:?}", name, value);
                 }
             }
+            AirInstruction::Watch(_, _) => {}
+            AirInstruction::Hint(i) => i.write_to_air(writer),
         }
     }
 }
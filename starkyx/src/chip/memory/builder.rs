@@ -2,20 +2,24 @@ use core::borrow::Borrow;
 
 use super::get::GetInstruction;
 use super::instruction::{MemoryInstruction, MemoryOutput, MemorySliceIndex};
+use super::pointer::raw::RawPointer;
 use super::pointer::slice::{RawSlice, Slice};
 use super::pointer::Pointer;
+use super::reads::{CountedSlot, CountedStore, ReadCount};
 use super::set::SetInstruction;
 use super::time::Time;
 use super::value::MemoryValue;
 use super::watch::WatchInstruction;
 use crate::chip::builder::AirBuilder;
 use crate::chip::instruction::set::AirInstruction;
+use crate::chip::register::bit::BitRegister;
 use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::element::ElementRegister;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::slice::RegisterSlice;
-use crate::chip::register::RegisterSerializable;
+use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::AirParameters;
+use crate::math::prelude::*;
 
 impl<L: AirParameters> AirBuilder<L> {
     /// Initializes the memory bus.
@@ -38,6 +42,7 @@ impl<L: AirParameters> AirBuilder<L> {
             panic!("Cannot initialize a trace register");
         }
         let ptr = self.uninit();
+        self.record_timestamp(time);
         let digest = value.compress(self, ptr.raw, time, &ptr.challenges);
         self.input_to_memory_bus(digest, multiplicity);
         self.unsafe_raw_write(&ptr, *value, multiplicity, true, None);
@@ -48,7 +53,7 @@ impl<L: AirParameters> AirBuilder<L> {
     #[inline]
     pub(crate) fn uninit<V: MemoryValue>(&mut self) -> Pointer<V> {
         let ptr_challenge_powers = self.challenge_powers(3);
-        let compression_challenges = self.challenge_powers(V::num_challenges());
+        let compression_challenges = self.challenge_powers(V::num_challenges::<L::Field>());
         Pointer::from_challenges(ptr_challenge_powers, compression_challenges)
     }
 
@@ -59,6 +64,7 @@ impl<L: AirParameters> AirBuilder<L> {
         value: V,
         last_write: &Time<L::Field>,
     ) {
+        self.record_timestamp(last_write);
         let digest = value.compress(self, ptr.raw, last_write, &ptr.challenges);
         self.output_from_memory_bus(digest)
     }
@@ -71,6 +77,7 @@ impl<L: AirParameters> AirBuilder<L> {
         multiplicity: Option<ElementRegister>,
     ) -> Slice<V> {
         let slice = self.uninit_slice();
+        self.record_timestamp(time);
 
         for (i, value) in values.value_iter().enumerate() {
             let value = value.borrow();
@@ -85,7 +92,7 @@ impl<L: AirParameters> AirBuilder<L> {
     #[inline]
     pub(crate) fn uninit_slice<V: MemoryValue>(&mut self) -> Slice<V> {
         let raw_slice = RawSlice::new(self);
-        let compression_challenges = self.challenge_powers(V::num_challenges());
+        let compression_challenges = self.challenge_powers(V::num_challenges::<L::Field>());
         Slice::new(raw_slice, compression_challenges)
     }
 
@@ -134,12 +141,80 @@ impl<L: AirParameters> AirBuilder<L> {
             ts: (*last_write_ts).clone(),
         });
 
+        self.record_timestamp(last_write_ts);
         let value = self.unsafe_raw_read(ptr, memory_output);
         let read_digest = value.compress(self, ptr.raw, last_write_ts, &ptr.challenges);
         self.output_from_memory_bus(read_digest);
         value
     }
 
+    /// Reads the value from the memory at location `ptr` into a public register.
+    ///
+    /// The read is done once, when writing the global instructions, so the memory location must be
+    /// written before by a global instruction.
+    pub(crate) fn get_public<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        last_write_ts: &Time<L::Field>,
+    ) -> V {
+        self.record_timestamp(last_write_ts);
+        let value = self.alloc_public::<V>();
+        let instr = MemoryInstruction::Get(GetInstruction::new(ptr.raw, *value.register(), None));
+        self.register_global_air_instruction_internal(AirInstruction::mem(instr));
+        let read_digest = value.compress(self, ptr.raw, last_write_ts, &ptr.challenges);
+        self.output_from_memory_bus(read_digest);
+        value
+    }
+
+    /// Reads the value from the memory at location `ptr` in the rows where `filter` is set.
+    ///
+    /// In the rows where `filter` is not set, nothing is read and the returned register is left
+    /// unconstrained.
+    pub fn get_filtered<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        last_write_ts: &Time<L::Field>,
+        filter: BitRegister,
+    ) -> V {
+        self.record_timestamp(last_write_ts);
+        let value = self.alloc::<V>();
+        let instr = MemoryInstruction::Get(GetInstruction::new(ptr.raw, *value.register(), None));
+        self.register_air_instruction_internal(
+            AirInstruction::mem(instr).as_filtered(filter.expr()),
+        );
+        let read_digest = value.compress(self, ptr.raw, last_write_ts, &ptr.challenges);
+        self.output_from_bus_filtered(0, read_digest, filter);
+        value
+    }
+
+    /// Reads the value from the memory at location `ptr` if the location is written, returning
+    /// the value and a bit set if it is.
+    ///
+    /// If the bit is not set, nothing is read from the memory bus and the value is zero. The bit
+    /// can only be set if the location was written with write time `last_write_ts`, for the read
+    /// to be balanced by the write.
+    ///
+    /// An unset bit only shows that the location was not written if the multiplicity of its write
+    /// is fixed by the builder to count this read, as for a write of multiplicity one that nothing
+    /// else reads or frees. A write whose multiplicity is a witness, or which is freed, can be
+    /// balanced without this read, so the prover is free to unset the bit of a written location.
+    pub fn get_checked<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        last_write_ts: &Time<L::Field>,
+    ) -> (V, BitRegister) {
+        self.record_timestamp(last_write_ts);
+        let value = self.alloc::<V>();
+        let written = self.alloc::<BitRegister>();
+        let instr =
+            MemoryInstruction::Get(GetInstruction::checked(ptr.raw, *value.register(), written));
+        self.register_air_instruction_internal(AirInstruction::mem(instr));
+        self.assert_expression_zero(written.not_expr() * value.expr());
+        let read_digest = value.compress(self, ptr.raw, last_write_ts, &ptr.challenges);
+        self.output_from_bus_filtered(0, read_digest, written);
+        (value, written)
+    }
+
     fn unsafe_raw_read<V: MemoryValue>(
         &mut self,
         ptr: &Pointer<V>,
@@ -196,6 +271,7 @@ impl<L: AirParameters> AirBuilder<L> {
                 assert!(mult.is_trace());
             }
         }
+        self.record_timestamp(write_ts);
         let write_digest = value.compress(self, ptr.raw, write_ts, &ptr.challenges);
         self.input_to_memory_bus(write_digest, multiplicity);
 
@@ -214,8 +290,537 @@ impl<L: AirParameters> AirBuilder<L> {
         );
     }
 
+    /// Writes `value` to the memory at location `ptr` with write time given by `write_ts`, with a
+    /// multiplicity equal to the loads registered against the returned slot with `count_reads`.
+    ///
+    /// The value can't be a trace register, since the slot is written once, by a global
+    /// instruction registered when the AIR is built.
+    pub fn set_counted<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        value: V,
+        write_ts: &Time<L::Field>,
+        label: Option<String>,
+        index: Option<MemorySliceIndex>,
+    ) -> CountedSlot {
+        assert!(
+            !value.is_trace(),
+            "Can only count the loads of a slot holding a value outside the trace"
+        );
+        let multiplicity = self.alloc_public::<ElementRegister>();
+        self.record_timestamp(write_ts);
+        let write_digest = value.compress(self, ptr.raw, write_ts, &ptr.challenges);
+        self.input_to_memory_bus(write_digest, Some(multiplicity));
+
+        let memory_output = label.map(|label| MemoryOutput {
+            label,
+            index,
+            ts: (*write_ts).clone(),
+        });
+        let instruction = SetInstruction::new(
+            ptr.raw,
+            *value.register(),
+            Some(multiplicity),
+            memory_output,
+        );
+        self.counted_stores.push(CountedStore {
+            instruction,
+            multiplicity,
+            reads: ReadCount::new(),
+        });
+        CountedSlot::new(self.counted_stores.len() - 1, ptr.raw, multiplicity)
+    }
+
+    /// Registers the loads `reads` of the counted slot `slot`, given by `set_counted`, by a load
+    /// of the location `ptr`.
+    ///
+    /// The location must be the slot in the rows of the loads, so `ptr` must be in the slice of
+    /// the slot, with the same shift or with an index selected from the index of the slot.
+    pub fn count_reads(&mut self, slot: &CountedSlot, ptr: &RawPointer, reads: ReadCount) {
+        let slot_ptr = slot.ptr();
+        let can_read_slot = ptr.is_in_slice_of(slot_ptr)
+            && ptr.constant_shift() == slot_ptr.constant_shift()
+            && match (ptr.element_shift(), slot_ptr.element_shift()) {
+                (None, None) => true,
+                (Some(index), Some(slot_index)) => {
+                    self.is_selected_from(*index.register(), *slot_index.register())
+                }
+                _ => false,
+            };
+        assert!(
+            can_read_slot,
+            "The location of a counted load can't point to the slot it is counted in"
+        );
+        let store = &mut self.counted_stores[slot.id()];
+        store.reads = store.reads.clone() + reads;
+    }
+
+    /// Whether `register` is `source`, or is selected from it by select instructions.
+    fn is_selected_from(&self, register: MemorySlice, source: MemorySlice) -> bool {
+        register == source
+            || self
+                .instructions
+                .iter()
+                .chain(self.global_instructions.iter())
+                .any(|instr| match instr {
+                    AirInstruction::Select(select) if select.result == register => {
+                        let (_, true_value, false_value) = select.selection();
+                        self.is_selected_from(true_value, source)
+                            || self.is_selected_from(false_value, source)
+                    }
+                    _ => false,
+                })
+    }
+
+    /// Writes the counted slots, each with the multiplicity set to the loads registered against
+    /// it.
+    ///
+    /// This is called by `build` and `merge`, once all loads are registered.
+    pub(crate) fn register_counted_stores(&mut self) {
+        for store in core::mem::take(&mut self.counted_stores) {
+            self.set_to_expression_public(&store.multiplicity, store.reads.expression());
+            let instr = MemoryInstruction::Set(store.instruction);
+            self.register_global_air_instruction_internal(AirInstruction::mem(instr));
+        }
+    }
+
+    /// Writes `value` to the memory at location `ptr` with write time given by `write_ts` in the
+    /// rows where `filter` is set.
+    ///
+    /// In the rows where `filter` is not set, nothing is written and the value is not added to the
+    /// memory bus, so these rows leave the memory argument unchanged whatever their values are.
+    pub fn set_filtered<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        value: V,
+        write_ts: &Time<L::Field>,
+        filter: BitRegister,
+    ) {
+        assert!(
+            value.is_trace(),
+            "Can only filter a write of a trace register"
+        );
+        self.record_timestamp(write_ts);
+        let write_digest = value.compress(self, ptr.raw, write_ts, &ptr.challenges);
+        self.input_to_bus_filtered(0, write_digest, filter);
+
+        let instr =
+            MemoryInstruction::Set(SetInstruction::new(ptr.raw, *value.register(), None, None));
+        self.register_air_instruction_internal(
+            AirInstruction::mem(instr).as_filtered(filter.expr()),
+        );
+    }
+
+    /// Reserves the timestamp `ts` for dummy memory accesses, whose timestamps must not be equal
+    /// to the constant timestamp of any other access.
+    pub fn reserve_timestamp(&mut self, ts: u64) {
+        assert!(
+            ts < L::Field::order(),
+            "Reserved timestamp {} is not smaller than the order of the field",
+            ts
+        );
+        self.reserved_timestamps.insert(ts);
+    }
+
+    /// The constant timestamps of the memory accesses so far, in increasing order.
+    pub fn constant_timestamps(&self) -> Vec<u64> {
+        self.constant_timestamps.iter().copied().collect()
+    }
+
+    /// Checks that no memory access has a constant timestamp which is reserved for the dummy
+    /// accesses.
+    ///
+    /// This is called by `build`.
+    pub fn check_timestamps(&self) {
+        if let Some(ts) = self
+            .constant_timestamps
+            .intersection(&self.reserved_timestamps)
+            .next()
+        {
+            panic!(
+                "The constant timestamp {} of a memory access is reserved for dummy accesses",
+                ts
+            );
+        }
+    }
+
+    fn record_timestamp(&mut self, time: &Time<L::Field>) {
+        if let Some(ts) = time.as_constant() {
+            self.constant_timestamps.insert(ts);
+        }
+    }
+
     pub fn watch_memory<V: MemoryValue>(&mut self, ptr: &Pointer<V>, name: &str) {
+        if !self.debug {
+            return;
+        }
         let instr = MemoryInstruction::Watch(WatchInstruction::new(ptr.raw, name.to_string()));
         self.register_air_instruction_internal(AirInstruction::mem(instr));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use rand::Rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::air::debug::ConstraintFailure;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::trace::writer::{InnerWriterData, TraceWriter};
+    use crate::machine::builder::Builder;
+    use crate::machine::stark::builder::StarkBuilder;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct FilteredMemoryTest;
+
+    impl AirParameters for FilteredMemoryTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 5;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_filtered_memory_accesses() {
+        type F = GoldilocksField;
+        type L = FilteredMemoryTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = StarkBuilder::<L>::new();
+        let clk = builder.clk();
+        let active = builder.alloc::<BitRegister>();
+        let value = builder.alloc::<ElementRegister>();
+        let ts = builder.alloc::<ElementRegister>();
+
+        // Every active row stores a value in its own slot and reads it back.
+        let slice = builder.uninit_slice::<ElementRegister>();
+        let ptr = slice.get_at(clk);
+        let time = Time::from_element(ts);
+        builder.store_filtered(&ptr, value, &time, active);
+        let loaded = builder.load_filtered(&ptr, &time, active);
+        builder.assert_expression_zero(active.expr() * (loaded.expr() - value.expr()));
+
+        let num_rows = 1 << 8;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        // The disabled rows use random values and times, which would not balance the memory
+        // argument if their accesses were counted.
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        let mut active_rows = Vec::new();
+        for i in 0..num_rows {
+            let is_active = rng.gen_bool(0.5);
+            writer.write(&active, &F::from_canonical_u8(is_active as u8), i);
+            writer.write(&value, &F::from_canonical_u32(rng.gen()), i);
+            writer.write(&ts, &F::from_canonical_u32(rng.gen()), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            if is_active {
+                active_rows.push(i);
+            }
+        }
+
+        for i in active_rows {
+            assert_eq!(writer.read(&loaded, i), writer.read(&value, i));
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_filtered_memory", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    /// Writes the accesses of `test_filtered_memory_accesses` with deterministic values, loading
+    /// a wrong value in the row `corrupt_row`, and validates them with fixed challenges.
+    fn validate_memory_accesses(
+        corrupt_row: Option<usize>,
+    ) -> Vec<ConstraintFailure<GoldilocksField>> {
+        type F = GoldilocksField;
+        type L = FilteredMemoryTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = StarkBuilder::<L>::new();
+        let clk = builder.clk();
+        let active = builder.alloc::<BitRegister>();
+        let value = builder.alloc::<ElementRegister>();
+        let ts = builder.alloc::<ElementRegister>();
+
+        let slice = builder.uninit_slice::<ElementRegister>();
+        let ptr = slice.get_at(clk);
+        let time = Time::from_element(ts);
+        builder.store_filtered(&ptr, value, &time, active);
+        let loaded = builder.load_filtered(&ptr, &time, active);
+
+        let num_rows = 1 << 8;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let challenges = (0..stark.air_data.num_challenges)
+            .map(|i| F::from_canonical_u64(0x1234_5678_9abc_def0 + i as u64))
+            .collect::<Vec<_>>();
+        let writer = TraceWriter::new(&stark.air_data, num_rows).with_fixed_challenges(&challenges);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            writer.write(&active, &F::from_canonical_usize(i % 3 % 2), i);
+            writer.write(&value, &F::from_canonical_usize(7 * i + 1), i);
+            writer.write(&ts, &F::from_canonical_usize(i / 2), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            if corrupt_row == Some(i) {
+                writer.write(&loaded, &F::from_canonical_usize(7 * i + 2), i);
+            }
+        }
+
+        writer.validate(stark.stark.air(), &stark.air_data)
+    }
+
+    #[test]
+    fn test_validate_memory_with_fixed_challenges() {
+        assert!(validate_memory_accesses(None).is_empty());
+
+        // The wrong value is only caught by the memory argument, as the load is not otherwise
+        // constrained to the stored value.
+        assert!(!validate_memory_accesses(Some(1)).is_empty());
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct CheckedMemoryTest;
+
+    impl AirParameters for CheckedMemoryTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 6;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_checked_memory_load() {
+        type F = GoldilocksField;
+        type L = CheckedMemoryTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = StarkBuilder::<L>::new();
+        let clk = builder.clk();
+        let active = builder.alloc::<BitRegister>();
+        let value = builder.alloc::<ElementRegister>();
+        let ts = builder.alloc::<ElementRegister>();
+
+        // Only the active rows write their slot, but every row loads it.
+        let slice = builder.uninit_slice::<ElementRegister>();
+        let ptr = slice.get_at(clk);
+        let time = Time::from_element(ts);
+        builder.store_filtered(&ptr, value, &time, active);
+        let (loaded, written) = builder.load_checked(&ptr, &time);
+
+        let num_rows = 1 << 8;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            let is_active = rng.gen_bool(0.5);
+            writer.write(&active, &F::from_canonical_u8(is_active as u8), i);
+            writer.write(&value, &F::from_canonical_u32(rng.gen()), i);
+            writer.write(&ts, &F::from_canonical_u32(rng.gen()), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            assert_eq!(writer.read(&written, i), writer.read(&active, i));
+            let expected = if is_active {
+                writer.read(&value, i)
+            } else {
+                F::ZERO
+            };
+            assert_eq!(writer.read(&loaded, i), expected);
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_checked_memory_load", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    /// Writes every slot and loads it with `load_checked`, claiming in the row `lying_row` that
+    /// the slot is not written, and validates the accesses with fixed challenges.
+    ///
+    /// The multiplicity of the writes is one, or a witness set to one in the honest rows and to
+    /// zero in the lying row if `witness_multiplicity` is set.
+    fn validate_checked_load(
+        witness_multiplicity: bool,
+        lying_row: Option<usize>,
+    ) -> Vec<ConstraintFailure<GoldilocksField>> {
+        type F = GoldilocksField;
+        type L = CheckedMemoryTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = StarkBuilder::<L>::new();
+        let clk = builder.clk();
+        let value = builder.alloc::<ElementRegister>();
+        let ts = builder.alloc::<ElementRegister>();
+        let multiplicity = builder.alloc::<ElementRegister>();
+
+        let slice = builder.uninit_slice::<ElementRegister>();
+        let ptr = slice.get_at(clk);
+        let time = Time::from_element(ts);
+        let store_multiplicity = witness_multiplicity.then_some(multiplicity);
+        builder.store(&ptr, value, &time, store_multiplicity, None, None);
+        let (loaded, written) = builder.load_checked(&ptr, &time);
+
+        let num_rows = 1 << 8;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let challenges = (0..stark.air_data.num_challenges)
+            .map(|i| F::from_canonical_u64(0x1234_5678_9abc_def0 + i as u64))
+            .collect::<Vec<_>>();
+        let writer = TraceWriter::new(&stark.air_data, num_rows).with_fixed_challenges(&challenges);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            writer.write(&value, &F::from_canonical_usize(7 * i + 1), i);
+            writer.write(&ts, &F::from_canonical_usize(i / 2), i);
+            writer.write(&multiplicity, &F::ONE, i);
+            writer.write_row_instructions(&stark.air_data, i);
+            if lying_row == Some(i) {
+                writer.write(&written, &F::ZERO, i);
+                writer.write(&loaded, &F::ZERO, i);
+                writer.write(&multiplicity, &F::ZERO, i);
+            }
+        }
+
+        writer.validate(stark.stark.air(), &stark.air_data)
+    }
+
+    #[test]
+    fn test_checked_load_of_written_slot_claimed_unwritten() {
+        assert!(validate_checked_load(false, None).is_empty());
+        assert!(validate_checked_load(true, None).is_empty());
+
+        // With a write of multiplicity one, the lying `written` bit leaves the write unread.
+        assert!(!validate_checked_load(false, Some(3)).is_empty());
+
+        // A witness multiplicity lets the prover drop the write with the read, so the lie is not
+        // caught, which is why `get_checked` needs the multiplicity to be fixed by the builder.
+        assert!(validate_checked_load(true, Some(3)).is_empty());
+    }
+
+    #[test]
+    fn test_constant_timestamps() {
+        type L = FilteredMemoryTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let x = builder.alloc::<ElementRegister>();
+        let ptr = builder.uninit::<ElementRegister>();
+        builder.set(&ptr, x, &Time::constant(3), None, None, None);
+        let y = builder.get(&ptr, &Time::constant(3), None, None);
+        builder.set(
+            &ptr,
+            y,
+            &Time::from_element(x).advance_by(8),
+            None,
+            None,
+            None,
+        );
+        builder.free(&ptr, y, &Time::constant(8).advance());
+
+        builder.reserve_timestamp(5);
+        assert_eq!(builder.constant_timestamps(), vec![3, 9]);
+        builder.check_timestamps();
+    }
+
+    #[test]
+    #[should_panic(expected = "reserved for dummy accesses")]
+    fn test_reserved_timestamp_collision() {
+        type L = FilteredMemoryTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let dummy_ts = 1 << 20;
+        builder.reserve_timestamp(dummy_ts);
+        let x = builder.alloc::<ElementRegister>();
+        let ptr = builder.uninit::<ElementRegister>();
+        builder.set(
+            &ptr,
+            x,
+            &Time::constant(dummy_ts as usize),
+            None,
+            None,
+            None,
+        );
+        builder.build();
+    }
+
+    #[test]
+    fn test_counted_load_of_selected_index() {
+        type L = FilteredMemoryTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let zero = builder.constant::<ElementRegister>(&GoldilocksField::ZERO);
+        let dummy_index = builder.constant::<ElementRegister>(&GoldilocksField::ONE);
+        let slice = builder.uninit_slice::<ElementRegister>();
+        let slot = builder.set_counted(&slice.get_at(dummy_index), zero, &Time::zero(), None, None);
+
+        // The index of the load is the index of the slot in the rows where `read_dummy` is set.
+        let read_dummy = builder.alloc::<BitRegister>();
+        let index = builder.alloc::<ElementRegister>();
+        let read_index = builder.select(&read_dummy, &dummy_index, &index);
+        builder.count_reads(
+            &slot,
+            &slice.get_at(read_index).raw,
+            ReadCount::new().rows(3, 1),
+        );
+        builder.count_reads(
+            &slot,
+            &slice.get_at(dummy_index).raw,
+            ReadCount::new().rows(2, 1),
+        );
+        assert_eq!(builder.counted_stores[slot.id()].reads.count(), 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "can't point to the slot")]
+    fn test_counted_load_of_other_index() {
+        type L = FilteredMemoryTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let zero = builder.constant::<ElementRegister>(&GoldilocksField::ZERO);
+        let dummy_index = builder.constant::<ElementRegister>(&GoldilocksField::ONE);
+        let slice = builder.uninit_slice::<ElementRegister>();
+        let slot = builder.set_counted(&slice.get_at(dummy_index), zero, &Time::zero(), None, None);
+
+        let index = builder.alloc::<ElementRegister>();
+        builder.count_reads(&slot, &slice.get_at(index).raw, ReadCount::new().rows(3, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "can't point to the slot")]
+    fn test_counted_load_of_other_slice() {
+        type L = FilteredMemoryTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let zero = builder.constant::<ElementRegister>(&GoldilocksField::ZERO);
+        let dummy_index = builder.constant::<ElementRegister>(&GoldilocksField::ONE);
+        let slice = builder.uninit_slice::<ElementRegister>();
+        let other_slice = builder.uninit_slice::<ElementRegister>();
+        let slot = builder.set_counted(&slice.get_at(dummy_index), zero, &Time::zero(), None, None);
+
+        let ptr = other_slice.get_at(dummy_index);
+        builder.count_reads(&slot, &ptr.raw, ReadCount::new().rows(3, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "not smaller than the order of the field")]
+    fn test_constant_timestamp_out_of_field() {
+        Time::<GoldilocksField>::constant(usize::MAX);
+    }
+}
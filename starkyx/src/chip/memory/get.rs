@@ -7,7 +7,9 @@ use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
 use crate::chip::memory::instruction::MemorySliceIndex;
+use crate::chip::register::bit::BitRegister;
 use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::prelude::*;
 
@@ -16,6 +18,8 @@ pub struct GetInstruction<F> {
     ptr: RawPointer,
     register: MemorySlice,
     memory_output: Option<MemoryOutput<F>>,
+    /// A bit set if the location is written, for a read that tolerates unwritten locations.
+    written: Option<BitRegister>,
 }
 
 impl<AP: AirParser> AirConstraint<AP> for GetInstruction<AP::Field> {
@@ -27,6 +31,20 @@ impl<F: Field> Instruction<F> for GetInstruction<F> {
     fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
         let mut memory = writer.memory_mut().unwrap();
         let key = self.ptr.read(writer, row_index);
+        if let Some(written) = self.written {
+            let is_written = memory
+                .get(&key)
+                .is_some_and(|entry| entry.multiplicity != F::ZERO);
+            writer.write(&written, &F::from_canonical_u8(is_written as u8), row_index);
+            if !is_written {
+                writer.write_slice(
+                    &self.register,
+                    &vec![F::ZERO; self.register.len()],
+                    row_index,
+                );
+                return;
+            }
+        }
         let (label, index, write_ts) = if self.memory_output.is_some() {
             let label = &self.memory_output.as_ref().unwrap().label;
             let index = match self.memory_output.as_ref().unwrap().index {
@@ -78,6 +96,17 @@ impl<F: Field> Instruction<F> for GetInstruction<F> {
 
     fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
         let key = self.ptr.read_from_air(writer);
+        if let Some(written) = self.written {
+            let is_written = writer
+                .memory()
+                .get(&key)
+                .is_some_and(|entry| entry.multiplicity != F::ZERO);
+            writer.write(&written, &F::from_canonical_u8(is_written as u8));
+            if !is_written {
+                writer.write_slice(&self.register, &vec![F::ZERO; self.register.len()]);
+                return;
+            }
+        }
         let row_index = writer.row_index();
         let (label, index, write_ts) = if self.memory_output.is_some() {
             let label = &self.memory_output.as_ref().unwrap().label;
@@ -143,6 +172,33 @@ impl<F: Field> GetInstruction<F> {
             ptr,
             register,
             memory_output,
+            written: None,
+        }
+    }
+
+    /// A read of the location `ptr` into `register` that writes whether the location is written
+    /// to `written`, and zero to `register` if it is not.
+    pub fn checked(ptr: RawPointer, register: MemorySlice, written: BitRegister) -> Self {
+        Self {
+            ptr,
+            register,
+            memory_output: None,
+            written: Some(written),
         }
     }
+
+    /// The label, index and timestamp recorded for the access, if any.
+    pub const fn memory_output(&self) -> Option<&MemoryOutput<F>> {
+        self.memory_output.as_ref()
+    }
+
+    /// The registers read by the instruction when writing the trace.
+    pub(crate) fn read_registers(&self) -> Vec<MemorySlice> {
+        let shift = self.ptr.element_shift().map(|e| *e.register());
+        let index = self
+            .memory_output
+            .as_ref()
+            .and_then(|output| output.index_register());
+        shift.into_iter().chain(index).collect()
+    }
 }
@@ -8,6 +8,8 @@ use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::field::Field;
 
@@ -33,6 +35,39 @@ pub enum MemorySliceIndex {
     IndexElement(ElementRegister),
 }
 
+impl<F: Field> MemoryInstruction<F> {
+    /// The registers read by the instruction when writing the trace.
+    ///
+    /// Memory instructions carry no constraints of their own, so these registers are constrained
+    /// only if they also appear in the compressed values of the memory argument.
+    pub(crate) fn read_registers(&self) -> Vec<MemorySlice> {
+        match self {
+            Self::Get(instr) => instr.read_registers(),
+            Self::Set(instr) => instr.read_registers(),
+            Self::Watch(instr) => instr.read_registers(),
+        }
+    }
+
+    /// The label, index and timestamp recorded for the memory access of the instruction, if any.
+    pub const fn memory_output(&self) -> Option<&MemoryOutput<F>> {
+        match self {
+            Self::Get(instr) => instr.memory_output(),
+            Self::Set(instr) => instr.memory_output(),
+            Self::Watch(_) => None,
+        }
+    }
+}
+
+impl<F> MemoryOutput<F> {
+    /// The register of the index of the memory output, if any.
+    pub(crate) fn index_register(&self) -> Option<MemorySlice> {
+        match self.index {
+            Some(MemorySliceIndex::IndexElement(index)) => Some(*index.register()),
+            _ => None,
+        }
+    }
+}
+
 impl<AP: AirParser> AirConstraint<AP> for MemoryInstruction<AP::Field> {
     fn eval(&self, parser: &mut AP) {
         match self {
@@ -4,6 +4,7 @@ use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
 use super::pointer::key::RawPointerKey;
+use crate::math::prelude::*;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MemEntry<T> {
@@ -36,6 +37,45 @@ impl<T: PartialEq + Eq + Hash> MemoryMap<T> {
     }
 }
 
+impl<F: Field> MemoryMap<F> {
+    /// Merges the memories written from copies of `initial`, taken in order.
+    ///
+    /// The multiplicity of an entry is changed by the sum of the changes made by every memory,
+    /// and its value is the one written by the last memory changing it. An entry removed by one
+    /// of the memories is removed.
+    pub(crate) fn merge(initial: &Self, memories: impl IntoIterator<Item = Self>) -> Self {
+        let mut merged = initial.clone();
+        let mut removed = Vec::new();
+        for memory in memories {
+            removed.extend(
+                initial
+                    .0
+                    .keys()
+                    .filter(|key| !memory.0.contains_key(key))
+                    .copied(),
+            );
+            for (key, entry) in memory.0 {
+                let initial_multiplicity = match initial.get(&key) {
+                    Some(initial_entry) if *initial_entry == entry => continue,
+                    Some(initial_entry) => initial_entry.multiplicity,
+                    None => F::ZERO,
+                };
+                match merged.get_mut(&key) {
+                    Some(merged_entry) => {
+                        merged_entry.multiplicity += entry.multiplicity - initial_multiplicity;
+                        merged_entry.value = entry.value;
+                    }
+                    None => merged.insert(key, entry),
+                }
+            }
+        }
+        for key in removed {
+            merged.remove(&key);
+        }
+        merged
+    }
+}
+
 impl<T: PartialEq + Eq + Hash> Default for MemoryMap<T> {
     fn default() -> Self {
         Self::new()
@@ -3,7 +3,10 @@ pub mod get;
 pub mod instruction;
 pub mod map;
 pub mod pointer;
+pub mod queue;
+pub mod reads;
 pub mod set;
+pub mod stack;
 pub mod time;
 pub mod value;
 pub mod watch;
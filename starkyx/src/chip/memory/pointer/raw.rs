@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use super::accumulate::{CompressedValue, PointerAccumulator};
 use super::key::RawPointerKey;
-use crate::air::extension::cubic::CubicParser;
+use crate::air::parser::AirParser;
 use crate::chip::arithmetic::expression::ArithmeticExpression;
 use crate::chip::builder::AirBuilder;
 use crate::chip::register::array::ArrayRegister;
@@ -13,19 +13,20 @@ use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::AirParameters;
 use crate::math::field::Field;
 use crate::math::prelude::cubic::element::CubicElement;
-use crate::math::prelude::CubicParameters;
 
+/// A pointer identified by the powers of a challenge in an extension, whose elements are held by
+/// registers of type `R`.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct RawPointer {
+pub struct RawPointer<R = CubicRegister> {
     /// The powers `1, gamma, gamma^2, ...` of the challenge identifying the unique pointer.
-    powers: ArrayRegister<CubicRegister>,
+    powers: ArrayRegister<R>,
     element_shift: Option<ElementRegister>,
     constant_shift: Option<i32>,
 }
 
-impl RawPointer {
+impl<R: Register> RawPointer<R> {
     pub(crate) fn new(
-        powers: ArrayRegister<CubicRegister>,
+        powers: ArrayRegister<R>,
         element_shift: Option<ElementRegister>,
         constant_shift: Option<i32>,
     ) -> Self {
@@ -36,7 +37,7 @@ impl RawPointer {
         }
     }
 
-    pub(crate) fn from_challenge(powers: ArrayRegister<CubicRegister>) -> Self {
+    pub(crate) fn from_challenge(powers: ArrayRegister<R>) -> Self {
         Self {
             powers,
             element_shift: None,
@@ -44,10 +45,58 @@ impl RawPointer {
         }
     }
 
+    /// The register shifting the pointer, if any.
+    pub(crate) fn element_shift(&self) -> Option<ElementRegister> {
+        self.element_shift
+    }
+
+    /// The constant shifting the pointer, if any.
+    pub(crate) fn constant_shift(&self) -> Option<i32> {
+        self.constant_shift
+    }
+
+    /// Whether the pointer is in the slice of `other`, identified by the same challenge.
+    pub(crate) fn is_in_slice_of(&self, other: &Self) -> bool {
+        self.powers.register() == other.powers.register()
+    }
+
     pub fn is_trace(&self) -> bool {
         self.element_shift.map(|e| e.is_trace()).unwrap_or(false)
     }
 
+    /// Evaluates the first three powers of the challenge and the shift of the pointer.
+    pub fn eval<AP: AirParser>(&self, parser: &mut AP) -> ([R::Value<AP::Var>; 3], AP::Var) {
+        let challenges = self.powers.eval_array::<_, 3>(parser);
+
+        let shift = match (self.element_shift, self.constant_shift) {
+            (Some(e), None) => Some(e.eval(parser)),
+            (None, Some(c)) => Some(parser.constant(i32_to_field(c))),
+            (Some(e), Some(c)) => {
+                let element = e.eval(parser);
+                let constant = i32_to_field(c);
+                Some(parser.add_const(element, constant))
+            }
+            (None, None) => None,
+        };
+
+        (challenges, shift.unwrap_or(parser.zero()))
+    }
+
+    pub fn shift_expr<F: Field>(&self) -> ArithmeticExpression<F> {
+        match (self.element_shift, self.constant_shift) {
+            (Some(e), None) => e.expr(),
+            (None, Some(c)) => ArithmeticExpression::from_constant(i32_to_field(c)),
+            (Some(e), Some(c)) => {
+                let element = e.expr::<F>();
+                let constant = i32_to_field::<F>(c);
+                element + constant
+            }
+            (None, None) => ArithmeticExpression::zero(),
+        }
+    }
+}
+
+impl RawPointer {
     pub fn accumulate<L: AirParameters>(
         &self,
         builder: &mut AirBuilder<L>,
@@ -83,39 +132,6 @@ impl RawPointer {
         digest
     }
 
-    pub fn eval<E: CubicParameters<AP::Field>, AP: CubicParser<E>>(
-        &self,
-        parser: &mut AP,
-    ) -> ([CubicElement<AP::Var>; 3], AP::Var) {
-        let challenges = self.powers.eval_array::<_, 3>(parser);
-
-        let shift = match (self.element_shift, self.constant_shift) {
-            (Some(e), None) => Some(e.eval(parser)),
-            (None, Some(c)) => Some(parser.constant(i32_to_field(c))),
-            (Some(e), Some(c)) => {
-                let element = e.eval(parser);
-                let constant = i32_to_field(c);
-                Some(parser.add_const(element, constant))
-            }
-            (None, None) => None,
-        };
-
-        (challenges, shift.unwrap_or(parser.zero()))
-    }
-
-    pub fn shift_expr<F: Field>(&self) -> ArithmeticExpression<F> {
-        match (self.element_shift, self.constant_shift) {
-            (Some(e), None) => e.expr(),
-            (None, Some(c)) => ArithmeticExpression::from_constant(i32_to_field(c)),
-            (Some(e), Some(c)) => {
-                let element = e.expr::<F>();
-                let constant = i32_to_field::<F>(c);
-                element + constant
-            }
-            (None, None) => ArithmeticExpression::zero(),
-        }
-    }
-
     pub fn read<F: Field>(&self, writer: &TraceWriter<F>, row_index: usize) -> RawPointerKey<F> {
         let element_shift = self
             .element_shift
@@ -0,0 +1,220 @@
+use super::pointer::slice::Slice;
+use super::time::Time;
+use super::value::MemoryValue;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::machine::builder::Builder;
+
+/// A first-in first-out queue of values along the rows of the trace.
+///
+/// Values are enqueued in the rows where `enqueue` is set and dequeued in the rows where
+/// `dequeue` is set, with at most one of the two operations in every row. The `i`-th enqueued
+/// value is kept in the slot `i` of a memory slice, read back when the head of the queue reaches
+/// it, so that the consistency of the queue is enforced by the memory argument.
+///
+/// Every enqueued value must be dequeued within the trace for the memory argument to balance.
+/// Dequeuing from an empty queue violates a constraint, since the memory argument alone would
+/// let the dequeue read a value enqueued in a later row.
+#[derive(Debug, Clone)]
+pub struct Queue<V> {
+    values: Slice<V>,
+    enqueue: BitRegister,
+    read: BitRegister,
+    head: ElementRegister,
+    tail: ElementRegister,
+}
+
+impl<V: MemoryValue> Queue<V> {
+    pub(crate) fn new<B: Builder>(
+        builder: &mut B,
+        enqueue: BitRegister,
+        dequeue: BitRegister,
+    ) -> Self {
+        let values = builder.uninit_slice();
+
+        builder.assert_expression_zero(enqueue.expr() * dequeue.expr());
+
+        // The slot of the next value to dequeue.
+        let head = builder.running_sum(dequeue.expr());
+
+        // The slot of the next value to enqueue.
+        let tail = builder.running_sum(enqueue.expr());
+
+        // The queue is empty when the head reaches the tail.
+        let is_empty = builder.is_zero(tail.expr() - head.expr());
+        builder.tagged("queue_empty_dequeue", |builder| {
+            builder.assert_expression_zero(dequeue.expr() * is_empty.expr())
+        });
+        // The rows reading a value, which are the dequeue rows once an empty dequeue is ruled
+        // out, so that no value is read in the trace of an empty dequeue.
+        let read = builder.expression(dequeue.expr() * is_empty.not_expr());
+
+        Self {
+            values,
+            enqueue,
+            read,
+            head,
+            tail,
+        }
+    }
+
+    /// Enqueues `value` in the rows where the `enqueue` flag is set.
+    ///
+    /// This should be called once per queue.
+    pub fn enqueue<B: Builder>(&self, builder: &mut B, value: V) {
+        builder.store(
+            &self.values.get_at(self.tail),
+            value,
+            &Time::zero(),
+            Some(self.enqueue.as_element()),
+            None,
+            None,
+        );
+    }
+
+    /// Dequeues the value at the head of the queue in the rows where the `dequeue` flag is set.
+    ///
+    /// In the other rows, the returned register is unconstrained. This should be called once per
+    /// queue.
+    pub fn dequeue<B: Builder>(&self, builder: &mut B) -> V {
+        builder
+            .api()
+            .get_filtered(&self.values.get_at(self.head), &Time::zero(), self.read)
+    }
+
+    /// The slot of the next value to dequeue.
+    pub fn head(&self) -> ElementRegister {
+        self.head
+    }
+
+    /// The slot of the next value to enqueue.
+    pub fn tail(&self) -> ElementRegister {
+        self.tail
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use rand::Rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::trace::generator::ArithmeticGenerator;
+    use crate::chip::trace::writer::{InnerWriterData, TraceWriter};
+    use crate::chip::AirParameters;
+    use crate::machine::stark::builder::StarkBuilder;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct QueueTest;
+
+    impl AirParameters for QueueTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 10;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_queue_fifo() {
+        type F = GoldilocksField;
+        type L = QueueTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = StarkBuilder::<L>::new();
+        let enqueue = builder.alloc::<BitRegister>();
+        let dequeue = builder.alloc::<BitRegister>();
+        let value = builder.alloc::<ElementRegister>();
+
+        let queue = builder.queue::<ElementRegister>(enqueue, dequeue);
+        queue.enqueue(&mut builder, value);
+        let dequeued = queue.dequeue(&mut builder);
+
+        let num_rows = 1 << 8;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        // Enqueue a random value or dequeue at random, emptying the queue at the end.
+        let mut rng = rand::thread_rng();
+        let mut queue_values = std::collections::VecDeque::new();
+        let mut expected = Vec::new();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            let remaining = num_rows - i;
+            let (enqueue_value, dequeue_value, value_value) =
+                if queue_values.len() < remaining - 1 && rng.gen_bool(0.5) {
+                    let v = rng.gen::<u32>() as u64;
+                    queue_values.push_back(v);
+                    (F::ONE, F::ZERO, F::from_canonical_u64(v))
+                } else if let Some(v) = queue_values.pop_front() {
+                    expected.push((i, v));
+                    (F::ZERO, F::ONE, F::ZERO)
+                } else {
+                    (F::ZERO, F::ZERO, F::ZERO)
+                };
+            writer.write(&enqueue, &enqueue_value, i);
+            writer.write(&dequeue, &dequeue_value, i);
+            writer.write(&value, &value_value, i);
+            writer.write_row_instructions(&stark.air_data, i);
+        }
+        assert!(queue_values.is_empty());
+
+        for (i, v) in expected {
+            assert_eq!(writer.read(&dequeued, i), F::from_canonical_u64(v));
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_queue", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_queue_empty_dequeue() {
+        type F = GoldilocksField;
+        type L = QueueTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let enqueue = builder.alloc::<BitRegister>();
+        let dequeue = builder.alloc::<BitRegister>();
+        let value = builder.alloc::<ElementRegister>();
+
+        let queue = builder.queue::<ElementRegister>(enqueue, dequeue);
+        queue.enqueue(&mut builder, value);
+        queue.dequeue(&mut builder);
+
+        let (air, trace_data) = builder.build();
+
+        // The dequeue of the second row reads from an empty queue, and the value enqueued in the
+        // next row is never dequeued.
+        let num_rows = 1 << 4;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        for i in 0..num_rows {
+            writer.write(&enqueue, &F::from_canonical_u8((i == 2) as u8), i);
+            writer.write(&dequeue, &F::from_canonical_u8((i == 1) as u8), i);
+            writer.write(&value, &F::from_canonical_u8(5), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let trace = generator.trace_clone();
+        let challenges = vec![F::ZERO; air.num_challenges];
+        let global_values = vec![F::ZERO; air.num_global_values];
+        let public_values = vec![F::ZERO; air.num_public_values];
+        let failures = air.debug_constraints(&trace, &challenges, &global_values, &public_values);
+        assert!(failures
+            .iter()
+            .any(|failure| failure.label == "queue_empty_dequeue" && failure.row == Some(1)));
+    }
+}
@@ -0,0 +1,179 @@
+use core::ops::Add;
+
+use super::pointer::raw::RawPointer;
+use super::set::SetInstruction;
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::machine::builder::Builder;
+use crate::math::prelude::*;
+
+/// The number of loads of a memory slot whose value is constant over the trace.
+///
+/// Such a slot is stored once, with a multiplicity equal to the number of times it is loaded, and
+/// a wrong multiplicity makes the memory argument unsatisfiable without any other error. Counting
+/// the loads row by row, with the same conditions that select them in the AIR, derives the
+/// multiplicity from the loads instead of computing it by hand. See `CountedSlot`.
+///
+/// The number of rows of some loads is only known from the public inputs, like the number of
+/// messages of a hash, and is given by a public register.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadCount {
+    num_reads: usize,
+    register_reads: Vec<(ElementRegister, usize)>,
+}
+
+impl ReadCount {
+    pub const fn new() -> Self {
+        Self {
+            num_reads: 0,
+            register_reads: Vec::new(),
+        }
+    }
+
+    /// Adds `reads_per_row` loads in each of `num_rows` rows.
+    pub fn rows(mut self, num_rows: usize, reads_per_row: usize) -> Self {
+        self.num_reads += num_rows * reads_per_row;
+        self
+    }
+
+    /// Adds the loads of the rows `0..num_rows`, with `reads(row)` loads in the row `row`.
+    pub fn over_rows(self, num_rows: usize, reads: impl Fn(usize) -> usize) -> Self {
+        let num_reads = (0..num_rows).map(reads).sum::<usize>();
+        self.rows(num_reads, 1)
+    }
+
+    /// Adds `reads_per_row` loads in each of the rows counted by the public register `num_rows`.
+    pub fn register_rows(mut self, num_rows: ElementRegister, reads_per_row: usize) -> Self {
+        assert!(
+            !num_rows.is_trace(),
+            "The number of rows of the loads must be a public register"
+        );
+        if reads_per_row > 0 {
+            self.register_reads.push((num_rows, reads_per_row));
+        }
+        self
+    }
+
+    /// The number of loads counted in the rows known when building the AIR, which is all of the
+    /// loads if none is counted by a register.
+    pub const fn count(&self) -> usize {
+        self.num_reads
+    }
+
+    /// The number of loads, as an expression of the registers counting their rows.
+    pub fn expression<F: Field>(&self) -> ArithmeticExpression<F> {
+        self.register_reads.iter().fold(
+            ArithmeticExpression::from_constant(F::from_canonical_usize(self.num_reads)),
+            |count, (num_rows, reads_per_row)| {
+                count + num_rows.expr() * F::from_canonical_usize(*reads_per_row)
+            },
+        )
+    }
+
+    /// Allocates the number of loads as a constant, or as a public register if it depends on the
+    /// registers counting the rows, to be used as the multiplicity of the store.
+    pub fn multiplicity<B: Builder>(&self, builder: &mut B) -> ElementRegister {
+        if self.register_reads.is_empty() {
+            builder.constant(&B::Field::from_canonical_usize(self.num_reads))
+        } else {
+            builder.public_expression(self.expression())
+        }
+    }
+}
+
+impl Add for ReadCount {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self.num_reads += other.num_reads;
+        self.register_reads.extend(other.register_reads);
+        self
+    }
+}
+
+/// A memory slot whose value is constant over the trace, stored with a multiplicity equal to the
+/// number of loads registered against it.
+///
+/// The slot is stored by `Builder::store_counted`, and every load that reads it in some rows is
+/// registered with `Builder::load_counted`, with the `ReadCount` of these rows. The store is
+/// written when the AIR is built, with its multiplicity set to the loads counted.
+#[derive(Debug, Clone, Copy)]
+pub struct CountedSlot {
+    id: usize,
+    ptr: RawPointer,
+    multiplicity: ElementRegister,
+}
+
+/// The store of a `CountedSlot`, kept by the builder until the loads of the slot are counted.
+#[derive(Debug, Clone)]
+pub(crate) struct CountedStore<F> {
+    pub(crate) instruction: SetInstruction<F>,
+    pub(crate) multiplicity: ElementRegister,
+    pub(crate) reads: ReadCount,
+}
+
+impl CountedSlot {
+    pub(crate) const fn new(id: usize, ptr: RawPointer, multiplicity: ElementRegister) -> Self {
+        Self {
+            id,
+            ptr,
+            multiplicity,
+        }
+    }
+
+    pub(crate) const fn id(&self) -> usize {
+        self.id
+    }
+
+    /// The pointer of the slot.
+    pub(crate) const fn ptr(&self) -> &RawPointer {
+        &self.ptr
+    }
+
+    /// The public register of the multiplicity of the store, set to the loads counted when the
+    /// AIR is built.
+    pub const fn multiplicity(&self) -> ElementRegister {
+        self.multiplicity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::chip::register::memory::MemorySlice;
+
+    #[test]
+    fn test_read_count() {
+        let count = ReadCount::new()
+            .rows(10, 3)
+            .over_rows(8, |row| row % 2)
+            .rows(0, 100);
+        assert_eq!(count.count(), 34);
+        assert_eq!((count + ReadCount::new().rows(2, 3)).count(), 40);
+        assert_eq!(ReadCount::default(), ReadCount::new());
+    }
+
+    #[test]
+    fn test_read_count_register_rows() {
+        type F = GoldilocksField;
+
+        let num_messages = ElementRegister::from_register_unsafe(MemorySlice::Public(0, 1));
+        let num_compresses = ElementRegister::from_register_unsafe(MemorySlice::Public(1, 1));
+        let count = ReadCount::new()
+            .rows(3, 2)
+            .register_rows(num_messages, 10)
+            .register_rows(num_compresses, 0)
+            + ReadCount::new().register_rows(num_compresses, 4);
+        assert_eq!(count.count(), 6);
+
+        // With 2 messages and 5 compresses, 6 + 2 * 10 + 5 * 4 loads.
+        let public = [F::from_canonical_u8(2), F::from_canonical_u8(5)];
+        assert_eq!(
+            count.expression::<F>().read_from_slice(&public),
+            vec![F::from_canonical_u8(46)]
+        );
+    }
+}
@@ -9,6 +9,7 @@ use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
 use crate::chip::register::element::ElementRegister;
 use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::prelude::*;
 
@@ -178,4 +179,30 @@ impl<F: Field> SetInstruction<F> {
             memory_output,
         }
     }
+
+    /// The label, index and timestamp recorded for the access, if any.
+    pub const fn memory_output(&self) -> Option<&MemoryOutput<F>> {
+        self.memory_output.as_ref()
+    }
+
+    /// The register of the multiplicity of the write, if it is not written once.
+    pub const fn multiplicity(&self) -> Option<ElementRegister> {
+        self.multiplicity
+    }
+
+    /// The registers read by the instruction when writing the trace.
+    pub(crate) fn read_registers(&self) -> Vec<MemorySlice> {
+        let shift = self.ptr.element_shift().map(|e| *e.register());
+        let multiplicity = self.multiplicity.map(|m| *m.register());
+        let index = self
+            .memory_output
+            .as_ref()
+            .and_then(|output| output.index_register());
+        [self.register]
+            .into_iter()
+            .chain(shift)
+            .chain(multiplicity)
+            .chain(index)
+            .collect()
+    }
 }
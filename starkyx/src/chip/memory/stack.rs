@@ -0,0 +1,243 @@
+use super::pointer::slice::Slice;
+use super::time::Time;
+use super::value::MemoryValue;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::machine::builder::Builder;
+use crate::math::prelude::*;
+
+/// A stack of values along the rows of the trace.
+///
+/// Values are pushed in the rows where `push` is set and popped in the rows where `pop` is set,
+/// with at most one of the two operations in every row. The `i`-th pushed value is kept in the
+/// slot `i` of a memory slice, and every slot links to the slot of the value below it, so that
+/// the consistency of the stack is enforced by the memory argument. The slot `0` is never
+/// written, which makes popping from an empty stack unsatisfiable.
+///
+/// Every pushed value must be popped within the trace for the memory argument to balance.
+#[derive(Debug, Clone)]
+pub struct Stack<V> {
+    values: Slice<V>,
+    push: BitRegister,
+    pop: BitRegister,
+    num_pushes: ElementRegister,
+    top: ElementRegister,
+}
+
+impl<V: MemoryValue> Stack<V> {
+    pub(crate) fn new<B: Builder>(builder: &mut B, push: BitRegister, pop: BitRegister) -> Self {
+        let values = builder.uninit_slice();
+        let links = builder.uninit_slice::<ElementRegister>();
+
+        builder.assert_expression_zero(push.expr() * pop.expr());
+
+        // The number of values pushed before the current row.
+        let num_pushes = builder.running_sum(push.expr());
+
+        // The slot of the value at the top of the stack, zero if the stack is empty.
+        let top = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&top, B::Field::ZERO.into());
+
+        // A pushed value links to the top of the stack it is pushed onto.
+        let new_slot = links.get_at_shifted(num_pushes, 1);
+        builder.store(
+            &new_slot,
+            top,
+            &Time::zero(),
+            Some(push.as_element()),
+            None,
+            None,
+        );
+        let below = builder
+            .api()
+            .get_filtered(&links.get_at(top), &Time::zero(), pop);
+
+        builder.set_to_expression_transition(
+            &top.next(),
+            top.expr()
+                + push.expr() * (num_pushes.expr() + B::Field::ONE - top.expr())
+                + pop.expr() * (below.expr() - top.expr()),
+        );
+
+        Self {
+            values,
+            push,
+            pop,
+            num_pushes,
+            top,
+        }
+    }
+
+    /// Pushes `value` onto the stack in the rows where the `push` flag is set.
+    ///
+    /// This should be called once per stack.
+    pub fn push<B: Builder>(&self, builder: &mut B, value: V) {
+        let slot = self.values.get_at_shifted(self.num_pushes, 1);
+        builder.store(
+            &slot,
+            value,
+            &Time::zero(),
+            Some(self.push.as_element()),
+            None,
+            None,
+        );
+    }
+
+    /// Pops the value at the top of the stack in the rows where the `pop` flag is set.
+    ///
+    /// In the other rows, the returned register is unconstrained. This should be called once per
+    /// stack.
+    pub fn pop<B: Builder>(&self, builder: &mut B) -> V {
+        builder
+            .api()
+            .get_filtered(&self.values.get_at(self.top), &Time::zero(), self.pop)
+    }
+
+    /// The slot of the value at the top of the stack, zero if the stack is empty.
+    pub fn top(&self) -> ElementRegister {
+        self.top
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use rand::Rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::trace::writer::{InnerWriterData, TraceWriter};
+    use crate::chip::AirParameters;
+    use crate::machine::stark::builder::StarkBuilder;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct StackTest;
+
+    impl AirParameters for StackTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 8;
+        const EXTENDED_COLUMNS: usize = 21;
+    }
+
+    type F = GoldilocksField;
+    type L = StackTest;
+    type C = CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Op {
+        Push(u64),
+        Pop,
+        Nop,
+    }
+
+    /// Writes and proves the trace of `ops`, padded with no-ops, and returns the popped values.
+    fn run_stack(ops: &[Op], num_rows: usize) -> Vec<u64> {
+        let mut builder = StarkBuilder::<L>::new();
+        let push = builder.alloc::<BitRegister>();
+        let pop = builder.alloc::<BitRegister>();
+        let value = builder.alloc::<ElementRegister>();
+
+        let stack = builder.stack::<ElementRegister>(push, pop);
+        stack.push(&mut builder, value);
+        let popped = stack.pop(&mut builder);
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        let mut pop_rows = Vec::new();
+        for i in 0..num_rows {
+            let op = ops.get(i).copied().unwrap_or(Op::Nop);
+            let (push_value, pop_value, value_value) = match op {
+                Op::Push(v) => (F::ONE, F::ZERO, F::from_canonical_u64(v)),
+                Op::Pop => {
+                    pop_rows.push(i);
+                    (F::ZERO, F::ONE, F::ZERO)
+                }
+                Op::Nop => (F::ZERO, F::ZERO, F::ZERO),
+            };
+            writer.write(&push, &push_value, i);
+            writer.write(&pop, &pop_value, i);
+            writer.write(&value, &value_value, i);
+            writer.write_row_instructions(&stark.air_data, i);
+        }
+
+        let popped_values = pop_rows
+            .iter()
+            .map(|i| writer.read(&popped, *i).as_canonical_u64())
+            .collect();
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_stack", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+
+        popped_values
+    }
+
+    #[test]
+    fn test_stack_lifo() {
+        let mut rng = rand::thread_rng();
+        let num_rows = 1 << 8;
+
+        // A random sequence of operations that leaves the stack empty.
+        let mut ops = Vec::new();
+        let mut depth = 0;
+        while ops.len() + depth < num_rows - 8 {
+            match rng.gen_range(0..3) {
+                0 => {
+                    ops.push(Op::Push(rng.gen::<u32>() as u64));
+                    depth += 1;
+                }
+                1 if depth > 0 => {
+                    ops.push(Op::Pop);
+                    depth -= 1;
+                }
+                _ => ops.push(Op::Nop),
+            }
+        }
+        ops.extend((0..depth).map(|_| Op::Pop));
+
+        let mut expected = Vec::new();
+        let mut stack = Vec::new();
+        for op in ops.iter() {
+            match op {
+                Op::Push(v) => stack.push(*v),
+                Op::Pop => expected.push(stack.pop().unwrap()),
+                Op::Nop => {}
+            }
+        }
+
+        assert_eq!(run_stack(&ops, num_rows), expected);
+    }
+
+    #[test]
+    fn test_stack_push_then_pop_all() {
+        // Pushing `N` values and then popping them all returns them in reverse order.
+        let n = 100;
+        let values = (1..=n).map(|v| v * 7 + 3).collect::<Vec<u64>>();
+        let ops = values
+            .iter()
+            .map(|v| Op::Push(*v))
+            .chain((0..n).map(|_| Op::Pop))
+            .collect::<Vec<_>>();
+
+        let expected = values.iter().rev().copied().collect::<Vec<_>>();
+        assert_eq!(run_stack(&ops, 1 << 8), expected);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_stack_pop_empty() {
+        let ops = [Op::Push(1), Op::Pop, Op::Pop];
+        run_stack(&ops, 1 << 5);
+    }
+}
@@ -22,10 +22,6 @@ impl<F: Field> Time<F> {
         Self::new(ArithmeticExpression::zero())
     }
 
-    pub fn constant(value: usize) -> Self {
-        Self::new(ArithmeticExpression::from(F::from_canonical_usize(value)))
-    }
-
     pub fn from_element(element: ElementRegister) -> Self {
         Self::new(element.expr())
     }
@@ -50,3 +46,23 @@ impl<F: Field> Time<F> {
         self.decrement_by(1)
     }
 }
+
+impl<F: PrimeField64> Time<F> {
+    /// A timestamp of constant `value`, which must be smaller than the order of the field.
+    pub fn constant(value: usize) -> Self {
+        assert!(
+            (value as u64) < F::order(),
+            "Timestamp {} is not smaller than the order of the field",
+            value
+        );
+        Self::new(ArithmeticExpression::from(F::from_canonical_usize(value)))
+    }
+
+    /// The value of the timestamp if it does not depend on any register.
+    pub fn as_constant(&self) -> Option<u64> {
+        if !self.0.registers().is_empty() {
+            return None;
+        }
+        Some(self.0.read_from_slice(&[])[0].as_canonical_u64())
+    }
+}
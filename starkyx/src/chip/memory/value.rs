@@ -5,10 +5,17 @@ use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::Register;
 use crate::chip::AirParameters;
+use crate::math::prelude::*;
 
 pub trait MemoryValue: Register {
-    fn num_challenges() -> usize;
+    /// The number of challenges used by `compress` for values in the field `F`.
+    fn num_challenges<F: PrimeField64>() -> usize;
 
+    /// Compresses the value and the time of an access into a single element.
+    ///
+    /// Distinct pairs of value and time must compress to distinct elements, so the time should
+    /// not be packed together with the value in the same field element, where a large time could
+    /// alias a different value.
     fn compress<L: AirParameters>(
         &self,
         builder: &mut AirBuilder<L>,
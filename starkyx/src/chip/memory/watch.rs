@@ -5,6 +5,8 @@ use super::pointer::raw::RawPointer;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::RegisterSerializable;
 use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::math::prelude::*;
 
@@ -65,4 +67,13 @@ impl WatchInstruction {
     pub fn new(ptr: RawPointer, name: String) -> Self {
         Self { ptr, name }
     }
+
+    /// The registers read by the instruction when writing the trace.
+    pub(crate) fn read_registers(&self) -> Vec<MemorySlice> {
+        self.ptr
+            .element_shift()
+            .map(|e| *e.register())
+            .into_iter()
+            .collect()
+    }
 }
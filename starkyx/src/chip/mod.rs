@@ -1,20 +1,27 @@
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-use self::constraint::Constraint;
+use self::constraint::{Constraint, ConstraintTag};
 use self::instruction::Instruction;
+use self::register::extension::ChallengeExtension;
+use crate::air::RAirData;
+use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
 use crate::plonky2::stark::Starky;
+use crate::utils::blake2b;
 
 pub mod air;
 pub mod arithmetic;
 pub mod bool;
 pub mod builder;
+pub mod const_div;
 pub mod constraint;
 pub mod ec;
 pub mod field;
 pub mod instruction;
 pub mod memory;
+pub mod plonk;
+pub mod radix;
 pub mod register;
 pub mod table;
 pub mod trace;
@@ -22,6 +29,22 @@ pub mod uint;
 pub mod utils;
 
 use core::fmt::Debug;
+
+/// The field, the extensions, the column budget and the instructions of an AIR.
+///
+/// # Range checks
+///
+/// The range checks of `AirBuilder::range_check` limit their bit width `n` as follows:
+///  - The field: `n` is at most `MAX_RANGE_CHECK_BITS`, which keeps `2^n` far below the order of
+///    `Field`, so that a value and its table entry are equal as integers exactly when they are
+///    equal as field elements. The field must have more than 32 bits.
+///  - The trace length: the table of a width lists `0..2^n` in one column, so the trace must have
+///    at least `2^n` rows, which bounds `n` by the log of the trace length in practice. With a
+///    base width set by `AirBuilder::set_range_check_base`, only `2^base_bits` rows are needed.
+///  - The column budget: every width takes 2 of the `NUM_FREE_COLUMNS` for its table and its
+///    multiplicities, and 9 of the `EXTENDED_COLUMNS`, plus 3 for every two trace values checked
+///    against it. With a base width, a value takes a free column for every limb and one for its
+///    shifted top limb.
 pub trait AirParameters:
     'static + Clone + Send + Sync + Sized + Debug + Serialize + DeserializeOwned
 {
@@ -29,6 +52,15 @@ pub trait AirParameters:
 
     type CubicParams: CubicParameters<Self::Field>;
 
+    /// The extension from which the challenges of the lookups of element registers, such as the
+    /// range checks, are drawn.
+    ///
+    /// This is the cubic extension of `CubicParams` unless chosen otherwise, and can be set to a
+    /// quintic extension for a larger soundness margin. The lookups of cubic registers are always
+    /// made in the cubic extension.
+    type LookupExtension: ChallengeExtension<Self::Field> =
+        CubicExtension<Self::Field, Self::CubicParams>;
+
     /// The number of columns that need to be ranged-checked to range 0..num_rows
     ///
     /// If NUM_ARITHMETIC_COLUMNS > 0 is used for field operations with 2^16 bit limbs
@@ -56,17 +88,131 @@ pub trait AirParameters:
     }
 }
 
+/// The number of columns of each kind an AIR allocates, matching the column constants of
+/// `AirParameters`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ColumnBudget {
+    pub num_arithmetic_columns: usize,
+    pub num_free_columns: usize,
+    pub extended_columns: usize,
+}
+
+impl ColumnBudget {
+    /// The column budget declared by the parameters `L`.
+    pub const fn of<L: AirParameters>() -> Self {
+        Self {
+            num_arithmetic_columns: L::NUM_ARITHMETIC_COLUMNS,
+            num_free_columns: L::NUM_FREE_COLUMNS,
+            extended_columns: L::EXTENDED_COLUMNS,
+        }
+    }
+
+    /// Whether the column constants of `L` are enough to hold `self`.
+    pub const fn fits<L: AirParameters>(&self) -> bool {
+        self.num_arithmetic_columns <= L::NUM_ARITHMETIC_COLUMNS
+            && self.num_free_columns <= L::NUM_FREE_COLUMNS
+            && self.extended_columns <= L::EXTENDED_COLUMNS
+    }
+}
+
+impl core::ops::Sub for ColumnBudget {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            num_arithmetic_columns: self.num_arithmetic_columns - rhs.num_arithmetic_columns,
+            num_free_columns: self.num_free_columns - rhs.num_free_columns,
+            extended_columns: self.extended_columns - rhs.extended_columns,
+        }
+    }
+}
+
+/// The resources used by a part of an AIR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ResourceReport {
+    /// The columns allocated, including the columns used to look up byte operations.
+    pub columns: ColumnBudget,
+    /// The number of constraints registered.
+    pub num_constraints: usize,
+    /// The number of byte operations looked up in every row.
+    pub num_byte_operations: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct Chip<L: AirParameters> {
     constraints: Vec<Constraint<L>>,
     global_constraints: Vec<Constraint<L>>,
+    tags: Vec<ConstraintTag>,
     pub execution_trace_length: usize,
+    pub num_extended_columns: usize,
     pub num_challenges: usize,
     pub num_public_values: usize,
     pub num_global_values: usize,
 }
 
+impl<L: AirParameters> Chip<L> {
+    /// The constraints evaluated on every row of the trace.
+    pub fn constraints(&self) -> &[Constraint<L>] {
+        &self.constraints
+    }
+
+    /// The constraints evaluated once over the public and global values.
+    pub fn global_constraints(&self) -> &[Constraint<L>] {
+        &self.global_constraints
+    }
+
+    /// The tags recorded while building the chip, in the order they were pushed.
+    pub fn tags(&self) -> &[ConstraintTag] {
+        &self.tags
+    }
+
+    /// The labels of all tags containing the constraint at `index`, from outermost to innermost.
+    pub fn tag_labels(&self, index: usize) -> Vec<&str> {
+        self.tags
+            .iter()
+            .filter(|tag| tag.constraints.contains(&index))
+            .map(|tag| tag.label.as_str())
+            .collect()
+    }
+
+    /// The labels of all tags containing the global constraint at `index`, from outermost to
+    /// innermost.
+    pub fn global_tag_labels(&self, index: usize) -> Vec<&str> {
+        self.tags
+            .iter()
+            .filter(|tag| tag.global_constraints.contains(&index))
+            .map(|tag| tag.label.as_str())
+            .collect()
+    }
+
+    /// A 32-byte hash of the structure of the AIR.
+    ///
+    /// The hash is the BLAKE2b digest of the canonical encoding of the constraints, the round
+    /// data and the layout of the public inputs. It does not depend on the tags. A prover and a
+    /// verifier computing different hashes run different circuits, and should not interoperate.
+    pub fn air_version_hash(&self) -> [u8; 32] {
+        self.air_version_hash_in_domain(&[])
+    }
+
+    /// The hash of the structure of the AIR like `air_version_hash`, bound to `domain`.
+    ///
+    /// The domain can be used to tell apart deployments of the same circuit, e.g. by a protocol
+    /// version label.
+    pub fn air_version_hash_in_domain(&self, domain: &[u8]) -> [u8; 32] {
+        let structure = (
+            domain,
+            &self.constraints,
+            &self.global_constraints,
+            self.round_data(),
+            self.constraint_degree(),
+            self.num_public_values,
+        );
+        let bytes = bincode::serialize(&structure).expect("Failed to serialize the AIR structure");
+        blake2b::hash(&bytes)
+    }
+}
+
 impl<L: AirParameters> Starky<Chip<L>> {
     pub fn from_chip(chip: Chip<L>) -> Self {
         Self::new(chip)
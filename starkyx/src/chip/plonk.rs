@@ -0,0 +1,306 @@
+use super::constraint::Constraint;
+use super::register::cubic::EvalCubic;
+use super::register::extension::ChallengeExtension;
+use super::register::memory::MemorySlice;
+use super::register::{Register, RegisterSerializable};
+use super::table::log_derivative::entry::LogEntry;
+use super::table::lookup::constraint::{LookupChipConstraint, LookupConstraint};
+use super::{AirParameters, Chip};
+use crate::air::plonk::{
+    PlonkCircuit, PlonkEntry, PlonkLookup, PlonkMultiplicity, PlonkMultiset, PlonkParser, PlonkWire,
+};
+use crate::air::{AirConstraint, RAirData};
+use crate::math::prelude::*;
+
+impl<L: AirParameters> Chip<L> {
+    /// Lowers the chip to a PLONKish arithmetization.
+    ///
+    /// The arithmetic constraints, including the compression of the memory and lookup entries by
+    /// the challenges, are lowered to gates. The memory buses and the lookup tables are not: their
+    /// log-derivative accumulators are dropped, and every bus becomes a multiset argument and
+    /// every table a lookup argument over the compressed entries. The extended columns holding the
+    /// accumulators are left unconstrained in the circuit.
+    pub fn to_plonk(&self) -> PlonkCircuit<L::Field>
+    where
+        Constraint<L>: AirConstraint<PlonkParser<L::Field>>,
+    {
+        let mut parser = PlonkParser::new(
+            self.width(),
+            self.num_challenges,
+            self.num_global_values,
+            self.num_public_values,
+        );
+        let mut arguments = Arguments::default();
+        for constraint in self.constraints.iter() {
+            if !arguments.insert(constraint) {
+                constraint.eval(&mut parser);
+            }
+        }
+        parser.set_global(true);
+        for constraint in self.global_constraints.iter() {
+            if !arguments.insert(constraint) {
+                constraint.eval(&mut parser);
+            }
+        }
+        parser.set_global(false);
+
+        let mut circuit = parser.into_circuit();
+        (circuit.multisets, circuit.lookups) = arguments.into_arguments();
+        circuit
+    }
+}
+
+/// The bus and lookup arguments of a chip, collected from its constraints.
+#[derive(Default)]
+struct Arguments {
+    /// The output channel and the entries of every bus channel.
+    channels: Vec<(MemorySlice, Vec<PlonkEntry>)>,
+    /// The channels and the global entries of every bus.
+    buses: Vec<(Vec<MemorySlice>, Vec<PlonkEntry>)>,
+    /// The digests of the values looked up in every table, and the table.
+    tables: Vec<(Vec<MemorySlice>, PlonkLookup)>,
+    /// The digest and the entries of every set of looked up values.
+    values: Vec<(MemorySlice, Vec<PlonkEntry>)>,
+}
+
+impl Arguments {
+    /// Collects the argument of `constraint`, returning `false` if it is not part of a bus or
+    /// lookup argument.
+    fn insert<L: AirParameters>(&mut self, constraint: &Constraint<L>) -> bool {
+        match constraint {
+            Constraint::BusChannel(channel) => self.channels.push((
+                *channel.out_channel.register(),
+                channel.entries().iter().map(plonk_entry).collect(),
+            )),
+            Constraint::Bus(bus) => self.buses.push((
+                bus.channels().iter().map(|c| *c.register()).collect(),
+                bus.global_entries().iter().map(plonk_entry).collect(),
+            )),
+            Constraint::Lookup(LookupChipConstraint::Element(lookup)) => self.insert_lookup(lookup),
+            Constraint::Lookup(LookupChipConstraint::CubicElement(lookup)) => {
+                self.insert_lookup(lookup)
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn insert_lookup<T: EvalCubic, F: Field, E: CubicParameters<F>, X: ChallengeExtension<F>>(
+        &mut self,
+        lookup: &LookupConstraint<T, F, E, X>,
+    ) {
+        match lookup {
+            LookupConstraint::Table(table) => self.tables.push((
+                table.values_digests.iter().map(|d| *d.register()).collect(),
+                PlonkLookup {
+                    table: table.table.iter().map(|t| wires(t.register())).collect(),
+                    multiplicities: table.multiplicities.iter().map(|m| wire(&m)).collect(),
+                    values: Vec::new(),
+                },
+            )),
+            LookupConstraint::ValuesLocal(values) => self.values.push((
+                *values.digest.register(),
+                values
+                    .trace_values
+                    .iter()
+                    .chain(values.public_values.iter())
+                    .map(plonk_entry)
+                    .collect(),
+            )),
+            // The public values are collected with the trace values, and the digests are only
+            // part of the log-derivative argument.
+            LookupConstraint::ValuesGlobal(_)
+            | LookupConstraint::ValuesDigest(..)
+            | LookupConstraint::Digest(..) => {}
+        }
+    }
+
+    fn into_arguments(self) -> (Vec<PlonkMultiset>, Vec<PlonkLookup>) {
+        let mut channels = self.channels.into_iter().map(Some).collect::<Vec<_>>();
+        let mut multisets = Vec::new();
+        for (bus_channels, global_entries) in self.buses {
+            let mut entries = Vec::new();
+            for channel in channels.iter_mut() {
+                if matches!(channel, Some((out, _)) if bus_channels.contains(out)) {
+                    entries.extend(channel.take().unwrap().1);
+                }
+            }
+            entries.extend(global_entries);
+            multisets.push(PlonkMultiset { entries });
+        }
+        // A channel which is not connected to a bus is balanced on its own.
+        multisets.extend(
+            channels
+                .into_iter()
+                .flatten()
+                .map(|(_, entries)| PlonkMultiset { entries }),
+        );
+
+        let mut values = self.values;
+        let lookups = self
+            .tables
+            .into_iter()
+            .map(|(digests, mut lookup)| {
+                for (_, entries) in values.iter_mut().filter(|(d, _)| digests.contains(d)) {
+                    lookup.values.append(entries);
+                }
+                lookup
+            })
+            .collect();
+
+        (multisets, lookups)
+    }
+}
+
+/// The wires of the cells of `register`.
+fn wires(register: &MemorySlice) -> Vec<PlonkWire> {
+    let (start, end) = register.get_range();
+    let wire = match register {
+        MemorySlice::Local(..) => PlonkWire::Local,
+        MemorySlice::Next(..) => PlonkWire::Next,
+        MemorySlice::Public(..) => PlonkWire::Public,
+        MemorySlice::Global(..) => PlonkWire::Global,
+        MemorySlice::Challenge(..) => PlonkWire::Challenge,
+    };
+    (start..end).map(wire).collect()
+}
+
+fn wire<T: Register>(register: &T) -> PlonkWire {
+    let wires = wires(register.register());
+    assert_eq!(wires.len(), 1, "Expected a register of a single cell");
+    wires[0]
+}
+
+fn plonk_entry<T: Register>(entry: &LogEntry<T>) -> PlonkEntry {
+    let (value, multiplicity) = match entry {
+        LogEntry::Input(value) => (value, PlonkMultiplicity::One),
+        LogEntry::Output(value) => (value, PlonkMultiplicity::MinusOne),
+        LogEntry::InputMultiplicity(value, m) => (value, PlonkMultiplicity::Wire(wire(m))),
+        LogEntry::OutputMultiplicity(value, m) => (value, PlonkMultiplicity::NegWire(wire(m))),
+    };
+    PlonkEntry {
+        tuple: wires(value.register()),
+        multiplicity,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::air::plonk::PlonkDomain;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::memory::time::Time;
+    use crate::chip::register::element::ElementRegister;
+
+    #[test]
+    fn test_fibonacci_chip_to_plonk() {
+        type L = FibonacciParameters;
+
+        let mut builder = AirBuilder::<L>::new();
+        let x_0 = builder.alloc::<ElementRegister>();
+        let x_1 = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_transition(&x_0.next(), x_1.expr());
+        builder.set_to_expression_transition(&x_1.next(), x_0.expr() + x_1.expr());
+        let (air, _) = builder.build();
+
+        let circuit = air.to_plonk();
+        // One gate for the difference of `x_0' - x_1`, two for `x_1' - (x_0 + x_1)`, and one for
+        // each transition constraint.
+        assert_eq!(circuit.gates.len(), 5);
+        assert_eq!(circuit.num_gates_in(PlonkDomain::Transition), 2);
+        assert_eq!(circuit.num_gates_in(PlonkDomain::EveryRow), 3);
+        assert!(circuit.multisets.is_empty() && circuit.lookups.is_empty());
+        assert_eq!(circuit, PlonkCircuit::from_air(&air));
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct PlonkMemoryTest;
+
+    impl AirParameters for PlonkMemoryTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 4;
+        const EXTENDED_COLUMNS: usize = 30;
+    }
+
+    #[test]
+    fn test_memory_to_plonk() {
+        type L = PlonkMemoryTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+        let value = builder.alloc_public::<ElementRegister>();
+        let ptr = builder.initialize(&value, &Time::zero(), None);
+        let read = builder.get(&ptr, &Time::zero(), None, None);
+        builder.set(&ptr, read, &Time::zero(), None, None, None);
+        builder.free(&ptr, value, &Time::zero());
+        let (air, _) = builder.build();
+
+        let circuit = air.to_plonk();
+
+        // The read and the write of every row, and the public initialization and free.
+        assert_eq!(circuit.multisets.len(), 1);
+        let entries = &circuit.multisets[0].entries;
+        assert_eq!(
+            entries.iter().map(|e| e.multiplicity).collect::<Vec<_>>(),
+            vec![
+                PlonkMultiplicity::MinusOne,
+                PlonkMultiplicity::One,
+                PlonkMultiplicity::One,
+                PlonkMultiplicity::MinusOne
+            ]
+        );
+        assert!(entries.iter().all(|e| e.tuple.len() == 3));
+        assert!(entries[..2]
+            .iter()
+            .all(|e| matches!(e.tuple[0], PlonkWire::Local(_))));
+        assert!(circuit.lookups.is_empty());
+
+        // The log-derivative accumulation of the bus is not part of the gates.
+        assert!(circuit.gates.len() < PlonkCircuit::from_air(&air).gates.len());
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct PlonkRangeTest;
+
+    impl AirParameters for PlonkRangeTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 2;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[test]
+    fn test_range_check_to_plonk() {
+        type L = PlonkRangeTest;
+
+        let builder = AirBuilder::<L>::new();
+        let (air, _) = builder.build();
+
+        let circuit = air.to_plonk();
+        assert!(circuit.multisets.is_empty());
+        assert_eq!(circuit.lookups.len(), 1);
+        let lookup = &circuit.lookups[0];
+        assert_eq!(lookup.table, vec![vec![PlonkWire::Local(2)]]);
+        assert_eq!(lookup.multiplicities, vec![PlonkWire::Local(3)]);
+        assert_eq!(
+            lookup.values,
+            (0..2)
+                .map(|i| PlonkEntry {
+                    tuple: vec![PlonkWire::Local(i)],
+                    multiplicity: PlonkMultiplicity::One,
+                })
+                .collect::<Vec<_>>()
+        );
+        // The counter of the table remains constrained by gates.
+        assert_eq!(circuit.num_gates_in(PlonkDomain::FirstRow), 1);
+    }
+}
@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+
+use super::arithmetic::expression::ArithmeticExpression;
+use super::builder::AirBuilder;
+use super::instruction::Instruction;
+use super::register::array::ArrayRegister;
+use super::register::element::ElementRegister;
+use super::register::Register;
+use super::trace::writer::{AirWriter, TraceWriter};
+use super::AirParameters;
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::math::prelude::*;
+
+/// Writes the digits of a value in a given radix, together with their complements.
+///
+/// The digits are in little-endian order. If the radix is not a power of two, the complement
+/// `radix - 1 - digit` of every digit is written as well.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RadixInstruction {
+    value: ElementRegister,
+    radix: u64,
+    digits: ArrayRegister<ElementRegister>,
+    complements: Option<ArrayRegister<ElementRegister>>,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Decomposes `value` into `num_digits` little-endian digits in base `radix`.
+    ///
+    /// Every digit is range checked to `[0, radix)`. Both the digit and, if the radix is not a
+    /// power of two, its complement `radix - 1 - digit` are checked to `num_bits` bits by
+    /// `range_check`, where `2^num_bits` is the smallest power of two not below the radix. The
+    /// recomposition of the digits is constrained to equal `value`. The value must be smaller
+    /// than `radix^num_digits`, which must itself be at most the order of the field, and
+    /// `num_bits` is limited like the widths of `range_check`.
+    pub fn decompose_radix(
+        &mut self,
+        value: &ElementRegister,
+        radix: u64,
+        num_digits: usize,
+    ) -> ArrayRegister<ElementRegister>
+    where
+        L::Instruction: From<RadixInstruction>,
+    {
+        assert!(radix >= 2, "The radix must be at least 2");
+        let range = u32::try_from(num_digits)
+            .ok()
+            .and_then(|n| (radix as u128).checked_pow(n));
+        assert!(
+            matches!(range, Some(range) if range <= L::Field::order() as u128),
+            "The range of the digits must not exceed the field order"
+        );
+
+        let num_bits = (u64::BITS - (radix - 1).leading_zeros()) as usize;
+        let digits = self.alloc_array::<ElementRegister>(num_digits);
+        for digit in digits.iter() {
+            self.range_check(&digit, num_bits);
+        }
+        let complements = (!radix.is_power_of_two()).then(|| {
+            let complements = self.alloc_array::<ElementRegister>(num_digits);
+            for (digit, complement) in digits.iter().zip(complements.iter()) {
+                self.assert_expressions_equal(
+                    digit.expr() + complement.expr(),
+                    ArithmeticExpression::from(L::Field::from_canonical_u64(radix - 1)),
+                );
+                self.range_check(&complement, num_bits);
+            }
+            complements
+        });
+
+        let mut power = L::Field::ONE;
+        let mut recomposition = ArithmeticExpression::zero();
+        for digit in digits.iter() {
+            recomposition = recomposition + digit.expr() * power;
+            power *= L::Field::from_canonical_u64(radix);
+        }
+        self.assert_expressions_equal(value.expr(), recomposition);
+
+        self.register_instruction(RadixInstruction {
+            value: *value,
+            radix,
+            digits,
+            complements,
+        });
+
+        digits
+    }
+}
+
+impl RadixInstruction {
+    /// The little-endian digits of `value` and their complements.
+    fn digits_and_complements(&self, value: u64) -> (Vec<u64>, Vec<u64>) {
+        let mut rest = value;
+        let digits = (0..self.digits.len())
+            .map(|_| {
+                let digit = rest % self.radix;
+                rest /= self.radix;
+                digit
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(rest, 0, "Value {} does not fit in the digits", value);
+
+        let complements = digits.iter().map(|d| self.radix - 1 - d).collect();
+        (digits, complements)
+    }
+}
+
+impl<AP: AirParser> AirConstraint<AP> for RadixInstruction {
+    // The constraints of the decomposition are registered by `decompose_radix`.
+    fn eval(&self, _parser: &mut AP) {}
+}
+
+impl<F: PrimeField64> Instruction<F> for RadixInstruction {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let value = writer.read(&self.value, row_index).as_canonical_u64();
+        let (digits, complements) = self.digits_and_complements(value);
+        writer.write_array(
+            &self.digits,
+            digits.into_iter().map(F::from_canonical_u64),
+            row_index,
+        );
+        if let Some(register) = &self.complements {
+            writer.write_array(
+                register,
+                complements.into_iter().map(F::from_canonical_u64),
+                row_index,
+            );
+        }
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let value = writer.read(&self.value).as_canonical_u64();
+        let (digits, complements) = self.digits_and_complements(value);
+        writer.write_array(&self.digits, digits.into_iter().map(F::from_canonical_u64));
+        if let Some(register) = &self.complements {
+            writer.write_array(register, complements.into_iter().map(F::from_canonical_u64));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::instruction::set::AirInstruction;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RadixTest;
+
+    impl AirParameters for RadixTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = RadixInstruction;
+
+        // The value, 6 digits and their complements in base 10, 4 digits in base 256, and the
+        // range check tables of 4 and 8 bits.
+        const NUM_FREE_COLUMNS: usize = 21;
+        const EXTENDED_COLUMNS: usize = 42;
+    }
+
+    /// Proves the decompositions of random values in base 10 and 256, with the first decimal
+    /// digit of the first row replaced by `first_digit` if set.
+    fn prove_decompose_radix(first_digit: Option<u64>) {
+        type F = GoldilocksField;
+        type L = RadixTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let value = builder.alloc::<ElementRegister>();
+        let decimal_digits = builder.decompose_radix(&value, 10, 6);
+        let byte_digits = builder.decompose_radix(&value, 256, 4);
+
+        let (air, trace_data) = builder.build();
+
+        // The trace holds the table of the 8-bit range checks.
+        let num_rows = 1 << 8;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        let mut rng = rand::thread_rng();
+        let mut values = vec![12, 999_999];
+        values.extend((2..num_rows).map(|_| rng.gen_range(0..1_000_000u64)));
+        for (i, value_int) in values.iter().enumerate() {
+            writer.write(&value, &F::from_canonical_u64(*value_int), i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let recompose = |digits: &ArrayRegister<ElementRegister>, radix: u64| {
+                writer
+                    .read_vec(digits, i)
+                    .iter()
+                    .rev()
+                    .fold(0, |acc, d| acc * radix + d.as_canonical_u64())
+            };
+            assert_eq!(recompose(&decimal_digits, 10), *value_int);
+            assert_eq!(recompose(&byte_digits, 256), *value_int);
+            assert!(writer
+                .read_vec(&decimal_digits, i)
+                .iter()
+                .all(|d| d.as_canonical_u64() < 10));
+        }
+
+        // Replace the digits `[2, 1]` of 12 by `[first_digit, 0]` in the first row, together with
+        // the complement of the first digit. This satisfies the recomposition for a first digit
+        // of 12, but its complement is `-3`, which is out of the range of 4 bits.
+        if let Some(digit) = first_digit {
+            let radix_minus_one = F::from_canonical_u8(9);
+            let complements = generator
+                .air_data
+                .instructions
+                .iter()
+                .find_map(|instruction| match instruction {
+                    AirInstruction::CustomInstruction(radix) => radix.complements,
+                    _ => None,
+                })
+                .unwrap();
+            writer.write(&decimal_digits.get(0), &F::from_canonical_u64(digit), 0);
+            writer.write(&decimal_digits.get(1), &F::ZERO, 0);
+            writer.write(
+                &complements.get(0),
+                &(radix_minus_one - F::from_canonical_u64(digit)),
+                0,
+            );
+            writer.write(&complements.get(1), &radix_minus_one, 0);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public_inputs = writer.public().unwrap().clone();
+        test_starky(&stark, &config, &generator, &public_inputs);
+    }
+
+    #[test]
+    fn test_decompose_radix() {
+        prove_decompose_radix(None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_decompose_radix_out_of_range_digit() {
+        prove_decompose_radix(Some(12));
+    }
+}
@@ -11,7 +11,7 @@ use crate::chip::builder::AirBuilder;
 use crate::chip::memory::pointer::raw::RawPointer;
 use crate::chip::memory::time::Time;
 use crate::chip::memory::value::MemoryValue;
-use crate::machine::builder::ops::{Add, And, Mul, Not, Or};
+use crate::machine::builder::ops::{Add, And, Mul, Not, Or, Xor};
 use crate::machine::builder::Builder;
 use crate::math::prelude::*;
 
@@ -62,7 +62,7 @@ impl Register for BitRegister {
 }
 
 impl MemoryValue for BitRegister {
-    fn num_challenges() -> usize {
+    fn num_challenges<F: PrimeField64>() -> usize {
         0
     }
 
@@ -116,3 +116,12 @@ impl<B: Builder> And<B> for BitRegister {
         builder.mul(self, rhs)
     }
 }
+
+impl<B: Builder> Xor<B> for BitRegister {
+    type Output = Self;
+
+    fn xor(self, rhs: Self, builder: &mut B) -> Self::Output {
+        let product = self.expr() * rhs.expr();
+        builder.expression(self.expr() + rhs.expr() - product.clone() - product)
+    }
+}
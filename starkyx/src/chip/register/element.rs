@@ -49,7 +49,7 @@ impl Register for ElementRegister {
 }
 
 impl MemoryValue for ElementRegister {
-    fn num_challenges() -> usize {
+    fn num_challenges<F: PrimeField64>() -> usize {
         0
     }
 
@@ -0,0 +1,72 @@
+use super::cubic::{CubicRegister, EvalCubic};
+use super::quintic::{EvalQuintic, QuinticRegister};
+use super::Register;
+use crate::air::extension::ExtensionParser;
+use crate::math::extension::cubic::extension::CubicExtension;
+use crate::math::extension::quintic::extension::QuinticExtension;
+use crate::math::prelude::*;
+
+/// A register whose value embeds in the extension field `X`, such as an element of the base
+/// field or of `X` itself.
+pub trait EvalExtension<X>: Register {
+    fn eval_extension<AP: ExtensionParser<X>>(&self, parser: &mut AP) -> AP::Element;
+}
+
+/// An extension field of `F` from which a challenge can be drawn, with the register holding its
+/// elements.
+pub trait ChallengeExtension<F: Field>: ExtensionField<F> {
+    type Register: EvalExtension<Self>;
+
+    /// The element of the extension embedding the value of a register of type `T`, whose
+    /// coefficients are the ones of the value followed by zeros.
+    fn from_register_value<T: Register>(value: &T::Value<F>) -> Self;
+
+    /// The value of a register of type `Self::Register` holding the element.
+    fn register_value(&self) -> <Self::Register as Register>::Value<F> {
+        Self::Register::value_from_slice(self.as_base_slice())
+    }
+}
+
+impl<F: Field, E: CubicParameters<F>, T: EvalCubic> EvalExtension<CubicExtension<F, E>> for T {
+    fn eval_extension<AP: ExtensionParser<CubicExtension<F, E>>>(
+        &self,
+        parser: &mut AP,
+    ) -> AP::Element {
+        let value = self.eval_cubic(parser);
+        parser.element_from_base_slice(value.as_slice())
+    }
+}
+
+impl<F: Field, E: QuinticParameters<F>, T: EvalQuintic> EvalExtension<QuinticExtension<F, E>>
+    for T
+{
+    fn eval_extension<AP: ExtensionParser<QuinticExtension<F, E>>>(
+        &self,
+        parser: &mut AP,
+    ) -> AP::Element {
+        let value = self.eval_quintic(parser);
+        parser.element_from_base_slice(value.as_slice())
+    }
+}
+
+impl<F: Field, E: CubicParameters<F>> ChallengeExtension<F> for CubicExtension<F, E> {
+    type Register = CubicRegister;
+
+    fn from_register_value<T: Register>(value: &T::Value<F>) -> Self {
+        let coefficients = T::align(value);
+        let mut array = [F::ZERO; 3];
+        array[..coefficients.len()].copy_from_slice(coefficients);
+        Self::from(array)
+    }
+}
+
+impl<F: Field, E: QuinticParameters<F>> ChallengeExtension<F> for QuinticExtension<F, E> {
+    type Register = QuinticRegister;
+
+    fn from_register_value<T: Register>(value: &T::Value<F>) -> Self {
+        let coefficients = T::align(value);
+        let mut array = [F::ZERO; 5];
+        array[..coefficients.len()].copy_from_slice(coefficients);
+        Self::from(array)
+    }
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+
+use super::array::ArrayRegister;
+use super::Register;
+
+/// A matrix of registers, stored in row-major order in contiguous memory.
+///
+/// The registers of a row are adjacent, and every row starts right after the previous one, so a
+/// row can be accessed as an `ArrayRegister`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Matrix<T> {
+    array: ArrayRegister<T>,
+    num_rows: usize,
+    num_cols: usize,
+}
+
+impl<T: Register> Matrix<T> {
+    /// Views `array` as a matrix with `num_rows` rows of `num_cols` registers.
+    pub fn from_array(array: ArrayRegister<T>, num_rows: usize, num_cols: usize) -> Self {
+        assert_eq!(
+            array.len(),
+            num_rows * num_cols,
+            "Array of length {} can't hold a {}x{} matrix",
+            array.len(),
+            num_rows,
+            num_cols
+        );
+        Self {
+            array,
+            num_rows,
+            num_cols,
+        }
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    /// The register in row `row` and column `col`.
+    #[inline]
+    pub fn get(&self, row: usize, col: usize) -> T {
+        self.check_bounds(row, col);
+        self.array.get(row * self.num_cols + col)
+    }
+
+    /// The registers of row `row`.
+    #[inline]
+    pub fn row(&self, row: usize) -> ArrayRegister<T> {
+        self.check_bounds(row, 0);
+        self.array
+            .get_subarray(row * self.num_cols..(row + 1) * self.num_cols)
+    }
+
+    /// The registers of column `col`, from the first row to the last.
+    pub fn col(&self, col: usize) -> Vec<T> {
+        self.check_bounds(0, col);
+        (0..self.num_rows).map(|row| self.get(row, col)).collect()
+    }
+
+    /// All registers of the matrix in row-major order.
+    pub fn as_array(&self) -> ArrayRegister<T> {
+        self.array
+    }
+
+    #[inline]
+    fn check_bounds(&self, row: usize, col: usize) {
+        if row >= self.num_rows || col >= self.num_cols {
+            panic!(
+                "Index ({}, {}) out of bounds for a {}x{} matrix",
+                row, col, self.num_rows, self.num_cols
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::memory::MemorySlice;
+    use crate::chip::AirParameters;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct MatrixTest;
+
+    impl AirParameters for MatrixTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 26;
+    }
+
+    #[test]
+    fn test_matrix_register() {
+        type F = GoldilocksField;
+        type L = MatrixTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let first = builder.alloc::<ElementRegister>();
+        let lanes = builder.alloc_matrix::<ElementRegister>(5, 5);
+        assert_eq!(*first.register(), MemorySlice::Local(0, 1));
+        assert_eq!(*lanes.as_array().register(), MemorySlice::Local(1, 25));
+        for row in 0..5 {
+            assert_eq!(
+                *lanes.row(row).register(),
+                MemorySlice::Local(1 + 5 * row, 5)
+            );
+            for col in 0..5 {
+                assert_eq!(
+                    *lanes.get(row, col).register(),
+                    MemorySlice::Local(1 + 5 * row + col, 1)
+                );
+            }
+        }
+
+        let (_, trace_data) = builder.build();
+        let num_rows = 1 << 3;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        let value =
+            |i: usize, row: usize, col: usize| F::from_canonical_usize(100 * i + 10 * row + col);
+        for i in 0..num_rows {
+            for row in 0..5 {
+                for col in 0..5 {
+                    writer.write(&lanes.get(row, col), &value(i, row, col), i);
+                }
+            }
+        }
+
+        for i in 0..num_rows {
+            for row in 0..5 {
+                let values = writer.read_array::<_, 5>(&lanes.row(row), i);
+                assert_eq!(values, core::array::from_fn(|col| value(i, row, col)));
+            }
+            let col_values = lanes
+                .col(3)
+                .iter()
+                .map(|reg| writer.read(reg, i))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                col_values,
+                (0..5).map(|row| value(i, row, 3)).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "out of bounds")]
+    fn test_matrix_out_of_bounds() {
+        let mut builder = AirBuilder::<MatrixTest>::new();
+        let lanes = builder.alloc_matrix::<ElementRegister>(5, 5);
+        lanes.get(0, 5);
+    }
+}
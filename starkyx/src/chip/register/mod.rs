@@ -14,7 +14,11 @@ pub mod bit;
 pub mod cell;
 pub mod cubic;
 pub mod element;
+pub mod extension;
+pub mod matrix;
 pub mod memory;
+pub mod quintic;
+pub mod record;
 pub mod slice;
 pub mod u16;
 
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use super::array::ArrayRegister;
+use super::cell::CellType;
+use super::element::ElementRegister;
+use super::memory::MemorySlice;
+use super::{Register, RegisterSerializable, RegisterSized};
+use crate::air::parser::AirParser;
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::math::extension::quintic::element::QuinticElement;
+use crate::math::prelude::*;
+
+/// A register for an element of a quintic extension, given by five consecutive columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct QuinticRegister(MemorySlice);
+
+pub trait EvalQuintic: Register {
+    fn value_as_quintic<T: Copy>(value: Self::Value<T>, zero: T) -> QuinticElement<T>;
+
+    fn eval_quintic<AP: AirParser>(&self, parser: &mut AP) -> QuinticElement<AP::Var> {
+        let value = self.eval(parser);
+        let zero = parser.zero();
+        Self::value_as_quintic(value, zero)
+    }
+
+    fn trace_value_as_quintic<F: Field>(value: Self::Value<F>) -> QuinticElement<F> {
+        Self::value_as_quintic(value, F::ZERO)
+    }
+}
+
+impl RegisterSerializable for QuinticRegister {
+    const CELL: CellType = CellType::Element;
+
+    fn register(&self) -> &MemorySlice {
+        &self.0
+    }
+
+    fn from_register_unsafe(register: MemorySlice) -> Self {
+        QuinticRegister(register)
+    }
+}
+
+impl RegisterSized for QuinticRegister {
+    fn size_of() -> usize {
+        5
+    }
+}
+
+impl Register for QuinticRegister {
+    type Value<T> = QuinticElement<T>;
+
+    fn value_from_slice<T: Copy>(slice: &[T]) -> Self::Value<T> {
+        debug_assert!(
+            slice.len() == 5,
+            "Slice length mismatch for quintic register (expected 5, got {})",
+            slice.len()
+        );
+        QuinticElement(core::array::from_fn(|i| slice[i]))
+    }
+
+    fn align<T>(value: &Self::Value<T>) -> &[T] {
+        &value.0
+    }
+
+    fn expr<F: Field>(&self) -> ArithmeticExpression<F> {
+        unimplemented!(
+            "Cannot create expression from quintic register, use the method ext_expr() instead"
+        )
+    }
+}
+
+impl QuinticRegister {
+    pub fn as_base_array(&self) -> [ElementRegister; 5] {
+        let array = ArrayRegister::<ElementRegister>::from_register_unsafe(*self.register());
+        core::array::from_fn(|i| array.get(i))
+    }
+
+    pub fn ext_expr<F: Field>(&self) -> QuinticElement<ArithmeticExpression<F>> {
+        QuinticElement(self.as_base_array().map(|e| e.expr()))
+    }
+}
+
+impl EvalQuintic for QuinticRegister {
+    fn value_as_quintic<T: Copy>(value: Self::Value<T>, _zero: T) -> QuinticElement<T> {
+        value
+    }
+}
+
+impl EvalQuintic for ElementRegister {
+    fn value_as_quintic<T: Copy>(value: Self::Value<T>, zero: T) -> QuinticElement<T> {
+        QuinticElement::from_base(value, zero)
+    }
+}
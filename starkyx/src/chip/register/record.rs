@@ -0,0 +1,193 @@
+use core::iter::once;
+use core::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use super::array::ArrayRegister;
+use super::cell::CellType;
+use super::cubic::CubicRegister;
+use super::element::ElementRegister;
+use super::memory::MemorySlice;
+use super::{Register, RegisterSerializable, RegisterSized};
+use crate::chip::builder::AirBuilder;
+use crate::chip::memory::pointer::raw::RawPointer;
+use crate::chip::memory::time::Time;
+use crate::chip::memory::value::MemoryValue;
+use crate::math::prelude::*;
+
+/// A register for a record of two typed fields laid out contiguously in memory, the first field
+/// followed by the second.
+///
+/// Records with more fields can be built by nesting, e.g. `RecordRegister<A, RecordRegister<B, C>>`.
+/// The value of a record is the concatenation of the values of its fields.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RecordRegister<A, B> {
+    register: MemorySlice,
+    _marker: PhantomData<(A, B)>,
+}
+
+impl<A: Register, B: Register> RecordRegister<A, B> {
+    fn field_slice(&self, offset: usize, len: usize) -> MemorySlice {
+        *ArrayRegister::<ElementRegister>::from_register_unsafe(self.register)
+            .get_subarray(offset..offset + len)
+            .register()
+    }
+
+    /// The first field of the record.
+    pub fn first(&self) -> A {
+        A::from_register(self.field_slice(0, A::size_of()))
+    }
+
+    /// The second field of the record.
+    pub fn second(&self) -> B {
+        B::from_register(self.field_slice(A::size_of(), B::size_of()))
+    }
+}
+
+impl<A: Register, B: Register> RegisterSerializable for RecordRegister<A, B> {
+    const CELL: CellType = CellType::Element;
+
+    fn register(&self) -> &MemorySlice {
+        &self.register
+    }
+
+    fn from_register_unsafe(register: MemorySlice) -> Self {
+        Self {
+            register,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<A: Register, B: Register> RegisterSized for RecordRegister<A, B> {
+    fn size_of() -> usize {
+        A::size_of() + B::size_of()
+    }
+}
+
+impl<A: Register, B: Register> Register for RecordRegister<A, B> {
+    type Value<T> = Vec<T>;
+
+    fn value_from_slice<T: Copy>(slice: &[T]) -> Self::Value<T> {
+        debug_assert_eq!(slice.len(), Self::size_of());
+        slice.to_vec()
+    }
+
+    fn align<T>(value: &Self::Value<T>) -> &[T] {
+        value
+    }
+}
+
+impl<A: Register, B: Register> MemoryValue for RecordRegister<A, B> {
+    fn num_challenges<F: PrimeField64>() -> usize {
+        Self::size_of() + 1
+    }
+
+    fn compress<L: crate::chip::AirParameters>(
+        &self,
+        builder: &mut AirBuilder<L>,
+        ptr: RawPointer,
+        time: &Time<L::Field>,
+        challenges: &ArrayRegister<CubicRegister>,
+    ) -> CubicRegister {
+        let expressions = ArrayRegister::<ElementRegister>::from_register_unsafe(self.register)
+            .iter()
+            .map(|cell| cell.expr())
+            .chain(once(time.expr()))
+            .collect::<Vec<_>>();
+        let compressed = if self.is_trace() {
+            builder.accumulate_expressions(challenges, &expressions)
+        } else {
+            builder.accumulate_public_expressions(challenges, &expressions)
+        };
+
+        ptr.accumulate_cubic(builder, compressed.ext_expr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::chip::builder::tests::ArithmeticGenerator;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::uint::register::{U32Register, U64Register};
+    use crate::chip::AirParameters;
+    use crate::machine::builder::Builder;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::PoseidonGoldilocksStarkConfig;
+    use crate::plonky2::stark::tests::{test_recursive_starky, test_starky};
+    use crate::plonky2::stark::Starky;
+
+    #[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+    struct RecordMemoryTest;
+
+    impl AirParameters for RecordMemoryTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 12;
+        const EXTENDED_COLUMNS: usize = 24;
+    }
+
+    type KeyValue = RecordRegister<U32Register, U64Register>;
+
+    #[test]
+    fn test_record_fields() {
+        let record = KeyValue::from_register(MemorySlice::Local(3, 12));
+        assert_eq!(*record.first().register(), MemorySlice::Local(3, 4));
+        assert_eq!(*record.second().register(), MemorySlice::Local(7, 8));
+
+        let nested =
+            RecordRegister::<ElementRegister, KeyValue>::from_register(MemorySlice::Public(0, 13));
+        assert_eq!(*nested.first().register(), MemorySlice::Public(0, 1));
+        assert_eq!(
+            *nested.second().second().register(),
+            MemorySlice::Public(5, 8)
+        );
+    }
+
+    #[test]
+    fn test_record_memory() {
+        type F = GoldilocksField;
+        type L = RecordMemoryTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let num_rows = 1 << 5;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+
+        let record_pub = builder.alloc_public::<KeyValue>();
+        let ptr = builder.uninit::<KeyValue>();
+        let mult = builder.constant(&F::from_canonical_usize(num_rows));
+        builder.store(&ptr, record_pub, &Time::zero(), Some(mult), None, None);
+
+        let record = builder.load(&ptr, &Time::zero(), None, None);
+
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        let key = [1u8, 2, 3, 4].map(F::from_canonical_u8);
+        let value = [5u8, 6, 7, 8, 9, 10, 11, 12].map(F::from_canonical_u8);
+        writer.write(&record_pub.first(), &key, 0);
+        writer.write(&record_pub.second(), &value, 0);
+        writer.write_global_instructions(&generator.air_data);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+            assert_eq!(writer.read(&record.first(), i), key);
+            assert_eq!(writer.read(&record.second(), i), value);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public = writer.public().unwrap().clone();
+        test_starky(&stark, &config, &generator, &public);
+        test_recursive_starky(stark, config, generator, &public);
+    }
+}
@@ -4,6 +4,7 @@ use crate::air::AirConstraint;
 use crate::chip::register::cubic::EvalCubic;
 use crate::chip::register::Register;
 use crate::chip::table::log_derivative::constraints::LogConstraints;
+use crate::math::extension::cubic::extension::CubicExtension;
 use crate::math::extension::cubic::parameters::CubicParameters;
 
 impl<T: EvalCubic, AP: CubicParser<E>, E: CubicParameters<AP::Field>> AirConstraint<AP>
@@ -13,7 +14,7 @@ impl<T: EvalCubic, AP: CubicParser<E>, E: CubicParameters<AP::Field>> AirConstra
         let beta = self.challenge.eval(parser);
 
         // Constrain the trace accumulation of the bus channel.
-        LogConstraints::log_trace_accumulation(
+        LogConstraints::<AP, CubicExtension<AP::Field, E>>::log_trace_accumulation(
             parser,
             beta,
             &self.entries,
@@ -101,6 +101,11 @@ impl<T: EvalCubic, E> BusChannel<T, E> {
         }
     }
 
+    /// The entries of the channel.
+    pub(crate) fn entries(&self) -> &[LogEntry<T>] {
+        &self.entries
+    }
+
     #[inline]
     pub fn input(&mut self, value: T) {
         let entry = LogEntry::Input(value);
@@ -0,0 +1,93 @@
+//! Lookups of values between the traces of two different AIRs.
+//!
+//! A cross-table lookup is a bus channel present in both AIRs: the sending AIR inputs values with
+//! a multiplicity to its channel and the receiving AIR inputs every value it looks up once. Both
+//! channels use the same challenge `beta`, and each accumulates the log-derivative running sum of
+//! its entries over the rows of its trace, so that
+//!
+//! `sum_i m_i / (beta - a_i)` and `sum_j 1 / (beta - b_j)`
+//!
+//! are the cumulative values of the sender and the receiver. The cumulative value of a channel is
+//! a global value of the extended round of its AIR, in the `global_values_range` of its
+//! `RoundDatum`, which the constraints of the channel tie to the last row of its running sum.
+//!
+//! The AIRs are proven with a shared challenger, which observes the execution traces of both
+//! AIRs before `beta` is drawn. The verifier then checks that the two cumulative values found in
+//! the global values of the proofs are equal, which holds with high probability only if every
+//! received value `b_j` is some sent value `a_i`, and each `a_i` is received `m_i` times.
+
+use core::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use super::channel::BusChannel;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::cubic::CubicRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::AirParameters;
+use crate::math::extension::cubic::element::CubicElement;
+
+/// One side of a cross-table lookup, allocated by `AirBuilder::cross_table_lookup`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CrossTableLookup {
+    channel_idx: usize,
+    challenge: CubicRegister,
+    cumulative: CubicRegister,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Allocates one side of a cross-table lookup, a bus channel whose challenge is shared with
+    /// the other side when the two AIRs are proven together.
+    pub fn cross_table_lookup(&mut self) -> CrossTableLookup {
+        let challenge = self.alloc_challenge::<CubicRegister>();
+        let cumulative = self.alloc_global::<CubicRegister>();
+        let accumulator = self.alloc_extended::<CubicRegister>();
+        let channel_idx = self.bus_channels.len();
+        self.bus_channels
+            .push(BusChannel::new(challenge, cumulative, accumulator));
+        CrossTableLookup {
+            channel_idx,
+            challenge,
+            cumulative,
+        }
+    }
+
+    /// Sends `value` to the other side of the lookup `multiplicity` times in every row.
+    pub fn send(
+        &mut self,
+        lookup: &CrossTableLookup,
+        value: CubicRegister,
+        multiplicity: ElementRegister,
+    ) {
+        self.input_to_bus_with_multiplicity(lookup.channel_idx, value, multiplicity);
+    }
+
+    /// Receives `value` from the other side of the lookup in every row.
+    pub fn receive(&mut self, lookup: &CrossTableLookup, value: CubicRegister) {
+        self.input_to_bus(lookup.channel_idx, value);
+    }
+}
+
+impl CrossTableLookup {
+    /// The challenge `beta` of the lookup.
+    pub fn challenge(&self) -> CubicRegister {
+        self.challenge
+    }
+
+    /// The global register holding the cumulative value of this side of the lookup.
+    pub fn cumulative(&self) -> CubicRegister {
+        self.cumulative
+    }
+
+    /// The indices of the challenge among the challenges of the AIR.
+    pub(crate) fn challenge_range(&self) -> Range<usize> {
+        let (start, end) = self.challenge.register().get_range();
+        start..end
+    }
+
+    /// Reads the cumulative value of this side of the lookup from the global values of the AIR.
+    pub fn read_cumulative<F: Copy>(&self, global_values: &[F]) -> CubicElement<F> {
+        self.cumulative.read_from_slice(global_values)
+    }
+}
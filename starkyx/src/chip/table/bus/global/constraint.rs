@@ -4,6 +4,7 @@ use crate::air::AirConstraint;
 use crate::chip::register::cubic::EvalCubic;
 use crate::chip::register::Register;
 use crate::chip::table::log_derivative::constraints::LogConstraints;
+use crate::math::extension::cubic::extension::CubicExtension;
 use crate::math::extension::cubic::parameters::CubicParameters;
 
 impl<T: EvalCubic, E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstraint<AP>
@@ -13,7 +14,7 @@ impl<T: EvalCubic, E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstra
         let beta = self.challenge.eval(parser);
 
         // Accumulate the global entries.
-        LogConstraints::log_global_accumulation(
+        LogConstraints::<AP, CubicExtension<AP::Field, E>>::log_global_accumulation(
             parser,
             beta,
             &self.global_entries,
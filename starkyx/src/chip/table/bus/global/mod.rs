@@ -76,6 +76,16 @@ impl<T: EvalCubic, E: Clone> Bus<T, E> {
         index
     }
 
+    /// The output channels of the bus channels connected to the bus.
+    pub(crate) fn channels(&self) -> &[CubicRegister] {
+        &self.channels
+    }
+
+    /// The entries of the bus over public and global values.
+    pub(crate) fn global_entries(&self) -> &[LogEntry<T>] {
+        &self.global_entries
+    }
+
     pub fn insert_global_value(&mut self, value: &T) {
         match value.register() {
             MemorySlice::Global(..) => {
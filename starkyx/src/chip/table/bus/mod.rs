@@ -5,4 +5,5 @@
 //!
 
 pub mod channel;
+pub mod cross;
 pub mod global;
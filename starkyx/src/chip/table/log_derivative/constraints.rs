@@ -5,17 +5,17 @@ use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use super::entry::{LogEntry, LogEntryValue};
-use crate::air::extension::cubic::CubicParser;
-use crate::chip::register::cubic::{CubicRegister, EvalCubic};
+use crate::air::extension::ExtensionParser;
+use crate::chip::register::extension::EvalExtension;
 use crate::chip::register::slice::RegisterSlice;
 use crate::chip::register::{Register, RegisterSerializable};
-use crate::math::prelude::cubic::element::CubicElement;
-use crate::math::prelude::CubicParameters;
 
+/// The constraints of the log-derivative argument, with the challenge `beta` in the extension `X`
+/// of the parser `AP`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct LogConstraints<AP, E>(pub PhantomData<(AP, E)>);
+pub struct LogConstraints<AP, X>(pub PhantomData<(AP, X)>);
 
-impl<AP: CubicParser<E>, E: CubicParameters<AP::Field>> LogConstraints<AP, E> {
+impl<X, AP: ExtensionParser<X>> LogConstraints<AP, X> {
     /// Computes the constraint for `mult_a/(beta - a) + mult_b/(beta - b) = result`.
     ///
     /// This rsulting constraint is of degree 3 and assumes that both `beta-a` and `beta-b` are
@@ -27,11 +27,11 @@ impl<AP: CubicParser<E>, E: CubicParameters<AP::Field>> LogConstraints<AP, E> {
     #[inline]
     pub fn log_arithmetic(
         parser: &mut AP,
-        beta: CubicElement<AP::Var>,
-        a: LogEntryValue<AP::Var>,
-        b: LogEntryValue<AP::Var>,
-        result: CubicElement<AP::Var>,
-    ) -> CubicElement<AP::Var> {
+        beta: AP::Element,
+        a: LogEntryValue<AP::Var, AP::Element>,
+        b: LogEntryValue<AP::Var, AP::Element>,
+        result: AP::Element,
+    ) -> AP::Element {
         let a_value = a.value;
         let b_value = b.value;
         let mult_a = a.multiplier;
@@ -58,10 +58,10 @@ impl<AP: CubicParser<E>, E: CubicParameters<AP::Field>> LogConstraints<AP, E> {
     #[inline]
     pub fn log(
         parser: &mut AP,
-        beta: CubicElement<AP::Var>,
-        a: LogEntryValue<AP::Var>,
-        result: CubicElement<AP::Var>,
-    ) -> CubicElement<AP::Var> {
+        beta: AP::Element,
+        a: LogEntryValue<AP::Var, AP::Element>,
+        result: AP::Element,
+    ) -> AP::Element {
         let beta_minus_a = parser.sub_extension(beta, a.value);
         let rhs = parser.mul_extension(beta_minus_a, result);
         let m_ext = parser.element_from_base_field(a.multiplier);
@@ -69,11 +69,11 @@ impl<AP: CubicParser<E>, E: CubicParameters<AP::Field>> LogConstraints<AP, E> {
     }
 
     #[inline]
-    pub fn log_row_accumulation<'a, T: EvalCubic>(
+    pub fn log_row_accumulation<'a, T: EvalExtension<X>>(
         parser: &mut AP,
-        beta: CubicElement<AP::Var>,
+        beta: AP::Element,
         entries: &'a [LogEntry<T>],
-        intermediate_values: &impl RegisterSlice<CubicRegister>,
+        intermediate_values: &impl RegisterSlice<AP::Register>,
     ) -> Option<&'a LogEntry<T>> {
         let entry_chunks = entries.chunks_exact(2);
         let last_element = entry_chunks.remainder().first();
@@ -81,11 +81,11 @@ impl<AP: CubicParser<E>, E: CubicParameters<AP::Field>> LogConstraints<AP, E> {
         let zero = parser.zero_extension();
         let mut prev = zero;
         for (chunk, row_acc) in entry_chunks.zip_eq(intermediate_values.value_iter()) {
-            let a = chunk[0].eval(parser);
-            let b = chunk[1].eval(parser);
+            let a = chunk[0].eval_extension::<X, _>(parser);
+            let b = chunk[1].eval_extension::<X, _>(parser);
             let acc = row_acc.borrow().eval(parser);
             let acc_minus_prev = parser.sub_extension(acc, prev);
-            let constraint = LogConstraints::log_arithmetic(parser, beta, a, b, acc_minus_prev);
+            let constraint = Self::log_arithmetic(parser, beta, a, b, acc_minus_prev);
             parser.constraint_extension(constraint);
             prev = acc;
         }
@@ -100,12 +100,12 @@ impl<AP: CubicParser<E>, E: CubicParameters<AP::Field>> LogConstraints<AP, E> {
     /// `log_trace_accumulation` constrains the extension column `trace_accumulator` to be equal to
     /// the cumuluative sum `sum_{i=0}^{n-1} mult_i * 1/(beta - value_i)`.
     #[inline]
-    pub fn log_trace_accumulation<T: EvalCubic>(
+    pub fn log_trace_accumulation<T: EvalExtension<X>>(
         parser: &mut AP,
-        beta: CubicElement<AP::Var>,
+        beta: AP::Element,
         entries: &[LogEntry<T>],
-        intermediate_values: &impl RegisterSlice<CubicRegister>,
-        trace_accumulator: CubicRegister,
+        intermediate_values: &impl RegisterSlice<AP::Register>,
+        trace_accumulator: AP::Register,
     ) {
         let last_element = Self::log_row_accumulation(parser, beta, entries, intermediate_values);
 
@@ -124,28 +124,26 @@ impl<AP: CubicParser<E>, E: CubicParameters<AP::Field>> LogConstraints<AP, E> {
         acc_transition_constraint =
             parser.sub_extension(acc_transition_constraint, accumulated_value_next);
         if let Some(last) = last_element {
-            let a = last.next().eval(parser);
-            acc_transition_constraint =
-                LogConstraints::log(parser, beta, a, acc_transition_constraint);
+            let a = last.next().eval_extension::<X, _>(parser);
+            acc_transition_constraint = Self::log(parser, beta, a, acc_transition_constraint);
         }
         parser.constraint_extension_transition(acc_transition_constraint);
 
         let mut acc_first_row_constraint = parser.sub_extension(accumulator, accumulated_value);
         if let Some(last) = last_element {
-            let a = last.eval(parser);
-            acc_first_row_constraint =
-                LogConstraints::log(parser, beta, a, acc_first_row_constraint);
+            let a = last.eval_extension::<X, _>(parser);
+            acc_first_row_constraint = Self::log(parser, beta, a, acc_first_row_constraint);
         }
         parser.constraint_extension_first_row(acc_first_row_constraint);
     }
 
     #[inline]
-    pub fn log_global_accumulation<T: EvalCubic>(
+    pub fn log_global_accumulation<T: EvalExtension<X>>(
         parser: &mut AP,
-        beta: CubicElement<AP::Var>,
+        beta: AP::Element,
         entries: &[LogEntry<T>],
-        intermediate_values: &impl RegisterSlice<CubicRegister>,
-        global_accumulator: CubicRegister,
+        intermediate_values: &impl RegisterSlice<AP::Register>,
+        global_accumulator: AP::Register,
     ) {
         let last_element = Self::log_row_accumulation(parser, beta, entries, intermediate_values);
         let accumulated_value = intermediate_values
@@ -155,9 +153,81 @@ impl<AP: CubicParser<E>, E: CubicParameters<AP::Field>> LogConstraints<AP, E> {
         let accumulator = global_accumulator.eval(parser);
         let mut accumulator_constraint = parser.sub_extension(accumulator, accumulated_value);
         if let Some(last) = last_element {
-            let a = last.eval(parser);
-            accumulator_constraint = LogConstraints::log(parser, beta, a, accumulator_constraint);
+            let a = last.eval_extension::<X, _>(parser);
+            accumulator_constraint = Self::log(parser, beta, a, accumulator_constraint);
         }
         parser.constraint_extension(accumulator_constraint);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+
+    use super::*;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::memory::MemorySlice;
+    use crate::chip::register::quintic::QuinticRegister;
+    use crate::chip::table::log_derivative::entry::LogEntry;
+    use crate::math::goldilocks::quintic::GF5;
+    use crate::math::prelude::*;
+    use crate::trace::window::TraceWindow;
+    use crate::trace::window_parser::TraceWindowParser;
+
+    type F = GoldilocksField;
+
+    /// Evaluates the global accumulation of `1/(beta - a) - 1/(beta - b) + m/(beta - c)` with the
+    /// challenge `beta` in the quintic extension, with the accumulator shifted by `offset`.
+    fn eval_quintic_accumulation(offset: GF5) {
+        let beta = GF5::rand();
+        let [a, b, c, m] = [F::rand(), F::rand(), F::rand(), F::rand()];
+
+        let row_acc = GF5::ONE / (beta - a) - GF5::ONE / (beta - b);
+        let accumulator = row_acc + GF5::from(m) / (beta - c) + offset;
+
+        let local = [a, b, c, m]
+            .into_iter()
+            .chain(row_acc.base_field_array())
+            .chain(accumulator.base_field_array())
+            .collect::<Vec<_>>();
+        let challenges = beta.base_field_array();
+
+        let element = |i| ElementRegister::from_register(MemorySlice::Local(i, 1));
+        let entries = [
+            LogEntry::input(element(0)),
+            LogEntry::output(element(1)),
+            LogEntry::input_with_multiplicity(element(2), element(3)),
+        ];
+        let row_accumulators = vec![QuinticRegister::from_register(MemorySlice::Local(4, 5))];
+        let global_accumulator = QuinticRegister::from_register(MemorySlice::Local(9, 5));
+        let beta_register = QuinticRegister::from_register(MemorySlice::Challenge(0, 5));
+
+        let window = TraceWindow {
+            local_slice: &local,
+            next_slice: &local,
+            row: 0,
+            is_first_row: true,
+            is_last_row: true,
+        };
+        let mut parser = TraceWindowParser::new(window, &challenges, &[], &[]);
+        let beta = beta_register.eval(&parser);
+        LogConstraints::<_, GF5>::log_global_accumulation(
+            &mut parser,
+            beta,
+            &entries,
+            &row_accumulators,
+            global_accumulator,
+        );
+    }
+
+    #[test]
+    fn test_quintic_log_accumulation() {
+        eval_quintic_accumulation(GF5::ZERO);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_quintic_log_accumulation_invalid() {
+        eval_quintic_accumulation(GF5::ONE);
+    }
+}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 
 use crate::air::extension::cubic::CubicParser;
+use crate::air::extension::ExtensionParser;
 use crate::chip::register::cubic::EvalCubic;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::extension::{ChallengeExtension, EvalExtension};
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::math::prelude::cubic::element::CubicElement;
 use crate::math::prelude::cubic::extension::CubicExtension;
@@ -25,9 +27,10 @@ pub enum LogEntry<T> {
     OutputMultiplicity(T, ElementRegister),
 }
 
-/// An evaluation of a `LogEntry` instance to be used in constraints.
-pub struct LogEntryValue<V> {
-    pub value: CubicElement<V>,
+/// An evaluation of a `LogEntry` instance to be used in constraints, with its value in an
+/// extension whose elements are of type `E`.
+pub struct LogEntryValue<V, E = CubicElement<V>> {
+    pub value: E,
     pub multiplier: V,
 }
 
@@ -41,6 +44,25 @@ impl<F: Field> LogEntryValue<F> {
     }
 }
 
+impl<F: Field, X: ExtensionField<F>> LogEntryValue<F, X> {
+    /// Evaluates all of `values`, inverting the denominators `beta - value` in a single batch.
+    ///
+    /// # Panics
+    /// Panics if `beta` is equal to one of the values.
+    pub fn evaluate_batch(values: &[Self], beta: X) -> Vec<X> {
+        let mut inverses = values
+            .iter()
+            .map(|entry| beta - entry.value)
+            .collect::<Vec<_>>();
+        X::batch_inverse(&mut inverses);
+        inverses
+            .into_iter()
+            .zip(values)
+            .map(|(inverse, entry)| inverse * entry.multiplier)
+            .collect()
+    }
+}
+
 impl<T: EvalCubic> LogEntry<T> {
     pub const fn input(value: T) -> Self {
         LogEntry::Input(value)
@@ -58,20 +80,6 @@ impl<T: EvalCubic> LogEntry<T> {
         LogEntry::OutputMultiplicity(value, multiplier)
     }
 
-    #[inline]
-    pub fn next(&self) -> Self {
-        match self {
-            LogEntry::Input(value) => LogEntry::Input(value.next()),
-            LogEntry::Output(value) => LogEntry::Output(value.next()),
-            LogEntry::InputMultiplicity(value, multiplier) => {
-                LogEntry::InputMultiplicity(value.next(), multiplier.next())
-            }
-            LogEntry::OutputMultiplicity(value, multiplier) => {
-                LogEntry::OutputMultiplicity(value.next(), multiplier.next())
-            }
-        }
-    }
-
     pub const fn value(&self) -> &T {
         match self {
             LogEntry::Input(value) => value,
@@ -86,55 +94,83 @@ impl<T: EvalCubic> LogEntry<T> {
         &self,
         parser: &mut AP,
     ) -> LogEntryValue<AP::Var> {
+        self.eval_extension::<CubicExtension<AP::Field, E>, AP>(parser)
+    }
+}
+
+impl<T: Register> LogEntry<T> {
+    #[inline]
+    pub fn next(&self) -> Self {
         match self {
-            LogEntry::Input(value) => {
-                let value = value.eval_cubic(parser);
-                let multiplier = parser.one();
-                LogEntryValue { value, multiplier }
+            LogEntry::Input(value) => LogEntry::Input(value.next()),
+            LogEntry::Output(value) => LogEntry::Output(value.next()),
+            LogEntry::InputMultiplicity(value, multiplier) => {
+                LogEntry::InputMultiplicity(value.next(), multiplier.next())
             }
-            LogEntry::Output(value) => {
-                let value = value.eval_cubic(parser);
-                let multiplier = parser.constant(-AP::Field::ONE);
-                LogEntryValue { value, multiplier }
+            LogEntry::OutputMultiplicity(value, multiplier) => {
+                LogEntry::OutputMultiplicity(value.next(), multiplier.next())
             }
+        }
+    }
+
+    /// Reads the entry from a row of the trace, with its value in the extension `X`.
+    #[inline]
+    pub fn read_from_slice<F: Field, X: ChallengeExtension<F>>(
+        &self,
+        slice: &[F],
+    ) -> LogEntryValue<F, X> {
+        let read = |value: &T| X::from_register_value::<T>(&value.read_from_slice(slice));
+        match self {
+            LogEntry::Input(value) => LogEntryValue {
+                value: read(value),
+                multiplier: F::ONE,
+            },
+            LogEntry::Output(value) => LogEntryValue {
+                value: read(value),
+                multiplier: -F::ONE,
+            },
             LogEntry::InputMultiplicity(value, multiplier) => {
-                let value = value.eval_cubic(parser);
-                let multiplier = multiplier.eval(parser);
+                let value = read(value);
+                let multiplier = multiplier.read_from_slice(slice);
                 LogEntryValue { value, multiplier }
             }
             LogEntry::OutputMultiplicity(value, multiplier) => {
-                let value = value.eval_cubic(parser);
-                let mut multiplier = multiplier.eval(parser);
-                multiplier = parser.neg(multiplier);
+                let value = read(value);
+                let multiplier = -multiplier.read_from_slice(slice);
                 LogEntryValue { value, multiplier }
             }
         }
     }
 
+    /// Evaluates the entry with its value in the extension `X`.
     #[inline]
-    pub fn read_from_slice<F: Field>(&self, slice: &[F]) -> LogEntryValue<F> {
+    pub fn eval_extension<X, AP: ExtensionParser<X>>(
+        &self,
+        parser: &mut AP,
+    ) -> LogEntryValue<AP::Var, AP::Element>
+    where
+        T: EvalExtension<X>,
+    {
         match self {
             LogEntry::Input(value) => {
-                let value = T::trace_value_as_cubic(value.read_from_slice(slice));
-                LogEntryValue {
-                    value,
-                    multiplier: F::ONE,
-                }
+                let value = value.eval_extension(parser);
+                let multiplier = parser.one();
+                LogEntryValue { value, multiplier }
             }
             LogEntry::Output(value) => {
-                let value = T::trace_value_as_cubic(value.read_from_slice(slice));
-                let multiplier = -F::ONE;
+                let value = value.eval_extension(parser);
+                let multiplier = parser.constant(-AP::Field::ONE);
                 LogEntryValue { value, multiplier }
             }
             LogEntry::InputMultiplicity(value, multiplier) => {
-                let value = T::trace_value_as_cubic(value.read_from_slice(slice));
-                let multiplier = multiplier.read_from_slice(slice);
+                let value = value.eval_extension(parser);
+                let multiplier = multiplier.eval(parser);
                 LogEntryValue { value, multiplier }
             }
             LogEntry::OutputMultiplicity(value, multiplier) => {
-                let value = T::trace_value_as_cubic(value.read_from_slice(slice));
-                let mut multiplier = multiplier.read_from_slice(slice);
-                multiplier = -multiplier;
+                let value = value.eval_extension(parser);
+                let mut multiplier = multiplier.eval(parser);
+                multiplier = parser.neg(multiplier);
                 LogEntryValue { value, multiplier }
             }
         }
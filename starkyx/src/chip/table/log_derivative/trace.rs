@@ -1,79 +1,80 @@
-use super::entry::LogEntry;
-use crate::chip::register::cubic::{CubicRegister, EvalCubic};
+use super::entry::{LogEntry, LogEntryValue};
+use crate::chip::register::extension::ChallengeExtension;
 use crate::chip::register::slice::RegisterSlice;
 use crate::chip::register::Register;
 use crate::chip::trace::writer::TraceWriter;
-use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
 use crate::maybe_rayon::*;
 
 impl<F: PrimeField> TraceWriter<F> {
-    pub fn write_log_trace_accumulation<T: EvalCubic, E: CubicParameters<F>>(
+    pub fn write_log_trace_accumulation<T: Register, X: ChallengeExtension<F>>(
         &self,
-        beta: CubicExtension<F, E>,
+        beta: X,
         entries: &[LogEntry<T>],
-        intermediate_values: &impl RegisterSlice<CubicRegister>,
-        trace_accumulator: CubicRegister,
-    ) -> CubicExtension<F, E> {
+        intermediate_values: &impl RegisterSlice<X::Register>,
+        trace_accumulator: X::Register,
+    ) -> X {
         // Accumulate lookup values in the trace
         let accumulators = self
             .write_trace()
             .unwrap()
             .rows_par_mut()
             .map(|row| {
-                let entry_chunks = entries.chunks_exact(2);
-                let last_element = entry_chunks
-                    .remainder()
-                    .first()
-                    .map(|reg| reg.read_from_slice(row).evaluate(beta))
-                    .unwrap_or(CubicExtension::ZERO);
-                let mut accumumulator = CubicExtension::ZERO;
+                let values = entries
+                    .iter()
+                    .map(|entry| entry.read_from_slice(row))
+                    .collect::<Vec<_>>();
+                let terms = LogEntryValue::evaluate_batch(&values, beta);
+                let term_chunks = terms.chunks_exact(2);
+                let last_element = term_chunks.remainder().first().copied().unwrap_or(X::ZERO);
+                let mut accumumulator = X::ZERO;
                 let accumulators = intermediate_values;
-                for (k, pair) in entry_chunks.enumerate() {
-                    let a = pair[0].read_from_slice(row);
-                    let b = pair[1].read_from_slice(row);
-                    accumumulator += a.evaluate(beta) + b.evaluate(beta);
+                for (k, pair) in term_chunks.enumerate() {
+                    accumumulator += pair[0] + pair[1];
                     accumulators
                         .get_value(k)
-                        .assign_to_raw_slice(row, &accumumulator.0);
+                        .assign_to_raw_slice(row, &accumumulator.register_value());
                 }
                 accumumulator + last_element
             })
             .collect::<Vec<_>>();
 
-        let mut value = CubicExtension::ZERO;
+        let mut value = X::ZERO;
         for (i, acc) in accumulators.into_iter().enumerate() {
             value += acc;
-            self.write(&trace_accumulator, &value.0, i);
+            self.write(&trace_accumulator, &value.register_value(), i);
         }
         // Write the local digest
-        self.write(&trace_accumulator, &value.0, self.height - 1);
+        self.write(&trace_accumulator, &value.register_value(), self.height - 1);
 
         value
     }
 
-    pub fn write_log_global_accumulation<T: EvalCubic, E: CubicParameters<F>>(
+    pub fn write_log_global_accumulation<T: Register, X: ChallengeExtension<F>>(
         &self,
-        beta: CubicExtension<F, E>,
+        beta: X,
         entries: &[LogEntry<T>],
-        intermediate_values: &impl RegisterSlice<CubicRegister>,
-        global_accumulator: CubicRegister,
-    ) -> CubicExtension<F, E> {
-        let value_chunks = entries.chunks_exact(2);
-        let last_element = value_chunks
-            .remainder()
-            .last()
-            .map(|reg| self.read_log_entry(reg, 0).evaluate(beta))
-            .unwrap_or(CubicExtension::ZERO);
-        let mut accumumulator = CubicExtension::ZERO;
-        for (k, pair) in value_chunks.enumerate() {
-            let a = self.read_log_entry(&pair[0], 0);
-            let b = self.read_log_entry(&pair[1], 0);
-            accumumulator += a.evaluate(beta) + b.evaluate(beta);
-            self.write(&intermediate_values.get_value(k), &accumumulator.0, 0);
+        intermediate_values: &impl RegisterSlice<X::Register>,
+        global_accumulator: X::Register,
+    ) -> X {
+        let values = entries
+            .iter()
+            .map(|entry| self.read_log_entry(entry, 0))
+            .collect::<Vec<_>>();
+        let terms = LogEntryValue::evaluate_batch(&values, beta);
+        let term_chunks = terms.chunks_exact(2);
+        let last_element = term_chunks.remainder().last().copied().unwrap_or(X::ZERO);
+        let mut accumumulator = X::ZERO;
+        for (k, pair) in term_chunks.enumerate() {
+            accumumulator += pair[0] + pair[1];
+            self.write(
+                &intermediate_values.get_value(k),
+                &accumumulator.register_value(),
+                0,
+            );
         }
         let value = accumumulator + last_element;
-        self.write(&global_accumulator, &value.0, 0);
+        self.write(&global_accumulator, &value.register_value(), 0);
 
         value
     }
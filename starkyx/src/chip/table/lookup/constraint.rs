@@ -2,31 +2,50 @@ use serde::{Deserialize, Serialize};
 
 use super::{LogLookupTable, LogLookupValues};
 use crate::air::extension::cubic::CubicParser;
+use crate::air::extension::ExtensionParser;
+use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::register::cubic::{CubicRegister, EvalCubic};
 use crate::chip::register::element::ElementRegister;
-use crate::chip::register::Register;
+use crate::chip::register::extension::{ChallengeExtension, EvalExtension};
+use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub enum LookupConstraint<T: EvalCubic, F: Field, E: CubicParameters<F>> {
-    Table(LogLookupTable<T, F, E>),
-    ValuesLocal(LogLookupValues<T, F, E>),
-    ValuesGlobal(LogLookupValues<T, F, E>),
-    ValuesDigest(CubicRegister, CubicRegister, Option<CubicRegister>),
-    Digest(CubicRegister, Vec<CubicRegister>),
+pub enum LookupConstraint<
+    T: EvalCubic,
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F> = CubicExtension<F, E>,
+> {
+    Table(LogLookupTable<T, F, E, X>),
+    ValuesLocal(LogLookupValues<T, F, E, X>),
+    ValuesGlobal(LogLookupValues<T, F, E, X>),
+    ValuesDigest(X::Register, X::Register, Option<X::Register>),
+    Digest(X::Register, Vec<X::Register>),
 }
 
+/// The constraints of a lookup, with the challenges of the lookups of element registers drawn
+/// from the extension `X`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub enum LookupChipConstraint<F: Field, E: CubicParameters<F>> {
-    Element(LookupConstraint<ElementRegister, F, E>),
+pub enum LookupChipConstraint<
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F> = CubicExtension<F, E>,
+> {
+    Element(LookupConstraint<ElementRegister, F, E, X>),
     CubicElement(LookupConstraint<CubicRegister, F, E>),
 }
 
-impl<T: EvalCubic, E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstraint<AP>
-    for LookupConstraint<T, AP::Field, E>
+impl<T, F, E, X, AP> AirConstraint<AP> for LookupConstraint<T, F, E, X>
+where
+    T: EvalCubic + EvalExtension<X>,
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F>,
+    AP: AirParser<Field = F> + ExtensionParser<X, Register = X::Register>,
 {
     fn eval(&self, parser: &mut AP) {
         match self {
@@ -34,10 +53,10 @@ impl<T: EvalCubic, E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstra
             LookupConstraint::ValuesLocal(values) => values.eval(parser),
             LookupConstraint::ValuesGlobal(values) => values.eval_global(parser),
             LookupConstraint::ValuesDigest(digest, local_digest, global_digest) => {
-                let digest = digest.eval(parser);
-                let local_digest = local_digest.eval(parser);
+                let digest = digest.eval_extension(parser);
+                let local_digest = local_digest.eval_extension(parser);
                 let global_digest = global_digest
-                    .map(|d| d.eval(parser))
+                    .map(|d| d.eval_extension(parser))
                     .unwrap_or_else(|| parser.zero_extension());
 
                 let mut digest_constraint = parser.add_extension(local_digest, global_digest);
@@ -45,10 +64,10 @@ impl<T: EvalCubic, E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstra
                 parser.constraint_extension_last_row(digest_constraint);
             }
             LookupConstraint::Digest(table_digest, element_digests) => {
-                let table = table_digest.eval_cubic(parser);
+                let table = table_digest.eval_extension(parser);
                 let elements = element_digests
                     .iter()
-                    .map(|b| b.eval_cubic(parser))
+                    .map(|b| b.eval_extension(parser))
                     .collect::<Vec<_>>();
                 let mut elem_sum = parser.zero_extension();
                 for e in elements {
@@ -61,8 +80,13 @@ impl<T: EvalCubic, E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstra
     }
 }
 
-impl<E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstraint<AP>
-    for LookupChipConstraint<AP::Field, E>
+impl<F, E, X, AP> AirConstraint<AP> for LookupChipConstraint<F, E, X>
+where
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F>,
+    AP: AirParser<Field = F> + CubicParser<E> + ExtensionParser<X, Register = X::Register>,
+    ElementRegister: EvalExtension<X>,
 {
     fn eval(&self, parser: &mut AP) {
         match self {
@@ -72,16 +96,16 @@ impl<E: CubicParameters<AP::Field>, AP: CubicParser<E>> AirConstraint<AP>
     }
 }
 
-impl<F: Field, E: CubicParameters<F>> From<LookupConstraint<ElementRegister, F, E>>
-    for LookupChipConstraint<F, E>
+impl<F: Field, E: CubicParameters<F>, X: ChallengeExtension<F>>
+    From<LookupConstraint<ElementRegister, F, E, X>> for LookupChipConstraint<F, E, X>
 {
-    fn from(constraint: LookupConstraint<ElementRegister, F, E>) -> Self {
+    fn from(constraint: LookupConstraint<ElementRegister, F, E, X>) -> Self {
         Self::Element(constraint)
     }
 }
 
-impl<F: Field, E: CubicParameters<F>> From<LookupConstraint<CubicRegister, F, E>>
-    for LookupChipConstraint<F, E>
+impl<F: Field, E: CubicParameters<F>, X: ChallengeExtension<F>>
+    From<LookupConstraint<CubicRegister, F, E>> for LookupChipConstraint<F, E, X>
 {
     fn from(constraint: LookupConstraint<CubicRegister, F, E>) -> Self {
         Self::CubicElement(constraint)
@@ -6,6 +6,8 @@ use self::table::LogLookupTable;
 use self::values::LogLookupValues;
 
 pub mod constraint;
+pub mod multi;
+pub mod range;
 pub mod table;
 pub mod trace;
 pub mod values;
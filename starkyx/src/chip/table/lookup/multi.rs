@@ -0,0 +1,254 @@
+//! Lookups of tuples of element registers into a table whose entries span several columns.
+//!
+//! Every entry of the table and the looked up tuple are folded into a single cubic element, the
+//! combination of their columns with the powers of a verifier challenge, and the folded values go
+//! through one log-derivative lookup. The number of extended columns of the lookup thus depends
+//! on the number of entries of the table, but not on their width.
+
+use alloc::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::table::LogLookupTable;
+use super::values::LogLookupValues;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::cubic::CubicRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::trace::writer::TraceWriter;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MultiLookupTable<F: Field, E: CubicParameters<F>> {
+    pub(crate) inputs: Vec<ElementRegister>,
+    pub(crate) table: Vec<ArrayRegister<ElementRegister>>,
+    pub(crate) lookup: LogLookupTable<CubicRegister, F, E>,
+    pub(crate) values: LogLookupValues<CubicRegister, F, E>,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Asserts that in every row of the trace, the tuple `inputs` is equal to one of the entries
+    /// of `table` in some row.
+    ///
+    /// Every entry of the table is a tuple of the same width as `inputs`, and the table consists
+    /// of the entries in all the rows of the trace. The multiplicities of the entries are written
+    /// with the range checks, once the execution trace has been written. A lookup takes a free
+    /// column for the multiplicity of every entry, and 9 extended columns plus 6 for every entry.
+    pub fn multi_lookup(
+        &mut self,
+        inputs: &[ElementRegister],
+        table: &[ArrayRegister<ElementRegister>],
+    ) {
+        assert!(!inputs.is_empty(), "Cannot look up an empty tuple");
+        assert!(!table.is_empty(), "The lookup table has no entries");
+        for entry in table.iter() {
+            assert_eq!(
+                entry.len(),
+                inputs.len(),
+                "The entries of the table must have the width of the inputs"
+            );
+            assert!(
+                entry.is_trace(),
+                "The entries of the table must be in the trace"
+            );
+        }
+        assert!(
+            inputs.iter().all(|input| input.is_trace()),
+            "The inputs of a lookup must be in the trace"
+        );
+
+        let challenges = self.challenge_powers(inputs.len());
+        let digests = table
+            .iter()
+            .map(|entry| self.accumulate(&challenges, &entry.iter().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let input_digest = self.accumulate(&challenges, inputs);
+
+        let multiplicities = self.alloc_array::<ElementRegister>(table.len());
+        let mut lookup = self.new_lookup(&digests, &multiplicities);
+        let values = lookup.register_lookup_values(self, &[input_digest]);
+        self.constrain_cubic_lookup_table(lookup.clone());
+
+        self.multi_lookups.push(MultiLookupTable {
+            inputs: inputs.to_vec(),
+            table: table.to_vec(),
+            lookup,
+            values,
+        });
+    }
+}
+
+impl<F: PrimeField64> TraceWriter<F> {
+    /// Writes the multiplicities of the entries of the table, assuming the table and the inputs
+    /// have been written.
+    ///
+    /// An entry appearing several times in the table is counted in its first occurrence. Inputs
+    /// not in the table are not counted, so their lookup fails to verify.
+    pub(crate) fn write_multi_lookup_table<E: CubicParameters<F>>(
+        &self,
+        multi_lookup: &MultiLookupTable<F, E>,
+    ) {
+        let num_entries = multi_lookup.table.len();
+        let table = multi_lookup
+            .table
+            .iter()
+            .map(|entry| entry.iter().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let read_tuple = |registers: &[ElementRegister], row: &[F]| {
+            registers
+                .iter()
+                .map(|register| register.read_from_slice(row).as_canonical_u64())
+                .collect::<Vec<_>>()
+        };
+
+        let trace = self.read_trace().unwrap();
+        let mut indices = BTreeMap::new();
+        for (i, row) in trace.rows().enumerate() {
+            for (j, entry) in table.iter().enumerate() {
+                indices
+                    .entry(read_tuple(entry, row))
+                    .or_insert(i * num_entries + j);
+            }
+        }
+
+        let mut multiplicities = vec![0u64; trace.height() * num_entries];
+        for row in trace.rows() {
+            let input = read_tuple(&multi_lookup.inputs, row);
+            if let Some(&index) = indices.get(&input) {
+                multiplicities[index] += 1;
+            }
+        }
+        drop(trace);
+
+        for (i, row_multiplicities) in multiplicities.chunks_exact(num_entries).enumerate() {
+            self.write_array(
+                &multi_lookup.lookup.multiplicities,
+                row_multiplicities.iter().map(|m| F::from_canonical_u64(*m)),
+                i,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use rand::Rng;
+
+    use super::*;
+    use crate::chip::trace::writer::InnerWriterData;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::machine::builder::Builder;
+    use crate::machine::bytes::builder::BytesBuilder;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct IdentityLookupTest;
+
+    impl AirParameters for IdentityLookupTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 6;
+        const EXTENDED_COLUMNS: usize = 24;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ArithmeticLookupTest;
+
+    impl AirParameters for ArithmeticLookupTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 12;
+        const EXTENDED_COLUMNS: usize = 30;
+    }
+
+    /// Looks up pairs into a table of the pairs `(i, i)` in the row `i`, with the input pair of
+    /// each row given by `input`.
+    fn prove_identity_lookup(input: impl Fn(usize) -> (u64, u64)) {
+        type L = IdentityLookupTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let table = builder.alloc_array::<ElementRegister>(2);
+        let inputs = builder.alloc_array::<ElementRegister>(2);
+        builder.set_to_expression_first_row(&table.get(0), F::ZERO.into());
+        builder.set_to_expression_transition(&table.get(0).next(), table.get(0).expr() + F::ONE);
+        builder.set_to_expression(&table.get(1), table.get(0).expr());
+        builder.multi_lookup(&inputs.iter().collect::<Vec<_>>(), &[table]);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&stark.air_data, i);
+            let (x, y) = input(i);
+            writer.write_array(&inputs, [x, y].map(F::from_canonical_u64), i);
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_identity_lookup", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_multi_lookup_identity() {
+        let values = (0..1 << 5)
+            .map(|_| rand::thread_rng().gen_range(0..1 << 5))
+            .collect::<Vec<u64>>();
+        prove_identity_lookup(|i| (values[i], values[i]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_multi_lookup_identity_missing_entry() {
+        // Both values are in the table, but not as an entry.
+        prove_identity_lookup(|i| (i as u64, (i as u64 + 1) % (1 << 5)));
+    }
+
+    #[test]
+    fn test_multi_lookup_arithmetic() {
+        type L = ArithmeticLookupTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        // The row `16 * b + a` holds the sum and the product of `a` and `b`, for `a, b < 16`.
+        let mut builder = BytesBuilder::<L>::new();
+        let sums = builder.alloc_array::<ElementRegister>(3);
+        let products = builder.alloc_array::<ElementRegister>(3);
+        let inputs = builder.alloc_array::<ElementRegister>(3);
+        builder.multi_lookup(&inputs.iter().collect::<Vec<_>>(), &[sums, products]);
+
+        let num_rows = 1 << 8;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let (a, b) = ((i % 16) as u64, (i / 16) as u64);
+            writer.write_array(&sums, [a, b, a + b].map(F::from_canonical_u64), i);
+            writer.write_array(&products, [a, b, a * b].map(F::from_canonical_u64), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            let (x, y) = (rng.gen_range(0..16u64), rng.gen_range(0..16u64));
+            let z = if i % 2 == 0 { x + y } else { x * y };
+            writer.write_array(&inputs, [x, y, z].map(F::from_canonical_u64), i);
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_multi_lookup_arithmetic", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+}
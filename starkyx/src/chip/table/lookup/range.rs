@@ -0,0 +1,152 @@
+//! Range checks of element registers against a table of all values of a given bit width.
+//!
+//! The table of a width `n` is a single trace column that starts at zero, increases by at most
+//! one from each row to the next, and ends at `2^n - 1`, so it takes every value of `0..2^n`. All
+//! the values checked against the same width share the table and its multiplicity column.
+//!
+//! With a base width set by `AirBuilder::set_range_check_base`, the trace values of the other
+//! widths are instead split into limbs of the base width, which are all checked against the table
+//! of the base width.
+
+use serde::{Deserialize, Serialize};
+
+use super::table::LogLookupTable;
+use super::values::LogLookupValues;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::extension::ChallengeExtension;
+use crate::chip::register::Register;
+use crate::chip::trace::writer::TraceWriter;
+use crate::math::prelude::cubic::extension::CubicExtension;
+use crate::math::prelude::*;
+
+/// The largest bit width of a range check, see `AirParameters` for the limits of the widths.
+pub const MAX_RANGE_CHECK_BITS: usize = 32;
+
+/// A range check table, whose lookup draws its challenge from the extension `X`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RangeCheckTable<
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F> = CubicExtension<F, E>,
+> {
+    pub(crate) bits: usize,
+    pub(crate) table: LogLookupTable<ElementRegister, F, E, X>,
+    pub(crate) values: LogLookupValues<ElementRegister, F, E, X>,
+    pub(crate) decompositions: Vec<RangeDecomposition>,
+}
+
+/// A trace value of a width other than the base width, checked by its little-endian limbs in the
+/// table of the base width.
+///
+/// The limbs are written with the table, and their values checked against it. The constraints
+/// recomposing the value from the limbs are registered by the builder.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct RangeDecomposition {
+    pub(crate) value: ElementRegister,
+    pub(crate) limbs: ArrayRegister<ElementRegister>,
+    /// When the top limb has fewer bits than the base width, the top limb shifted to the top of
+    /// the base width, and the shift. Checking both the limb and its shift bounds the limb by the
+    /// remaining bits.
+    pub(crate) shifted_top: Option<(ElementRegister, usize)>,
+}
+
+impl<F: Field, E: CubicParameters<F>, X: ChallengeExtension<F>> RangeCheckTable<F, E, X> {
+    /// The bit width of the values checked against the table.
+    pub const fn bits(&self) -> usize {
+        self.bits
+    }
+
+    /// The number of entries of the table.
+    pub const fn size(&self) -> usize {
+        1 << self.bits
+    }
+}
+
+impl<F: PrimeField64> TraceWriter<F> {
+    /// Writes the table column and its multiplicities, assuming the checked values have been
+    /// written.
+    ///
+    /// Values out of range are not counted, so the lookup of such a value fails to verify.
+    pub(crate) fn write_range_check_table<E: CubicParameters<F>, X: ChallengeExtension<F>>(
+        &self,
+        range_check: &RangeCheckTable<F, E, X>,
+    ) {
+        let num_rows = self.height;
+        let size = range_check.size();
+        assert!(
+            num_rows >= size,
+            "A range check of {} bits needs a trace of at least {} rows, got {}",
+            range_check.bits,
+            size,
+            num_rows
+        );
+
+        for decomposition in range_check.decompositions.iter() {
+            for i in 0..num_rows {
+                self.write_range_decomposition(decomposition, range_check.bits, i);
+            }
+        }
+
+        let table_column = range_check.table.table[0];
+        for i in 0..num_rows {
+            self.write(&table_column, &F::from_canonical_usize(i.min(size - 1)), i);
+        }
+
+        let mut multiplicities = vec![0u64; size];
+        let mut count = |value: F| {
+            let index = value.as_canonical_u64();
+            if index < size as u64 {
+                multiplicities[index as usize] += 1;
+            }
+        };
+
+        let trace = self.read_trace().unwrap();
+        for row in trace.rows() {
+            for entry in range_check.values.trace_values.iter() {
+                count(entry.value().read_from_slice(row));
+            }
+        }
+        drop(trace);
+
+        for entry in range_check.values.public_values.iter() {
+            count(self.read(entry.value(), 0));
+        }
+
+        let multiplicity = range_check.table.multiplicities.get(0);
+        for i in 0..num_rows {
+            let value = multiplicities.get(i).copied().unwrap_or(0);
+            self.write(&multiplicity, &F::from_canonical_u64(value), i);
+        }
+    }
+
+    /// Writes the limbs of base width `base_bits` of a decomposed value in a row.
+    ///
+    /// The bits of a value which is out of range are dropped, so that the recomposition of the
+    /// value fails to verify.
+    fn write_range_decomposition(
+        &self,
+        decomposition: &RangeDecomposition,
+        base_bits: usize,
+        row_index: usize,
+    ) {
+        let value = self
+            .read(&decomposition.value, row_index)
+            .as_canonical_u64();
+        let mask = (1 << base_bits) - 1;
+        for (j, limb) in decomposition.limbs.iter().enumerate() {
+            let limb_value = value.checked_shr((j * base_bits) as u32).unwrap_or(0) & mask;
+            self.write(&limb, &F::from_canonical_u64(limb_value), row_index);
+        }
+        if let Some((shifted, shift)) = decomposition.shifted_top {
+            let top = decomposition.limbs.get(decomposition.limbs.len() - 1);
+            let top_value = self.read(&top, row_index).as_canonical_u64();
+            self.write(
+                &shifted,
+                &F::from_canonical_u64(top_value << shift),
+                row_index,
+            );
+        }
+    }
+}
@@ -12,40 +12,60 @@ use crate::chip::constraint::Constraint;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::cubic::{CubicRegister, EvalCubic};
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::extension::ChallengeExtension;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::Register;
 use crate::chip::table::log_derivative::entry::LogEntry;
 use crate::chip::AirParameters;
+use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
 
+/// A lookup table, whose element entries are looked up with a challenge in the extension `X` and
+/// cubic entries with a challenge in the cubic extension.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub enum LookupTable<F, E> {
-    Element(LogLookupTable<ElementRegister, F, E>),
+pub enum LookupTable<
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F> = CubicExtension<F, E>,
+> {
+    Element(LogLookupTable<ElementRegister, F, E, X>),
     Cubic(LogLookupTable<CubicRegister, F, E>),
 }
 
+/// A log-derivative lookup table, with the challenge `beta` drawn from the extension `X`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub struct LogLookupTable<T: Register, F, E> {
-    pub(crate) challenge: CubicRegister,
+pub struct LogLookupTable<
+    T: Register,
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F> = CubicExtension<F, E>,
+> {
+    pub(crate) challenge: X::Register,
     pub(crate) table: Vec<T>,
     pub(crate) multiplicities: ArrayRegister<ElementRegister>,
-    pub(crate) multiplicities_table_log: ArrayRegister<CubicRegister>,
-    pub(crate) table_accumulator: CubicRegister,
-    pub(crate) digest: CubicRegister,
-    pub(crate) values_digests: Vec<CubicRegister>,
-    pub(crate) _marker: core::marker::PhantomData<(F, E)>,
+    pub(crate) multiplicities_table_log: ArrayRegister<X::Register>,
+    pub(crate) table_accumulator: X::Register,
+    pub(crate) digest: X::Register,
+    pub(crate) values_digests: Vec<X::Register>,
+    pub(crate) _marker: core::marker::PhantomData<(F, E, X)>,
 }
 
 impl<L: AirParameters> AirBuilder<L> {
-    pub fn new_lookup<T: EvalCubic>(
+    /// Creates a lookup table of the entries `table`, with the challenge drawn from the extension
+    /// `X`.
+    ///
+    /// A table of element registers is constrained by `constrain_element_lookup_table`, with `X`
+    /// the extension `L::LookupExtension`, and a table of cubic registers by
+    /// `constrain_cubic_lookup_table`, with `X` the cubic extension.
+    pub fn new_lookup<T: EvalCubic, X: ChallengeExtension<L::Field>>(
         &mut self,
         table: &[T],
         multiplicities: &ArrayRegister<ElementRegister>,
-    ) -> LogLookupTable<T, L::Field, L::CubicParams> {
+    ) -> LogLookupTable<T, L::Field, L::CubicParams, X> {
         let challenge = self.alloc_challenge();
-        let multiplicities_table_log = self.alloc_array_extended::<CubicRegister>(table.len());
+        let multiplicities_table_log = self.alloc_array_extended::<X::Register>(table.len());
         let table_accumulator = self.alloc_extended();
         let digest = self.alloc_global();
 
@@ -63,14 +83,14 @@ impl<L: AirParameters> AirBuilder<L> {
 
     pub fn constrain_element_lookup_table(
         &mut self,
-        table: LogLookupTable<ElementRegister, L::Field, L::CubicParams>,
+        table: LogLookupTable<ElementRegister, L::Field, L::CubicParams, L::LookupExtension>,
     ) {
         // insert the table to the builder
         self.lookup_tables.push(LookupTable::Element(table.clone()));
 
         // Register digest constraints between the table and the lookup values.
         self.global_constraints.push(Constraint::lookup(
-            LookupConstraint::<ElementRegister, _, _>::Digest(
+            LookupConstraint::<ElementRegister, _, _, _>::Digest(
                 table.digest,
                 table.values_digests.clone(),
             )
@@ -104,33 +124,48 @@ impl<L: AirParameters> AirBuilder<L> {
     }
 }
 
-impl<T: EvalCubic, F: Field, E: CubicParameters<F>> LogLookupTable<T, F, E> {
+impl<T: EvalCubic, F: Field, E: CubicParameters<F>, X: ChallengeExtension<F>>
+    LogLookupTable<T, F, E, X>
+{
     pub(crate) fn new_lookup_values<L: AirParameters<Field = F, CubicParams = E>>(
         &mut self,
         builder: &mut AirBuilder<L>,
         values: &[T],
-    ) -> LogLookupValues<T, F, E> {
+    ) -> LogLookupValues<T, F, E, X> {
+        let entries = values
+            .iter()
+            .map(|value| LogEntry::input(*value))
+            .collect::<Vec<_>>();
+        self.new_lookup_entries(builder, &entries)
+    }
+
+    /// Creates the lookup of the entries `entries`, each of which is looked up as many times as
+    /// its multiplier.
+    pub(crate) fn new_lookup_entries<L: AirParameters<Field = F, CubicParams = E>>(
+        &mut self,
+        builder: &mut AirBuilder<L>,
+        entries: &[LogEntry<T>],
+    ) -> LogLookupValues<T, F, E, X> {
         let mut trace_values = Vec::new();
         let mut public_values = Vec::new();
 
-        for value in values.iter() {
-            match value.register() {
-                MemorySlice::Public(..) => public_values.push(LogEntry::input(*value)),
-                MemorySlice::Local(..) => trace_values.push(LogEntry::input(*value)),
+        for entry in entries.iter() {
+            match entry.value().register() {
+                MemorySlice::Public(..) => public_values.push(entry.clone()),
+                MemorySlice::Local(..) => trace_values.push(entry.clone()),
                 MemorySlice::Next(..) => unreachable!("Next register not supported for lookup"),
-                MemorySlice::Global(..) => public_values.push(LogEntry::input(*value)),
+                MemorySlice::Global(..) => public_values.push(entry.clone()),
                 MemorySlice::Challenge(..) => unreachable!("Cannot lookup challenge register"),
             }
         }
 
-        let row_accumulators =
-            builder.alloc_array_extended::<CubicRegister>(trace_values.len() / 2);
+        let row_accumulators = builder.alloc_array_extended::<X::Register>(trace_values.len() / 2);
         let global_accumulators =
-            builder.alloc_array_global::<CubicRegister>(public_values.len() / 2);
-        let local_digest = builder.alloc_extended::<CubicRegister>();
+            builder.alloc_array_global::<X::Register>(public_values.len() / 2);
+        let local_digest = builder.alloc_extended::<X::Register>();
 
-        let digest = builder.alloc_global::<CubicRegister>();
-        let global_digest = Some(builder.alloc_global::<CubicRegister>());
+        let digest = builder.alloc_global::<X::Register>();
+        let global_digest = Some(builder.alloc_global::<X::Register>());
 
         self.values_digests.push(digest);
 
@@ -148,12 +183,16 @@ impl<T: EvalCubic, F: Field, E: CubicParameters<F>> LogLookupTable<T, F, E> {
     }
 }
 
-impl<F: Field, E: CubicParameters<F>> LogLookupTable<ElementRegister, F, E> {
-    pub fn register_lookup_values<L: AirParameters<Field = F, CubicParams = E>>(
+impl<F: Field, E: CubicParameters<F>, X: ChallengeExtension<F>>
+    LogLookupTable<ElementRegister, F, E, X>
+{
+    pub fn register_lookup_values<
+        L: AirParameters<Field = F, CubicParams = E, LookupExtension = X>,
+    >(
         &mut self,
         builder: &mut AirBuilder<L>,
         values: &[ElementRegister],
-    ) -> LogLookupValues<ElementRegister, F, E> {
+    ) -> LogLookupValues<ElementRegister, F, E, X> {
         let lookup_values = self.new_lookup_values(builder, values);
         lookup_values.register_constraints(builder);
         builder
@@ -176,4 +215,19 @@ impl<F: Field, E: CubicParameters<F>> LogLookupTable<CubicRegister, F, E> {
             .push(LookupValues::Cubic(lookup_values.clone()));
         lookup_values
     }
+
+    /// Registers the lookup of `entries`, where an entry with a multiplier is looked up as many
+    /// times as the value of the multiplier in its row.
+    pub fn register_lookup_entries<L: AirParameters<Field = F, CubicParams = E>>(
+        &mut self,
+        builder: &mut AirBuilder<L>,
+        entries: &[LogEntry<CubicRegister>],
+    ) -> LogLookupValues<CubicRegister, F, E> {
+        let lookup_values = self.new_lookup_entries(builder, entries);
+        lookup_values.register_constraints(builder);
+        builder
+            .lookup_values
+            .push(LookupValues::Cubic(lookup_values.clone()));
+        lookup_values
+    }
 }
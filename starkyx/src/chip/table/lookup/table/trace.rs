@@ -2,19 +2,23 @@ use itertools::Itertools;
 
 use super::{LogLookupTable, LookupTable};
 use crate::chip::register::cubic::EvalCubic;
+use crate::chip::register::extension::ChallengeExtension;
 use crate::chip::register::Register;
 use crate::chip::trace::writer::TraceWriter;
-use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
 use crate::maybe_rayon::*;
 
 impl<F: PrimeField> TraceWriter<F> {
     /// Writes the table lookups and accumulate assumes multiplicities have been written
-    pub(crate) fn write_log_lookup_table<T: EvalCubic, E: CubicParameters<F>>(
+    pub(crate) fn write_log_lookup_table<
+        T: EvalCubic,
+        E: CubicParameters<F>,
+        X: ChallengeExtension<F>,
+    >(
         &self,
-        table_data: &LogLookupTable<T, F, E>,
-    ) -> Vec<CubicExtension<F, E>> {
-        let beta = CubicExtension::<F, E>::from(self.read(&table_data.challenge, 0));
+        table_data: &LogLookupTable<T, F, E, X>,
+    ) -> Vec<X> {
+        let beta = X::from_register_value::<X::Register>(&self.read(&table_data.challenge, 0));
         assert_eq!(
             table_data.table.len(),
             table_data.multiplicities_table_log.len()
@@ -25,19 +29,26 @@ impl<F: PrimeField> TraceWriter<F> {
             .unwrap()
             .rows_par_mut()
             .map(|row| {
-                let mut sum = CubicExtension::ZERO;
-                for ((table, multiplicity), table_log_register) in table_data
+                // Invert the denominators `beta - table` of the row in a single batch.
+                let mut table_inverses = table_data
                     .table
                     .iter()
+                    .map(|table| {
+                        let table_val = table.read_from_slice(row);
+                        beta - X::from_register_value::<T>(&table_val)
+                    })
+                    .collect::<Vec<_>>();
+                X::batch_inverse(&mut table_inverses);
+
+                let mut sum = X::ZERO;
+                for ((table_inverse, multiplicity), table_log_register) in table_inverses
+                    .into_iter()
                     .zip_eq(table_data.multiplicities.iter())
                     .zip_eq(table_data.multiplicities_table_log.iter())
                 {
-                    let table_val = table.read_from_slice(row);
                     let mult_val = multiplicity.read_from_slice(row);
-                    let table = CubicExtension::from(T::trace_value_as_cubic(table_val));
-                    let mult = CubicExtension::from(mult_val);
-                    let table_log = mult / (beta - table);
-                    table_log_register.assign_to_raw_slice(row, &table_log.0);
+                    let table_log = table_inverse * mult_val;
+                    table_log_register.assign_to_raw_slice(row, &table_log.register_value());
                     sum += table_log;
                 }
                 sum
@@ -45,19 +56,48 @@ impl<F: PrimeField> TraceWriter<F> {
             .collect::<Vec<_>>();
 
         // Write accumulation
-        let mut acc = CubicExtension::ZERO;
+        let mut acc = X::ZERO;
         for (i, mult_table) in mult_table_log_entries.iter().enumerate() {
             acc += *mult_table;
-            self.write(&table_data.table_accumulator, &acc.0, i);
+            self.write(&table_data.table_accumulator, &acc.register_value(), i);
         }
 
         // Write the digest value
-        self.write(&table_data.digest, &acc.0, self.height - 1);
+        self.write(&table_data.digest, &acc.register_value(), self.height - 1);
 
         mult_table_log_entries
     }
 
-    pub(crate) fn write_lookup_table<E: CubicParameters<F>>(&self, table_data: &LookupTable<F, E>) {
+    /// Exports the entries of a lookup table and their multiplicities.
+    ///
+    /// The entries are listed row by row, and within a row in the order of the table columns, so
+    /// that an external tool can independently verify the log-derivative sum of the lookup. The
+    /// multiplicities must already be written to the trace.
+    pub fn export_lookup<T: EvalCubic, E: CubicParameters<F>, X: ChallengeExtension<F>>(
+        &self,
+        table_data: &LogLookupTable<T, F, E, X>,
+    ) -> (Vec<T::Value<F>>, Vec<F>) {
+        let trace = self.read_trace().unwrap();
+        let num_entries = trace.height() * table_data.table.len();
+        let mut entries = Vec::with_capacity(num_entries);
+        let mut multiplicities = Vec::with_capacity(num_entries);
+        for row in trace.rows() {
+            for (table, multiplicity) in table_data
+                .table
+                .iter()
+                .zip_eq(table_data.multiplicities.iter())
+            {
+                entries.push(table.read_from_slice(row));
+                multiplicities.push(multiplicity.read_from_slice(row));
+            }
+        }
+        (entries, multiplicities)
+    }
+
+    pub(crate) fn write_lookup_table<E: CubicParameters<F>, X: ChallengeExtension<F>>(
+        &self,
+        table_data: &LookupTable<F, E, X>,
+    ) {
         match table_data {
             LookupTable::Element(table) => {
                 self.write_log_lookup_table(table);
@@ -68,3 +108,76 @@ impl<F: PrimeField> TraceWriter<F> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::memory::MemorySlice;
+    use crate::chip::AirParameters;
+    use crate::trace::generator::TraceGenerator;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ExportLookupTest;
+
+    impl AirParameters for ExportLookupTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 1;
+        const NUM_FREE_COLUMNS: usize = 2;
+        const EXTENDED_COLUMNS: usize = 9;
+    }
+
+    #[test]
+    fn test_export_range_check_lookup() {
+        type F = GoldilocksField;
+        type L = ExportLookupTest;
+
+        let builder = AirBuilder::<L>::new();
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        // Fill the range checked column with random values in the range of the table.
+        let mut rng = rand::thread_rng();
+        let checked_values = (0..num_rows)
+            .map(|_| rng.gen_range(0..num_rows))
+            .collect::<Vec<_>>();
+        for (i, value) in checked_values.iter().enumerate() {
+            writer.write_unsafe_raw(
+                MemorySlice::Local(0, 1),
+                &[F::from_canonical_usize(*value)],
+                i,
+            );
+        }
+        generator
+            .generate_round(&air, 0, &[], &mut [], &[])
+            .unwrap();
+
+        let table = match &generator.air_data.range_data {
+            Some((LookupTable::Element(table), _)) => table,
+            _ => panic!("Expected an element range check table"),
+        };
+        let (entries, multiplicities) = writer.export_lookup(table);
+
+        assert_eq!(entries.len(), num_rows);
+        let total = multiplicities
+            .iter()
+            .map(|m| m.as_canonical_u64() as usize)
+            .sum::<usize>();
+        assert_eq!(total, num_rows);
+        for (entry, multiplicity) in entries.iter().zip_eq(multiplicities.iter()) {
+            let value = entry.as_canonical_u64() as usize;
+            let count = checked_values.iter().filter(|v| **v == value).count();
+            assert_eq!(*multiplicity, F::from_canonical_usize(count));
+        }
+    }
+}
@@ -2,6 +2,7 @@ use super::LogLookupTable;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::cubic::EvalCubic;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::extension::ChallengeExtension;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::table::log_derivative::entry::LogEntry;
 use crate::chip::trace::writer::TraceWriter;
@@ -10,10 +11,14 @@ use crate::maybe_rayon::*;
 use crate::trace::AirTrace;
 
 impl<F: PrimeField> TraceWriter<F> {
-    pub fn write_multiplicities_from_fn<E: CubicParameters<F>, T: Register>(
+    pub fn write_multiplicities_from_fn<
+        E: CubicParameters<F>,
+        X: ChallengeExtension<F>,
+        T: Register,
+    >(
         &self,
         num_rows: usize,
-        table_data: &LogLookupTable<T, F, E>,
+        table_data: &LogLookupTable<T, F, E, X>,
         table_index: impl Fn(T::Value<F>) -> usize,
         trace_values: &[T],
         public_values: &[T],
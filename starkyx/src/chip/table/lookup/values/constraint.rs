@@ -1,25 +1,28 @@
 use super::LogLookupValues;
-use crate::air::extension::cubic::CubicParser;
+use crate::air::extension::ExtensionParser;
 use crate::air::parser::AirParser;
 use crate::chip::builder::AirBuilder;
 use crate::chip::constraint::Constraint;
 use crate::chip::register::cubic::{CubicRegister, EvalCubic};
 use crate::chip::register::element::ElementRegister;
-use crate::chip::register::Register;
+use crate::chip::register::extension::{ChallengeExtension, EvalExtension};
 use crate::chip::table::log_derivative::constraints::LogConstraints;
 use crate::chip::table::lookup::constraint::LookupConstraint;
 use crate::chip::AirParameters;
 use crate::math::prelude::*;
 
-impl<T: EvalCubic, F: Field, E: CubicParameters<F>> LogLookupValues<T, F, E> {
+impl<T: EvalCubic, F: Field, E: CubicParameters<F>, X: ChallengeExtension<F>>
+    LogLookupValues<T, F, E, X>
+{
     pub(crate) fn eval<AP>(&self, parser: &mut AP)
     where
-        AP: CubicParser<E>,
+        T: EvalExtension<X>,
+        AP: ExtensionParser<X, Register = X::Register>,
         AP: AirParser<Field = F>,
     {
-        let beta = self.challenge.eval(parser);
+        let beta = self.challenge.eval_extension(parser);
 
-        LogConstraints::log_trace_accumulation(
+        LogConstraints::<AP, X>::log_trace_accumulation(
             parser,
             beta,
             &self.trace_values,
@@ -30,13 +33,14 @@ impl<T: EvalCubic, F: Field, E: CubicParameters<F>> LogLookupValues<T, F, E> {
 
     pub(crate) fn eval_global<AP>(&self, parser: &mut AP)
     where
-        AP: CubicParser<E>,
+        T: EvalExtension<X>,
+        AP: ExtensionParser<X, Register = X::Register>,
         AP: AirParser<Field = F>,
     {
-        let beta = self.challenge.eval(parser);
+        let beta = self.challenge.eval_extension(parser);
 
         if let Some(digest) = self.global_digest {
-            LogConstraints::log_global_accumulation(
+            LogConstraints::<AP, X>::log_global_accumulation(
                 parser,
                 beta,
                 &self.public_values,
@@ -47,24 +51,28 @@ impl<T: EvalCubic, F: Field, E: CubicParameters<F>> LogLookupValues<T, F, E> {
     }
 }
 
-impl<F: Field, E: CubicParameters<F>> LogLookupValues<ElementRegister, F, E> {
-    pub(crate) fn register_constraints<L: AirParameters<Field = F, CubicParams = E>>(
+impl<F: Field, E: CubicParameters<F>, X: ChallengeExtension<F>>
+    LogLookupValues<ElementRegister, F, E, X>
+{
+    pub(crate) fn register_constraints<
+        L: AirParameters<Field = F, CubicParams = E, LookupExtension = X>,
+    >(
         &self,
         builder: &mut AirBuilder<L>,
     ) {
         // Register the constraints on the trace values.
         builder.constraints.push(Constraint::lookup(
-            LookupConstraint::<ElementRegister, _, _>::ValuesLocal(self.clone()).into(),
+            LookupConstraint::<ElementRegister, _, _, _>::ValuesLocal(self.clone()).into(),
         ));
         // If global values are present, register the constraints on the global values.
         if self.global_digest.is_some() {
             builder.global_constraints.push(Constraint::lookup(
-                LookupConstraint::<ElementRegister, _, _>::ValuesGlobal(self.clone()).into(),
+                LookupConstraint::<ElementRegister, _, _, _>::ValuesGlobal(self.clone()).into(),
             ));
         }
         // Register the constraints on the digest.
         builder.constraints.push(Constraint::lookup(
-            LookupConstraint::<ElementRegister, _, _>::ValuesDigest(
+            LookupConstraint::<ElementRegister, _, _, _>::ValuesDigest(
                 self.digest,
                 self.local_digest,
                 self.global_digest,
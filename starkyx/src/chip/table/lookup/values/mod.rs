@@ -6,26 +6,38 @@ use serde::{Deserialize, Serialize};
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::cubic::{CubicRegister, EvalCubic};
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::extension::ChallengeExtension;
 use crate::chip::table::log_derivative::entry::LogEntry;
+use crate::math::prelude::cubic::extension::CubicExtension;
+use crate::math::prelude::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub enum LookupValues<F, E> {
-    Element(LogLookupValues<ElementRegister, F, E>),
+pub enum LookupValues<
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F> = CubicExtension<F, E>,
+> {
+    Element(LogLookupValues<ElementRegister, F, E, X>),
     Cubic(LogLookupValues<CubicRegister, F, E>),
 }
 
 /// Currently, only supports an even number of values
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
-pub struct LogLookupValues<T: EvalCubic, F, E> {
-    pub(crate) challenge: CubicRegister,
+pub struct LogLookupValues<
+    T: EvalCubic,
+    F: Field,
+    E: CubicParameters<F>,
+    X: ChallengeExtension<F> = CubicExtension<F, E>,
+> {
+    pub(crate) challenge: X::Register,
     pub(crate) trace_values: Vec<LogEntry<T>>,
     pub(crate) public_values: Vec<LogEntry<T>>,
-    pub(crate) row_accumulators: ArrayRegister<CubicRegister>,
-    pub(crate) global_accumulators: ArrayRegister<CubicRegister>,
-    pub local_digest: CubicRegister,
-    pub global_digest: Option<CubicRegister>,
-    pub digest: CubicRegister,
-    pub(crate) _marker: core::marker::PhantomData<(F, E)>,
+    pub(crate) row_accumulators: ArrayRegister<X::Register>,
+    pub(crate) global_accumulators: ArrayRegister<X::Register>,
+    pub local_digest: X::Register,
+    pub global_digest: Option<X::Register>,
+    pub digest: X::Register,
+    pub(crate) _marker: core::marker::PhantomData<(F, E, X)>,
 }
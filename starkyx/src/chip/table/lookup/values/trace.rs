@@ -1,15 +1,19 @@
 use super::{LogLookupValues, LookupValues};
 use crate::chip::register::cubic::EvalCubic;
+use crate::chip::register::extension::ChallengeExtension;
 use crate::chip::trace::writer::TraceWriter;
-use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
 
 impl<F: PrimeField> TraceWriter<F> {
-    pub(crate) fn write_log_lookup_values<T: EvalCubic, E: CubicParameters<F>>(
+    pub(crate) fn write_log_lookup_values<
+        T: EvalCubic,
+        E: CubicParameters<F>,
+        X: ChallengeExtension<F>,
+    >(
         &self,
-        values_data: &LogLookupValues<T, F, E>,
+        values_data: &LogLookupValues<T, F, E, X>,
     ) {
-        let beta = CubicExtension::<F, E>::from(self.read(&values_data.challenge, 0));
+        let beta = X::from_register_value::<X::Register>(&self.read(&values_data.challenge, 0));
 
         // Accumulate lookup values in the trace
         let trace_accumulated_value = self.write_log_trace_accumulation(
@@ -28,18 +32,22 @@ impl<F: PrimeField> TraceWriter<F> {
                 global_digest,
             )
         } else {
-            CubicExtension::ZERO
+            X::ZERO
         };
 
         let value = trace_accumulated_value + global_accumulated_value;
 
         // Write the total digest value
-        self.write(&values_data.digest, &value.0, self.height - 1);
+        self.write(
+            &values_data.digest,
+            &value.register_value(),
+            self.height - 1,
+        );
     }
 
-    pub(crate) fn write_lookup_values<E: CubicParameters<F>>(
+    pub(crate) fn write_lookup_values<E: CubicParameters<F>, X: ChallengeExtension<F>>(
         &self,
-        values_data: &LookupValues<F, E>,
+        values_data: &LookupValues<F, E, X>,
     ) {
         match values_data {
             LookupValues::Element(values) => {
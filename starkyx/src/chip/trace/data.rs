@@ -7,6 +7,8 @@ use crate::chip::register::cubic::CubicRegister;
 use crate::chip::table::accumulator::Accumulator;
 use crate::chip::table::bus::channel::BusChannel;
 use crate::chip::table::bus::global::Bus;
+use crate::chip::table::lookup::multi::MultiLookupTable;
+use crate::chip::table::lookup::range::RangeCheckTable;
 use crate::chip::table::lookup::table::LookupTable;
 use crate::chip::table::lookup::values::LookupValues;
 use crate::chip::table::powers::Powers;
@@ -27,12 +29,14 @@ pub struct AirTraceData<L: AirParameters> {
     pub pointer_global_accumulators: Vec<PointerAccumulator<L::Field, L::CubicParams>>,
     pub bus_channels: Vec<BusChannel<CubicRegister, L::CubicParams>>,
     pub buses: Vec<Bus<CubicRegister, L::CubicParams>>,
-    pub lookup_values: Vec<LookupValues<L::Field, L::CubicParams>>,
-    pub lookup_tables: Vec<LookupTable<L::Field, L::CubicParams>>,
+    pub lookup_values: Vec<LookupValues<L::Field, L::CubicParams, L::LookupExtension>>,
+    pub lookup_tables: Vec<LookupTable<L::Field, L::CubicParams, L::LookupExtension>>,
     pub range_data: Option<(
-        LookupTable<L::Field, L::CubicParams>,
-        LookupValues<L::Field, L::CubicParams>,
+        LookupTable<L::Field, L::CubicParams, L::LookupExtension>,
+        LookupValues<L::Field, L::CubicParams, L::LookupExtension>,
     )>,
+    pub range_checks: Vec<RangeCheckTable<L::Field, L::CubicParams, L::LookupExtension>>,
+    pub multi_lookups: Vec<MultiLookupTable<L::Field, L::CubicParams>>,
 }
 
 impl<L: AirParameters> AirTraceData<L> {
@@ -50,6 +54,32 @@ impl<L: AirParameters> AirTraceData<L> {
         }
     }
 
+    /// The number of rows of every cycle registered with `AirBuilder::cycle`, of every loop
+    /// registered with `AirBuilder::loop_instr`, and of every process counted by a process id.
+    pub fn cycle_lengths(&self) -> Vec<usize> {
+        self.instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                AirInstruction::Cycle(cycle) => Some(cycle.length()),
+                AirInstruction::Loop(loop_instr) => Some(loop_instr.num_iterations()),
+                AirInstruction::ProcessId(process_id) => Some(process_id.size()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Writes the tables and multiplicities of the range checks, and the multiplicities of the
+    /// multi-column lookups, once the checked values of the execution trace and the public inputs
+    /// have been written.
+    pub fn write_range_checks(&self, writer: &TraceWriter<L::Field>) {
+        for range_check in self.range_checks.iter() {
+            writer.write_range_check_table(range_check);
+        }
+        for multi_lookup in self.multi_lookups.iter() {
+            writer.write_multi_lookup_table(multi_lookup);
+        }
+    }
+
     pub fn write_extended_trace(&self, writer: &TraceWriter<L::Field>) {
         let num_rows = writer.read_trace().unwrap().height();
 
@@ -125,6 +125,7 @@ impl<L: AirParameters> TraceGenerator<L::Field, Chip<L>> for ArithmeticGenerator
                             .collect::<Vec<_>>(),
                     );
                 }
+                self.air_data.write_range_checks(&self.writer);
 
                 let trace = self.trace_clone();
                 let execution_trace_values = trace
@@ -8,21 +8,26 @@ use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
 use super::data::AirTraceData;
+use crate::air::debug::{ConstraintFailure, DebugParser};
+use crate::air::AirConstraint;
 use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::constraint::Constraint;
 use crate::chip::instruction::Instruction;
 use crate::chip::memory::map::MemoryMap;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::cubic::EvalCubic;
+use crate::chip::register::extension::ChallengeExtension;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable};
 use crate::chip::table::log_derivative::entry::{LogEntry, LogEntryValue};
-use crate::chip::AirParameters;
+use crate::chip::{AirParameters, Chip};
 use crate::math::prelude::*;
 use crate::trace::window::TraceWindow;
 use crate::trace::window_parser::TraceWindowParser;
 use crate::trace::AirTrace;
 
 pub mod data;
+pub mod parallel;
 pub mod public;
 pub mod row;
 pub mod window;
@@ -227,6 +232,19 @@ impl<T: PartialEq + Eq + Hash> TraceWriter<T> {
         self.0.public.read()
     }
 
+    /// Replaces the challenges of the writer by `values`, so that the extended trace of the
+    /// memory and lookup arguments can be written and checked without a prover.
+    ///
+    /// Challenges known before the trace is written make the arguments unsound, so this is only
+    /// meant for testing a witness numerically, as in `validate`.
+    pub fn with_fixed_challenges(self, values: &[T]) -> Self
+    where
+        T: Clone,
+    {
+        *self.0.challenges.write().unwrap() = values.to_vec();
+        self
+    }
+
     pub fn memory(&self) -> LockResult<RwLockReadGuard<'_, MemoryMap<T>>> {
         self.0.memory.read()
     }
@@ -237,6 +255,34 @@ impl<T: PartialEq + Eq + Hash> TraceWriter<T> {
 }
 
 impl<F: Field> TraceWriter<F> {
+    /// Writes the extended trace with the challenges of the writer and returns the constraints of
+    /// `air` that do not vanish on the whole trace, global values and public inputs.
+    ///
+    /// The execution trace and the public inputs must be written, and the challenges set by
+    /// `with_fixed_challenges`.
+    pub fn validate<L: AirParameters<Field = F>>(
+        &self,
+        air: &Chip<L>,
+        air_data: &AirTraceData<L>,
+    ) -> Vec<ConstraintFailure<F>>
+    where
+        Constraint<L>: for<'a> AirConstraint<DebugParser<'a, F>>,
+    {
+        let challenges = self.0.challenges.read().unwrap().clone();
+        assert_eq!(
+            challenges.len(),
+            air_data.num_challenges,
+            "the writer needs {} fixed challenges",
+            air_data.num_challenges
+        );
+        air_data.write_extended_trace(self);
+
+        let trace = self.read_trace().unwrap();
+        let global = self.global().unwrap();
+        let public = self.public().unwrap();
+        air.debug_constraints(&trace, &challenges, &global, &public)
+    }
+
     #[inline]
     pub fn read<R: Register>(&self, register: &R, row_index: usize) -> R::Value<F> {
         match register.register() {
@@ -318,14 +364,15 @@ impl<F: Field> TraceWriter<F> {
         expression.eval(&mut parser)
     }
 
-    /// Evaluates the log derivative entry `LogEntry` at the given row index.
+    /// Evaluates the log derivative entry `LogEntry` at the given row index, with its value in the
+    /// extension `X`.
     #[inline]
-    pub fn read_log_entry<T: EvalCubic>(
+    pub fn read_log_entry<T: Register, X: ChallengeExtension<F>>(
         &self,
         entry: &LogEntry<T>,
         row_index: usize,
-    ) -> LogEntryValue<F> {
-        let eval = |value: &T| T::trace_value_as_cubic(self.read(value, row_index));
+    ) -> LogEntryValue<F, X> {
+        let eval = |value: &T| X::from_register_value::<T>(&self.read(value, row_index));
         match entry {
             LogEntry::Input(value) => LogEntryValue {
                 value: eval(value),
@@ -0,0 +1,222 @@
+use super::window::WindowWriter;
+use super::TraceWriter;
+use crate::chip::memory::map::MemoryMap;
+use crate::chip::trace::data::AirTraceData;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+use crate::maybe_rayon::*;
+
+impl<F: Field> TraceWriter<F> {
+    /// Writes the instructions of `air_data` in every row, with the rows split into chunks of
+    /// `chunk_size` consecutive rows written in parallel.
+    ///
+    /// Every chunk is written in increasing row order by a single thread, with a scratch copy of
+    /// the memory of the writer, and the scratch memories are merged back in the order of the
+    /// chunks. The trace and the memory thus only depend on `chunk_size` and not on the number of
+    /// threads, and the timestamps read by the memory instructions are the ones of the rows.
+    ///
+    /// The result is the one of `write_row_instructions` in every row when the rows of a chunk
+    /// do not depend on the rows of other chunks. This is the case for:
+    ///  - instructions of a single row, such as byte operations, field and arithmetic
+    ///    instructions, the clock, the selectors of the cycles and loops, and the process ids.
+    ///  - transitions within a cycle, as long as the first row of every cycle is written from the
+    ///    row itself.
+    ///  - memory initialized before the call, by the global instructions, and memory written and
+    ///    read within the same chunk.
+    ///
+    /// A transition writing the next row is dropped in the last row of a chunk, and a memory
+    /// value written in one chunk is not seen by the next ones, so that chips carrying a state
+    /// through memory from row to row, like the compress state of BLAKE2b, must be written
+    /// serially. The instructions may only read the trace and the public values.
+    ///
+    /// # Panics
+    /// Panics if `chunk_size` does not divide the number of rows, or is not a multiple of the
+    /// length of every cycle, loop and process registered by the AIR, so that every chunk starts
+    /// a new cycle of each of them.
+    pub fn write_trace_rows_parallel<L: AirParameters<Field = F>>(
+        &self,
+        air_data: &AirTraceData<L>,
+        chunk_size: usize,
+    ) {
+        let height = self.height;
+        assert_eq!(
+            height % chunk_size,
+            0,
+            "The chunk size must divide the number of rows"
+        );
+        for length in air_data.cycle_lengths() {
+            assert_eq!(
+                chunk_size % length,
+                0,
+                "The chunk size must be a multiple of the cycle length {}",
+                length
+            );
+        }
+
+        let mut trace = self.0.trace.write().unwrap();
+        let public = self.0.public.read().unwrap();
+        let public_values: &[F] = &public;
+        let mut memory = self.0.memory.write().unwrap();
+
+        let initial_memory = memory.clone();
+        let chunk_memories = trace
+            .chunks_par_mut(chunk_size)
+            .enumerate()
+            .map(|(i, mut chunk)| {
+                let mut chunk_memory = initial_memory.clone();
+                for j in 0..chunk_size {
+                    let mut writer = WindowWriter::new(
+                        chunk.window_mut(j),
+                        public_values,
+                        &mut chunk_memory,
+                        i * chunk_size + j,
+                        height,
+                    );
+                    air_data.write_trace_instructions(&mut writer);
+                }
+                chunk_memory
+            })
+            .collect::<Vec<_>>();
+
+        *memory = MemoryMap::merge(&initial_memory, chunk_memories);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use rand::Rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::memory::time::Time;
+    use crate::chip::register::bit::BitRegister;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::trace::writer::InnerWriterData;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::register::U32Register;
+    use crate::chip::uint::util::u32_to_le_field_bytes;
+    use crate::machine::builder::Builder;
+    use crate::machine::bytes::builder::BytesBuilder;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ParallelWriterTest;
+
+    impl AirParameters for ParallelWriterTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 40;
+        const EXTENDED_COLUMNS: usize = 48;
+    }
+
+    #[test]
+    fn test_write_trace_rows_parallel() {
+        type F = GoldilocksField;
+        type L = ParallelWriterTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let num_rows = 1 << 8;
+
+        // A value read from memory in every row, byte operations, a cycle, a loop and a process
+        // id.
+        let mut builder = BytesBuilder::<L>::new();
+        let key = builder.alloc_public::<U32Register>();
+        let num_reads = builder.constant::<ElementRegister>(&F::from_canonical_usize(num_rows));
+        let key_ptr = builder.initialize(&key, &Time::zero(), Some(num_reads));
+        let a = builder.alloc::<U32Register>();
+        let key_value = builder.load(&key_ptr, &Time::zero(), None, None);
+        let masked = builder.xor(&a, &key_value);
+        let _ = builder.and(&masked, &a);
+        let cycle = builder.cycle(3);
+        let _ = builder.api.loop_instr(4);
+        let _ = builder.process_id(8, cycle.end_bit);
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let key_val = u32_to_le_field_bytes(rng.gen::<u32>());
+        let a_vals = (0..num_rows).map(|_| rng.gen::<u32>()).collect::<Vec<_>>();
+        let new_writer = || {
+            let writer = TraceWriter::new(&stark.air_data, num_rows);
+            writer.write(&key, &key_val, 0);
+            writer.write_global_instructions(&stark.air_data);
+            for (i, a_val) in a_vals.iter().enumerate() {
+                writer.write(&a, &u32_to_le_field_bytes(*a_val), i);
+            }
+            writer
+        };
+
+        let serial_writer = new_writer();
+        for i in 0..num_rows {
+            serial_writer.write_row_instructions(&stark.air_data, i);
+        }
+
+        for chunk_size in [1 << 3, 1 << 5, num_rows] {
+            let parallel_writer = new_writer();
+            parallel_writer.write_trace_rows_parallel(&stark.air_data, chunk_size);
+
+            assert_eq!(
+                parallel_writer.read_trace().unwrap().values,
+                serial_writer.read_trace().unwrap().values
+            );
+            assert_eq!(
+                *parallel_writer.memory().unwrap(),
+                *serial_writer.memory().unwrap()
+            );
+
+            let InnerWriterData { trace, public, .. } = parallel_writer.into_inner().unwrap();
+            let mut timing = TimingTree::new("test_write_trace_rows_parallel", log::Level::Debug);
+            let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+            stark.verify(proof, &public).unwrap();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "The chunk size must be a multiple of the cycle length 8")]
+    fn test_write_trace_rows_parallel_unaligned_chunks() {
+        type L = ParallelWriterTest;
+
+        let num_rows = 1 << 5;
+        let mut builder = AirBuilder::<L>::new();
+        let _ = builder.cycle(3);
+        let (_, trace_data) = builder.build();
+
+        let writer = TraceWriter::new(&trace_data, num_rows);
+        writer.write_trace_rows_parallel(&trace_data, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "The chunk size must be a multiple of the cycle length 3")]
+    fn test_write_trace_rows_parallel_unaligned_loop() {
+        type L = ParallelWriterTest;
+
+        let num_rows = 1 << 5;
+        let mut builder = AirBuilder::<L>::new();
+        let _ = builder.loop_instr(3);
+        let (_, trace_data) = builder.build();
+
+        let writer = TraceWriter::new(&trace_data, num_rows);
+        writer.write_trace_rows_parallel(&trace_data, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "The chunk size must be a multiple of the cycle length 8")]
+    fn test_write_trace_rows_parallel_unaligned_process() {
+        type L = ParallelWriterTest;
+
+        let num_rows = 1 << 5;
+        let mut builder = AirBuilder::<L>::new();
+        let end_bit = builder.alloc::<BitRegister>();
+        let _ = builder.process_id(8, end_bit);
+        let (_, trace_data) = builder.build();
+
+        let writer = TraceWriter::new(&trace_data, num_rows);
+        writer.write_trace_rows_parallel(&trace_data, 4);
+    }
+}
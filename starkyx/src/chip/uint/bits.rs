@@ -0,0 +1,260 @@
+//! Bit decompositions of byte array registers.
+//!
+//! A `BitDecomposition` keeps the `8 * N` bits of the value of a byte array register, with the
+//! constraints that the bits of every byte recompose to the byte. Rotations, shifts and bitwise
+//! operations of a decomposition set the bytes of their result directly from its bits, so any
+//! number of bit-level operations on the same value share the columns of one decomposition.
+
+use serde::{Deserialize, Serialize};
+
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::Register;
+use crate::chip::uint::bytes::decode::ByteDecodeInstruction;
+use crate::chip::uint::register::ByteArrayRegister;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// The bits of a byte array register, allocated by `AirBuilder::decompose_bits`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BitDecomposition<const N: usize> {
+    value: ByteArrayRegister<N>,
+    bits: ArrayRegister<BitRegister>,
+}
+
+impl<const N: usize> BitDecomposition<N> {
+    /// The decomposed value.
+    pub fn value(&self) -> ByteArrayRegister<N> {
+        self.value
+    }
+
+    /// The `8 * N` bits of the value, from the least significant one.
+    pub fn bits(&self) -> ArrayRegister<BitRegister> {
+        self.bits
+    }
+
+    /// The bit of the value of weight `2^i`.
+    pub fn bit(&self, i: usize) -> BitRegister {
+        self.bits.get(i)
+    }
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Decomposes `value` into its bits, with a decode instruction for every byte.
+    ///
+    /// A decomposition takes `8 * N` columns, and the operations on it only take the `N` columns
+    /// of their results.
+    pub fn decompose_bits<const N: usize>(
+        &mut self,
+        value: &ByteArrayRegister<N>,
+    ) -> BitDecomposition<N>
+    where
+        L::Instruction: From<ByteDecodeInstruction>,
+    {
+        let bits = self.alloc_array::<BitRegister>(8 * N);
+        for (i, byte) in value.to_le_bytes().iter().enumerate() {
+            self.decode_byte(&byte, &bits.get_subarray(8 * i..8 * (i + 1)));
+        }
+        BitDecomposition {
+            value: *value,
+            bits,
+        }
+    }
+
+    /// Returns a register whose bits, from the least significant one, are the values of `bits`.
+    fn compose_bits<const N: usize>(
+        &mut self,
+        bits: Vec<ArithmeticExpression<L::Field>>,
+    ) -> ByteArrayRegister<N> {
+        debug_assert_eq!(bits.len(), 8 * N);
+        let result = self.alloc::<ByteArrayRegister<N>>();
+        for (byte, byte_bits) in result.to_le_bytes().iter().zip(bits.chunks_exact(8)) {
+            let expression = byte_bits
+                .iter()
+                .enumerate()
+                .fold(ArithmeticExpression::zero(), |acc, (i, bit)| {
+                    acc + bit.clone() * L::Field::from_canonical_u32(1 << i)
+                });
+            self.set_to_expression(&byte, expression);
+        }
+        result
+    }
+
+    /// Returns the value of `a` rotated right by `rotation` bits.
+    pub fn decomposed_rotate_right<const N: usize>(
+        &mut self,
+        a: &BitDecomposition<N>,
+        rotation: usize,
+    ) -> ByteArrayRegister<N> {
+        let num_bits = 8 * N;
+        let bits = (0..num_bits)
+            .map(|i| a.bit((i + rotation) % num_bits).expr())
+            .collect();
+        self.compose_bits(bits)
+    }
+
+    /// Returns the value of `a` rotated left by `rotation` bits.
+    pub fn decomposed_rotate_left<const N: usize>(
+        &mut self,
+        a: &BitDecomposition<N>,
+        rotation: usize,
+    ) -> ByteArrayRegister<N> {
+        let num_bits = 8 * N;
+        self.decomposed_rotate_right(a, num_bits - rotation % num_bits)
+    }
+
+    /// Returns the value of `a` shifted right by `shift` bits, shifting in zeros.
+    pub fn decomposed_shr<const N: usize>(
+        &mut self,
+        a: &BitDecomposition<N>,
+        shift: usize,
+    ) -> ByteArrayRegister<N> {
+        let bits = (0..8 * N)
+            .map(|i| match i.checked_add(shift).filter(|j| *j < 8 * N) {
+                Some(j) => a.bit(j).expr(),
+                None => ArithmeticExpression::zero(),
+            })
+            .collect();
+        self.compose_bits(bits)
+    }
+
+    /// Returns the value of `a` shifted left by `shift` bits, shifting in zeros.
+    pub fn decomposed_shl<const N: usize>(
+        &mut self,
+        a: &BitDecomposition<N>,
+        shift: usize,
+    ) -> ByteArrayRegister<N> {
+        let bits = (0..8 * N)
+            .map(|i| match i.checked_sub(shift) {
+                Some(j) => a.bit(j).expr(),
+                None => ArithmeticExpression::zero(),
+            })
+            .collect();
+        self.compose_bits(bits)
+    }
+
+    /// Returns the bitwise AND of the values of `a` and `b`.
+    pub fn decomposed_and<const N: usize>(
+        &mut self,
+        a: &BitDecomposition<N>,
+        b: &BitDecomposition<N>,
+    ) -> ByteArrayRegister<N> {
+        let bits = (0..8 * N)
+            .map(|i| a.bit(i).expr() * b.bit(i).expr())
+            .collect();
+        self.compose_bits(bits)
+    }
+
+    /// Returns the bitwise OR of the values of `a` and `b`.
+    pub fn decomposed_or<const N: usize>(
+        &mut self,
+        a: &BitDecomposition<N>,
+        b: &BitDecomposition<N>,
+    ) -> ByteArrayRegister<N> {
+        let bits = (0..8 * N)
+            .map(|i| {
+                let (a, b) = (a.bit(i).expr(), b.bit(i).expr());
+                a.clone() + b.clone() - a * b
+            })
+            .collect();
+        self.compose_bits(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{thread_rng, Rng};
+
+    use super::*;
+    pub use crate::chip::builder::tests::*;
+    use crate::chip::uint::register::U64Register;
+    use crate::chip::uint::util::u64_to_le_field_bytes;
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    struct DecompositionTest;
+
+    impl AirParameters for DecompositionTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = ByteDecodeInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 192;
+    }
+
+    #[test]
+    fn test_bit_decomposition_operations() {
+        type L = DecompositionTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<U64Register>();
+        let b = builder.alloc::<U64Register>();
+        let a_bits = builder.decompose_bits(&a);
+        let b_bits = builder.decompose_bits(&b);
+
+        let rotate_right = builder.decomposed_rotate_right(&a_bits, 13);
+        let rotate_left = builder.decomposed_rotate_left(&a_bits, 21);
+        let shr = builder.decomposed_shr(&a_bits, 7);
+        let shl = builder.decomposed_shl(&b_bits, 9);
+        let and = builder.decomposed_and(&a_bits, &b_bits);
+        let or = builder.decomposed_or(&a_bits, &b_bits);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 6;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        let mut rng = thread_rng();
+        for i in 0..num_rows {
+            let (a_val, b_val) = (rng.gen::<u64>(), rng.gen::<u64>());
+            writer.write(&a, &u64_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u64_to_le_field_bytes(b_val), i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let expected = [
+                (rotate_right, a_val.rotate_right(13)),
+                (rotate_left, a_val.rotate_left(21)),
+                (shr, a_val >> 7),
+                (shl, b_val << 9),
+                (and, a_val & b_val),
+                (or, a_val | b_val),
+            ];
+            for (register, value) in expected {
+                assert_eq!(writer.read(&register, i), u64_to_le_field_bytes(value));
+            }
+        }
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_shared_bit_decomposition_columns() {
+        type L = DecompositionTest;
+
+        // A rotation and a shift of the same decomposition.
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<U64Register>();
+        let a_bits = builder.decompose_bits(&a);
+        builder.decomposed_rotate_right(&a_bits, 13);
+        builder.decomposed_shr(&a_bits, 7);
+        let shared = builder.column_budget().num_free_columns;
+
+        // The same operations, each on its own decomposition of the value.
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<U64Register>();
+        let a_bits = builder.decompose_bits(&a);
+        builder.decomposed_rotate_right(&a_bits, 13);
+        let a_bits = builder.decompose_bits(&a);
+        builder.decomposed_shr(&a_bits, 7);
+        let independent = builder.column_budget().num_free_columns;
+
+        assert!(shared < independent);
+        assert_eq!(independent - shared, 64);
+    }
+}
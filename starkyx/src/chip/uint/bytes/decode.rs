@@ -4,10 +4,11 @@ use super::register::ByteRegister;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
-use crate::chip::instruction::ConstraintInstruction;
+use crate::chip::instruction::Instruction;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
 use crate::chip::register::Register;
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
 use crate::chip::AirParameters;
 use crate::math::prelude::*;
 
@@ -48,7 +49,21 @@ impl<AP: AirParser> AirConstraint<AP> for ByteDecodeInstruction {
     }
 }
 
-impl ConstraintInstruction for ByteDecodeInstruction {}
+impl<F: PrimeField64> Instruction<F> for ByteDecodeInstruction {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let byte = writer.read(&self.byte, row_index).as_canonical_u64();
+        for (i, bit) in self.bits.iter().enumerate() {
+            writer.write(&bit, &F::from_canonical_u64((byte >> i) & 1), row_index);
+        }
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let byte = writer.read(&self.byte).as_canonical_u64();
+        for (i, bit) in self.bits.iter().enumerate() {
+            writer.write(&bit, &F::from_canonical_u64((byte >> i) & 1));
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -69,7 +69,9 @@ impl<L: AirParameters> AirBuilder<L> {
 
         let values = [trace_digest_values, public_digest_values].concat();
 
+        self.push_tag("byte_lookup");
         let _ = table.lookup.register_lookup_values(self, &values);
+        self.pop_tag();
 
         ByteMultiplicityData::new(
             table.multiplicity_data.clone(),
@@ -82,7 +84,9 @@ impl<L: AirParameters> AirBuilder<L> {
         &mut self,
         table: &ByteLogLookupTable<L::Field, L::CubicParams>,
     ) {
-        self.constrain_cubic_lookup_table(table.lookup.clone())
+        self.push_tag("byte_lookup_table");
+        self.constrain_cubic_lookup_table(table.lookup.clone());
+        self.pop_tag();
     }
 }
 
@@ -178,8 +182,8 @@ mod tests {
 
         type Instruction = ByteInstructionSet;
 
-        const NUM_FREE_COLUMNS: usize = 195;
-        const EXTENDED_COLUMNS: usize = 453;
+        const NUM_FREE_COLUMNS: usize = 197;
+        const EXTENDED_COLUMNS: usize = 459;
         const NUM_ARITHMETIC_COLUMNS: usize = 0;
     }
 
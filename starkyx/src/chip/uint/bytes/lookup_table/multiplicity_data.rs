@@ -8,8 +8,8 @@ use crate::chip::register::element::ElementRegister;
 use crate::chip::trace::writer::TraceWriter;
 use crate::chip::uint::bytes::operations::value::ByteOperation;
 use crate::chip::uint::bytes::operations::{
-    NUM_BIT_OPPS, OPCODE_AND, OPCODE_INDICES, OPCODE_NOT, OPCODE_RANGE, OPCODE_ROT, OPCODE_SHR,
-    OPCODE_SHR_CARRY, OPCODE_XOR,
+    NUM_BIT_OPPS, OPCODE_AND, OPCODE_INDICES, OPCODE_NOT, OPCODE_POPCOUNT, OPCODE_RANGE,
+    OPCODE_ROT, OPCODE_SHR, OPCODE_SHR_CARRY, OPCODE_XOR,
 };
 use crate::chip::uint::bytes::register::ByteRegister;
 use crate::math::prelude::*;
@@ -45,6 +45,7 @@ impl MultiplicityData {
                     OPCODE_ROT => ByteOperation::rot(a, b),
                     OPCODE_NOT => ByteOperation::not(a),
                     OPCODE_RANGE => ByteOperation::range(a),
+                    OPCODE_POPCOUNT => ByteOperation::popcount(a),
                     _ => unreachable!("Invalid opcode: {}", opcode),
                 };
                 operations_multipcitiy_dict.insert(operation, (row_index, op_index));
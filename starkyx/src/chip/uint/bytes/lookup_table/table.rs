@@ -13,8 +13,8 @@ use crate::chip::trace::writer::TraceWriter;
 use crate::chip::uint::bytes::decode::ByteDecodeInstruction;
 use crate::chip::uint::bytes::operations::value::ByteOperation;
 use crate::chip::uint::bytes::operations::{
-    OPCODE_AND, OPCODE_INDICES, OPCODE_NOT, OPCODE_RANGE, OPCODE_ROT, OPCODE_SHR, OPCODE_SHR_CARRY,
-    OPCODE_XOR,
+    OPCODE_AND, OPCODE_INDICES, OPCODE_NOT, OPCODE_POPCOUNT, OPCODE_RANGE, OPCODE_ROT, OPCODE_SHR,
+    OPCODE_SHR_CARRY, OPCODE_XOR,
 };
 use crate::chip::uint::bytes::register::ByteRegister;
 use crate::chip::AirParameters;
@@ -22,7 +22,8 @@ use crate::math::prelude::*;
 use crate::maybe_rayon::*;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ByteLogLookupTable<F, E> {
+#[serde(bound = "")]
+pub struct ByteLogLookupTable<F: Field, E: CubicParameters<F>> {
     pub challenges: ArrayRegister<CubicRegister>,
     pub a: ByteRegister,
     pub b: ByteRegister,
@@ -32,6 +33,7 @@ pub struct ByteLogLookupTable<F, E> {
     pub a_shr_carry_b: ByteRegister,
     pub a_rot_b: ByteRegister,
     pub a_not: ByteRegister,
+    pub a_popcount: ByteRegister,
     pub multiplicity_data: MultiplicityData,
     pub digests: Vec<CubicRegister>,
     pub lookup: LogLookupTable<CubicRegister, F, E>,
@@ -53,6 +55,7 @@ impl<L: AirParameters> AirBuilder<L> {
         let a_shr_carry_b = self.alloc::<ByteRegister>();
         let a_rot_b = self.alloc::<ByteRegister>();
         let a_not = self.alloc::<ByteRegister>();
+        let a_popcount = self.alloc::<ByteRegister>();
 
         let multiplicity_data = MultiplicityData::new(multiplicities);
 
@@ -70,6 +73,7 @@ impl<L: AirParameters> AirBuilder<L> {
                     OPCODE_ROT => ByteOperation::Rot(a, b, a_rot_b),
                     OPCODE_NOT => ByteOperation::Not(a, a_not),
                     OPCODE_RANGE => ByteOperation::Range(a),
+                    OPCODE_POPCOUNT => ByteOperation::Popcount(a, a_popcount),
                     _ => unreachable!("Invalid opcode: {}", op),
                 };
                 let values = operation.expressions();
@@ -89,6 +93,7 @@ impl<L: AirParameters> AirBuilder<L> {
             a_shr_carry_b,
             a_rot_b,
             a_not,
+            a_popcount,
             multiplicity_data,
             digests,
             lookup,
@@ -139,6 +144,10 @@ impl<F: PrimeField64, E: CubicParameters<F>> ByteLogLookupTable<F, E> {
                             // Write field value
                             self.a_rot_b.assign_to_raw_slice(row, &as_field(c));
                         }
+                        ByteOperation::Popcount(_, c) => {
+                            // Write field value
+                            self.a_popcount.assign_to_raw_slice(row, &as_field(c));
+                        }
                         ByteOperation::Range(_) => {}
                         _ => unreachable!("const parameter operations are not supported"),
                     }
@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 use super::{
-    OPCODE_AND, OPCODE_NOT, OPCODE_RANGE, OPCODE_ROT, OPCODE_SHR, OPCODE_SHR_CARRY, OPCODE_XOR,
+    OPCODE_AND, OPCODE_NOT, OPCODE_POPCOUNT, OPCODE_RANGE, OPCODE_ROT, OPCODE_SHR,
+    OPCODE_SHR_CARRY, OPCODE_XOR,
 };
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
@@ -28,6 +29,7 @@ pub enum ByteOperation<T> {
     Rot(T, T, T),
     Not(T, T),
     Range(T),
+    Popcount(T, T),
 }
 
 // TODO: Remove
@@ -120,6 +122,13 @@ impl ByteOperation<ByteRegister> {
                 F::ZERO.into(),
                 F::ZERO.into(),
             ],
+            ByteOperation::Popcount(a, res) => [
+                opcode.into(),
+                a.expr(),
+                F::ZERO.into(),
+                res.expr(),
+                F::ZERO.into(),
+            ],
         }
     }
     // pub fn lookup_digest_constraint<AP: AirParser>(
@@ -298,6 +307,12 @@ impl ByteOperation<ByteRegister> {
                 let a_val = from_field(writer.read(a, row_index));
                 ByteOperation::Range(a_val)
             }
+            ByteOperation::Popcount(a, b) => {
+                let a_val = from_field(writer.read(a, row_index));
+                let b_val = a_val.count_ones() as u8;
+                writer.write(b, &as_field(b_val), row_index);
+                ByteOperation::Popcount(a_val, b_val)
+            }
         }
     }
 
@@ -398,6 +413,12 @@ impl ByteOperation<ByteRegister> {
                 let a_val = from_field(writer.read(a));
                 ByteOperation::Range(a_val)
             }
+            ByteOperation::Popcount(a, b) => {
+                let a_val = from_field(writer.read(a));
+                let b_val = a_val.count_ones() as u8;
+                writer.write(b, &as_field(b_val));
+                ByteOperation::Popcount(a_val, b_val)
+            }
         }
     }
 
@@ -464,6 +485,11 @@ impl ByteOperation<ByteRegister> {
                 let a_val = from_field(writer.read(a, row_index));
                 ByteOperation::Range(a_val)
             }
+            ByteOperation::Popcount(a, b) => {
+                let a_val = from_field(writer.read(a, row_index));
+                let b_val = from_field(writer.read(b, row_index));
+                ByteOperation::Popcount(a_val, b_val)
+            }
         }
     }
 
@@ -526,6 +552,11 @@ impl ByteOperation<ByteRegister> {
                 let a_val = from_field(a.read_from_slice(slice));
                 ByteOperation::Range(a_val)
             }
+            ByteOperation::Popcount(a, b) => {
+                let a_val = from_field(a.read_from_slice(slice));
+                let b_val = from_field(b.read_from_slice(slice));
+                ByteOperation::Popcount(a_val, b_val)
+            }
         }
     }
 }
@@ -543,6 +574,7 @@ impl<T> ByteOperation<T> {
             ByteOperation::RotConst(_, _, _) => OPCODE_ROT,
             ByteOperation::Not(_, _) => OPCODE_NOT,
             ByteOperation::Range(_) => OPCODE_RANGE,
+            ByteOperation::Popcount(_, _) => OPCODE_POPCOUNT,
         }
     }
 
@@ -590,6 +622,10 @@ impl ByteOperation<u8> {
         ByteOperation::Range(a)
     }
 
+    pub fn popcount(a: u8) -> Self {
+        ByteOperation::Popcount(a, a.count_ones() as u8)
+    }
+
     pub fn lookup_digest_value(&self) -> u32 {
         let opcode = self.opcode();
         match self {
@@ -605,6 +641,7 @@ impl ByteOperation<u8> {
             ByteOperation::RotConst(a, b, c) => u32::from_le_bytes([opcode, *a, *b, *c]),
             ByteOperation::Not(a, b) => u32::from_le_bytes([opcode, *a, *b, 0]),
             ByteOperation::Range(a) => u32::from_le_bytes([opcode, *a, 0, 0]),
+            ByteOperation::Popcount(a, b) => u32::from_le_bytes([opcode, *a, *b, 0]),
             _ => unimplemented!(),
         }
     }
@@ -638,6 +675,7 @@ impl ByteOperation<u8> {
             }
             ByteOperation::Not(a, b) => ByteOperation::Not(as_field(a), as_field(b)),
             ByteOperation::Range(a) => ByteOperation::Range(as_field(a)),
+            ByteOperation::Popcount(a, b) => ByteOperation::Popcount(as_field(a), as_field(b)),
         }
     }
 
@@ -658,6 +696,9 @@ impl ByteOperation<u8> {
             }
             ByteOperation::Not(a, b) => ByteOperation::Not(as_field_bits(a), as_field_bits(b)),
             ByteOperation::Range(a) => ByteOperation::Range(as_field_bits(a)),
+            ByteOperation::Popcount(a, b) => {
+                ByteOperation::Popcount(as_field_bits(a), as_field_bits(b))
+            }
             _ => unreachable!("Const parameters operations cannot convert to field bits"),
         }
     }
@@ -724,6 +765,11 @@ impl<L: AirParameters> AirBuilder<L> {
                 let a = self.alloc_public::<ByteRegister>();
                 ByteOperation::Range(a)
             }
+            ByteOperation::Popcount(_, _) => {
+                let a = self.alloc_public::<ByteRegister>();
+                let result = self.alloc_public::<ByteRegister>();
+                ByteOperation::Popcount(a, result)
+            }
         }
     }
 }
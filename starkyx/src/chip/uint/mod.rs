@@ -1,3 +1,4 @@
+pub mod bits;
 pub mod bytes;
 pub mod operations;
 pub mod register;
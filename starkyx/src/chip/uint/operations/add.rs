@@ -75,6 +75,22 @@ impl<L: AirParameters> AirBuilder<L> {
         result
     }
 
+    /// Adds `a` and `b` and constrains the carry-out bit to be zero, i.e. that the addition does
+    /// not overflow.
+    pub fn add_checked_u32(
+        &mut self,
+        a: &U32Register,
+        b: &U32Register,
+        operations: &mut ByteLookupOperations,
+    ) -> U32Register
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let (result, out_carry) = self.carrying_add_u32(a, b, &None, operations);
+        self.assert_zero(&out_carry);
+        result
+    }
+
     pub fn set_add_u32(
         &mut self,
         a: &U32Register,
@@ -161,6 +177,22 @@ impl<L: AirParameters> AirBuilder<L> {
         let (result, _) = self.carrying_add_u64(a, b, &None, operations);
         result
     }
+
+    /// Adds `a` and `b` and constrains the carry-out bit to be zero, i.e. that the addition does
+    /// not overflow.
+    pub fn add_checked_u64(
+        &mut self,
+        a: &U64Register,
+        b: &U64Register,
+        operations: &mut ByteLookupOperations,
+    ) -> U64Register
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let (result, out_carry) = self.carrying_add_u64(a, b, &None, operations);
+        self.assert_zero(&out_carry);
+        result
+    }
 }
 
 impl<AP: AirParser, const N: usize> AirConstraint<AP> for ByteArrayAdd<N> {
@@ -244,3 +276,76 @@ impl<F: PrimeField64> Instruction<F> for ByteArrayAdd<4> {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::u64_to_le_field_bytes;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct AddCheckedTest;
+
+    impl AirParameters for AddCheckedTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 100;
+        const EXTENDED_COLUMNS: usize = 200;
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+    }
+
+    fn prove_add_checked_u64(a_val: u64, b_val: u64) {
+        type L = AddCheckedTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let mut builder = AirBuilder::<L>::new();
+        let mut operations = builder.byte_operations();
+
+        let a = builder.alloc::<U64Register>();
+        let b = builder.alloc::<U64Register>();
+        let sum = builder.add_checked_u64(&a, &b, &mut operations);
+
+        let mut byte_table = builder.new_byte_lookup_table();
+        let byte_data = builder.register_byte_lookup(&mut byte_table, operations);
+        builder.constraint_byte_lookup_table(&byte_table);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 16;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+
+        byte_table.write_table_entries(&writer);
+        for i in 0..num_rows {
+            writer.write(&a, &u64_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u64_to_le_field_bytes(b_val), i);
+            writer.write_row_instructions(&generator.air_data, i);
+            assert_eq!(
+                writer.read(&sum, i),
+                u64_to_le_field_bytes(a_val.wrapping_add(b_val))
+            );
+        }
+        let multiplicities = byte_data.get_multiplicities(&writer);
+        writer.write_lookup_multiplicities(byte_table.multiplicities(), &[multiplicities]);
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_add_checked_u64() {
+        prove_add_checked_u64(u64::MAX - 5, 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_add_checked_u64_overflow() {
+        prove_add_checked_u64(u64::MAX - 5, 6);
+    }
+}
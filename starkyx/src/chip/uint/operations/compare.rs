@@ -0,0 +1,75 @@
+use super::add::ByteArrayAdd;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use crate::chip::uint::bytes::operations::instruction::ByteOperationInstruction;
+use crate::chip::uint::register::{U32Register, U64Register};
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+// The comparisons read the carry of `b + !a = 2^n + (b - a - 1)`, which is set exactly when
+// `a < b`, and of `b + !a + 1`, which is set exactly when `a <= b`. The sum is range checked
+// to its bytes, so the carry bit is determined by the operands.
+impl<L: AirParameters> AirBuilder<L> {
+    /// Returns a bit set if `a < b` as unsigned integers.
+    pub fn lt_u32(
+        &mut self,
+        a: &U32Register,
+        b: &U32Register,
+        operations: &mut ByteLookupOperations,
+    ) -> BitRegister
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let not_a = self.bitwise_not(a, operations);
+        let (_, carry) = self.carrying_add_u32(b, &not_a, &None, operations);
+        carry
+    }
+
+    /// Returns a bit set if `a <= b` as unsigned integers.
+    pub fn le_u32(
+        &mut self,
+        a: &U32Register,
+        b: &U32Register,
+        operations: &mut ByteLookupOperations,
+    ) -> BitRegister
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let not_a = self.bitwise_not(a, operations);
+        let one = self.constant::<BitRegister>(&L::Field::ONE);
+        let (_, carry) = self.carrying_add_u32(b, &not_a, &Some(one), operations);
+        carry
+    }
+
+    /// Returns a bit set if `a < b` as unsigned integers.
+    pub fn lt_u64(
+        &mut self,
+        a: &U64Register,
+        b: &U64Register,
+        operations: &mut ByteLookupOperations,
+    ) -> BitRegister
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let not_a = self.bitwise_not(a, operations);
+        let (_, carry) = self.carrying_add_u64(b, &not_a, &None, operations);
+        carry
+    }
+
+    /// Returns a bit set if `a <= b` as unsigned integers.
+    pub fn le_u64(
+        &mut self,
+        a: &U64Register,
+        b: &U64Register,
+        operations: &mut ByteLookupOperations,
+    ) -> BitRegister
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let not_a = self.bitwise_not(a, operations);
+        let one = self.constant::<BitRegister>(&L::Field::ONE);
+        let (_, carry) = self.carrying_add_u64(b, &not_a, &Some(one), operations);
+        carry
+    }
+}
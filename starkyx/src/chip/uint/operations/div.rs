@@ -0,0 +1,418 @@
+use serde::{Deserialize, Serialize};
+
+use crate::air::parser::AirParser;
+use crate::air::AirConstraint;
+use crate::chip::builder::AirBuilder;
+use crate::chip::instruction::Instruction;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::trace::writer::{AirWriter, TraceWriter};
+use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use crate::chip::uint::bytes::operations::instruction::ByteOperationInstruction;
+use crate::chip::uint::bytes::operations::value::ByteOperation;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::register::ByteArrayRegister;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+/// The division with remainder of byte arrays as integers mod 2^{8 * N}.
+///
+/// The quotient `q` and remainder `r` of `a` by `b` are constrained by `a = q * b + r` over the
+/// integers, computed byte by byte with two-byte carries, and by `r + d + 1 = b` for a range
+/// checked complement `d`, so that `r < b`. The product `q * b` has up to `2N` bytes, and its
+/// bytes above the first `N`, as well as the carry out of them, are constrained to be zero. There
+/// is no quotient and remainder satisfying the constraints if `b` is zero.
+///
+/// Assumes N <= 8.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ByteArrayDivRem {
+    a: ArrayRegister<ByteRegister>,
+    b: ArrayRegister<ByteRegister>,
+    quotient: ArrayRegister<ByteRegister>,
+    remainder: ArrayRegister<ByteRegister>,
+    complement: ArrayRegister<ByteRegister>,
+    product_carries: ArrayRegister<ByteRegister>,
+    complement_carries: ArrayRegister<BitRegister>,
+}
+
+/// The values written by `ByteArrayDivRem`, in little-endian byte order.
+struct DivRemWitness {
+    quotient: Vec<u8>,
+    remainder: Vec<u8>,
+    complement: Vec<u8>,
+    product_carries: Vec<u8>,
+    complement_carries: Vec<u8>,
+}
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Divides `a` by `b`, returning the quotient and the remainder.
+    ///
+    /// The constraints can't be satisfied in the rows where `b` is zero.
+    pub fn div_rem<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        b: &ByteArrayRegister<N>,
+        operations: &mut ByteLookupOperations,
+    ) -> (ByteArrayRegister<N>, ByteArrayRegister<N>)
+    where
+        L::Instruction: From<ByteArrayDivRem> + From<ByteOperationInstruction>,
+    {
+        assert!(N <= 8, "Division is only supported for up to 8 bytes");
+        let quotient = self.alloc::<ByteArrayRegister<N>>();
+        let remainder = self.alloc::<ByteArrayRegister<N>>();
+        let complement = self.alloc::<ByteArrayRegister<N>>();
+        let product_carries = self.alloc_array::<ByteRegister>(2 * (N - 1));
+        let complement_carries = self.alloc_array::<BitRegister>(N - 1);
+
+        let div_rem = ByteArrayDivRem {
+            a: a.to_le_bytes(),
+            b: b.to_le_bytes(),
+            quotient: quotient.to_le_bytes(),
+            remainder: remainder.to_le_bytes(),
+            complement: complement.to_le_bytes(),
+            product_carries,
+            complement_carries,
+        };
+        self.register_instruction(div_rem);
+
+        for byte in quotient
+            .to_le_bytes()
+            .into_iter()
+            .chain(remainder.to_le_bytes())
+            .chain(complement.to_le_bytes())
+            .chain(product_carries)
+        {
+            self.set_byte_operation(&ByteOperation::Range(byte), operations);
+        }
+
+        (quotient, remainder)
+    }
+}
+
+impl ByteArrayDivRem {
+    fn num_bytes(&self) -> usize {
+        self.a.len()
+    }
+
+    fn witness(&self, a: &[u8], b: &[u8]) -> DivRemWitness {
+        let n = self.num_bytes();
+        let to_u64 = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .rev()
+                .fold(0u64, |acc, byte| (acc << 8) | *byte as u64)
+        };
+        let to_bytes = |value: u64| value.to_le_bytes()[..n].to_vec();
+        let (a_val, b_val) = (to_u64(a), to_u64(b));
+
+        // For a zero divisor, the values are those of `q = 0` and `r = a`, which only fail the
+        // constraint `r < b`.
+        let (quotient, remainder) = match b_val {
+            0 => (0, a_val),
+            _ => (a_val / b_val, a_val % b_val),
+        };
+        let mask = u64::MAX >> (64 - 8 * n);
+        let complement = b_val.wrapping_sub(remainder).wrapping_sub(1) & mask;
+        let (quotient, remainder, complement) = (
+            to_bytes(quotient),
+            to_bytes(remainder),
+            to_bytes(complement),
+        );
+
+        let mut product_carries = Vec::with_capacity(2 * (n - 1));
+        let mut carry = 0u64;
+        for k in 0..n - 1 {
+            let column = (0..=k)
+                .map(|i| quotient[i] as u64 * b[k - i] as u64)
+                .sum::<u64>()
+                + remainder[k] as u64
+                + carry;
+            carry = (column - a[k] as u64) >> 8;
+            product_carries.extend_from_slice(&(carry as u16).to_le_bytes());
+        }
+
+        let mut complement_carries = Vec::with_capacity(n - 1);
+        let mut carry = 1u64;
+        for k in 0..n - 1 {
+            carry = (remainder[k] as u64 + complement[k] as u64 + carry) >> 8;
+            complement_carries.push(carry as u8);
+        }
+
+        DivRemWitness {
+            quotient,
+            remainder,
+            complement,
+            product_carries,
+            complement_carries,
+        }
+    }
+}
+
+impl<AP: AirParser> AirConstraint<AP> for ByteArrayDivRem {
+    fn eval(&self, parser: &mut AP) {
+        let n = self.num_bytes();
+        let a = self.a.eval_vec(parser);
+        let b = self.b.eval_vec(parser);
+        let quotient = self.quotient.eval_vec(parser);
+        let remainder = self.remainder.eval_vec(parser);
+        let complement = self.complement.eval_vec(parser);
+        let product_carries = self.product_carries.eval_vec(parser);
+        let complement_carries = self.complement_carries.eval_vec(parser);
+
+        let byte_base = AP::Field::from_canonical_u32(1 << 8);
+        let carries = product_carries
+            .chunks_exact(2)
+            .map(|carry| {
+                let high = parser.mul_const(carry[1], byte_base);
+                parser.add(carry[0], high)
+            })
+            .collect::<Vec<_>>();
+
+        // The bytes of `q * b + r - a`, with the carries moved to the next byte.
+        for k in 0..2 * n - 1 {
+            let mut column = parser.zero();
+            for i in k.saturating_sub(n - 1)..=k.min(n - 1) {
+                let product = parser.mul(quotient[i], b[k - i]);
+                column = parser.add(column, product);
+            }
+            if k < n {
+                column = parser.add(column, remainder[k]);
+                column = parser.sub(column, a[k]);
+            }
+            if let Some(carry) = k.checked_sub(1).and_then(|j| carries.get(j)) {
+                column = parser.add(column, *carry);
+            }
+            if let Some(carry) = carries.get(k) {
+                let carry_times_base = parser.mul_const(*carry, byte_base);
+                column = parser.sub(column, carry_times_base);
+            }
+            parser.constraint(column);
+        }
+
+        // The bytes of `r + d + 1 - b`, with no carry out of the last byte.
+        for k in 0..n {
+            let mut column = parser.add(remainder[k], complement[k]);
+            column = parser.sub(column, b[k]);
+            column = match k {
+                0 => parser.add_const(column, AP::Field::ONE),
+                _ => parser.add(column, complement_carries[k - 1]),
+            };
+            if let Some(carry) = complement_carries.get(k) {
+                let carry_times_base = parser.mul_const(*carry, byte_base);
+                column = parser.sub(column, carry_times_base);
+            }
+            parser.constraint(column);
+        }
+    }
+}
+
+impl<F: PrimeField64> Instruction<F> for ByteArrayDivRem {
+    fn write(&self, writer: &TraceWriter<F>, row_index: usize) {
+        let to_bytes = |values: Vec<F>| {
+            values
+                .into_iter()
+                .map(|x| x.as_canonical_u64() as u8)
+                .collect::<Vec<_>>()
+        };
+        let a = to_bytes(writer.read_vec(&self.a, row_index));
+        let b = to_bytes(writer.read_vec(&self.b, row_index));
+        let witness = self.witness(&a, &b);
+
+        let to_field = |bytes: Vec<u8>| bytes.into_iter().map(F::from_canonical_u8);
+        writer.write_array(&self.quotient, to_field(witness.quotient), row_index);
+        writer.write_array(&self.remainder, to_field(witness.remainder), row_index);
+        writer.write_array(&self.complement, to_field(witness.complement), row_index);
+        writer.write_array(
+            &self.product_carries,
+            to_field(witness.product_carries),
+            row_index,
+        );
+        writer.write_array(
+            &self.complement_carries,
+            to_field(witness.complement_carries),
+            row_index,
+        );
+    }
+
+    fn write_to_air(&self, writer: &mut impl AirWriter<Field = F>) {
+        let to_bytes = |values: Vec<F>| {
+            values
+                .into_iter()
+                .map(|x| x.as_canonical_u64() as u8)
+                .collect::<Vec<_>>()
+        };
+        let a = to_bytes(writer.read_vec(&self.a));
+        let b = to_bytes(writer.read_vec(&self.b));
+        let witness = self.witness(&a, &b);
+
+        let to_field = |bytes: Vec<u8>| bytes.into_iter().map(F::from_canonical_u8);
+        writer.write_array(&self.quotient, to_field(witness.quotient));
+        writer.write_array(&self.remainder, to_field(witness.remainder));
+        writer.write_array(&self.complement, to_field(witness.complement));
+        writer.write_array(&self.product_carries, to_field(witness.product_carries));
+        writer.write_array(
+            &self.complement_carries,
+            to_field(witness.complement_carries),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use rand::Rng;
+
+    use super::*;
+    use crate::chip::trace::writer::InnerWriterData;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::register::{U32Register, U64Register};
+    use crate::chip::uint::util::{u32_to_le_field_bytes, u64_to_le_field_bytes};
+    use crate::machine::builder::Builder;
+    use crate::machine::bytes::builder::BytesBuilder;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct DivRemU32Test;
+
+    impl AirParameters for DivRemU32Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 30;
+        const EXTENDED_COLUMNS: usize = 100;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct DivRemU64Test;
+
+    impl AirParameters for DivRemU64Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 62;
+        const EXTENDED_COLUMNS: usize = 190;
+    }
+
+    /// Proves the divisions of the pairs, repeated over the rows of the trace.
+    fn prove_div_rem_u32(pairs: &[(u32, u32)]) {
+        type L = DivRemU32Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<U32Register>();
+        let b = builder.alloc::<U32Register>();
+        let (quotient, remainder) = builder.div_rem(&a, &b);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let (a_val, b_val) = pairs[i % pairs.len()];
+            writer.write(&a, &u32_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u32_to_le_field_bytes(b_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            if b_val != 0 {
+                assert_eq!(
+                    writer.read(&quotient, i),
+                    u32_to_le_field_bytes(a_val / b_val)
+                );
+                assert_eq!(
+                    writer.read(&remainder, i),
+                    u32_to_le_field_bytes(a_val % b_val)
+                );
+            }
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_div_rem_u32", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_div_rem_u32() {
+        let mut rng = rand::thread_rng();
+        // Dividends smaller than, equal to and multiples of the divisor, and the full range,
+        // followed by random divisors of random sizes, so that the quotients are of all sizes.
+        let mut pairs = vec![
+            (3, 7),
+            (7, 7),
+            (0, 1),
+            (u32::MAX, 1),
+            (u32::MAX, u32::MAX),
+            (u32::MAX - 1, u32::MAX),
+            (u32::MAX, 256),
+            (1 << 24, 255),
+        ];
+        while pairs.len() < 32 {
+            let b_val = rng.gen::<u32>() >> rng.gen_range(0..32);
+            pairs.push((rng.gen::<u32>(), b_val.max(1)));
+        }
+        prove_div_rem_u32(&pairs);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_div_rem_u32_zero_divisor() {
+        prove_div_rem_u32(&[(5, 3), (5, 0)]);
+    }
+
+    #[test]
+    fn test_div_rem_u64() {
+        type L = DivRemU64Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<U64Register>();
+        let b = builder.alloc::<U64Register>();
+        let (quotient, remainder) = builder.div_rem(&a, &b);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let boundaries = [
+            (3, 7),
+            (7, 7),
+            (0, 1),
+            (u64::MAX, 1),
+            (u64::MAX, u64::MAX),
+            (u64::MAX - 1, u64::MAX),
+            (u64::MAX, 1 << 32),
+            (1 << 56, 255),
+        ];
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let (a_val, b_val) = match boundaries.get(i) {
+                Some(pair) => *pair,
+                None => {
+                    let b_val = rng.gen::<u64>() >> rng.gen_range(0..64);
+                    (rng.gen::<u64>(), b_val.max(1))
+                }
+            };
+            writer.write(&a, &u64_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u64_to_le_field_bytes(b_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            assert_eq!(
+                writer.read(&quotient, i),
+                u64_to_le_field_bytes(a_val / b_val)
+            );
+            assert_eq!(
+                writer.read(&remainder, i),
+                u64_to_le_field_bytes(a_val % b_val)
+            );
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_div_rem_u64", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+}
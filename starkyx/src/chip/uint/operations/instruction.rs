@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::add::ByteArrayAdd;
+use super::div::ByteArrayDivRem;
 use crate::air::parser::AirParser;
 use crate::air::AirConstraint;
 use crate::chip::instruction::Instruction;
@@ -15,10 +16,11 @@ use crate::math::prelude::*;
 pub enum UintInstruction {
     Bit(ByteInstructionSet),
     Add(ByteArrayAdd<4>),
+    DivRem(ByteArrayDivRem),
 }
 
 pub trait UintInstructions:
-    ByteInstructions + From<UintInstruction> + From<ByteArrayAdd<4>>
+    ByteInstructions + From<UintInstruction> + From<ByteArrayAdd<4>> + From<ByteArrayDivRem>
 {
 }
 
@@ -31,6 +33,7 @@ impl<AP: AirParser> AirConstraint<AP> for UintInstruction {
         match self {
             Self::Bit(op) => op.eval(parser),
             Self::Add(op) => op.eval(parser),
+            Self::DivRem(op) => op.eval(parser),
         }
     }
 }
@@ -40,6 +43,7 @@ impl<F: PrimeField64> Instruction<F> for UintInstruction {
         match self {
             Self::Bit(op) => Instruction::<F>::write(op, writer, row_index),
             Self::Add(op) => Instruction::<F>::write(op, writer, row_index),
+            Self::DivRem(op) => Instruction::<F>::write(op, writer, row_index),
         }
     }
 
@@ -47,6 +51,7 @@ impl<F: PrimeField64> Instruction<F> for UintInstruction {
         match self {
             Self::Bit(op) => Instruction::<F>::write_to_air(op, writer),
             Self::Add(op) => Instruction::<F>::write_to_air(op, writer),
+            Self::DivRem(op) => Instruction::<F>::write_to_air(op, writer),
         }
     }
 }
@@ -63,6 +68,12 @@ impl From<ByteArrayAdd<4>> for UintInstruction {
     }
 }
 
+impl From<ByteArrayDivRem> for UintInstruction {
+    fn from(op: ByteArrayDivRem) -> Self {
+        Self::DivRem(op)
+    }
+}
+
 impl From<ByteOperationInstruction> for UintInstruction {
     fn from(op: ByteOperationInstruction) -> Self {
         Self::Bit(op.into())
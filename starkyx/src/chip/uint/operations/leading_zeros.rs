@@ -0,0 +1,60 @@
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::uint::bytes::decode::ByteDecodeInstruction;
+use crate::chip::uint::register::ByteArrayRegister;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Sets `result` to the number of leading zeros of `a`, which is `8 * N` if `a` is zero.
+    ///
+    /// The bytes of `a` are decoded into bits, and a flag is set for every bit position if a bit
+    /// at or above it is set. The bit length of `a` is the number of set flags, so the count `k`
+    /// satisfies `2^(8N - k - 1) <= a < 2^(8N - k)` for a nonzero `a`.
+    pub fn set_leading_zeros<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        result: &ElementRegister,
+    ) where
+        L::Instruction: From<ByteDecodeInstruction>,
+    {
+        let bits = self.alloc_array::<BitRegister>(8 * N);
+        for (i, byte) in a.to_le_bytes().iter().enumerate() {
+            self.decode_byte(&byte, &bits.get_subarray(8 * i..8 * (i + 1)));
+        }
+
+        // Go from the most significant bit down, keeping the OR of the bits seen so far.
+        let mut bit_length = ArithmeticExpression::zero();
+        let mut seen: Option<BitRegister> = None;
+        for bit in (0..8 * N).rev().map(|i| bits.get(i)) {
+            let flag = match seen {
+                None => bit,
+                Some(seen) => {
+                    let flag = self.alloc::<BitRegister>();
+                    self.set_to_expression(
+                        &flag,
+                        seen.expr() + bit.expr() - seen.expr() * bit.expr(),
+                    );
+                    flag
+                }
+            };
+            bit_length = bit_length + flag.expr();
+            seen = Some(flag);
+        }
+
+        let num_bits = ArithmeticExpression::from(L::Field::from_canonical_usize(8 * N));
+        self.set_to_expression(result, num_bits - bit_length);
+    }
+
+    pub fn leading_zeros<const N: usize>(&mut self, a: &ByteArrayRegister<N>) -> ElementRegister
+    where
+        L::Instruction: From<ByteDecodeInstruction>,
+    {
+        let result = self.alloc::<ElementRegister>();
+        self.set_leading_zeros(a, &result);
+        result
+    }
+}
@@ -1,7 +1,14 @@
 pub mod add;
 pub mod and;
+pub mod compare;
+pub mod div;
 pub mod instruction;
+pub mod leading_zeros;
 pub mod not;
+pub mod or;
+pub mod popcount;
 pub mod rotate;
+pub mod shl;
 pub mod shr;
+pub mod signed;
 pub mod xor;
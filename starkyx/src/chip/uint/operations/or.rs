@@ -0,0 +1,39 @@
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::Register;
+use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use crate::chip::uint::bytes::operations::instruction::ByteOperationInstruction;
+use crate::chip::uint::register::ByteArrayRegister;
+use crate::chip::AirParameters;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Sets `result` to the bitwise OR of `a` and `b`.
+    ///
+    /// The OR of two bytes is `a | b = a + b - (a & b)`, so it is derived from the lookup of the
+    /// AND and takes no byte operation of its own.
+    pub fn set_bitwise_or<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        b: &ByteArrayRegister<N>,
+        result: &ByteArrayRegister<N>,
+        operations: &mut ByteLookupOperations,
+    ) where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let and = self.bitwise_and(a, b, operations);
+        self.set_to_expression(result, a.expr() + b.expr() - and.expr());
+    }
+
+    pub fn bitwise_or<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        b: &ByteArrayRegister<N>,
+        operations: &mut ByteLookupOperations,
+    ) -> ByteArrayRegister<N>
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let result = self.alloc::<ByteArrayRegister<N>>();
+        self.set_bitwise_or(a, b, &result, operations);
+        result
+    }
+}
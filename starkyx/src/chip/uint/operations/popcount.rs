@@ -0,0 +1,47 @@
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use crate::chip::uint::bytes::operations::instruction::ByteOperationInstruction;
+use crate::chip::uint::bytes::operations::value::ByteOperation;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::register::ByteArrayRegister;
+use crate::chip::AirParameters;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Sets `result` to the number of set bits of `a`.
+    ///
+    /// The number of set bits of every byte is looked up in the byte table, and `result` is
+    /// their sum.
+    pub fn set_popcount<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        result: &ElementRegister,
+        operations: &mut ByteLookupOperations,
+    ) where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let mut sum = ArithmeticExpression::zero();
+        for a_byte in a.to_le_bytes().iter() {
+            let count = self.alloc::<ByteRegister>();
+            let popcount = ByteOperation::Popcount(a_byte, count);
+            self.set_byte_operation(&popcount, operations);
+            sum = sum + count.expr();
+        }
+        self.set_to_expression(result, sum);
+    }
+
+    pub fn popcount<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        operations: &mut ByteLookupOperations,
+    ) -> ElementRegister
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let result = self.alloc::<ElementRegister>();
+        self.set_popcount(a, &result, operations);
+        result
+    }
+}
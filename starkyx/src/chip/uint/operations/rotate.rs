@@ -74,4 +74,19 @@ impl<L: AirParameters> AirBuilder<L> {
         self.set_bit_rotate_right(a, rotation, &result, operations);
         result
     }
+
+    /// Rotates `a` left by `rotation` bits, as a rotation right by the complement of `rotation`
+    /// modulo the bit width.
+    pub fn bit_rotate_left<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        rotation: usize,
+        operations: &mut ByteLookupOperations,
+    ) -> ByteArrayRegister<N>
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let num_bits = N * 8;
+        self.bit_rotate_right(a, (num_bits - rotation % num_bits) % num_bits, operations)
+    }
 }
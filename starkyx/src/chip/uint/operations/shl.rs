@@ -0,0 +1,75 @@
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::Register;
+use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use crate::chip::uint::bytes::operations::instruction::ByteOperationInstruction;
+use crate::chip::uint::bytes::operations::value::ByteOperation;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::register::ByteArrayRegister;
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+impl<L: AirParameters> AirBuilder<L> {
+    pub fn bit_shl<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        shift: usize,
+        operations: &mut ByteLookupOperations,
+    ) -> ByteArrayRegister<N>
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let result = self.alloc::<ByteArrayRegister<N>>();
+        self.set_bit_shl(a, shift, &result, operations);
+        result
+    }
+
+    /// Sets `result` to `a` shifted left by `shift` bits modulo the bit width, shifting in zeros.
+    pub fn set_bit_shl<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        shift: usize,
+        result: &ByteArrayRegister<N>,
+        operations: &mut ByteLookupOperations,
+    ) where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let a_bytes = a.to_le_bytes();
+        let result_bytes = result.to_le_bytes();
+
+        let shift = shift % (N * 8);
+        let byte_shift = shift / 8;
+        let bit_shift = shift % 8;
+
+        for i in 0..byte_shift {
+            self.assert_zero(&result_bytes.get(i));
+        }
+
+        // A shift of whole bytes moves the bytes without splitting them.
+        if bit_shift == 0 {
+            for i in byte_shift..N {
+                self.set_to_expression(&result_bytes.get(i), a_bytes.get(i - byte_shift).expr());
+            }
+            return;
+        }
+
+        // Every byte is split into its top `bit_shift` bits, carried into the next byte, and the
+        // remaining low bits, moved up by `bit_shift`.
+        let mult = L::Field::from_canonical_u32(1 << bit_shift);
+        let mut carry = ArithmeticExpression::zero();
+        for i in byte_shift..N {
+            let (next_carry, low_bits) =
+                (self.alloc::<ByteRegister>(), self.alloc::<ByteRegister>());
+            let shr_carry = ByteOperation::ShrCarry(
+                a_bytes.get(i - byte_shift),
+                8 - bit_shift as u8,
+                next_carry,
+                low_bits,
+            );
+            self.set_byte_operation(&shr_carry, operations);
+            let expected_res = low_bits.expr() * mult + carry.clone();
+            self.set_to_expression(&result_bytes.get(i), expected_res);
+            carry = next_carry.expr();
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use super::add::ByteArrayAdd;
+use crate::chip::builder::AirBuilder;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::RegisterSerializable;
+use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use crate::chip::uint::bytes::operations::instruction::ByteOperationInstruction;
+use crate::chip::uint::bytes::operations::value::ByteOperation;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::register::{IRegister, U32Register, U64Register};
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
+
+impl<L: AirParameters> AirBuilder<L> {
+    /// Subtracts `b` from `a` modulo 2^32, computed as `a + !b + 1`.
+    pub fn wrapping_sub_u32(
+        &mut self,
+        a: &U32Register,
+        b: &U32Register,
+        operations: &mut ByteLookupOperations,
+    ) -> U32Register
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let not_b = self.bitwise_not(b, operations);
+        let one = self.constant::<BitRegister>(&L::Field::ONE);
+        let (result, _) = self.carrying_add_u32(a, &not_b, &Some(one), operations);
+        result
+    }
+
+    /// Subtracts `b` from `a` modulo 2^64, computed as `a + !b + 1`.
+    pub fn wrapping_sub_u64(
+        &mut self,
+        a: &U64Register,
+        b: &U64Register,
+        operations: &mut ByteLookupOperations,
+    ) -> U64Register
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let not_b = self.bitwise_not(b, operations);
+        let one = self.constant::<BitRegister>(&L::Field::ONE);
+        let (result, _) = self.carrying_add_u64(a, &not_b, &Some(one), operations);
+        result
+    }
+
+    /// Negates `a` modulo 2^32, so that the negation of `i32::MIN` is itself.
+    pub fn wrapping_neg_u32(
+        &mut self,
+        a: &U32Register,
+        operations: &mut ByteLookupOperations,
+    ) -> U32Register
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let zero = self.constant::<U32Register>(&[L::Field::ZERO; 4]);
+        self.wrapping_sub_u32(&zero, a, operations)
+    }
+
+    /// Negates `a` modulo 2^64, so that the negation of `i64::MIN` is itself.
+    pub fn wrapping_neg_u64(
+        &mut self,
+        a: &U64Register,
+        operations: &mut ByteLookupOperations,
+    ) -> U64Register
+    where
+        L::Instruction: From<ByteArrayAdd<4>> + From<ByteOperationInstruction>,
+    {
+        let zero = self.constant::<U64Register>(&[L::Field::ZERO; 8]);
+        self.wrapping_sub_u64(&zero, a, operations)
+    }
+
+    /// Returns the sign bit of `a`, the top bit of its most significant byte.
+    pub fn is_negative<const N: usize>(
+        &mut self,
+        a: &IRegister<N>,
+        operations: &mut ByteLookupOperations,
+    ) -> BitRegister
+    where
+        L::Instruction: From<ByteOperationInstruction>,
+    {
+        let top_byte = a.to_le_bytes().get(N - 1);
+        // The byte lookup constrains the sign to be zero or one, so it is kept in a bit column.
+        let sign = self.alloc::<BitRegister>();
+        let sign_byte = ByteRegister::from_register_unsafe(*sign.register());
+        self.set_byte_operation(&ByteOperation::ShrConst(top_byte, 7, sign_byte), operations);
+        sign
+    }
+}
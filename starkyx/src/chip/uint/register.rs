@@ -1,3 +1,5 @@
+use core::iter::once;
+
 use serde::{Deserialize, Serialize};
 
 use super::bytes::register::ByteRegister;
@@ -11,6 +13,7 @@ use crate::chip::register::cell::CellType;
 use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable, RegisterSized};
+use crate::chip::AirParameters;
 use crate::math::prelude::cubic::element::CubicElement;
 use crate::math::prelude::*;
 
@@ -19,6 +22,8 @@ pub struct ByteArrayRegister<const N: usize>(MemorySlice);
 
 pub type U32Register = ByteArrayRegister<4>;
 pub type U64Register = ByteArrayRegister<8>;
+pub type U128Register = ByteArrayRegister<16>;
+pub type U256Register = ByteArrayRegister<32>;
 
 impl<const N: usize> ByteArrayRegister<N> {
     pub fn to_le_bytes(&self) -> ArrayRegister<ByteRegister> {
@@ -67,59 +72,229 @@ impl<const N: usize> Register for ByteArrayRegister<N> {
     }
 }
 
-impl MemoryValue for U32Register {
-    fn num_challenges() -> usize {
+/// A signed integer of `N` bytes in two's complement, stored as the little-endian bytes of its
+/// unsigned representation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct IRegister<const N: usize>(MemorySlice);
+
+pub type I32Register = IRegister<4>;
+pub type I64Register = IRegister<8>;
+
+impl<const N: usize> IRegister<N> {
+    /// The register of the unsigned integer with the same bytes.
+    pub fn as_unsigned(&self) -> ByteArrayRegister<N> {
+        ByteArrayRegister::from_register_unsafe(self.0)
+    }
+
+    /// The signed integer with the same bytes as `register`.
+    pub fn from_unsigned(register: &ByteArrayRegister<N>) -> Self {
+        Self(*register.register())
+    }
+
+    pub fn to_le_bytes(&self) -> ArrayRegister<ByteRegister> {
+        ArrayRegister::from_register_unsafe(self.0)
+    }
+}
+
+impl<const N: usize> RegisterSerializable for IRegister<N> {
+    const CELL: CellType = CellType::Element;
+
+    fn register(&self) -> &MemorySlice {
+        &self.0
+    }
+
+    fn from_register_unsafe(register: MemorySlice) -> Self {
+        Self(register)
+    }
+}
+
+impl<const N: usize> RegisterSized for IRegister<N> {
+    fn size_of() -> usize {
+        N
+    }
+}
+
+impl<const N: usize> Register for IRegister<N> {
+    type Value<T> = [T; N];
+
+    fn value_from_slice<T: Copy>(slice: &[T]) -> Self::Value<T> {
+        let elem_fn = |i| slice[i];
+        core::array::from_fn(elem_fn)
+    }
+
+    fn align<T>(value: &Self::Value<T>) -> &[T] {
+        value
+    }
+}
+
+/// Whether every value of `bits` bits is a distinct element of `F`, so that packing bytes into a
+/// word of this width cannot alias two different values.
+pub(crate) fn word_fits_in_field<F: PrimeField64>(bits: usize) -> bool {
+    bits < 64 && (1u64 << bits) <= F::order()
+}
+
+/// The number of bytes packed into each word of a compressed value.
+///
+/// Bytes are packed into 32-bit words when these fit in the field, and into 16-bit half-words in
+/// a 31-bit field such as Mersenne-31, where a 32-bit word would wrap around the modulus.
+pub(crate) fn word_bytes<F: PrimeField64>() -> usize {
+    if word_fits_in_field::<F>(32) {
+        4
+    } else {
+        debug_assert!(
+            word_fits_in_field::<F>(16),
+            "16-bit words alias in a field of order {}",
+            F::order()
+        );
+        2
+    }
+}
+
+/// The number of challenges needed to compress a value of `num_bytes` bytes.
+///
+/// The compressed values keep the time in its own coordinate, or as its own term of the
+/// accumulation, rather than packing it together with the value as `value + 2^32 * time`. When
+/// the words and the time fit in the three coordinates of a cubic element no challenges are
+/// needed, and otherwise there is one challenge for each word and one for the time.
+pub(crate) fn num_word_challenges<F: PrimeField64>(num_bytes: usize) -> usize {
+    let num_words = num_bytes.div_ceil(word_bytes::<F>());
+    if num_words < 3 {
         0
+    } else {
+        num_words + 1
     }
+}
+
+/// Compresses `bytes` and the time by packing the bytes into words of `word_bytes` bytes.
+///
+/// The words and the time are either the coordinates of the compressed element, or are combined
+/// with the challenges of the memory slice if there are too many of them.
+pub(crate) fn compress_bytes<L: AirParameters>(
+    bytes: &ArrayRegister<ByteRegister>,
+    is_trace: bool,
+    builder: &mut AirBuilder<L>,
+    ptr: RawPointer,
+    time: &Time<L::Field>,
+    challenges: &ArrayRegister<CubicRegister>,
+) -> CubicRegister {
+    let word_len = word_bytes::<L::Field>();
+    let words = (0..bytes.len())
+        .step_by(word_len)
+        .map(|i| {
+            bytes
+                .get_subarray(i..(i + word_len).min(bytes.len()))
+                .iter()
+                .enumerate()
+                .fold(ArithmeticExpression::zero(), |acc, (j, byte)| {
+                    acc + byte.expr() * L::Field::from_canonical_u32(1 << (8 * j))
+                })
+        })
+        .collect::<Vec<_>>();
+
+    if num_word_challenges::<L::Field>(bytes.len()) == 0 {
+        let mut coordinates = words.into_iter().chain(once(time.expr()));
+        let acc_expression = CubicElement(core::array::from_fn(|_| {
+            coordinates
+                .next()
+                .unwrap_or_else(ArithmeticExpression::zero)
+        }));
+        return ptr.accumulate_cubic(builder, acc_expression);
+    }
+
+    let expressions = words
+        .into_iter()
+        .chain(once(time.expr()))
+        .collect::<Vec<_>>();
+    let compressed = if is_trace {
+        builder.accumulate_expressions(challenges, &expressions)
+    } else {
+        builder.accumulate_public_expressions(challenges, &expressions)
+    };
+
+    ptr.accumulate_cubic(builder, compressed.ext_expr())
+}
 
-    fn compress<L: crate::chip::AirParameters>(
+impl<const N: usize> ByteArrayRegister<N> {
+    /// Compresses the value and the time of a memory access, see [`compress_bytes`].
+    fn compress_words<L: AirParameters>(
         &self,
         builder: &mut AirBuilder<L>,
         ptr: RawPointer,
         time: &Time<L::Field>,
-        _: &ArrayRegister<CubicRegister>,
+        challenges: &ArrayRegister<CubicRegister>,
     ) -> CubicRegister {
-        let bytes = self.to_le_bytes();
-        let mut acc = ArithmeticExpression::zero();
-
-        for (i, byte) in bytes.iter().enumerate() {
-            let two_i = ArithmeticExpression::from(L::Field::from_canonical_u32(1 << (8 * i)));
-            acc = acc + two_i * byte.expr();
-        }
+        compress_bytes(
+            &self.to_le_bytes(),
+            self.is_trace(),
+            builder,
+            ptr,
+            time,
+            challenges,
+        )
+    }
+}
 
-        let zero = ArithmeticExpression::zero();
-        let acc_expression = CubicElement([acc, time.expr(), zero]);
+impl MemoryValue for U32Register {
+    fn num_challenges<F: PrimeField64>() -> usize {
+        num_word_challenges::<F>(4)
+    }
 
-        ptr.accumulate_cubic(builder, acc_expression)
+    fn compress<L: AirParameters>(
+        &self,
+        builder: &mut AirBuilder<L>,
+        ptr: RawPointer,
+        time: &Time<L::Field>,
+        challenges: &ArrayRegister<CubicRegister>,
+    ) -> CubicRegister {
+        self.compress_words(builder, ptr, time, challenges)
     }
 }
 
 impl MemoryValue for U64Register {
-    fn num_challenges() -> usize {
-        0
+    fn num_challenges<F: PrimeField64>() -> usize {
+        num_word_challenges::<F>(8)
     }
 
-    fn compress<L: crate::chip::AirParameters>(
+    fn compress<L: AirParameters>(
         &self,
         builder: &mut AirBuilder<L>,
         ptr: RawPointer,
         time: &Time<L::Field>,
-        _: &ArrayRegister<CubicRegister>,
+        challenges: &ArrayRegister<CubicRegister>,
     ) -> CubicRegister {
-        let bytes = self.to_le_bytes();
-        let low_bytes = bytes.get_subarray(0..4);
-        let high_bytes = bytes.get_subarray(4..8);
+        self.compress_words(builder, ptr, time, challenges)
+    }
+}
 
-        let mut acc_low = ArithmeticExpression::zero();
-        let mut acc_high = ArithmeticExpression::zero();
+impl MemoryValue for U128Register {
+    fn num_challenges<F: PrimeField64>() -> usize {
+        num_word_challenges::<F>(16)
+    }
 
-        for (i, (byte_low, byte_high)) in low_bytes.iter().zip(high_bytes).enumerate() {
-            let two_i = ArithmeticExpression::from(L::Field::from_canonical_u32(1 << (8 * i)));
-            acc_low = acc_low + two_i.clone() * byte_low.expr();
-            acc_high = acc_high + two_i * byte_high.expr();
-        }
-        let accumulated_expr = CubicElement([acc_low, acc_high, time.expr()]);
-        ptr.accumulate_cubic(builder, accumulated_expr)
+    fn compress<L: AirParameters>(
+        &self,
+        builder: &mut AirBuilder<L>,
+        ptr: RawPointer,
+        time: &Time<L::Field>,
+        challenges: &ArrayRegister<CubicRegister>,
+    ) -> CubicRegister {
+        self.compress_words(builder, ptr, time, challenges)
+    }
+}
+
+impl MemoryValue for U256Register {
+    fn num_challenges<F: PrimeField64>() -> usize {
+        num_word_challenges::<F>(32)
+    }
+
+    fn compress<L: AirParameters>(
+        &self,
+        builder: &mut AirBuilder<L>,
+        ptr: RawPointer,
+        time: &Time<L::Field>,
+        challenges: &ArrayRegister<CubicRegister>,
+    ) -> CubicRegister {
+        self.compress_words(builder, ptr, time, challenges)
     }
 }
 
@@ -128,9 +303,15 @@ mod tests {
     use plonky2::field::goldilocks_field::GoldilocksField;
 
     use super::*;
+    use crate::chip::builder::tests::ArithmeticGenerator;
+    use crate::chip::instruction::empty::EmptyInstruction;
     use crate::chip::uint::operations::instruction::UintInstruction;
-    use crate::chip::AirParameters;
+    use crate::machine::builder::Builder;
     use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::mersenne31::field::Mersenne31;
+    use crate::plonky2::stark::config::PoseidonGoldilocksStarkConfig;
+    use crate::plonky2::stark::tests::test_starky;
+    use crate::plonky2::stark::Starky;
 
     #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
     pub struct RegisterConversionTest;
@@ -163,4 +344,150 @@ mod tests {
 
         builder.assert_equal(&a, &b);
     }
+
+    #[test]
+    fn test_wide_register_limbs() {
+        type L = RegisterConversionTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<U256Register>();
+        let b = builder.alloc::<U128Register>();
+
+        let a_words = a.to_le_limbs::<4>();
+        assert_eq!(a_words.len(), 8);
+        assert_eq!(*a_words.get(7).register(), MemorySlice::Local(28, 4));
+        let a_halves = a.to_le_limbs::<16>();
+        assert_eq!(a_halves.len(), 2);
+        assert_eq!(*a_halves.get(1).register(), MemorySlice::Local(16, 16));
+        assert_eq!(
+            *U256Register::from_limbs(&a_halves).register(),
+            *a.register()
+        );
+        assert_eq!(
+            *U256Register::from_limbs(&a_words).register(),
+            *a.register()
+        );
+
+        let b_words = b.to_le_limbs::<8>();
+        assert_eq!(b_words.len(), 2);
+        assert_eq!(
+            *U128Register::from_limbs(&b_words).register(),
+            *b.register()
+        );
+        assert_eq!(
+            *b.to_le_bytes().get(15).register(),
+            MemorySlice::Local(47, 1)
+        );
+    }
+
+    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    pub struct WideMemoryTest;
+
+    impl AirParameters for WideMemoryTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 48;
+        const EXTENDED_COLUMNS: usize = 36;
+    }
+
+    /// Stores `stored` at a pointer, loads it in every row and frees the pointer with `freed`.
+    fn run_wide_memory(stored: [u8; 32], freed: [u8; 32]) {
+        type F = GoldilocksField;
+        type L = WideMemoryTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let num_rows = 1 << 5;
+
+        let mut builder = AirBuilder::<L>::new();
+        builder.init_local_memory();
+
+        let stored_pub = builder.alloc_public::<U256Register>();
+        let freed_pub = builder.alloc_public::<U256Register>();
+        let low_pub = builder.alloc_public::<U128Register>();
+        let ptr = builder.uninit::<U256Register>();
+        let low_ptr = builder.uninit::<U128Register>();
+        let mult = builder.constant(&F::from_canonical_usize(num_rows + 1));
+        builder.store(&ptr, stored_pub, &Time::zero(), Some(mult), None, None);
+        builder.store(&low_ptr, low_pub, &Time::zero(), Some(mult), None, None);
+
+        let value = builder.load(&ptr, &Time::zero(), None, None);
+        let low = builder.load(&low_ptr, &Time::zero(), None, None);
+        builder.free(&ptr, freed_pub, &Time::zero());
+        builder.free(&low_ptr, low_pub, &Time::zero());
+
+        let (air, trace_data) = builder.build();
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+
+        let writer = generator.new_writer();
+        let stored_value = stored.map(F::from_canonical_u8);
+        let low_value: [F; 16] = core::array::from_fn(|i| stored_value[i]);
+        writer.write(&stored_pub, &stored_value, 0);
+        writer.write(&freed_pub, &freed.map(F::from_canonical_u8), 0);
+        writer.write(&low_pub, &low_value, 0);
+        writer.write_global_instructions(&generator.air_data);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+            assert_eq!(writer.read(&value, i), stored_value);
+            assert_eq!(writer.read(&low, i), low_value);
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public = writer.public().unwrap().clone();
+        test_starky(&stark, &config, &generator, &public);
+    }
+
+    #[test]
+    fn test_wide_register_memory() {
+        let value: [u8; 32] = core::array::from_fn(|i| 0xff - i as u8);
+        run_wide_memory(value, value);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wide_register_memory_high_byte_mismatch() {
+        // The values differ only in the most significant byte, which an accumulation of all the
+        // bytes into a single field element would lose to the reduction modulo the field order.
+        let value: [u8; 32] = core::array::from_fn(|i| 0xff - i as u8);
+        let mut freed = value;
+        freed[31] ^= 1;
+        run_wide_memory(value, freed);
+    }
+
+    #[test]
+    fn test_word_fits_in_field() {
+        type F = GoldilocksField;
+
+        // The Goldilocks order is `2^64 - 2^32 + 1`, so words of up to 63 bits are distinct field
+        // elements, while a 64-bit word would wrap around the modulus.
+        assert!(word_fits_in_field::<F>(32));
+        assert!(word_fits_in_field::<F>(63));
+        assert!(!word_fits_in_field::<F>(64));
+    }
+
+    #[test]
+    fn test_word_challenges() {
+        type F = GoldilocksField;
+        type M31 = Mersenne31;
+
+        assert_eq!(word_bytes::<F>(), 4);
+        assert_eq!(U32Register::num_challenges::<F>(), 0);
+        assert_eq!(U64Register::num_challenges::<F>(), 0);
+        assert_eq!(U128Register::num_challenges::<F>(), 5);
+        assert_eq!(U256Register::num_challenges::<F>(), 9);
+
+        // A 32-bit word would wrap around the Mersenne-31 modulus, so the bytes are packed into
+        // 16-bit half-words instead.
+        assert!(!word_fits_in_field::<M31>(32));
+        assert!(!word_fits_in_field::<M31>(31));
+        assert!(word_fits_in_field::<M31>(30));
+        assert_eq!(word_bytes::<M31>(), 2);
+        assert_eq!(U32Register::num_challenges::<M31>(), 0);
+        assert_eq!(U64Register::num_challenges::<M31>(), 5);
+        assert_eq!(U128Register::num_challenges::<M31>(), 9);
+        assert_eq!(U256Register::num_challenges::<M31>(), 17);
+    }
 }
@@ -1,5 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::new_without_default)]
+#![feature(associated_type_defaults)]
 #![feature(bigint_helper_methods)]
 
 extern crate alloc;
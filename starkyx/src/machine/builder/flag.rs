@@ -0,0 +1,147 @@
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::Register;
+use crate::math::prelude::*;
+
+/// The value of a cyclic flag in the next row, computed from its value in the current row.
+#[derive(Debug, Clone)]
+pub enum FlagUpdate<F> {
+    /// Keep the current value.
+    Hold,
+    /// Add one to the current value.
+    Increment,
+    /// Set the value to a constant.
+    Constant(F),
+    /// Set the value to an expression of the current row.
+    Expression(ArithmeticExpression<F>),
+}
+
+/// A declarative description of a control-flow register.
+///
+/// The register is set to `first_row` in the first row. In every transition, the update of the
+/// first rule whose condition bit is set in the current row is applied, and if none of them are
+/// set, the default update is applied.
+#[derive(Debug, Clone)]
+pub struct CyclicFlag<F> {
+    pub(crate) name: String,
+    pub(crate) first_row: F,
+    pub(crate) rules: Vec<(BitRegister, FlagUpdate<F>)>,
+    pub(crate) default: FlagUpdate<F>,
+}
+
+impl<F: Field> FlagUpdate<F> {
+    fn expr(&self, register: &impl Register) -> ArithmeticExpression<F> {
+        match self {
+            FlagUpdate::Hold => register.expr(),
+            FlagUpdate::Increment => register.expr() + F::ONE,
+            FlagUpdate::Constant(value) => ArithmeticExpression::from(*value),
+            FlagUpdate::Expression(expression) => expression.clone(),
+        }
+    }
+}
+
+impl<F: Field> CyclicFlag<F> {
+    /// A flag starting at `first_row` and updated with `default` when no rule applies.
+    pub fn new(name: &str, first_row: F, default: FlagUpdate<F>) -> Self {
+        Self {
+            name: name.to_string(),
+            first_row,
+            rules: Vec::new(),
+            default,
+        }
+    }
+
+    /// Adds a rule applying `update` in the transition from the rows in which `condition` is set.
+    ///
+    /// Rules added first take precedence over the ones added later.
+    pub fn on(mut self, condition: BitRegister, update: FlagUpdate<F>) -> Self {
+        self.rules.push((condition, update));
+        self
+    }
+
+    /// A counter of the rows of a cycle: starts at zero, is incremented in every row, and resets
+    /// to zero after every row in which `end_bit` is set.
+    pub fn counter(name: &str, end_bit: BitRegister) -> Self {
+        Self::new(name, F::ZERO, FlagUpdate::Increment).on(end_bit, FlagUpdate::Constant(F::ZERO))
+    }
+
+    /// The expression for the value of `register` in the next row.
+    pub(crate) fn transition(&self, register: &impl Register) -> ArithmeticExpression<F> {
+        self.rules
+            .iter()
+            .rev()
+            .fold(self.default.expr(register), |acc, (condition, update)| {
+                condition.expr() * update.expr(register) + condition.not_expr() * acc
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::AirParameters;
+    use crate::machine::builder::Builder;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct CyclicFlagTest;
+
+    impl AirParameters for CyclicFlagTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 8;
+    }
+
+    #[test]
+    fn test_cyclic_flag_period_8() {
+        type F = GoldilocksField;
+        type L = CyclicFlagTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let cycle_8 = builder.cycle(3);
+        let end_bit = cycle_8.end_bit;
+
+        let declared: ElementRegister = builder.cyclic_flag(&CyclicFlag::counter("index", end_bit));
+
+        let hand_written = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&hand_written, F::ZERO.into());
+        builder.set_to_expression_transition(
+            &hand_written.next(),
+            end_bit.not_expr() * (hand_written.expr() + F::ONE) + end_bit.expr() * F::ZERO,
+        );
+
+        let is_first: BitRegister = builder.cyclic_flag(
+            &CyclicFlag::new("is_first", F::ONE, FlagUpdate::Constant(F::ZERO))
+                .on(end_bit, FlagUpdate::Constant(F::ONE)),
+        );
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 6;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        for i in 0..num_rows {
+            assert_eq!(writer.read(&declared, i), writer.read(&hand_written, i));
+            assert_eq!(writer.read(&declared, i), F::from_canonical_usize(i % 8));
+            assert_eq!(
+                writer.read(&is_first, i),
+                F::from_canonical_u8((i % 8 == 0) as u8)
+            );
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+}
@@ -1,25 +1,46 @@
-use self::ops::{Adc, Add, And, Div, Double, Mul, Neg, Not, One, Or, Shl, Shr, Sub, Xor, Zero};
+use std::collections::BTreeMap;
+
+use self::flag::CyclicFlag;
+use self::ops::{
+    Adc, Add, AddChecked, And, Div, Double, Le, LeadingZeros, Lt, Mul, Neg, Not, One, Or, Popcount,
+    Shl, Shr, Sub, Xor, Zero,
+};
+use self::repeat::Repetition;
+use self::state::{StateMachine, StateSelectors};
 use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::builder::padding::PaddingPolicy;
 use crate::chip::builder::AirBuilder;
+use crate::chip::const_div::DivRemConstInstruction;
 use crate::chip::ec::scalar::LimbBitInstruction;
 use crate::chip::instruction::cycle::Cycle;
+use crate::chip::instruction::hint::HintReader;
 use crate::chip::instruction::Instruction;
 use crate::chip::memory::instruction::MemorySliceIndex;
 use crate::chip::memory::pointer::slice::Slice;
 use crate::chip::memory::pointer::Pointer;
+use crate::chip::memory::queue::Queue;
+use crate::chip::memory::reads::{CountedSlot, ReadCount};
+use crate::chip::memory::stack::Stack;
 use crate::chip::memory::time::Time;
 use crate::chip::memory::value::MemoryValue;
+use crate::chip::radix::RadixInstruction;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
+use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::matrix::Matrix;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::slice::RegisterSlice;
-use crate::chip::register::Register;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::uint::register::ByteArrayRegister;
 use crate::chip::AirParameters;
-use crate::math::field::PrimeField64;
-use crate::math::prelude::CubicParameters;
+use crate::math::prelude::*;
 
+pub mod flag;
 pub mod ops;
+pub mod public;
+pub mod repeat;
+pub mod state;
 
 /// A safe interface for an AIR builder.
 pub trait Builder: Sized {
@@ -37,6 +58,16 @@ pub trait Builder: Sized {
 
     fn clk(&mut self) -> ElementRegister;
 
+    /// The number of rows of the trace, if it is fixed by one of the machines in the builder.
+    fn trace_length(&mut self) -> Option<usize> {
+        AirBuilder::trace_length(self.api())
+    }
+
+    /// Sets the padding policy of the trace, see `AirBuilder::set_padding_policy`.
+    fn set_padding_policy(&mut self, policy: PaddingPolicy) {
+        self.api().set_padding_policy(policy)
+    }
+
     /// Allocates a trace register.
     fn alloc<T: Register>(&mut self) -> T {
         self.api().alloc()
@@ -47,6 +78,12 @@ pub trait Builder: Sized {
         self.api().alloc_array(len)
     }
 
+    /// Allocates a matrix of trace registers with type `T`, `num_rows` rows and `num_cols`
+    /// columns.
+    fn alloc_matrix<T: Register>(&mut self, num_rows: usize, num_cols: usize) -> Matrix<T> {
+        self.api().alloc_matrix(num_rows, num_cols)
+    }
+
     /// Allocates a register in public inputs.
     fn alloc_public<T: Register>(&mut self) -> T {
         self.api().alloc_public()
@@ -57,11 +94,28 @@ pub trait Builder: Sized {
         self.api().alloc_array_public(len)
     }
 
+    /// Allocates a register whose value is computed by `hint` during trace generation. See
+    /// `AirBuilder::alloc_with_hint`.
+    fn alloc_with_hint<T: Register>(
+        &mut self,
+        hint: impl Fn(&HintReader<Self::Field>, usize) -> T::Value<Self::Field> + Send + Sync + 'static,
+    ) -> T {
+        self.api().alloc_with_hint(hint)
+    }
+
     /// Allocates a constant register with set value `value`.
     fn constant<T: Register>(&mut self, value: &T::Value<Self::Field>) -> T {
         self.api().constant(value)
     }
 
+    /// Returns the expression of the constant `value` without allocating a register.
+    ///
+    /// Literals can be used freely as coefficients in expressions. Use `constant` when a
+    /// materialized register is needed, e.g. to store the value in memory.
+    fn lit(&self, value: Self::Field) -> ArithmeticExpression<Self::Field> {
+        ArithmeticExpression::from(value)
+    }
+
     fn constant_array<T: Register>(
         &mut self,
         values: &[T::Value<Self::Field>],
@@ -128,11 +182,95 @@ pub trait Builder: Sized {
         self.api()
             .set(ptr, value, write_ts, multiplicity, label, index)
     }
+
+    /// Writes `value` to the memory at location `ptr` with write time given by `write_ts`, in a
+    /// slot whose multiplicity is the number of loads registered against it with `load_counted`.
+    fn store_counted<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        value: V,
+        write_ts: &Time<Self::Field>,
+        label: Option<String>,
+        index: Option<MemorySliceIndex>,
+    ) -> CountedSlot {
+        self.api().set_counted(ptr, value, write_ts, label, index)
+    }
+
+    /// Reads the memory at location `ptr` like `load`, where `ptr` is the counted slot `slot` in
+    /// the rows counted by `reads`.
+    ///
+    /// Panics if `ptr` can't point to the slot, see `AirBuilder::count_reads`.
+    fn load_counted<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        last_write_ts: &Time<Self::Field>,
+        label: Option<String>,
+        index: Option<MemorySliceIndex>,
+        slot: &CountedSlot,
+        reads: ReadCount,
+    ) -> V {
+        self.api().count_reads(slot, &ptr.raw, reads);
+        self.load(ptr, last_write_ts, label, index)
+    }
+
+    /// Reads the memory at location `ptr` in the rows where `filter` is set.
+    ///
+    /// In the other rows, nothing is read from the memory bus and the returned register is left
+    /// unconstrained.
+    fn load_filtered<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        last_write_ts: &Time<Self::Field>,
+        filter: BitRegister,
+    ) -> V {
+        self.api().get_filtered(ptr, last_write_ts, filter)
+    }
+
+    /// Reads the memory at location `ptr` if it is written, returning the value and a bit set if
+    /// the location is written with last write time given by `last_write_ts`.
+    ///
+    /// If the bit is not set, the value is zero and nothing is read from the memory bus. An unset
+    /// bit only shows that the location was not written if the multiplicity of its write counts
+    /// this read, see `AirBuilder::get_checked`.
+    fn load_checked<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        last_write_ts: &Time<Self::Field>,
+    ) -> (V, BitRegister) {
+        self.api().get_checked(ptr, last_write_ts)
+    }
+
+    /// Writes `value` to the memory at location `ptr` in the rows where `filter` is set.
+    ///
+    /// In the other rows, nothing is written to the memory bus, so their values don't affect the
+    /// memory argument.
+    fn store_filtered<V: MemoryValue>(
+        &mut self,
+        ptr: &Pointer<V>,
+        value: V,
+        write_ts: &Time<Self::Field>,
+        filter: BitRegister,
+    ) {
+        self.api().set_filtered(ptr, value, write_ts, filter)
+    }
+
     /// Frees the memory at location `ptr` with last write time given by `last_write_ts`.
     fn free<V: MemoryValue>(&mut self, ptr: &Pointer<V>, value: V, last_write: &Time<Self::Field>) {
         self.api().free(ptr, value, last_write)
     }
 
+    /// Creates a stack of values pushed in the rows where `push` is set and popped in the rows
+    /// where `pop` is set.
+    fn stack<V: MemoryValue>(&mut self, push: BitRegister, pop: BitRegister) -> Stack<V> {
+        Stack::new(self, push, pop)
+    }
+
+    /// Creates a queue of values enqueued in the rows where `enqueue` is set and dequeued in the
+    /// rows where `dequeue` is set.
+    fn queue<V: MemoryValue>(&mut self, enqueue: BitRegister, dequeue: BitRegister) -> Queue<V> {
+        Queue::new(self, enqueue, dequeue)
+    }
+
     /// Prints out a log message (using the log::debug! macro) with the value and multiplicity
     /// of the memory slot.
     ///
@@ -146,6 +284,45 @@ pub trait Builder: Sized {
         self.api().assert_equal(a, b)
     }
 
+    /// Asserts that the cubic extension elements `a` and `b` are equal, such as two digests
+    /// given by `RawPointer::accumulate_cubic`.
+    fn assert_cubic_equal(&mut self, a: &CubicRegister, b: &CubicRegister) {
+        self.api().assert_cubic_equal(a, b)
+    }
+
+    /// Asserts that the bytes of `register` equal the little-endian bytes of the constant `value`
+    /// in all rows of the trace.
+    fn assert_eq_const<const N: usize>(&mut self, register: &ByteArrayRegister<N>, value: u64) {
+        assert!(
+            N >= 8 || value >> (8 * N) == 0,
+            "The constant {} does not fit in {} bytes",
+            value,
+            N
+        );
+        let bytes = (0..N)
+            .map(|i| {
+                let byte = if i < 8 { (value >> (8 * i)) as u8 } else { 0 };
+                Self::Field::from_canonical_u8(byte)
+            })
+            .collect::<Vec<_>>();
+        self.assert_expressions_equal(
+            register.expr(),
+            ArithmeticExpression::from_constant_vec(bytes),
+        );
+    }
+
+    /// Asserts that `value` lies in `0..2^bits`. The checks of the same width share a lookup
+    /// table, see `AirBuilder::range_check`.
+    fn range_check(&mut self, value: &ElementRegister, bits: usize) {
+        self.api().range_check(value, bits)
+    }
+
+    /// Checks the trace values of every width against the table of `base_bits` bits, see
+    /// `AirBuilder::set_range_check_base`.
+    fn set_range_check_base(&mut self, base_bits: usize) {
+        self.api().set_range_check_base(base_bits)
+    }
+
     /// Asserts that `a = b` in the first row of the trace.
     fn assert_equal_first_row<T: Register>(&mut self, a: &T, b: &T) {
         self.api().assert_equal_first_row(a, b)
@@ -161,6 +338,16 @@ pub trait Builder: Sized {
         self.api().assert_equal_transition(a, b)
     }
 
+    /// Asserts that the trace register `register` has the same value in every row, by the
+    /// transition constraint `register.next() = register`.
+    fn assert_constant<T: Register>(&mut self, register: &T) {
+        assert!(
+            register.is_trace(),
+            "Only a trace register can vary between rows"
+        );
+        self.assert_equal_transition(&register.next(), register)
+    }
+
     /// Asserts that `expression = 0` in all rows of the trace.
     fn assert_expression_zero(&mut self, expression: ArithmeticExpression<Self::Field>) {
         self.api().assert_expression_zero(expression)
@@ -181,6 +368,17 @@ pub trait Builder: Sized {
         self.api().assert_expression_zero_transition(expression)
     }
 
+    /// Asserts that `expression = 0` in the rows of the trace where `selector` is set.
+    ///
+    /// The constraint is multiplied by `selector`, so it is vacuous in the other rows.
+    fn constrain_when(
+        &mut self,
+        selector: BitRegister,
+        expression: ArithmeticExpression<Self::Field>,
+    ) {
+        self.assert_expression_zero(selector.expr() * expression)
+    }
+
     /// Sets `dest` in the next row of the trace to equal the value of `src` in the current row.
     fn set_next<T: Register>(&mut self, dest: &T, src: &T) {
         self.set_to_expression_transition(&dest.next(), src.expr());
@@ -220,6 +418,43 @@ pub trait Builder: Sized {
         self.api().select(&flag, true_value, false_value)
     }
 
+    /// Returns the array equal to `true_value` if `flag` is one and to `false_value` otherwise.
+    fn select_array<T: Register>(
+        &mut self,
+        flag: BitRegister,
+        true_value: &ArrayRegister<T>,
+        false_value: &ArrayRegister<T>,
+    ) -> ArrayRegister<T> {
+        self.api().select_array(&flag, true_value, false_value)
+    }
+
+    /// Selects between the byte arrays of `true_value` and `false_value` in every position, for
+    /// byte arrays that are not the elements of a single array register.
+    fn select_byte_arrays<const N: usize>(
+        &mut self,
+        flag: BitRegister,
+        true_value: &[ByteArrayRegister<N>],
+        false_value: &[ByteArrayRegister<N>],
+    ) -> Vec<ByteArrayRegister<N>> {
+        assert_eq!(
+            true_value.len(),
+            false_value.len(),
+            "Cannot select between arrays of different lengths"
+        );
+        true_value
+            .iter()
+            .zip(false_value.iter())
+            .map(|(a, b)| self.select(flag, a, b))
+            .collect()
+    }
+
+    /// Returns `(a, b)` if `bit` is zero and `(b, a)` if `bit` is one.
+    fn conditional_swap<T: Register>(&mut self, bit: BitRegister, a: &T, b: &T) -> (T, T) {
+        let first = self.select(bit, b, a);
+        let second = self.select(bit, a, b);
+        (first, second)
+    }
+
     fn select_next<T: Register>(
         &mut self,
         flag: BitRegister,
@@ -281,6 +516,43 @@ pub trait Builder: Sized {
         register
     }
 
+    /// Returns a bit set in the rows where `expression` is zero.
+    fn is_zero(&mut self, expression: ArithmeticExpression<Self::Field>) -> BitRegister {
+        self.api().is_zero(expression)
+    }
+
+    /// Reads the entry of the constant `table` selected by `selector`.
+    ///
+    /// The selector must be one-hot: in every row, exactly the bit at the index of the entry to
+    /// read is set. Unlike a table stored in memory, the reads need no multiplicities or dummy
+    /// reads, since the table is part of the constraints.
+    fn read_constant_table<T: Register>(
+        &mut self,
+        table: &[T::Value<Self::Field>],
+        selector: &[BitRegister],
+    ) -> T {
+        assert_eq!(
+            table.len(),
+            selector.len(),
+            "The table and the selector must have the same length"
+        );
+        let expression = table
+            .iter()
+            .zip(selector.iter())
+            .map(|(value, bit)| {
+                bit.expr::<Self::Field>()
+                    * ArithmeticExpression::from_constant_vec(T::align(value).to_vec())
+            })
+            .reduce(|acc, term| acc + term)
+            .expect("The table must not be empty");
+        self.expression(expression)
+    }
+
+    /// Allocates a fresh trace register of type `T` constrained to be equal to `value`.
+    fn copy<T: Register>(&mut self, value: &T) -> T {
+        self.expression(value.expr())
+    }
+
     /// Prints out a log message (using the log::debug! macro) with the value of the register.
     ///
     /// The message will be presented with `RUST_LOG=debug` or `RUST_LOG=trace`.
@@ -288,6 +560,15 @@ pub trait Builder: Sized {
         self.api().watch(data, name);
     }
 
+    /// Runs `f` with `label` pushed onto the constraint tag stack, so that every constraint
+    /// registered by `f` carries `label` in `Chip::tag_labels`.
+    fn tagged<T>(&mut self, label: &str, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.api().push_tag(label);
+        let result = f(self);
+        self.api().pop_tag();
+        result
+    }
+
     /// Computes the expression `expression` and returns the result as a public register of type `T`.
     fn public_expression<T: Register>(
         &mut self,
@@ -298,6 +579,21 @@ pub trait Builder: Sized {
         register
     }
 
+    /// Computes the expression `expression` and returns the result as a global register.
+    ///
+    /// The expression can only depend on public inputs, challenges and other global values. The
+    /// value is committed together with the global values of the last round and constrained in
+    /// `eval_global`, so assertions over global registers become global constraints.
+    fn global_value(&mut self, expression: ArithmeticExpression<Self::Field>) -> ElementRegister {
+        assert!(
+            !expression.is_trace(),
+            "Cannot set a global value to a trace expression"
+        );
+        let register = self.api().alloc_global::<ElementRegister>();
+        self.api().set_to_expression_public(&register, expression);
+        register
+    }
+
     fn add<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::Add<Self, Rhs>>::Output
     where
         Lhs: Add<Self, Rhs>,
@@ -305,6 +601,18 @@ pub trait Builder: Sized {
         lhs.add(rhs, self)
     }
 
+    /// Computes `lhs + rhs` and constrains the addition to not overflow.
+    fn add_checked<Lhs, Rhs>(
+        &mut self,
+        lhs: Lhs,
+        rhs: Rhs,
+    ) -> <Lhs as ops::AddChecked<Self, Rhs>>::Output
+    where
+        Lhs: AddChecked<Self, Rhs>,
+    {
+        lhs.add_checked(rhs, self)
+    }
+
     fn double<T: Double<Self>>(&mut self, value: T) -> <T as Double<Self>>::Output {
         value.double(self)
     }
@@ -354,6 +662,42 @@ pub trait Builder: Sized {
         lhs.adc(rhs, carry, self)
     }
 
+    /// Returns a bit set if `lhs < rhs`.
+    fn lt<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::Lt<Self, Rhs>>::Output
+    where
+        Lhs: Lt<Self, Rhs>,
+    {
+        lhs.lt(rhs, self)
+    }
+
+    /// Returns a bit set if `lhs <= rhs`.
+    fn le<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::Le<Self, Rhs>>::Output
+    where
+        Lhs: Le<Self, Rhs>,
+    {
+        lhs.le(rhs, self)
+    }
+
+    /// Returns a bit set if `lhs > rhs`.
+    fn gt<T: Lt<Self>>(&mut self, lhs: T, rhs: T) -> <T as ops::Lt<Self>>::Output {
+        rhs.lt(lhs, self)
+    }
+
+    /// Returns a bit set if `lhs >= rhs`.
+    fn ge<T: Le<Self>>(&mut self, lhs: T, rhs: T) -> <T as ops::Le<Self>>::Output {
+        rhs.le(lhs, self)
+    }
+
+    /// Returns a bit set if `lhs == rhs`, as the conjunction of `lhs <= rhs` and `lhs >= rhs`.
+    fn eq<T>(&mut self, lhs: T, rhs: T) -> BitRegister
+    where
+        T: Le<Self, Output = BitRegister> + Copy,
+    {
+        let le = lhs.le(rhs, self);
+        let ge = rhs.le(lhs, self);
+        self.and(le, ge)
+    }
+
     fn and<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::And<Self, Rhs>>::Output
     where
         Lhs: And<Self, Rhs>,
@@ -379,6 +723,75 @@ pub trait Builder: Sized {
         lhs.xor(rhs, self)
     }
 
+    /// Asserts that `value` is a bit, by the constraint `value * (1 - value) = 0` in all rows of
+    /// the trace.
+    fn assert_bool(&mut self, value: &ElementRegister) {
+        self.assert_expression_zero(value.expr() * (ArithmeticExpression::one() - value.expr()))
+    }
+
+    /// The bit `a & b`, computed as `a * b`.
+    fn and_bits(&mut self, a: BitRegister, b: BitRegister) -> BitRegister {
+        self.and(a, b)
+    }
+
+    /// The bit `a | b`, computed as `a + b - a * b`.
+    fn or_bits(&mut self, a: BitRegister, b: BitRegister) -> BitRegister {
+        self.or(a, b)
+    }
+
+    /// The bit `a ^ b`, computed as `a + b - 2 * a * b`.
+    fn xor_bits(&mut self, a: BitRegister, b: BitRegister) -> BitRegister {
+        self.xor(a, b)
+    }
+
+    /// The bit `!a`, computed as `1 - a`.
+    fn not_bits(&mut self, a: BitRegister) -> BitRegister {
+        self.not(a)
+    }
+
+    /// The number of leading zero bits of `value`.
+    fn leading_zeros<T: LeadingZeros<Self>>(
+        &mut self,
+        value: T,
+    ) -> <T as LeadingZeros<Self>>::Output {
+        value.leading_zeros(self)
+    }
+
+    /// The number of set bits of `value`.
+    fn popcount<T: Popcount<Self>>(&mut self, value: T) -> <T as Popcount<Self>>::Output {
+        value.popcount(self)
+    }
+
+    /// The choose function of `e`, `f` and `g`, taking the bits of `f` where `e` is set and the
+    /// bits of `g` elsewhere.
+    ///
+    /// Computed as `g ^ (e & (f ^ g))`, with three operations instead of the four of
+    /// `(e & f) ^ (!e & g)`.
+    fn ch<T>(&mut self, e: T, f: T, g: T) -> T
+    where
+        T: Copy + And<Self, Output = T> + Xor<Self, Output = T>,
+    {
+        let f_xor_g = self.xor(f, g);
+        let masked = self.and(e, f_xor_g);
+        self.xor(g, masked)
+    }
+
+    /// The majority function of `a`, `b` and `c`, taking the value of the majority of the three
+    /// bits at every position.
+    ///
+    /// Computed as `(a & b) ^ (c & (a ^ b))`, with four operations instead of the five of
+    /// `(a & b) ^ (a & c) ^ (b & c)`. The two terms have no bit in common, so the XOR is also
+    /// their OR.
+    fn maj<T>(&mut self, a: T, b: T, c: T) -> T
+    where
+        T: Copy + And<Self, Output = T> + Xor<Self, Output = T>,
+    {
+        let a_and_b = self.and(a, b);
+        let a_xor_b = self.xor(a, b);
+        let c_and_a_xor_b = self.and(c, a_xor_b);
+        self.xor(a_and_b, c_and_a_xor_b)
+    }
+
     fn shl<Lhs, Rhs>(&mut self, lhs: Lhs, rhs: Rhs) -> <Lhs as ops::Shl<Self, Rhs>>::Output
     where
         Lhs: Shl<Self, Rhs>,
@@ -419,6 +832,158 @@ pub trait Builder: Sized {
         self.api().cycle(length_log)
     }
 
+    /// Allocates for every period of `periods` a bit set in the last row of every `period` rows,
+    /// that is in the rows whose index is `period - 1` modulo `period`.
+    ///
+    /// A period `2^k * m` with `m` odd is composed of a cycle of `2^k` rows and a loop of `m`
+    /// iterations, whose end bits are both set exactly in the rows at `-1` modulo the period. The
+    /// cycles and loops are shared between the periods.
+    fn period_end_bits(&mut self, periods: &[usize]) -> Vec<BitRegister> {
+        let mut cycle_end_bits = BTreeMap::<usize, BitRegister>::new();
+        let mut loop_end_bits = BTreeMap::<usize, BitRegister>::new();
+        let mut end_bits = BTreeMap::<usize, BitRegister>::new();
+
+        for &period in periods {
+            assert!(period > 1, "A period must be greater than one");
+            if end_bits.contains_key(&period) {
+                continue;
+            }
+            let length_log = period.trailing_zeros() as usize;
+            let num_iterations = period >> length_log;
+
+            let cycle_end_bit = (length_log > 0).then(|| {
+                *cycle_end_bits
+                    .entry(length_log)
+                    .or_insert_with(|| self.cycle(length_log).end_bit)
+            });
+            let loop_end_bit = (num_iterations > 1).then(|| {
+                *loop_end_bits.entry(num_iterations).or_insert_with(|| {
+                    self.api()
+                        .loop_instr(num_iterations)
+                        .get_iteration_reg(num_iterations - 1)
+                })
+            });
+
+            let end_bit = match (cycle_end_bit, loop_end_bit) {
+                (Some(cycle_end_bit), Some(loop_end_bit)) => self.mul(loop_end_bit, cycle_end_bit),
+                (Some(end_bit), None) | (None, Some(end_bit)) => end_bit,
+                (None, None) => unreachable!(),
+            };
+            end_bits.insert(period, end_bit);
+        }
+
+        periods.iter().map(|period| end_bits[period]).collect()
+    }
+
+    /// Allocates a control-flow register following the specification `flag`.
+    ///
+    /// The constraints of the register are tagged with the name of the flag.
+    fn cyclic_flag<T: Register>(&mut self, flag: &CyclicFlag<Self::Field>) -> T {
+        assert_eq!(T::size_of(), 1, "A cyclic flag must be a single column");
+        self.tagged(&flag.name, |builder| {
+            let register = builder.alloc::<T>();
+            builder.set_to_expression_first_row(&register, flag.first_row.into());
+            builder.set_to_expression_transition(&register.next(), flag.transition(&register));
+            register
+        })
+    }
+
+    /// Allocates a register holding the running sum of `increment`.
+    ///
+    /// The register is zero in the first row, and in any other row it is the sum of the values
+    /// of `increment` in all the previous rows.
+    fn running_sum(&mut self, increment: ArithmeticExpression<Self::Field>) -> ElementRegister {
+        let sum = self.alloc::<ElementRegister>();
+        self.set_to_expression_first_row(&sum, Self::Field::ZERO.into());
+        self.set_to_expression_transition(&sum.next(), sum.expr() + increment);
+        sum
+    }
+
+    /// Allocates a register holding the running product of `factor`.
+    ///
+    /// The register is one in the first row, and in any other row it is the product of the
+    /// values of `factor` in all the previous rows.
+    fn running_product(&mut self, factor: ArithmeticExpression<Self::Field>) -> ElementRegister {
+        let product = self.alloc::<ElementRegister>();
+        self.set_to_expression_first_row(&product, Self::Field::ONE.into());
+        self.set_to_expression_transition(&product.next(), product.expr() * factor);
+        product
+    }
+
+    /// Allocates the selector bits of the state machine `machine`, one for each of its states.
+    ///
+    /// The selectors follow the transitions of the machine, and exactly one of them is
+    /// constrained to be set in every row. The constraints are tagged with the name of the
+    /// machine.
+    fn state_machine<S: Copy + Eq + core::fmt::Debug>(
+        &mut self,
+        machine: &StateMachine<S>,
+    ) -> StateSelectors<S> {
+        self.tagged(&machine.name, |builder| {
+            let selectors = builder.alloc_array::<BitRegister>(machine.states.len());
+            let initial = machine.index(machine.initial);
+            for (i, selector) in selectors.iter().enumerate() {
+                let first_row = Self::Field::from_canonical_u8((i == initial) as u8);
+                builder.set_to_expression_first_row(&selector, first_row.into());
+            }
+            for (selector, next) in selectors.iter().zip(machine.next_selectors(&selectors)) {
+                builder.set_to_expression_transition(&selector.next(), next);
+            }
+            let sum = selectors
+                .iter()
+                .fold(ArithmeticExpression::zero(), |acc, selector| {
+                    acc + selector.expr()
+                });
+            builder.assert_expression_zero(sum - Self::Field::ONE);
+            StateSelectors::new(machine.states.clone(), selectors)
+        })
+    }
+
+    /// Repeats the sub-circuit built by `f` over cycles of `num_iterations` rows, each row of a
+    /// cycle being one iteration.
+    ///
+    /// `f` is given the index of the iteration within the cycle and the state carried into the
+    /// iteration, and returns the state carried out of it. The state carried into the first
+    /// iteration of every cycle is `initial`, and into any other iteration the state carried out
+    /// of the previous one. The number of iterations must be a power of two.
+    fn repeat<T: Register>(
+        &mut self,
+        num_iterations: usize,
+        initial: &T,
+        f: impl FnOnce(&mut Self, ElementRegister, &T) -> T,
+    ) -> Repetition<T> {
+        assert!(
+            num_iterations.is_power_of_two(),
+            "The number of iterations must be a power of two"
+        );
+        let cycle = self.cycle(num_iterations.trailing_zeros() as usize);
+        let end_bit = cycle.end_bit;
+        let index = self.cyclic_flag(&CyclicFlag::counter("repeat_index", end_bit));
+
+        // The first row is set before the sub-circuit reads it, and the transition after the
+        // sub-circuit writes the output it is computed from.
+        let state = self.alloc::<T>();
+        self.set_to_expression_first_row(&state, initial.expr());
+        let output = f(self, index, &state);
+
+        let initial_next = if initial.is_trace() {
+            initial.next().expr()
+        } else {
+            initial.expr()
+        };
+        self.set_to_expression_transition(
+            &state.next(),
+            end_bit.expr() * initial_next + end_bit.not_expr() * output.expr(),
+        );
+
+        Repetition {
+            index,
+            end_bit,
+            state,
+            output,
+        }
+    }
+
     /// `process_id` is a register is computed by counting the number of cycles. We do this by
     /// setting `process_id` to be the cumulative sum of the `end_bit` of each cycle.
     fn process_id(&mut self, size: usize, end_bit: BitRegister) -> ElementRegister {
@@ -436,6 +1001,33 @@ pub trait Builder: Sized {
     {
         self.api().bit_decomposition(limb, start_bit, end_bit)
     }
+
+    /// Decomposes `value` into `num_digits` little-endian digits in base `radix`, each range
+    /// checked to `[0, radix)`.
+    fn decompose_radix(
+        &mut self,
+        value: &ElementRegister,
+        radix: u64,
+        num_digits: usize,
+    ) -> ArrayRegister<ElementRegister>
+    where
+        Self::Instruction: From<RadixInstruction>,
+    {
+        self.api().decompose_radix(value, radix, num_digits)
+    }
+
+    /// Divides `value` by the constant `divisor`, returning the quotient and the remainder, which
+    /// is range checked to `[0, divisor)`.
+    fn div_rem_const(
+        &mut self,
+        value: &ElementRegister,
+        divisor: u64,
+    ) -> (ElementRegister, ElementRegister)
+    where
+        Self::Instruction: From<DivRemConstInstruction>,
+    {
+        self.api().div_rem_const(value, divisor)
+    }
 }
 
 impl<L: AirParameters> Builder for AirBuilder<L> {
@@ -452,3 +1044,758 @@ impl<L: AirParameters> Builder for AirBuilder<L> {
         self.clock()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::uint::register::U64Register;
+    use crate::chip::uint::util::u64_to_le_field_bytes;
+    use crate::math::extension::cubic::element::CubicElement;
+    use crate::math::prelude::*;
+    use crate::trace::window::TraceWindow;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct CopyTest;
+
+    impl AirParameters for CopyTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 16;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ConditionalSwapTest;
+
+    impl AirParameters for ConditionalSwapTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 33;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SelectArrayTest;
+
+    impl AirParameters for SelectArrayTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 129;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ConstrainWhenTest;
+
+    impl AirParameters for ConstrainWhenTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 3;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PeriodEndBitsTest;
+
+    impl AirParameters for PeriodEndBitsTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 20;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct LiteralTest;
+
+    impl AirParameters for LiteralTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 2;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct AssertConstTest;
+
+    impl AirParameters for AssertConstTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 8;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct CubicEqualTest;
+
+    impl AirParameters for CubicEqualTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 6;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct AssertConstantTest;
+
+    impl AirParameters for AssertConstantTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 1;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct BitOpsTest;
+
+    impl AirParameters for BitOpsTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 6;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct GlobalValueTest;
+
+    impl AirParameters for GlobalValueTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 1;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RunningTest;
+
+    impl AirParameters for RunningTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 1;
+    }
+
+    #[test]
+    fn test_copy() {
+        type L = CopyTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<U64Register>();
+        let b = builder.copy(&a);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let value = (i as u64).wrapping_mul(0x0123_4567_89ab_cdef);
+            writer.write(&a, &u64_to_le_field_bytes(value), i);
+            writer.write_row_instructions(&generator.air_data, i);
+            assert_eq!(writer.read(&b, i), writer.read(&a, i));
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_conditional_swap() {
+        type F = GoldilocksField;
+        type L = ConditionalSwapTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let bit = builder.alloc::<BitRegister>();
+        let a = builder.alloc::<U64Register>();
+        let b = builder.alloc::<U64Register>();
+        let (first, second) = builder.conditional_swap(bit, &a, &b);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let swap = i % 2 == 1;
+            let a_val = u64_to_le_field_bytes(i as u64);
+            let b_val = u64_to_le_field_bytes(u64::MAX - i as u64);
+            writer.write(&bit, &F::from_canonical_u8(swap as u8), i);
+            writer.write(&a, &a_val, i);
+            writer.write(&b, &b_val, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let (expected_first, expected_second) =
+                if swap { (b_val, a_val) } else { (a_val, b_val) };
+            assert_eq!(writer.read(&first, i), expected_first);
+            assert_eq!(writer.read(&second, i), expected_second);
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    /// Computes `3 * a + 5` in every row, with the constants given as literals or as registers,
+    /// and returns the number of public values of the AIR with the computed values.
+    fn affine_map(use_literals: bool) -> (usize, Vec<GoldilocksField>) {
+        type F = GoldilocksField;
+        type L = LiteralTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<ElementRegister>();
+        let (scale, offset) = if use_literals {
+            (
+                builder.lit(F::from_canonical_u32(3)),
+                builder.lit(F::from_canonical_u32(5)),
+            )
+        } else {
+            let scale = builder.constant::<ElementRegister>(&F::from_canonical_u32(3));
+            let offset = builder.constant::<ElementRegister>(&F::from_canonical_u32(5));
+            (scale.expr(), offset.expr())
+        };
+        let result = builder.expression::<ElementRegister>(a.expr() * scale + offset);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write_global_instructions(&generator.air_data);
+        for i in 0..num_rows {
+            writer.write(&a, &F::from_canonical_usize(i), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let trace = generator.trace_clone();
+        let public = writer.public().unwrap();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &public);
+            air.eval(&mut window_parser);
+        }
+
+        let values = (0..num_rows).map(|i| writer.read(&result, i)).collect();
+        (air.num_public_values, values)
+    }
+
+    #[test]
+    fn test_select_array() {
+        type F = GoldilocksField;
+        type L = SelectArrayTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let bit = builder.alloc::<BitRegister>();
+        let a = builder.alloc_array::<U64Register>(4);
+        let b = builder.alloc_array::<U64Register>(4);
+        let selected = builder.select_array(&bit, &a, &b);
+        let selected_bytes = Builder::select_byte_arrays(
+            &mut builder,
+            bit,
+            &a.iter().collect::<Vec<_>>(),
+            &b.iter().collect::<Vec<_>>(),
+        );
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let flag = i % 2 == 0;
+            let a_val = (0..4)
+                .map(|j| u64_to_le_field_bytes((i * 4 + j) as u64))
+                .collect::<Vec<_>>();
+            let b_val = (0..4)
+                .map(|j| u64_to_le_field_bytes(u64::MAX - (i * 4 + j) as u64))
+                .collect::<Vec<_>>();
+            writer.write(&bit, &F::from_canonical_u8(flag as u8), i);
+            writer.write_array(&a, &a_val, i);
+            writer.write_array(&b, &b_val, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let expected = if flag { &a_val } else { &b_val };
+            assert_eq!(&writer.read_vec(&selected, i), expected);
+            for (register, value) in selected_bytes.iter().zip(expected.iter()) {
+                assert_eq!(writer.read(register, i), *value);
+            }
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "different lengths")]
+    fn test_select_array_different_lengths() {
+        let mut builder = AirBuilder::<SelectArrayTest>::new();
+        let bit = builder.alloc::<BitRegister>();
+        let a = builder.alloc_array::<U64Register>(4);
+        let b = builder.alloc_array::<U64Register>(3);
+        builder.select_array(&bit, &a, &b);
+    }
+
+    #[test]
+    fn test_lit() {
+        type F = GoldilocksField;
+
+        let (num_literal_public_values, literal_values) = affine_map(true);
+        let (num_constant_public_values, constant_values) = affine_map(false);
+
+        assert_eq!(literal_values, constant_values);
+        for (i, value) in literal_values.iter().enumerate() {
+            assert_eq!(*value, F::from_canonical_usize(3 * i + 5));
+        }
+        assert_eq!(num_literal_public_values, 0);
+        assert_eq!(num_constant_public_values, 2);
+    }
+
+    /// Evaluates the assertion that a register holding `value` in every row equals `constant`.
+    fn eval_assert_eq_const(value: u64, constant: u64) {
+        type L = AssertConstTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<U64Register>();
+        builder.assert_eq_const(&a, constant);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&a, &u64_to_le_field_bytes(value), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_assert_eq_const() {
+        eval_assert_eq_const(0x6a09e667f3bcc908, 0x6a09e667f3bcc908);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_eq_const_wrong_constant() {
+        eval_assert_eq_const(0x6a09e667f3bcc908, 0x6a09e667f3bcc909);
+    }
+
+    /// Evaluates the assertion that two cubic digests are equal, with the second one changed at
+    /// the `(row, coordinate)` given by `violation`, if any.
+    fn eval_assert_cubic_equal(violation: Option<(usize, usize)>) {
+        type F = GoldilocksField;
+        type L = CubicEqualTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<CubicRegister>();
+        let b = builder.alloc::<CubicRegister>();
+        builder.assert_cubic_equal(&a, &b);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let value = CubicElement([i, 3 * i + 1, 7 * i + 2].map(F::from_canonical_usize));
+            let mut other = value;
+            if let Some((row, coordinate)) = violation {
+                if row == i {
+                    other.0[coordinate] = other.0[coordinate] + F::ONE;
+                }
+            }
+            writer.write(&a, &value, i);
+            writer.write(&b, &other, i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_assert_cubic_equal() {
+        eval_assert_cubic_equal(None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_cubic_equal_first_coordinate() {
+        eval_assert_cubic_equal(Some((4, 0)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_cubic_equal_last_coordinate() {
+        eval_assert_cubic_equal(Some((17, 2)));
+    }
+
+    /// Evaluates the assertion that a column is constant, with the value `value(i)` in row `i`.
+    fn eval_assert_constant(value: impl Fn(usize) -> u64) {
+        type F = GoldilocksField;
+        type L = AssertConstantTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<ElementRegister>();
+        builder.assert_constant(&a);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&a, &F::from_canonical_u64(value(i)), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_assert_constant() {
+        type F = GoldilocksField;
+        type L = AssertConstantTest;
+
+        eval_assert_constant(|_| 0x0123_4567);
+
+        // The constraint does not apply from the last row, whose next row wraps around.
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<ElementRegister>();
+        builder.assert_constant(&a);
+        let (air, _) = builder.build();
+        let (local, next) = ([F::from_canonical_u32(1)], [F::from_canonical_u32(2)]);
+        let window = TraceWindow {
+            local_slice: &local,
+            next_slice: &next,
+            row: 31,
+            is_first_row: false,
+            is_last_row: true,
+        };
+        let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+        air.eval(&mut window_parser);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_constant_varying() {
+        eval_assert_constant(|i| if i == 12 { 1 } else { 0 });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_constant_varying_last_row() {
+        eval_assert_constant(|i| if i == 31 { 1 } else { 0 });
+    }
+
+    /// Evaluates `a = b` gated on every third row, with `a != b` in all the other rows and in the
+    /// selected row `violation`, if any.
+    fn eval_constrain_when(violation: Option<usize>) {
+        type F = GoldilocksField;
+        type L = ConstrainWhenTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let selector = builder.alloc::<BitRegister>();
+        let a = builder.alloc::<ElementRegister>();
+        let b = builder.alloc::<ElementRegister>();
+        builder.constrain_when(selector, a.expr() - b.expr());
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let selected = i % 3 == 0;
+            let equal = selected && violation != Some(i);
+            writer.write(&selector, &F::from_canonical_u8(selected as u8), i);
+            writer.write(&a, &F::from_canonical_usize(i), i);
+            writer.write(&b, &F::from_canonical_usize(i + !equal as usize), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_constrain_when() {
+        eval_constrain_when(None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_constrain_when_violation() {
+        eval_constrain_when(Some(9));
+    }
+
+    #[test]
+    fn test_bit_ops() {
+        type F = GoldilocksField;
+        type L = BitOpsTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<BitRegister>();
+        let b = builder.alloc::<BitRegister>();
+        let and = builder.and_bits(a, b);
+        let or = builder.or_bits(a, b);
+        let xor = builder.xor_bits(a, b);
+        let not = builder.not_bits(a);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            // Go through the truth table of the operations.
+            let (a_val, b_val) = (i & 1 == 1, i & 2 == 2);
+            writer.write(&a, &F::from_canonical_u8(a_val as u8), i);
+            writer.write(&b, &F::from_canonical_u8(b_val as u8), i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let bit = |value: bool| F::from_canonical_u8(value as u8);
+            assert_eq!(writer.read(&and, i), bit(a_val & b_val));
+            assert_eq!(writer.read(&or, i), bit(a_val | b_val));
+            assert_eq!(writer.read(&xor, i), bit(a_val ^ b_val));
+            assert_eq!(writer.read(&not, i), bit(!a_val));
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    /// Evaluates `assert_bool` on a register holding `value(i)` in row `i`.
+    fn eval_assert_bool(value: impl Fn(usize) -> u64) {
+        type F = GoldilocksField;
+        type L = BitOpsTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc::<ElementRegister>();
+        builder.assert_bool(&a);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&a, &F::from_canonical_u64(value(i)), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_assert_bool() {
+        eval_assert_bool(|i| (i % 2) as u64);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_bool_two() {
+        eval_assert_bool(|i| if i == 7 { 2 } else { 1 });
+    }
+
+    /// Evaluates an AIR with global values computed from the public inputs `a = 7` and `b = 3`,
+    /// and a global register `c`, written as `c_value`, which is only constrained by the
+    /// assertion `c = a * b`.
+    fn eval_global_value(c_value: u32) {
+        type F = GoldilocksField;
+        type L = GlobalValueTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let a = builder.alloc_public::<ElementRegister>();
+        let b = builder.alloc_public::<ElementRegister>();
+        let sum = builder.global_value(a.expr() + b.expr());
+        let difference = builder.global_value(a.expr() - b.expr());
+        let product = builder.global_value(sum.expr() * difference.expr());
+        builder.assert_expressions_equal(sum.expr() + difference.expr(), a.expr() + a.expr());
+        builder.assert_expressions_equal(product.expr(), a.expr() * a.expr() - b.expr() * b.expr());
+        let c = builder.alloc_global::<ElementRegister>();
+        builder.assert_expressions_equal(c.expr(), a.expr() * b.expr());
+        let x = builder.alloc::<ElementRegister>();
+        builder.assert_expressions_equal(x.expr(), sum.expr());
+
+        let (air, trace_data) = builder.build();
+        assert_eq!(air.num_global_values, 4);
+        // Only the assertion involving the trace register `x` is a constraint of the rows.
+        assert_eq!(air.constraints().len(), 1);
+
+        let num_rows = 1 << 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        writer.write(&a, &F::from_canonical_u32(7), 0);
+        writer.write(&b, &F::from_canonical_u32(3), 0);
+        writer.write_global_instructions(&generator.air_data);
+        writer.write(&c, &F::from_canonical_u32(c_value), 0);
+        for i in 0..num_rows {
+            writer.write(&x, &F::from_canonical_u32(10), i);
+        }
+
+        assert_eq!(writer.read(&sum, 0), F::from_canonical_u32(10));
+        assert_eq!(writer.read(&difference, 0), F::from_canonical_u32(4));
+        assert_eq!(writer.read(&product, 0), F::from_canonical_u32(40));
+
+        let global = writer.global().unwrap();
+        let public = writer.public().unwrap();
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &global, &public);
+            air.eval(&mut window_parser);
+        }
+        let mut global_parser = TraceWindowParser::new(TraceWindow::empty(), &[], &global, &public);
+        air.eval_global(&mut global_parser);
+    }
+
+    #[test]
+    fn test_global_value() {
+        eval_global_value(21);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_global_value_invalid() {
+        eval_global_value(22);
+    }
+
+    #[test]
+    fn test_period_end_bits() {
+        type F = GoldilocksField;
+        type L = PeriodEndBitsTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let periods = [4, 8, 12, 96];
+        let end_bits = builder.period_end_bits(&periods);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 9;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for (period, end_bit) in periods.iter().zip(end_bits.iter()) {
+            for i in 0..num_rows {
+                let is_end = (i + 1) % period == 0;
+                assert_eq!(
+                    writer.read(end_bit, i),
+                    F::from_canonical_usize(is_end as usize),
+                    "end bit of period {period} in row {i}"
+                );
+            }
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_running_sum() {
+        type F = GoldilocksField;
+        type L = RunningTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let c = F::from_canonical_u32(7);
+        let sum = builder.running_sum(c.into());
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 6;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in 0..num_rows {
+            assert_eq!(writer.read(&sum, i), F::from_canonical_usize(i) * c);
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_running_product() {
+        type F = GoldilocksField;
+        type L = RunningTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let c = F::from_canonical_u32(3);
+        let product = builder.running_product(c.into());
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 6;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        let mut power = F::ONE;
+        for i in 0..num_rows {
+            assert_eq!(writer.read(&product, i), power);
+            power *= c;
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+}
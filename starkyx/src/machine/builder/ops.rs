@@ -10,6 +10,15 @@ pub trait Add<B: Builder, Rhs = Self> {
     fn add(self, rhs: Rhs, builder: &mut B) -> Self::Output;
 }
 
+/// The addition operation with an overflow check.
+///
+/// Types implementing this trait can be used within the `builder.add_checked(lhs, rhs)` method.
+pub trait AddChecked<B: Builder, Rhs = Self> {
+    type Output;
+
+    fn add_checked(self, rhs: Rhs, builder: &mut B) -> Self::Output;
+}
+
 /// The doubling operation.
 ///
 /// Types implementing this trait can be used within the `builder.double(value)` method.
@@ -83,6 +92,26 @@ pub trait Adc<B: Builder, Rhs = Self, Carry = BitRegister> {
     fn adc(self, rhs: Rhs, carry: Carry, builder: &mut B) -> Self::Output;
 }
 
+/// The unsigned less-than comparison.
+///
+/// Types implementing this trait can be used within the `builder.lt(lhs, rhs)` and
+/// `builder.gt(lhs, rhs)` methods.
+pub trait Lt<B: Builder, Rhs = Self> {
+    type Output;
+
+    fn lt(self, rhs: Rhs, builder: &mut B) -> Self::Output;
+}
+
+/// The unsigned less-than-or-equal comparison.
+///
+/// Types implementing this trait can be used within the `builder.le(lhs, rhs)`,
+/// `builder.ge(lhs, rhs)` and `builder.eq(lhs, rhs)` methods.
+pub trait Le<B: Builder, Rhs = Self> {
+    type Output;
+
+    fn le(self, rhs: Rhs, builder: &mut B) -> Self::Output;
+}
+
 /// The bitwise AND operation.
 ///
 /// Types implementing this trait can be used within the `builder.and(lhs, rhs)` method.
@@ -110,6 +139,24 @@ pub trait Or<B: Builder, Rhs = Self> {
     fn or(self, rhs: Rhs, builder: &mut B) -> Self::Output;
 }
 
+/// The number of leading zero bits of a value.
+///
+/// Types implementing this trait can be used within the `builder.leading_zeros(value)` method.
+pub trait LeadingZeros<B: Builder> {
+    type Output;
+
+    fn leading_zeros(self, builder: &mut B) -> Self::Output;
+}
+
+/// The population count, the number of set bits of a value.
+///
+/// Types implementing this trait can be used within the `builder.popcount(value)` method.
+pub trait Popcount<B: Builder> {
+    type Output;
+
+    fn popcount(self, builder: &mut B) -> Self::Output;
+}
+
 /// The bitwise XOR operation.
 ///
 /// Types implementing this trait can be used within the `builder.xor(lhs, rhs)` method.
@@ -0,0 +1,239 @@
+use core::ops::Range;
+
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::Challenger;
+use plonky2::plonk::config::Hasher;
+use serde::{Deserialize, Serialize};
+
+use super::Builder;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
+
+/// A named public value, held in `range` of the public inputs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PublicInput {
+    pub name: String,
+    pub range: Range<usize>,
+}
+
+/// A layout of named public values, ordered by their position in the public inputs.
+///
+/// The prover observes the public inputs at the start of the transcript, so that a layout
+/// covering all of them observes the same values with `observe`, and `commitment` hashes them in
+/// the same order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicInputs {
+    inputs: Vec<PublicInput>,
+}
+
+impl PublicInputs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allocates a public register named `name`.
+    pub fn alloc<B: Builder, T: Register>(&mut self, builder: &mut B, name: &str) -> T {
+        let register = builder.alloc_public::<T>();
+        self.insert(name, &register);
+        register
+    }
+
+    /// Allocates a public array of `length` registers named `name`.
+    pub fn alloc_array<B: Builder, T: Register>(
+        &mut self,
+        builder: &mut B,
+        name: &str,
+        length: usize,
+    ) -> ArrayRegister<T> {
+        let array = builder.alloc_array_public::<T>(length);
+        self.insert(name, &array);
+        array
+    }
+
+    /// Names the public register, or array of registers, `register`.
+    pub fn insert(&mut self, name: &str, register: &impl RegisterSerializable) {
+        assert!(
+            self.get(name).is_none(),
+            "The public input {} is declared twice",
+            name
+        );
+        let range = match register.register() {
+            MemorySlice::Public(index, length) => *index..*index + *length,
+            _ => panic!("The input {} is not a public register", name),
+        };
+        let position = self
+            .inputs
+            .partition_point(|input| input.range.start < range.start);
+        let overlaps_previous = position > 0 && self.inputs[position - 1].range.end > range.start;
+        let overlaps_next = self
+            .inputs
+            .get(position)
+            .map_or(false, |input| input.range.start < range.end);
+        assert!(
+            !overlaps_previous && !overlaps_next,
+            "The public input {} overlaps another input",
+            name
+        );
+        self.inputs.insert(
+            position,
+            PublicInput {
+                name: name.to_string(),
+                range,
+            },
+        );
+    }
+
+    /// The named inputs, in the order of the public inputs.
+    pub fn layout(&self) -> &[PublicInput] {
+        &self.inputs
+    }
+
+    /// The input named `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&PublicInput> {
+        self.inputs.iter().find(|input| input.name == name)
+    }
+
+    /// The number of public values held by the named inputs.
+    pub fn num_public_inputs(&self) -> usize {
+        self.inputs.iter().map(|input| input.range.len()).sum()
+    }
+
+    /// The values of the input named `name` in `public_inputs`.
+    pub fn read<'a, T>(&self, name: &str, public_inputs: &'a [T]) -> &'a [T] {
+        let input = self
+            .get(name)
+            .unwrap_or_else(|| panic!("The public input {} is not declared", name));
+        &public_inputs[input.range.clone()]
+    }
+
+    /// The values of the named inputs, in the order of the layout.
+    pub fn values<T: Copy>(&self, public_inputs: &[T]) -> Vec<T> {
+        self.inputs
+            .iter()
+            .flat_map(|input| public_inputs[input.range.clone()].iter().copied())
+            .collect()
+    }
+
+    /// Observes the values of the named inputs, in the order of the layout.
+    pub fn observe<F: RichField, H: Hasher<F>>(
+        &self,
+        challenger: &mut Challenger<F, H>,
+        public_inputs: &[F],
+    ) {
+        challenger.observe_elements(&self.values(public_inputs));
+    }
+
+    /// The hash of the values of the named inputs, in the order of the layout.
+    pub fn commitment<F: RichField, H: Hasher<F>>(&self, public_inputs: &[F]) -> H::Hash {
+        H::hash_no_pad(&self.values(public_inputs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::hash::poseidon::PoseidonHash;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::air::RAirData;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::uint::register::U64Register;
+    use crate::chip::AirParameters;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PublicInputsTest;
+
+    impl AirParameters for PublicInputsTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 1;
+    }
+
+    /// The public inputs of hashing two messages of one chunk, declared in a different order
+    /// than they are allocated.
+    fn blake2b_public_inputs() -> (AirBuilder<PublicInputsTest>, PublicInputs) {
+        let mut builder = AirBuilder::<PublicInputsTest>::new();
+        let mut inputs = PublicInputs::new();
+        let _ = builder.alloc::<ElementRegister>();
+
+        let padded_chunks = builder.alloc_array_public::<U64Register>(32);
+        let t_values = builder.alloc_array_public::<U64Register>(2);
+        inputs.alloc_array::<_, ElementRegister>(&mut builder, "digest_indices", 2);
+        inputs.alloc::<_, ElementRegister>(&mut builder, "num_messages");
+        inputs.insert("t_values", &t_values);
+        inputs.insert("padded_chunks", &padded_chunks);
+        (builder, inputs)
+    }
+
+    #[test]
+    fn test_public_inputs_layout() {
+        let (builder, inputs) = blake2b_public_inputs();
+        let names = inputs
+            .layout()
+            .iter()
+            .map(|input| input.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "padded_chunks",
+                "t_values",
+                "digest_indices",
+                "num_messages"
+            ]
+        );
+        assert_eq!(inputs.get("padded_chunks").unwrap().range, 0..256);
+        assert_eq!(inputs.get("num_messages").unwrap().range, 274..275);
+
+        // The layout only depends on the allocations.
+        let (_, other_inputs) = blake2b_public_inputs();
+        assert_eq!(inputs, other_inputs);
+
+        let (air, _) = builder.build();
+        assert_eq!(inputs.num_public_inputs(), air.num_public_inputs());
+    }
+
+    #[test]
+    fn test_public_inputs_commitment() {
+        type F = GoldilocksField;
+
+        let (_, inputs) = blake2b_public_inputs();
+        let public_inputs = F::rand_vec(inputs.num_public_inputs());
+        assert_eq!(
+            inputs.read("digest_indices", &public_inputs),
+            &public_inputs[272..274]
+        );
+
+        // A layout of all the public inputs observes them as the prover does.
+        let mut challenger = Challenger::<F, PoseidonHash>::new();
+        inputs.observe(&mut challenger, &public_inputs);
+        let mut expected_challenger = Challenger::<F, PoseidonHash>::new();
+        expected_challenger.observe_elements(&public_inputs);
+        assert_eq!(
+            challenger.get_challenge(),
+            expected_challenger.get_challenge()
+        );
+
+        assert_eq!(
+            inputs.commitment::<F, PoseidonHash>(&public_inputs),
+            PoseidonHash::hash_no_pad(&public_inputs)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "declared twice")]
+    fn test_public_inputs_duplicate_name() {
+        let mut builder = AirBuilder::<PublicInputsTest>::new();
+        let mut inputs = PublicInputs::new();
+        inputs.alloc::<_, ElementRegister>(&mut builder, "num_messages");
+        inputs.alloc::<_, ElementRegister>(&mut builder, "num_messages");
+    }
+}
@@ -0,0 +1,99 @@
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+
+/// The registers of a sub-circuit repeated over cycles of rows by `Builder::repeat`.
+///
+/// Every row is an iteration of the sub-circuit, and every cycle of rows is a run of all the
+/// iterations.
+#[derive(Debug, Clone, Copy)]
+pub struct Repetition<T> {
+    /// The index of the iteration within the cycle.
+    pub index: ElementRegister,
+    /// A bit set in the last iteration of every cycle.
+    pub end_bit: BitRegister,
+    /// The state carried into the iteration.
+    pub state: T,
+    /// The state carried out of the iteration.
+    pub output: T,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::chip::AirParameters;
+    use crate::machine::builder::Builder;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RepeatTest;
+
+    impl AirParameters for RepeatTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 9;
+    }
+
+    #[test]
+    fn test_repeat_accumulator() {
+        type F = GoldilocksField;
+        type L = RepeatTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let start = builder.alloc::<ElementRegister>();
+
+        // Adds `index + 1` to the state in every iteration.
+        let repetition = builder.repeat(8, &start, |builder, index, state| {
+            builder.expression::<ElementRegister>(state.expr() + index.expr() + F::ONE)
+        });
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 6;
+        let start_value = 5;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write(&start, &F::from_canonical_usize(start_value), i);
+        }
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        for i in 0..num_rows {
+            let index = i % 8;
+            let partial_sum = start_value + index * (index + 1) / 2;
+            assert_eq!(
+                writer.read(&repetition.index, i),
+                F::from_canonical_usize(index)
+            );
+            assert_eq!(
+                writer.read(&repetition.state, i),
+                F::from_canonical_usize(partial_sum)
+            );
+            assert_eq!(
+                writer.read(&repetition.output, i),
+                F::from_canonical_usize(partial_sum + index + 1)
+            );
+            if index == 7 {
+                assert_eq!(writer.read(&repetition.end_bit, i), F::ONE);
+                assert_eq!(
+                    writer.read(&repetition.output, i),
+                    F::from_canonical_usize(start_value + 36)
+                );
+            }
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+}
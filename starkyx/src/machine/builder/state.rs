@@ -0,0 +1,211 @@
+use core::fmt::Debug;
+
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::Register;
+use crate::math::prelude::*;
+
+/// A declarative description of a state machine over the rows of the trace, with the states
+/// given by the values of `S`, usually the variants of an enum.
+///
+/// The machine is in the initial state in the first row. In every transition, the first
+/// transition declared from the current state whose condition bit is set in the current row is
+/// taken, and if none of them are set, the machine stays in its state.
+#[derive(Debug, Clone)]
+pub struct StateMachine<S> {
+    pub(crate) name: String,
+    pub(crate) states: Vec<S>,
+    pub(crate) initial: S,
+    pub(crate) transitions: Vec<(S, S, BitRegister)>,
+}
+
+/// The selector bits of a state machine allocated by `Builder::state_machine`, exactly one of
+/// which is set in every row.
+#[derive(Debug, Clone)]
+pub struct StateSelectors<S> {
+    states: Vec<S>,
+    selectors: ArrayRegister<BitRegister>,
+}
+
+impl<S: Copy + Eq + Debug> StateMachine<S> {
+    /// A machine over `states`, starting in `initial`.
+    pub fn new(name: &str, states: impl IntoIterator<Item = S>, initial: S) -> Self {
+        let states = states.into_iter().collect::<Vec<_>>();
+        for (i, state) in states.iter().enumerate() {
+            assert!(
+                !states[..i].contains(state),
+                "The state {:?} is declared twice",
+                state
+            );
+        }
+        let machine = Self {
+            name: name.to_string(),
+            states,
+            initial,
+            transitions: Vec::new(),
+        };
+        machine.index(initial);
+        machine
+    }
+
+    /// Adds a transition from `from` to `to` taken from the rows in `from` in which `condition`
+    /// is set.
+    ///
+    /// Transitions added first take precedence over the ones added later from the same state.
+    pub fn transition(mut self, from: S, to: S, condition: BitRegister) -> Self {
+        self.index(from);
+        self.index(to);
+        self.transitions.push((from, to, condition));
+        self
+    }
+
+    pub(crate) fn index(&self, state: S) -> usize {
+        self.states
+            .iter()
+            .position(|s| *s == state)
+            .unwrap_or_else(|| panic!("The state {:?} is not declared", state))
+    }
+
+    /// The expressions for the selectors in the next row, given the selectors of the current row.
+    ///
+    /// The degree of the expression of a state is one more than the number of transitions out of
+    /// the states leading to it.
+    pub(crate) fn next_selectors<F: Field>(
+        &self,
+        selectors: &ArrayRegister<BitRegister>,
+    ) -> Vec<ArithmeticExpression<F>> {
+        let mut next = vec![ArithmeticExpression::zero(); self.states.len()];
+        for (i, state) in self.states.iter().enumerate() {
+            // The weight of a transition is set if its condition is and none of the conditions
+            // of the transitions declared before it are.
+            let mut none_taken = ArithmeticExpression::one();
+            for (_, to, condition) in self.transitions.iter().filter(|(from, ..)| from == state) {
+                let weight = none_taken.clone() * condition.expr();
+                let j = self.index(*to);
+                next[j] = next[j].clone() + selectors.get(i).expr() * weight;
+                none_taken = none_taken * condition.not_expr();
+            }
+            next[i] = next[i].clone() + selectors.get(i).expr() * none_taken;
+        }
+        next
+    }
+}
+
+impl<S: Copy + Eq + Debug> StateSelectors<S> {
+    pub(crate) fn new(states: Vec<S>, selectors: ArrayRegister<BitRegister>) -> Self {
+        Self { states, selectors }
+    }
+
+    /// The bit set in the rows in which the machine is in `state`.
+    pub fn get(&self, state: S) -> BitRegister {
+        let index = self
+            .states
+            .iter()
+            .position(|s| *s == state)
+            .unwrap_or_else(|| panic!("The state {:?} is not declared", state));
+        self.selectors.get(index)
+    }
+
+    /// The selector bits, in the order the states were declared.
+    pub fn selectors(&self) -> ArrayRegister<BitRegister> {
+        self.selectors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::AirParameters;
+    use crate::machine::builder::Builder;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct StateMachineTest;
+
+    impl AirParameters for StateMachineTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 13;
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Phase {
+        Initialize,
+        Round,
+        Finalize,
+    }
+
+    #[test]
+    fn test_state_machine() {
+        type F = GoldilocksField;
+        type L = StateMachineTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let cycle_4 = builder.cycle(2);
+        let cycle_16 = builder.cycle(4);
+
+        // Initialize for one row, run rounds until the end of a cycle of 4, finalize until the
+        // end of a cycle of 16 and start over.
+        let machine = StateMachine::new(
+            "phase",
+            [Phase::Initialize, Phase::Round, Phase::Finalize],
+            Phase::Initialize,
+        )
+        .transition(Phase::Initialize, Phase::Round, cycle_16.start_bit)
+        .transition(Phase::Round, Phase::Finalize, cycle_4.end_bit)
+        .transition(Phase::Finalize, Phase::Initialize, cycle_16.end_bit);
+        let selectors = builder.state_machine(&machine);
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 6;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+        for i in 0..num_rows {
+            let expected = match i % 16 {
+                0 => Phase::Initialize,
+                1..=3 => Phase::Round,
+                _ => Phase::Finalize,
+            };
+            for phase in [Phase::Initialize, Phase::Round, Phase::Finalize] {
+                assert_eq!(
+                    writer.read(&selectors.get(phase), i),
+                    F::from_canonical_u8((phase == expected) as u8),
+                    "Wrong selector of {:?} in row {}",
+                    phase,
+                    i
+                );
+            }
+        }
+
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "is not declared")]
+    fn test_state_machine_undeclared_state() {
+        type L = StateMachineTest;
+
+        let mut builder = AirBuilder::<L>::new();
+        let cycle = builder.cycle(2);
+        let _ = StateMachine::new(
+            "phase",
+            [Phase::Initialize, Phase::Round],
+            Phase::Initialize,
+        )
+        .transition(Phase::Round, Phase::Finalize, cycle.end_bit);
+    }
+}
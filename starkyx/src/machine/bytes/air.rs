@@ -28,8 +28,8 @@ impl<F: PrimeField64, E: CubicParameters<F>> AirParameters for ByteParameters<F,
     type Instruction = UintInstruction;
 
     const NUM_ARITHMETIC_COLUMNS: usize = 0;
-    const NUM_FREE_COLUMNS: usize = 15;
-    const EXTENDED_COLUMNS: usize = 45;
+    const NUM_FREE_COLUMNS: usize = 17;
+    const EXTENDED_COLUMNS: usize = 51;
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
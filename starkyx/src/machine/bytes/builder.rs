@@ -2,16 +2,36 @@ use plonky2::field::extension::Extendable;
 use plonky2::hash::hash_types::RichField;
 
 use super::air::{get_preprocessed_byte_trace, ByteAir, ByteParameters};
-use super::stark::ByteStark;
+use super::stark::{ByteStark, ByteTraceData};
+use crate::air::debug::{ConstraintFailure, DebugParser};
+use crate::air::AirConstraint;
 use crate::chip::builder::AirBuilder;
+use crate::chip::constraint::Constraint;
+use crate::chip::memory::pointer::slice::Slice;
+use crate::chip::memory::time::Time;
+use crate::chip::memory::value::MemoryValue;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::table::bus::cross::CrossTableLookup;
+use crate::chip::trace::data::AirTraceData;
 use crate::chip::trace::writer::TraceWriter;
+use crate::chip::uint::bits::BitDecomposition;
 use crate::chip::uint::bytes::lookup_table::builder_operations::ByteLookupOperations;
+use crate::chip::uint::bytes::lookup_table::multiplicity_data::ByteMultiplicityData;
+use crate::chip::uint::bytes::lookup_table::table::ByteLogLookupTable;
+use crate::chip::uint::bytes::operations::value::ByteOperation;
+use crate::chip::uint::bytes::register::ByteRegister;
 use crate::chip::uint::operations::instruction::UintInstructions;
-use crate::chip::AirParameters;
+use crate::chip::uint::register::{ByteArrayRegister, IRegister};
+use crate::chip::{AirParameters, Chip, ColumnBudget};
 use crate::machine::builder::Builder;
+use crate::math::prelude::*;
 use crate::plonky2::stark::config::{CurtaConfig, StarkyConfig};
 use crate::plonky2::stark::Starky;
+use crate::trace::AirTrace;
 
 pub(crate) const NUM_LOOKUP_ROWS: usize = 1 << 16;
 
@@ -51,13 +71,135 @@ where
         }
     }
 
-    pub fn build<C: CurtaConfig<D, F = L::Field>, const D: usize>(
-        self,
-        num_rows: usize,
-    ) -> ByteStark<L, C, D>
-    where
-        L::Field: RichField + Extendable<D>,
+    /// Asserts that the message stored in `message` starts with the bytes of `prefix`.
+    ///
+    /// The words of `message` are read in little-endian byte order, with the last write time given
+    /// by `time`. The prefix need not be a multiple of the word size, in which case the bytes of
+    /// the last word after the prefix are only range checked. Every word containing bytes of the
+    /// prefix is read once, so its stored multiplicity must account for that read.
+    pub fn assert_bytes_prefix<const N: usize>(
+        &mut self,
+        message: &Slice<ByteArrayRegister<N>>,
+        time: &Time<L::Field>,
+        prefix: &[u8],
+    ) where
+        ByteArrayRegister<N>: MemoryValue,
     {
+        for (i, word_prefix) in prefix.chunks(N).enumerate() {
+            let word = self.api.get_public(&message.get(i), time);
+            for (j, byte) in word.to_le_bytes().iter().enumerate() {
+                match word_prefix.get(j) {
+                    Some(expected) => self.api.assert_expression_zero(
+                        byte.expr() - L::Field::from_canonical_u8(*expected),
+                    ),
+                    None => self.api.set_public_inputs_byte_operation(
+                        &ByteOperation::Range(byte),
+                        &mut self.operations,
+                    ),
+                }
+            }
+        }
+    }
+
+    /// Asserts that the values of `register` are non-decreasing from each row to the next.
+    ///
+    /// The difference between consecutive values is kept in a byte register and range checked, so
+    /// consecutive values may be equal, but may not increase by more than 255 in a single row. The
+    /// value of `register` in the next row must be written before the instructions of the current
+    /// row, as is the case for registers set with a transition constraint.
+    pub fn assert_monotonic(&mut self, register: &ElementRegister) {
+        let difference = self.alloc::<ByteRegister>();
+        self.set_to_expression_first_row(&difference, L::Field::ZERO.into());
+        self.set_to_expression_transition(
+            &difference.next(),
+            register.next().expr() - register.expr(),
+        );
+        self.api
+            .set_byte_operation(&ByteOperation::Range(difference), &mut self.operations);
+    }
+
+    /// Returns a bit set in the rows where the signed integer `value` is negative.
+    pub fn is_negative<const N: usize>(&mut self, value: &IRegister<N>) -> BitRegister {
+        self.api.is_negative(value, &mut self.operations)
+    }
+
+    /// Asserts that in every row, the tuple `inputs` is one of the entries of `table` in some
+    /// row. See `AirBuilder::multi_lookup`.
+    pub fn multi_lookup(
+        &mut self,
+        inputs: &[ElementRegister],
+        table: &[ArrayRegister<ElementRegister>],
+    ) {
+        self.api.multi_lookup(inputs, table);
+    }
+
+    /// Allocates one side of a cross-table lookup. See `AirBuilder::cross_table_lookup`.
+    pub fn cross_table_lookup(&mut self) -> CrossTableLookup {
+        self.api.cross_table_lookup()
+    }
+
+    /// Sends `value` to the other side of `lookup` `multiplicity` times in every row.
+    pub fn send(
+        &mut self,
+        lookup: &CrossTableLookup,
+        value: CubicRegister,
+        multiplicity: ElementRegister,
+    ) {
+        self.api.send(lookup, value, multiplicity);
+    }
+
+    /// Receives `value` from the other side of `lookup` in every row.
+    pub fn receive(&mut self, lookup: &CrossTableLookup, value: CubicRegister) {
+        self.api.receive(lookup, value);
+    }
+
+    /// Divides `a` by `b`, returning the quotient and the remainder. The constraints can't be
+    /// satisfied in the rows where `b` is zero.
+    pub fn div_rem<const N: usize>(
+        &mut self,
+        a: &ByteArrayRegister<N>,
+        b: &ByteArrayRegister<N>,
+    ) -> (ByteArrayRegister<N>, ByteArrayRegister<N>) {
+        self.api.div_rem(a, b, &mut self.operations)
+    }
+
+    /// Decomposes `value` into its bits, to be shared by several bit-level operations on it. See
+    /// `AirBuilder::decompose_bits`.
+    pub fn decompose_bits<const N: usize>(
+        &mut self,
+        value: &ByteArrayRegister<N>,
+    ) -> BitDecomposition<N> {
+        self.api.decompose_bits(value)
+    }
+
+    /// Enables or disables the debugging instructions registered from now on, to be turned off
+    /// for production traces. See `AirBuilder::set_debug`.
+    pub fn set_debug(&mut self, debug: bool) {
+        self.api.set_debug(debug);
+    }
+
+    /// The number of columns of each kind the builder needs, including the columns used by the
+    /// byte lookup and the buses and range checks that are registered in `build`.
+    pub fn column_budget(self) -> ColumnBudget {
+        let BytesBuilder {
+            mut api,
+            operations,
+            ..
+        } = self;
+        let shared_memory = api.shared_memory.clone();
+        let mut lookup_builder =
+            AirBuilder::<ByteParameters<L::Field, L::CubicParams>>::init(shared_memory);
+
+        let mut lookup_table = lookup_builder.new_byte_lookup_table();
+        api.register_byte_lookup(&mut lookup_table, operations);
+        api.register_build_constraints();
+
+        api.column_budget()
+    }
+
+    /// Builds the AIR of the machine and the AIR of its byte lookup table, without fixing the
+    /// configuration of the prover.
+    pub fn build_airs(self) -> ByteAirs<L> {
         let BytesBuilder {
             mut api,
             operations,
@@ -71,12 +213,45 @@ where
         let multiplicity_data = api.register_byte_lookup(&mut lookup_table, operations);
         lookup_builder.constraint_byte_lookup_table(&lookup_table);
 
+        let (air, air_data) = api.build();
+        let (lookup_air, lookup_air_data) = lookup_builder.build();
+
+        ByteAirs {
+            air,
+            air_data,
+            lookup_air,
+            lookup_air_data,
+            multiplicity_data,
+            lookup_table,
+        }
+    }
+
+    pub fn build<C: CurtaConfig<D, F = L::Field>, const D: usize>(
+        self,
+        num_rows: usize,
+    ) -> ByteStark<L, C, D>
+    where
+        L::Field: RichField + Extendable<D>,
+    {
+        if let Some(trace_length) = self.api.trace_length() {
+            assert_eq!(
+                num_rows, trace_length,
+                "The number of rows does not match the trace length of the machine"
+            );
+        }
+        let ByteAirs {
+            air,
+            air_data: trace_data,
+            lookup_air,
+            lookup_air_data: lookup_trace_data,
+            multiplicity_data,
+            lookup_table,
+        } = self.build_airs();
+
         let config = StarkyConfig::<C, D>::standard_fast_config(num_rows);
-        let (air, trace_data) = api.build();
         let stark = Starky::new(air);
 
         let lookup_config = StarkyConfig::<C, D>::standard_fast_config(NUM_LOOKUP_ROWS);
-        let (lookup_air, lookup_trace_data) = lookup_builder.build();
         let lookup_stark = Starky::new(ByteAir(lookup_air));
 
         // Get the commitment to the preprocessed byte trace.
@@ -107,3 +282,208 @@ where
         }
     }
 }
+
+/// The AIR of a machine of a `BytesBuilder` and the AIR of its byte lookup table, before a
+/// configuration of the prover is chosen for them.
+pub struct ByteAirs<L: AirParameters> {
+    pub air: Chip<L>,
+    pub air_data: AirTraceData<L>,
+    pub lookup_air: Chip<ByteParameters<L::Field, L::CubicParams>>,
+    pub lookup_air_data: AirTraceData<ByteParameters<L::Field, L::CubicParams>>,
+    pub(crate) multiplicity_data: ByteMultiplicityData,
+    pub(crate) lookup_table: ByteLogLookupTable<L::Field, L::CubicParams>,
+}
+
+impl<L: AirParameters> ByteAirs<L> {
+    /// Writes the traces of the two AIRs from the execution trace and the public inputs of the
+    /// machine, with the AIR challenges `challenges`, and returns the constraints of both AIRs
+    /// that do not vanish.
+    ///
+    /// Unlike a proof, the check works for a trace of any number of rows. Challenges known before
+    /// the trace is written make the arguments unsound, so this is only meant for testing a
+    /// witness numerically, as in `TraceWriter::validate`.
+    pub fn debug_constraints(
+        &self,
+        execution_trace: &AirTrace<L::Field>,
+        public_values: &[L::Field],
+        challenges: &[L::Field],
+    ) -> Vec<ConstraintFailure<L::Field>>
+    where
+        Constraint<L>: for<'a> AirConstraint<DebugParser<'a, L::Field>>,
+        Constraint<ByteParameters<L::Field, L::CubicParams>>:
+            for<'a> AirConstraint<DebugParser<'a, L::Field>>,
+    {
+        assert_eq!(
+            challenges.len(),
+            self.air.num_challenges,
+            "The AIR needs {} challenges",
+            self.air.num_challenges
+        );
+        let trace_data = ByteTraceData {
+            air_data: &self.air_data,
+            execution_trace_length: self.air.execution_trace_length,
+            multiplicity_data: &self.multiplicity_data,
+            lookup_air_data: &self.lookup_air_data,
+            lookup_table: &self.lookup_table,
+        };
+        let (main_writer, lookup_writer) =
+            trace_data.generate_execution_traces(execution_trace, public_values);
+        let main_writer = main_writer.with_fixed_challenges(challenges);
+        let lookup_writer = lookup_writer.with_fixed_challenges(challenges);
+        trace_data.generate_extended_traces(&main_writer, &lookup_writer);
+
+        let global_values = main_writer.global().unwrap();
+        let mut failures = self.air.debug_constraints(
+            &main_writer.read_trace().unwrap(),
+            challenges,
+            &global_values,
+            public_values,
+        );
+        failures.extend(self.lookup_air.debug_constraints(
+            &lookup_writer.read_trace().unwrap(),
+            challenges,
+            &global_values,
+            public_values,
+        ));
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::register::u16::U16Register;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::register::U32Register;
+    use crate::chip::Chip;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct WatchTest;
+
+    impl AirParameters for WatchTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 2;
+    }
+
+    #[test]
+    fn test_watch_disabled() {
+        type L = WatchTest;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let value = builder.alloc::<ElementRegister>();
+        let public = builder.alloc_public::<ElementRegister>();
+        let slice = builder.uninit_slice::<ElementRegister>();
+        builder.store(&slice.get(0), public, &Time::zero(), None, None, None);
+
+        let num_instructions = builder.api.instructions.len();
+        let num_global_instructions = builder.api.global_instructions.len();
+        let num_constraints = builder.api.constraints.len();
+
+        builder.set_debug(false);
+        builder.watch(&value, "value");
+        builder.watch(&public, "public");
+        builder.watch_memory(&slice.get(0), "slice");
+        assert_eq!(builder.api.instructions.len(), num_instructions);
+        assert_eq!(
+            builder.api.global_instructions.len(),
+            num_global_instructions
+        );
+        assert_eq!(builder.api.constraints.len(), num_constraints);
+
+        builder.set_debug(true);
+        builder.watch(&value, "value");
+        builder.watch(&public, "public");
+        builder.watch_memory(&slice.get(0), "slice");
+        assert_eq!(builder.api.instructions.len(), num_instructions + 2);
+        assert_eq!(
+            builder.api.global_instructions.len(),
+            num_global_instructions + 1
+        );
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct BudgetTest;
+
+    impl AirParameters for BudgetTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 2;
+        const NUM_FREE_COLUMNS: usize = 20;
+        const EXTENDED_COLUMNS: usize = 54;
+    }
+
+    #[test]
+    fn test_column_budget_of_build() {
+        type L = BudgetTest;
+
+        // A machine with byte operations, range checks, a memory bus and arithmetic columns, all
+        // of which take columns registered in `build`.
+        let new_builder = || {
+            let mut builder = BytesBuilder::<L>::new();
+            let a = builder.alloc::<U32Register>();
+            let b = builder.alloc::<U32Register>();
+            builder.add(a, b);
+            let value = builder.alloc::<ElementRegister>();
+            builder.range_check(&value, 8);
+            let slice = builder.uninit_slice::<ElementRegister>();
+            builder.store(&slice.get(0), value, &Time::zero(), None, None, None);
+            builder.load(&slice.get(0), &Time::zero(), None, None);
+            builder.alloc_array::<U16Register>(L::NUM_ARITHMETIC_COLUMNS);
+            builder
+        };
+
+        let budget = new_builder().column_budget();
+        assert_eq!(budget, ColumnBudget::of::<L>());
+
+        let airs = new_builder().build_airs();
+        assert_eq!(
+            airs.air.execution_trace_length,
+            L::NUM_ARITHMETIC_COLUMNS + L::NUM_FREE_COLUMNS
+        );
+        assert_eq!(airs.air.num_extended_columns, L::EXTENDED_COLUMNS);
+    }
+
+    #[test]
+    fn test_build_constraint_tags() {
+        type L = BudgetTest;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<U32Register>();
+        let b = builder.alloc::<U32Register>();
+        builder.add(a, b);
+        let value = builder.alloc::<ElementRegister>();
+        builder.range_check(&value, 8);
+        let slice = builder.uninit_slice::<ElementRegister>();
+        builder.store(&slice.get(0), value, &Time::zero(), None, None, None);
+        builder.load(&slice.get(0), &Time::zero(), None, None);
+        builder.alloc_array::<U16Register>(L::NUM_ARITHMETIC_COLUMNS);
+        let airs = builder.build_airs();
+
+        // The constraints registered when the AIRs are built are tagged like the constraints of
+        // the operations.
+        fn tagged<P: AirParameters>(air: &Chip<P>, label: &str) -> bool {
+            air.tags().iter().any(|tag| {
+                tag.label == label
+                    && (!tag.constraints.is_empty() || !tag.global_constraints.is_empty())
+            })
+        }
+        for label in [
+            "byte_lookup",
+            "bus",
+            "range_check",
+            "arithmetic_range_check",
+        ] {
+            assert!(tagged(&airs.air, label), "No constraints tagged {label}");
+        }
+        assert!(tagged(&airs.lookup_air, "byte_lookup_table"));
+    }
+}
@@ -0,0 +1,393 @@
+//! Proofs of two `ByteStark`s connected by a cross-table lookup.
+//!
+//! Both STARKs are proven with a single challenger. It observes the public values and the
+//! execution trace commitments of the sender and then of the receiver, and only then are the
+//! challenges of the two AIRs drawn, the challenge of the lookup in the receiver being replaced
+//! by the one of the sender. The extended traces of the sender and of the receiver are then
+//! committed to and the four AIRs are proven in turn.
+//!
+//! The verifier replays the same transcript, verifies both STARKs and checks that the cumulative
+//! values of the two sides of the lookup, read from the global values of the proofs, are equal.
+
+use anyhow::{ensure, Result};
+use plonky2::field::extension::Extendable;
+use plonky2::hash::hash_types::RichField;
+use plonky2::iop::challenger::Challenger;
+use plonky2::timed;
+use plonky2::util::timing::TimingTree;
+
+use super::proof::ByteStarkProof;
+use super::stark::ByteStark;
+use crate::air::RAirData;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::table::bus::cross::CrossTableLookup;
+use crate::chip::{AirParameters, Chip};
+use crate::plonky2::stark::config::CurtaConfig;
+use crate::plonky2::Plonky2Air;
+use crate::trace::AirTrace;
+
+/// A `ByteStark` sending values through a cross-table lookup and a `ByteStark` receiving them.
+#[derive(Debug, Clone)]
+pub struct CrossTableByteStark<L: AirParameters, M: AirParameters, C, const D: usize>
+where
+    L::Field: RichField,
+    C: CurtaConfig<D, F = L::Field>,
+    M: AirParameters<Field = L::Field, CubicParams = L::CubicParams>,
+{
+    pub sender: ByteStark<L, C, D>,
+    pub receiver: ByteStark<M, C, D>,
+    sender_lookup: CrossTableLookup,
+    receiver_lookup: CrossTableLookup,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrossTableByteStarkProof<
+    F: RichField + Extendable<D>,
+    C: CurtaConfig<D, F = F>,
+    const D: usize,
+> {
+    pub sender_proof: ByteStarkProof<F, C, D>,
+    pub receiver_proof: ByteStarkProof<F, C, D>,
+}
+
+impl<L: AirParameters, M: AirParameters, C, const D: usize> CrossTableByteStark<L, M, C, D>
+where
+    L::Field: RichField + Extendable<D>,
+    C: CurtaConfig<D, F = L::Field, FE = <L::Field as Extendable<D>>::Extension>,
+    M: AirParameters<Field = L::Field, CubicParams = L::CubicParams>,
+    Chip<L>: Plonky2Air<L::Field, D>,
+    Chip<M>: Plonky2Air<L::Field, D>,
+{
+    /// Connects the side `sender_lookup` of `sender` to the side `receiver_lookup` of `receiver`.
+    pub fn new(
+        sender: ByteStark<L, C, D>,
+        sender_lookup: CrossTableLookup,
+        receiver: ByteStark<M, C, D>,
+        receiver_lookup: CrossTableLookup,
+    ) -> Self {
+        // The cumulative values are written with the extended traces.
+        for (round_data, lookup) in [
+            (sender.stark.air.round_data(), sender_lookup),
+            (receiver.stark.air.round_data(), receiver_lookup),
+        ] {
+            let (start, end) = round_data.last().unwrap().global_values_range;
+            let (cumulative_start, cumulative_end) = lookup.cumulative().register().get_range();
+            assert!(
+                start <= cumulative_start && cumulative_end <= end,
+                "The cumulative value of a lookup must be a global value of the extended round"
+            );
+        }
+        Self {
+            sender,
+            receiver,
+            sender_lookup,
+            receiver_lookup,
+        }
+    }
+
+    /// Draws the challenges of both AIRs, with the lookup challenge of the sender shared with the
+    /// receiver.
+    fn get_air_challenges(
+        &self,
+        challenger: &mut Challenger<L::Field, C::Hasher>,
+    ) -> (Vec<L::Field>, Vec<L::Field>) {
+        let sender_challenges = challenger.get_n_challenges(self.sender.stark.air.num_challenges);
+        let mut receiver_challenges =
+            challenger.get_n_challenges(self.receiver.stark.air.num_challenges);
+        receiver_challenges[self.receiver_lookup.challenge_range()]
+            .copy_from_slice(&sender_challenges[self.sender_lookup.challenge_range()]);
+        (sender_challenges, receiver_challenges)
+    }
+
+    pub fn prove(
+        &self,
+        sender_trace: &AirTrace<L::Field>,
+        sender_public_values: &[L::Field],
+        receiver_trace: &AirTrace<L::Field>,
+        receiver_public_values: &[L::Field],
+        timing: &mut TimingTree,
+    ) -> Result<CrossTableByteStarkProof<L::Field, C, D>> {
+        // Initialize challenger.
+        let mut challenger = Challenger::new();
+
+        // Commit to the execution traces of both STARKs before drawing any challenge.
+        let sender_execution = timed!(
+            timing,
+            "Commit to sender execution traces",
+            self.sender.commit_execution_traces(
+                sender_trace,
+                sender_public_values,
+                &mut challenger,
+                timing
+            )
+        );
+        let receiver_execution = timed!(
+            timing,
+            "Commit to receiver execution traces",
+            self.receiver.commit_execution_traces(
+                receiver_trace,
+                receiver_public_values,
+                &mut challenger,
+                timing
+            )
+        );
+
+        // Get random AIR challenges.
+        let (sender_challenges, receiver_challenges) = self.get_air_challenges(&mut challenger);
+
+        // Commit to the extended traces.
+        let (sender_main, sender_lookup) = timed!(
+            timing,
+            "Commit to sender extended traces",
+            self.sender.commit_extended_traces(
+                sender_execution,
+                &sender_challenges,
+                &mut challenger,
+                timing
+            )
+        );
+        let (receiver_main, receiver_lookup) = timed!(
+            timing,
+            "Commit to receiver extended traces",
+            self.receiver.commit_extended_traces(
+                receiver_execution,
+                &receiver_challenges,
+                &mut challenger,
+                timing
+            )
+        );
+
+        let sender_proof = timed!(
+            timing,
+            "Generate sender proof",
+            self.sender.prove_with_commitments(
+                sender_main,
+                sender_lookup,
+                &mut challenger,
+                timing
+            )?
+        );
+        let receiver_proof = timed!(
+            timing,
+            "Generate receiver proof",
+            self.receiver.prove_with_commitments(
+                receiver_main,
+                receiver_lookup,
+                &mut challenger,
+                timing
+            )?
+        );
+
+        Ok(CrossTableByteStarkProof {
+            sender_proof,
+            receiver_proof,
+        })
+    }
+
+    /// Verifies both proofs and checks that the cumulative values of the two sides of the lookup
+    /// are equal.
+    pub fn verify(
+        &self,
+        proof: CrossTableByteStarkProof<L::Field, C, D>,
+        sender_public_values: &[L::Field],
+        receiver_public_values: &[L::Field],
+    ) -> Result<()> {
+        let CrossTableByteStarkProof {
+            sender_proof,
+            receiver_proof,
+        } = proof;
+
+        // Replay the transcript of the prover.
+        let mut challenger = Challenger::<L::Field, C::Hasher>::new();
+        self.sender
+            .observe_execution_caps(&sender_proof, sender_public_values, &mut challenger);
+        self.receiver.observe_execution_caps(
+            &receiver_proof,
+            receiver_public_values,
+            &mut challenger,
+        );
+
+        let (sender_challenges, receiver_challenges) = self.get_air_challenges(&mut challenger);
+
+        self.sender
+            .observe_extended_caps(&sender_proof, &mut challenger);
+        self.receiver
+            .observe_extended_caps(&receiver_proof, &mut challenger);
+
+        let sender_challenges =
+            self.sender
+                .get_iop_challenges(&sender_proof, sender_challenges, &mut challenger);
+        let receiver_challenges =
+            self.receiver
+                .get_iop_challenges(&receiver_proof, receiver_challenges, &mut challenger);
+
+        // The values sent and the values received match only if the log-derivative sums of both
+        // sides agree.
+        let sender_cumulative = self
+            .sender_lookup
+            .read_cumulative(&sender_proof.global_values);
+        let receiver_cumulative = self
+            .receiver_lookup
+            .read_cumulative(&receiver_proof.global_values);
+        ensure!(
+            sender_cumulative == receiver_cumulative,
+            "The cumulative values of the cross-table lookup are not equal"
+        );
+
+        self.sender.verify_with_challenges(
+            sender_proof,
+            sender_public_values,
+            sender_challenges,
+        )?;
+        self.receiver.verify_with_challenges(
+            receiver_proof,
+            receiver_public_values,
+            receiver_challenges,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use rand::Rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::register::cubic::CubicRegister;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::trace::writer::{InnerWriterData, TraceWriter};
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::machine::builder::Builder;
+    use crate::machine::bytes::builder::BytesBuilder;
+    use crate::math::extension::cubic::element::CubicElement;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SenderTest;
+
+    impl AirParameters for SenderTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 6;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ReceiverTest;
+
+    impl AirParameters for ReceiverTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 5;
+        const EXTENDED_COLUMNS: usize = 12;
+    }
+
+    /// Sends the table whose row `i` is `table(i)` to a receiver looking up the entry
+    /// `received(j)` in its row `j`, and verifies the joint proof.
+    fn prove_cross_table_lookup(received: impl Fn(usize) -> CubicElement<GoldilocksField>) {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let _ = env_logger::builder().is_test(true).try_init();
+        let mut timing = TimingTree::new("test_cross_table_lookup", log::Level::Debug);
+
+        let table = |i: usize| CubicElement([i, i * i, 7].map(F::from_canonical_usize));
+
+        let mut sender_builder = BytesBuilder::<SenderTest>::new();
+        let sender_lookup = sender_builder.cross_table_lookup();
+        let entry = sender_builder.alloc::<CubicRegister>();
+        let multiplicity = sender_builder.alloc::<ElementRegister>();
+        sender_builder.send(&sender_lookup, entry, multiplicity);
+
+        let mut receiver_builder = BytesBuilder::<ReceiverTest>::new();
+        let receiver_lookup = receiver_builder.cross_table_lookup();
+        let value = receiver_builder.alloc::<CubicRegister>();
+        receiver_builder.receive(&receiver_lookup, value);
+
+        let (sender_rows, receiver_rows) = (1 << 5, 1 << 6);
+        let stark = CrossTableByteStark::new(
+            sender_builder.build::<C, 2>(sender_rows),
+            sender_lookup,
+            receiver_builder.build::<C, 2>(receiver_rows),
+            receiver_lookup,
+        );
+
+        let receiver_writer = TraceWriter::new(&stark.receiver.air_data, receiver_rows);
+        let mut multiplicities = vec![0u64; sender_rows];
+        for j in 0..receiver_rows {
+            let received_value = received(j);
+            if let Some(i) = (0..sender_rows).find(|i| table(*i) == received_value) {
+                multiplicities[i] += 1;
+            }
+            receiver_writer.write(&value, &received_value, j);
+            receiver_writer.write_row_instructions(&stark.receiver.air_data, j);
+        }
+
+        let sender_writer = TraceWriter::new(&stark.sender.air_data, sender_rows);
+        for (i, m) in multiplicities.iter().enumerate() {
+            sender_writer.write(&entry, &table(i), i);
+            sender_writer.write(&multiplicity, &F::from_canonical_u64(*m), i);
+            sender_writer.write_row_instructions(&stark.sender.air_data, i);
+        }
+
+        let InnerWriterData {
+            trace: sender_trace,
+            public: sender_public,
+            ..
+        } = sender_writer.into_inner().unwrap();
+        let InnerWriterData {
+            trace: receiver_trace,
+            public: receiver_public,
+            ..
+        } = receiver_writer.into_inner().unwrap();
+
+        let proof = stark
+            .prove(
+                &sender_trace,
+                &sender_public,
+                &receiver_trace,
+                &receiver_public,
+                &mut timing,
+            )
+            .unwrap();
+        stark
+            .verify(proof, &sender_public, &receiver_public)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_cross_table_lookup() {
+        type F = GoldilocksField;
+
+        let indices = (0..1 << 6)
+            .map(|_| rand::thread_rng().gen_range(0..1 << 5))
+            .collect::<Vec<usize>>();
+        prove_cross_table_lookup(|j| {
+            let i = indices[j];
+            CubicElement([i, i * i, 7].map(F::from_canonical_usize))
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "cumulative values")]
+    fn test_cross_table_lookup_missing_value() {
+        type F = GoldilocksField;
+
+        // The last received value is not one of the entries of the table.
+        prove_cross_table_lookup(|j| {
+            let i = if j == (1 << 6) - 1 {
+                1 << 5
+            } else {
+                j % (1 << 5)
+            };
+            CubicElement([i, i * i, 7].map(F::from_canonical_usize))
+        });
+    }
+}
@@ -1,5 +1,6 @@
 pub mod air;
 pub mod builder;
+pub mod cross;
 pub mod ops;
 pub mod proof;
 pub mod stark;
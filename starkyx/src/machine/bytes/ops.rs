@@ -1,9 +1,16 @@
 use super::builder::BytesBuilder;
 use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::uint::bits::BitDecomposition;
 use crate::chip::uint::operations::instruction::UintInstructions;
-use crate::chip::uint::register::{ByteArrayRegister, U32Register, U64Register};
+use crate::chip::uint::register::{
+    ByteArrayRegister, I32Register, I64Register, U32Register, U64Register,
+};
 use crate::chip::AirParameters;
-use crate::machine::builder::ops::{Adc, Add, And, Not, RotateRight, Shr, Xor};
+use crate::machine::builder::ops::{
+    Adc, Add, AddChecked, And, Le, LeadingZeros, Lt, Neg, Not, Or, Popcount, RotateLeft,
+    RotateRight, Shl, Shr, Sub, Xor,
+};
 use crate::machine::builder::Builder;
 
 impl<L: AirParameters, const N: usize> And<BytesBuilder<L>> for &ByteArrayRegister<N>
@@ -50,6 +57,72 @@ where
     }
 }
 
+impl<L: AirParameters, const N: usize> Or<BytesBuilder<L>> for &ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn or(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.bitwise_or(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters, const N: usize> Or<BytesBuilder<L>> for ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn or(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.or(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters, const N: usize> LeadingZeros<BytesBuilder<L>> for &ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ElementRegister;
+
+    fn leading_zeros(self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.leading_zeros(self)
+    }
+}
+
+impl<L: AirParameters, const N: usize> LeadingZeros<BytesBuilder<L>> for ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ElementRegister;
+
+    fn leading_zeros(self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.leading_zeros(&self)
+    }
+}
+
+impl<L: AirParameters, const N: usize> Popcount<BytesBuilder<L>> for &ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ElementRegister;
+
+    fn popcount(self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.popcount(self, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters, const N: usize> Popcount<BytesBuilder<L>> for ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ElementRegister;
+
+    fn popcount(self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.popcount(&self)
+    }
+}
+
 impl<L: AirParameters, const N: usize> Xor<BytesBuilder<L>> for &ByteArrayRegister<N>
 where
     L::Instruction: UintInstructions,
@@ -72,6 +145,42 @@ where
     }
 }
 
+/// The result of a shift by at least the bit width, in which all the bits are shifted out.
+fn shifted_out<L: AirParameters, const N: usize>(
+    builder: &mut BytesBuilder<L>,
+) -> ByteArrayRegister<N> {
+    let result = builder.alloc::<ByteArrayRegister<N>>();
+    for byte in result.to_le_bytes() {
+        builder.api.assert_zero(&byte);
+    }
+    result
+}
+
+impl<L: AirParameters, const N: usize> Shl<BytesBuilder<L>, usize> for &ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn shl(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        if rhs >= N * 8 {
+            return shifted_out(builder);
+        }
+        builder.api.bit_shl(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters, const N: usize> Shl<BytesBuilder<L>, usize> for ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn shl(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.shl(&self, rhs)
+    }
+}
+
 impl<L: AirParameters, const N: usize> Shr<BytesBuilder<L>, usize> for &ByteArrayRegister<N>
 where
     L::Instruction: UintInstructions,
@@ -79,6 +188,9 @@ where
     type Output = ByteArrayRegister<N>;
 
     fn shr(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        if rhs >= N * 8 {
+            return shifted_out(builder);
+        }
         builder.api.bit_shr(self, rhs, &mut builder.operations)
     }
 }
@@ -118,6 +230,96 @@ where
     }
 }
 
+impl<L: AirParameters, const N: usize> RotateLeft<BytesBuilder<L>, usize> for &ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn rotate_left(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder
+            .api
+            .bit_rotate_left(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters, const N: usize> RotateLeft<BytesBuilder<L>, usize> for ByteArrayRegister<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn rotate_left(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.rotate_left(&self, rhs)
+    }
+}
+
+impl<L: AirParameters, const N: usize> And<BytesBuilder<L>> for &BitDecomposition<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn and(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.decomposed_and(self, rhs)
+    }
+}
+
+impl<L: AirParameters, const N: usize> Or<BytesBuilder<L>> for &BitDecomposition<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn or(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.decomposed_or(self, rhs)
+    }
+}
+
+impl<L: AirParameters, const N: usize> Shl<BytesBuilder<L>, usize> for &BitDecomposition<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn shl(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.decomposed_shl(self, rhs)
+    }
+}
+
+impl<L: AirParameters, const N: usize> Shr<BytesBuilder<L>, usize> for &BitDecomposition<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn shr(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.decomposed_shr(self, rhs)
+    }
+}
+
+impl<L: AirParameters, const N: usize> RotateRight<BytesBuilder<L>, usize> for &BitDecomposition<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn rotate_right(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.decomposed_rotate_right(self, rhs)
+    }
+}
+
+impl<L: AirParameters, const N: usize> RotateLeft<BytesBuilder<L>, usize> for &BitDecomposition<N>
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = ByteArrayRegister<N>;
+
+    fn rotate_left(self, rhs: usize, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.decomposed_rotate_left(self, rhs)
+    }
+}
+
 impl<L: AirParameters> Adc<BytesBuilder<L>> for &U32Register
 where
     L::Instruction: UintInstructions,
@@ -209,3 +411,1128 @@ where
         builder.add(&self, &rhs)
     }
 }
+
+impl<L: AirParameters> AddChecked<BytesBuilder<L>> for &U32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = U32Register;
+
+    fn add_checked(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder
+            .api
+            .add_checked_u32(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters> AddChecked<BytesBuilder<L>> for U32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = U32Register;
+
+    fn add_checked(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.add_checked(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> AddChecked<BytesBuilder<L>> for &U64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = U64Register;
+
+    fn add_checked(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder
+            .api
+            .add_checked_u64(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters> AddChecked<BytesBuilder<L>> for U64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = U64Register;
+
+    fn add_checked(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.add_checked(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Lt<BytesBuilder<L>> for &U32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = BitRegister;
+
+    fn lt(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.lt_u32(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters> Lt<BytesBuilder<L>> for U32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = BitRegister;
+
+    fn lt(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.lt(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Le<BytesBuilder<L>> for &U32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = BitRegister;
+
+    fn le(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.le_u32(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters> Le<BytesBuilder<L>> for U32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = BitRegister;
+
+    fn le(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.le(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Lt<BytesBuilder<L>> for &U64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = BitRegister;
+
+    fn lt(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.lt_u64(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters> Lt<BytesBuilder<L>> for U64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = BitRegister;
+
+    fn lt(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.lt(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Le<BytesBuilder<L>> for &U64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = BitRegister;
+
+    fn le(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.api.le_u64(self, rhs, &mut builder.operations)
+    }
+}
+
+impl<L: AirParameters> Le<BytesBuilder<L>> for U64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = BitRegister;
+
+    fn le(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.le(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Add<BytesBuilder<L>> for &I32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I32Register;
+
+    fn add(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        let result = builder.api.add_u32(
+            &self.as_unsigned(),
+            &rhs.as_unsigned(),
+            &mut builder.operations,
+        );
+        I32Register::from_unsigned(&result)
+    }
+}
+
+impl<L: AirParameters> Add<BytesBuilder<L>> for I32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I32Register;
+
+    fn add(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.add(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Sub<BytesBuilder<L>> for &I32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I32Register;
+
+    fn sub(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        let result = builder.api.wrapping_sub_u32(
+            &self.as_unsigned(),
+            &rhs.as_unsigned(),
+            &mut builder.operations,
+        );
+        I32Register::from_unsigned(&result)
+    }
+}
+
+impl<L: AirParameters> Sub<BytesBuilder<L>> for I32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I32Register;
+
+    fn sub(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.sub(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Neg<BytesBuilder<L>> for &I32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I32Register;
+
+    fn neg(self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        let result = builder
+            .api
+            .wrapping_neg_u32(&self.as_unsigned(), &mut builder.operations);
+        I32Register::from_unsigned(&result)
+    }
+}
+
+impl<L: AirParameters> Neg<BytesBuilder<L>> for I32Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I32Register;
+
+    fn neg(self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.neg(&self)
+    }
+}
+
+impl<L: AirParameters> Add<BytesBuilder<L>> for &I64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I64Register;
+
+    fn add(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        let result = builder.api.add_u64(
+            &self.as_unsigned(),
+            &rhs.as_unsigned(),
+            &mut builder.operations,
+        );
+        I64Register::from_unsigned(&result)
+    }
+}
+
+impl<L: AirParameters> Add<BytesBuilder<L>> for I64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I64Register;
+
+    fn add(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.add(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Sub<BytesBuilder<L>> for &I64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I64Register;
+
+    fn sub(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        let result = builder.api.wrapping_sub_u64(
+            &self.as_unsigned(),
+            &rhs.as_unsigned(),
+            &mut builder.operations,
+        );
+        I64Register::from_unsigned(&result)
+    }
+}
+
+impl<L: AirParameters> Sub<BytesBuilder<L>> for I64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I64Register;
+
+    fn sub(self, rhs: Self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.sub(&self, &rhs)
+    }
+}
+
+impl<L: AirParameters> Neg<BytesBuilder<L>> for &I64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I64Register;
+
+    fn neg(self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        let result = builder
+            .api
+            .wrapping_neg_u64(&self.as_unsigned(), &mut builder.operations);
+        I64Register::from_unsigned(&result)
+    }
+}
+
+impl<L: AirParameters> Neg<BytesBuilder<L>> for I64Register
+where
+    L::Instruction: UintInstructions,
+{
+    type Output = I64Register;
+
+    fn neg(self, builder: &mut BytesBuilder<L>) -> Self::Output {
+        builder.neg(&self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use rand::Rng;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::register::{Register, RegisterSerializable};
+    use crate::chip::trace::writer::{InnerWriterData, TraceWriter};
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::{u32_to_le_field_bytes, u64_to_le_field_bytes};
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RotateU32Test;
+
+    impl AirParameters for RotateU32Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 149;
+        const EXTENDED_COLUMNS: usize = 222;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct RotateU64Test;
+
+    impl AirParameters for RotateU64Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 297;
+        const EXTENDED_COLUMNS: usize = 438;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ShiftU32Test;
+
+    impl AirParameters for ShiftU32Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 131;
+        const EXTENDED_COLUMNS: usize = 150;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ShiftU64Test;
+
+    impl AirParameters for ShiftU64Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 263;
+        const EXTENDED_COLUMNS: usize = 294;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct ChooseMajorityTest;
+
+    impl AirParameters for ChooseMajorityTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 41;
+        const EXTENDED_COLUMNS: usize = 132;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct AndOrTest;
+
+    impl AirParameters for AndOrTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 145;
+        const EXTENDED_COLUMNS: usize = 438;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct CompareU32Test;
+
+    impl AirParameters for CompareU32Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 72;
+        const EXTENDED_COLUMNS: usize = 260;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct CompareU64Test;
+
+    impl AirParameters for CompareU64Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 140;
+        const EXTENDED_COLUMNS: usize = 520;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SignedI32Test;
+
+    impl AirParameters for SignedI32Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 48;
+        const EXTENDED_COLUMNS: usize = 120;
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SignedI64Test;
+
+    impl AirParameters for SignedI64Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 96;
+        const EXTENDED_COLUMNS: usize = 240;
+    }
+
+    #[test]
+    fn test_and_or() {
+        type L = AndOrTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<U32Register>();
+        let b = builder.alloc::<U32Register>();
+        let x = builder.alloc::<U64Register>();
+        let y = builder.alloc::<U64Register>();
+
+        let and_u32 = builder.and(a, b);
+        let or_u32 = builder.or(a, b);
+        let and_u64 = builder.and(x, y);
+        let or_u64 = builder.or(x, y);
+
+        // De Morgan: !(a & b) = !a | !b and !(a | b) = !a & !b.
+        let (not_a, not_b) = (builder.not(a), builder.not(b));
+        let not_and_u32 = builder.not(and_u32);
+        let or_not_u32 = builder.or(not_a, not_b);
+        builder.assert_equal(&not_and_u32, &or_not_u32);
+        let not_or_u32 = builder.not(or_u32);
+        let and_not_u32 = builder.and(not_a, not_b);
+        builder.assert_equal(&not_or_u32, &and_not_u32);
+
+        let (not_x, not_y) = (builder.not(x), builder.not(y));
+        let not_and_u64 = builder.not(and_u64);
+        let or_not_u64 = builder.or(not_x, not_y);
+        builder.assert_equal(&not_and_u64, &or_not_u64);
+        let not_or_u64 = builder.not(or_u64);
+        let and_not_u64 = builder.and(not_x, not_y);
+        builder.assert_equal(&not_or_u64, &and_not_u64);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let (a_val, b_val) = (rng.gen::<u32>(), rng.gen::<u32>());
+            let (x_val, y_val) = (rng.gen::<u64>(), rng.gen::<u64>());
+            writer.write(&a, &u32_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u32_to_le_field_bytes(b_val), i);
+            writer.write(&x, &u64_to_le_field_bytes(x_val), i);
+            writer.write(&y, &u64_to_le_field_bytes(y_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            assert_eq!(
+                writer.read(&and_u32, i),
+                u32_to_le_field_bytes(a_val & b_val)
+            );
+            assert_eq!(
+                writer.read(&or_u32, i),
+                u32_to_le_field_bytes(a_val | b_val)
+            );
+            assert_eq!(
+                writer.read(&and_u64, i),
+                u64_to_le_field_bytes(x_val & y_val)
+            );
+            assert_eq!(
+                writer.read(&or_u64, i),
+                u64_to_le_field_bytes(x_val | y_val)
+            );
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_and_or", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PopcountTest;
+
+    impl AirParameters for PopcountTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 37;
+        const EXTENDED_COLUMNS: usize = 69;
+    }
+
+    #[test]
+    fn test_popcount() {
+        type L = PopcountTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<ByteArrayRegister<1>>();
+        let b = builder.alloc::<ByteArrayRegister<1>>();
+        let x = builder.alloc::<U32Register>();
+        let y = builder.alloc::<U64Register>();
+        let values = [
+            a.to_le_bytes(),
+            b.to_le_bytes(),
+            x.to_le_bytes(),
+            y.to_le_bytes(),
+        ];
+        let counts = [
+            builder.popcount(a),
+            builder.popcount(b),
+            builder.popcount(x),
+            builder.popcount(y),
+        ];
+        let expected = builder.alloc_array::<ElementRegister>(counts.len());
+        for (count, expected_count) in counts.iter().zip(expected.iter()) {
+            builder.assert_equal(count, &expected_count);
+            // The counts can be used in transition constraints.
+            builder.assert_equal_transition(&count.next(), &expected_count.next());
+        }
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            // The first row is all zeros and the second all ones.
+            let mut random_value = || match i {
+                0 => 0u64,
+                1 => u64::MAX,
+                _ => rng.gen::<u64>(),
+            };
+            let words = [
+                random_value() as u8 as u64,
+                random_value() as u8 as u64,
+                random_value() as u32 as u64,
+                random_value(),
+            ];
+            for ((bytes, word), expected_count) in values.iter().zip(words).zip(expected.iter()) {
+                writer.write_array(
+                    bytes,
+                    word.to_le_bytes()[..bytes.len()]
+                        .iter()
+                        .map(|byte| F::from_canonical_u8(*byte)),
+                    i,
+                );
+                writer.write(
+                    &expected_count,
+                    &F::from_canonical_u32(word.count_ones()),
+                    i,
+                );
+            }
+            writer.write_row_instructions(&stark.air_data, i);
+
+            for (count, word) in counts.iter().zip(words) {
+                assert_eq!(
+                    writer.read(count, i),
+                    F::from_canonical_u32(word.count_ones())
+                );
+            }
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_popcount", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct LeadingZerosTest;
+
+    impl AirParameters for LeadingZerosTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_ARITHMETIC_COLUMNS: usize = 0;
+        const NUM_FREE_COLUMNS: usize = 207;
+        const EXTENDED_COLUMNS: usize = 6;
+    }
+
+    #[test]
+    fn test_leading_zeros() {
+        type L = LeadingZerosTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let x = builder.alloc::<U32Register>();
+        let y = builder.alloc::<U64Register>();
+        let x_zeros = builder.leading_zeros(x);
+        let y_zeros = builder.leading_zeros(y);
+        let x_expected = builder.alloc::<ElementRegister>();
+        let y_expected = builder.alloc::<ElementRegister>();
+        builder.assert_equal(&x_zeros, &x_expected);
+        builder.assert_equal(&y_zeros, &y_expected);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            // Zero, all ones, powers of two, and random values of every bit length.
+            let (x_val, y_val) = match i {
+                0 => (0, 0),
+                1 => (u32::MAX, u64::MAX),
+                _ if i % 2 == 0 => (1u32 << i, 1u64 << (2 * i)),
+                _ => (
+                    rng.gen::<u32>() >> rng.gen_range(0..32u32),
+                    rng.gen::<u64>() >> rng.gen_range(0..64u32),
+                ),
+            };
+            writer.write(&x, &u32_to_le_field_bytes(x_val), i);
+            writer.write(&y, &u64_to_le_field_bytes(y_val), i);
+            let x_count = F::from_canonical_u32(x_val.leading_zeros());
+            let y_count = F::from_canonical_u32(y_val.leading_zeros());
+            writer.write(&x_expected, &x_count, i);
+            writer.write(&y_expected, &y_count, i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            assert_eq!(writer.read(&x_zeros, i), x_count);
+            assert_eq!(writer.read(&y_zeros, i), y_count);
+        }
+        assert_eq!(writer.read(&x_zeros, 0), F::from_canonical_u32(32));
+        assert_eq!(writer.read(&y_zeros, 0), F::from_canonical_u32(64));
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_leading_zeros", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_ch_maj_u32() {
+        type L = ChooseMajorityTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<U32Register>();
+        let b = builder.alloc::<U32Register>();
+        let c = builder.alloc::<U32Register>();
+        let ch = builder.ch(a, b, c);
+        let maj = builder.maj(a, b, c);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let (a_val, b_val, c_val) = (rng.gen::<u32>(), rng.gen::<u32>(), rng.gen::<u32>());
+            writer.write(&a, &u32_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u32_to_le_field_bytes(b_val), i);
+            writer.write(&c, &u32_to_le_field_bytes(c_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            let ch_val = (a_val & b_val) ^ (!a_val & c_val);
+            let maj_val = (a_val & b_val) ^ (a_val & c_val) ^ (b_val & c_val);
+            assert_eq!(writer.read(&ch, i), u32_to_le_field_bytes(ch_val));
+            assert_eq!(writer.read(&maj, i), u32_to_le_field_bytes(maj_val));
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_ch_maj_u32", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_left_u32() {
+        type L = RotateU32Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        // Rotations of zero, of whole bytes, of the full width and beyond it.
+        let rotations = [0, 1, 8, 13, 32, 45];
+
+        let mut builder = BytesBuilder::<L>::new();
+        let x = builder.alloc::<U32Register>();
+        let rotated = rotations
+            .iter()
+            .map(|&k| {
+                let left = builder.rotate_left(x, k);
+                let right = builder.rotate_right(x, 32 - k % 32);
+                builder.assert_equal(&left, &right);
+                left
+            })
+            .collect::<Vec<_>>();
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let x_val = rng.gen::<u32>();
+            writer.write(&x, &u32_to_le_field_bytes(x_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            for (register, &k) in rotated.iter().zip(rotations.iter()) {
+                assert_eq!(
+                    writer.read(register, i),
+                    u32_to_le_field_bytes(x_val.rotate_left(k as u32))
+                );
+            }
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_rotate_left_u32", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_rotate_left_u64() {
+        type L = RotateU64Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let rotations = [0, 1, 8, 13, 64, 77];
+
+        let mut builder = BytesBuilder::<L>::new();
+        let x = builder.alloc::<U64Register>();
+        let rotated = rotations
+            .iter()
+            .map(|&k| {
+                let left = builder.rotate_left(x, k);
+                let right = builder.rotate_right(x, 64 - k % 64);
+                builder.assert_equal(&left, &right);
+                left
+            })
+            .collect::<Vec<_>>();
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let x_val = rng.gen::<u64>();
+            writer.write(&x, &u64_to_le_field_bytes(x_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            for (register, &k) in rotated.iter().zip(rotations.iter()) {
+                assert_eq!(
+                    writer.read(register, i),
+                    u64_to_le_field_bytes(x_val.rotate_left(k as u32))
+                );
+            }
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_rotate_left_u64", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_shift_u32() {
+        type L = ShiftU32Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        // Shifts of zero, within a byte, of whole bytes, across bytes, of the full width and
+        // beyond it.
+        let shifts = [0, 1, 7, 8, 13, 31, 32, 45];
+
+        let mut builder = BytesBuilder::<L>::new();
+        let x = builder.alloc::<U32Register>();
+        let shifted = shifts
+            .iter()
+            .map(|&k| (builder.shl(x, k), builder.shr(x, k)))
+            .collect::<Vec<_>>();
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let x_val = match i {
+                0 => u32::MAX,
+                1 => 0x8000_0001,
+                _ => rng.gen::<u32>(),
+            };
+            writer.write(&x, &u32_to_le_field_bytes(x_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            for ((shl, shr), &k) in shifted.iter().zip(shifts.iter()) {
+                assert_eq!(
+                    writer.read(shl, i),
+                    u32_to_le_field_bytes(x_val.checked_shl(k as u32).unwrap_or(0))
+                );
+                assert_eq!(
+                    writer.read(shr, i),
+                    u32_to_le_field_bytes(x_val.checked_shr(k as u32).unwrap_or(0))
+                );
+            }
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_shift_u32", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_shift_u64() {
+        type L = ShiftU64Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let shifts = [0, 1, 7, 8, 13, 63, 64, 77];
+
+        let mut builder = BytesBuilder::<L>::new();
+        let x = builder.alloc::<U64Register>();
+        let shifted = shifts
+            .iter()
+            .map(|&k| (builder.shl(x, k), builder.shr(x, k)))
+            .collect::<Vec<_>>();
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            let x_val = match i {
+                0 => u64::MAX,
+                1 => 0x8000_0000_0000_0001,
+                _ => rng.gen::<u64>(),
+            };
+            writer.write(&x, &u64_to_le_field_bytes(x_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            for ((shl, shr), &k) in shifted.iter().zip(shifts.iter()) {
+                assert_eq!(
+                    writer.read(shl, i),
+                    u64_to_le_field_bytes(x_val.checked_shl(k as u32).unwrap_or(0))
+                );
+                assert_eq!(
+                    writer.read(shr, i),
+                    u64_to_le_field_bytes(x_val.checked_shr(k as u32).unwrap_or(0))
+                );
+            }
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_shift_u64", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_signed_i32() {
+        type L = SignedI32Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<I32Register>();
+        let b = builder.alloc::<I32Register>();
+        let sum = builder.add(a, b);
+        let difference = builder.sub(a, b);
+        let negation = builder.neg(a);
+        let a_is_negative = builder.is_negative(&a);
+        let sum_is_negative = builder.is_negative(&sum);
+
+        let num_rows = 1 << 6;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        // All the pairs of values around the sign boundaries, then random values.
+        let boundaries = [i32::MIN, i32::MIN + 1, -1, 0, 1, i32::MAX];
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            let (a_val, b_val) = if i < boundaries.len() * boundaries.len() {
+                (
+                    boundaries[i % boundaries.len()],
+                    boundaries[i / boundaries.len()],
+                )
+            } else {
+                (rng.gen::<i32>(), rng.gen::<i32>())
+            };
+            writer.write(&a, &u32_to_le_field_bytes(a_val as u32), i);
+            writer.write(&b, &u32_to_le_field_bytes(b_val as u32), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            assert_eq!(
+                writer.read(&sum, i),
+                u32_to_le_field_bytes(a_val.wrapping_add(b_val) as u32)
+            );
+            assert_eq!(
+                writer.read(&difference, i),
+                u32_to_le_field_bytes(a_val.wrapping_sub(b_val) as u32)
+            );
+            assert_eq!(
+                writer.read(&negation, i),
+                u32_to_le_field_bytes(a_val.wrapping_neg() as u32)
+            );
+            assert_eq!(
+                writer.read(&a_is_negative, i),
+                F::from_canonical_u8((a_val < 0) as u8)
+            );
+            assert_eq!(
+                writer.read(&sum_is_negative, i),
+                F::from_canonical_u8((a_val.wrapping_add(b_val) < 0) as u8)
+            );
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_signed_i32", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_signed_i64() {
+        type L = SignedI64Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<I64Register>();
+        let b = builder.alloc::<I64Register>();
+        let sum = builder.add(a, b);
+        let difference = builder.sub(a, b);
+        let negation = builder.neg(a);
+        let a_is_negative = builder.is_negative(&a);
+        let sum_is_negative = builder.is_negative(&sum);
+
+        let num_rows = 1 << 6;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        // All the pairs of values around the sign boundaries, then random values.
+        let boundaries = [i64::MIN, i64::MIN + 1, -1, 0, 1, i64::MAX];
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            let (a_val, b_val) = if i < boundaries.len() * boundaries.len() {
+                (
+                    boundaries[i % boundaries.len()],
+                    boundaries[i / boundaries.len()],
+                )
+            } else {
+                (rng.gen::<i64>(), rng.gen::<i64>())
+            };
+            writer.write(&a, &u64_to_le_field_bytes(a_val as u64), i);
+            writer.write(&b, &u64_to_le_field_bytes(b_val as u64), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            assert_eq!(
+                writer.read(&sum, i),
+                u64_to_le_field_bytes(a_val.wrapping_add(b_val) as u64)
+            );
+            assert_eq!(
+                writer.read(&difference, i),
+                u64_to_le_field_bytes(a_val.wrapping_sub(b_val) as u64)
+            );
+            assert_eq!(
+                writer.read(&negation, i),
+                u64_to_le_field_bytes(a_val.wrapping_neg() as u64)
+            );
+            assert_eq!(
+                writer.read(&a_is_negative, i),
+                F::from_canonical_u8((a_val < 0) as u8)
+            );
+            assert_eq!(
+                writer.read(&sum_is_negative, i),
+                F::from_canonical_u8((a_val.wrapping_add(b_val) < 0) as u8)
+            );
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_signed_i64", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_compare_u32() {
+        type L = CompareU32Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<U32Register>();
+        let b = builder.alloc::<U32Register>();
+        let lt = builder.lt(a, b);
+        let le = builder.le(a, b);
+        let gt = builder.gt(a, b);
+        let ge = builder.ge(a, b);
+        let eq = builder.eq(&a, &b);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        // The full-range boundaries and adjacent values, then random values, every fourth pair
+        // of them equal.
+        let boundaries = [
+            (0, u32::MAX),
+            (u32::MAX, 0),
+            (0, 0),
+            (u32::MAX, u32::MAX),
+            (u32::MAX - 1, u32::MAX),
+            (1, 0),
+        ];
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            let (a_val, b_val) = match boundaries.get(i) {
+                Some(pair) => *pair,
+                None if i % 4 == 0 => {
+                    let value = rng.gen::<u32>();
+                    (value, value)
+                }
+                None => (rng.gen::<u32>(), rng.gen::<u32>()),
+            };
+            writer.write(&a, &u32_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u32_to_le_field_bytes(b_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            let bit = |value: bool| F::from_canonical_u8(value as u8);
+            assert_eq!(writer.read(&lt, i), bit(a_val < b_val));
+            assert_eq!(writer.read(&le, i), bit(a_val <= b_val));
+            assert_eq!(writer.read(&gt, i), bit(a_val > b_val));
+            assert_eq!(writer.read(&ge, i), bit(a_val >= b_val));
+            assert_eq!(writer.read(&eq, i), bit(a_val == b_val));
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_compare_u32", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_compare_u64() {
+        type L = CompareU64Test;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let a = builder.alloc::<U64Register>();
+        let b = builder.alloc::<U64Register>();
+        let lt = builder.lt(a, b);
+        let le = builder.le(a, b);
+        let gt = builder.gt(a, b);
+        let ge = builder.ge(a, b);
+        let eq = builder.eq(&a, &b);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        // The full-range boundaries and adjacent values, then random values, every fourth pair
+        // of them equal.
+        let boundaries = [
+            (0, u64::MAX),
+            (u64::MAX, 0),
+            (0, 0),
+            (u64::MAX, u64::MAX),
+            (u64::MAX - 1, u64::MAX),
+            (1, 0),
+        ];
+        let mut rng = rand::thread_rng();
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            let (a_val, b_val) = match boundaries.get(i) {
+                Some(pair) => *pair,
+                None if i % 4 == 0 => {
+                    let value = rng.gen::<u64>();
+                    (value, value)
+                }
+                None => (rng.gen::<u64>(), rng.gen::<u64>()),
+            };
+            writer.write(&a, &u64_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u64_to_le_field_bytes(b_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            let bit = |value: bool| F::from_canonical_u8(value as u8);
+            assert_eq!(writer.read(&lt, i), bit(a_val < b_val));
+            assert_eq!(writer.read(&le, i), bit(a_val <= b_val));
+            assert_eq!(writer.read(&gt, i), bit(a_val > b_val));
+            assert_eq!(writer.read(&ge, i), bit(a_val >= b_val));
+            assert_eq!(writer.read(&eq, i), bit(a_val == b_val));
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_compare_u64", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+}
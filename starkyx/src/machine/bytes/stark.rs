@@ -50,36 +50,32 @@ where
     pub(crate) lookup_table: ByteLogLookupTable<L::Field, L::CubicParams>,
 }
 
-impl<L: AirParameters, C, const D: usize> ByteStark<L, C, D>
-where
-    L::Field: RichField + Extendable<D>,
-    C: CurtaConfig<D, F = L::Field, FE = <L::Field as Extendable<D>>::Extension>,
-    Chip<L>: Plonky2Air<L::Field, D>,
-{
-    pub const fn stark(&self) -> &Starky<Chip<L>> {
-        &self.stark
-    }
-
-    pub const fn config(&self) -> &StarkyConfig<C, D> {
-        &self.config
-    }
-
-    pub const fn lookup_stark(&self) -> &Starky<ByteAir<L::Field, L::CubicParams>> {
-        &self.lookup_stark
-    }
-
-    pub const fn lookup_config(&self) -> &StarkyConfig<C, D> {
-        &self.lookup_config
-    }
+/// The trace writers of a `ByteStark` and the commitments to its execution traces, before the
+/// challenges of the AIR are drawn.
+pub(crate) struct ByteStarkExecution<
+    F: RichField + Extendable<D>,
+    C: CurtaConfig<D, F = F>,
+    const D: usize,
+> {
+    main_writer: TraceWriter<F>,
+    lookup_writer: TraceWriter<F>,
+    main_execution_commitment: PolynomialBatch<F, C::GenericConfig, D>,
+    lookup_multiplicity_commitment: PolynomialBatch<F, C::GenericConfig, D>,
+    lookup_preprocessed_commitment: PolynomialBatch<F, C::GenericConfig, D>,
+}
 
-    fn get_preprocessed_byte_trace(
-        &self,
-        lookup_writer: &TraceWriter<L::Field>,
-    ) -> PolynomialBatch<L::Field, C::GenericConfig, D> {
-        get_preprocessed_byte_trace(lookup_writer, &self.lookup_config, &self.lookup_stark)
-    }
+/// The data needed to write the traces of a `ByteStark`, borrowed from the stark or from the
+/// `ByteAirs` it is built from.
+pub(crate) struct ByteTraceData<'a, L: AirParameters> {
+    pub(crate) air_data: &'a AirTraceData<L>,
+    pub(crate) execution_trace_length: usize,
+    pub(crate) multiplicity_data: &'a ByteMultiplicityData,
+    pub(crate) lookup_air_data: &'a AirTraceData<ByteParameters<L::Field, L::CubicParams>>,
+    pub(crate) lookup_table: &'a ByteLogLookupTable<L::Field, L::CubicParams>,
+}
 
-    fn generate_execution_traces(
+impl<'a, L: AirParameters> ByteTraceData<'a, L> {
+    pub(crate) fn generate_execution_traces(
         &self,
         execution_trace: &AirTrace<L::Field>,
         public_values: &[L::Field],
@@ -89,7 +85,7 @@ where
         let lookup_writer = TraceWriter::new(&self.lookup_air_data, NUM_LOOKUP_ROWS);
 
         // Insert execution trace and into main writer.
-        let execution_trace_length = self.stark.air.execution_trace_length;
+        let execution_trace_length = self.execution_trace_length;
         main_writer
             .write_trace()
             .unwrap()
@@ -109,6 +105,9 @@ where
             .unwrap()
             .copy_from_slice(public_values);
 
+        // Write the range check tables.
+        self.air_data.write_range_checks(&main_writer);
+
         // Write lookup table values
         self.lookup_table.write_table_entries(&lookup_writer);
         for i in 0..NUM_LOOKUP_ROWS {
@@ -122,7 +121,7 @@ where
         (main_writer, lookup_writer)
     }
 
-    fn generate_extended_traces(
+    pub(crate) fn generate_extended_traces(
         &self,
         main_writer: &TraceWriter<L::Field>,
         lookup_writer: &TraceWriter<L::Field>,
@@ -146,20 +145,64 @@ where
             .unwrap()
             .copy_from_slice(&main_writer.global.read().unwrap());
     }
+}
 
-    fn generate_trace(
+impl<L: AirParameters, C, const D: usize> ByteStark<L, C, D>
+where
+    L::Field: RichField + Extendable<D>,
+    C: CurtaConfig<D, F = L::Field, FE = <L::Field as Extendable<D>>::Extension>,
+    Chip<L>: Plonky2Air<L::Field, D>,
+{
+    pub const fn stark(&self) -> &Starky<Chip<L>> {
+        &self.stark
+    }
+
+    pub const fn config(&self) -> &StarkyConfig<C, D> {
+        &self.config
+    }
+
+    pub const fn lookup_stark(&self) -> &Starky<ByteAir<L::Field, L::CubicParams>> {
+        &self.lookup_stark
+    }
+
+    pub const fn lookup_config(&self) -> &StarkyConfig<C, D> {
+        &self.lookup_config
+    }
+
+    fn get_preprocessed_byte_trace(
+        &self,
+        lookup_writer: &TraceWriter<L::Field>,
+    ) -> PolynomialBatch<L::Field, C::GenericConfig, D> {
+        get_preprocessed_byte_trace(lookup_writer, &self.lookup_config, &self.lookup_stark)
+    }
+
+    /// The data to write the traces of the stark with.
+    pub(crate) fn trace_data(&self) -> ByteTraceData<'_, L> {
+        ByteTraceData {
+            air_data: &self.air_data,
+            execution_trace_length: self.stark.air.execution_trace_length,
+            multiplicity_data: &self.multiplicity_data,
+            lookup_air_data: &self.lookup_air_data,
+            lookup_table: &self.lookup_table,
+        }
+    }
+
+    /// Observes the public values, writes the execution traces and commits to them, observing
+    /// the commitments into the challenger.
+    pub(crate) fn commit_execution_traces(
         &self,
         execution_trace: &AirTrace<L::Field>,
         public_values: &[L::Field],
         challenger: &mut Challenger<L::Field, C::Hasher>,
         timing: &mut TimingTree,
-    ) -> (AirCommitment<L::Field, C, D>, AirCommitment<L::Field, C, D>) {
+    ) -> ByteStarkExecution<L::Field, C, D> {
         // Absorve public values into the challenger.
         challenger.observe_elements(public_values);
 
         // Generate execution traces.
-        let (main_writer, lookup_writer) =
-            self.generate_execution_traces(execution_trace, public_values);
+        let (main_writer, lookup_writer) = self
+            .trace_data()
+            .generate_execution_traces(execution_trace, public_values);
 
         let main_execution_trace_values = main_writer
             .read_trace()
@@ -209,22 +252,47 @@ where
         challenger.observe_cap(&main_execution_commitment.merkle_tree.cap);
         challenger.observe_cap(&lookup_multiplicity_commitment.merkle_tree.cap);
 
-        // Get random AIR challenges.
-        let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
+        ByteStarkExecution {
+            main_writer,
+            lookup_writer,
+            main_execution_commitment,
+            lookup_multiplicity_commitment,
+            lookup_preprocessed_commitment,
+        }
+    }
+
+    /// Writes the extended traces with the AIR challenges `challenges` and commits to them,
+    /// observing the global values and the commitments into the challenger.
+    pub(crate) fn commit_extended_traces(
+        &self,
+        execution: ByteStarkExecution<L::Field, C, D>,
+        challenges: &[L::Field],
+        challenger: &mut Challenger<L::Field, C::Hasher>,
+        timing: &mut TimingTree,
+    ) -> (AirCommitment<L::Field, C, D>, AirCommitment<L::Field, C, D>) {
+        let ByteStarkExecution {
+            main_writer,
+            lookup_writer,
+            main_execution_commitment,
+            lookup_multiplicity_commitment,
+            lookup_preprocessed_commitment,
+        } = execution;
+
         // Save challenges to both writers.
         main_writer
             .challenges
             .write()
             .unwrap()
-            .extend_from_slice(&challenges);
+            .extend_from_slice(challenges);
         lookup_writer
             .challenges
             .write()
             .unwrap()
-            .extend_from_slice(&challenges);
+            .extend_from_slice(challenges);
 
         // Generate extended traces.
-        self.generate_extended_traces(&main_writer, &lookup_writer);
+        self.trace_data()
+            .generate_extended_traces(&main_writer, &lookup_writer);
 
         let InnerWriterData {
             trace: main_trace,
@@ -298,22 +366,30 @@ where
         )
     }
 
-    pub fn prove(
+    fn generate_trace(
         &self,
         execution_trace: &AirTrace<L::Field>,
         public_values: &[L::Field],
+        challenger: &mut Challenger<L::Field, C::Hasher>,
         timing: &mut TimingTree,
-    ) -> Result<ByteStarkProof<L::Field, C, D>> {
-        // Initialize challenger.
-        let mut challenger = Challenger::new();
+    ) -> (AirCommitment<L::Field, C, D>, AirCommitment<L::Field, C, D>) {
+        let execution =
+            self.commit_execution_traces(execution_trace, public_values, challenger, timing);
 
-        // Generate stark commitment.
-        let (main_air_commitment, lookup_air_commitment) = timed!(
-            timing,
-            "Generate stark trace",
-            self.generate_trace(execution_trace, public_values, &mut challenger, timing)
-        );
+        // Get random AIR challenges.
+        let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
+
+        self.commit_extended_traces(execution, &challenges, challenger, timing)
+    }
 
+    /// Proves the main and the lookup AIRs from their commitments.
+    pub(crate) fn prove_with_commitments(
+        &self,
+        main_air_commitment: AirCommitment<L::Field, C, D>,
+        lookup_air_commitment: AirCommitment<L::Field, C, D>,
+        challenger: &mut Challenger<L::Field, C::Hasher>,
+        timing: &mut TimingTree,
+    ) -> Result<ByteStarkProof<L::Field, C, D>> {
         // Generate individual stark proofs.
         let main_proof = timed!(
             timing,
@@ -322,7 +398,7 @@ where
                 &self.config,
                 &self.stark,
                 main_air_commitment,
-                &mut challenger,
+                challenger,
                 &mut TimingTree::default(),
             )?
         );
@@ -334,7 +410,7 @@ where
                 &self.lookup_config,
                 &self.lookup_stark,
                 lookup_air_commitment,
-                &mut challenger,
+                challenger,
                 &mut TimingTree::default(),
             )?
         );
@@ -347,14 +423,38 @@ where
         })
     }
 
-    pub fn get_challenges(
+    pub fn prove(
         &self,
-        proof: &ByteStarkProof<L::Field, C, D>,
+        execution_trace: &AirTrace<L::Field>,
         public_values: &[L::Field],
-    ) -> ByteStarkChallenges<L::Field, D> {
+        timing: &mut TimingTree,
+    ) -> Result<ByteStarkProof<L::Field, C, D>> {
         // Initialize challenger.
-        let mut challenger = Challenger::<L::Field, C::Hasher>::new();
+        let mut challenger = Challenger::new();
+
+        // Generate stark commitment.
+        let (main_air_commitment, lookup_air_commitment) = timed!(
+            timing,
+            "Generate stark trace",
+            self.generate_trace(execution_trace, public_values, &mut challenger, timing)
+        );
 
+        self.prove_with_commitments(
+            main_air_commitment,
+            lookup_air_commitment,
+            &mut challenger,
+            timing,
+        )
+    }
+
+    /// Observes the public values and the execution trace commitments of `proof`, in the order
+    /// of `commit_execution_traces`.
+    pub(crate) fn observe_execution_caps(
+        &self,
+        proof: &ByteStarkProof<L::Field, C, D>,
+        public_values: &[L::Field],
+        challenger: &mut Challenger<L::Field, C::Hasher>,
+    ) {
         // Observe public values.
         challenger.observe_elements(public_values);
 
@@ -364,28 +464,40 @@ where
         // Observe execution trace commitments.
         challenger.observe_cap(&proof.main_proof.trace_caps[0]);
         challenger.observe_cap(&proof.lookup_proof.trace_caps[0]);
+    }
 
-        // Get challenges.
-        let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
-
+    /// Observes the global values and the extended trace commitments of `proof`, in the order of
+    /// `commit_extended_traces`.
+    pub(crate) fn observe_extended_caps(
+        &self,
+        proof: &ByteStarkProof<L::Field, C, D>,
+        challenger: &mut Challenger<L::Field, C::Hasher>,
+    ) {
         // Observe global values.
         challenger.observe_elements(&proof.global_values);
         // Observe extended trace commitments.
         challenger.observe_cap(&proof.main_proof.trace_caps[1]);
         challenger.observe_cap(&proof.lookup_proof.trace_caps[2]);
+    }
 
-        // Get all challenges.
+    /// The challenges of the IOPs of both AIRs, given the AIR challenges `challenges`.
+    pub(crate) fn get_iop_challenges(
+        &self,
+        proof: &ByteStarkProof<L::Field, C, D>,
+        challenges: Vec<L::Field>,
+        challenger: &mut Challenger<L::Field, C::Hasher>,
+    ) -> ByteStarkChallenges<L::Field, D> {
         let main_challenges = proof.main_proof.get_iop_challenges(
             &self.config,
             self.config.degree_bits,
             challenges.clone(),
-            &mut challenger,
+            challenger,
         );
         let lookup_challenges = proof.lookup_proof.get_iop_challenges(
             &self.lookup_config,
             self.lookup_config.degree_bits,
             challenges,
-            &mut challenger,
+            challenger,
         );
 
         ByteStarkChallenges {
@@ -394,15 +506,36 @@ where
         }
     }
 
-    pub fn verify(
+    pub fn get_challenges(
+        &self,
+        proof: &ByteStarkProof<L::Field, C, D>,
+        public_values: &[L::Field],
+    ) -> ByteStarkChallenges<L::Field, D> {
+        // Initialize challenger.
+        let mut challenger = Challenger::<L::Field, C::Hasher>::new();
+
+        self.observe_execution_caps(proof, public_values, &mut challenger);
+
+        // Get challenges.
+        let challenges = challenger.get_n_challenges(self.stark.air.num_challenges);
+
+        self.observe_extended_caps(proof, &mut challenger);
+
+        // Get all challenges.
+        self.get_iop_challenges(proof, challenges, &mut challenger)
+    }
+
+    /// Verifies `proof` with the challenges `challenges`.
+    pub(crate) fn verify_with_challenges(
         &self,
         proof: ByteStarkProof<L::Field, C, D>,
         public_values: &[L::Field],
+        challenges: ByteStarkChallenges<L::Field, D>,
     ) -> Result<()> {
         let ByteStarkChallenges {
             main_challenges,
             lookup_challenges,
-        } = self.get_challenges(&proof, public_values);
+        } = challenges;
 
         let ByteStarkProof {
             main_proof,
@@ -433,6 +566,15 @@ where
         )
     }
 
+    pub fn verify(
+        &self,
+        proof: ByteStarkProof<L::Field, C, D>,
+        public_values: &[L::Field],
+    ) -> Result<()> {
+        let challenges = self.get_challenges(&proof, public_values);
+        self.verify_with_challenges(proof, public_values, challenges)
+    }
+
     pub fn add_virtual_proof_with_pis_target(
         &self,
         builder: &mut CircuitBuilder<L::Field, D>,
@@ -569,11 +711,14 @@ mod tests {
 
     use super::*;
     use crate::chip::memory::time::Time;
+    use crate::chip::register::bit::BitRegister;
     use crate::chip::register::element::ElementRegister;
     use crate::chip::register::Register;
+    use crate::chip::uint::bytes::bit_operations::xor::Xor;
+    use crate::chip::uint::bytes::lookup_table::ByteInstructionSet;
     use crate::chip::uint::operations::instruction::UintInstruction;
-    use crate::chip::uint::register::U32Register;
-    use crate::chip::uint::util::u32_to_le_field_bytes;
+    use crate::chip::uint::register::{U32Register, U64Register};
+    use crate::chip::uint::util::{u32_to_le_field_bytes, u64_to_le_field_bytes};
     use crate::machine::builder::Builder;
     use crate::machine::bytes::builder::BytesBuilder;
     use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
@@ -841,4 +986,203 @@ mod tests {
 
         timing.print();
     }
+
+    /// Proves that the message of two words `message` starts with `prefix`.
+    fn prove_bytes_prefix(message: &[u8; 16], prefix: &[u8]) {
+        type L = ByteSliceMemTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mut timing = TimingTree::new("test_assert_bytes_prefix", log::Level::Debug);
+
+        let mut builder = BytesBuilder::<L>::new();
+        let num_words = 2;
+        let words = builder.alloc_array_public::<U64Register>(num_words);
+        let message_ptr = builder.initialize_slice::<U64Register>(&words, &Time::zero(), None);
+
+        builder.assert_bytes_prefix(&message_ptr, &Time::zero(), prefix);
+
+        let num_rows = 1 << 8;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        let word_values = message
+            .chunks_exact(8)
+            .map(|chunk| {
+                <[u8; 8]>::try_from(chunk)
+                    .unwrap()
+                    .map(GoldilocksField::from_canonical_u8)
+            })
+            .collect::<Vec<_>>();
+        writer.write_array(&words, word_values, 0);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            writer.write_row_instructions(&stark.air_data, i);
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_assert_bytes_prefix() {
+        // The prefix ends in the middle of the second word.
+        let message = b"structured messa";
+        prove_bytes_prefix(message, &message[..10]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_bytes_prefix_wrong_prefix() {
+        // The prefix differs from the message in its last byte, in the second word.
+        prove_bytes_prefix(b"structured messa", b"structuref");
+    }
+
+    fn prove_monotonic(values: impl Fn(usize) -> u32) {
+        type L = ByteTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mut timing = TimingTree::new("test_assert_monotonic", log::Level::Debug);
+
+        let mut builder = BytesBuilder::<L>::new();
+        let counter = builder.alloc::<ElementRegister>();
+        builder.assert_monotonic(&counter);
+
+        let num_rows = 1 << 5;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        for i in 0..num_rows {
+            writer.write(&counter, &GoldilocksField::from_canonical_u32(values(i)), i);
+        }
+        for i in 0..num_rows {
+            writer.write_row_instructions(&stark.air_data, i);
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_assert_monotonic() {
+        // Consecutive values are allowed to be equal.
+        prove_monotonic(|i| (i / 3) as u32);
+        prove_monotonic(|i| (i * 255) as u32);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_monotonic_decrease() {
+        prove_monotonic(|i| if i == 10 { 2 } else { i as u32 });
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct XorDifferentialTest;
+
+    impl AirParameters for XorDifferentialTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 225;
+        const EXTENDED_COLUMNS: usize = 42;
+    }
+
+    #[test]
+    fn test_xor_lookup_matches_bit_instruction() {
+        type F = GoldilocksField;
+        type L = XorDifferentialTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let _ = env_logger::builder().is_test(true).try_init();
+
+        let mut timing = TimingTree::new("test_xor_differential", log::Level::Debug);
+
+        let mut builder = BytesBuilder::<L>::new();
+
+        let a = builder.alloc::<U64Register>();
+        let b = builder.alloc::<U64Register>();
+
+        // The XOR through the byte lookup table.
+        let lookup_result = builder.xor(&a, &b);
+
+        // The XOR through the bit instruction, on the bit decomposition of every byte.
+        let a_bits = builder.alloc_array::<BitRegister>(64);
+        let b_bits = builder.alloc_array::<BitRegister>(64);
+        let result_bits = builder.alloc_array::<BitRegister>(64);
+        let instruction_result = builder.alloc::<U64Register>();
+        for k in 0..8 {
+            let range = 8 * k..8 * (k + 1);
+            let a_byte_bits = a_bits.get_subarray(range.clone());
+            let b_byte_bits = b_bits.get_subarray(range.clone());
+            let result_byte_bits = result_bits.get_subarray(range);
+            builder
+                .api
+                .decode_byte(&a.to_le_bytes().get(k), &a_byte_bits);
+            builder
+                .api
+                .decode_byte(&b.to_le_bytes().get(k), &b_byte_bits);
+            builder
+                .api
+                .register_instruction(ByteInstructionSet::from(Xor::<8> {
+                    a: a_byte_bits,
+                    b: b_byte_bits,
+                    result: result_byte_bits,
+                }));
+            let byte = result_byte_bits
+                .iter()
+                .enumerate()
+                .map(|(i, bit)| bit.expr::<F>() * F::from_canonical_u32(1 << i))
+                .reduce(|acc, term| acc + term)
+                .unwrap();
+            builder.set_to_expression(&instruction_result.to_le_bytes().get(k), byte);
+        }
+
+        builder.assert_equal(&lookup_result, &instruction_result);
+
+        let num_rows = 1 << 8;
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+
+        let mut rng = rand::thread_rng();
+        let same = rng.gen::<u64>();
+        let inputs = [
+            (0, 0),
+            (u64::MAX, 0),
+            (0, u64::MAX),
+            (u64::MAX, u64::MAX),
+            (same, same),
+        ]
+        .into_iter()
+        .chain(core::iter::repeat_with(|| {
+            (rng.gen::<u64>(), rng.gen::<u64>())
+        }))
+        .take(num_rows);
+        for (i, (a_val, b_val)) in inputs.enumerate() {
+            let bits = |x: u64| (0..64).map(move |j| F::from_canonical_u64((x >> j) & 1));
+            writer.write(&a, &u64_to_le_field_bytes(a_val), i);
+            writer.write(&b, &u64_to_le_field_bytes(b_val), i);
+            writer.write_array(&a_bits, bits(a_val), i);
+            writer.write_array(&b_bits, bits(b_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+            assert_eq!(
+                writer.read(&instruction_result, i),
+                u64_to_le_field_bytes(a_val ^ b_val)
+            );
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+
+        stark.verify(proof, &public).unwrap();
+    }
 }
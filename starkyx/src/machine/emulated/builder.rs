@@ -47,8 +47,9 @@ impl<L: AirParameters> EmulatedBuilder<L> {
     ) -> EmulatedStark<L, C, D> {
         let EmulatedBuilder { mut api, .. } = self;
         let shared_memory = api.shared_memory.clone();
-        let mut lookup_builder =
-            AirBuilder::<RangeParameters<L::Field, L::CubicParams>>::init(shared_memory);
+        let mut lookup_builder = AirBuilder::<
+            RangeParameters<L::Field, L::CubicParams, L::LookupExtension>,
+        >::init(shared_memory);
 
         // Lookup table entry.
         let lookup_table = lookup_builder.clock();
@@ -3,23 +3,30 @@ use core::marker::PhantomData;
 use serde::{Deserialize, Serialize};
 
 use crate::chip::instruction::empty::EmptyInstruction;
+use crate::chip::register::extension::ChallengeExtension;
 use crate::chip::AirParameters;
+use crate::math::prelude::cubic::extension::CubicExtension;
 use crate::math::prelude::*;
 
 pub mod builder;
 pub mod proof;
 pub mod stark;
 
+/// The parameters of the AIR of the range check table of an emulated AIR, whose lookup draws its
+/// challenge from the extension `X`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct RangeParameters<F, E>(pub PhantomData<(F, E)>);
+pub struct RangeParameters<F, E, X = CubicExtension<F, E>>(pub PhantomData<(F, E, X)>);
 
-impl<F: PrimeField64, E: CubicParameters<F>> AirParameters for RangeParameters<F, E> {
+impl<F: PrimeField64, E: CubicParameters<F>, X: ChallengeExtension<F>> AirParameters
+    for RangeParameters<F, E, X>
+{
     type Field = F;
     type CubicParams = E;
+    type LookupExtension = X;
 
     type Instruction = EmptyInstruction<F>;
 
     const NUM_ARITHMETIC_COLUMNS: usize = 0;
     const NUM_FREE_COLUMNS: usize = 2;
-    const EXTENDED_COLUMNS: usize = 6;
+    const EXTENDED_COLUMNS: usize = 2 * X::D;
 }
@@ -39,9 +39,12 @@ pub struct EmulatedStark<L: AirParameters, C, const D: usize> {
     pub stark: Starky<Chip<L>>,
     pub air_data: AirTraceData<L>,
     pub(crate) lookup_config: StarkyConfig<C, D>,
-    pub(crate) lookup_stark: Starky<Chip<RangeParameters<L::Field, L::CubicParams>>>,
-    pub(crate) lookup_air_data: AirTraceData<RangeParameters<L::Field, L::CubicParams>>,
-    pub(crate) lookup_values: LogLookupValues<ElementRegister, L::Field, L::CubicParams>,
+    pub(crate) lookup_stark:
+        Starky<Chip<RangeParameters<L::Field, L::CubicParams, L::LookupExtension>>>,
+    pub(crate) lookup_air_data:
+        AirTraceData<RangeParameters<L::Field, L::CubicParams, L::LookupExtension>>,
+    pub(crate) lookup_values:
+        LogLookupValues<ElementRegister, L::Field, L::CubicParams, L::LookupExtension>,
     pub(crate) lookup_table: ElementRegister,
     pub(crate) multiplicity: ArrayRegister<ElementRegister>,
 }
@@ -51,6 +54,7 @@ where
     L::Field: RichField + Extendable<D>,
     C: CurtaConfig<D, F = L::Field, FE = <L::Field as Extendable<D>>::Extension>,
     Chip<L>: Plonky2Air<L::Field, D>,
+    Chip<RangeParameters<L::Field, L::CubicParams, L::LookupExtension>>: Plonky2Air<L::Field, D>,
 {
     pub const fn stark(&self) -> &Starky<Chip<L>> {
         &self.stark
@@ -60,7 +64,9 @@ where
         &self.config
     }
 
-    pub const fn lookup_stark(&self) -> &Starky<Chip<RangeParameters<L::Field, L::CubicParams>>> {
+    pub const fn lookup_stark(
+        &self,
+    ) -> &Starky<Chip<RangeParameters<L::Field, L::CubicParams, L::LookupExtension>>> {
         &self.lookup_stark
     }
 
@@ -103,6 +109,9 @@ where
             .unwrap()
             .copy_from_slice(public_values);
 
+        // Write the range check tables.
+        self.air_data.write_range_checks(&main_writer);
+
         // Write lookup table values
         for i in 0..NUM_LOOKUP_ROWS {
             lookup_writer.write(&self.lookup_table, &L::Field::from_canonical_usize(i), i);
@@ -256,7 +265,7 @@ where
             .collect::<Vec<_>>();
         let lookup_extended_trace = AirTrace {
             values: lookup_extended_trace_values,
-            width: RangeParameters::<L::Field, L::CubicParams>::num_columns()
+            width: RangeParameters::<L::Field, L::CubicParams, L::LookupExtension>::num_columns()
                 - self.lookup_stark.air.execution_trace_length,
         };
         let lookup_extended_commitment = timed!(
@@ -0,0 +1,1472 @@
+use log::debug;
+use num::Zero;
+
+use super::data::{
+    BLAKE2ConstNums, BLAKE2Consts, BLAKE2Data, BLAKE2Memory, BLAKE2Mode, BLAKE2ModeTrace,
+    BLAKE2PublicData, BLAKE2TraceData, CompressFlag, CompressRows,
+};
+use super::{BLAKE2, MIX_LENGTH, MSG_ARRAY_SIZE, STATE_SIZE};
+use crate::chip::memory::instruction::MemorySliceIndex;
+use crate::chip::memory::pointer::slice::Slice;
+use crate::chip::memory::time::Time;
+use crate::chip::memory::value::MemoryValue;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSerializable};
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::AirParameters;
+use crate::machine::builder::flag::{CyclicFlag, FlagUpdate};
+use crate::machine::builder::ops::Xor;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::blake::blake2b::air::DummyMemoryValues;
+use crate::machine::hash::blake::blake2b::data::MemoryArray;
+use crate::machine::hash::blake::blake2b::{V_INDICES, V_LAST_WRITE_AGES};
+use crate::machine::hash::HashIntConversion;
+use crate::math::prelude::*;
+
+/// The AIR of the compresses of a BLAKE2 hash function, shared by BLAKE2b, BLAKE2s and BLAKE3.
+///
+/// A compress takes `COMPRESS_LENGTH` rows, one for each mix of its rounds. The work vector is
+/// held in memory, where every mix reads its four words and writes them back, and the rows after
+/// the real compresses are filled with dummy compresses whose accesses are redirected to dummy
+/// slots.
+pub trait BLAKE2Air<B: Builder>: BLAKE2<B> {
+    fn blake2_const_nums(builder: &mut B) -> BLAKE2ConstNums<Self::IntRegister>;
+
+    fn blake2_consts(
+        builder: &mut B,
+        num_rows: usize,
+        num_compresses: usize,
+        num_total_mix_iterations: usize,
+        num_mix_iterations_last_compress: usize,
+    ) -> BLAKE2Consts<B, Self>;
+
+    #[allow(clippy::too_many_arguments)]
+    fn blake2_trace_data(
+        builder: &mut B,
+        const_nums: &BLAKE2ConstNums<Self::IntRegister>,
+        consts: &BLAKE2Consts<B, Self>,
+        mode: &BLAKE2Mode<Self::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        num_dummy_compresses: usize,
+        length_last_compress: usize,
+        length_last_compress_element: &ElementRegister,
+    ) -> BLAKE2TraceData;
+
+    #[allow(clippy::too_many_arguments)]
+    fn blake2_memory(
+        builder: &mut B,
+        padded_chunks: &[(usize, ArrayRegister<Self::IntRegister>)],
+        mode: &BLAKE2Mode<Self::IntRegister>,
+        const_nums: &BLAKE2ConstNums<Self::IntRegister>,
+        consts: &BLAKE2Consts<B, Self>,
+        num_messages_element: &ElementRegister,
+        num_real_compresses: usize,
+        num_real_compresses_element: &ElementRegister,
+        num_dummy_rows: usize,
+    ) -> BLAKE2Memory<Self::IntRegister>;
+
+    /// Allocates the registers and memory of the compresses flagged in `end_bits`, chained by
+    /// `mode`.
+    ///
+    /// The message words of the compresses are given by `padded_chunks`, with the index of their
+    /// compress, and the state is stored at the compresses flagged in `digest_bits`.
+    #[allow(clippy::too_many_arguments)]
+    fn blake2_data(
+        builder: &mut B,
+        padded_chunks: &[(usize, ArrayRegister<Self::IntRegister>)],
+        mode: &BLAKE2Mode<Self::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages_element: &ElementRegister,
+    ) -> BLAKE2Data<B, Self>;
+
+    fn blake2_compress_initialize(
+        builder: &mut B,
+        data: &BLAKE2Data<B, Self>,
+    ) -> ([ElementRegister; 4], [Self::IntRegister; 4]);
+
+    fn blake2_compress(
+        builder: &mut B,
+        v_indices: &[ElementRegister; 4],
+        v_values: &[Self::IntRegister; 4],
+        data: &BLAKE2Data<B, Self>,
+    );
+
+    fn blake2_compress_finalize(
+        builder: &mut B,
+        state_ptr: &Slice<Self::IntRegister>,
+        data: &BLAKE2Data<B, Self>,
+    );
+
+    /// Runs the compresses described by `data`, storing the first `data.consts.digest_words`
+    /// words of the state to `state_ptr` at every digest compress, at the time of its compress id.
+    fn blake2_hash(
+        builder: &mut B,
+        state_ptr: &Slice<Self::IntRegister>,
+        data: &BLAKE2Data<B, Self>,
+    );
+
+    /// Hashes the compresses described by `data`, returning public registers with the first
+    /// `data.consts.digest_words` words of the state at every digest compress.
+    fn blake2_states(
+        builder: &mut B,
+        data: &BLAKE2Data<B, Self>,
+    ) -> Vec<ArrayRegister<Self::IntRegister>>;
+}
+
+/// Stores the value of every compress in a new slice, followed by `dummy_value` for the dummy
+/// compresses. Every row of a compress reads the value of its compress once.
+fn compress_values<L: AirParameters, V: MemoryValue, W>(
+    builder: &mut BytesBuilder<L>,
+    values: &[V],
+    dummy_value: V,
+    num_total_compresses: usize,
+    const_nums: &BLAKE2ConstNums<W>,
+    length_last_compress_element: &ElementRegister,
+    label: &str,
+) -> Slice<V> {
+    let slice = builder.uninit_slice();
+    let last_compress_idx = num_total_compresses - 1;
+    for i in 0..num_total_compresses {
+        builder.store(
+            &slice.get(i),
+            values.get(i).copied().unwrap_or(dummy_value),
+            &Time::zero(),
+            Some(if i < last_compress_idx {
+                const_nums.const_compress_length
+            } else {
+                *length_last_compress_element
+            }),
+            Some(label.to_string()),
+            Some(MemorySliceIndex::Index(i)),
+        );
+    }
+    slice
+}
+
+impl<L: AirParameters, H> BLAKE2Air<BytesBuilder<L>> for H
+where
+    L::Instruction: UintInstructions,
+    H: BLAKE2<BytesBuilder<L>>,
+    H::IntRegister: Xor<BytesBuilder<L>, Output = H::IntRegister>,
+{
+    fn blake2_const_nums(builder: &mut BytesBuilder<L>) -> BLAKE2ConstNums<Self::IntRegister> {
+        let word = <Self as HashIntConversion<BytesBuilder<L>>>::int_to_field_value;
+        BLAKE2ConstNums {
+            const_0: builder.constant(&L::Field::from_canonical_u8(0)),
+            const_0_word: builder.constant(&word(Self::Integer::zero())),
+            const_1: builder.constant(&L::Field::from_canonical_u8(1)),
+            const_2: builder.constant(&L::Field::from_canonical_u8(2)),
+            const_3: builder.constant(&L::Field::from_canonical_u8(3)),
+            const_4: builder.constant(&L::Field::from_canonical_u8(4)),
+            const_8: builder.constant(&L::Field::from_canonical_u8(8)),
+            const_16: builder.constant(&L::Field::from_canonical_u8(16)),
+            const_num_mix_rounds: builder
+                .constant(&L::Field::from_canonical_usize(Self::NUM_MIX_ROUNDS)),
+            const_compress_length: builder
+                .constant(&L::Field::from_canonical_usize(Self::COMPRESS_LENGTH)),
+            const_word_max: builder.constant(&word(Self::WORD_MAX)),
+        }
+    }
+
+    fn blake2_consts(
+        builder: &mut BytesBuilder<L>,
+        num_rows: usize,
+        num_compresses: usize,
+        num_total_mix_iterations: usize,
+        num_mix_iterations_last_compress: usize,
+    ) -> BLAKE2Consts<BytesBuilder<L>, Self> {
+        let dummy_values = DummyMemoryValues::for_compress_length(num_rows, Self::COMPRESS_LENGTH);
+
+        assert!(dummy_values.index < L::Field::order());
+        let dummy_index: ElementRegister =
+            builder.constant(&L::Field::from_canonical_u64(dummy_values.index));
+
+        let dummy_index_2: ElementRegister =
+            builder.constant(&L::Field::from_canonical_u64(dummy_values.index_2));
+
+        builder.api.reserve_timestamp(dummy_values.ts);
+        let dummy_ts: ElementRegister =
+            builder.constant(&L::Field::from_canonical_u64(dummy_values.ts));
+
+        assert!(dummy_values.first_compress_h_read_ts < L::Field::order());
+        let first_compress_h_read_ts: ElementRegister = builder.constant(
+            &L::Field::from_canonical_u64(dummy_values.first_compress_h_read_ts),
+        );
+
+        let iv_values = builder.constant_array::<Self::IntRegister>(
+            &Self::IV.map(<Self as HashIntConversion<BytesBuilder<L>>>::int_to_field_value),
+        );
+        let num_total_mix_iterations_element = builder
+            .constant::<ElementRegister>(&L::Field::from_canonical_usize(num_total_mix_iterations));
+        let mut v_indices = MemoryArray::<BytesBuilder<L>, MIX_LENGTH, 4>::new(builder);
+        for (i, indices) in V_INDICES.iter().enumerate() {
+            v_indices.store_row(
+                builder,
+                i,
+                indices,
+                num_total_mix_iterations_element,
+                Some("v_indices".to_string()),
+            );
+        }
+
+        let mut v_last_write_ages = MemoryArray::<BytesBuilder<L>, MIX_LENGTH, 4>::new(builder);
+        for (i, ages) in V_LAST_WRITE_AGES.iter().enumerate() {
+            v_last_write_ages.store_row(
+                builder,
+                i,
+                ages,
+                num_total_mix_iterations_element,
+                Some("v_last_write".to_string()),
+            );
+        }
+
+        // Every round is read by all the compresses, but the last one only reads the rounds that
+        // fit in its rows.
+        let mut permutations = MemoryArray::new(builder);
+        let num_compresses_element =
+            builder.constant::<ElementRegister>(&L::Field::from_canonical_usize(num_compresses));
+        let num_full_compresses_element = builder
+            .constant::<ElementRegister>(&L::Field::from_canonical_usize(num_compresses - 1));
+        assert_eq!(Self::SIGMA_PERMUTATIONS.len(), Self::NUM_MIX_ROUNDS);
+        for (i, permutation) in Self::SIGMA_PERMUTATIONS.iter().enumerate() {
+            permutations.store_row(
+                builder,
+                i,
+                permutation,
+                if i < num_mix_iterations_last_compress {
+                    num_compresses_element
+                } else {
+                    num_full_compresses_element
+                },
+                Some("permutation".to_string()),
+            );
+        }
+
+        BLAKE2Consts {
+            iv_values,
+            compress_iv: Self::COMPRESS_IV,
+            digest_words: Self::DIGEST_WORDS,
+            v_indices,
+            v_last_write_ages,
+            permutations,
+            dummy_index,
+            dummy_index_2,
+            dummy_ts,
+            first_compress_h_read_ts,
+        }
+    }
+
+    // This function will create all the registers/memory slots that will be used for control flow
+    // related functions.
+    #[allow(clippy::too_many_arguments)]
+    fn blake2_trace_data(
+        builder: &mut BytesBuilder<L>,
+        const_nums: &BLAKE2ConstNums<Self::IntRegister>,
+        consts: &BLAKE2Consts<BytesBuilder<L>, Self>,
+        mode: &BLAKE2Mode<Self::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        num_dummy_compresses: usize,
+        length_last_compress: usize,
+        length_last_compress_element: &ElementRegister,
+    ) -> BLAKE2TraceData {
+        let (cycle_4_end_bit, cycle_8_end_bit, compress_end_bit) = Self::cycles_end_bits(builder);
+
+        let true_const = builder.constant::<BitRegister>(&L::Field::from_canonical_usize(1));
+        let false_const = builder.constant::<BitRegister>(&L::Field::from_canonical_usize(0));
+
+        let num_real_compresses = end_bits.len();
+        let num_total_compresses = num_real_compresses + num_dummy_compresses;
+
+        // Store the bits from the public input, followed by zeros for the dummy compresses.
+        let mut bit_slice = |bits: &ArrayRegister<BitRegister>, label: &str| {
+            compress_values(
+                builder,
+                &bits.iter().collect::<Vec<_>>(),
+                false_const,
+                num_total_compresses,
+                const_nums,
+                length_last_compress_element,
+                label,
+            )
+        };
+        let end_bit: Slice<BitRegister> = bit_slice(end_bits, "end_bit");
+        let digest_bit: Slice<BitRegister> = bit_slice(digest_bits, "digest_bit");
+
+        // `compress_id` is a register is computed by counting the number of cycles. We do this by
+        // setting `process_id` to be the cumulative sum of the `end_bit` of each cycle.
+        let compress_id: ElementRegister = builder.cyclic_flag(
+            &CyclicFlag::new("compress_id", L::Field::ZERO, FlagUpdate::Hold)
+                .on(compress_end_bit, FlagUpdate::Increment),
+        );
+
+        let mix_index: ElementRegister =
+            builder.cyclic_flag(&CyclicFlag::counter("mix_index", cycle_8_end_bit));
+
+        // One-hot selector of the row within each cycle of 4 rows.  Since a compress starts at a
+        // multiple of 4 rows, in the first 4 rows of a compress it selects the compress index.
+        let loop_4 = builder.api().loop_instr(4);
+        let initialize_row_selector = core::array::from_fn(|i| loop_4.get_iteration_reg(i));
+
+        // The array index register can be computed as `clock - process_id * CYCLE_LENGTH`.
+        let clk = builder.clk;
+        let compress_index = builder
+            .expression(clk.expr() - compress_id.expr() * const_nums.const_compress_length.expr());
+
+        // The end of a compress is also the end of a mix, so it takes precedence.
+        let mix_id: ElementRegister = builder.cyclic_flag(
+            &CyclicFlag::new("mix_id", L::Field::ZERO, FlagUpdate::Hold)
+                .on(compress_end_bit, FlagUpdate::Constant(L::Field::ZERO))
+                .on(cycle_8_end_bit, FlagUpdate::Increment),
+        );
+
+        let at_end_compress = builder.load(
+            &end_bit.get_at(compress_id),
+            &Time::zero(),
+            Some("end_bit".to_string()),
+            Some(MemorySliceIndex::IndexElement(compress_id)),
+        );
+        let at_first_compress: BitRegister = builder.cyclic_flag(
+            &CyclicFlag::new("at_first_compress", L::Field::ONE, FlagUpdate::Hold).on(
+                compress_end_bit,
+                FlagUpdate::Expression(at_end_compress.expr()),
+            ),
+        );
+
+        // Set previous compress id.  If we are the first compress, then set to
+        // first_compress_h_read_ts.
+        //
+        // In the first compress of the trace, `compress_id - 1` wraps around the field. This is
+        // harmless: `at_first_compress` is a bit, so the select below replaces the wrapped value
+        // exactly, and every h read indexed by `previous_compress_id` in a first compress is
+        // redirected to the dummy index.
+        let mut previous_compress_id =
+            builder.expression(compress_id.expr() - const_nums.const_1.expr());
+
+        previous_compress_id = builder.select(
+            at_first_compress,
+            &consts.first_compress_h_read_ts,
+            &previous_compress_id,
+        );
+
+        // Flag if we are within the first four rows of a compress.  In these rows, we will need to
+        // use the COMPRESS_IV values.
+        let is_compress_initialize: BitRegister = builder.cyclic_flag(
+            &CyclicFlag::new("is_compress_initialize", L::Field::ONE, FlagUpdate::Hold)
+                .on(compress_end_bit, FlagUpdate::Constant(L::Field::ONE))
+                .on(cycle_4_end_bit, FlagUpdate::Constant(L::Field::ZERO)),
+        );
+
+        // Flag if we are in the first row of a hash.  In that case, we will need to do an
+        // xor for the v_12 value.
+        let is_compress_first_row: BitRegister = builder.cyclic_flag(
+            &CyclicFlag::new(
+                "is_compress_first_row",
+                L::Field::ONE,
+                FlagUpdate::Constant(L::Field::ZERO),
+            )
+            .on(compress_end_bit, FlagUpdate::Constant(L::Field::ONE)),
+        );
+
+        // Need to flag to the last 4 rows of the compress cycle.
+        // At those rows, the V values should be saved to v_final, so that those values can be used
+        // to calculate the compress h values.
+        let save_final_v: Slice<BitRegister> = builder.uninit_slice();
+        let num_compresses_element = builder
+            .constant::<ElementRegister>(&L::Field::from_canonical_usize(num_total_compresses));
+        let num_full_compresses_element = builder
+            .constant::<ElementRegister>(&L::Field::from_canonical_usize(num_total_compresses - 1));
+        for i in 0..Self::COMPRESS_LENGTH {
+            builder.store(
+                &save_final_v.get(i),
+                if i < Self::COMPRESS_LENGTH - 4 {
+                    false_const
+                } else {
+                    true_const
+                },
+                &Time::zero(),
+                Some(if i < length_last_compress {
+                    num_compresses_element
+                } else {
+                    num_full_compresses_element
+                }),
+                Some("save_final_v".to_string()),
+                Some(MemorySliceIndex::Index(i)),
+            );
+        }
+        let is_compress_finalize = builder.load(
+            &save_final_v.get_at(compress_index),
+            &Time::zero(),
+            Some("save_final_v".to_string()),
+            Some(MemorySliceIndex::IndexElement(compress_index)),
+        );
+
+        let at_dummy_compress_memory = compress_values(
+            builder,
+            &vec![false_const; num_real_compresses],
+            true_const,
+            num_total_compresses,
+            const_nums,
+            length_last_compress_element,
+            "at_dummy_compress_memory",
+        );
+
+        let at_dummy_compress = builder.load(
+            &at_dummy_compress_memory.get_at(compress_id),
+            &Time::zero(),
+            Some("at_dummy_compress_memory".to_string()),
+            Some(MemorySliceIndex::IndexElement(compress_id)),
+        );
+
+        // If we are the digest compress of the message, then save the digest.
+        let at_digest_compress = builder.load(
+            &digest_bit.get_at(compress_id),
+            &Time::zero(),
+            Some("digest_bit".to_string()),
+            Some(MemorySliceIndex::IndexElement(compress_id)),
+        );
+        let is_digest_row = builder.and_bits(compress_end_bit, at_digest_compress);
+
+        let mode = match mode {
+            BLAKE2Mode::Sequential { final_bits, .. } => {
+                assert_eq!(final_bits.len(), num_real_compresses);
+                let final_bit = compress_values(
+                    builder,
+                    &final_bits.iter().collect::<Vec<_>>(),
+                    false_const,
+                    num_total_compresses,
+                    const_nums,
+                    length_last_compress_element,
+                    "final_bit",
+                );
+
+                // Flag if we are in the 3rd row of a hash.  In that case, we will need to do a xor
+                // on the v_14 value.
+                let is_compress_third_row =
+                    builder.and_bits(is_compress_initialize, initialize_row_selector[2]);
+
+                // If we are the final compress of the message, then apply the finalization flag.
+                let at_final_compress = builder.load(
+                    &final_bit.get_at(compress_id),
+                    &Time::zero(),
+                    Some("final_bit".to_string()),
+                    Some(MemorySliceIndex::IndexElement(compress_id)),
+                );
+                BLAKE2ModeTrace::Sequential {
+                    is_compress_third_row,
+                    at_final_compress,
+                }
+            }
+            BLAKE2Mode::Tree { parent_indices, .. } => {
+                assert_eq!(parent_indices.len(), num_real_compresses);
+                let child_bits = parent_indices
+                    .iter()
+                    .map(|index| {
+                        if index.is_some() {
+                            true_const
+                        } else {
+                            false_const
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let parent_index_values = parent_indices
+                    .iter()
+                    .map(|index| {
+                        builder.constant::<ElementRegister>(&L::Field::from_canonical_usize(
+                            index.unwrap_or(0),
+                        ))
+                    })
+                    .collect::<Vec<_>>();
+                let child_bit = compress_values(
+                    builder,
+                    &child_bits,
+                    false_const,
+                    num_total_compresses,
+                    const_nums,
+                    length_last_compress_element,
+                    "child_bit",
+                );
+                let parent_index_memory = compress_values(
+                    builder,
+                    &parent_index_values,
+                    const_nums.const_0,
+                    num_total_compresses,
+                    const_nums,
+                    length_last_compress_element,
+                    "parent_index",
+                );
+
+                // If we are the end compress of a child, then save the output in the message words
+                // of the parent.
+                let at_child_compress = builder.load(
+                    &child_bit.get_at(compress_id),
+                    &Time::zero(),
+                    Some("child_bit".to_string()),
+                    Some(MemorySliceIndex::IndexElement(compress_id)),
+                );
+                let parent_index = builder.load(
+                    &parent_index_memory.get_at(compress_id),
+                    &Time::zero(),
+                    Some("parent_index".to_string()),
+                    Some(MemorySliceIndex::IndexElement(compress_id)),
+                );
+                BLAKE2ModeTrace::Tree {
+                    at_child_compress,
+                    parent_index,
+                }
+            }
+        };
+
+        BLAKE2TraceData {
+            clk,
+            is_compress_initialize,
+            is_compress_first_row,
+            is_digest_row,
+            is_compress_finalize,
+            at_first_compress,
+            at_digest_compress,
+            at_end_compress,
+            at_dummy_compress,
+            is_compress_final_row: compress_end_bit,
+            compress_id,
+            previous_compress_id,
+            compress_index,
+            mix_id,
+            mix_index,
+            initialize_row_selector,
+            mode,
+            initialize_rows: CompressRows::periodic(Self::COMPRESS_LENGTH, 0..4),
+            final_row_rows: CompressRows::periodic(
+                Self::COMPRESS_LENGTH,
+                Self::COMPRESS_LENGTH - 1..Self::COMPRESS_LENGTH,
+            ),
+            first_compress_rows: CompressRows::first_compress(Self::COMPRESS_LENGTH),
+            dummy_compress_rows: CompressRows::dummy_compress(Self::COMPRESS_LENGTH),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn blake2_memory(
+        builder: &mut BytesBuilder<L>,
+        padded_chunks: &[(usize, ArrayRegister<Self::IntRegister>)],
+        mode: &BLAKE2Mode<Self::IntRegister>,
+        const_nums: &BLAKE2ConstNums<Self::IntRegister>,
+        consts: &BLAKE2Consts<BytesBuilder<L>, Self>,
+        num_messages_element: &ElementRegister,
+        num_real_compresses: usize,
+        num_real_compresses_element: &ElementRegister,
+        num_dummy_rows: usize,
+    ) -> BLAKE2Memory<Self::IntRegister> {
+        // Initialize the h memory
+        let h = builder.uninit_slice();
+
+        // The loads of the dummy slots differ between the first compress of a message and the
+        // other real compresses, so they are counted by the number of messages.
+        let num_non_first_compresses: ElementRegister = builder
+            .public_expression(num_real_compresses_element.expr() - num_messages_element.expr());
+
+        // Set dummy reads for h, counted by the loads in `blake2_compress_initialize` and, in the
+        // sequential mode, `blake2_compress_finalize`.
+        let dummy_h = builder.store_counted(
+            &h.get_at(consts.dummy_index),
+            const_nums.const_0_word,
+            &Time::from_element(consts.dummy_ts),
+            Some("h".to_string()),
+            Some(MemorySliceIndex::IndexElement(consts.dummy_index)),
+        );
+
+        // Initialize the v memory
+        let v = builder.uninit_slice();
+        // Set dummy reads for v, counted by the loads in `blake2_compress_initialize`.
+        let dummy_v = builder.store_counted(
+            &v.get_at(consts.dummy_index),
+            const_nums.const_0_word,
+            &Time::from_element(consts.dummy_ts),
+            Some("v".to_string()),
+            Some(MemorySliceIndex::IndexElement(consts.dummy_index)),
+        );
+
+        // Initialize the v_final memory
+        let v_final = builder.uninit_slice();
+        // Set dummy reads for v_final, counted by the loads in `blake2_compress_finalize`.
+        let dummy_v_final = builder.store_counted(
+            &v_final.get_at(consts.dummy_index),
+            const_nums.const_0_word,
+            &Time::from_element(consts.dummy_ts),
+            Some("v_final".to_string()),
+            Some(MemorySliceIndex::IndexElement(consts.dummy_index)),
+        );
+
+        // Initialize the m memory
+        let m = builder.uninit_slice();
+
+        // Each message chunk will be read twice per round of a compress.
+        // The chunks held in the trace are stored by their compresses, see `blake2_data`, and the
+        // message words of the parents of a tree are stored by their children, see
+        // `blake2_compress_finalize`.
+        for (compress_id_value, padded_chunk) in
+            padded_chunks.iter().filter(|(_, chunk)| !chunk.is_trace())
+        {
+            assert!(padded_chunk.len() == MSG_ARRAY_SIZE);
+            for (j, word) in padded_chunk.iter().enumerate() {
+                builder.store(
+                    &m.get(compress_id_value * 16 + j),
+                    word,
+                    &Time::zero(),
+                    Some(const_nums.const_num_mix_rounds),
+                    Some("m".to_string()),
+                    Some(MemorySliceIndex::Index(compress_id_value * 16 + j)),
+                );
+            }
+        }
+        // Set dummy reads for m, counted by the loads in `blake2_compress`.
+        let dummy_m = builder.store_counted(
+            &m.get_at(consts.dummy_index),
+            const_nums.const_0_word,
+            &Time::zero(),
+            Some("m".to_string()),
+            Some(MemorySliceIndex::IndexElement(consts.dummy_index)),
+        );
+
+        // Initialize the memory of the last row of the work vector. In the sequential mode, the
+        // counter `t` of a compress is read in every row of the compress, and in the tree mode,
+        // each of the four parameters of a compress is read once, in one of its first four rows.
+        let parameters = builder.uninit_slice();
+        let label = mode.parameters_label();
+        let (values, multiplicity) = match mode {
+            BLAKE2Mode::Sequential { t_values, .. } => (t_values, const_nums.const_compress_length),
+            BLAKE2Mode::Tree {
+                parameters: values, ..
+            } => (values, const_nums.const_1),
+        };
+        for (i, value) in values.iter().enumerate() {
+            builder.store(
+                &parameters.get(i),
+                value,
+                &Time::zero(),
+                Some(multiplicity),
+                Some(label.to_string()),
+                Some(MemorySliceIndex::Index(i)),
+            );
+        }
+        // Set dummy reads for the parameters, counted by the load in `blake2_compress_initialize`.
+        let dummy_parameters = builder.store_counted(
+            &parameters.get_at(consts.dummy_index),
+            const_nums.const_0_word,
+            &Time::zero(),
+            Some(label.to_string()),
+            Some(MemorySliceIndex::IndexElement(consts.dummy_index)),
+        );
+
+        BLAKE2Memory {
+            h,
+            v,
+            v_final,
+            m,
+            parameters,
+            dummy_h,
+            dummy_v,
+            dummy_v_final,
+            dummy_m,
+            dummy_parameters,
+            num_messages: *num_messages_element,
+            num_non_first_compresses,
+            num_real_compresses,
+            num_dummy_rows,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn blake2_data(
+        builder: &mut BytesBuilder<L>,
+        padded_chunks: &[(usize, ArrayRegister<Self::IntRegister>)],
+        mode: &BLAKE2Mode<Self::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages_element: &ElementRegister,
+    ) -> BLAKE2Data<BytesBuilder<L>, Self> {
+        // Every compress of the sequential mode has a chunk, while the parents of a tree take the
+        // outputs of their children.
+        let num_real_compresses = end_bits.len();
+        if let BLAKE2Mode::Sequential { .. } = mode {
+            assert_eq!(padded_chunks.len(), num_real_compresses);
+        }
+        assert!(padded_chunks
+            .iter()
+            .all(|(compress_id, _)| *compress_id < num_real_compresses));
+        debug!("num_real_compresses: {}", num_real_compresses);
+        let num_real_compresses_element = builder
+            .constant::<ElementRegister>(&L::Field::from_canonical_usize(num_real_compresses));
+        let num_rows = builder
+            .api()
+            .padded_trace_length(num_real_compresses * Self::COMPRESS_LENGTH);
+        assert!(num_rows < 1 << 31, "AIR degree is too large");
+        debug!("AIR degree after padding: {}", num_rows);
+        builder.api().set_trace_length(num_rows);
+
+        // The rows after the real compresses are filled with dummy compresses, the last of which
+        // may be cut short or, if the trace is not padded, empty.
+        let num_dummy_compresses = num_rows / Self::COMPRESS_LENGTH + 1 - num_real_compresses;
+        let length_last_compress = num_rows % Self::COMPRESS_LENGTH;
+        let length_last_compress_element = builder
+            .constant::<ElementRegister>(&L::Field::from_canonical_usize(length_last_compress));
+        let num_dummy_rows =
+            (num_dummy_compresses - 1) * Self::COMPRESS_LENGTH + length_last_compress;
+
+        // create the const numbers data
+        let const_nums = Self::blake2_const_nums(builder);
+
+        // The last compress, real or dummy, only takes the mix iterations that fit in its rows.
+        assert_eq!(
+            length_last_compress % MIX_LENGTH,
+            0,
+            "The padded trace length must be a multiple of {} rows",
+            MIX_LENGTH
+        );
+        let num_mixes_last_compress = length_last_compress / MIX_LENGTH;
+        let num_compresses = num_real_compresses + num_dummy_compresses;
+        let num_total_mixes = (num_compresses - 1) * Self::NUM_MIX_ROUNDS + num_mixes_last_compress;
+
+        let public = BLAKE2PublicData {
+            padded_chunks: padded_chunks.to_vec(),
+            mode: mode.clone(),
+            end_bits: *end_bits,
+            digest_indices: *digest_indices,
+        };
+
+        // create the consts data
+        let consts = Self::blake2_consts(
+            builder,
+            num_rows,
+            num_compresses,
+            num_total_mixes,
+            num_mixes_last_compress,
+        );
+
+        // create the trace data
+        let trace = Self::blake2_trace_data(
+            builder,
+            &const_nums,
+            &consts,
+            mode,
+            end_bits,
+            digest_bits,
+            num_dummy_compresses,
+            length_last_compress,
+            &length_last_compress_element,
+        );
+
+        // create the memory data
+        let memory = Self::blake2_memory(
+            builder,
+            padded_chunks,
+            mode,
+            &const_nums,
+            &consts,
+            num_messages_element,
+            num_real_compresses,
+            &num_real_compresses_element,
+            num_dummy_rows,
+        );
+
+        // A chunk held in the trace is the same register for every compress, which holds the
+        // chunk of the compress in its rows. It is stored from the first row of every real
+        // compress, for the two reads of each word in every round.
+        if padded_chunks.iter().any(|(_, chunk)| chunk.is_trace()) {
+            assert!(
+                matches!(mode, BLAKE2Mode::Sequential { .. }),
+                "The padded chunks held in the trace must be chained sequentially"
+            );
+            let (_, chunk) = padded_chunks[0];
+            assert!(
+                padded_chunks
+                    .iter()
+                    .all(|(_, c)| c.register() == chunk.register()),
+                "The padded chunks held in the trace must be a single register"
+            );
+            let multiplicity: ElementRegister = builder.expression(
+                trace.is_compress_first_row.expr()
+                    * (const_nums.const_1.expr() - trace.at_dummy_compress.expr())
+                    * const_nums.const_num_mix_rounds.expr(),
+            );
+            for (j, word) in chunk.iter().enumerate() {
+                let index: ElementRegister = builder.expression(
+                    trace.compress_id.expr() * const_nums.const_16.expr()
+                        + L::Field::from_canonical_usize(j),
+                );
+                builder.store(
+                    &memory.m.get_at(index),
+                    word,
+                    &Time::zero(),
+                    Some(multiplicity),
+                    Some("m".to_string()),
+                    Some(MemorySliceIndex::IndexElement(index)),
+                );
+            }
+        }
+
+        BLAKE2Data {
+            public,
+            trace,
+            memory,
+            consts,
+            const_nums,
+        }
+    }
+
+    /// This function will retrieve the v values that will be inputted into the mix function
+    fn blake2_compress_initialize(
+        builder: &mut BytesBuilder<L>,
+        data: &BLAKE2Data<BytesBuilder<L>, Self>,
+    ) -> ([ElementRegister; 4], [Self::IntRegister; 4]) {
+        let init_idx_1 = data.trace.compress_index;
+        let init_idx_2 = builder.add(data.trace.compress_index, data.const_nums.const_4);
+
+        // Read the h values.
+        //
+        // Read the dummy index from h at any of the following conditions
+        // 1) in the first compress of a message (first 4 rows will read from IV instead)
+        // 2) NOT in the first 4 rows of compress (e.g. not is_compress_initialize)
+        // 3) in the dummy compress.
+        //
+        // Boolean expression is at_first_compress OR NOT(is_compress_initialize) OR at_dummy_compress
+        // That is equivalent to
+        // NOT(NOT(at_first_compress) AND is_compress_initialize AND NOT(at_dummy_compress))
+        let read_dummy_h = CompressFlag::any(
+            builder,
+            &[
+                data.trace.first_compress_flag(),
+                data.trace.initialize_flag().not(),
+                data.trace.dummy_compress_flag(),
+            ],
+        );
+        let read_dummy_h_idx = read_dummy_h.bit();
+
+        let mut h_idx_1 = builder.expression(
+            data.trace.previous_compress_id.expr() * data.const_nums.const_8.expr()
+                + init_idx_1.expr(),
+        );
+        h_idx_1 = builder.select(read_dummy_h_idx, &data.consts.dummy_index, &h_idx_1);
+
+        let mut h_idx_2 = builder.expression(
+            data.trace.previous_compress_id.expr() * data.const_nums.const_8.expr()
+                + init_idx_2.expr(),
+        );
+        h_idx_2 = builder.select(read_dummy_h_idx, &data.consts.dummy_index, &h_idx_2);
+
+        let h_ts = builder.select(
+            read_dummy_h_idx,
+            &data.consts.dummy_ts,
+            &data.const_nums.const_0,
+        );
+
+        let dummy_h_reads = data.memory.reads(read_dummy_h.rows());
+        let mut h_value_1 = builder.load_counted(
+            &data.memory.h.get_at(h_idx_1),
+            &Time::from_element(h_ts),
+            Some("h".to_string()),
+            Some(MemorySliceIndex::IndexElement(h_idx_1)),
+            &data.memory.dummy_h,
+            dummy_h_reads.clone(),
+        );
+        let mut h_value_2 = builder.load_counted(
+            &data.memory.h.get_at(h_idx_2),
+            &Time::from_element(h_ts),
+            Some("h".to_string()),
+            Some(MemorySliceIndex::IndexElement(h_idx_2)),
+            &data.memory.dummy_h,
+            dummy_h_reads,
+        );
+
+        // Read the iv and compress iv values from constant tables.  In the first 4 rows of a
+        // compress, the index of the values to read is the row within each cycle of 4 rows.
+        let table = |values: &[Self::Integer]| {
+            values
+                .iter()
+                .map(|value| {
+                    <Self as HashIntConversion<BytesBuilder<L>>>::int_to_field_value(*value)
+                })
+                .collect::<Vec<_>>()
+        };
+        let selector = &data.trace.initialize_row_selector;
+        // The iv words are read from `iv_values`, which hold the personalization or the chaining
+        // state of a segment if there is one.
+        let read_iv_values = |builder: &mut BytesBuilder<L>, offset: usize| -> Self::IntRegister {
+            builder.expression(
+                selector
+                    .iter()
+                    .enumerate()
+                    .map(|(i, bit)| bit.expr() * data.consts.iv_values.get(offset + i).expr())
+                    .reduce(|acc, term| acc + term)
+                    .unwrap(),
+            )
+        };
+        let iv_value_1 = read_iv_values(builder, 0);
+        let iv_value_2 = read_iv_values(builder, 4);
+        let compress_iv = &data.consts.compress_iv;
+        let compress_iv_value_1: Self::IntRegister =
+            builder.read_constant_table(&table(&compress_iv[0..4]), selector);
+
+        // Read the v values.
+        //
+        // First get the v indicies and last write timestamps.
+        let v_indices = &data.consts.v_indices;
+        let v_last_write_ages = &data.consts.v_last_write_ages;
+        let cols = [
+            data.const_nums.const_0,
+            data.const_nums.const_1,
+            data.const_nums.const_2,
+            data.const_nums.const_3,
+        ];
+        let v_idx = cols.map(|col| {
+            v_indices.get_at(
+                builder,
+                data.trace.mix_index,
+                col,
+                Some("mix_index".to_string()),
+            )
+        });
+        let v_last_write_ages = cols.map(|col| {
+            v_last_write_ages.get_at(
+                builder,
+                data.trace.mix_index,
+                col,
+                Some("v_last_write_ages".to_string()),
+            )
+        });
+
+        // Read the dummy value at any of the following conditions
+        // 1) In the first 4 rows of compress (e.g. not is_compress_initialize)
+        // 2) In the dummy compress
+        let read_dummy_v = CompressFlag::any(
+            builder,
+            &[
+                data.trace.initialize_flag(),
+                data.trace.dummy_compress_flag(),
+            ],
+        );
+        let read_dummy_v_idx = read_dummy_v.bit();
+        let dummy_v_reads = data.memory.reads(read_dummy_v.rows());
+        let mut v_values = [data.const_nums.const_0_word; 4];
+        for (i, last_write_age) in v_last_write_ages.iter().enumerate() {
+            let mut last_write_ts =
+                builder.expression(data.trace.clk.expr() - last_write_age.expr());
+            last_write_ts = builder.select(read_dummy_v_idx, &data.consts.dummy_ts, &last_write_ts);
+
+            let read_idx = builder.select(read_dummy_v_idx, &data.consts.dummy_index, &v_idx[i]);
+            v_values[i] = builder.load_counted(
+                &data.memory.v.get_at(read_idx),
+                &Time::from_element(last_write_ts),
+                Some("v".to_string()),
+                Some(MemorySliceIndex::IndexElement(read_idx)),
+                &data.memory.dummy_v,
+                dummy_v_reads.clone(),
+            );
+        }
+
+        // Set the v values based on where in the compress we are.
+
+        // Set v1 and v2 value.
+        // Use the iv values if we are in the first 4 rows of a message.
+        let use_iv_values = builder.and(
+            data.trace.is_compress_initialize,
+            data.trace.at_first_compress,
+        );
+
+        h_value_1 = builder.select(use_iv_values, &iv_value_1, &h_value_1);
+        h_value_2 = builder.select(use_iv_values, &iv_value_2, &h_value_2);
+
+        // If we are in the first 4 rows of a compress, then we will need to use the h values, else use the v values.
+        let [mut v1_value, mut v2_value, mut v3_value, mut v4_value] = v_values;
+        v1_value = builder.select(data.trace.is_compress_initialize, &h_value_1, &v1_value);
+        v2_value = builder.select(data.trace.is_compress_initialize, &h_value_2, &v2_value);
+
+        // Set v3 value.
+        // Use the compress iv values if we are in the first 4 rows of a compress, else use the v values
+        v3_value = builder.select(
+            data.trace.is_compress_initialize,
+            &compress_iv_value_1,
+            &v3_value,
+        );
+
+        // Set v4 value.
+        let label = data.public.mode.parameters_label();
+        match data.trace.mode {
+            BLAKE2ModeTrace::Sequential {
+                is_compress_third_row,
+                at_final_compress,
+            } => {
+                // Use the compress iv values if we are in the first 4 rows of a compress, else use
+                // the v values
+                let compress_iv_value_2: Self::IntRegister =
+                    builder.read_constant_table(&table(&compress_iv[4..8]), selector);
+                v4_value = builder.select(
+                    data.trace.is_compress_initialize,
+                    &compress_iv_value_2,
+                    &v4_value,
+                );
+
+                // If we are at the first compress row, then will need to xor v4 with t
+                let t_idx = builder.select(
+                    data.trace.at_dummy_compress,
+                    &data.consts.dummy_index,
+                    &data.trace.compress_id,
+                );
+                let t = builder.load_counted(
+                    &data.memory.parameters.get_at(t_idx),
+                    &Time::zero(),
+                    Some(label.to_string()),
+                    Some(MemorySliceIndex::IndexElement(t_idx)),
+                    &data.memory.dummy_parameters,
+                    data.memory.reads(data.trace.dummy_compress_flag().rows()),
+                );
+
+                let v4_xor_t = builder.xor(v4_value, t);
+                v4_value = builder.select(data.trace.is_compress_first_row, &v4_xor_t, &v4_value);
+
+                // If we are at the third compress row of a final compress, then will need to xor
+                // v4 with the word of all ones.
+                let inverse_v4_value = builder.xor(v4_value, data.const_nums.const_word_max);
+                let use_inverse_v4_value = builder.mul(at_final_compress, is_compress_third_row);
+                v4_value = builder.select(use_inverse_v4_value, &inverse_v4_value, &v4_value);
+            }
+            BLAKE2ModeTrace::Tree { .. } => {
+                // Use the parameters of the compress if we are in the first 4 rows of a compress,
+                // else use the v values.
+                //
+                // Read the dummy parameters if NOT in the first 4 rows of a compress OR in a dummy
+                // compress.
+                let read_dummy_parameters = CompressFlag::any(
+                    builder,
+                    &[
+                        data.trace.initialize_flag().not(),
+                        data.trace.dummy_compress_flag(),
+                    ],
+                );
+                let mut parameters_idx = builder.expression(
+                    data.trace.compress_id.expr() * data.const_nums.const_4.expr()
+                        + data.trace.compress_index.expr(),
+                );
+                parameters_idx = builder.select(
+                    read_dummy_parameters.bit(),
+                    &data.consts.dummy_index,
+                    &parameters_idx,
+                );
+                let parameter = builder.load_counted(
+                    &data.memory.parameters.get_at(parameters_idx),
+                    &Time::zero(),
+                    Some(label.to_string()),
+                    Some(MemorySliceIndex::IndexElement(parameters_idx)),
+                    &data.memory.dummy_parameters,
+                    data.memory.reads(read_dummy_parameters.rows()),
+                );
+                v4_value = builder.select(data.trace.is_compress_initialize, &parameter, &v4_value);
+            }
+        }
+
+        (v_idx, [v1_value, v2_value, v3_value, v4_value])
+    }
+
+    /// The processing step of a BLAKE2 round.
+    fn blake2_compress(
+        builder: &mut BytesBuilder<L>,
+        v_indices: &[ElementRegister; 4],
+        v_values: &[Self::IntRegister; 4],
+        data: &BLAKE2Data<BytesBuilder<L>, Self>,
+    ) {
+        // Load the permutation values.
+        let mut permutation_col: ElementRegister =
+            builder.mul(data.trace.mix_index, data.const_nums.const_2);
+
+        let mut m_idx_1 = data.consts.permutations.get_at(
+            builder,
+            data.trace.mix_id,
+            permutation_col,
+            Some("permutation".to_string()),
+        );
+
+        m_idx_1 = builder.expression(
+            data.trace.compress_id.expr() * data.const_nums.const_16.expr() + m_idx_1.expr(),
+        );
+        permutation_col = builder.add(permutation_col, data.const_nums.const_1);
+
+        let mut m_idx_2 = data.consts.permutations.get_at(
+            builder,
+            data.trace.mix_id,
+            permutation_col,
+            Some("permutation".to_string()),
+        );
+
+        m_idx_2 = builder.expression(
+            data.trace.compress_id.expr() * data.const_nums.const_16.expr() + m_idx_2.expr(),
+        );
+
+        m_idx_1 = builder.select(
+            data.trace.at_dummy_compress,
+            &data.consts.dummy_index,
+            &m_idx_1,
+        );
+        m_idx_2 = builder.select(
+            data.trace.at_dummy_compress,
+            &data.consts.dummy_index,
+            &m_idx_2,
+        );
+
+        // Load the message values. The dummy m is read in every dummy row.
+        let dummy_m_reads = data.memory.reads(data.trace.dummy_compress_flag().rows());
+        let m_1 = builder.load_counted(
+            &data.memory.m.get_at(m_idx_1),
+            &Time::zero(),
+            Some("m".to_string()),
+            Some(MemorySliceIndex::IndexElement(m_idx_1)),
+            &data.memory.dummy_m,
+            dummy_m_reads.clone(),
+        );
+        let m_2 = builder.load_counted(
+            &data.memory.m.get_at(m_idx_2),
+            &Time::zero(),
+            Some("m".to_string()),
+            Some(MemorySliceIndex::IndexElement(m_idx_2)),
+            &data.memory.dummy_m,
+            dummy_m_reads,
+        );
+
+        let (updated_v0, updated_v1, updated_v2, updated_v3) = Self::mix(
+            builder,
+            &v_values[0],
+            &v_values[1],
+            &v_values[2],
+            &v_values[3],
+            &m_1,
+            &m_2,
+        );
+
+        // Save the output of the mix in v or v_final.
+
+        // Save the output into v in all of the following conditions.
+        // 1) NOT in the last 4 rows of compress (e.g. not is_compress_finalize)
+        // 2) NOT in the dummy compress.
+        //
+        // Boolean expression is NOT(is_compress_initialize) AND NOT(at_dummy_compress)
+        let save_v = builder.expression(
+            data.trace.is_compress_finalize.not_expr() * data.trace.at_dummy_compress.not_expr(),
+        );
+
+        // Save the output into v in all of the following conditions.
+        // 1) in the last 4 rows of compress (e.g. is_compress_finalize)
+        // 2) NOT in the dummy compress.
+        //
+        // Boolean expression is is_compress_finalize AND NOT(at_dummy_compress)
+        let save_v_final = builder.expression(
+            data.trace.is_compress_finalize.expr() * data.trace.at_dummy_compress.not_expr(),
+        );
+
+        let updated_v_values = [updated_v0, updated_v1, updated_v2, updated_v3];
+        let clk = builder.clk;
+        for (value, v_index) in updated_v_values.iter().zip(v_indices.iter()) {
+            let v_idx = builder.select(save_v, v_index, &data.consts.dummy_index_2);
+            let v_value = builder.select(save_v, value, &data.const_nums.const_0_word);
+            let v_ts = builder.select(save_v, &clk, &data.consts.dummy_ts);
+
+            builder.store(
+                &data.memory.v.get_at(v_idx),
+                v_value,
+                &Time::from_element(v_ts),
+                Some(save_v.as_element()),
+                Some("v".to_string()),
+                Some(MemorySliceIndex::IndexElement(v_idx)),
+            );
+
+            let v_final_idx = builder.select(save_v_final, v_index, &data.consts.dummy_index_2);
+            let v_final_ts =
+                builder.select(save_v_final, &data.trace.compress_id, &data.consts.dummy_ts);
+            let v_final_value = builder.select(save_v_final, value, &data.const_nums.const_0_word);
+
+            builder.store(
+                &data.memory.v_final.get_at(v_final_idx),
+                v_final_value,
+                &Time::from_element(v_final_ts),
+                Some(save_v_final.as_element()),
+                Some("v_final".to_string()),
+                Some(MemorySliceIndex::IndexElement(v_final_idx)),
+            );
+        }
+    }
+
+    fn blake2_compress_finalize(
+        builder: &mut BytesBuilder<L>,
+        state_ptr: &Slice<Self::IntRegister>,
+        data: &BLAKE2Data<BytesBuilder<L>, Self>,
+    ) {
+        // If we are at the last row of compress, then compute and save the h value.
+
+        // In the sequential mode, first load the previous round's h value, which is fed forward
+        // into the h value. The xors are held in workspaces.
+        let workspaces = match data.trace.mode {
+            BLAKE2ModeTrace::Sequential { .. } => {
+                let h_workspace_1 = builder.alloc_array::<Self::IntRegister>(STATE_SIZE);
+
+                // Read dummy h values if any of the following conditions are true
+                // 1) NOT at last row of a compress
+                // 2) at the first compress
+                // 3) at a dummy compress
+                //
+                // Boolean expression is NOT(is_compress_final_row) OR at_first_compress OR at_dummy_compress
+                // That is equivalent to
+                // NOT(is_compress_final_row AND NOT(at_first_compress) AND NOT(at_dummy_compress))
+                let read_dummy_h = CompressFlag::any(
+                    builder,
+                    &[
+                        data.trace.final_row_flag().not(),
+                        data.trace.first_compress_flag(),
+                        data.trace.dummy_compress_flag(),
+                    ],
+                );
+                let read_dummy_h_idx = read_dummy_h.bit();
+                let dummy_h_reads = data.memory.reads(read_dummy_h.rows());
+
+                let h_ts = builder.select(
+                    read_dummy_h_idx,
+                    &data.consts.dummy_ts,
+                    &data.const_nums.const_0,
+                );
+                for i in 0..STATE_SIZE {
+                    let i_element =
+                        builder.constant::<ElementRegister>(&L::Field::from_canonical_usize(i));
+                    let mut h_idx = builder.expression(
+                        data.trace.previous_compress_id.expr() * data.const_nums.const_8.expr()
+                            + i_element.expr(),
+                    );
+                    h_idx = builder.select(read_dummy_h_idx, &data.consts.dummy_index, &h_idx);
+                    let mut h_value = builder.load_counted(
+                        &data.memory.h.get_at(h_idx),
+                        &Time::from_element(h_ts),
+                        Some("h".to_string()),
+                        Some(MemorySliceIndex::IndexElement(h_idx)),
+                        &data.memory.dummy_h,
+                        dummy_h_reads.clone(),
+                    );
+
+                    // If we are at the first compress of a message, then use the iv values instead
+                    // of the h values.
+                    h_value = builder.select(
+                        data.trace.at_first_compress,
+                        &data.consts.iv_values.get(i),
+                        &h_value,
+                    );
+                    builder.set_to_expression(&h_workspace_1.get(i), h_value.expr());
+                }
+
+                let h_workspace_2 = builder.alloc_array::<Self::IntRegister>(STATE_SIZE);
+                let h = builder.alloc_array::<Self::IntRegister>(STATE_SIZE);
+                Some((h_workspace_1, h_workspace_2, h))
+            }
+            BLAKE2ModeTrace::Tree { .. } => None,
+        };
+
+        // Xor the first 8 final v values
+        //
+        // Read dummy v_final values if NOT at last row of a compress OR in a dummy compress.
+        //
+        // Boolean expression is NOT(is_compress_final_row) OR at_dummy_compress
+        // That is equivalent to
+        // NOT(is_compress_final_row AND NOT(at_dummy_compress))
+        let read_dummy_v_final = CompressFlag::any(
+            builder,
+            &[
+                data.trace.final_row_flag().not(),
+                data.trace.dummy_compress_flag(),
+            ],
+        );
+        let read_dummy_v_final_idx = read_dummy_v_final.bit();
+        let dummy_v_final_reads = data.memory.reads(read_dummy_v_final.rows());
+        let v_final_ts = builder.select(
+            read_dummy_v_final_idx,
+            &data.consts.dummy_ts,
+            &data.trace.compress_id,
+        );
+        let mut first_half = Vec::with_capacity(STATE_SIZE);
+        for i in 0..STATE_SIZE {
+            let i_element = builder.constant::<ElementRegister>(&L::Field::from_canonical_usize(i));
+            let v_final_idx =
+                builder.select(read_dummy_v_final_idx, &data.consts.dummy_index, &i_element);
+            let v_i = builder.load_counted(
+                &data.memory.v_final.get_at(v_final_idx),
+                &Time::from_element(v_final_ts),
+                Some("v_final".to_string()),
+                Some(MemorySliceIndex::IndexElement(v_final_idx)),
+                &data.memory.dummy_v_final,
+                dummy_v_final_reads.clone(),
+            );
+            match workspaces {
+                Some((h_workspace_1, h_workspace_2, _)) => {
+                    let updated_h = builder.xor(h_workspace_1.get(i), v_i);
+                    builder.set_to_expression(&h_workspace_2.get(i), updated_h.expr());
+                    first_half.push(h_workspace_2.get(i));
+                }
+                None => first_half.push(v_i),
+            }
+        }
+
+        // Xor the second 8 final v values
+
+        // Save h into memory if we are at the final row and it is not the end compress and not in a dummy compress.
+        let save_h = builder.expression(
+            data.trace.is_compress_final_row.expr()
+                * data.trace.at_end_compress.not_expr()
+                * data.trace.at_dummy_compress.not_expr(),
+        );
+        // In the sequential mode, the h value is read twice by the next compress, in its first
+        // four rows and in its last row, and in the tree mode only in its first four rows.
+        let h_reads = match data.trace.mode {
+            BLAKE2ModeTrace::Sequential { .. } => data.const_nums.const_2,
+            BLAKE2ModeTrace::Tree { .. } => data.const_nums.const_1,
+        };
+
+        // In the tree mode, save the h value into the message words of the parent if we are at
+        // the final row of a child.
+        let save_parent_m = match data.trace.mode {
+            BLAKE2ModeTrace::Tree {
+                at_child_compress,
+                parent_index,
+            } => {
+                let save_parent_m: BitRegister = builder
+                    .expression(data.trace.is_compress_final_row.expr() * at_child_compress.expr());
+                Some((save_parent_m, parent_index))
+            }
+            BLAKE2ModeTrace::Sequential { .. } => None,
+        };
+
+        for (i, first_half_value) in first_half.into_iter().enumerate() {
+            let i_element = builder.constant::<ElementRegister>(&L::Field::from_canonical_usize(i));
+            let i_element_plus_8 = builder.add(i_element, data.const_nums.const_8);
+
+            let v_final_idx = builder.select(
+                read_dummy_v_final_idx,
+                &data.consts.dummy_index,
+                &i_element_plus_8,
+            );
+
+            let v_value = builder.load_counted(
+                &data.memory.v_final.get_at(v_final_idx),
+                &Time::from_element(v_final_ts),
+                Some("v_final".to_string()),
+                Some(MemorySliceIndex::IndexElement(v_final_idx)),
+                &data.memory.dummy_v_final,
+                dummy_v_final_reads.clone(),
+            );
+            let xor = builder.xor(first_half_value, v_value);
+            if let Some((_, _, h)) = workspaces {
+                builder.set_to_expression(&h.get(i), xor.expr());
+            }
+
+            let mut h_idx = builder.expression(
+                data.trace.compress_id.expr() * data.const_nums.const_8.expr() + i_element.expr(),
+            );
+            h_idx = builder.select(save_h, &h_idx, &data.consts.dummy_index_2);
+            let h_value = builder.select(save_h, &xor, &data.const_nums.const_0_word);
+            let h_ts = builder.select(save_h, &data.const_nums.const_0, &data.consts.dummy_ts);
+            let h_multiplicity = builder.select(save_h, &h_reads, &data.const_nums.const_0);
+
+            builder.store(
+                &data.memory.h.get_at(h_idx),
+                h_value,
+                &Time::from_element(h_ts),
+                Some(h_multiplicity),
+                Some("h".to_string()),
+                Some(MemorySliceIndex::IndexElement(h_idx)),
+            );
+
+            // The message words of the parent are read once per round.
+            if let Some((save_parent_m, parent_index)) = save_parent_m {
+                let mut m_idx = builder.add(parent_index, i_element);
+                m_idx = builder.select(save_parent_m, &m_idx, &data.consts.dummy_index_2);
+                let m_value = builder.select(save_parent_m, &xor, &data.const_nums.const_0_word);
+                let m_ts = builder.select(
+                    save_parent_m,
+                    &data.const_nums.const_0,
+                    &data.consts.dummy_ts,
+                );
+                let m_multiplicity = builder.select(
+                    save_parent_m,
+                    &data.const_nums.const_num_mix_rounds,
+                    &data.const_nums.const_0,
+                );
+
+                builder.store(
+                    &data.memory.m.get_at(m_idx),
+                    m_value,
+                    &Time::from_element(m_ts),
+                    Some(m_multiplicity),
+                    Some("m".to_string()),
+                    Some(MemorySliceIndex::IndexElement(m_idx)),
+                );
+            }
+
+            // If this is the digest row, then also store the calculated digest.
+            // Only need to do so for the words of h that are part of the digest.
+            if i < data.consts.digest_words {
+                builder.store(
+                    &state_ptr.get(i),
+                    xor,
+                    &Time::from_element(data.trace.compress_id),
+                    Some(data.trace.is_digest_row.as_element()),
+                    Some("state_ptr".to_string()),
+                    Some(MemorySliceIndex::Index(i)),
+                );
+            }
+        }
+    }
+
+    fn blake2_hash(
+        builder: &mut BytesBuilder<L>,
+        state_ptr: &Slice<Self::IntRegister>,
+        data: &BLAKE2Data<BytesBuilder<L>, Self>,
+    ) {
+        let tag = |step: &str| format!("{}_{}", Self::NAME, step);
+        let (v_indices, v_values) = builder.tagged(&tag("compress_initialize"), |builder| {
+            Self::blake2_compress_initialize(builder, data)
+        });
+        builder.tagged(&tag("compress"), |builder| {
+            Self::blake2_compress(builder, &v_indices, &v_values, data)
+        });
+        builder.tagged(&tag("compress_finalize"), |builder| {
+            Self::blake2_compress_finalize(builder, state_ptr, data)
+        });
+    }
+
+    fn blake2_states(
+        builder: &mut BytesBuilder<L>,
+        data: &BLAKE2Data<BytesBuilder<L>, Self>,
+    ) -> Vec<ArrayRegister<Self::IntRegister>> {
+        let state_ptr = builder.uninit_slice();
+        let num_digests = data.public.digest_indices.len();
+
+        // Create the public registers to input the expected digests.
+        let hash_state_public: Vec<ArrayRegister<Self::IntRegister>> = (0..num_digests)
+            .map(|_| builder.alloc_array_public::<Self::IntRegister>(data.consts.digest_words))
+            .collect::<_>();
+
+        for (i, h_slice) in data
+            .public
+            .digest_indices
+            .iter()
+            .zip(hash_state_public.iter())
+        {
+            for (j, h) in h_slice.iter().enumerate() {
+                builder.free(&state_ptr.get(j), h, &Time::from_element(i));
+            }
+        }
+
+        Self::blake2_hash(builder, &state_ptr, data);
+
+        hash_state_public
+    }
+}
@@ -0,0 +1,361 @@
+use core::ops::{BitOr, Not, Range};
+
+use super::{MIX_LENGTH, MSG_ARRAY_SIZE, STATE_SIZE};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::memory::pointer::slice::Slice;
+use crate::chip::memory::reads::{CountedSlot, ReadCount};
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::machine::builder::Builder;
+use crate::machine::hash::blake::blake2b::data::MemoryArray;
+use crate::machine::hash::blake::blake2b::NUM_MIX_ROUNDS;
+use crate::machine::hash::{HashInteger, HashPureInteger};
+use crate::math::prelude::*;
+
+/// The registers and memory of the compresses of a BLAKE2 hash function `H`.
+pub struct BLAKE2Data<B: Builder, H: HashInteger<B> + HashPureInteger> {
+    pub public: BLAKE2PublicData<H::IntRegister>,
+    pub trace: BLAKE2TraceData,
+    pub memory: BLAKE2Memory<H::IntRegister>,
+    pub consts: BLAKE2Consts<B, H>,
+    pub const_nums: BLAKE2ConstNums<H::IntRegister>,
+}
+
+pub struct BLAKE2PublicData<W> {
+    /// The message words of the compresses, with the index of their compress.
+    pub padded_chunks: Vec<(usize, ArrayRegister<W>)>,
+    pub mode: BLAKE2Mode<W>,
+    pub end_bits: ArrayRegister<BitRegister>,
+    pub digest_indices: ArrayRegister<ElementRegister>,
+}
+
+/// How the compresses of a message are chained, and what fills the last row of their work
+/// vector.
+#[derive(Debug, Clone)]
+pub enum BLAKE2Mode<W> {
+    /// The compresses of a message follow each other, as in BLAKE2. The last row of the work
+    /// vector is the second half of the compress IV, xored with the counter `t` of the compress
+    /// and, in the compresses flagged in `final_bits`, with the finalization flag. The state is
+    /// fed forward into the output of every compress.
+    Sequential {
+        t_values: ArrayRegister<W>,
+        final_bits: ArrayRegister<BitRegister>,
+    },
+    /// The compresses form a tree, as in BLAKE3. The last row of the work vector is given by the
+    /// four words of every compress in `parameters`, and the output of a compress isn't fed
+    /// forward. The output of the end compress `i` of a child is stored in the message words of
+    /// its parent, starting at the index `parent_indices[i]` of the message memory.
+    Tree {
+        parameters: ArrayRegister<W>,
+        parent_indices: Vec<Option<usize>>,
+    },
+}
+
+impl<W> BLAKE2Mode<W> {
+    /// The label of the memory of the last row of the work vector.
+    pub(crate) const fn parameters_label(&self) -> &'static str {
+        match self {
+            Self::Sequential { .. } => "t",
+            Self::Tree { .. } => "parameters",
+        }
+    }
+}
+
+pub struct BLAKE2TraceData {
+    pub(crate) clk: ElementRegister,
+    pub(crate) is_compress_initialize: BitRegister,
+    pub(crate) is_compress_first_row: BitRegister,
+    pub(crate) is_compress_final_row: BitRegister,
+    pub(crate) is_compress_finalize: BitRegister,
+    pub(crate) is_digest_row: BitRegister,
+    pub(crate) at_first_compress: BitRegister,
+    pub(crate) at_digest_compress: BitRegister,
+    pub(crate) at_end_compress: BitRegister,
+    pub(crate) at_dummy_compress: BitRegister,
+    pub(crate) compress_id: ElementRegister,
+    pub(crate) previous_compress_id: ElementRegister,
+    pub(crate) compress_index: ElementRegister,
+    pub(crate) mix_id: ElementRegister,
+    pub(crate) mix_index: ElementRegister,
+    pub(crate) initialize_row_selector: [BitRegister; 4],
+    pub(crate) mode: BLAKE2ModeTrace,
+    /// The rows of the flags selecting the dummy reads.
+    pub(crate) initialize_rows: CompressRows,
+    pub(crate) final_row_rows: CompressRows,
+    pub(crate) first_compress_rows: CompressRows,
+    pub(crate) dummy_compress_rows: CompressRows,
+}
+
+impl BLAKE2TraceData {
+    /// `is_compress_initialize`, set in the first four rows of every compress.
+    pub(crate) const fn initialize_flag(&self) -> CompressFlag {
+        CompressFlag::new(self.is_compress_initialize, self.initialize_rows)
+    }
+
+    /// `is_compress_final_row`, set in the last row of every compress.
+    pub(crate) const fn final_row_flag(&self) -> CompressFlag {
+        CompressFlag::new(self.is_compress_final_row, self.final_row_rows)
+    }
+
+    /// `at_first_compress`, set in the first compress of every message.
+    pub(crate) const fn first_compress_flag(&self) -> CompressFlag {
+        CompressFlag::new(self.at_first_compress, self.first_compress_rows)
+    }
+
+    /// `at_dummy_compress`, set in the dummy compresses.
+    pub(crate) const fn dummy_compress_flag(&self) -> CompressFlag {
+        CompressFlag::new(self.at_dummy_compress, self.dummy_compress_rows)
+    }
+}
+
+/// The registers of the control flow specific to the mode of the compresses.
+pub enum BLAKE2ModeTrace {
+    Sequential {
+        is_compress_third_row: BitRegister,
+        at_final_compress: BitRegister,
+    },
+    Tree {
+        at_child_compress: BitRegister,
+        parent_index: ElementRegister,
+    },
+}
+
+/// The rows of every compress in which a bit of the control flow is set, known when building the
+/// AIR, as masks of the rows of a compress.
+///
+/// The rows can differ between the first compress of a message and the other real compresses,
+/// and they are known in the dummy compresses only for the bits that don't depend on the messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressRows {
+    all_rows: u128,
+    first_compress: u128,
+    compress: u128,
+    dummy_compress: Option<u128>,
+}
+
+impl CompressRows {
+    /// The rows `rows` of every compress of `compress_length` rows.
+    pub fn periodic(compress_length: usize, rows: Range<usize>) -> Self {
+        let mask = Self::mask(compress_length, rows);
+        Self {
+            all_rows: Self::mask(compress_length, 0..compress_length),
+            first_compress: mask,
+            compress: mask,
+            dummy_compress: Some(mask),
+        }
+    }
+
+    /// The rows of the first compress of every message, which are unknown in the dummy compresses.
+    pub fn first_compress(compress_length: usize) -> Self {
+        let all_rows = Self::mask(compress_length, 0..compress_length);
+        Self {
+            all_rows,
+            first_compress: all_rows,
+            compress: 0,
+            dummy_compress: None,
+        }
+    }
+
+    /// The rows of the dummy compresses.
+    pub fn dummy_compress(compress_length: usize) -> Self {
+        let all_rows = Self::mask(compress_length, 0..compress_length);
+        Self {
+            all_rows,
+            first_compress: 0,
+            compress: 0,
+            dummy_compress: Some(all_rows),
+        }
+    }
+
+    fn mask(compress_length: usize, rows: Range<usize>) -> u128 {
+        assert!(compress_length <= 128, "A compress has at most 128 rows");
+        assert!(rows.start <= rows.end && rows.end <= compress_length);
+        rows.map(|row| 1 << row).sum()
+    }
+
+    /// The number of rows set in the first compress of a message and in the other real
+    /// compresses.
+    pub const fn num_compress_rows(&self) -> (usize, usize) {
+        (
+            self.first_compress.count_ones() as usize,
+            self.compress.count_ones() as usize,
+        )
+    }
+
+    /// The number of rows set in the first `num_dummy_rows` rows of the dummy compresses.
+    pub fn num_dummy_rows(&self, num_dummy_rows: usize) -> usize {
+        let compress_length = self.all_rows.count_ones() as usize;
+        let mask = self
+            .dummy_compress
+            .expect("The rows of the flag are unknown in the dummy compresses");
+        let num_full_compresses = num_dummy_rows / compress_length;
+        let last_compress = mask & Self::mask(compress_length, 0..num_dummy_rows % compress_length);
+        num_full_compresses * mask.count_ones() as usize + last_compress.count_ones() as usize
+    }
+}
+
+impl BitOr for CompressRows {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        assert_eq!(self.all_rows, other.all_rows);
+        let dummy_compress = match (self.dummy_compress, other.dummy_compress) {
+            (Some(rows), Some(other_rows)) => Some(rows | other_rows),
+            (Some(rows), None) | (None, Some(rows)) if rows == self.all_rows => Some(rows),
+            _ => None,
+        };
+        Self {
+            all_rows: self.all_rows,
+            first_compress: self.first_compress | other.first_compress,
+            compress: self.compress | other.compress,
+            dummy_compress,
+        }
+    }
+}
+
+impl Not for CompressRows {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Self {
+            all_rows: self.all_rows,
+            first_compress: !self.first_compress & self.all_rows,
+            compress: !self.compress & self.all_rows,
+            dummy_compress: self.dummy_compress.map(|rows| !rows & self.all_rows),
+        }
+    }
+}
+
+/// A bit of the control flow, or its negation, with the rows of the compresses in which it is set.
+///
+/// The flags selecting the dummy reads are built from these bits with `CompressFlag::any`, so
+/// that the reads of a dummy slot are counted from the flags that select them, with
+/// `BLAKE2Memory::reads`.
+#[derive(Debug, Clone, Copy)]
+pub struct CompressFlag {
+    bit: BitRegister,
+    is_negated: bool,
+    rows: CompressRows,
+}
+
+impl CompressFlag {
+    pub const fn new(bit: BitRegister, rows: CompressRows) -> Self {
+        Self {
+            bit,
+            is_negated: false,
+            rows,
+        }
+    }
+
+    /// The negation of the flag, set in the rows where the flag is not.
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Self {
+            bit: self.bit,
+            is_negated: !self.is_negated,
+            rows: !self.rows,
+        }
+    }
+
+    /// The bit of the flag, which can't be negated.
+    pub fn bit(&self) -> BitRegister {
+        assert!(
+            !self.is_negated,
+            "The bit of a negated flag is not in the trace"
+        );
+        self.bit
+    }
+
+    pub const fn rows(&self) -> &CompressRows {
+        &self.rows
+    }
+
+    pub fn expr<F: Field>(&self) -> ArithmeticExpression<F> {
+        if self.is_negated {
+            self.bit.not_expr()
+        } else {
+            self.bit.expr()
+        }
+    }
+
+    /// The flag set in the rows where any of `flags` is set, computed as one minus the product of
+    /// their negations.
+    pub fn any<B: Builder>(builder: &mut B, flags: &[Self]) -> Self {
+        let (first, rest) = flags.split_first().expect("No flags to combine");
+        let none = rest.iter().fold(first.not().expr(), |product, flag| {
+            product * flag.not().expr()
+        });
+        let rows = rest.iter().fold(first.rows, |rows, flag| rows | flag.rows);
+        Self::new(builder.expression(ArithmeticExpression::one() - none), rows)
+    }
+}
+
+pub struct BLAKE2Memory<W> {
+    pub(crate) h: Slice<W>,
+    pub(crate) v: Slice<W>,
+    pub(crate) v_final: Slice<W>,
+    pub(crate) m: Slice<W>,
+    /// The words of the last row of the work vector, `t` or the parameters of the mode.
+    pub(crate) parameters: Slice<W>,
+    /// The slots of the dummy reads of `h`, `v`, `v_final`, `m` and `parameters`.
+    pub(crate) dummy_h: CountedSlot,
+    pub(crate) dummy_v: CountedSlot,
+    pub(crate) dummy_v_final: CountedSlot,
+    pub(crate) dummy_m: CountedSlot,
+    pub(crate) dummy_parameters: CountedSlot,
+    /// The public registers of the number of messages and of the other real compresses.
+    pub(crate) num_messages: ElementRegister,
+    pub(crate) num_non_first_compresses: ElementRegister,
+    pub(crate) num_real_compresses: usize,
+    pub(crate) num_dummy_rows: usize,
+}
+
+impl<W> BLAKE2Memory<W> {
+    /// The reads of a load in the rows `rows` of the compresses.
+    ///
+    /// The rows of the compresses of the messages are counted by the number of messages if they
+    /// differ in the first compress of a message.
+    pub(crate) fn reads(&self, rows: &CompressRows) -> ReadCount {
+        let reads = ReadCount::new().rows(rows.num_dummy_rows(self.num_dummy_rows), 1);
+        match rows.num_compress_rows() {
+            (first_compress_rows, compress_rows) if first_compress_rows == compress_rows => {
+                reads.rows(self.num_real_compresses, compress_rows)
+            }
+            (first_compress_rows, compress_rows) => reads
+                .register_rows(self.num_messages, first_compress_rows)
+                .register_rows(self.num_non_first_compresses, compress_rows),
+        }
+    }
+}
+
+pub struct BLAKE2Consts<B: Builder, H: HashInteger<B> + HashPureInteger> {
+    pub(crate) iv_values: ArrayRegister<H::IntRegister>,
+    pub(crate) compress_iv: [H::Integer; STATE_SIZE],
+    /// The number of words of the state stored at the digest compresses.
+    pub(crate) digest_words: usize,
+    pub(crate) v_indices: MemoryArray<B, MIX_LENGTH, 4>,
+    pub(crate) v_last_write_ages: MemoryArray<B, MIX_LENGTH, 4>,
+    /// The permutations of the rounds, in a table sized for the 12 rounds of BLAKE2b.
+    pub(crate) permutations: MemoryArray<B, NUM_MIX_ROUNDS, MSG_ARRAY_SIZE>,
+    pub(crate) dummy_index: ElementRegister,
+    pub(crate) dummy_index_2: ElementRegister,
+    pub(crate) dummy_ts: ElementRegister,
+    pub(crate) first_compress_h_read_ts: ElementRegister,
+}
+
+pub struct BLAKE2ConstNums<W> {
+    pub(crate) const_0: ElementRegister,
+    pub(crate) const_0_word: W,
+    pub(crate) const_1: ElementRegister,
+    pub(crate) const_2: ElementRegister,
+    pub(crate) const_3: ElementRegister,
+    pub(crate) const_4: ElementRegister,
+    pub(crate) const_8: ElementRegister,
+    pub(crate) const_16: ElementRegister,
+    /// The number of rounds of a compress, which is the number of reads of every message word.
+    pub(crate) const_num_mix_rounds: ElementRegister,
+    pub(crate) const_compress_length: ElementRegister,
+    pub(crate) const_word_max: W,
+}
@@ -0,0 +1,63 @@
+//! The AIR shared by BLAKE2b, BLAKE2s and BLAKE3.
+//!
+//! The hash functions are laid out with one mix per row, and a compress of `NUM_MIX_ROUNDS`
+//! rounds of 8 mixes. The memory layout of the work vector, the control flags of the compresses
+//! and the dummy accesses of the padding rows don't depend on the size of the words, so they are
+//! built once by `BLAKE2Air` for every hash function implementing `BLAKE2`. The chaining of the
+//! compresses, sequential in BLAKE2 and a tree in BLAKE3, is given by a `BLAKE2Mode`.
+
+use super::blake2b::{MIX_LENGTH, MSG_ARRAY_SIZE, STATE_SIZE};
+use crate::chip::register::bit::BitRegister;
+use crate::machine::builder::Builder;
+use crate::machine::hash::{HashDigest, HashIntConversion};
+
+pub mod air;
+pub mod data;
+
+/// A BLAKE2 hash function, given by its words, its number of rounds and its mix.
+pub trait BLAKE2<B: Builder>: HashIntConversion<B> + HashDigest<B> + Sized {
+    /// The prefix of the labels of the constraint tags.
+    const NAME: &'static str;
+
+    /// The number of rounds of a compress.
+    const NUM_MIX_ROUNDS: usize;
+
+    /// The number of rows of a compress, one for each mix of its rounds.
+    const COMPRESS_LENGTH: usize = MIX_LENGTH * Self::NUM_MIX_ROUNDS;
+
+    /// The initial state of a message.
+    const IV: [Self::Integer; STATE_SIZE];
+
+    /// The words that initialize the second half of the work vector of every compress. In the
+    /// tree mode, only the first four words are used.
+    const COMPRESS_IV: [Self::Integer; STATE_SIZE];
+
+    /// The word with all of its bits set, xored into the work vector by the finalization flag.
+    const WORD_MAX: Self::Integer;
+
+    /// The number of words of the state in a digest.
+    const DIGEST_WORDS: usize;
+
+    /// The permutations of the message words in every round.
+    const SIGMA_PERMUTATIONS: &'static [[u8; MSG_ARRAY_SIZE]];
+
+    /// The bits set in the last row of every 4 rows, every 8 rows and every compress.
+    fn cycles_end_bits(builder: &mut B) -> (BitRegister, BitRegister, BitRegister);
+
+    /// The mix function `G` of the hash function, mixing the message words `x` and `y` into the
+    /// words `v_a`, `v_b`, `v_c` and `v_d` of the work vector.
+    fn mix(
+        builder: &mut B,
+        v_a: &Self::IntRegister,
+        v_b: &Self::IntRegister,
+        v_c: &Self::IntRegister,
+        v_d: &Self::IntRegister,
+        x: &Self::IntRegister,
+        y: &Self::IntRegister,
+    ) -> (
+        Self::IntRegister,
+        Self::IntRegister,
+        Self::IntRegister,
+        Self::IntRegister,
+    );
+}
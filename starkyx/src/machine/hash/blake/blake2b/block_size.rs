@@ -0,0 +1,343 @@
+//! BLAKE2b with a nonstandard block size, for experimentation only.
+//!
+//! The hashes computed with a block size other than `BlockSize::STANDARD` are NOT BLAKE2b
+//! digests and must not be used where BLAKE2b is expected.
+//!
+//! Every mix round of the compress function consumes the 16 message words of the block, so a
+//! block can be shorter than the standard 128 bytes but not longer. A block of fewer words is
+//! absorbed with the remaining message words set to zero, and the byte counter `t` counts the
+//! bytes of the smaller blocks. The compress keeps its 96 rows, so the cycle lengths and the
+//! memory layout of the machine are those of the standard one.
+
+use super::padding::BLAKE2BPaddedValues;
+use super::pure::BLAKE2BPure;
+use super::register::BLAKE2BDigestRegister;
+use super::units::BLOCK_SIZE;
+use super::{BLAKE2B, IV, MSG_ARRAY_SIZE};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSized};
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
+use crate::chip::AirParameters;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::blake::blake2b::builder::BlakeBuilder;
+use crate::math::prelude::*;
+use crate::prelude::Builder;
+
+/// The number of 64-bit message words of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSize(usize);
+
+impl BlockSize {
+    /// The block size of BLAKE2b, 16 words of 128 bytes.
+    pub const STANDARD: BlockSize = BlockSize(MSG_ARRAY_SIZE);
+
+    /// A block of `words` message words.
+    pub const fn new(words: usize) -> Self {
+        assert!(
+            words > 0 && words <= MSG_ARRAY_SIZE,
+            "A block has between 1 and 16 message words"
+        );
+        BlockSize(words)
+    }
+
+    pub const fn words(&self) -> usize {
+        self.0
+    }
+
+    pub const fn bytes(&self) -> usize {
+        self.0 * 8
+    }
+
+    pub const fn is_standard(&self) -> bool {
+        self.0 == MSG_ARRAY_SIZE
+    }
+
+    /// The number of blocks a message of `msg_len` bytes is padded to. An empty message still
+    /// takes a block.
+    pub const fn num_blocks(&self, msg_len: usize) -> usize {
+        if msg_len == 0 {
+            1
+        } else {
+            (msg_len + self.bytes() - 1) / self.bytes()
+        }
+    }
+}
+
+impl BLAKE2B {
+    /// The digest of `msg` with blocks of `block_size`, which is the BLAKE2b digest only for
+    /// `BlockSize::STANDARD`.
+    pub fn hash_with_block_size(msg: &[u8], block_size: BlockSize) -> [u8; 32] {
+        let values = Self::pad_values_with_block_size(&[msg.to_vec()], block_size);
+
+        let mut state = IV;
+        for (i, block) in values
+            .padded_chunks
+            .chunks_exact(block_size.bytes())
+            .enumerate()
+        {
+            let mut chunk = [0u8; BLOCK_SIZE];
+            chunk[..block.len()].copy_from_slice(block);
+            Self::compress(&chunk, &mut state, values.t_values[i], values.end_bits[i]);
+        }
+
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(8).zip(state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+
+    /// Pads every message of `messages` to blocks of `block_size`, laid out as by `pad_values`.
+    /// The padded chunks hold `block_size.bytes()` bytes for every compress.
+    pub fn pad_values_with_block_size(
+        messages: &[Vec<u8>],
+        block_size: BlockSize,
+    ) -> BLAKE2BPaddedValues {
+        let mut values = BLAKE2BPaddedValues {
+            padded_chunks: Vec::new(),
+            t_values: Vec::new(),
+            end_bits: Vec::new(),
+            digest_indices: Vec::new(),
+        };
+        for msg in messages {
+            let num_blocks = block_size.num_blocks(msg.len());
+            values.padded_chunks.extend_from_slice(msg);
+            values.padded_chunks.resize(
+                values.padded_chunks.len() + num_blocks * block_size.bytes() - msg.len(),
+                0,
+            );
+            for i in 0..num_blocks {
+                let is_last = i == num_blocks - 1;
+                values.t_values.push(if is_last {
+                    msg.len() as u64
+                } else {
+                    ((i + 1) * block_size.bytes()) as u64
+                });
+                values.end_bits.push(is_last);
+            }
+            values.digest_indices.push(values.end_bits.len() - 1);
+        }
+        values
+    }
+
+    /// Hashes the padded chunks of `block_size` words like `BLAKEAir::blake2b`.
+    ///
+    /// The chunks of a nonstandard size are copied to public chunks of 16 words, with the words
+    /// after `block_size` set to zero, so the machine itself is the standard one. The copies are
+    /// written by the global instructions.
+    #[allow(clippy::too_many_arguments)]
+    pub fn blake2b_with_block_size<L: AirParameters>(
+        builder: &mut BytesBuilder<L>,
+        block_size: BlockSize,
+        padded_chunks: &[ArrayRegister<U64Register>],
+        t_values: &ArrayRegister<U64Register>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+    ) -> Vec<BLAKE2BDigestRegister>
+    where
+        L::Instruction: UintInstructions,
+    {
+        assert!(
+            padded_chunks
+                .iter()
+                .all(|chunk| chunk.len() == block_size.words()),
+            "A padded chunk must have {} words",
+            block_size.words()
+        );
+
+        let padded_chunks = if block_size.is_standard() {
+            padded_chunks.to_vec()
+        } else {
+            let zero = ArithmeticExpression::from_constant_vec(vec![
+                L::Field::ZERO;
+                U64Register::size_of()
+            ]);
+            padded_chunks
+                .iter()
+                .map(|chunk| {
+                    let block = builder.alloc_array_public::<U64Register>(MSG_ARRAY_SIZE);
+                    for (j, word) in block.iter().enumerate() {
+                        let value = if j < chunk.len() {
+                            chunk.get(j).expr()
+                        } else {
+                            zero.clone()
+                        };
+                        builder.set_to_expression(&word, value);
+                    }
+                    block
+                })
+                .collect()
+        };
+
+        builder.blake2b::<BLAKE2B>(
+            &padded_chunks,
+            t_values,
+            end_bits,
+            digest_bits,
+            digest_indices,
+            num_messages,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+
+    use super::*;
+    use crate::chip::uint::util::u64_to_le_field_bytes;
+    use crate::machine::hash::blake::blake2b::builder::test_utils::BLAKE2BTest;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::prelude::{AirWriter, AirWriterData};
+
+    #[test]
+    fn test_hash_with_standard_block_size() {
+        for msg_len in [0, 1, 127, 128, 129, 256, 300] {
+            let msg = (0..msg_len).map(|i| (i * 11 + 7) as u8).collect::<Vec<_>>();
+            assert_eq!(
+                BLAKE2B::hash_with_block_size(&msg, BlockSize::STANDARD),
+                BLAKE2B::hash(&msg)
+            );
+            assert_eq!(
+                BLAKE2B::pad_values_with_block_size(&[msg.clone()], BlockSize::STANDARD),
+                BLAKE2B::pad_values(&[msg])
+            );
+        }
+    }
+
+    #[test]
+    fn test_nonstandard_block_size_values() {
+        let block_size = BlockSize::new(4);
+        let values =
+            BLAKE2B::pad_values_with_block_size(&[vec![], vec![1; 32], vec![2; 70]], block_size);
+        assert_eq!(values.padded_chunks.len(), 5 * block_size.bytes());
+        assert_eq!(values.t_values, [0, 32, 32, 64, 70]);
+        assert_eq!(values.end_bits, [true, true, false, false, true]);
+        assert_eq!(values.digest_indices, [0, 1, 4]);
+
+        // A message longer than the smaller block no longer has the BLAKE2b digest.
+        let msg = vec![3u8; 70];
+        assert_ne!(
+            BLAKE2B::hash_with_block_size(&msg, block_size),
+            BLAKE2B::hash(&msg)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_block_size_too_large() {
+        BlockSize::new(MSG_ARRAY_SIZE + 1);
+    }
+
+    /// Proves the hashes of `messages` with blocks of `block_size`, checking the digests against
+    /// `BLAKE2B::hash_with_block_size`.
+    fn prove_with_block_size(messages: &[Vec<u8>], block_size: BlockSize) {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+        type F = GoldilocksField;
+
+        let values = BLAKE2B::pad_values_with_block_size(messages, block_size);
+        let num_compresses = values.end_bits.len();
+
+        let mut builder = BytesBuilder::<L>::new();
+        let padded_chunks = (0..num_compresses)
+            .map(|_| builder.alloc_array_public::<U64Register>(block_size.words()))
+            .collect::<Vec<_>>();
+        let t_values = builder.alloc_array_public::<U64Register>(num_compresses);
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let digest_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let digest_indices = builder.alloc_array_public::<ElementRegister>(messages.len());
+        let num_messages = builder.alloc_public::<ElementRegister>();
+        let digests = BLAKE2B::blake2b_with_block_size(
+            &mut builder,
+            block_size,
+            &padded_chunks,
+            &t_values,
+            &end_bits,
+            &digest_bits,
+            &digest_indices,
+            &num_messages,
+        );
+
+        let num_rows = BLAKE2B::rows_for(num_compresses);
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        for (i, (chunk, block)) in padded_chunks
+            .iter()
+            .zip(values.padded_chunks.chunks_exact(block_size.bytes()))
+            .enumerate()
+        {
+            writer.write_array(
+                chunk,
+                block
+                    .chunks_exact(8)
+                    .map(|word| <[u8; 8]>::try_from(word).unwrap().map(F::from_canonical_u8)),
+            );
+            writer.write(&t_values.get(i), &u64_to_le_field_bytes(values.t_values[i]));
+            let end_bit = F::from_canonical_usize(values.end_bits[i] as usize);
+            writer.write(&end_bits.get(i), &end_bit);
+            writer.write(&digest_bits.get(i), &end_bit);
+        }
+        for (i, index) in values.digest_indices.iter().enumerate() {
+            writer.write(&digest_indices.get(i), &F::from_canonical_usize(*index));
+        }
+        writer.write(&num_messages, &F::from_canonical_usize(messages.len()));
+        for (digest, msg) in digests.iter().zip(messages.iter()) {
+            let array: ArrayRegister<_> = (*digest).into();
+            writer.write_array(
+                &array,
+                BLAKE2B::hash_with_block_size(msg, block_size)
+                    .chunks_exact(8)
+                    .map(|word| <[u8; 8]>::try_from(word).unwrap().map(F::from_canonical_u8)),
+            );
+        }
+
+        stark.air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new("test_blake2b_block_size", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    fn test_messages() -> Vec<Vec<u8>> {
+        vec![
+            vec![],
+            b"abc".to_vec(),
+            (0..200).map(|i| (i * 5 + 1) as u8).collect(),
+        ]
+    }
+
+    #[test]
+    fn test_blake2b_standard_block_size() {
+        // The standard block size proves the BLAKE2b digests.
+        let messages = test_messages();
+        for msg in messages.iter() {
+            assert_eq!(
+                BLAKE2B::hash_with_block_size(msg, BlockSize::STANDARD),
+                BLAKE2B::hash(msg)
+            );
+        }
+        prove_with_block_size(&messages, BlockSize::STANDARD);
+    }
+
+    #[test]
+    fn test_blake2b_half_block_size() {
+        prove_with_block_size(&test_messages(), BlockSize::new(8));
+    }
+}
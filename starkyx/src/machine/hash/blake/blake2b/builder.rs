@@ -1,7 +1,15 @@
 use super::air::BLAKEAir;
+use super::preimage::PreimageMessage;
+use super::{BLAKE2B, COMPRESS_LENGTH, MSG_ARRAY_SIZE, STATE_SIZE};
+use crate::chip::builder::padding::PaddingPolicy;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
+use crate::chip::{AirParameters, ColumnBudget, ResourceReport};
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::blake::blake2::BLAKE2;
 use crate::prelude::Builder;
 
 pub trait BlakeBuilder: Builder {
@@ -24,14 +32,234 @@ pub trait BlakeBuilder: Builder {
             num_messages,
         )
     }
+
+    #[allow(clippy::too_many_arguments)]
+    fn blake2b_with_intermediate_digests<B: BLAKEAir<Self>>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<B::IntRegister>],
+        t_values: &ArrayRegister<B::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        final_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+    ) -> Vec<B::DigestRegister> {
+        B::blake2b_with_intermediate_digests(
+            self,
+            padded_chunks,
+            t_values,
+            end_bits,
+            digest_bits,
+            final_bits,
+            digest_indices,
+            num_messages,
+        )
+    }
+
+    /// Hashes a segment of a message starting from the public `initial_state`, returning the
+    /// whole state at every digest compress. See `BLAKEAir::blake2b_segment`.
+    #[allow(clippy::too_many_arguments)]
+    fn blake2b_segment<B: BLAKEAir<Self>>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<B::IntRegister>],
+        t_values: &ArrayRegister<B::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        final_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+        initial_state: &ArrayRegister<B::IntRegister>,
+    ) -> Vec<ArrayRegister<B::IntRegister>> {
+        B::blake2b_segment(
+            self,
+            padded_chunks,
+            t_values,
+            end_bits,
+            digest_bits,
+            final_bits,
+            digest_indices,
+            num_messages,
+            initial_state,
+        )
+    }
+
+    /// Hashes the padded chunks like `blake2b`, with the BLAKE2b personalization given by the two
+    /// public words of `personalization`.
+    #[allow(clippy::too_many_arguments)]
+    fn blake2b_personalized<B: BLAKEAir<Self>>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<B::IntRegister>],
+        t_values: &ArrayRegister<B::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+        personalization: &ArrayRegister<B::IntRegister>,
+    ) -> Vec<B::DigestRegister> {
+        B::blake2b_personalized(
+            self,
+            padded_chunks,
+            t_values,
+            end_bits,
+            digest_bits,
+            digest_indices,
+            num_messages,
+            personalization,
+        )
+    }
+
+    /// Hashes the padded chunks like `blake2b`, with `compress_iv` in place of the standard
+    /// BLAKE2b IV in the work vector of every compress.
+    #[allow(clippy::too_many_arguments)]
+    fn blake2b_with_compress_iv<B: BLAKEAir<Self>>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<B::IntRegister>],
+        t_values: &ArrayRegister<B::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+        compress_iv: &[u64; STATE_SIZE],
+    ) -> Vec<B::DigestRegister> {
+        B::blake2b_with_compress_iv(
+            self,
+            padded_chunks,
+            t_values,
+            end_bits,
+            digest_bits,
+            digest_indices,
+            num_messages,
+            compress_iv,
+        )
+    }
+
+    /// Hashes the padded chunks like `blake2b`, and proves that every digest belongs to the public
+    /// set of digests `set`.
+    ///
+    /// Returns the trace registers of the multiplicities of the entries of the set, which the
+    /// prover writes with the number of digests equal to every entry.
+    #[allow(clippy::too_many_arguments)]
+    fn blake2b_set_membership<B: BLAKEAir<Self>>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<B::IntRegister>],
+        t_values: &ArrayRegister<B::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+        set: &[B::DigestRegister],
+    ) -> ArrayRegister<ElementRegister> {
+        B::blake2b_set_membership(
+            self,
+            padded_chunks,
+            t_values,
+            end_bits,
+            digest_bits,
+            digest_indices,
+            num_messages,
+            set,
+        )
+    }
+
+    /// Proves the knowledge of a private message of `message_len` bytes whose digest is the
+    /// public `expected_digest`. See `BLAKEAir::prove_preimage`.
+    fn prove_preimage<B: BLAKEAir<Self>>(
+        &mut self,
+        expected_digest: &ArrayRegister<B::IntRegister>,
+        message_len: usize,
+    ) -> PreimageMessage {
+        B::prove_preimage(self, expected_digest, message_len)
+    }
 }
 
 impl<B: Builder> BlakeBuilder for B {}
 
+impl BLAKE2B {
+    /// The number of rows of the trace of the BLAKE2b machine hashing `num_compresses` padded
+    /// chunks. The rows of the last cycle are padded with dummy compresses up to a power of two.
+    pub fn rows_for(num_compresses: usize) -> usize {
+        Self::rows_for_policy(num_compresses, PaddingPolicy::NextPowerOfTwo)
+    }
+
+    /// The number of rows of the trace of the BLAKE2b machine hashing `num_compresses` padded
+    /// chunks, with the rows after the last compress padded according to `policy`.
+    pub fn rows_for_policy(num_compresses: usize, policy: PaddingPolicy) -> usize {
+        policy.padded_length(num_compresses * COMPRESS_LENGTH)
+    }
+
+    /// The columns needed by the BLAKE2b machine hashing `num_compresses` padded chunks, to be
+    /// used as the column constants of `L`.
+    pub fn required_columns<L: AirParameters>(num_compresses: usize) -> ColumnBudget
+    where
+        L::Instruction: UintInstructions,
+    {
+        let mut builder = BytesBuilder::<L>::new();
+        let padded_chunks = (0..num_compresses)
+            .map(|_| builder.alloc_array_public::<U64Register>(MSG_ARRAY_SIZE))
+            .collect::<Vec<_>>();
+        let t_values = builder.alloc_array_public::<U64Register>(num_compresses);
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let digest_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let digest_indices = builder.alloc_array_public::<ElementRegister>(num_compresses);
+        let num_messages = builder.alloc_public::<ElementRegister>();
+        builder.blake2b::<BLAKE2B>(
+            &padded_chunks,
+            &t_values,
+            &end_bits,
+            &digest_bits,
+            &digest_indices,
+            &num_messages,
+        );
+
+        builder.column_budget()
+    }
+
+    /// The resources used by a single call to the BLAKE2b `mix`.
+    ///
+    /// A mix does 6 additions, 4 xors and 4 rotations of 64-bit words. The additions take 10 free
+    /// columns each for the result and the carries, the xors take 8 for the result, and the
+    /// rotations take 24 for the result and the shifted bytes, for a total of 188 free columns.
+    /// Each of the 112 byte operations looked up takes 3 extended columns for its digest, and
+    /// every two of them take 3 more for an accumulator, for a total of 504 extended columns.
+    pub fn mix_cost<L: AirParameters>() -> ResourceReport
+    where
+        L::Instruction: UintInstructions,
+    {
+        let new_builder = || {
+            let mut builder = BytesBuilder::<L>::new();
+            let values = builder.alloc_array::<U64Register>(6);
+            (builder, values)
+        };
+
+        let (baseline, _) = new_builder();
+        let (mut builder, values) = new_builder();
+        let num_constraints = builder.api.constraints.len();
+        let num_byte_operations = builder.operations.trace_operations.len();
+        <Self as BLAKE2<BytesBuilder<L>>>::mix(
+            &mut builder,
+            &values.get(0),
+            &values.get(1),
+            &values.get(2),
+            &values.get(3),
+            &values.get(4),
+            &values.get(5),
+        );
+        let num_constraints = builder.api.constraints.len() - num_constraints;
+        let num_byte_operations = builder.operations.trace_operations.len() - num_byte_operations;
+
+        ResourceReport {
+            columns: builder.column_budget() - baseline.column_budget(),
+            num_constraints,
+            num_byte_operations,
+        }
+    }
+}
+
 #[cfg(test)]
 pub mod test_utils {
 
     use core::fmt::Debug;
+    use std::collections::BTreeMap;
     use std::env;
 
     use itertools::Itertools;
@@ -45,18 +273,27 @@ pub mod test_utils {
     use serde::{Deserialize, Serialize};
 
     use super::*;
+    use crate::air::{AirMetadata, RAirData};
+    use crate::chip::instruction::set::AirInstruction;
+    use crate::chip::memory::instruction::{MemoryInstruction, MemorySliceIndex};
+    use crate::chip::register::memory::MemorySlice;
+    use crate::chip::register::RegisterSerializable;
+    use crate::chip::trace::data::AirTraceData;
     use crate::chip::uint::operations::instruction::UintInstruction;
     use crate::chip::uint::util::u64_to_le_field_bytes;
-    use crate::chip::AirParameters;
+    use crate::chip::{AirParameters, Chip};
     use crate::machine;
     use crate::machine::bytes::builder::BytesBuilder;
+    use crate::machine::bytes::stark::ByteStark;
     use crate::machine::hash::blake::blake2b::pure::BLAKE2BPure;
+    use crate::machine::hash::blake::blake2b::register::BLAKE2BDigestRegister;
     use crate::machine::hash::blake::blake2b::utils::BLAKE2BUtil;
-    use crate::machine::hash::blake::blake2b::{BLAKE2B, IV};
+    use crate::machine::hash::blake::blake2b::{BLAKE2B, COMPRESS_IV, IV};
     use crate::machine::hash::HashDigest;
     use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
     use crate::math::prelude::*;
     use crate::plonky2::stark::config::{CurtaConfig, CurtaPoseidonGoldilocksConfig};
+    use crate::plonky2::Plonky2Air;
     use crate::prelude::{AirWriter, AirWriterData};
 
     #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -67,8 +304,292 @@ pub mod test_utils {
         type CubicParams = GoldilocksCubicParameters;
         type Instruction = UintInstruction;
 
-        const NUM_FREE_COLUMNS: usize = 1271;
-        const EXTENDED_COLUMNS: usize = 1476;
+        const NUM_FREE_COLUMNS: usize = 1270;
+        const EXTENDED_COLUMNS: usize = 1464;
+    }
+
+    /// The public inputs of the BLAKE2b AIR hashing a batch of compresses.
+    pub struct BLAKE2BInputs {
+        pub padded_chunks: Vec<ArrayRegister<U64Register>>,
+        pub t_values: ArrayRegister<U64Register>,
+        pub end_bits: ArrayRegister<BitRegister>,
+        pub digest_bits: ArrayRegister<BitRegister>,
+        pub digest_indices: ArrayRegister<ElementRegister>,
+        pub num_messages: ElementRegister,
+    }
+
+    impl BLAKE2BInputs {
+        /// Allocates the public inputs of `num_compresses` compresses and `num_digests` digests.
+        pub fn alloc<L: AirParameters>(
+            builder: &mut BytesBuilder<L>,
+            num_compresses: usize,
+            num_digests: usize,
+        ) -> Self {
+            let padded_chunks = (0..num_compresses)
+                .map(|_| builder.alloc_array_public::<U64Register>(MSG_ARRAY_SIZE))
+                .collect::<Vec<_>>();
+            let t_values = builder.alloc_array_public::<U64Register>(num_compresses);
+            let end_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+            let digest_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+            let digest_indices = builder.alloc_array_public(num_digests);
+            let num_messages = builder.alloc_public();
+            Self {
+                padded_chunks,
+                t_values,
+                end_bits,
+                digest_bits,
+                digest_indices,
+                num_messages,
+            }
+        }
+
+        /// Allocates the public inputs of the compresses of `msgs`, with a digest per message.
+        pub fn alloc_messages<L: AirParameters>(
+            builder: &mut BytesBuilder<L>,
+            msgs: &[Vec<u8>],
+        ) -> Self {
+            let num_compresses = msgs.iter().map(|msg| num_chunks(msg)).sum();
+            Self::alloc(builder, num_compresses, msgs.len())
+        }
+
+        /// Hashes the compresses with `blake2b`, with a digest at the end of every message.
+        pub fn blake2b<L: AirParameters>(
+            &self,
+            builder: &mut BytesBuilder<L>,
+        ) -> Vec<BLAKE2BDigestRegister>
+        where
+            L::Instruction: UintInstructions,
+        {
+            builder.blake2b::<BLAKE2B>(
+                &self.padded_chunks,
+                &self.t_values,
+                &self.end_bits,
+                &self.digest_bits,
+                &self.digest_indices,
+                &self.num_messages,
+            )
+        }
+
+        /// Writes the padded `msgs`, each of which ends with a digest compress, and returns the
+        /// state of every message after its last compress.
+        pub fn write_messages(
+            &self,
+            writer: &mut impl AirWriter<Field = GoldilocksField>,
+            msgs: &[Vec<u8>],
+        ) -> Vec<[u64; STATE_SIZE]> {
+            let to_field = |x: bool| GoldilocksField::from_canonical_usize(x as usize);
+            writer.write(
+                &self.num_messages,
+                &GoldilocksField::from_canonical_usize(msgs.len()),
+            );
+
+            let mut compress_id = 0;
+            let mut states = Vec::new();
+            for (i, msg) in msgs.iter().enumerate() {
+                let num_chunks = num_chunks(msg);
+                let padded_msg = BLAKE2BUtil::pad(msg, num_chunks as u64);
+                let mut state = IV;
+                for (j, chunk) in padded_msg.chunks_exact(128).enumerate() {
+                    let is_last = j == num_chunks - 1;
+                    let t_value = if is_last {
+                        msg.len() as u64
+                    } else {
+                        (128 * (j + 1)) as u64
+                    };
+
+                    writer.write_array(
+                        &self.padded_chunks[compress_id],
+                        chunk.chunks_exact(8).map(|x| {
+                            <[u8; 8]>::try_from(x)
+                                .unwrap()
+                                .map(GoldilocksField::from_canonical_u8)
+                        }),
+                    );
+                    writer.write(
+                        &self.t_values.get(compress_id),
+                        &u64_to_le_field_bytes(t_value),
+                    );
+                    writer.write(&self.end_bits.get(compress_id), &to_field(is_last));
+                    writer.write(&self.digest_bits.get(compress_id), &to_field(is_last));
+
+                    BLAKE2B::compress(chunk, &mut state, t_value, is_last);
+                    compress_id += 1;
+                }
+
+                writer.write(
+                    &self.digest_indices.get(i),
+                    &GoldilocksField::from_canonical_usize(compress_id - 1),
+                );
+                states.push(state);
+            }
+            states
+        }
+    }
+
+    /// The number of compresses of `msg`, which is at least one for the empty message.
+    pub fn num_chunks(msg: &[u8]) -> usize {
+        ((msg.len() + 127) / 128).max(1)
+    }
+
+    /// Writes the first four words of each of `states` to the matching register of `digests`.
+    pub fn write_digests(
+        writer: &mut impl AirWriter<Field = GoldilocksField>,
+        digests: &[BLAKE2BDigestRegister],
+        states: &[[u64; STATE_SIZE]],
+    ) {
+        for (digest, state) in digests.iter().zip_eq(states.iter()) {
+            let array: ArrayRegister<_> = (*digest).into();
+            writer.write_array(
+                &array,
+                state[0..4].iter().map(|x| u64_to_le_field_bytes(*x)),
+            );
+        }
+    }
+
+    /// Writes the global instructions and the instructions of every row of the trace, once the
+    /// public inputs have been written.
+    pub fn write_trace<L: AirParameters>(
+        air_data: &AirTraceData<L>,
+        writer_data: &mut AirWriterData<L::Field>,
+        num_rows: usize,
+    ) {
+        air_data.write_global_instructions(&mut writer_data.public_writer());
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                air_data.write_trace_instructions(&mut writer);
+            }
+        }
+    }
+
+    /// Proves the trace of `writer_data` and verifies the proof.
+    pub fn prove_and_verify<L>(
+        stark: &ByteStark<L, CurtaPoseidonGoldilocksConfig, 2>,
+        writer_data: AirWriterData<GoldilocksField>,
+        name: &str,
+    ) where
+        L: AirParameters<Field = GoldilocksField, CubicParams = GoldilocksCubicParameters>,
+        Chip<L>: Plonky2Air<GoldilocksField, 2>,
+    {
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new(name, log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    /// A memory access with an index element, as written in a row of the trace.
+    #[derive(Debug, Clone)]
+    pub struct MemoryAccess {
+        /// The row of the access, or `None` for an access of the global instructions.
+        pub row: Option<usize>,
+        pub label: String,
+        pub is_write: bool,
+        /// Whether the index register is one of the dummy indices, or is selected to one of them
+        /// by the bits of the row.
+        pub is_dummy: bool,
+        /// Whether the index register is one of the dummy indices itself, as in the writes of the
+        /// slots read by the dummy accesses.
+        pub is_dummy_slot: bool,
+        pub index: u64,
+        pub ts: u64,
+        /// The number of reads allowed by a write, or one for a read.
+        pub multiplicity: u64,
+    }
+
+    /// Reads the memory accesses with an index element of the global instructions and of every
+    /// row of the trace of `writer_data`.
+    ///
+    /// An access is labelled as dummy by following the select instructions that compute its
+    /// index in its row, not by the value of the index.
+    pub fn memory_accesses<L: AirParameters>(
+        air_data: &AirTraceData<L>,
+        writer_data: &mut AirWriterData<L::Field>,
+        num_rows: usize,
+        dummy_indices: &[ElementRegister],
+    ) -> Vec<MemoryAccess> {
+        let selects = air_data
+            .instructions
+            .iter()
+            .chain(air_data.global_instructions.iter())
+            .filter_map(|instr| match instr {
+                AirInstruction::Select(select) => Some((select.result, select.selection())),
+                _ => None,
+            })
+            .collect::<BTreeMap<_, _>>();
+        let dummy_registers = dummy_indices
+            .iter()
+            .map(|index| *index.register())
+            .collect::<Vec<_>>();
+
+        let mut accesses = instruction_accesses(
+            &air_data.global_instructions,
+            &writer_data.public_writer(),
+            &selects,
+            &dummy_registers,
+            None,
+        );
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                accesses.extend(instruction_accesses(
+                    &air_data.instructions,
+                    &chunk.window_writer(i),
+                    &selects,
+                    &dummy_registers,
+                    Some(i),
+                ));
+            }
+        }
+        accesses
+    }
+
+    fn instruction_accesses<F: PrimeField64, I>(
+        instructions: &[AirInstruction<F, I>],
+        writer: &impl AirWriter<Field = F>,
+        selects: &BTreeMap<MemorySlice, (BitRegister, MemorySlice, MemorySlice)>,
+        dummy_registers: &[MemorySlice],
+        row: Option<usize>,
+    ) -> Vec<MemoryAccess> {
+        let mut accesses = Vec::new();
+        for instr in instructions {
+            let (memory_output, is_write, multiplicity) = match instr {
+                AirInstruction::Mem(MemoryInstruction::Get(get)) => {
+                    (get.memory_output(), false, None)
+                }
+                AirInstruction::Mem(MemoryInstruction::Set(set)) => {
+                    (set.memory_output(), true, set.multiplicity())
+                }
+                _ => continue,
+            };
+            let Some(output) = memory_output else {
+                continue;
+            };
+            let Some(MemorySliceIndex::IndexElement(index)) = output.index else {
+                continue;
+            };
+
+            // Follow the selects of the index to the register it is taken from in this row.
+            let mut register = *index.register();
+            while let Some((bit, true_value, false_value)) = selects.get(&register) {
+                register = if writer.read(bit) == F::ONE {
+                    *true_value
+                } else {
+                    *false_value
+                };
+            }
+            accesses.push(MemoryAccess {
+                row,
+                label: output.label.clone(),
+                is_write,
+                is_dummy: dummy_registers.contains(&register),
+                is_dummy_slot: dummy_registers.contains(index.register()),
+                index: writer.read(&index).as_canonical_u64(),
+                ts: writer.read_expression(&output.ts.0)[0].as_canonical_u64(),
+                multiplicity: multiplicity.map_or(1, |multiplicity| {
+                    writer.read(&multiplicity).as_canonical_u64()
+                }),
+            });
+        }
+        accesses
     }
 
     #[test]
@@ -275,4 +796,912 @@ pub mod test_utils {
 
         timing.print();
     }
+
+    /// Golden round data of the BLAKE2b AIR hashing 4 compresses into one digest, as reported by
+    /// `round_data()`.
+    ///
+    /// The column counts do not depend on the number of compresses, while the number of global
+    /// values grows with the public inputs of the compresses and digests. A change to the BLAKE2b
+    /// circuit that intentionally changes its column usage should update these values together
+    /// with `BLAKE2BTest`.
+    const GOLDEN_EXECUTION_COLUMNS: usize = 1270;
+    const GOLDEN_EXTENDED_COLUMNS: usize = 1464;
+    const GOLDEN_NUM_CHALLENGES: usize = 93;
+    const GOLDEN_NUM_GLOBAL_VALUES: usize = 2193;
+
+    #[test]
+    fn test_blake2b_round_data_golden() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type IntRegister = <machine::hash::blake::blake2b::BLAKE2B as machine::hash::HashInteger<
+            BytesBuilder<BLAKE2BTest>,
+        >>::IntRegister;
+
+        let num_compresses = 4;
+        let num_rows = 1 << 9;
+
+        let mut builder = BytesBuilder::<BLAKE2BTest>::new();
+        let padded_chunks = (0..num_compresses)
+            .map(|_| builder.alloc_array_public::<IntRegister>(16))
+            .collect::<Vec<_>>();
+        let t_values = builder.alloc_array_public::<IntRegister>(num_compresses);
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let digest_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let digest_indices = builder.alloc_array_public(1);
+        let num_messages = builder.alloc_public();
+        builder.blake2b::<BLAKE2B>(
+            &padded_chunks,
+            &t_values,
+            &end_bits,
+            &digest_bits,
+            &digest_indices,
+            &num_messages,
+        );
+
+        let stark = builder.build::<C, 2>(num_rows);
+        let air = stark.stark().air();
+        let round_data = air.round_data();
+
+        assert_eq!(round_data.len(), 2);
+
+        let (execution, extended) = (round_data[0], round_data[1]);
+        assert_eq!(execution.num_columns, GOLDEN_EXECUTION_COLUMNS);
+        assert_eq!(execution.global_values_range, (0, 0));
+        assert_eq!(execution.num_challenges, GOLDEN_NUM_CHALLENGES);
+        assert_eq!(extended.num_columns, GOLDEN_EXTENDED_COLUMNS);
+        assert_eq!(extended.global_values_range, (0, GOLDEN_NUM_GLOBAL_VALUES));
+        assert_eq!(extended.num_challenges, 0);
+
+        // The extended columns are all used, so the golden counts are not just the budget of
+        // `BLAKE2BTest`.
+        assert_eq!(air.num_extended_columns, GOLDEN_EXTENDED_COLUMNS);
+        assert_eq!(
+            air.num_columns(),
+            GOLDEN_EXECUTION_COLUMNS + GOLDEN_EXTENDED_COLUMNS
+        );
+        assert_eq!(air.num_global_values(), GOLDEN_NUM_GLOBAL_VALUES);
+    }
+
+    #[test]
+    fn test_blake2b_intermediate_digests() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        let num_compresses = 4;
+        let num_rows = 1 << 9;
+
+        // Emit the intermediate states after block 1 and block 3 of a 4 block message.
+        let digest_compresses = [0, 2];
+        let msg = (0..num_compresses * 128)
+            .map(|i| (i * 7 + 3) as u8)
+            .collect::<Vec<_>>();
+        let padded_msg = BLAKE2BUtil::pad(&msg, num_compresses as u64);
+        assert_eq!(padded_msg.len(), num_compresses * 128);
+
+        let mut builder = BytesBuilder::<L>::new();
+        let inputs = BLAKE2BInputs::alloc(&mut builder, num_compresses, digest_compresses.len());
+        let final_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let hash_state = builder.blake2b_with_intermediate_digests::<BLAKE2B>(
+            &inputs.padded_chunks,
+            &inputs.t_values,
+            &inputs.end_bits,
+            &inputs.digest_bits,
+            &final_bits,
+            &inputs.digest_indices,
+            &inputs.num_messages,
+        );
+        assert_eq!(hash_state.len(), digest_compresses.len());
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+
+        writer.write(&inputs.num_messages, &GoldilocksField::ONE);
+        let to_field = |x: bool| GoldilocksField::from_canonical_usize(x as usize);
+
+        let mut current_state = IV;
+        let mut expected_digests = Vec::new();
+        for (i, chunk) in padded_msg.chunks_exact(128).enumerate() {
+            let t_value = (128 * (i + 1)) as u64;
+            let is_digest = digest_compresses.contains(&i);
+            let is_final = i == num_compresses - 1;
+
+            writer.write_array(
+                &inputs.padded_chunks[i],
+                chunk.chunks_exact(8).map(|x| {
+                    <[u8; 8]>::try_from(x)
+                        .unwrap()
+                        .map(GoldilocksField::from_canonical_u8)
+                }),
+            );
+            writer.write(&inputs.t_values.get(i), &u64_to_le_field_bytes(t_value));
+            writer.write(&inputs.end_bits.get(i), &to_field(is_final));
+            writer.write(&inputs.digest_bits.get(i), &to_field(is_digest));
+            writer.write(&final_bits.get(i), &to_field(is_final));
+
+            BLAKE2B::compress(chunk, &mut current_state, t_value, is_final);
+            if is_digest {
+                expected_digests.push(current_state);
+            }
+        }
+
+        for (i, compress_id) in digest_compresses.iter().enumerate() {
+            writer.write(
+                &inputs.digest_indices.get(i),
+                &GoldilocksField::from_canonical_usize(*compress_id),
+            );
+        }
+        write_digests(&mut writer, &hash_state, &expected_digests);
+        write_trace(&stark.air_data, &mut writer_data, num_rows);
+
+        // The intermediate states must not have the finalization flag applied.
+        let mut finalized_state = IV;
+        BLAKE2B::compress(&padded_msg[..128], &mut finalized_state, 128, true);
+        assert_ne!(finalized_state, expected_digests[0]);
+
+        prove_and_verify(&stark, writer_data, "test_blake2b_intermediate_digests");
+    }
+
+    #[test]
+    fn test_blake2b_iv_constant_tables() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        // Column counts of the AIR when the IV values were read from timestamped memory.
+        const MEMORY_IV_EXECUTION_COLUMNS: usize = 1272;
+        const MEMORY_IV_EXTENDED_COLUMNS: usize = 1482;
+
+        // A two block message followed by a one block message, so that the IV values are read
+        // both in a first compress and in the compress following it.
+        let msgs = [
+            (0..200).map(|i| (i * 5 + 1) as u8).collect::<Vec<_>>(),
+            (0..50).map(|i| (i * 3 + 2) as u8).collect::<Vec<_>>(),
+        ];
+        let num_rows = BLAKE2B::rows_for(3);
+
+        let mut builder = BytesBuilder::<L>::new();
+        let inputs = BLAKE2BInputs::alloc_messages(&mut builder, &msgs);
+        let hash_state = inputs.blake2b(&mut builder);
+
+        let stark = builder.build::<C, 2>(num_rows);
+        let round_data = stark.stark().air().round_data();
+        assert_eq!(round_data[0].num_columns, GOLDEN_EXECUTION_COLUMNS);
+        assert_eq!(round_data[1].num_columns, GOLDEN_EXTENDED_COLUMNS);
+        assert!(round_data[0].num_columns < MEMORY_IV_EXECUTION_COLUMNS);
+        assert!(round_data[1].num_columns < MEMORY_IV_EXTENDED_COLUMNS);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        let states = inputs.write_messages(&mut writer, &msgs);
+        write_digests(&mut writer, &hash_state, &states);
+        write_trace(&stark.air_data, &mut writer_data, num_rows);
+
+        prove_and_verify(&stark, writer_data, "test_blake2b_iv_constant_tables");
+    }
+
+    fn prove_personalized_digest(msg: &[u8], personalization: [u64; 2]) -> [u64; 8] {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        let num_rows = BLAKE2B::rows_for(1);
+        let msgs = [msg.to_vec()];
+
+        let mut builder = BytesBuilder::<L>::new();
+        let inputs = BLAKE2BInputs::alloc_messages(&mut builder, &msgs);
+        let personalization_words = builder.alloc_array_public::<U64Register>(2);
+        let hash_state = builder.blake2b_personalized::<BLAKE2B>(
+            &inputs.padded_chunks,
+            &inputs.t_values,
+            &inputs.end_bits,
+            &inputs.digest_bits,
+            &inputs.digest_indices,
+            &inputs.num_messages,
+            &personalization_words,
+        );
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+
+        writer.write_array(
+            &personalization_words,
+            personalization.iter().map(|x| u64_to_le_field_bytes(*x)),
+        );
+        inputs.write_messages(&mut writer, &msgs);
+
+        let mut state = IV;
+        state[6] ^= personalization[0];
+        state[7] ^= personalization[1];
+        let padded_msg = BLAKE2BUtil::pad(msg, 1);
+        BLAKE2B::compress(&padded_msg, &mut state, msg.len() as u64, true);
+        write_digests(&mut writer, &hash_state, &[state]);
+        write_trace(&stark.air_data, &mut writer_data, num_rows);
+
+        prove_and_verify(&stark, writer_data, "test_blake2b_personalized");
+
+        state
+    }
+
+    #[test]
+    fn test_blake2b_personalized() {
+        let msg = b"domain separated message";
+
+        let digest_a = prove_personalized_digest(msg, [0x6c6f636f746f7270, 0x0000000000000031]);
+        let digest_b = prove_personalized_digest(msg, [0x6c6f636f746f7270, 0x0000000000000032]);
+        assert_ne!(digest_a[0..4], digest_b[0..4]);
+
+        // The zero personalization is the plain BLAKE2b hash.
+        let digest = prove_personalized_digest(msg, [0, 0]);
+        let mut state = IV;
+        let padded_msg = BLAKE2BUtil::pad(msg, 1);
+        BLAKE2B::compress(&padded_msg, &mut state, msg.len() as u64, true);
+        assert_eq!(digest, state);
+    }
+
+    fn prove_compress_iv_digest(msg: &[u8], compress_iv: [u64; 8]) -> [u64; 8] {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        let num_rows = BLAKE2B::rows_for(1);
+        let msgs = [msg.to_vec()];
+
+        let mut builder = BytesBuilder::<L>::new();
+        let inputs = BLAKE2BInputs::alloc_messages(&mut builder, &msgs);
+        let hash_state = builder.blake2b_with_compress_iv::<BLAKE2B>(
+            &inputs.padded_chunks,
+            &inputs.t_values,
+            &inputs.end_bits,
+            &inputs.digest_bits,
+            &inputs.digest_indices,
+            &inputs.num_messages,
+            &compress_iv,
+        );
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        inputs.write_messages(&mut writer, &msgs);
+
+        let mut state = IV;
+        let padded_msg = BLAKE2BUtil::pad(msg, 1);
+        BLAKE2B::compress_with_iv(
+            &padded_msg,
+            &mut state,
+            msg.len() as u64,
+            true,
+            &compress_iv,
+        );
+        write_digests(&mut writer, &hash_state, &[state]);
+        write_trace(&stark.air_data, &mut writer_data, num_rows);
+
+        prove_and_verify(&stark, writer_data, "test_blake2b_compress_iv");
+
+        state
+    }
+
+    #[test]
+    fn test_blake2b_custom_compress_iv() {
+        let msg = b"variant test vector";
+
+        // The standard constants give the plain BLAKE2b hash.
+        let digest = prove_compress_iv_digest(msg, COMPRESS_IV);
+        let mut state = IV;
+        let padded_msg = BLAKE2BUtil::pad(msg, 1);
+        BLAKE2B::compress(&padded_msg, &mut state, msg.len() as u64, true);
+        assert_eq!(digest, state);
+
+        let mut compress_iv = COMPRESS_IV;
+        compress_iv[3] ^= 1;
+        let tweaked_digest = prove_compress_iv_digest(msg, compress_iv);
+        assert_ne!(tweaked_digest[0..4], digest[0..4]);
+    }
+
+    /// Proves a batch of `msgs` and checks that `previous_compress_id` is the dummy timestamp
+    /// exactly in the first compress of every message, and the id of the previous compress
+    /// otherwise.
+    fn prove_previous_compress_ids(msgs: &[Vec<u8>]) {
+        use crate::chip::register::Register;
+        use crate::machine::hash::blake::blake2b::air::DummyMemoryValues;
+
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        let num_compresses = msgs.iter().map(|msg| num_chunks(msg)).sum::<usize>();
+        let num_rows = BLAKE2B::rows_for(num_compresses);
+
+        let mut builder = BytesBuilder::<L>::new();
+        let inputs = BLAKE2BInputs::alloc_messages(&mut builder, msgs);
+        let data = <BLAKE2B as BLAKEAir<BytesBuilder<L>>>::blake2b_data(
+            &mut builder,
+            &inputs.padded_chunks,
+            &inputs.t_values,
+            &inputs.end_bits,
+            &inputs.digest_bits,
+            &inputs.digest_bits,
+            &inputs.digest_indices,
+            &inputs.num_messages,
+        );
+        let hash_state =
+            <BLAKE2B as BLAKEAir<BytesBuilder<L>>>::blake2b_digests(&mut builder, &data);
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        let states = inputs.write_messages(&mut writer, msgs);
+        write_digests(&mut writer, &hash_state, &states);
+        write_trace(&stark.air_data, &mut writer_data, num_rows);
+
+        let first_compresses = msgs
+            .iter()
+            .scan(0, |compress_id, msg| {
+                let first_compress = *compress_id;
+                *compress_id += num_chunks(msg);
+                Some(first_compress)
+            })
+            .collect::<Vec<_>>();
+        let to_field = |x: bool| GoldilocksField::from_canonical_usize(x as usize);
+        let first_compress_ts = GoldilocksField::from_canonical_u64(
+            DummyMemoryValues::new(num_compresses).first_compress_h_read_ts,
+        );
+        for row_index in 0..num_compresses * COMPRESS_LENGTH {
+            let row = writer_data.trace.row(row_index);
+            let compress_id = row_index / COMPRESS_LENGTH;
+            let at_first_compress = first_compresses.contains(&compress_id);
+            assert_eq!(
+                data.trace.at_first_compress.read_from_slice(row),
+                to_field(at_first_compress)
+            );
+            let expected_previous_id = if at_first_compress {
+                first_compress_ts
+            } else {
+                GoldilocksField::from_canonical_usize(compress_id - 1)
+            };
+            assert_eq!(
+                data.trace.previous_compress_id.read_from_slice(row),
+                expected_previous_id
+            );
+        }
+
+        prove_and_verify(&stark, writer_data, "test_blake2b_previous_compress_id");
+    }
+
+    #[test]
+    fn test_blake2b_previous_compress_id_single_compress() {
+        // The only compress of the trace is a first compress, where `compress_id - 1` wraps.
+        prove_previous_compress_ids(&[b"a single block message".to_vec()]);
+    }
+
+    #[test]
+    fn test_blake2b_previous_compress_id_multi_message() {
+        let msgs = [
+            (0..300).map(|i| (i * 11 + 5) as u8).collect::<Vec<_>>(),
+            (0..17).map(|i| (i * 13 + 1) as u8).collect::<Vec<_>>(),
+            (0..129).map(|i| (i * 3 + 7) as u8).collect::<Vec<_>>(),
+            (0..128).map(|i| i as u8).collect::<Vec<_>>(),
+        ];
+        prove_previous_compress_ids(&msgs);
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BLAKE2BMembershipTest;
+
+    impl AirParameters for BLAKE2BMembershipTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 1305;
+        const EXTENDED_COLUMNS: usize = 1509;
+    }
+
+    /// The first four words of the BLAKE2b state after hashing `msg`.
+    fn blake2b_digest_words(msg: &[u8]) -> [u64; 4] {
+        let num_chunks = num_chunks(msg);
+        let padded_msg = BLAKE2BUtil::pad(msg, num_chunks as u64);
+        let mut state = IV;
+        for (j, chunk) in padded_msg.chunks_exact(128).enumerate() {
+            let is_last = j == num_chunks - 1;
+            let t_value = if is_last {
+                msg.len() as u64
+            } else {
+                (128 * (j + 1)) as u64
+            };
+            BLAKE2B::compress(chunk, &mut state, t_value, is_last);
+        }
+        [state[0], state[1], state[2], state[3]]
+    }
+
+    /// Proves that the digests of `msgs` belong to the set of digests `set_digests`. The digest
+    /// of a message which is not in the set is counted in the multiplicity of the first entry.
+    fn prove_set_membership(msgs: &[Vec<u8>], set_digests: &[[u64; 4]]) {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BMembershipTest;
+
+        let num_compresses = msgs.iter().map(|msg| num_chunks(msg)).sum::<usize>();
+        let num_rows = BLAKE2B::rows_for(num_compresses);
+
+        let mut builder = BytesBuilder::<L>::new();
+        let inputs = BLAKE2BInputs::alloc_messages(&mut builder, msgs);
+        let set = (0..set_digests.len())
+            .map(|_| builder.alloc_public::<BLAKE2BDigestRegister>())
+            .collect::<Vec<_>>();
+        let multiplicities = builder.blake2b_set_membership::<BLAKE2B>(
+            &inputs.padded_chunks,
+            &inputs.t_values,
+            &inputs.end_bits,
+            &inputs.digest_bits,
+            &inputs.digest_indices,
+            &inputs.num_messages,
+            &set,
+        );
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        for (digest, words) in set.iter().zip_eq(set_digests.iter()) {
+            let array: ArrayRegister<_> = (*digest).into();
+            writer.write_array(&array, words.iter().map(|x| u64_to_le_field_bytes(*x)));
+        }
+        let states = inputs.write_messages(&mut writer, msgs);
+        write_trace(&stark.air_data, &mut writer_data, num_rows);
+
+        // The multiplicities are only read by the lookup, so they can be written last.
+        let mut set_multiplicities = vec![0; set_digests.len()];
+        for state in states.iter() {
+            let index = set_digests
+                .iter()
+                .position(|words| words[..] == state[0..4])
+                .unwrap_or(0);
+            set_multiplicities[index] += 1;
+        }
+        for mut chunk in writer_data.chunks(num_rows) {
+            chunk.window_writer(0).write_array(
+                &multiplicities,
+                set_multiplicities
+                    .iter()
+                    .map(|m| GoldilocksField::from_canonical_usize(*m)),
+            );
+        }
+
+        prove_and_verify(&stark, writer_data, "test_blake2b_set_membership");
+    }
+
+    #[test]
+    fn test_blake2b_set_membership() {
+        let set_msgs = [
+            b"abc".to_vec(),
+            (0..200).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>(),
+            b"".to_vec(),
+        ];
+        let set_digests = set_msgs
+            .iter()
+            .map(|msg| blake2b_digest_words(msg))
+            .collect::<Vec<_>>();
+        // The messages are proven members regardless of their order in the set, and an entry
+        // can be matched by several messages.
+        let msgs = [
+            set_msgs[1].clone(),
+            set_msgs[0].clone(),
+            set_msgs[1].clone(),
+        ];
+        prove_set_membership(&msgs, &set_digests);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_blake2b_set_membership_not_in_set() {
+        let set_digests = [b"abc".as_slice(), b"abd"].map(blake2b_digest_words);
+        let msgs = [b"abc".to_vec(), b"abe".to_vec()];
+        prove_set_membership(&msgs, &set_digests);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_blake2b_set_membership_forged_digest() {
+        // The only entry of the set differs from the digest of the message in a single bit.
+        let mut forged_digest = blake2b_digest_words(b"abc");
+        forged_digest[3] ^= 1;
+        prove_set_membership(&[b"abc".to_vec()], &[forged_digest]);
+    }
+
+    #[test]
+    fn test_blake2b_required_columns() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        let num_compresses = 4;
+        let num_rows = 1 << 9;
+
+        let budget = BLAKE2B::required_columns::<L>(num_compresses);
+        assert!(budget.fits::<L>());
+        assert_eq!(budget.num_arithmetic_columns, 0);
+
+        // Compare against the columns allocated when building the same machine.
+        let mut builder = BytesBuilder::<L>::new();
+        BLAKE2BInputs::alloc(&mut builder, num_compresses, num_compresses).blake2b(&mut builder);
+
+        let stark = builder.build::<C, 2>(num_rows);
+        let air = stark.stark().air();
+        assert_eq!(air.execution_trace_length, budget.num_free_columns);
+        assert_eq!(air.num_extended_columns, budget.extended_columns);
+    }
+
+    #[test]
+    fn test_blake2b_mix_cost() {
+        let cost = BLAKE2B::mix_cost::<BLAKE2BTest>();
+        assert_eq!(
+            cost.columns,
+            ColumnBudget {
+                num_arithmetic_columns: 0,
+                num_free_columns: 188,
+                extended_columns: 504,
+            }
+        );
+        assert_eq!(cost.num_byte_operations, 112);
+        assert!(cost.num_constraints > 0);
+    }
+
+    #[test]
+    fn test_blake2b_trace_length() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        let num_compresses = 4;
+        assert_eq!(BLAKE2B::rows_for(num_compresses), 512);
+        assert_eq!(BLAKE2B::rows_for(1), 128);
+
+        let mut builder = BytesBuilder::<L>::new();
+        assert_eq!(builder.trace_length(), None);
+        BLAKE2BInputs::alloc(&mut builder, num_compresses, num_compresses).blake2b(&mut builder);
+
+        let num_rows = builder.trace_length().unwrap();
+        assert_eq!(num_rows, BLAKE2B::rows_for(num_compresses));
+
+        let stark = builder.build::<C, 2>(num_rows);
+        let writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        assert_eq!(writer_data.trace.height(), num_rows);
+    }
+
+    #[test]
+    fn test_blake2b_unpadded_trace_length() {
+        type L = BLAKE2BTest;
+
+        let num_compresses = 3;
+        assert_eq!(
+            BLAKE2B::rows_for_policy(num_compresses, PaddingPolicy::None),
+            288
+        );
+        assert_eq!(
+            BLAKE2B::rows_for_policy(num_compresses, PaddingPolicy::Multiple(64)),
+            320
+        );
+
+        for policy in [PaddingPolicy::None, PaddingPolicy::Multiple(64)] {
+            let mut builder = BytesBuilder::<L>::new();
+            builder.set_padding_policy(policy);
+            BLAKE2BInputs::alloc(&mut builder, num_compresses, num_compresses)
+                .blake2b(&mut builder);
+
+            assert_eq!(
+                builder.trace_length(),
+                Some(BLAKE2B::rows_for_policy(num_compresses, policy))
+            );
+        }
+    }
+
+    #[test]
+    fn test_blake2b_unpadded_trace() {
+        type L = BLAKE2BTest;
+        type F = GoldilocksField;
+
+        // Without padding, the 3 compresses take 288 rows and the last cycle of 128 rows ends
+        // with an empty dummy compress.
+        let num_compresses = 3;
+        let num_rows = BLAKE2B::rows_for_policy(num_compresses, PaddingPolicy::None);
+        assert_eq!(num_rows, 288);
+        assert_eq!(num_rows % 128, 32);
+
+        let msg = (0..num_compresses * 128 - 5)
+            .map(|i| (i * 11 + 1) as u8)
+            .collect::<Vec<_>>();
+        assert_eq!(num_chunks(&msg), num_compresses);
+        let msgs = [msg];
+
+        let mut builder = BytesBuilder::<L>::new();
+        builder.set_padding_policy(PaddingPolicy::None);
+        let inputs = BLAKE2BInputs::alloc_messages(&mut builder, &msgs);
+        let hash_state = inputs.blake2b(&mut builder);
+        assert_eq!(builder.trace_length(), Some(num_rows));
+
+        let airs = builder.build_airs();
+
+        let mut writer_data = AirWriterData::new(&airs.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        let states = inputs.write_messages(&mut writer, &msgs);
+        assert_eq!(
+            states[0][0..4]
+                .iter()
+                .flat_map(|x| x.to_le_bytes())
+                .collect::<Vec<_>>(),
+            BLAKE2B::hash(&msgs[0])
+        );
+        write_digests(&mut writer, &hash_state, &states);
+        write_trace(&airs.air_data, &mut writer_data, num_rows);
+
+        let challenges = (0..airs.air.num_challenges)
+            .map(|i| F::from_canonical_u64(0x1234_5678_9abc_def0 + i as u64))
+            .collect::<Vec<_>>();
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        assert_eq!(trace.height(), num_rows);
+        let failures = airs.debug_constraints(&trace, &public, &challenges);
+        assert!(failures.is_empty(), "{failures:?}");
+    }
+
+    #[test]
+    fn test_blake2b_air_metadata() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        let num_compresses = 4;
+        let num_rows = BLAKE2B::rows_for(num_compresses);
+
+        let mut builder = BytesBuilder::<L>::new();
+        BLAKE2BInputs::alloc(&mut builder, num_compresses, num_compresses).blake2b(&mut builder);
+        let stark = builder.build::<C, 2>(num_rows);
+        let air = stark.stark().air();
+
+        let bytes = AirMetadata::new(air).to_bytes().unwrap();
+        let metadata = AirMetadata::from_bytes(&bytes).unwrap();
+        assert_eq!(metadata.version, AirMetadata::VERSION);
+        assert_eq!(metadata.round_data(), air.round_data());
+
+        // Reconstruct the trace layout of each round from the metadata alone.
+        let mut column_offset = 0;
+        for (datum, expected) in metadata.round_data().iter().zip(air.round_data()) {
+            assert_eq!(datum.num_columns, expected.num_columns);
+            column_offset += datum.num_columns;
+        }
+        assert_eq!(column_offset, air.num_columns());
+        assert_eq!(metadata.num_columns(), air.num_columns());
+        assert_eq!(metadata.num_global_values(), air.num_global_values());
+        assert_eq!(metadata.num_public_inputs(), air.num_public_inputs());
+        assert_eq!(
+            metadata.quotient_degree_factor(),
+            air.quotient_degree_factor()
+        );
+
+        // Metadata from an unknown version is rejected.
+        let mut unsupported = metadata.clone();
+        unsupported.version += 1;
+        assert!(AirMetadata::from_bytes(&unsupported.to_bytes().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_blake2b_dummy_memory_values() {
+        use crate::machine::hash::blake::blake2b::air::DummyMemoryValues;
+
+        // The largest batch has a trace of 2^30 rows, whose clock approaches the previous
+        // hardcoded dummy values of `i32::MAX`.
+        let max_num_compresses = (1 << 30) / 96;
+        for num_compresses in [1, 4, 1326, max_num_compresses] {
+            let num_rows = BLAKE2B::rows_for(num_compresses);
+            let num_total_compresses = num_rows / 96 + 1;
+            let max_real_ts = (num_rows - 1) as u64;
+            let max_real_index = (num_total_compresses * 16 - 1) as u64;
+
+            let dummy_values = DummyMemoryValues::new(num_compresses);
+            for value in [
+                dummy_values.index,
+                dummy_values.index_2,
+                dummy_values.ts,
+                dummy_values.first_compress_h_read_ts,
+            ] {
+                assert!(value > max_real_ts);
+                assert!(value > max_real_index);
+                assert!(value < 0xFFFF_FFFF_0000_0001);
+            }
+            assert_ne!(dummy_values.index, dummy_values.index_2);
+        }
+    }
+
+    /// The memory accesses of the trace of a BLAKE2B AIR hashing `msgs`.
+    fn blake2b_memory_accesses(msgs: &[Vec<u8>]) -> Vec<MemoryAccess> {
+        type L = BLAKE2BTest;
+
+        let num_compresses = msgs.iter().map(|msg| num_chunks(msg)).sum::<usize>();
+        let num_rows = BLAKE2B::rows_for(num_compresses);
+
+        let mut builder = BytesBuilder::<L>::new();
+        let inputs = BLAKE2BInputs::alloc_messages(&mut builder, msgs);
+        let data = <BLAKE2B as BLAKEAir<BytesBuilder<L>>>::blake2b_data(
+            &mut builder,
+            &inputs.padded_chunks,
+            &inputs.t_values,
+            &inputs.end_bits,
+            &inputs.digest_bits,
+            &inputs.digest_bits,
+            &inputs.digest_indices,
+            &inputs.num_messages,
+        );
+        let hash_state =
+            <BLAKE2B as BLAKEAir<BytesBuilder<L>>>::blake2b_digests(&mut builder, &data);
+
+        let airs = builder.build_airs();
+
+        let mut writer_data = AirWriterData::new(&airs.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        let states = inputs.write_messages(&mut writer, msgs);
+        write_digests(&mut writer, &hash_state, &states);
+        write_trace(&airs.air_data, &mut writer_data, num_rows);
+
+        memory_accesses(
+            &airs.air_data,
+            &mut writer_data,
+            num_rows,
+            &[data.consts.dummy_index, data.consts.dummy_index_2],
+        )
+    }
+
+    #[test]
+    fn test_blake2b_dummy_memory_values_multi_message() {
+        use crate::machine::hash::blake::blake2b::air::DummyMemoryValues;
+
+        let msgs = [
+            (0..300).map(|i| (i * 11 + 5) as u8).collect::<Vec<_>>(),
+            (0..17).map(|i| (i * 13 + 1) as u8).collect::<Vec<_>>(),
+            (0..129).map(|i| (i * 3 + 7) as u8).collect::<Vec<_>>(),
+            (0..128).map(|i| i as u8).collect::<Vec<_>>(),
+        ];
+        let num_rows = BLAKE2B::rows_for(msgs.iter().map(|msg| num_chunks(msg)).sum());
+        let accesses = blake2b_memory_accesses(&msgs);
+
+        let dummy_values = DummyMemoryValues::for_num_rows(num_rows);
+        let dummy_indices = [dummy_values.index, dummy_values.index_2];
+        let dummy_timestamps = [dummy_values.ts, dummy_values.first_compress_h_read_ts];
+        let max_real_value = DummyMemoryValues::max_real_value(num_rows, COMPRESS_LENGTH);
+        assert!(dummy_indices.iter().all(|value| *value > max_real_value));
+        assert!(dummy_timestamps.iter().all(|value| *value > max_real_value));
+        assert_ne!(dummy_values.index, dummy_values.index_2);
+
+        // No real access has a dummy index or timestamp.
+        let real_accesses = accesses.iter().filter(|access| !access.is_dummy);
+        for access in real_accesses {
+            assert!(access.index <= max_real_value, "{:?}", access);
+            assert!(access.ts <= max_real_value, "{:?}", access);
+        }
+
+        // The dummy reads read the dummy index, while the dummy writes outside the dummy slots
+        // write the second one and allow no read.
+        let dummy_accesses = accesses
+            .iter()
+            .filter(|access| access.is_dummy && !access.is_dummy_slot)
+            .collect::<Vec<_>>();
+        assert!(dummy_accesses.iter().any(|access| !access.is_write));
+        assert!(dummy_accesses.iter().any(|access| access.is_write));
+        for access in dummy_accesses.iter() {
+            if access.is_write {
+                assert_eq!(access.index, dummy_values.index_2, "{:?}", access);
+                assert_eq!(access.multiplicity, 0, "{:?}", access);
+            } else {
+                assert_eq!(access.index, dummy_values.index, "{:?}", access);
+            }
+        }
+
+        // Every dummy read is matched by the multiplicity of the dummy slot of its memory.
+        let labels = accesses
+            .iter()
+            .filter(|access| access.is_dummy_slot)
+            .map(|access| access.label.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(labels.len(), 5);
+        for label in labels {
+            let slot_accesses = accesses.iter().filter(|access| access.label == label);
+            let num_dummy_reads = slot_accesses
+                .clone()
+                .filter(|access| access.is_dummy && !access.is_write)
+                .count() as u64;
+            let slot_multiplicity = slot_accesses
+                .filter(|access| access.is_dummy_slot)
+                .map(|access| {
+                    assert!(access.is_write);
+                    assert_eq!(access.index, dummy_values.index);
+                    access.multiplicity
+                })
+                .sum::<u64>();
+            assert!(num_dummy_reads > 0, "{}", label);
+            assert_eq!(num_dummy_reads, slot_multiplicity, "{}", label);
+        }
+    }
+
+    #[test]
+    fn test_blake2b_v_final_alignment() {
+        let msgs = [0, 129, 300]
+            .iter()
+            .map(|len| (0..*len).map(|i| (i * 3 + 5) as u8).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        let accesses = blake2b_memory_accesses(&msgs);
+        let real_accesses = |label: &str, is_write: bool| {
+            accesses
+                .iter()
+                .filter(|access| access.label == label && access.is_write == is_write)
+                .filter(|access| !access.is_dummy && access.row.is_some())
+                .collect::<Vec<_>>()
+        };
+        let v_final_writes = real_accesses("v_final", true);
+        let v_final_reads = real_accesses("v_final", false);
+        let h_writes = real_accesses("h", true);
+        let h_reads = real_accesses("h", false);
+
+        let mut compress_id = 0;
+        for msg in msgs.iter() {
+            for chunk in 0..num_chunks(msg) {
+                let rows = compress_id * COMPRESS_LENGTH..(compress_id + 1) * COMPRESS_LENGTH;
+                let last_row = rows.end - 1;
+                let in_compress = |access: &&&MemoryAccess| rows.contains(&access.row.unwrap());
+
+                // The last four rows of the compress each write four values of v_final, covering
+                // every index once, with the id of the compress as timestamp.
+                let writes = v_final_writes
+                    .iter()
+                    .filter(in_compress)
+                    .collect::<Vec<_>>();
+                assert_eq!(writes.len(), 16);
+                for row in last_row - 3..=last_row {
+                    let row_writes = writes.iter().filter(|access| access.row == Some(row));
+                    assert_eq!(row_writes.count(), 4, "row {}", row);
+                }
+                assert!(writes.iter().all(|access| access.ts == compress_id as u64));
+                assert!(writes.iter().all(|access| access.multiplicity == 1));
+                let mut indices = writes.iter().map(|access| access.index).collect::<Vec<_>>();
+                indices.sort();
+                assert_eq!(indices, (0..16).collect::<Vec<_>>());
+
+                // The last row reads them all back, at the timestamp of the writes.
+                let reads = v_final_reads.iter().filter(in_compress).collect::<Vec<_>>();
+                assert_eq!(reads.len(), 16);
+                assert!(reads.iter().all(|access| access.row == Some(last_row)));
+                let mut read_keys = reads
+                    .iter()
+                    .map(|access| (access.index, access.ts))
+                    .collect::<Vec<_>>();
+                read_keys.sort();
+                let mut write_keys = writes
+                    .iter()
+                    .map(|access| (access.index, access.ts))
+                    .collect::<Vec<_>>();
+                write_keys.sort();
+                assert_eq!(read_keys, write_keys);
+
+                // The h written at the end of a compress is read twice by the next compress of
+                // the message, in its first four rows and in its last row.
+                let writes = h_writes.iter().filter(in_compress).collect::<Vec<_>>();
+                let is_end_compress = chunk == num_chunks(msg) - 1;
+                if is_end_compress {
+                    assert!(writes.is_empty());
+                } else {
+                    assert_eq!(writes.len(), 8);
+                    assert!(writes.iter().all(|access| access.row == Some(last_row)));
+                    assert!(writes.iter().all(|access| access.multiplicity == 2));
+                    for write in writes {
+                        let reads = h_reads
+                            .iter()
+                            .filter(|access| (access.index, access.ts) == (write.index, write.ts))
+                            .map(|access| access.row.unwrap())
+                            .collect::<Vec<_>>();
+                        assert_eq!(reads.len(), 2, "{:?}", write);
+                        assert!((rows.end..rows.end + 4).contains(&reads[0]), "{:?}", write);
+                        assert_eq!(reads[1], last_row + COMPRESS_LENGTH, "{:?}", write);
+                    }
+                }
+                compress_id += 1;
+            }
+        }
+        assert_eq!(v_final_writes.len(), 16 * compress_id);
+        assert_eq!(v_final_reads.len(), 16 * compress_id);
+        assert_eq!(h_reads.len(), 2 * h_writes.len());
+    }
 }
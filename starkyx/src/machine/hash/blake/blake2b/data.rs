@@ -1,93 +1,28 @@
-use super::{MIX_LENGTH, MSG_ARRAY_SIZE, NUM_MIX_ROUNDS};
+use super::BLAKE2B;
 use crate::chip::memory::instruction::MemorySliceIndex;
 use crate::chip::memory::pointer::slice::Slice;
 use crate::chip::memory::time::Time;
 use crate::chip::register::array::ArrayRegister;
-use crate::chip::register::bit::BitRegister;
 use crate::chip::register::element::ElementRegister;
-use crate::chip::uint::register::U64Register;
+use crate::chip::register::memory::MemorySlice;
+use crate::chip::register::{Register, RegisterSerializable};
 use crate::machine::builder::Builder;
+use crate::machine::hash::blake::blake2::data::BLAKE2Data;
 use crate::math::field::Field;
 
-pub struct BLAKE2BData<B: Builder> {
-    pub public: BLAKE2BPublicData,
-    pub trace: BLAKE2BTraceData,
-    pub memory: BLAKE2BMemory,
-    pub consts: BLAKE2BConsts<B>,
-    pub const_nums: BLAKE2BConstNums,
-}
-
-pub struct BLAKE2BPublicData {
-    pub padded_chunks: Vec<ArrayRegister<U64Register>>,
-    pub t_values: ArrayRegister<U64Register>,
-    pub end_bits: ArrayRegister<BitRegister>,
-    pub digest_indices: ArrayRegister<ElementRegister>,
-}
-
-pub struct BLAKE2BTraceData {
-    pub(crate) clk: ElementRegister,
-    pub(crate) is_compress_initialize: BitRegister,
-    pub(crate) is_compress_first_row: BitRegister,
-    pub(crate) is_compress_third_row: BitRegister,
-    pub(crate) is_compress_final_row: BitRegister,
-    pub(crate) is_compress_finalize: BitRegister,
-    pub(crate) is_digest_row: BitRegister,
-    pub(crate) at_first_compress: BitRegister,
-    pub(crate) at_digest_compress: BitRegister,
-    pub(crate) at_end_compress: BitRegister,
-    pub(crate) at_dummy_compress: BitRegister,
-    pub(crate) compress_id: ElementRegister,
-    pub(crate) previous_compress_id: ElementRegister,
-    pub(crate) compress_index: ElementRegister,
-    pub(crate) mix_id: ElementRegister,
-    pub(crate) mix_index: ElementRegister,
-}
-
-pub struct BLAKE2BMemory {
-    pub(crate) h: Slice<U64Register>,
-    pub(crate) v: Slice<U64Register>,
-    pub(crate) v_final: Slice<U64Register>,
-    pub(crate) m: Slice<U64Register>,
-    pub(crate) t: Slice<U64Register>,
-}
-
-pub struct BLAKE2BConsts<B: Builder> {
-    pub(crate) iv: Slice<U64Register>,
-    pub(crate) iv_values: ArrayRegister<U64Register>,
-    pub(crate) compress_iv: Slice<U64Register>,
-    pub(crate) v_indices: MemoryArray<B, MIX_LENGTH, 4>,
-    pub(crate) v_last_write_ages: MemoryArray<B, MIX_LENGTH, 4>,
-    pub(crate) permutations: MemoryArray<B, NUM_MIX_ROUNDS, MSG_ARRAY_SIZE>,
-    pub(crate) dummy_index: ElementRegister,
-    pub(crate) dummy_index_2: ElementRegister,
-    pub(crate) dummy_ts: ElementRegister,
-    pub(crate) first_compress_h_read_ts: ElementRegister,
-}
-
-pub struct BLAKE2BConstNums {
-    pub(crate) const_0: ElementRegister,
-    pub(crate) const_0_u64: U64Register,
-    pub(crate) const_1: ElementRegister,
-    pub(crate) const_2: ElementRegister,
-    pub(crate) const_3: ElementRegister,
-    pub(crate) const_4: ElementRegister,
-    pub(crate) const_8: ElementRegister,
-    pub(crate) const_10: ElementRegister,
-    pub(crate) const_12: ElementRegister,
-    pub(crate) const_16: ElementRegister,
-    pub(crate) const_91: ElementRegister,
-    pub(crate) const_96: ElementRegister,
-    pub(crate) const_ffffffffffffffff: U64Register,
-}
+/// The registers and memory of the BLAKE2b compresses.
+pub type BLAKE2BData<B> = BLAKE2Data<B, BLAKE2B>;
 
-pub(crate) struct MemoryArray<B: Builder, const R: usize, const C: usize> {
+/// A constant table of `R` rows and `C` columns stored in memory in row-major order, whose
+/// cells can be looked up at indices given by registers.
+pub struct MemoryArray<B: Builder, const R: usize, const C: usize> {
     pub flattened_memory: Slice<ElementRegister>,
     c_const: ElementRegister,
     _marker: std::marker::PhantomData<B>,
 }
 
 impl<B: Builder, const R: usize, const C: usize> MemoryArray<B, R, C> {
-    pub(crate) fn new(builder: &mut B) -> Self {
+    pub fn new(builder: &mut B) -> Self {
         Self {
             flattened_memory: builder.uninit_slice(),
             c_const: builder.constant(&B::Field::from_canonical_usize(C)),
@@ -95,7 +30,8 @@ impl<B: Builder, const R: usize, const C: usize> MemoryArray<B, R, C> {
         }
     }
 
-    pub(crate) fn store_row(
+    /// Stores `values` as the row `row` of the table, each cell with multiplicity `mul`.
+    pub fn store_row(
         &mut self,
         builder: &mut B,
         row: usize,
@@ -103,8 +39,13 @@ impl<B: Builder, const R: usize, const C: usize> MemoryArray<B, R, C> {
         mul: ElementRegister,
         label: Option<String>,
     ) {
-        assert_eq!(values.len(), C);
-        assert!(row < R);
+        assert_eq!(values.len(), C, "A row of the table must have {} values", C);
+        assert!(
+            row < R,
+            "Row {} out of bounds of a table of {} rows",
+            row,
+            R
+        );
 
         for (i, value) in values.iter().enumerate() {
             let value_const = builder.constant(&B::Field::from_canonical_u8(*value));
@@ -119,7 +60,11 @@ impl<B: Builder, const R: usize, const C: usize> MemoryArray<B, R, C> {
         }
     }
 
-    pub(crate) fn get_at(
+    /// Reads the cell at row `row` and column `col` of the table.
+    ///
+    /// An index out of bounds of the table reads a cell that is never stored, so it fails the
+    /// memory argument.
+    pub fn get_at(
         &self,
         builder: &mut B,
         row: ElementRegister,
@@ -136,4 +81,132 @@ impl<B: Builder, const R: usize, const C: usize> MemoryArray<B, R, C> {
             Some(MemorySliceIndex::IndexElement(idx)),
         )
     }
+
+    /// Reads all the cells of the row `row` of the table.
+    pub fn get_row(
+        &self,
+        builder: &mut B,
+        row: ElementRegister,
+        label: Option<String>,
+    ) -> ArrayRegister<ElementRegister> {
+        let start = builder.mul(row, self.c_const);
+        let indices = (0..C)
+            .map(|col| match col {
+                0 => start,
+                _ => builder.expression(start.expr() + B::Field::from_canonical_usize(col)),
+            })
+            .collect::<Vec<_>>();
+
+        // The values are read with nothing allocated in between, so that they are contiguous.
+        let values = indices
+            .into_iter()
+            .map(|idx| {
+                builder.load(
+                    &self.flattened_memory.get_at(idx),
+                    &Time::zero(),
+                    label.clone(),
+                    Some(MemorySliceIndex::IndexElement(idx)),
+                )
+            })
+            .collect::<Vec<_>>();
+        let first_column = values[0].register().index();
+        for (col, value) in values.iter().enumerate() {
+            assert_eq!(
+                *value.register(),
+                MemorySlice::Local(first_column + col, 1),
+                "The cells of a row must be read into contiguous columns"
+            );
+        }
+
+        ArrayRegister::from_register_unsafe(MemorySlice::Local(first_column, C))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::instruction::empty::EmptyInstruction;
+    use crate::chip::trace::writer::{InnerWriterData, TraceWriter};
+    use crate::chip::AirParameters;
+    use crate::machine::stark::builder::StarkBuilder;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct MemoryArrayTest;
+
+    impl AirParameters for MemoryArrayTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 16;
+        const EXTENDED_COLUMNS: usize = 48;
+    }
+
+    #[test]
+    fn test_memory_array_8x4() {
+        type F = GoldilocksField;
+        type L = MemoryArrayTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        const NUM_TABLE_ROWS: usize = 8;
+        const NUM_TABLE_COLUMNS: usize = 4;
+        let table = |row: usize, col: usize| (10 * row + col) as u8;
+
+        let num_rows = 1 << 8;
+        // Every cell is read once by `get_at` in every 32 rows, and its row is read by
+        // `get_row` in every 8 rows.
+        let num_reads = num_rows / (NUM_TABLE_ROWS * NUM_TABLE_COLUMNS) + num_rows / NUM_TABLE_ROWS;
+
+        let mut builder = StarkBuilder::<L>::new();
+        let mut array =
+            MemoryArray::<StarkBuilder<L>, NUM_TABLE_ROWS, NUM_TABLE_COLUMNS>::new(&mut builder);
+        let mul = builder.constant(&F::from_canonical_usize(num_reads));
+        for row in 0..NUM_TABLE_ROWS {
+            let values = (0..NUM_TABLE_COLUMNS)
+                .map(|col| table(row, col))
+                .collect::<Vec<_>>();
+            array.store_row(&mut builder, row, &values, mul, None);
+        }
+
+        let row = builder.alloc::<ElementRegister>();
+        let col = builder.alloc::<ElementRegister>();
+        let cell = array.get_at(&mut builder, row, col, None);
+        let row_values = array.get_row(&mut builder, row, None);
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let writer = TraceWriter::new(&stark.air_data, num_rows);
+        writer.write_global_instructions(&stark.air_data);
+        for i in 0..num_rows {
+            let (row_val, col_val) = (
+                i / NUM_TABLE_COLUMNS % NUM_TABLE_ROWS,
+                i % NUM_TABLE_COLUMNS,
+            );
+            writer.write(&row, &F::from_canonical_usize(row_val), i);
+            writer.write(&col, &F::from_canonical_usize(col_val), i);
+            writer.write_row_instructions(&stark.air_data, i);
+
+            assert_eq!(
+                writer.read(&cell, i),
+                F::from_canonical_u8(table(row_val, col_val))
+            );
+            for (j, value) in row_values.iter().enumerate() {
+                assert_eq!(
+                    writer.read(&value, i),
+                    F::from_canonical_u8(table(row_val, j))
+                );
+            }
+        }
+
+        let InnerWriterData { trace, public, .. } = writer.into_inner().unwrap();
+        let mut timing = TimingTree::new("test_memory_array_8x4", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
 }
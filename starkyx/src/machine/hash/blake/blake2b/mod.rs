@@ -1,49 +1,32 @@
 use serde::{Deserialize, Serialize};
 
 pub mod air;
+pub mod block_size;
 pub mod builder;
 pub mod data;
+pub mod padding;
+pub mod preimage;
 pub mod pure;
 pub mod register;
+pub mod segment;
+pub mod sim;
+pub mod units;
 pub mod utils;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct BLAKE2B;
 
-const NUM_MIX_ROUNDS: usize = 12;
-const MIX_LENGTH: usize = 8;
-const MSG_ARRAY_SIZE: usize = 16;
-const STATE_SIZE: usize = 8;
+pub(crate) const NUM_MIX_ROUNDS: usize = 12;
+pub(crate) const MIX_LENGTH: usize = 8;
+pub(crate) const MSG_ARRAY_SIZE: usize = 16;
+pub(crate) const STATE_SIZE: usize = 8;
 const WORK_VECTOR_SIZE: usize = 16;
 const COMPRESS_LENGTH: usize = MIX_LENGTH * NUM_MIX_ROUNDS;
 
-pub const IV: [u64; STATE_SIZE] = [
-    0x6a09e667f2bdc928,
-    0xbb67ae8584caa73b,
-    0x3c6ef372fe94f82b,
-    0xa54ff53a5f1d36f1,
-    0x510e527fade682d1,
-    0x9b05688c2b3e6c1f,
-    0x1f83d9abfb41bd6b,
-    0x5be0cd19137e2179,
-];
-
-// Note that for this blake2b implementation, we don't support a key input and
-// we assume that the output is 32 bytes
-// So that means the initial hash entry to be
-// 0x6a09e667f3bcc908 xor 0x01010020
-const COMPRESS_IV: [u64; STATE_SIZE] = [
-    0x6a09e667f3bcc908,
-    0xbb67ae8584caa73b,
-    0x3c6ef372fe94f82b,
-    0xa54ff53a5f1d36f1,
-    0x510e527fade682d1,
-    0x9b05688c2b3e6c1f,
-    0x1f83d9abfb41bd6b,
-    0x5be0cd19137e2179,
-];
+pub(crate) use crate::utils::blake2b::SIGMA_PERMUTATIONS;
+pub use crate::utils::blake2b::{COMPRESS_IV, IV};
 
-const V_INDICES: [[u8; 4]; MIX_LENGTH] = [
+pub(crate) const V_INDICES: [[u8; 4]; MIX_LENGTH] = [
     [0, 4, 8, 12],
     [1, 5, 9, 13],
     [2, 6, 10, 14],
@@ -54,7 +37,7 @@ const V_INDICES: [[u8; 4]; MIX_LENGTH] = [
     [3, 4, 9, 14],
 ];
 
-const V_LAST_WRITE_AGES: [[u8; 4]; MIX_LENGTH] = [
+pub(crate) const V_LAST_WRITE_AGES: [[u8; 4]; MIX_LENGTH] = [
     [4, 1, 2, 3],
     [4, 5, 2, 3],
     [4, 5, 6, 3],
@@ -65,17 +48,66 @@ const V_LAST_WRITE_AGES: [[u8; 4]; MIX_LENGTH] = [
     [4, 7, 6, 5],
 ];
 
-const SIGMA_PERMUTATIONS: [[u8; MSG_ARRAY_SIZE]; NUM_MIX_ROUNDS] = [
-    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
-    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
-    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
-    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
-    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
-    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
-    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
-    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
-    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
-    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
-    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
-    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
-];
+// Check at compile time that every row of `SIGMA_PERMUTATIONS` is a permutation of `0..16`.
+const _: () = assert!(
+    are_permutations(&SIGMA_PERMUTATIONS),
+    "SIGMA_PERMUTATIONS rows must be permutations of 0..16"
+);
+
+/// Returns `true` if every row of `table` is a permutation of `0..MSG_ARRAY_SIZE`.
+const fn are_permutations(table: &[[u8; MSG_ARRAY_SIZE]; NUM_MIX_ROUNDS]) -> bool {
+    let mut i = 0;
+    while i < NUM_MIX_ROUNDS {
+        let mut seen = [false; MSG_ARRAY_SIZE];
+        let mut j = 0;
+        while j < MSG_ARRAY_SIZE {
+            let index = table[i][j] as usize;
+            if index >= MSG_ARRAY_SIZE || seen[index] {
+                return false;
+            }
+            seen[index] = true;
+            j += 1;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The message schedule `SIGMA` of RFC 7693, section 2.7. Rounds 10 and 11 reuse the first
+    /// two rows.
+    const RFC_7693_SIGMA: [[u8; MSG_ARRAY_SIZE]; 10] = [
+        [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+        [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+        [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+        [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+        [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+        [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+        [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+        [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+        [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+        [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    ];
+
+    #[test]
+    fn test_sigma_permutations() {
+        for (i, permutation) in SIGMA_PERMUTATIONS.iter().enumerate() {
+            let mut seen = [false; MSG_ARRAY_SIZE];
+            for &index in permutation.iter() {
+                let index = index as usize;
+                assert!(index < MSG_ARRAY_SIZE, "round {i} has out of range index");
+                assert!(!seen[index], "round {i} has duplicate index {index}");
+                seen[index] = true;
+            }
+            assert_eq!(*permutation, RFC_7693_SIGMA[i % 10], "round {i}");
+        }
+        assert!(are_permutations(&SIGMA_PERMUTATIONS));
+
+        let mut bad_table = SIGMA_PERMUTATIONS;
+        bad_table[3][5] = bad_table[3][6];
+        assert!(!are_permutations(&bad_table));
+    }
+}
@@ -0,0 +1,256 @@
+use super::units::{Bytes, BLOCK_SIZE};
+use super::utils::BLAKE2BUtil;
+use super::{BLAKE2B, MSG_ARRAY_SIZE};
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::trace::writer::AirWriter;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
+use crate::chip::uint::util::u64_to_le_field_bytes;
+use crate::chip::AirParameters;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::math::prelude::*;
+use crate::prelude::Builder;
+
+/// The public input values of a batch of messages, each padded to whole blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BLAKE2BPaddedValues {
+    /// The padded blocks of all the messages, one after the other.
+    pub padded_chunks: Vec<u8>,
+    /// The byte counter of every compress, which is the length of the message in the last
+    /// compress of the message.
+    pub t_values: Vec<u64>,
+    /// Whether every compress is the last of its message.
+    pub end_bits: Vec<bool>,
+    /// The index of the last compress of every message.
+    pub digest_indices: Vec<usize>,
+}
+
+impl BLAKE2B {
+    /// Pads every message of `messages` to its number of blocks, and computes the byte counters
+    /// and end bits of the compresses.
+    ///
+    /// An empty message takes a single zero block with a byte counter of zero, and a message
+    /// whose length is a multiple of the block size takes no extra block.
+    pub fn pad_values(messages: &[Vec<u8>]) -> BLAKE2BPaddedValues {
+        let mut values = BLAKE2BPaddedValues {
+            padded_chunks: Vec::new(),
+            t_values: Vec::new(),
+            end_bits: Vec::new(),
+            digest_indices: Vec::new(),
+        };
+        for msg in messages {
+            let num_blocks = Bytes(msg.len()).blocks().0;
+            values
+                .padded_chunks
+                .extend(BLAKE2BUtil::pad(msg, num_blocks as u64));
+            for i in 0..num_blocks {
+                let is_last = i == num_blocks - 1;
+                values.t_values.push(if is_last {
+                    msg.len() as u64
+                } else {
+                    ((i + 1) * BLOCK_SIZE) as u64
+                });
+                values.end_bits.push(is_last);
+            }
+            values.digest_indices.push(values.end_bits.len() - 1);
+        }
+        values
+    }
+
+    /// Allocates the public inputs of the BLAKE2b machine hashing `messages`, to be given to
+    /// `BLAKEAir::blake2b` and written by `PaddedMessages::write`.
+    pub fn pad_messages<L: AirParameters>(
+        builder: &mut BytesBuilder<L>,
+        messages: &[Vec<u8>],
+    ) -> PaddedMessages
+    where
+        L::Instruction: UintInstructions,
+    {
+        let values = Self::pad_values(messages);
+        let num_compresses = values.end_bits.len();
+
+        PaddedMessages {
+            padded_chunks: (0..num_compresses)
+                .map(|_| builder.alloc_array_public::<U64Register>(MSG_ARRAY_SIZE))
+                .collect(),
+            t_values: builder.alloc_array_public::<U64Register>(num_compresses),
+            end_bits: builder.alloc_array_public::<BitRegister>(num_compresses),
+            digest_bits: builder.alloc_array_public::<BitRegister>(num_compresses),
+            digest_indices: builder.alloc_array_public::<ElementRegister>(messages.len()),
+            num_messages: builder.alloc_public::<ElementRegister>(),
+            values,
+        }
+    }
+}
+
+/// The public registers of a batch of padded messages, together with their values.
+///
+/// The digest of every message is emitted at its last compress, so `digest_bits` holds the same
+/// values as `end_bits`.
+#[derive(Debug, Clone)]
+pub struct PaddedMessages {
+    pub padded_chunks: Vec<ArrayRegister<U64Register>>,
+    pub t_values: ArrayRegister<U64Register>,
+    pub end_bits: ArrayRegister<BitRegister>,
+    pub digest_bits: ArrayRegister<BitRegister>,
+    pub digest_indices: ArrayRegister<ElementRegister>,
+    pub num_messages: ElementRegister,
+    pub values: BLAKE2BPaddedValues,
+}
+
+impl PaddedMessages {
+    /// The number of compresses hashing the messages.
+    pub fn num_compresses(&self) -> usize {
+        self.padded_chunks.len()
+    }
+
+    /// Writes the public inputs of the messages.
+    pub fn write<W: AirWriter>(&self, writer: &mut W) {
+        let to_field = |x: bool| W::Field::from_canonical_usize(x as usize);
+
+        for (i, (chunk, block)) in self
+            .padded_chunks
+            .iter()
+            .zip(self.values.padded_chunks.chunks_exact(BLOCK_SIZE))
+            .enumerate()
+        {
+            writer.write_array(
+                chunk,
+                block.chunks_exact(8).map(|word| {
+                    <[u8; 8]>::try_from(word)
+                        .unwrap()
+                        .map(W::Field::from_canonical_u8)
+                }),
+            );
+            writer.write(
+                &self.t_values.get(i),
+                &u64_to_le_field_bytes(self.values.t_values[i]),
+            );
+            writer.write(&self.end_bits.get(i), &to_field(self.values.end_bits[i]));
+            writer.write(&self.digest_bits.get(i), &to_field(self.values.end_bits[i]));
+        }
+        for (i, index) in self.values.digest_indices.iter().enumerate() {
+            writer.write(
+                &self.digest_indices.get(i),
+                &W::Field::from_canonical_usize(*index),
+            );
+        }
+        writer.write(
+            &self.num_messages,
+            &W::Field::from_canonical_usize(self.values.digest_indices.len()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+
+    use super::*;
+    use crate::machine::hash::blake::blake2b::builder::test_utils::BLAKE2BTest;
+    use crate::machine::hash::blake::blake2b::builder::BlakeBuilder;
+    use crate::machine::hash::blake::blake2b::pure::BLAKE2BPure;
+    use crate::machine::hash::blake::blake2b::IV;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::prelude::AirWriterData;
+
+    #[test]
+    fn test_blake2b_pad_values() {
+        let messages = vec![
+            vec![],
+            vec![1u8; 128],
+            vec![2u8; 129],
+            vec![3u8; 256],
+            vec![4u8; 3],
+        ];
+        let values = BLAKE2B::pad_values(&messages);
+
+        assert_eq!(values.padded_chunks.len(), 7 * BLOCK_SIZE);
+        assert_eq!(values.t_values, [0, 128, 128, 129, 128, 256, 3]);
+        assert_eq!(
+            values.end_bits,
+            [true, true, false, true, false, true, true]
+        );
+        assert_eq!(values.digest_indices, [0, 1, 3, 5, 6]);
+        // The empty message is a single zero block.
+        assert!(values.padded_chunks[..BLOCK_SIZE].iter().all(|b| *b == 0));
+
+        // Compressing the padded blocks gives the digest of every message.
+        let mut state = IV;
+        let mut message_index = 0;
+        for (i, block) in values.padded_chunks.chunks_exact(BLOCK_SIZE).enumerate() {
+            let is_last = values.end_bits[i];
+            BLAKE2B::compress(block, &mut state, values.t_values[i], is_last);
+            if is_last {
+                let mut digest = [0u8; 32];
+                for (bytes, word) in digest.chunks_exact_mut(8).zip(state) {
+                    bytes.copy_from_slice(&word.to_le_bytes());
+                }
+                assert_eq!(digest, BLAKE2B::hash(&messages[message_index]));
+                assert_eq!(values.digest_indices[message_index], i);
+                message_index += 1;
+                state = IV;
+            }
+        }
+        assert_eq!(message_index, messages.len());
+    }
+
+    #[test]
+    fn test_blake2b_pad_messages() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BTest;
+
+        let messages = vec![
+            vec![],
+            (0..256).map(|i| (i * 3 + 1) as u8).collect::<Vec<_>>(),
+            (0..150).map(|i| (i * 5 + 2) as u8).collect::<Vec<_>>(),
+        ];
+
+        let mut builder = BytesBuilder::<L>::new();
+        let padded = BLAKE2B::pad_messages(&mut builder, &messages);
+        assert_eq!(padded.num_compresses(), 5);
+        let digests = builder.blake2b::<BLAKE2B>(
+            &padded.padded_chunks,
+            &padded.t_values,
+            &padded.end_bits,
+            &padded.digest_bits,
+            &padded.digest_indices,
+            &padded.num_messages,
+        );
+        assert_eq!(digests.len(), messages.len());
+
+        let num_rows = BLAKE2B::rows_for(padded.num_compresses());
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        padded.write(&mut writer);
+        for (digest, msg) in digests.iter().zip(messages.iter()) {
+            let array: ArrayRegister<_> = (*digest).into();
+            writer.write_array(
+                &array,
+                BLAKE2B::hash(msg).chunks_exact(8).map(|word| {
+                    <[u8; 8]>::try_from(word)
+                        .unwrap()
+                        .map(GoldilocksField::from_canonical_u8)
+                }),
+            );
+        }
+
+        stark.air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new("test_blake2b_pad_messages", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+}
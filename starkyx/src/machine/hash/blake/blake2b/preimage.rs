@@ -0,0 +1,143 @@
+use super::units::{Bytes, BLOCK_SIZE};
+use super::utils::BLAKE2BUtil;
+use super::COMPRESS_LENGTH;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::trace::writer::AirWriter;
+use crate::chip::uint::register::U64Register;
+use crate::math::prelude::*;
+
+/// The private message of `BLAKEAir::prove_preimage`, whose length is fixed when building the
+/// AIR.
+#[derive(Debug, Clone, Copy)]
+pub struct PreimageMessage {
+    /// The trace register holding the padded block of every compress, in all of its rows.
+    pub block: ArrayRegister<U64Register>,
+    /// The length of the message in bytes.
+    pub message_len: usize,
+}
+
+impl PreimageMessage {
+    /// The number of blocks, and so of compresses, of the padded message.
+    pub fn num_blocks(&self) -> usize {
+        Bytes(self.message_len).blocks().0
+    }
+
+    /// Pads `message` to the blocks written by `write_row`.
+    pub fn pad(&self, message: &[u8]) -> Vec<u8> {
+        assert_eq!(
+            message.len(),
+            self.message_len,
+            "The message must be {} bytes long",
+            self.message_len
+        );
+        BLAKE2BUtil::pad(message, self.num_blocks() as u64)
+    }
+
+    /// Writes the block of the current row of `padded_message`, as given by `pad`. This is to be
+    /// called in every row of the trace, before writing the trace instructions.
+    ///
+    /// The block of a compress is stored from its first row, but written in all of its rows, so
+    /// that the trace values agree with the memory of the writer.
+    pub fn write_row<W: AirWriter>(&self, writer: &mut W, padded_message: &[u8]) {
+        let row_index = writer.row_index().expect("A row writer is needed");
+        // The rows of the dummy compresses don't store their block.
+        if let Some(block) = padded_message
+            .chunks_exact(BLOCK_SIZE)
+            .nth(row_index / COMPRESS_LENGTH)
+        {
+            writer.write_array(
+                &self.block,
+                block.chunks_exact(8).map(|word| {
+                    <[u8; 8]>::try_from(word)
+                        .unwrap()
+                        .map(W::Field::from_canonical_u8)
+                }),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::u64_to_le_field_bytes;
+    use crate::chip::AirParameters;
+    use crate::machine::bytes::builder::BytesBuilder;
+    use crate::machine::hash::blake::blake2b::builder::BlakeBuilder;
+    use crate::machine::hash::blake::blake2b::pure::BLAKE2BPure;
+    use crate::machine::hash::blake::blake2b::BLAKE2B;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::prelude::{AirWriterData, Builder};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BLAKE2BPreimageTest;
+
+    impl AirParameters for BLAKE2BPreimageTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 1420;
+        const EXTENDED_COLUMNS: usize = 1560;
+    }
+
+    /// Proves the knowledge of `message` as a preimage of the digest of `digest_message`.
+    fn prove_preimage(message: &[u8], digest_message: &[u8]) {
+        type F = GoldilocksField;
+        type L = BLAKE2BPreimageTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let expected_digest = builder.alloc_array_public::<U64Register>(4);
+        let preimage = builder.prove_preimage::<BLAKE2B>(&expected_digest, message.len());
+
+        let num_rows = BLAKE2B::rows_for(preimage.num_blocks());
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        writer.write_array(
+            &expected_digest,
+            BLAKE2B::hash(digest_message).chunks_exact(8).map(|word| {
+                u64_to_le_field_bytes::<F>(u64::from_le_bytes(word.try_into().unwrap()))
+            }),
+        );
+        stark.air_data.write_global_instructions(&mut writer);
+
+        let padded_message = preimage.pad(message);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                preimage.write_row(&mut writer, &padded_message);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new("test_blake2b_preimage", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_blake2b_preimage() {
+        let message = (0..200).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>();
+        prove_preimage(&message, &message);
+        prove_preimage(b"", b"");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_blake2b_wrong_preimage() {
+        let message = (0..200).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>();
+        let mut wrong_message = message.clone();
+        wrong_message[150] ^= 1;
+        prove_preimage(&wrong_message, &message);
+    }
+}
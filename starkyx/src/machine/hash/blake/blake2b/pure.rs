@@ -1,6 +1,6 @@
 use super::{BLAKE2B, COMPRESS_IV, STATE_SIZE, WORK_VECTOR_SIZE};
-use crate::machine::hash::blake::blake2b::SIGMA_PERMUTATIONS;
 use crate::machine::hash::HashPureInteger;
+use crate::utils::blake2b;
 
 impl HashPureInteger for BLAKE2B {
     type Integer = u64;
@@ -14,6 +14,16 @@ pub trait BLAKE2BPure: HashPureInteger {
         last_chunk: bool,
     ) -> [Self::Integer; STATE_SIZE];
 
+    /// Compresses like `compress`, with `compress_iv` in place of the standard BLAKE2b IV in the
+    /// second half of the work vector.
+    fn compress_with_iv(
+        msg_chunk: &[u8],
+        state: &mut [Self::Integer; STATE_SIZE],
+        bytes_compressed: u64,
+        last_chunk: bool,
+        compress_iv: &[Self::Integer; STATE_SIZE],
+    ) -> [Self::Integer; STATE_SIZE];
+
     fn mix(
         v: &mut [Self::Integer; WORK_VECTOR_SIZE],
         a: usize,
@@ -32,107 +42,17 @@ impl BLAKE2BPure for BLAKE2B {
         bytes_compressed: u64,
         last_chunk: bool,
     ) -> [Self::Integer; STATE_SIZE] {
-        // Set up the work vector V
-        let mut v: [Self::Integer; WORK_VECTOR_SIZE] = [0; WORK_VECTOR_SIZE];
-
-        v[..8].copy_from_slice(&state[..STATE_SIZE]);
-        v[8..16].copy_from_slice(&COMPRESS_IV);
-
-        v[12] ^= bytes_compressed;
-        if last_chunk {
-            v[14] ^= 0xFFFFFFFFFFFFFFFF;
-        }
-
-        let msg_u64_chunks = msg_chunk
-            .chunks_exact(8)
-            .map(|x| Self::Integer::from_le_bytes(x.try_into().unwrap()))
-            .collect::<Vec<_>>();
-
-        for s in SIGMA_PERMUTATIONS.iter() {
-            Self::mix(
-                &mut v,
-                0,
-                4,
-                8,
-                12,
-                msg_u64_chunks[s[0] as usize],
-                msg_u64_chunks[s[1] as usize],
-            );
-            Self::mix(
-                &mut v,
-                1,
-                5,
-                9,
-                13,
-                msg_u64_chunks[s[2] as usize],
-                msg_u64_chunks[s[3] as usize],
-            );
-            Self::mix(
-                &mut v,
-                2,
-                6,
-                10,
-                14,
-                msg_u64_chunks[s[4] as usize],
-                msg_u64_chunks[s[5] as usize],
-            );
-            Self::mix(
-                &mut v,
-                3,
-                7,
-                11,
-                15,
-                msg_u64_chunks[s[6] as usize],
-                msg_u64_chunks[s[7] as usize],
-            );
-
-            Self::mix(
-                &mut v,
-                0,
-                5,
-                10,
-                15,
-                msg_u64_chunks[s[8] as usize],
-                msg_u64_chunks[s[9] as usize],
-            );
-            Self::mix(
-                &mut v,
-                1,
-                6,
-                11,
-                12,
-                msg_u64_chunks[s[10] as usize],
-                msg_u64_chunks[s[11] as usize],
-            );
-            Self::mix(
-                &mut v,
-                2,
-                7,
-                8,
-                13,
-                msg_u64_chunks[s[12] as usize],
-                msg_u64_chunks[s[13] as usize],
-            );
-            Self::mix(
-                &mut v,
-                3,
-                4,
-                9,
-                14,
-                msg_u64_chunks[s[14] as usize],
-                msg_u64_chunks[s[15] as usize],
-            );
-        }
-
-        for i in 0..STATE_SIZE {
-            state[i] ^= v[i];
-        }
-
-        for i in 0..STATE_SIZE {
-            state[i] ^= v[i + 8];
-        }
+        Self::compress_with_iv(msg_chunk, state, bytes_compressed, last_chunk, &COMPRESS_IV)
+    }
 
-        *state
+    fn compress_with_iv(
+        msg_chunk: &[u8],
+        state: &mut [Self::Integer; STATE_SIZE],
+        bytes_compressed: u64,
+        last_chunk: bool,
+        compress_iv: &[Self::Integer; STATE_SIZE],
+    ) -> [Self::Integer; STATE_SIZE] {
+        blake2b::compress(msg_chunk, state, bytes_compressed, last_chunk, compress_iv)
     }
 
     fn mix(
@@ -144,13 +64,39 @@ impl BLAKE2BPure for BLAKE2B {
         x: Self::Integer,
         y: Self::Integer,
     ) {
-        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
-        v[d] = (v[d] ^ v[a]).rotate_right(32);
-        v[c] = v[c].wrapping_add(v[d]);
-        v[b] = (v[b] ^ v[c]).rotate_right(24);
-        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
-        v[d] = (v[d] ^ v[a]).rotate_right(16);
-        v[c] = v[c].wrapping_add(v[d]);
-        v[b] = (v[b] ^ v[c]).rotate_right(63);
+        blake2b::mix(v, a, b, c, d, x, y)
+    }
+}
+
+impl BLAKE2B {
+    /// The unkeyed BLAKE2b digest of `msg`, with a 32-byte output.
+    pub fn hash(msg: &[u8]) -> [u8; 32] {
+        blake2b::hash(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blake2b_hash() {
+        let expected = [
+            (
+                b"".to_vec(),
+                "0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8",
+            ),
+            (
+                b"abc".to_vec(),
+                "bddd813c634239723171ef3fee98579b94964e3bb1cb3e427262c8c068d52319",
+            ),
+            (
+                (0..200).map(|i| i as u8).collect(),
+                "63c3d97a9f8894d5e043a707b0fee7f7ec4c049a23bbf1079df20b4165f9e22d",
+            ),
+        ];
+        for (msg, digest) in expected {
+            assert_eq!(BLAKE2B::hash(&msg).to_vec(), hex::decode(digest).unwrap());
+        }
     }
 }
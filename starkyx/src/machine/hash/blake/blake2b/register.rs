@@ -1,10 +1,18 @@
 use serde::{Deserialize, Serialize};
 
+use crate::chip::builder::AirBuilder;
+use crate::chip::memory::pointer::raw::RawPointer;
+use crate::chip::memory::time::Time;
+use crate::chip::memory::value::MemoryValue;
 use crate::chip::register::array::{ArrayIterator, ArrayRegister};
 use crate::chip::register::cell::CellType;
+use crate::chip::register::cubic::CubicRegister;
 use crate::chip::register::memory::MemorySlice;
 use crate::chip::register::{Register, RegisterSerializable, RegisterSized};
-use crate::chip::uint::register::U64Register;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::register::{compress_bytes, num_word_challenges, U64Register};
+use crate::chip::AirParameters;
+use crate::math::prelude::*;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct BLAKE2BDigestRegister(ArrayRegister<U64Register>);
@@ -39,6 +47,25 @@ impl Register for BLAKE2BDigestRegister {
     }
 }
 
+/// A digest is compressed to a single cubic element by packing its bytes into words and
+/// combining the words and the time with the challenges of the memory slice.
+impl MemoryValue for BLAKE2BDigestRegister {
+    fn num_challenges<F: PrimeField64>() -> usize {
+        num_word_challenges::<F>(32)
+    }
+
+    fn compress<L: AirParameters>(
+        &self,
+        builder: &mut AirBuilder<L>,
+        ptr: RawPointer,
+        time: &Time<L::Field>,
+        challenges: &ArrayRegister<CubicRegister>,
+    ) -> CubicRegister {
+        let bytes = ArrayRegister::<ByteRegister>::from_register_unsafe(*self.register());
+        compress_bytes(&bytes, self.is_trace(), builder, ptr, time, challenges)
+    }
+}
+
 impl BLAKE2BDigestRegister {
     pub fn as_array(&self) -> ArrayRegister<U64Register> {
         self.0
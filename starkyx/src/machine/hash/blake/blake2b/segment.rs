@@ -0,0 +1,494 @@
+use anyhow::{ensure, Result};
+
+use super::builder::BlakeBuilder;
+use super::pure::BLAKE2BPure;
+use super::units::{Bytes, BLOCK_SIZE};
+use super::utils::BLAKE2BUtil;
+use super::{BLAKE2B, IV, MSG_ARRAY_SIZE, STATE_SIZE};
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::trace::writer::AirWriter;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
+use crate::chip::uint::util::{u64_from_le_field_bytes, u64_to_le_field_bytes};
+use crate::chip::AirParameters;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::math::prelude::*;
+use crate::prelude::Builder;
+
+/// The state chained between the segments of a message: the running `h` words and the number of
+/// bytes compressed so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainingState {
+    pub h: [u64; STATE_SIZE],
+    pub t: u64,
+}
+
+impl ChainingState {
+    /// The state before the first segment of a message.
+    pub const fn initial() -> Self {
+        Self { h: IV, t: 0 }
+    }
+
+    /// The digest of a message whose final segment ended in this state, truncated to the 32 bytes
+    /// of output set in the parameter block of the IV.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(8).zip(self.h) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+
+    /// Moves to the state emitted by the segment of the `k`-th proof, which starts in the state
+    /// `initial_h` and whose compresses before its digest have the byte counters `t_values`.
+    ///
+    /// The segment must start in this state and compress the blocks following those of the
+    /// previous segments: every block but the last one of the message counts a full block.
+    fn chain(
+        &mut self,
+        k: usize,
+        initial_h: &[u64; STATE_SIZE],
+        t_values: &[u64],
+        is_final: bool,
+        next_h: [u64; STATE_SIZE],
+    ) -> Result<()> {
+        ensure!(
+            *initial_h == self.h,
+            "Segment {} does not start from the state of the previous segment",
+            k
+        );
+        for (i, &t) in t_values.iter().enumerate() {
+            if is_final && i == t_values.len() - 1 {
+                ensure!(
+                    (self.t < t || (self.t == 0 && t == 0)) && t <= self.t + BLOCK_SIZE as u64,
+                    "The last block of segment {} does not follow the previous blocks",
+                    k
+                );
+            } else {
+                ensure!(
+                    t == self.t + BLOCK_SIZE as u64,
+                    "Block {} of segment {} does not follow the previous blocks",
+                    i,
+                    k
+                );
+            }
+            self.t = t;
+        }
+        self.h = next_h;
+        Ok(())
+    }
+}
+
+/// The public inputs of the proof of one segment of a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BLAKE2BSegment {
+    /// The padded blocks of the segment. The final segment is padded with zero blocks up to the
+    /// number of blocks of every segment, which are compressed after the digest and ignored.
+    pub padded_chunks: Vec<u8>,
+    /// The byte counter of every compress, including the bytes of the previous segments.
+    pub t_values: Vec<u64>,
+    /// The index of the compress whose state is emitted.
+    pub digest_index: usize,
+    /// Whether this is the last segment, whose digest compress applies the finalization flag.
+    pub is_final: bool,
+    /// The state left by the previous segment.
+    pub initial: ChainingState,
+    /// The state emitted by this segment.
+    pub next: ChainingState,
+}
+
+impl BLAKE2B {
+    /// Splits `msg` into segments of `blocks_per_segment` blocks, each to be proven by the AIR of
+    /// `BLAKE2BSegmentAir` with the state left by the previous segment as a public input.
+    pub fn segments(msg: &[u8], blocks_per_segment: usize) -> Vec<BLAKE2BSegment> {
+        assert!(
+            blocks_per_segment > 0,
+            "A segment must have at least one block"
+        );
+        let num_blocks = Bytes(msg.len()).blocks().0;
+        let padded_msg = BLAKE2BUtil::pad(msg, num_blocks as u64);
+
+        let mut state = ChainingState::initial();
+        let segment_size = blocks_per_segment * BLOCK_SIZE;
+        padded_msg
+            .chunks(segment_size)
+            .enumerate()
+            .map(|(k, blocks)| {
+                let num_segment_blocks = blocks.len() / BLOCK_SIZE;
+                let is_final = k * blocks_per_segment + num_segment_blocks == num_blocks;
+
+                let initial = state;
+                let mut t_values = Vec::with_capacity(blocks_per_segment);
+                for (i, block) in blocks.chunks_exact(BLOCK_SIZE).enumerate() {
+                    let is_last = is_final && i == num_segment_blocks - 1;
+                    state.t = if is_last {
+                        msg.len() as u64
+                    } else {
+                        state.t + BLOCK_SIZE as u64
+                    };
+                    Self::compress(block, &mut state.h, state.t, is_last);
+                    t_values.push(state.t);
+                }
+                t_values.resize(blocks_per_segment, state.t);
+
+                let mut padded_chunks = blocks.to_vec();
+                padded_chunks.resize(segment_size, 0);
+
+                BLAKE2BSegment {
+                    padded_chunks,
+                    t_values,
+                    digest_index: num_segment_blocks - 1,
+                    is_final,
+                    initial,
+                    next: state,
+                }
+            })
+            .collect()
+    }
+
+    /// Checks that every segment starts from the state of the previous one and continues its byte
+    /// counter, and returns the digest of the message given by the final segment.
+    ///
+    /// This only checks the segments on the host, the proofs are chained by
+    /// `BLAKE2BSegmentAir::chain_public_inputs`.
+    pub fn chain_segments(segments: &[BLAKE2BSegment]) -> Result<[u8; 32]> {
+        ensure!(!segments.is_empty(), "A message has at least one segment");
+        let mut state = ChainingState::initial();
+        for (k, segment) in segments.iter().enumerate() {
+            ensure!(
+                segment.is_final == (k == segments.len() - 1),
+                "Only the last segment must be final"
+            );
+            ensure!(
+                segment.initial.t == state.t,
+                "Segment {} does not start from the byte counter of the previous segment",
+                k
+            );
+            state.chain(
+                k,
+                &segment.initial.h,
+                &segment.t_values[..=segment.digest_index],
+                segment.is_final,
+                segment.next.h,
+            )?;
+            ensure!(
+                state == segment.next,
+                "Segment {} does not end in its emitted state",
+                k
+            );
+        }
+        Ok(state.digest())
+    }
+}
+
+/// The public registers of an AIR proving one segment of `num_blocks` blocks.
+///
+/// The state emitted by a segment is given by its public `next_state` and the byte counter of its
+/// digest compress.
+#[derive(Debug, Clone)]
+pub struct BLAKE2BSegmentAir {
+    pub padded_chunks: Vec<ArrayRegister<U64Register>>,
+    pub t_values: ArrayRegister<U64Register>,
+    pub end_bits: ArrayRegister<BitRegister>,
+    pub digest_bits: ArrayRegister<BitRegister>,
+    pub final_bits: ArrayRegister<BitRegister>,
+    pub digest_indices: ArrayRegister<ElementRegister>,
+    pub num_messages: ElementRegister,
+    pub initial_state: ArrayRegister<U64Register>,
+    pub next_state: ArrayRegister<U64Register>,
+}
+
+impl BLAKE2BSegmentAir {
+    pub fn new<L: AirParameters>(builder: &mut BytesBuilder<L>, num_blocks: usize) -> Self
+    where
+        L::Instruction: UintInstructions,
+    {
+        let padded_chunks = (0..num_blocks)
+            .map(|_| builder.alloc_array_public::<U64Register>(MSG_ARRAY_SIZE))
+            .collect::<Vec<_>>();
+        let t_values = builder.alloc_array_public::<U64Register>(num_blocks);
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_blocks);
+        let digest_bits = builder.alloc_array_public::<BitRegister>(num_blocks);
+        let final_bits = builder.alloc_array_public::<BitRegister>(num_blocks);
+        let digest_indices = builder.alloc_array_public::<ElementRegister>(1);
+        let num_messages = builder.alloc_public::<ElementRegister>();
+        let initial_state = builder.alloc_array_public::<U64Register>(STATE_SIZE);
+        let states = builder.blake2b_segment::<BLAKE2B>(
+            &padded_chunks,
+            &t_values,
+            &end_bits,
+            &digest_bits,
+            &final_bits,
+            &digest_indices,
+            &num_messages,
+            &initial_state,
+        );
+
+        Self {
+            padded_chunks,
+            t_values,
+            end_bits,
+            digest_bits,
+            final_bits,
+            digest_indices,
+            num_messages,
+            initial_state,
+            next_state: states[0],
+        }
+    }
+
+    /// Checks that the proofs of consecutive segments of a message, given by their public inputs
+    /// in order, chain into the proof of the whole message, and returns its digest.
+    ///
+    /// Every segment must start from the state emitted by the previous one, continue its byte
+    /// counter, and compress all its blocks unless it is the final segment, whose digest compress
+    /// alone applies the finalization flag.
+    pub fn chain_public_inputs<F: PrimeField64>(
+        &self,
+        public_inputs: &[Vec<F>],
+    ) -> Result<[u8; 32]> {
+        ensure!(
+            !public_inputs.is_empty(),
+            "A message has at least one segment"
+        );
+        let num_blocks = self.padded_chunks.len();
+        let mut state = ChainingState::initial();
+        for (k, public) in public_inputs.iter().enumerate() {
+            let is_final = k == public_inputs.len() - 1;
+            ensure!(
+                self.num_messages.read_from_slice(public) == F::ONE,
+                "Segment {} does not prove a single message",
+                k
+            );
+            let digest_index = self
+                .digest_indices
+                .get(0)
+                .read_from_slice(public)
+                .as_canonical_u64();
+            ensure!(
+                digest_index < num_blocks as u64
+                    && (is_final || digest_index == num_blocks as u64 - 1),
+                "Segment {} does not emit the state of its last block",
+                k
+            );
+            let digest_index = digest_index as usize;
+
+            let flag = |x: bool| F::from_canonical_usize(x as usize);
+            for i in 0..num_blocks {
+                ensure!(
+                    self.end_bits.get(i).read_from_slice(public) == flag(i == num_blocks - 1)
+                        && self.digest_bits.get(i).read_from_slice(public)
+                            == flag(i == digest_index)
+                        && self.final_bits.get(i).read_from_slice(public)
+                            == flag(is_final && i == digest_index),
+                    "The flags of block {} of segment {} do not match its digest index",
+                    i,
+                    k
+                );
+            }
+
+            let read_word = |register: U64Register| -> Result<u64> {
+                let bytes = register.read_from_slice(public);
+                ensure!(
+                    bytes.iter().all(|byte| byte.as_canonical_u64() < 256),
+                    "A public word of segment {} is not given by bytes",
+                    k
+                );
+                Ok(u64_from_le_field_bytes(&bytes))
+            };
+            let read_state = |array: &ArrayRegister<U64Register>| -> Result<[u64; STATE_SIZE]> {
+                let mut words = [0u64; STATE_SIZE];
+                for (i, word) in words.iter_mut().enumerate() {
+                    *word = read_word(array.get(i))?;
+                }
+                Ok(words)
+            };
+            let t_values = (0..=digest_index)
+                .map(|i| read_word(self.t_values.get(i)))
+                .collect::<Result<Vec<_>>>()?;
+            state.chain(
+                k,
+                &read_state(&self.initial_state)?,
+                &t_values,
+                is_final,
+                read_state(&self.next_state)?,
+            )?;
+        }
+        Ok(state.digest())
+    }
+
+    /// Writes the public inputs of `segment`.
+    pub fn write<W: AirWriter>(&self, writer: &mut W, segment: &BLAKE2BSegment) {
+        let num_blocks = self.padded_chunks.len();
+        assert_eq!(segment.t_values.len(), num_blocks);
+        let to_field = |x: bool| W::Field::from_canonical_usize(x as usize);
+
+        for (i, (chunk, block)) in self
+            .padded_chunks
+            .iter()
+            .zip(segment.padded_chunks.chunks_exact(BLOCK_SIZE))
+            .enumerate()
+        {
+            writer.write_array(
+                chunk,
+                block.chunks_exact(8).map(|word| {
+                    <[u8; 8]>::try_from(word)
+                        .unwrap()
+                        .map(W::Field::from_canonical_u8)
+                }),
+            );
+            writer.write(
+                &self.t_values.get(i),
+                &u64_to_le_field_bytes(segment.t_values[i]),
+            );
+            writer.write(&self.end_bits.get(i), &to_field(i == num_blocks - 1));
+            writer.write(
+                &self.digest_bits.get(i),
+                &to_field(i == segment.digest_index),
+            );
+            writer.write(
+                &self.final_bits.get(i),
+                &to_field(segment.is_final && i == segment.digest_index),
+            );
+        }
+        writer.write(
+            &self.digest_indices.get(0),
+            &W::Field::from_canonical_usize(segment.digest_index),
+        );
+        writer.write(&self.num_messages, &W::Field::ONE);
+        writer.write_array(
+            &self.initial_state,
+            segment.initial.h.map(u64_to_le_field_bytes::<W::Field>),
+        );
+        writer.write_array(
+            &self.next_state,
+            segment.next.h.map(u64_to_le_field_bytes::<W::Field>),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::prelude::AirWriterData;
+
+    /// The parameters of the segment AIR, which takes more extended columns than `BLAKE2BTest`.
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BLAKE2BSegmentTest;
+
+    impl AirParameters for BLAKE2BSegmentTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 1270;
+        const EXTENDED_COLUMNS: usize = 1482;
+    }
+
+    #[test]
+    fn test_blake2b_segments_host() {
+        for msg_len in [0, 1, 128, 129, 640, 700, 1000] {
+            let msg = (0..msg_len).map(|i| (i * 13 + 5) as u8).collect::<Vec<_>>();
+            for blocks_per_segment in [1, 2, 3] {
+                let segments = BLAKE2B::segments(&msg, blocks_per_segment);
+                assert_eq!(
+                    BLAKE2B::chain_segments(&segments).unwrap(),
+                    BLAKE2B::hash(&msg)
+                );
+                assert!(segments
+                    .iter()
+                    .all(|s| s.padded_chunks.len() == blocks_per_segment * BLOCK_SIZE));
+
+                if segments.len() > 1 {
+                    let mut swapped = segments.clone();
+                    swapped.swap(0, 1);
+                    assert!(BLAKE2B::chain_segments(&swapped).is_err());
+
+                    let mut skipped = segments.clone();
+                    skipped[1].t_values[0] += BLOCK_SIZE as u64;
+                    assert!(BLAKE2B::chain_segments(&skipped).is_err());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_blake2b_two_segments() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = BLAKE2BSegmentTest;
+
+        // A 5 block message proven in two segments of 3 blocks, the last of which is padded with
+        // a block after the digest.
+        let blocks_per_segment = 3;
+        let msg = (0..600).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>();
+        let segments = BLAKE2B::segments(&msg, blocks_per_segment);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[1].digest_index, 1);
+
+        let num_rows = BLAKE2B::rows_for(blocks_per_segment);
+        let mut builder = BytesBuilder::<L>::new();
+        let segment_air = BLAKE2BSegmentAir::new(&mut builder, blocks_per_segment);
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let public_inputs = |segment: &BLAKE2BSegment| {
+            let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+            segment_air.write(&mut writer_data.public_writer(), segment);
+            writer_data.public
+        };
+
+        let mut proven_inputs = Vec::new();
+        for segment in segments.iter() {
+            let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+            let mut writer = writer_data.public_writer();
+            segment_air.write(&mut writer, segment);
+
+            stark.air_data.write_global_instructions(&mut writer);
+            for mut chunk in writer_data.chunks(num_rows) {
+                for i in 0..num_rows {
+                    let mut writer = chunk.window_writer(i);
+                    stark.air_data.write_trace_instructions(&mut writer);
+                }
+            }
+
+            let (trace, public) = (writer_data.trace, writer_data.public);
+            let mut timing = TimingTree::new("test_blake2b_two_segments", log::Level::Debug);
+            let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+            stark.verify(proof, &public).unwrap();
+            proven_inputs.push(public);
+        }
+
+        assert_eq!(
+            segment_air.chain_public_inputs(&proven_inputs).unwrap(),
+            BLAKE2B::hash(&msg)
+        );
+
+        // Proofs of the segments out of order don't chain.
+        let mut swapped = proven_inputs.clone();
+        swapped.swap(0, 1);
+        assert!(segment_air.chain_public_inputs(&swapped).is_err());
+
+        // Neither does a second segment whose byte counters skip a block, even though its state
+        // starts from the first segment.
+        let mut skipped = segments[1].clone();
+        for t in skipped.t_values.iter_mut() {
+            *t += BLOCK_SIZE as u64;
+        }
+        let skipped_inputs = [proven_inputs[0].clone(), public_inputs(&skipped)];
+        assert!(segment_air.chain_public_inputs(&skipped_inputs).is_err());
+
+        // Nor a first segment alone, which is not final.
+        assert!(segment_air
+            .chain_public_inputs(&proven_inputs[..1])
+            .is_err());
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashMap;
+
+use super::pure::BLAKE2BPure;
+use super::utils::BLAKE2BUtil;
+use super::{
+    BLAKE2B, COMPRESS_IV, COMPRESS_LENGTH, IV, MIX_LENGTH, MSG_ARRAY_SIZE, SIGMA_PERMUTATIONS,
+    STATE_SIZE, V_INDICES, V_LAST_WRITE_AGES,
+};
+
+/// The memory slices used by the BLAKE2B AIR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryKind {
+    H,
+    V,
+    VFinal,
+    M,
+    T,
+}
+
+/// The kind of a memory access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryOp {
+    Read,
+    Write { multiplicity: u32 },
+}
+
+/// A single access to the memory of the BLAKE2B AIR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoryAccess {
+    /// The row of the trace performing the access.
+    pub row: usize,
+    pub op: MemoryOp,
+    pub memory: MemoryKind,
+    pub index: usize,
+    pub ts: usize,
+    pub value: u64,
+}
+
+/// A pure simulation of the memory accesses of the BLAKE2B AIR.
+///
+/// The simulation performs the same `h`, `v`, `v_final`, `m` and `t` reads and writes as the
+/// rows of the AIR, with the same indices and timestamps and in the order in which the AIR
+/// registers them within a row. The reads and writes to the dummy index are not simulated, and
+/// neither are the initial writes of the message chunks and the `t` values, which are done before
+/// the first row.
+///
+/// Every read is checked against the last value written at its index, and panics if the
+/// timestamps do not match.
+#[derive(Debug, Clone)]
+pub struct BLAKE2BMemorySim {
+    accesses: Vec<MemoryAccess>,
+    states: Vec<[u64; STATE_SIZE]>,
+    memory: HashMap<(MemoryKind, usize), (usize, u64)>,
+}
+
+impl BLAKE2BMemorySim {
+    /// Simulates the compresses of the padded chunks with the given `t` values, end bits and
+    /// final bits, as laid out in the public inputs of the AIR.
+    pub fn new(
+        padded_chunks: &[&[u8]],
+        t_values: &[u64],
+        end_bits: &[bool],
+        final_bits: &[bool],
+    ) -> Self {
+        let num_compresses = padded_chunks.len();
+        assert_eq!(t_values.len(), num_compresses);
+        assert_eq!(end_bits.len(), num_compresses);
+        assert_eq!(final_bits.len(), num_compresses);
+
+        let mut sim = Self {
+            accesses: Vec::new(),
+            states: Vec::with_capacity(num_compresses),
+            memory: HashMap::new(),
+        };
+
+        for (compress_id, (chunk, t)) in padded_chunks.iter().zip(t_values.iter()).enumerate() {
+            assert_eq!(chunk.len(), MSG_ARRAY_SIZE * 8);
+            for (j, word) in chunk.chunks_exact(8).enumerate() {
+                let word = u64::from_le_bytes(word.try_into().unwrap());
+                sim.memory
+                    .insert((MemoryKind::M, compress_id * MSG_ARRAY_SIZE + j), (0, word));
+            }
+            sim.memory.insert((MemoryKind::T, compress_id), (0, *t));
+        }
+
+        for compress_id in 0..num_compresses {
+            let at_first_compress = compress_id == 0 || end_bits[compress_id - 1];
+            sim.compress(
+                compress_id,
+                at_first_compress,
+                end_bits[compress_id],
+                final_bits[compress_id],
+            );
+        }
+
+        sim
+    }
+
+    /// Simulates the hashes of `msgs`, with one digest per message at its last chunk.
+    pub fn from_messages(msgs: &[&[u8]]) -> Self {
+        let mut padded_chunks = Vec::new();
+        let mut t_values = Vec::new();
+        let mut end_bits = Vec::new();
+        for msg in msgs {
+            let num_chunks = ((msg.len() + 127) / 128).max(1);
+            let padded_msg = BLAKE2BUtil::pad(msg, num_chunks as u64);
+            for (j, chunk) in padded_msg.chunks_exact(128).enumerate() {
+                let is_last = j == num_chunks - 1;
+                padded_chunks.push(chunk.to_vec());
+                t_values.push(if is_last {
+                    msg.len() as u64
+                } else {
+                    (128 * (j + 1)) as u64
+                });
+                end_bits.push(is_last);
+            }
+        }
+        let padded_chunks = padded_chunks.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        Self::new(&padded_chunks, &t_values, &end_bits, &end_bits)
+    }
+
+    /// The memory accesses of the AIR, in order.
+    pub fn accesses(&self) -> &[MemoryAccess] {
+        &self.accesses
+    }
+
+    /// The state after every compress.
+    pub fn states(&self) -> &[[u64; STATE_SIZE]] {
+        &self.states
+    }
+
+    fn read(&mut self, row: usize, memory: MemoryKind, index: usize, ts: usize) -> u64 {
+        let (write_ts, value) = *self
+            .memory
+            .get(&(memory, index))
+            .unwrap_or_else(|| panic!("Read of unwritten {:?}[{}] at row {}", memory, index, row));
+        assert_eq!(
+            write_ts, ts,
+            "Timestamp mismatch reading {:?}[{}] at row {}",
+            memory, index, row
+        );
+        self.accesses.push(MemoryAccess {
+            row,
+            op: MemoryOp::Read,
+            memory,
+            index,
+            ts,
+            value,
+        });
+        value
+    }
+
+    fn write(
+        &mut self,
+        row: usize,
+        memory: MemoryKind,
+        index: usize,
+        ts: usize,
+        value: u64,
+        multiplicity: u32,
+    ) {
+        self.memory.insert((memory, index), (ts, value));
+        self.accesses.push(MemoryAccess {
+            row,
+            op: MemoryOp::Write { multiplicity },
+            memory,
+            index,
+            ts,
+            value,
+        });
+    }
+
+    fn compress(
+        &mut self,
+        compress_id: usize,
+        at_first_compress: bool,
+        at_end_compress: bool,
+        at_final_compress: bool,
+    ) {
+        let previous_compress_id = compress_id.wrapping_sub(1);
+        for compress_index in 0..COMPRESS_LENGTH {
+            let clk = compress_id * COMPRESS_LENGTH + compress_index;
+            let mix_index = compress_index % MIX_LENGTH;
+            let mix_id = compress_index / MIX_LENGTH;
+            let v_indices = V_INDICES[mix_index].map(|i| i as usize);
+
+            // Initialize the work vector from h and the compress iv in the first 4 rows, and
+            // read it from v in the other rows.
+            let mut v = [0u64; 4];
+            if compress_index < 4 {
+                if at_first_compress {
+                    v[0] = IV[compress_index];
+                    v[1] = IV[compress_index + 4];
+                } else {
+                    let h_idx = previous_compress_id * STATE_SIZE + compress_index;
+                    v[0] = self.read(clk, MemoryKind::H, h_idx, 0);
+                    v[1] = self.read(clk, MemoryKind::H, h_idx + 4, 0);
+                }
+                v[2] = COMPRESS_IV[compress_index];
+                v[3] = COMPRESS_IV[compress_index + 4];
+            } else {
+                for (k, (index, age)) in v_indices
+                    .iter()
+                    .zip(V_LAST_WRITE_AGES[mix_index].iter())
+                    .enumerate()
+                {
+                    v[k] = self.read(clk, MemoryKind::V, *index, clk - *age as usize);
+                }
+            }
+
+            let t = self.read(clk, MemoryKind::T, compress_id, 0);
+            if compress_index == 0 {
+                v[3] ^= t;
+            }
+            if compress_index == 2 && at_final_compress {
+                v[3] ^= 0xFFFFFFFFFFFFFFFF;
+            }
+
+            let permutation = SIGMA_PERMUTATIONS[mix_id];
+            let m_idx_1 = compress_id * MSG_ARRAY_SIZE + permutation[2 * mix_index] as usize;
+            let m_idx_2 = compress_id * MSG_ARRAY_SIZE + permutation[2 * mix_index + 1] as usize;
+            let m_1 = self.read(clk, MemoryKind::M, m_idx_1, 0);
+            let m_2 = self.read(clk, MemoryKind::M, m_idx_2, 0);
+
+            let mut work_vector = [0u64; 16];
+            work_vector[..4].copy_from_slice(&v);
+            BLAKE2B::mix(&mut work_vector, 0, 1, 2, 3, m_1, m_2);
+
+            let is_compress_finalize = compress_index >= COMPRESS_LENGTH - 4;
+            for (index, value) in v_indices.iter().zip(work_vector[..4].iter()) {
+                if is_compress_finalize {
+                    self.write(clk, MemoryKind::VFinal, *index, compress_id, *value, 1);
+                } else {
+                    self.write(clk, MemoryKind::V, *index, clk, *value, 1);
+                }
+            }
+
+            if compress_index == COMPRESS_LENGTH - 1 {
+                let mut h = [0u64; STATE_SIZE];
+                for (i, h_value) in h.iter_mut().enumerate() {
+                    *h_value = if at_first_compress {
+                        IV[i]
+                    } else {
+                        let h_idx = previous_compress_id * STATE_SIZE + i;
+                        self.read(clk, MemoryKind::H, h_idx, 0)
+                    };
+                }
+                for (i, h_value) in h.iter_mut().enumerate() {
+                    *h_value ^= self.read(clk, MemoryKind::VFinal, i, compress_id);
+                }
+                for (i, h_value) in h.iter_mut().enumerate() {
+                    *h_value ^= self.read(clk, MemoryKind::VFinal, i + 8, compress_id);
+                    // The h values are read twice by the next compress, in its first 4 rows and
+                    // in its last row.
+                    if !at_end_compress {
+                        let h_idx = compress_id * STATE_SIZE + i;
+                        self.write(clk, MemoryKind::H, h_idx, 0, *h_value, 2);
+                    }
+                }
+                self.states.push(h);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The states after every compress of `msgs`, computed with `BLAKE2BPure`.
+    fn pure_states(msgs: &[&[u8]]) -> Vec<[u64; STATE_SIZE]> {
+        let mut states = Vec::new();
+        for msg in msgs {
+            let num_chunks = ((msg.len() + 127) / 128).max(1);
+            let padded_msg = BLAKE2BUtil::pad(msg, num_chunks as u64);
+            let mut state = IV;
+            for (j, chunk) in padded_msg.chunks_exact(128).enumerate() {
+                let is_last = j == num_chunks - 1;
+                let t = if is_last {
+                    msg.len() as u64
+                } else {
+                    128 * (j as u64 + 1)
+                };
+                states.push(BLAKE2B::compress(chunk, &mut state, t, is_last));
+            }
+        }
+        states
+    }
+
+    #[test]
+    fn test_blake2b_memory_sim() {
+        let msgs: Vec<Vec<u8>> = [0, 3, 128, 129, 300]
+            .iter()
+            .map(|len| (0..*len).map(|i| (i * 7 + 1) as u8).collect())
+            .collect();
+        let msgs = msgs.iter().map(Vec::as_slice).collect::<Vec<_>>();
+
+        for msg in msgs.iter() {
+            let sim = BLAKE2BMemorySim::from_messages(&[msg]);
+            assert_eq!(sim.states(), pure_states(&[msg]));
+        }
+
+        let sim = BLAKE2BMemorySim::from_messages(&msgs);
+        assert_eq!(sim.states(), pure_states(&msgs));
+
+        // Every write to v is read exactly once, by a later row of the same compress.
+        let accesses = sim.accesses();
+        let count = |memory, op: fn(&MemoryOp) -> bool| {
+            accesses
+                .iter()
+                .filter(|a| a.memory == memory && op(&a.op))
+                .count()
+        };
+        let is_read = |op: &MemoryOp| *op == MemoryOp::Read;
+        let is_write = |op: &MemoryOp| matches!(op, MemoryOp::Write { .. });
+        let num_compresses = sim.states().len();
+        assert_eq!(count(MemoryKind::V, is_read), num_compresses * 4 * 92);
+        assert_eq!(count(MemoryKind::V, is_write), num_compresses * 4 * 92);
+        assert_eq!(count(MemoryKind::VFinal, is_read), num_compresses * 16);
+        assert_eq!(count(MemoryKind::M, is_read), num_compresses * 192);
+        assert_eq!(count(MemoryKind::T, is_read), num_compresses * 96);
+        assert!(accesses.windows(2).all(|w| w[0].row <= w[1].row));
+
+        // The h reads of a compress are for the state written by the previous compress.
+        let h_writes = count(MemoryKind::H, is_write);
+        assert_eq!(h_writes, (num_compresses - msgs.len()) * STATE_SIZE);
+        assert_eq!(count(MemoryKind::H, is_read), 2 * h_writes);
+    }
+}
@@ -0,0 +1,152 @@
+//! Counts of the units the BLAKE2b machine is laid out in.
+//!
+//! A message of `Bytes` is padded to `Blocks` of 128 bytes, each hashed by one of the
+//! `Compresses` of the trace, which in turn take `NUM_MIX_ROUNDS` `MixIterations` of
+//! `MIX_LENGTH` rows. The wrappers keep the counts apart, and convert between them only
+//! explicitly.
+
+use core::ops::{Add, Sub};
+
+use super::{COMPRESS_LENGTH, MIX_LENGTH, NUM_MIX_ROUNDS};
+
+/// The number of bytes of a BLAKE2b block.
+pub const BLOCK_SIZE: usize = 128;
+
+/// A number of message bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Bytes(pub usize);
+
+/// A number of 128-byte message blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Blocks(pub usize);
+
+/// A number of compresses of the trace, real or dummy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Compresses(pub usize);
+
+/// A number of mix iterations, each of which applies the 8 mixes of a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct MixIterations(pub usize);
+
+impl Bytes {
+    /// The number of blocks the message is padded to. An empty message still takes a block.
+    pub const fn blocks(self) -> Blocks {
+        if self.0 == 0 {
+            Blocks(1)
+        } else {
+            Blocks((self.0 + BLOCK_SIZE - 1) / BLOCK_SIZE)
+        }
+    }
+}
+
+impl Blocks {
+    /// The number of bytes of the padded blocks.
+    pub const fn bytes(self) -> Bytes {
+        Bytes(self.0 * BLOCK_SIZE)
+    }
+
+    /// The number of compresses hashing the blocks, one for every block.
+    pub const fn compresses(self) -> Compresses {
+        Compresses(self.0)
+    }
+
+    pub const fn mix_iterations(self) -> MixIterations {
+        self.compresses().mix_iterations()
+    }
+}
+
+impl Compresses {
+    pub const fn mix_iterations(self) -> MixIterations {
+        MixIterations(self.0 * NUM_MIX_ROUNDS)
+    }
+
+    /// The number of rows taken by the compresses.
+    pub const fn rows(self) -> usize {
+        self.0 * COMPRESS_LENGTH
+    }
+
+    /// The number of compresses started in `num_rows` rows, counting a last compress that is cut
+    /// short.
+    pub const fn started_in(num_rows: usize) -> Self {
+        Self((num_rows + COMPRESS_LENGTH - 1) / COMPRESS_LENGTH)
+    }
+}
+
+impl MixIterations {
+    /// The number of rows taken by the mix iterations.
+    pub const fn rows(self) -> usize {
+        self.0 * MIX_LENGTH
+    }
+
+    /// The number of mix iterations that fit in `num_rows` rows.
+    ///
+    /// Panics if the rows don't split into whole mix iterations.
+    pub fn in_rows(num_rows: usize) -> Self {
+        assert_eq!(
+            num_rows % MIX_LENGTH,
+            0,
+            "The padded trace length must be a multiple of {} rows",
+            MIX_LENGTH
+        );
+        Self(num_rows / MIX_LENGTH)
+    }
+}
+
+macro_rules! impl_arithmetic {
+    ($($unit:ident),*) => {
+        $(
+            impl Add for $unit {
+                type Output = Self;
+
+                fn add(self, rhs: Self) -> Self {
+                    Self(self.0 + rhs.0)
+                }
+            }
+
+            impl Sub for $unit {
+                type Output = Self;
+
+                fn sub(self, rhs: Self) -> Self {
+                    Self(self.0 - rhs.0)
+                }
+            }
+        )*
+    };
+}
+
+impl_arithmetic!(Bytes, Blocks, Compresses, MixIterations);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blake2b_units() {
+        assert_eq!(Blocks(2).mix_iterations(), MixIterations(24));
+        assert_eq!(Blocks(2).bytes(), Bytes(256));
+        assert_eq!(Blocks(3).compresses().rows(), 3 * COMPRESS_LENGTH);
+        assert_eq!(MixIterations(24).rows(), Compresses(2).rows());
+
+        assert_eq!(Bytes(0).blocks(), Blocks(1));
+        assert_eq!(Bytes(1).blocks(), Blocks(1));
+        assert_eq!(Bytes(128).blocks(), Blocks(1));
+        assert_eq!(Bytes(129).blocks(), Blocks(2));
+        for num_blocks in 1..10 {
+            assert_eq!(Blocks(num_blocks).bytes().blocks(), Blocks(num_blocks));
+        }
+
+        assert_eq!(Compresses::started_in(2 * COMPRESS_LENGTH), Compresses(2));
+        assert_eq!(
+            Compresses::started_in(2 * COMPRESS_LENGTH + 8),
+            Compresses(3)
+        );
+        assert_eq!(MixIterations::in_rows(64), MixIterations(8));
+        assert_eq!(Compresses(3) - Compresses(1) + Compresses(4), Compresses(6));
+    }
+
+    #[test]
+    #[should_panic(expected = "multiple of 8 rows")]
+    fn test_blake2b_units_partial_mix() {
+        MixIterations::in_rows(12);
+    }
+}
@@ -0,0 +1,165 @@
+use super::data::BLAKE2SData;
+use super::register::BLAKE2SDigestRegister;
+use super::{
+    BLAKE2S, COMPRESS_IV, IV, MSG_ARRAY_SIZE, NUM_MIX_ROUNDS, SIGMA_PERMUTATIONS, STATE_SIZE,
+};
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U32Register;
+use crate::chip::uint::util::{u32_from_le_field_bytes, u32_to_le_field_bytes};
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::blake::blake2::air::BLAKE2Air;
+use crate::machine::hash::blake::blake2::data::BLAKE2Mode;
+use crate::machine::hash::blake::blake2::BLAKE2;
+use crate::machine::hash::{HashDigest, HashIntConversion, HashInteger};
+
+impl<B: Builder> HashInteger<B> for BLAKE2S {
+    type Value = <U32Register as Register>::Value<B::Field>;
+    type IntRegister = U32Register;
+}
+
+impl<B: Builder> HashIntConversion<B> for BLAKE2S {
+    fn int_to_field_value(int: Self::Integer) -> Self::Value {
+        u32_to_le_field_bytes(int)
+    }
+
+    fn field_value_to_int(value: &Self::Value) -> Self::Integer {
+        u32_from_le_field_bytes(value)
+    }
+}
+
+impl<B: Builder> HashDigest<B> for BLAKE2S {
+    type DigestRegister = BLAKE2SDigestRegister;
+}
+
+impl<L: AirParameters> BLAKE2<BytesBuilder<L>> for BLAKE2S
+where
+    L::Instruction: UintInstructions,
+{
+    const NAME: &'static str = "blake2s";
+    const NUM_MIX_ROUNDS: usize = NUM_MIX_ROUNDS;
+    const IV: [u32; STATE_SIZE] = IV;
+    const COMPRESS_IV: [u32; STATE_SIZE] = COMPRESS_IV;
+    const WORD_MAX: u32 = 0xFFFFFFFF;
+    const DIGEST_WORDS: usize = STATE_SIZE;
+    const SIGMA_PERMUTATIONS: &'static [[u8; MSG_ARRAY_SIZE]] = &SIGMA_PERMUTATIONS;
+
+    fn cycles_end_bits(builder: &mut BytesBuilder<L>) -> (BitRegister, BitRegister, BitRegister) {
+        let cycle_4 = builder.cycle(2);
+        let cycle_8 = builder.cycle(3);
+        let loop_5 = builder.api().loop_instr(5);
+        let cycle_80_end_bit = {
+            let cycle_16 = builder.cycle(4);
+            builder.mul(loop_5.get_iteration_reg(4), cycle_16.end_bit)
+        };
+
+        (cycle_4.end_bit, cycle_8.end_bit, cycle_80_end_bit)
+    }
+
+    fn mix(
+        builder: &mut BytesBuilder<L>,
+        v_a: &Self::IntRegister,
+        v_b: &Self::IntRegister,
+        v_c: &Self::IntRegister,
+        v_d: &Self::IntRegister,
+        x: &Self::IntRegister,
+        y: &Self::IntRegister,
+    ) -> (
+        Self::IntRegister,
+        Self::IntRegister,
+        Self::IntRegister,
+        Self::IntRegister,
+    ) {
+        builder.tagged("blake2s_mix", |builder| {
+            let mut v_a_inter = builder.add(*v_a, *v_b);
+            v_a_inter = builder.add(v_a_inter, *x);
+
+            let mut v_d_inter = builder.xor(*v_d, v_a_inter);
+            v_d_inter = builder.rotate_right(v_d_inter, 16);
+
+            let mut v_c_inter = builder.add(*v_c, v_d_inter);
+
+            let mut v_b_inter = builder.xor(*v_b, v_c_inter);
+            v_b_inter = builder.rotate_right(v_b_inter, 12);
+
+            v_a_inter = builder.add(v_a_inter, v_b_inter);
+            v_a_inter = builder.add(v_a_inter, *y);
+
+            v_d_inter = builder.xor(v_d_inter, v_a_inter);
+            v_d_inter = builder.rotate_right(v_d_inter, 8);
+
+            v_c_inter = builder.add(v_c_inter, v_d_inter);
+
+            v_b_inter = builder.xor(v_b_inter, v_c_inter);
+            v_b_inter = builder.rotate_right(v_b_inter, 7);
+
+            (v_a_inter, v_b_inter, v_c_inter, v_d_inter)
+        })
+    }
+}
+
+/// The BLAKE2s hash function, laid out by `BLAKE2Air` with one mix per row.
+///
+/// A compress takes 80 rows, one for each of the 8 mixes of the 10 rounds. The words are 32 bits
+/// long, and the digest is the whole state of 8 words. The counter of the bytes compressed is
+/// given by a single word per compress, so the messages must be shorter than 2^32 bytes.
+pub trait BLAKE2SAir<B: Builder>: BLAKE2<B> {
+    /// Hashes the padded chunks, emitting a digest at every compress flagged in `digest_bits`.
+    ///
+    /// Each digest compress is also the final compress of its message.
+    fn blake2s(
+        builder: &mut B,
+        padded_chunks: &[ArrayRegister<Self::IntRegister>],
+        t_values: &ArrayRegister<Self::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+    ) -> Vec<Self::DigestRegister>;
+}
+
+impl<L: AirParameters> BLAKE2SAir<BytesBuilder<L>> for BLAKE2S
+where
+    L::Instruction: UintInstructions,
+{
+    fn blake2s(
+        builder: &mut BytesBuilder<L>,
+        padded_chunks: &[ArrayRegister<Self::IntRegister>],
+        t_values: &ArrayRegister<Self::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+    ) -> Vec<Self::DigestRegister> {
+        let padded_chunks = padded_chunks
+            .iter()
+            .copied()
+            .enumerate()
+            .collect::<Vec<_>>();
+        let mode = BLAKE2Mode::Sequential {
+            t_values: *t_values,
+            final_bits: *digest_bits,
+        };
+        let data: BLAKE2SData<BytesBuilder<L>> = builder.tagged("blake2s_data", |builder| {
+            Self::blake2_data(
+                builder,
+                &padded_chunks,
+                &mode,
+                end_bits,
+                digest_bits,
+                digest_indices,
+                num_messages,
+            )
+        });
+
+        Self::blake2_states(builder, &data)
+            .into_iter()
+            .map(BLAKE2SDigestRegister::from_array)
+            .collect()
+    }
+}
@@ -0,0 +1,248 @@
+use super::air::BLAKE2SAir;
+use super::{BLAKE2S, COMPRESS_LENGTH, MSG_ARRAY_SIZE};
+use crate::chip::builder::padding::PaddingPolicy;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U32Register;
+use crate::chip::{AirParameters, ColumnBudget};
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::prelude::Builder;
+
+pub trait BLAKE2SBuilder: Builder {
+    fn blake2s<B: BLAKE2SAir<Self>>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<B::IntRegister>],
+        t_values: &ArrayRegister<B::IntRegister>,
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+    ) -> Vec<B::DigestRegister> {
+        B::blake2s(
+            self,
+            padded_chunks,
+            t_values,
+            end_bits,
+            digest_bits,
+            digest_indices,
+            num_messages,
+        )
+    }
+}
+
+impl<B: Builder> BLAKE2SBuilder for B {}
+
+impl BLAKE2S {
+    /// The number of rows of the trace of the BLAKE2s machine hashing `num_compresses` padded
+    /// chunks. The rows of the last cycle are padded with dummy compresses up to a power of two.
+    pub fn rows_for(num_compresses: usize) -> usize {
+        PaddingPolicy::NextPowerOfTwo.padded_length(num_compresses * COMPRESS_LENGTH)
+    }
+
+    /// The columns needed by the BLAKE2s machine hashing `num_compresses` padded chunks, to be
+    /// used as the column constants of `L`.
+    pub fn required_columns<L: AirParameters>(num_compresses: usize) -> ColumnBudget
+    where
+        L::Instruction: UintInstructions,
+    {
+        let mut builder = BytesBuilder::<L>::new();
+        let padded_chunks = (0..num_compresses)
+            .map(|_| builder.alloc_array_public::<U32Register>(MSG_ARRAY_SIZE))
+            .collect::<Vec<_>>();
+        let t_values = builder.alloc_array_public::<U32Register>(num_compresses);
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let digest_bits = builder.alloc_array_public::<BitRegister>(num_compresses);
+        let digest_indices = builder.alloc_array_public::<ElementRegister>(num_compresses);
+        let num_messages = builder.alloc_public::<ElementRegister>();
+        builder.blake2s::<BLAKE2S>(
+            &padded_chunks,
+            &t_values,
+            &end_bits,
+            &digest_bits,
+            &digest_indices,
+            &num_messages,
+        );
+
+        builder.column_budget()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::u32_to_le_field_bytes;
+    use crate::machine::hash::blake::blake2s::pure::BLAKE2SPure;
+    use crate::machine::hash::blake::blake2s::utils::BLAKE2SUtil;
+    use crate::machine::hash::blake::blake2s::IV;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::prelude::{AirWriter, AirWriterData};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BLAKE2STest;
+
+    impl AirParameters for BLAKE2STest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 800;
+        const EXTENDED_COLUMNS: usize = 1100;
+    }
+
+    #[test]
+    fn test_blake2s_required_columns() {
+        type L = BLAKE2STest;
+
+        let budget = BLAKE2S::required_columns::<L>(4);
+        assert!(budget.fits::<L>());
+        assert_eq!(budget.num_arithmetic_columns, 0);
+    }
+
+    #[test]
+    fn test_blake2s() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        let msgs: [(Vec<u8>, u64); 4] = [
+            (b"".to_vec(), 1),
+            (b"abc".to_vec(), 2),
+            ((0..200).map(|i| i as u8).collect(), 4),
+            ((0..64).map(|i| (i * 7 + 3) as u8).collect(), 1),
+        ];
+
+        let mut padded_chunks_values = Vec::new();
+        let mut t_values_values = Vec::new();
+        let mut end_bits_values = Vec::new();
+        let mut digest_bits_values = Vec::new();
+        let mut digest_indices_values = Vec::new();
+
+        let mut start_index = 0;
+        for (msg, max_chunk_size) in msgs.iter() {
+            let padded_msg = BLAKE2SUtil::pad(msg, *max_chunk_size);
+            let num_chunks = padded_msg.len() / 64;
+            let msg_digest_idx = if msg.is_empty() {
+                0
+            } else {
+                (msg.len() - 1) / 64
+            };
+            for (i, chunk) in padded_msg.chunks_exact(64).enumerate() {
+                padded_chunks_values.push(chunk.to_vec());
+                let at_digest_chunk = i == msg_digest_idx;
+                t_values_values.push(if at_digest_chunk {
+                    msg.len() as u32
+                } else {
+                    64 * (i as u32 + 1)
+                });
+                digest_bits_values.push(at_digest_chunk);
+                end_bits_values.push(i == num_chunks - 1);
+                if at_digest_chunk {
+                    digest_indices_values.push(start_index + msg_digest_idx);
+                }
+            }
+            start_index += num_chunks;
+        }
+
+        let num_rounds = padded_chunks_values.len();
+        let num_rows = BLAKE2S::rows_for(num_rounds);
+        let mut builder = BytesBuilder::<BLAKE2STest>::new();
+        let padded_chunks = (0..num_rounds)
+            .map(|_| builder.alloc_array_public::<U32Register>(MSG_ARRAY_SIZE))
+            .collect::<Vec<_>>();
+        let t_values = builder.alloc_array_public::<U32Register>(num_rounds);
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_rounds);
+        let digest_bits = builder.alloc_array_public::<BitRegister>(num_rounds);
+        let digest_indices = builder.alloc_array_public::<ElementRegister>(msgs.len());
+        let num_messages = builder.alloc_public::<ElementRegister>();
+        let hash_state = builder.blake2s::<BLAKE2S>(
+            &padded_chunks,
+            &t_values,
+            &end_bits,
+            &digest_bits,
+            &digest_indices,
+            &num_messages,
+        );
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+
+        writer.write(&num_messages, &F::from_canonical_usize(msgs.len()));
+        let mut hash_state_iter = hash_state.iter();
+        let mut digests = Vec::new();
+        let mut current_state = IV;
+        for i in 0..num_rounds {
+            let chunk = &padded_chunks_values[i];
+            writer.write_array(
+                &padded_chunks[i],
+                chunk.chunks_exact(4).map(|word| {
+                    u32_to_le_field_bytes(u32::from_le_bytes(word.try_into().unwrap()))
+                }),
+            );
+            writer.write(
+                &end_bits.get(i),
+                &F::from_canonical_usize(end_bits_values[i] as usize),
+            );
+            writer.write(
+                &digest_bits.get(i),
+                &F::from_canonical_usize(digest_bits_values[i] as usize),
+            );
+            writer.write(&t_values.get(i), &u32_to_le_field_bytes(t_values_values[i]));
+
+            <BLAKE2S as BLAKE2SPure>::compress(
+                chunk,
+                &mut current_state,
+                t_values_values[i] as u64,
+                digest_bits_values[i],
+            );
+
+            if digest_bits_values[i] {
+                let digest = hash_state_iter.next().unwrap();
+                writer.write_array(
+                    &digest.as_array(),
+                    current_state.map(u32_to_le_field_bytes::<F>),
+                );
+                digests.push(current_state);
+            }
+
+            if end_bits_values[i] {
+                current_state = IV;
+            }
+        }
+        for (i, digest_index) in digest_indices_values.iter().enumerate() {
+            writer.write(
+                &digest_indices.get(i),
+                &F::from_canonical_usize(*digest_index),
+            );
+        }
+
+        // The digests written to the public inputs are the BLAKE2s digests of the messages.
+        for ((msg, _), digest) in msgs.iter().zip_eq(digests.iter()) {
+            let digest_bytes = digest.iter().flat_map(|w| w.to_le_bytes()).collect_vec();
+            assert_eq!(digest_bytes, BLAKE2S::hash(msg).to_vec());
+        }
+
+        stark.air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new("test_blake2s", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+}
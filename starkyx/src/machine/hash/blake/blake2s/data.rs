@@ -0,0 +1,5 @@
+use super::BLAKE2S;
+use crate::machine::hash::blake::blake2::data::BLAKE2Data;
+
+/// The registers and memory of the BLAKE2s compresses.
+pub type BLAKE2SData<B> = BLAKE2Data<B, BLAKE2S>;
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+pub mod air;
+pub mod builder;
+pub mod data;
+pub mod pure;
+pub mod register;
+pub mod utils;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BLAKE2S;
+
+const NUM_MIX_ROUNDS: usize = 10;
+const MIX_LENGTH: usize = 8;
+const MSG_ARRAY_SIZE: usize = 16;
+const STATE_SIZE: usize = 8;
+const WORK_VECTOR_SIZE: usize = 16;
+const COMPRESS_LENGTH: usize = MIX_LENGTH * NUM_MIX_ROUNDS;
+
+// Note that for this blake2s implementation, we don't support a key input and
+// we assume that the output is 32 bytes
+// So that means the initial hash entry to be
+// 0x6a09e667 xor 0x01010020
+pub const IV: [u32; STATE_SIZE] = [
+    0x6b08e647, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+pub const COMPRESS_IV: [u32; STATE_SIZE] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The permutations of the message words in the ten rounds of BLAKE2s, which are the first ten
+/// rounds of BLAKE2b.
+const SIGMA_PERMUTATIONS: [[u8; MSG_ARRAY_SIZE]; NUM_MIX_ROUNDS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine::hash::blake::blake2b;
+
+    #[test]
+    fn test_blake2s_sigma_permutations() {
+        assert_eq!(
+            SIGMA_PERMUTATIONS[..],
+            blake2b::SIGMA_PERMUTATIONS[..NUM_MIX_ROUNDS]
+        );
+        assert_eq!(IV[0], COMPRESS_IV[0] ^ 0x01010020);
+        assert_eq!(IV[1..], COMPRESS_IV[1..]);
+    }
+}
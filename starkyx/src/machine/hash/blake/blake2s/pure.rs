@@ -0,0 +1,203 @@
+use super::utils::BLAKE2SUtil;
+use super::{BLAKE2S, COMPRESS_IV, IV, SIGMA_PERMUTATIONS, STATE_SIZE, WORK_VECTOR_SIZE};
+use crate::machine::hash::HashPureInteger;
+
+impl HashPureInteger for BLAKE2S {
+    type Integer = u32;
+}
+
+pub trait BLAKE2SPure: HashPureInteger {
+    fn compress(
+        msg_chunk: &[u8],
+        state: &mut [Self::Integer; STATE_SIZE],
+        bytes_compressed: u64,
+        last_chunk: bool,
+    ) -> [Self::Integer; STATE_SIZE];
+
+    fn mix(
+        v: &mut [Self::Integer; WORK_VECTOR_SIZE],
+        a: usize,
+        b: usize,
+        c: usize,
+        d: usize,
+        x: Self::Integer,
+        y: Self::Integer,
+    );
+}
+
+impl BLAKE2SPure for BLAKE2S {
+    fn compress(
+        msg_chunk: &[u8],
+        state: &mut [Self::Integer; STATE_SIZE],
+        bytes_compressed: u64,
+        last_chunk: bool,
+    ) -> [Self::Integer; STATE_SIZE] {
+        // Set up the work vector V
+        let mut v: [Self::Integer; WORK_VECTOR_SIZE] = [0; WORK_VECTOR_SIZE];
+
+        v[..8].copy_from_slice(&state[..STATE_SIZE]);
+        v[8..16].copy_from_slice(&COMPRESS_IV);
+
+        v[12] ^= bytes_compressed as u32;
+        v[13] ^= (bytes_compressed >> 32) as u32;
+        if last_chunk {
+            v[14] ^= 0xFFFFFFFF;
+        }
+
+        let msg_u32_chunks = msg_chunk
+            .chunks_exact(4)
+            .map(|x| Self::Integer::from_le_bytes(x.try_into().unwrap()))
+            .collect::<Vec<_>>();
+
+        for s in SIGMA_PERMUTATIONS.iter() {
+            Self::mix(
+                &mut v,
+                0,
+                4,
+                8,
+                12,
+                msg_u32_chunks[s[0] as usize],
+                msg_u32_chunks[s[1] as usize],
+            );
+            Self::mix(
+                &mut v,
+                1,
+                5,
+                9,
+                13,
+                msg_u32_chunks[s[2] as usize],
+                msg_u32_chunks[s[3] as usize],
+            );
+            Self::mix(
+                &mut v,
+                2,
+                6,
+                10,
+                14,
+                msg_u32_chunks[s[4] as usize],
+                msg_u32_chunks[s[5] as usize],
+            );
+            Self::mix(
+                &mut v,
+                3,
+                7,
+                11,
+                15,
+                msg_u32_chunks[s[6] as usize],
+                msg_u32_chunks[s[7] as usize],
+            );
+
+            Self::mix(
+                &mut v,
+                0,
+                5,
+                10,
+                15,
+                msg_u32_chunks[s[8] as usize],
+                msg_u32_chunks[s[9] as usize],
+            );
+            Self::mix(
+                &mut v,
+                1,
+                6,
+                11,
+                12,
+                msg_u32_chunks[s[10] as usize],
+                msg_u32_chunks[s[11] as usize],
+            );
+            Self::mix(
+                &mut v,
+                2,
+                7,
+                8,
+                13,
+                msg_u32_chunks[s[12] as usize],
+                msg_u32_chunks[s[13] as usize],
+            );
+            Self::mix(
+                &mut v,
+                3,
+                4,
+                9,
+                14,
+                msg_u32_chunks[s[14] as usize],
+                msg_u32_chunks[s[15] as usize],
+            );
+        }
+
+        for i in 0..STATE_SIZE {
+            state[i] ^= v[i];
+        }
+
+        for i in 0..STATE_SIZE {
+            state[i] ^= v[i + 8];
+        }
+
+        *state
+    }
+
+    fn mix(
+        v: &mut [Self::Integer; WORK_VECTOR_SIZE],
+        a: usize,
+        b: usize,
+        c: usize,
+        d: usize,
+        x: Self::Integer,
+        y: Self::Integer,
+    ) {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(12);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(8);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(7);
+    }
+}
+
+impl BLAKE2S {
+    /// The unkeyed BLAKE2s digest of `msg`, with a 32-byte output.
+    pub fn hash(msg: &[u8]) -> [u8; 32] {
+        let num_chunks = ((msg.len() + 63) / 64).max(1);
+        let padded_msg = BLAKE2SUtil::pad(msg, num_chunks as u64);
+        let mut state = IV;
+        for (i, chunk) in padded_msg.chunks_exact(64).enumerate() {
+            let is_last = i == num_chunks - 1;
+            let bytes_compressed = if is_last { msg.len() } else { 64 * (i + 1) };
+            Self::compress(chunk, &mut state, bytes_compressed as u64, is_last);
+        }
+
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blake2s_hash() {
+        let expected = [
+            (
+                b"".to_vec(),
+                "69217a3079908094e11121d042354a7c1f55b6482ca1a51e1b250dfd1ed0eef9",
+            ),
+            (
+                b"abc".to_vec(),
+                "508c5e8c327c14e2e1a72ba34eeb452f37458b209ed63a294d999b4c86675982",
+            ),
+            (
+                (0..200).map(|i| i as u8).collect(),
+                "6d244e1a06ce4ef578dd0f63aff0936706735119ca9c8d22d86c801414ab9741",
+            ),
+        ];
+        for (msg, digest) in expected {
+            assert_eq!(BLAKE2S::hash(&msg).to_vec(), hex::decode(digest).unwrap());
+        }
+    }
+}
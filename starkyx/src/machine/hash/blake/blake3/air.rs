@@ -0,0 +1,234 @@
+use super::data::BLAKE3Data;
+use super::register::BLAKE3DigestRegister;
+use super::tree::{BLAKE3Compress, BLAKE3Input};
+use super::{
+    BLAKE3, COMPRESS_LENGTH, IV, MIX_LENGTH, MSG_ARRAY_SIZE, NUM_MIX_ROUNDS, SIGMA_PERMUTATIONS,
+    STATE_SIZE,
+};
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U32Register;
+use crate::chip::uint::util::{u32_from_le_field_bytes, u32_to_le_field_bytes};
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::blake::blake2::air::BLAKE2Air;
+use crate::machine::hash::blake::blake2::data::{BLAKE2Data, BLAKE2Mode};
+use crate::machine::hash::blake::blake2::BLAKE2;
+use crate::machine::hash::{HashDigest, HashIntConversion, HashInteger};
+use crate::math::prelude::*;
+
+impl<B: Builder> HashInteger<B> for BLAKE3 {
+    type Value = <U32Register as Register>::Value<B::Field>;
+    type IntRegister = U32Register;
+}
+
+impl<B: Builder> HashIntConversion<B> for BLAKE3 {
+    fn int_to_field_value(int: Self::Integer) -> Self::Value {
+        u32_to_le_field_bytes(int)
+    }
+
+    fn field_value_to_int(value: &Self::Value) -> Self::Integer {
+        u32_from_le_field_bytes(value)
+    }
+}
+
+impl<B: Builder> HashDigest<B> for BLAKE3 {
+    type DigestRegister = BLAKE3DigestRegister;
+}
+
+impl<L: AirParameters> BLAKE2<BytesBuilder<L>> for BLAKE3
+where
+    L::Instruction: UintInstructions,
+{
+    const NAME: &'static str = "blake3";
+    const NUM_MIX_ROUNDS: usize = NUM_MIX_ROUNDS;
+    const IV: [u32; STATE_SIZE] = IV;
+    const COMPRESS_IV: [u32; STATE_SIZE] = IV;
+    const WORD_MAX: u32 = 0xFFFFFFFF;
+    const DIGEST_WORDS: usize = STATE_SIZE;
+    const SIGMA_PERMUTATIONS: &'static [[u8; MSG_ARRAY_SIZE]] = &SIGMA_PERMUTATIONS;
+
+    fn cycles_end_bits(builder: &mut BytesBuilder<L>) -> (BitRegister, BitRegister, BitRegister) {
+        let end_bits = builder.period_end_bits(&[4, MIX_LENGTH, COMPRESS_LENGTH]);
+        (end_bits[0], end_bits[1], end_bits[2])
+    }
+
+    fn mix(
+        builder: &mut BytesBuilder<L>,
+        v_a: &Self::IntRegister,
+        v_b: &Self::IntRegister,
+        v_c: &Self::IntRegister,
+        v_d: &Self::IntRegister,
+        x: &Self::IntRegister,
+        y: &Self::IntRegister,
+    ) -> (
+        Self::IntRegister,
+        Self::IntRegister,
+        Self::IntRegister,
+        Self::IntRegister,
+    ) {
+        builder.tagged("blake3_mix", |builder| {
+            let mut v_a_inter = builder.add(*v_a, *v_b);
+            v_a_inter = builder.add(v_a_inter, *x);
+
+            let mut v_d_inter = builder.xor(*v_d, v_a_inter);
+            v_d_inter = builder.rotate_right(v_d_inter, 16);
+
+            let mut v_c_inter = builder.add(*v_c, v_d_inter);
+
+            let mut v_b_inter = builder.xor(*v_b, v_c_inter);
+            v_b_inter = builder.rotate_right(v_b_inter, 12);
+
+            v_a_inter = builder.add(v_a_inter, v_b_inter);
+            v_a_inter = builder.add(v_a_inter, *y);
+
+            v_d_inter = builder.xor(v_d_inter, v_a_inter);
+            v_d_inter = builder.rotate_right(v_d_inter, 8);
+
+            v_c_inter = builder.add(v_c_inter, v_d_inter);
+
+            v_b_inter = builder.xor(v_b_inter, v_c_inter);
+            v_b_inter = builder.rotate_right(v_b_inter, 7);
+
+            (v_a_inter, v_b_inter, v_c_inter, v_d_inter)
+        })
+    }
+}
+
+/// The BLAKE3 hash function, laid out by `BLAKE2Air` with one mix per row.
+///
+/// A compress takes 56 rows, one for each of the 8 mixes of the 7 rounds. The trees of the
+/// messages are given by their lengths, and their compresses follow each other with the children
+/// of every parent before it, chained in the tree mode of `BLAKE2Air`. The chaining value of a
+/// chunk or a parent is stored in the message memory of its parent, and the digest of a message
+/// is the chaining value of its root.
+pub trait BLAKE3Air<B: Builder>: BLAKE2<B> {
+    /// Hashes the messages of public bytes `messages`, returning their root hashes.
+    fn blake3(
+        builder: &mut B,
+        messages: &[ArrayRegister<ByteRegister>],
+    ) -> Vec<Self::DigestRegister>;
+
+    /// Allocates the registers and memory of the compresses of the trees of `messages`.
+    fn blake3_data(
+        builder: &mut B,
+        messages: &[ArrayRegister<ByteRegister>],
+    ) -> BLAKE2Data<B, Self>;
+}
+
+impl<L: AirParameters> BLAKE3Air<BytesBuilder<L>> for BLAKE3
+where
+    L::Instruction: UintInstructions,
+{
+    fn blake3(
+        builder: &mut BytesBuilder<L>,
+        messages: &[ArrayRegister<ByteRegister>],
+    ) -> Vec<Self::DigestRegister> {
+        let data = builder.tagged("blake3_data", |builder| {
+            Self::blake3_data(builder, messages)
+        });
+
+        Self::blake2_states(builder, &data)
+            .into_iter()
+            .map(BLAKE3DigestRegister::from_array)
+            .collect()
+    }
+
+    fn blake3_data(
+        builder: &mut BytesBuilder<L>,
+        messages: &[ArrayRegister<ByteRegister>],
+    ) -> BLAKE3Data<BytesBuilder<L>> {
+        // Lay out the trees of the messages one after the other, and set the message words of
+        // the chunk blocks to the bytes of the messages, padded with zeros.
+        let mut compresses = Vec::new();
+        let mut padded_chunks = Vec::new();
+        let mut digest_indices_values = Vec::new();
+        for message in messages {
+            let first_compress_id = compresses.len();
+            for compress in BLAKE3::compresses(message.len()) {
+                let input = match compress.input {
+                    BLAKE3Input::Block { offset } => {
+                        let words = builder.alloc_array_public::<U32Register>(MSG_ARRAY_SIZE);
+                        for (i, word) in words.iter().enumerate() {
+                            for (j, byte) in word.to_le_bytes().iter().enumerate() {
+                                let k = 4 * i + j;
+                                let value = if k < compress.block_len as usize {
+                                    message.get(offset + k).expr()
+                                } else {
+                                    builder.lit(L::Field::ZERO)
+                                };
+                                builder.set_to_expression(&byte, value);
+                            }
+                        }
+                        padded_chunks.push((compresses.len(), words));
+                        compress.input
+                    }
+                    BLAKE3Input::Parent { left, right } => BLAKE3Input::Parent {
+                        left: first_compress_id + left,
+                        right: first_compress_id + right,
+                    },
+                };
+                compresses.push(BLAKE3Compress { input, ..compress });
+            }
+            digest_indices_values.push(L::Field::from_canonical_usize(compresses.len() - 1));
+        }
+        let digest_indices = builder.constant_array::<ElementRegister>(&digest_indices_values);
+
+        // The chaining value of every chunk and parent but the roots is stored in the message
+        // words of its parent, in the first half for the left child and in the second half for
+        // the right child.
+        let mut parent_indices = vec![None; compresses.len()];
+        for (i, compress) in compresses.iter().enumerate() {
+            if let BLAKE3Input::Parent { left, right } = compress.input {
+                parent_indices[left] = Some(i * MSG_ARRAY_SIZE);
+                parent_indices[right] = Some(i * MSG_ARRAY_SIZE + STATE_SIZE);
+            }
+        }
+
+        // The last row of the work vector of every compress holds the counter, the block length
+        // and the flags.
+        let parameters_values = compresses
+            .iter()
+            .flat_map(|compress| compress.parameters())
+            .map(<Self as HashIntConversion<BytesBuilder<L>>>::int_to_field_value)
+            .collect::<Vec<_>>();
+        let parameters = builder.constant_array::<U32Register>(&parameters_values);
+
+        // A chunk or a parent ends its chain of compresses, and a root outputs the digest. The
+        // compresses taking the IV as their chaining value are the first of their chain.
+        let mut bits = |flag: fn(&BLAKE3Compress) -> bool| {
+            let values = compresses
+                .iter()
+                .map(|compress| L::Field::from_canonical_u8(flag(compress) as u8))
+                .collect::<Vec<_>>();
+            builder.constant_array::<BitRegister>(&values)
+        };
+        let end_bits = bits(BLAKE3Compress::is_end);
+        let digest_bits = bits(BLAKE3Compress::is_root);
+        let num_first_compresses = compresses
+            .iter()
+            .filter(|compress| compress.is_start())
+            .count();
+        let num_first_compresses_element = builder
+            .constant::<ElementRegister>(&L::Field::from_canonical_usize(num_first_compresses));
+
+        let mode = BLAKE2Mode::Tree {
+            parameters,
+            parent_indices,
+        };
+        Self::blake2_data(
+            builder,
+            &padded_chunks,
+            &mode,
+            &end_bits,
+            &digest_bits,
+            &digest_indices,
+            &num_first_compresses_element,
+        )
+    }
+}
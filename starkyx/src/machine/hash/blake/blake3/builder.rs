@@ -0,0 +1,228 @@
+use super::air::BLAKE3Air;
+use super::{BLAKE3, COMPRESS_LENGTH};
+use crate::chip::builder::padding::PaddingPolicy;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::{AirParameters, ColumnBudget};
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::prelude::Builder;
+
+pub trait BLAKE3Builder: Builder {
+    /// Returns the BLAKE3 hashes of `messages`, whose bytes must be public registers.
+    fn blake3<B: BLAKE3Air<Self>>(
+        &mut self,
+        messages: &[ArrayRegister<ByteRegister>],
+    ) -> Vec<B::DigestRegister> {
+        B::blake3(self, messages)
+    }
+}
+
+impl<B: Builder> BLAKE3Builder for B {}
+
+impl BLAKE3 {
+    /// The number of compresses of the trees of messages of lengths `lengths`.
+    pub fn num_compresses(lengths: &[usize]) -> usize {
+        lengths
+            .iter()
+            .map(|length| Self::compresses(*length).len())
+            .sum()
+    }
+
+    /// The number of rows of the trace of the BLAKE3 machine hashing `num_compresses`
+    /// compresses. The rows of the last cycle are padded with dummy compresses up to a power of
+    /// two.
+    pub fn rows_for(num_compresses: usize) -> usize {
+        PaddingPolicy::NextPowerOfTwo.padded_length(num_compresses * COMPRESS_LENGTH)
+    }
+
+    /// The columns needed by the BLAKE3 machine hashing messages of lengths `lengths`, to be
+    /// used as the column constants of `L`.
+    pub fn required_columns<L: AirParameters>(lengths: &[usize]) -> ColumnBudget
+    where
+        L::Instruction: UintInstructions,
+    {
+        let mut builder = BytesBuilder::<L>::new();
+        let messages = lengths
+            .iter()
+            .map(|length| builder.alloc_array_public::<ByteRegister>(*length))
+            .collect::<Vec<_>>();
+        builder.blake3::<BLAKE3>(&messages);
+
+        builder.column_budget()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::u32_to_le_field_bytes;
+    use crate::machine::hash::blake::blake3::CHUNK_LEN;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::prelude::{AirWriter, AirWriterData};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BLAKE3Test;
+
+    impl AirParameters for BLAKE3Test {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 800;
+        const EXTENDED_COLUMNS: usize = 1100;
+    }
+
+    #[test]
+    fn test_blake3_required_columns() {
+        type L = BLAKE3Test;
+
+        let budget = BLAKE3::required_columns::<L>(&[0, 2 * CHUNK_LEN + 1]);
+        assert!(budget.fits::<L>());
+        assert_eq!(budget.num_arithmetic_columns, 0);
+    }
+
+    #[test]
+    fn test_blake3_dummy_read_counts() {
+        use crate::chip::instruction::set::AirInstruction;
+        use crate::chip::memory::instruction::{MemoryInstruction, MemorySliceIndex};
+        use crate::machine::hash::blake::blake2::air::BLAKE2Air;
+
+        type L = BLAKE3Test;
+        type F = GoldilocksField;
+
+        // A single compress, and a tree with a parent of a parent and a chunk.
+        let msgs =
+            [0, 2 * CHUNK_LEN + 1].map(|length| (0..length).map(|i| (i % 251) as u8).collect_vec());
+
+        let mut builder = BytesBuilder::<L>::new();
+        let messages = msgs
+            .iter()
+            .map(|msg| builder.alloc_array_public::<ByteRegister>(msg.len()))
+            .collect_vec();
+        let data = BLAKE3::blake3_data(&mut builder, &messages);
+        let digests = BLAKE3::blake2_states(&mut builder, &data);
+
+        let num_rows = builder.trace_length().unwrap();
+        let airs = builder.build_airs();
+
+        let mut writer_data = AirWriterData::new(&airs.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        for ((msg, message), digest) in msgs.iter().zip_eq(messages.iter()).zip_eq(digests.iter()) {
+            writer.write_array(message, msg.iter().map(|byte| F::from_canonical_u8(*byte)));
+            let root = *BLAKE3::chaining_values(msg).last().unwrap();
+            writer.write_array(digest, root.map(u32_to_le_field_bytes::<F>));
+        }
+        airs.air_data.write_global_instructions(&mut writer);
+        let dummy_index = writer.read(&data.consts.dummy_index);
+        let multiplicities = [
+            ("h", writer.read(&data.memory.dummy_h.multiplicity())),
+            ("v", writer.read(&data.memory.dummy_v.multiplicity())),
+            (
+                "v_final",
+                writer.read(&data.memory.dummy_v_final.multiplicity()),
+            ),
+            ("m", writer.read(&data.memory.dummy_m.multiplicity())),
+            (
+                "parameters",
+                writer.read(&data.memory.dummy_parameters.multiplicity()),
+            ),
+        ];
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                airs.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        // The loads of the dummy index in the trace, by the label of the slice.
+        let loads = airs
+            .air_data
+            .instructions
+            .iter()
+            .filter_map(|instr| match instr {
+                AirInstruction::Mem(MemoryInstruction::Get(instr)) => instr.memory_output(),
+                _ => None,
+            })
+            .filter_map(|output| match output.index {
+                Some(MemorySliceIndex::IndexElement(index)) => Some((output.label.as_str(), index)),
+                _ => None,
+            })
+            .collect_vec();
+        for (label, multiplicity) in multiplicities {
+            let mut num_dummy_loads = 0;
+            for mut chunk in writer_data.chunks(num_rows) {
+                for i in 0..num_rows {
+                    let writer = chunk.window_writer(i);
+                    num_dummy_loads += loads
+                        .iter()
+                        .filter(|(load_label, index)| {
+                            *load_label == label && writer.read(index) == dummy_index
+                        })
+                        .count();
+                }
+            }
+            assert_eq!(
+                multiplicity,
+                F::from_canonical_usize(num_dummy_loads),
+                "{label}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_blake3() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type F = GoldilocksField;
+
+        // The empty message, a single chunk, and three chunks, whose tree has a parent of a
+        // parent and a chunk.
+        let msgs = [0, CHUNK_LEN, 2 * CHUNK_LEN + 1]
+            .map(|length| (0..length).map(|i| (i % 251) as u8).collect_vec());
+        let lengths = msgs.iter().map(|msg| msg.len()).collect_vec();
+
+        let mut builder = BytesBuilder::<BLAKE3Test>::new();
+        let messages = lengths
+            .iter()
+            .map(|length| builder.alloc_array_public::<ByteRegister>(*length))
+            .collect_vec();
+        let digests = builder.blake3::<BLAKE3>(&messages);
+
+        let num_rows = BLAKE3::rows_for(BLAKE3::num_compresses(&lengths));
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+
+        for ((msg, message), digest) in msgs.iter().zip_eq(messages.iter()).zip_eq(digests.iter()) {
+            writer.write_array(message, msg.iter().map(|byte| F::from_canonical_u8(*byte)));
+            let root = *BLAKE3::chaining_values(msg).last().unwrap();
+            writer.write_array(&digest.as_array(), root.map(u32_to_le_field_bytes::<F>));
+
+            // The digests written to the public inputs are the BLAKE3 hashes of the messages.
+            let digest_bytes = root.iter().flat_map(|w| w.to_le_bytes()).collect_vec();
+            assert_eq!(digest_bytes, blake3::hash(msg).as_bytes().to_vec());
+        }
+
+        stark.air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new("test_blake3", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+}
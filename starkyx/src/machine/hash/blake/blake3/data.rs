@@ -0,0 +1,5 @@
+use super::BLAKE3;
+use crate::machine::hash::blake::blake2::data::BLAKE2Data;
+
+/// The registers and memory of the BLAKE3 compresses.
+pub type BLAKE3Data<B> = BLAKE2Data<B, BLAKE3>;
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+pub mod air;
+pub mod builder;
+pub mod data;
+pub mod pure;
+pub mod register;
+pub mod tree;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BLAKE3;
+
+const NUM_MIX_ROUNDS: usize = 7;
+const MIX_LENGTH: usize = 8;
+const MSG_ARRAY_SIZE: usize = 16;
+const STATE_SIZE: usize = 8;
+const WORK_VECTOR_SIZE: usize = 16;
+const COMPRESS_LENGTH: usize = MIX_LENGTH * NUM_MIX_ROUNDS;
+
+/// The length of a block, in bytes.
+pub const BLOCK_LEN: usize = 64;
+
+/// The length of a chunk, in bytes.
+pub const CHUNK_LEN: usize = 1024;
+
+pub const CHUNK_START: u32 = 1 << 0;
+pub const CHUNK_END: u32 = 1 << 1;
+pub const PARENT: u32 = 1 << 2;
+pub const ROOT: u32 = 1 << 3;
+
+/// The initial chaining value of the chunks and the parents, for the unkeyed hash. The first four
+/// words are also the constants of the third row of the work vector.
+pub const IV: [u32; STATE_SIZE] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The permutation of the message words applied after every round.
+const MSG_PERMUTATION: [u8; MSG_ARRAY_SIZE] =
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8];
+
+/// The message words used by the seven rounds, given by iterating `MSG_PERMUTATION`.
+const SIGMA_PERMUTATIONS: [[u8; MSG_ARRAY_SIZE]; NUM_MIX_ROUNDS] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [2, 6, 3, 10, 7, 0, 4, 13, 1, 11, 12, 5, 9, 14, 15, 8],
+    [3, 4, 10, 12, 13, 2, 7, 14, 6, 5, 9, 0, 11, 15, 8, 1],
+    [10, 7, 12, 9, 14, 3, 13, 15, 4, 0, 11, 2, 5, 8, 1, 6],
+    [12, 13, 9, 11, 15, 10, 14, 8, 7, 2, 5, 3, 0, 1, 6, 4],
+    [9, 14, 11, 5, 8, 12, 15, 1, 13, 3, 0, 10, 2, 6, 4, 7],
+    [11, 15, 5, 0, 1, 9, 8, 6, 14, 10, 2, 12, 3, 4, 7, 13],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::machine::hash::blake::blake2s;
+
+    #[test]
+    fn test_blake3_sigma_permutations() {
+        for round in 1..NUM_MIX_ROUNDS {
+            let permuted = MSG_PERMUTATION.map(|i| SIGMA_PERMUTATIONS[round - 1][i as usize]);
+            assert_eq!(SIGMA_PERMUTATIONS[round], permuted);
+        }
+        assert_eq!(IV, blake2s::COMPRESS_IV);
+    }
+}
@@ -0,0 +1,144 @@
+use super::tree::BLAKE3Input;
+use super::{BLAKE3, IV, SIGMA_PERMUTATIONS, STATE_SIZE, WORK_VECTOR_SIZE};
+use crate::machine::hash::HashPureInteger;
+
+impl HashPureInteger for BLAKE3 {
+    type Integer = u32;
+}
+
+pub trait BLAKE3Pure: HashPureInteger {
+    /// Compresses `msg_chunk` into the chaining value `state`, returning the new chaining value,
+    /// which is the first half of the output of the compression function.
+    fn compress(
+        msg_chunk: &[u8],
+        state: &mut [Self::Integer; STATE_SIZE],
+        parameters: [Self::Integer; 4],
+    ) -> [Self::Integer; STATE_SIZE];
+
+    fn mix(
+        v: &mut [Self::Integer; WORK_VECTOR_SIZE],
+        a: usize,
+        b: usize,
+        c: usize,
+        d: usize,
+        x: Self::Integer,
+        y: Self::Integer,
+    );
+}
+
+impl BLAKE3Pure for BLAKE3 {
+    fn compress(
+        msg_chunk: &[u8],
+        state: &mut [Self::Integer; STATE_SIZE],
+        parameters: [Self::Integer; 4],
+    ) -> [Self::Integer; STATE_SIZE] {
+        // Set up the work vector V
+        let mut v: [Self::Integer; WORK_VECTOR_SIZE] = [0; WORK_VECTOR_SIZE];
+
+        v[..8].copy_from_slice(&state[..STATE_SIZE]);
+        v[8..12].copy_from_slice(&IV[..4]);
+        v[12..16].copy_from_slice(&parameters);
+
+        let msg_u32_chunks = msg_chunk
+            .chunks_exact(4)
+            .map(|x| Self::Integer::from_le_bytes(x.try_into().unwrap()))
+            .collect::<Vec<_>>();
+
+        for s in SIGMA_PERMUTATIONS.iter() {
+            let m = |i: usize| msg_u32_chunks[s[i] as usize];
+            Self::mix(&mut v, 0, 4, 8, 12, m(0), m(1));
+            Self::mix(&mut v, 1, 5, 9, 13, m(2), m(3));
+            Self::mix(&mut v, 2, 6, 10, 14, m(4), m(5));
+            Self::mix(&mut v, 3, 7, 11, 15, m(6), m(7));
+
+            Self::mix(&mut v, 0, 5, 10, 15, m(8), m(9));
+            Self::mix(&mut v, 1, 6, 11, 12, m(10), m(11));
+            Self::mix(&mut v, 2, 7, 8, 13, m(12), m(13));
+            Self::mix(&mut v, 3, 4, 9, 14, m(14), m(15));
+        }
+
+        for i in 0..STATE_SIZE {
+            state[i] = v[i] ^ v[i + 8];
+        }
+
+        *state
+    }
+
+    fn mix(
+        v: &mut [Self::Integer; WORK_VECTOR_SIZE],
+        a: usize,
+        b: usize,
+        c: usize,
+        d: usize,
+        x: Self::Integer,
+        y: Self::Integer,
+    ) {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(12);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(8);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(7);
+    }
+}
+
+impl BLAKE3 {
+    /// The chaining values output by the compresses of the tree of `msg`, the last of which is
+    /// the root hash.
+    pub fn chaining_values(msg: &[u8]) -> Vec<[u32; STATE_SIZE]> {
+        let mut chaining_values = Vec::new();
+        for compress in Self::compresses(msg.len()) {
+            let mut state = match compress.input {
+                BLAKE3Input::Block { .. } if !compress.is_start() => {
+                    *chaining_values.last().unwrap()
+                }
+                _ => IV,
+            };
+            let block = compress.block(msg, &chaining_values);
+            Self::compress(&block, &mut state, compress.parameters());
+            chaining_values.push(state);
+        }
+        chaining_values
+    }
+
+    /// The unkeyed BLAKE3 hash of `msg`, with a 32-byte output.
+    pub fn hash(msg: &[u8]) -> [u8; 32] {
+        let root = *Self::chaining_values(msg).last().unwrap();
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(root) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blake3_hash() {
+        assert_eq!(
+            BLAKE3::hash(b"").to_vec(),
+            hex::decode("af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262")
+                .unwrap()
+        );
+
+        // The lengths of the official test vectors, whose inputs repeat the bytes 0..251.
+        let lengths = [
+            0, 1, 63, 64, 65, 127, 128, 129, 1023, 1024, 1025, 2048, 2049, 3072, 3073, 4096, 4097,
+            5120, 5121, 6144, 6145, 7168, 7169, 8192, 8193, 16384, 31744,
+        ];
+        for length in lengths {
+            let msg = (0..length).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+            assert_eq!(
+                BLAKE3::hash(&msg),
+                *blake3::hash(&msg).as_bytes(),
+                "length {}",
+                length
+            );
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use core::ops::Range;
+
+use super::{BLAKE3, BLOCK_LEN, CHUNK_END, CHUNK_LEN, CHUNK_START, PARENT, ROOT, STATE_SIZE};
+
+/// The message block of a compress of the BLAKE3 tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BLAKE3Input {
+    /// The block of the message starting at byte `offset`, padded with zeros to `BLOCK_LEN` bytes.
+    Block { offset: usize },
+    /// The chaining values output by the compresses of indices `left` and `right`.
+    Parent { left: usize, right: usize },
+}
+
+/// A compress of the BLAKE3 tree of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BLAKE3Compress {
+    pub input: BLAKE3Input,
+    pub counter: u64,
+    pub block_len: u32,
+    pub flags: u32,
+}
+
+impl BLAKE3Compress {
+    /// Whether the input chaining value is the IV, rather than the output of the previous
+    /// compress.
+    pub fn is_start(&self) -> bool {
+        self.flags & (CHUNK_START | PARENT) != 0
+    }
+
+    /// Whether the output is the chaining value of a chunk or of a parent.
+    pub fn is_end(&self) -> bool {
+        self.flags & (CHUNK_END | PARENT) != 0
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.flags & ROOT != 0
+    }
+
+    /// The words of the counter, the block length and the flags, which are the last row of the
+    /// work vector.
+    pub fn parameters(&self) -> [u32; 4] {
+        [
+            self.counter as u32,
+            (self.counter >> 32) as u32,
+            self.block_len,
+            self.flags,
+        ]
+    }
+
+    /// The message block, given the message and the chaining values output by the previous
+    /// compresses.
+    pub fn block(&self, msg: &[u8], chaining_values: &[[u32; STATE_SIZE]]) -> [u8; BLOCK_LEN] {
+        let mut block = [0u8; BLOCK_LEN];
+        match self.input {
+            BLAKE3Input::Block { offset } => {
+                let end = offset + self.block_len as usize;
+                block[..self.block_len as usize].copy_from_slice(&msg[offset..end]);
+            }
+            BLAKE3Input::Parent { left, right } => {
+                let words = chaining_values[left].iter().chain(&chaining_values[right]);
+                for (bytes, word) in block.chunks_exact_mut(4).zip(words) {
+                    bytes.copy_from_slice(&word.to_le_bytes());
+                }
+            }
+        }
+        block
+    }
+}
+
+impl BLAKE3 {
+    /// The compresses of the tree of a message of `length` bytes, with the children of every
+    /// parent before it. The last compress is the root.
+    pub fn compresses(length: usize) -> Vec<BLAKE3Compress> {
+        let num_chunks = length.div_ceil(CHUNK_LEN).max(1);
+        let mut compresses = Vec::new();
+        Self::subtree(length, 0..num_chunks, true, &mut compresses);
+        compresses
+    }
+
+    /// Pushes the compresses of the subtree of the chunks `chunks` and returns the index of the
+    /// compress whose output is its chaining value.
+    ///
+    /// The left subtree holds the largest power of two of chunks that leaves a chunk to the right.
+    fn subtree(
+        length: usize,
+        chunks: Range<usize>,
+        is_root: bool,
+        compresses: &mut Vec<BLAKE3Compress>,
+    ) -> usize {
+        let root_flag = if is_root { ROOT } else { 0 };
+        if chunks.len() == 1 {
+            let chunk = chunks.start;
+            let chunk_len = (length - chunk * CHUNK_LEN).min(CHUNK_LEN);
+            let num_blocks = chunk_len.div_ceil(BLOCK_LEN).max(1);
+            for block in 0..num_blocks {
+                let mut flags = 0;
+                if block == 0 {
+                    flags |= CHUNK_START;
+                }
+                if block == num_blocks - 1 {
+                    flags |= CHUNK_END | root_flag;
+                }
+                compresses.push(BLAKE3Compress {
+                    input: BLAKE3Input::Block {
+                        offset: chunk * CHUNK_LEN + block * BLOCK_LEN,
+                    },
+                    counter: chunk as u64,
+                    block_len: (chunk_len - block * BLOCK_LEN).min(BLOCK_LEN) as u32,
+                    flags,
+                });
+            }
+            return compresses.len() - 1;
+        }
+
+        let num_left_chunks = 1 << (usize::BITS - 1 - (chunks.len() - 1).leading_zeros());
+        let middle = chunks.start + num_left_chunks;
+        let left = Self::subtree(length, chunks.start..middle, false, compresses);
+        let right = Self::subtree(length, middle..chunks.end, false, compresses);
+        compresses.push(BLAKE3Compress {
+            input: BLAKE3Input::Parent { left, right },
+            counter: 0,
+            block_len: BLOCK_LEN as u32,
+            flags: PARENT | root_flag,
+        });
+        compresses.len() - 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blake3_compresses() {
+        let empty = BLAKE3::compresses(0);
+        assert_eq!(
+            empty,
+            [BLAKE3Compress {
+                input: BLAKE3Input::Block { offset: 0 },
+                counter: 0,
+                block_len: 0,
+                flags: CHUNK_START | CHUNK_END | ROOT,
+            }]
+        );
+
+        // One full chunk of 16 blocks.
+        let one_chunk = BLAKE3::compresses(CHUNK_LEN);
+        assert_eq!(one_chunk.len(), 16);
+        assert!(one_chunk[0].is_start() && !one_chunk[0].is_end());
+        assert!(one_chunk[15].is_end() && one_chunk[15].is_root());
+
+        // Five chunks, the last of one byte: the left subtree holds four chunks.
+        let compresses = BLAKE3::compresses(4 * CHUNK_LEN + 1);
+        assert_eq!(compresses.len(), 4 * 16 + 1 + 4);
+        let parents = compresses
+            .iter()
+            .enumerate()
+            .filter_map(|(i, compress)| match compress.input {
+                BLAKE3Input::Parent { left, right } => Some((i, left, right)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parents,
+            [(32, 15, 31), (65, 48, 64), (66, 32, 65), (68, 66, 67)]
+        );
+        assert_eq!(compresses[67].counter, 4);
+        assert_eq!(compresses[67].block_len, 1);
+        assert_eq!(compresses.iter().filter(|c| c.is_root()).count(), 1);
+        assert!(compresses.last().unwrap().is_root());
+    }
+}
@@ -1 +1,4 @@
+pub mod blake2;
 pub mod blake2b;
+pub mod blake2s;
+pub mod blake3;
@@ -0,0 +1,321 @@
+//! HMAC over the hash functions of the machine.
+//!
+//! `HMAC(K, m) = H((K' ^ opad) || H((K' ^ ipad) || m))`, where `K'` is the key `K`, or its digest
+//! if `K` is longer than the block size of `H`, padded with zeros to the block size, and `ipad`
+//! and `opad` repeat the bytes `0x36` and `0x5c`.
+//!
+//! All the hashes of an HMAC are computed by one instance of the AIR of `H`, whose messages are
+//! public byte registers. The padded keys of the inner and outer messages are public XORs of the
+//! bytes of `K'` with the pad bytes, and the rest of the inner and outer messages are constrained
+//! to be `m` and the inner digest.
+
+use core::fmt::Debug;
+use core::marker::PhantomData;
+
+use itertools::Itertools;
+
+use crate::chip::register::Register;
+use crate::chip::trace::writer::AirWriter;
+use crate::chip::uint::bytes::operations::value::ByteOperation;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::math::prelude::*;
+
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// A hash function whose AIR hashes messages of public byte registers.
+pub trait HashGadget<B: Builder> {
+    /// The block size of the hash function, in bytes.
+    const BLOCK_SIZE: usize;
+
+    /// The size of a digest, in bytes.
+    const DIGEST_SIZE: usize;
+
+    /// The registers of the messages hashed by one instance of the AIR.
+    type Messages: Debug + Clone;
+
+    /// Hashes messages of the given lengths, in bytes, with one instance of the AIR.
+    ///
+    /// The messages and their digests are public registers, to be written with `write_messages`
+    /// before the global instructions.
+    fn hash_messages(builder: &mut B, lengths: &[usize]) -> Self::Messages;
+
+    /// The bytes of the message of index `index`.
+    fn message_bytes(messages: &Self::Messages, index: usize) -> Vec<ByteRegister>;
+
+    /// The bytes of the digest of the message of index `index`.
+    fn digest_bytes(messages: &Self::Messages, index: usize) -> Vec<ByteRegister>;
+
+    /// Writes the values of the messages and their digests.
+    fn write_messages(
+        messages: &Self::Messages,
+        values: &[Vec<u8>],
+        writer: &mut impl AirWriter<Field = B::Field>,
+    );
+
+    /// The digest of `message`.
+    fn hash(message: &[u8]) -> Vec<u8>;
+}
+
+/// The registers of an HMAC, allocated by `BytesBuilder::hmac`.
+#[derive(Debug, Clone)]
+pub struct HMAC<B: Builder, H: HashGadget<B>> {
+    key: Vec<ByteRegister>,
+    message: Vec<ByteRegister>,
+    messages: H::Messages,
+    digest: Vec<ByteRegister>,
+    _marker: PhantomData<B>,
+}
+
+impl<L: AirParameters> BytesBuilder<L>
+where
+    L::Instruction: UintInstructions,
+{
+    /// Returns the registers of the HMAC of `message` with the key `key`, with the hash function
+    /// `H`.
+    ///
+    /// The key and the message are public registers, written together with the messages of the
+    /// hash function by `HMAC::write`.
+    pub fn hmac<H: HashGadget<Self>>(
+        &mut self,
+        key: &[ByteRegister],
+        message: &[ByteRegister],
+    ) -> HMAC<Self, H> {
+        let hashes_key = key.len() > H::BLOCK_SIZE;
+        let mut lengths = vec![
+            H::BLOCK_SIZE + message.len(),
+            H::BLOCK_SIZE + H::DIGEST_SIZE,
+        ];
+        if hashes_key {
+            lengths.insert(0, key.len());
+        }
+        let messages = H::hash_messages(self, &lengths);
+        let (inner, outer) = (lengths.len() - 2, lengths.len() - 1);
+
+        let padded_key = if hashes_key {
+            for (a, b) in key.iter().zip_eq(H::message_bytes(&messages, 0)) {
+                self.assert_equal(a, &b);
+            }
+            H::digest_bytes(&messages, 0)
+        } else {
+            key.to_vec()
+        };
+
+        let inner_bytes = H::message_bytes(&messages, inner);
+        self.assert_padded_key(&padded_key, &inner_bytes[..H::BLOCK_SIZE], IPAD);
+        for (a, b) in message.iter().zip_eq(&inner_bytes[H::BLOCK_SIZE..]) {
+            self.assert_equal(a, b);
+        }
+
+        let outer_bytes = H::message_bytes(&messages, outer);
+        self.assert_padded_key(&padded_key, &outer_bytes[..H::BLOCK_SIZE], OPAD);
+        for (a, b) in H::digest_bytes(&messages, inner)
+            .iter()
+            .zip_eq(&outer_bytes[H::BLOCK_SIZE..])
+        {
+            self.assert_equal(a, b);
+        }
+
+        HMAC {
+            key: key.to_vec(),
+            message: message.to_vec(),
+            digest: H::digest_bytes(&messages, outer),
+            messages,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Constrains `block` to the bytes of `key` padded with zeros to the length of `block`, XORed
+    /// with `pad`.
+    fn assert_padded_key(&mut self, key: &[ByteRegister], block: &[ByteRegister], pad: u8) {
+        let pad_value = L::Field::from_canonical_u8(pad);
+        let pad_register = self.constant::<ByteRegister>(&pad_value);
+        for (i, byte) in block.iter().enumerate() {
+            match key.get(i) {
+                Some(key_byte) => self.api.set_public_inputs_byte_operation(
+                    &ByteOperation::Xor(*key_byte, pad_register, *byte),
+                    &mut self.operations,
+                ),
+                None => self.assert_expression_zero(byte.expr() - pad_value),
+            }
+        }
+    }
+}
+
+impl<B: Builder, H: HashGadget<B>> HMAC<B, H> {
+    /// The bytes of the digest.
+    pub fn digest(&self) -> &[ByteRegister] {
+        &self.digest
+    }
+
+    /// The HMAC of `message` with the key `key`.
+    pub fn hash(key: &[u8], message: &[u8]) -> Vec<u8> {
+        H::hash(Self::messages(key, message).last().unwrap())
+    }
+
+    /// Writes the key, the message and the messages hashed by the AIR, which must be done before
+    /// the global instructions.
+    pub fn write(&self, key: &[u8], message: &[u8], writer: &mut impl AirWriter<Field = B::Field>) {
+        for (register, value) in self
+            .key
+            .iter()
+            .zip_eq(key)
+            .chain(self.message.iter().zip_eq(message))
+        {
+            writer.write(register, &B::Field::from_canonical_u8(*value));
+        }
+        H::write_messages(&self.messages, &Self::messages(key, message), writer);
+    }
+
+    /// The messages hashed by the AIR: the key if it is hashed, the inner and the outer message.
+    fn messages(key: &[u8], message: &[u8]) -> Vec<Vec<u8>> {
+        let mut messages = Vec::new();
+        let mut padded_key = if key.len() > H::BLOCK_SIZE {
+            messages.push(key.to_vec());
+            H::hash(key)
+        } else {
+            key.to_vec()
+        };
+        padded_key.resize(H::BLOCK_SIZE, 0);
+
+        let inner = padded_key
+            .iter()
+            .map(|byte| byte ^ IPAD)
+            .chain(message.iter().copied())
+            .collect::<Vec<_>>();
+        let outer = padded_key
+            .iter()
+            .map(|byte| byte ^ OPAD)
+            .chain(H::hash(&inner))
+            .collect();
+        messages.push(inner);
+        messages.push(outer);
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::trace::writer::data::AirWriterData;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::machine::hash::sha::sha256::SHA256;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct HMACTest;
+
+    impl AirParameters for HMACTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 418;
+        const EXTENDED_COLUMNS: usize = 912;
+    }
+
+    type HMACSHA256 = HMAC<BytesBuilder<HMACTest>, SHA256>;
+
+    // Test cases 1, 2 and 6 of RFC 4231, and an empty key and message.
+    const TEST_VECTORS: [(&[u8], &[u8], &str); 4] = [
+        (
+            &[0x0b; 20],
+            b"Hi There",
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+        ),
+        (
+            b"Jefe",
+            b"what do ya want for nothing?",
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+        ),
+        (
+            &[0xaa; 131],
+            b"Test Using Larger Than Block-Size Key - Hash Key First",
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+        ),
+        (
+            b"",
+            b"",
+            "b613679a0814d9ec772f95d778c35fc5ff1697c493715653c6c712144292c5ad",
+        ),
+    ];
+
+    fn test_hmac_sha256(key: &[u8], message: &[u8], expected_digest: &str) {
+        type L = HMACTest;
+        type C = CurtaPoseidonGoldilocksConfig;
+
+        let mut builder = BytesBuilder::<L>::new();
+        let key_registers = (0..key.len())
+            .map(|_| builder.alloc_public::<ByteRegister>())
+            .collect::<Vec<_>>();
+        let message_registers = (0..message.len())
+            .map(|_| builder.alloc_public::<ByteRegister>())
+            .collect::<Vec<_>>();
+        let hmac = builder.hmac::<SHA256>(&key_registers, &message_registers);
+
+        let num_rows = builder.trace_length().unwrap();
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+        hmac.write(key, message, &mut writer);
+        stark.air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let writer = writer_data.public_writer();
+        let digest = hmac
+            .digest()
+            .iter()
+            .map(|byte| writer.read(byte).as_canonical_u64() as u8)
+            .collect::<Vec<_>>();
+        assert_eq!(digest, hex::decode(expected_digest).unwrap());
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new("test_hmac_sha256", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_hmac_sha256_hash() {
+        for (key, message, expected_digest) in TEST_VECTORS {
+            assert_eq!(
+                HMACSHA256::hash(key, message),
+                hex::decode(expected_digest).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256_short_key() {
+        let (key, message, expected_digest) = TEST_VECTORS[0];
+        test_hmac_sha256(key, message, expected_digest);
+    }
+
+    #[test]
+    fn test_hmac_sha256_long_key() {
+        let (key, message, expected_digest) = TEST_VECTORS[2];
+        test_hmac_sha256(key, message, expected_digest);
+    }
+
+    #[test]
+    fn test_hmac_sha256_empty_key() {
+        let (key, message, expected_digest) = TEST_VECTORS[3];
+        test_hmac_sha256(key, message, expected_digest);
+    }
+}
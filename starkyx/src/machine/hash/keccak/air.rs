@@ -0,0 +1,239 @@
+use super::{
+    lane, pi_index, DIGEST_LANES, KECCAK256, NUM_LANES, NUM_ROUNDS, RATE_LANES, ROTATIONS,
+    ROUND_CONSTANTS,
+};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::memory::time::Time;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::{Register, RegisterSerializable, RegisterSized};
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
+use crate::chip::uint::util::u64_to_le_field_bytes;
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::math::prelude::*;
+
+pub trait KeccakAir<B: Builder> {
+    /// Applies one round of Keccak-f[1600] with round constant `rc` to `state`, returning the
+    /// new state.
+    fn keccak_round(
+        builder: &mut B,
+        state: &[U64Register; NUM_LANES],
+        rc: &U64Register,
+    ) -> [U64Register; NUM_LANES];
+
+    /// Hashes the padded chunks of 17 lanes, emitting a digest at every chunk flagged in
+    /// `end_bits`.
+    ///
+    /// Every row computes one round of Keccak-f[1600], so a chunk takes 24 rows. The first row of
+    /// every chunk absorbs it in the state, and the state is reset to zero after every chunk
+    /// flagged in `end_bits`. The digest of the `i`-th message is checked against the last chunk
+    /// of the message, given by the `i`-th entry of `digest_indices`.
+    fn keccak256(
+        builder: &mut B,
+        padded_chunks: &[ArrayRegister<U64Register>],
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+    ) -> Vec<ArrayRegister<U64Register>>;
+}
+
+impl<L: AirParameters> KeccakAir<BytesBuilder<L>> for KECCAK256
+where
+    L::Instruction: UintInstructions,
+{
+    fn keccak_round(
+        builder: &mut BytesBuilder<L>,
+        state: &[U64Register; NUM_LANES],
+        rc: &U64Register,
+    ) -> [U64Register; NUM_LANES] {
+        // Theta.
+        let c: [U64Register; 5] = core::array::from_fn(|x| {
+            (1..5).fold(state[lane(x, 0)], |acc, y| {
+                builder.xor(&acc, &state[lane(x, y)])
+            })
+        });
+        let d: [U64Register; 5] = core::array::from_fn(|x| {
+            let rotated = builder.rotate_left(&c[(x + 1) % 5], 1);
+            builder.xor(&c[(x + 4) % 5], &rotated)
+        });
+        let a: [U64Register; NUM_LANES] =
+            core::array::from_fn(|i| builder.xor(&state[i], &d[i % 5]));
+
+        // Rho and pi.
+        let mut b = a;
+        for y in 0..5 {
+            for x in 0..5 {
+                let i = lane(x, y);
+                b[pi_index(x, y)] = match ROTATIONS[i] {
+                    0 => a[i],
+                    offset => builder.rotate_left(&a[i], offset),
+                };
+            }
+        }
+
+        // Chi.
+        let mut out: [U64Register; NUM_LANES] = core::array::from_fn(|i| {
+            let (x, y) = (i % 5, i / 5);
+            let not_next = builder.not(&b[lane(x + 1, y)]);
+            let and = builder.and(&not_next, &b[lane(x + 2, y)]);
+            builder.xor(&b[i], &and)
+        });
+
+        // Iota.
+        out[0] = builder.xor(&out[0], rc);
+
+        out
+    }
+
+    fn keccak256(
+        builder: &mut BytesBuilder<L>,
+        padded_chunks: &[ArrayRegister<U64Register>],
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+    ) -> Vec<ArrayRegister<U64Register>> {
+        let num_chunks = padded_chunks.len();
+        assert!(num_chunks > 0, "There must be at least one chunk");
+        assert_eq!(
+            end_bits.len(),
+            num_chunks,
+            "There must be an end bit per chunk"
+        );
+        assert!(
+            padded_chunks.iter().all(|chunk| chunk.len() == RATE_LANES),
+            "A padded chunk must have {} lanes",
+            RATE_LANES
+        );
+
+        // Store the public chunks and end bits in memory, to be read at the chunk index.
+        let chunk_slice = builder.uninit_slice::<U64Register>();
+        let end_bit_slice = builder.uninit_slice::<BitRegister>();
+        for (i, (chunk, end_bit)) in padded_chunks.iter().zip(end_bits.iter()).enumerate() {
+            for (j, word) in chunk.iter().enumerate() {
+                let ptr = chunk_slice.get(i * RATE_LANES + j);
+                builder.store(&ptr, word, &Time::zero(), None, None, None);
+            }
+            builder.store(
+                &end_bit_slice.get(i),
+                end_bit,
+                &Time::zero(),
+                None,
+                None,
+                None,
+            );
+        }
+        // The bit marking the last chunk, after which the rows are inactive.
+        let last_bits = (0..num_chunks)
+            .map(|i| L::Field::from_canonical_usize((i == num_chunks - 1) as usize))
+            .collect::<Vec<_>>();
+        let last_bits = builder.constant_array::<BitRegister>(&last_bits);
+        let last_bit_slice = builder.initialize_slice(&last_bits, &Time::zero(), None);
+
+        // The round of every row, and the index of its chunk.
+        let rounds = builder.api().loop_instr(NUM_ROUNDS);
+        let round_bits = (0..NUM_ROUNDS)
+            .map(|i| rounds.get_iteration_reg(i))
+            .collect::<Vec<_>>();
+        let is_first_round = round_bits[0];
+        let is_last_round = round_bits[NUM_ROUNDS - 1];
+        let chunk_index = builder.process_id(NUM_ROUNDS, is_last_round);
+        let rc = builder.read_constant_table::<U64Register>(
+            &ROUND_CONSTANTS.map(u64_to_le_field_bytes::<L::Field>),
+            &round_bits,
+        );
+
+        // The rows of the chunks are active, and the rows after the last chunk are not. The flag
+        // is cleared after the last round of the chunk marked in `last_bit_slice`.
+        let active = builder.alloc::<BitRegister>();
+        builder.set_to_expression_first_row(&active, ArithmeticExpression::one());
+        let is_chunk_start =
+            builder.expression::<BitRegister>(active.expr() * is_first_round.expr());
+        let is_chunk_end = builder.expression::<BitRegister>(active.expr() * is_last_round.expr());
+
+        let chunk_word_index = builder.expression::<ElementRegister>(
+            chunk_index.expr() * L::Field::from_canonical_usize(RATE_LANES),
+        );
+        let chunk = (0..RATE_LANES)
+            .map(|j| {
+                let ptr = chunk_slice.get_at_shifted(chunk_word_index, j as i32);
+                let word = builder.load_filtered(&ptr, &Time::zero(), is_chunk_start);
+                // The chunk is only absorbed in the first row.
+                builder.assert_expression_zero(is_chunk_start.not_expr() * word.expr());
+                word
+            })
+            .collect::<Vec<_>>();
+        let end_bit = builder.load_filtered(
+            &end_bit_slice.get_at(chunk_index),
+            &Time::zero(),
+            is_chunk_end,
+        );
+        let last_bit = builder.load_filtered(
+            &last_bit_slice.get_at(chunk_index),
+            &Time::zero(),
+            is_chunk_end,
+        );
+        let is_digest = builder.expression::<BitRegister>(is_chunk_end.expr() * end_bit.expr());
+
+        // The state carried from the previous row, with the chunk absorbed in the first round.
+        let state = builder.alloc_array::<U64Register>(NUM_LANES);
+        let input: [U64Register; NUM_LANES] = core::array::from_fn(|i| match chunk.get(i) {
+            Some(word) => builder.xor(&state.get(i), word),
+            None => state.get(i),
+        });
+        let output = Self::keccak_round(builder, &input, &rc);
+
+        // The state starts at zero, and is reset to zero after the last chunk of every message.
+        let zero =
+            ArithmeticExpression::from_constant_vec(vec![L::Field::ZERO; U64Register::size_of()]);
+        for (i, word) in output.iter().enumerate() {
+            builder.set_to_expression_first_row(&state.get(i), zero.clone());
+            builder.set_to_expression_transition(
+                &state.get(i).next(),
+                is_digest.not_expr() * word.expr(),
+            );
+        }
+        builder.set_to_expression_transition(
+            &active.next(),
+            active.expr() * (ArithmeticExpression::one() - is_last_round.expr() * last_bit.expr()),
+        );
+
+        // Emit the digests and check that there is one per message.
+        let digest_slice = builder.uninit_slice::<U64Register>();
+        for (j, word) in output.iter().take(DIGEST_LANES).enumerate() {
+            builder.store_filtered(
+                &digest_slice.get(j),
+                *word,
+                &Time::from_element(chunk_index),
+                is_digest,
+            );
+        }
+        let num_digests = builder.alloc::<ElementRegister>();
+        builder.set_to_expression_first_row(&num_digests, ArithmeticExpression::zero());
+        builder.set_to_expression_transition(
+            &num_digests.next(),
+            num_digests.expr() + is_digest.expr(),
+        );
+        builder.assert_expression_zero_last_row(
+            num_digests.expr() + is_digest.expr() - num_messages.expr(),
+        );
+
+        digest_indices
+            .iter()
+            .map(|digest_index| {
+                let digest = builder.alloc_array_public::<U64Register>(DIGEST_LANES);
+                for (j, word) in digest.iter().enumerate() {
+                    builder.free(
+                        &digest_slice.get(j),
+                        word,
+                        &Time::from_element(digest_index),
+                    );
+                }
+                digest
+            })
+            .collect()
+    }
+}
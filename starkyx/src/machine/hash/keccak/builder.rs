@@ -0,0 +1,233 @@
+use super::air::KeccakAir;
+use super::{KECCAK256, NUM_LANES, NUM_ROUNDS, RATE_LANES};
+use crate::chip::builder::padding::PaddingPolicy;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U64Register;
+use crate::chip::{AirParameters, ColumnBudget, ResourceReport};
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::prelude::Builder;
+
+pub trait KeccakBuilder: Builder {
+    fn keccak256<K: KeccakAir<Self>>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<U64Register>],
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_indices: &ArrayRegister<ElementRegister>,
+        num_messages: &ElementRegister,
+    ) -> Vec<ArrayRegister<U64Register>> {
+        K::keccak256(self, padded_chunks, end_bits, digest_indices, num_messages)
+    }
+}
+
+impl<B: Builder> KeccakBuilder for B {}
+
+impl KECCAK256 {
+    /// The number of rows of the trace of the Keccak-256 machine hashing `num_chunks` padded
+    /// chunks. The rows after the last chunk are inactive.
+    pub fn rows_for(num_chunks: usize) -> usize {
+        Self::rows_for_policy(num_chunks, PaddingPolicy::NextPowerOfTwo)
+    }
+
+    /// The number of rows of the trace of the Keccak-256 machine hashing `num_chunks` padded
+    /// chunks, with the rows after the last chunk padded according to `policy`.
+    pub fn rows_for_policy(num_chunks: usize, policy: PaddingPolicy) -> usize {
+        policy.padded_length(num_chunks * NUM_ROUNDS)
+    }
+
+    /// The columns needed by the Keccak-256 machine hashing `num_chunks` padded chunks, to be
+    /// used as the column constants of `L`.
+    pub fn required_columns<L: AirParameters>(num_chunks: usize) -> ColumnBudget
+    where
+        L::Instruction: UintInstructions,
+    {
+        let mut builder = BytesBuilder::<L>::new();
+        let padded_chunks = (0..num_chunks)
+            .map(|_| builder.alloc_array_public::<U64Register>(RATE_LANES))
+            .collect::<Vec<_>>();
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_chunks);
+        let digest_indices = builder.alloc_array_public::<ElementRegister>(num_chunks);
+        let num_messages = builder.alloc_public::<ElementRegister>();
+        builder.keccak256::<KECCAK256>(&padded_chunks, &end_bits, &digest_indices, &num_messages);
+
+        builder.column_budget()
+    }
+
+    /// The resources used by a single call to `keccak_round`.
+    ///
+    /// A round does 76 xors, 25 nots and 25 ands of 64-bit words, which take 8 free columns each
+    /// for the result, and 29 rotations, which take 24 for the result and the shifted bytes, for
+    /// a total of 1704 free columns. Each of the 1240 byte operations looked up takes 3 extended
+    /// columns for its digest, and every two of them take 3 more for an accumulator, for a total
+    /// of 5580 extended columns.
+    pub fn round_cost<L: AirParameters>() -> ResourceReport
+    where
+        L::Instruction: UintInstructions,
+    {
+        let new_builder = || {
+            let mut builder = BytesBuilder::<L>::new();
+            let values = builder.alloc_array::<U64Register>(NUM_LANES + 1);
+            (builder, values)
+        };
+
+        let (baseline, _) = new_builder();
+        let (mut builder, values) = new_builder();
+        let num_constraints = builder.api.constraints.len();
+        let num_byte_operations = builder.operations.trace_operations.len();
+        let state: [U64Register; NUM_LANES] = core::array::from_fn(|i| values.get(i));
+        <Self as KeccakAir<BytesBuilder<L>>>::keccak_round(
+            &mut builder,
+            &state,
+            &values.get(NUM_LANES),
+        );
+        let num_constraints = builder.api.constraints.len() - num_constraints;
+        let num_byte_operations = builder.operations.trace_operations.len() - num_byte_operations;
+
+        ResourceReport {
+            columns: builder.column_budget() - baseline.column_budget(),
+            num_constraints,
+            num_byte_operations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::util::timing::TimingTree;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::uint::operations::instruction::UintInstruction;
+    use crate::chip::uint::util::u64_to_le_field_bytes;
+    use crate::machine::hash::keccak::BLOCK_SIZE;
+    use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
+    use crate::math::prelude::*;
+    use crate::plonky2::stark::config::CurtaPoseidonGoldilocksConfig;
+    use crate::prelude::{AirWriter, AirWriterData};
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct KeccakTest;
+
+    impl AirParameters for KeccakTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = UintInstruction;
+
+        const NUM_FREE_COLUMNS: usize = 2240;
+        const EXTENDED_COLUMNS: usize = 6400;
+    }
+
+    #[test]
+    fn test_keccak256() {
+        type C = CurtaPoseidonGoldilocksConfig;
+        type L = KeccakTest;
+        type F = GoldilocksField;
+
+        let msgs = [
+            b"".to_vec(),
+            b"abc".to_vec(),
+            (0..200).map(|i| (i * 7 + 3) as u8).collect::<Vec<_>>(),
+        ];
+        let padded_msgs = msgs
+            .iter()
+            .map(|msg| KECCAK256::pad(msg))
+            .collect::<Vec<_>>();
+        let num_chunks = padded_msgs
+            .iter()
+            .map(|padded_msg| padded_msg.len() / BLOCK_SIZE)
+            .sum::<usize>();
+        assert_eq!(num_chunks, 4);
+        let num_rows = KECCAK256::rows_for(num_chunks);
+        assert_eq!(num_rows, 128);
+
+        let mut builder = BytesBuilder::<L>::new();
+        let padded_chunks = (0..num_chunks)
+            .map(|_| builder.alloc_array_public::<U64Register>(RATE_LANES))
+            .collect::<Vec<_>>();
+        let end_bits = builder.alloc_array_public::<BitRegister>(num_chunks);
+        let digest_indices = builder.alloc_array_public::<ElementRegister>(msgs.len());
+        let num_messages = builder.alloc_public::<ElementRegister>();
+        let digests = builder.keccak256::<KECCAK256>(
+            &padded_chunks,
+            &end_bits,
+            &digest_indices,
+            &num_messages,
+        );
+        assert_eq!(digests.len(), msgs.len());
+
+        let stark = builder.build::<C, 2>(num_rows);
+
+        let mut writer_data = AirWriterData::new(&stark.air_data, num_rows);
+        let mut writer = writer_data.public_writer();
+
+        writer.write(&num_messages, &F::from_canonical_usize(msgs.len()));
+        let mut chunk_index = 0;
+        for (i, (msg, padded_msg)) in msgs.iter().zip(padded_msgs.iter()).enumerate() {
+            let blocks = padded_msg.chunks_exact(BLOCK_SIZE).collect::<Vec<_>>();
+            for (j, block) in blocks.iter().enumerate() {
+                writer.write_array(
+                    &padded_chunks[chunk_index],
+                    KECCAK256::block_lanes(block).map(u64_to_le_field_bytes::<F>),
+                );
+                let is_end = j == blocks.len() - 1;
+                writer.write(
+                    &end_bits.get(chunk_index),
+                    &F::from_canonical_usize(is_end as usize),
+                );
+                chunk_index += 1;
+            }
+            writer.write(
+                &digest_indices.get(i),
+                &F::from_canonical_usize(chunk_index - 1),
+            );
+
+            let digest = KECCAK256::absorb(padded_msg);
+            let mut digest_bytes = Vec::new();
+            for word in digest {
+                digest_bytes.extend_from_slice(&word.to_le_bytes());
+            }
+            assert_eq!(digest_bytes, KECCAK256::hash(msg));
+            writer.write_array(&digests[i], digest.map(u64_to_le_field_bytes::<F>));
+        }
+
+        stark.air_data.write_global_instructions(&mut writer);
+        for mut chunk in writer_data.chunks(num_rows) {
+            for i in 0..num_rows {
+                let mut writer = chunk.window_writer(i);
+                stark.air_data.write_trace_instructions(&mut writer);
+            }
+        }
+
+        let (trace, public) = (writer_data.trace, writer_data.public);
+        let mut timing = TimingTree::new("test_keccak256", log::Level::Debug);
+        let proof = stark.prove(&trace, &public, &mut timing).unwrap();
+        stark.verify(proof, &public).unwrap();
+    }
+
+    #[test]
+    fn test_keccak256_required_columns() {
+        type L = KeccakTest;
+
+        let budget = KECCAK256::required_columns::<L>(4);
+        assert!(budget.fits::<L>());
+        assert_eq!(budget.num_arithmetic_columns, 0);
+    }
+
+    #[test]
+    fn test_keccak_round_cost() {
+        let cost = KECCAK256::round_cost::<KeccakTest>();
+        assert_eq!(
+            cost.columns,
+            ColumnBudget {
+                num_arithmetic_columns: 0,
+                num_free_columns: 1704,
+                extended_columns: 5580,
+            }
+        );
+        assert_eq!(cost.num_byte_operations, 1240);
+        assert!(cost.num_constraints > 0);
+    }
+}
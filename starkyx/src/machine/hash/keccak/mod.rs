@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+pub mod air;
+pub mod builder;
+pub mod pure;
+
+/// Keccak-256, the Keccak sponge over Keccak-f[1600] with a rate of 136 bytes, the original
+/// `0x01` padding and a 32-byte output.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct KECCAK256;
+
+/// The number of 64-bit lanes of the Keccak-f[1600] state.
+pub const NUM_LANES: usize = 25;
+/// The number of lanes absorbed from every block of the message.
+pub const RATE_LANES: usize = 17;
+/// The number of bytes of a block.
+pub const BLOCK_SIZE: usize = RATE_LANES * 8;
+/// The number of lanes of the digest.
+pub const DIGEST_LANES: usize = 4;
+/// The number of rounds of Keccak-f[1600]. The AIR computes one round in every row.
+pub const NUM_ROUNDS: usize = 24;
+
+/// The round constants of the iota step.
+pub const ROUND_CONSTANTS: [u64; NUM_ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The rotation offsets of the rho step, with the lane `(x, y)` at index `x + 5 * y`.
+pub const ROTATIONS: [usize; NUM_LANES] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+/// The index of the lane `(x, y)` in the state.
+const fn lane(x: usize, y: usize) -> usize {
+    x % 5 + 5 * (y % 5)
+}
+
+/// The index the pi step moves the lane `(x, y)` to.
+const fn pi_index(x: usize, y: usize) -> usize {
+    lane(y, 2 * x + 3 * y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Derives the rotation offsets as in section 3.2.2 of FIPS 202: the offset of the `t`-th
+    /// lane on the orbit of `(1, 0)` under `(x, y) -> (y, 2x + 3y)` is `(t + 1)(t + 2) / 2`.
+    #[test]
+    fn test_rotations() {
+        let mut offsets = [0; NUM_LANES];
+        let (mut x, mut y) = (1, 0);
+        for t in 0..24 {
+            offsets[lane(x, y)] = ((t + 1) * (t + 2) / 2) % 64;
+            (x, y) = (y, (2 * x + 3 * y) % 5);
+        }
+        assert_eq!(offsets, ROTATIONS);
+    }
+
+    /// Derives the round constants from the linear feedback shift register of section 3.2.5 of
+    /// FIPS 202.
+    #[test]
+    fn test_round_constants() {
+        let mut r = 1u8;
+        let mut rc_bit = || {
+            let bit = r & 1;
+            r = if r & 0x80 != 0 {
+                (r << 1) ^ 0x71
+            } else {
+                r << 1
+            };
+            bit as u64
+        };
+        for (i, constant) in ROUND_CONSTANTS.iter().enumerate() {
+            let mut expected = 0u64;
+            for j in 0..7 {
+                expected |= rc_bit() << ((1 << j) - 1);
+            }
+            assert_eq!(*constant, expected, "round {i}");
+        }
+    }
+
+    #[test]
+    fn test_pi_is_permutation() {
+        let mut seen = [false; NUM_LANES];
+        for y in 0..5 {
+            for x in 0..5 {
+                let index = pi_index(x, y);
+                assert!(!seen[index]);
+                seen[index] = true;
+            }
+        }
+    }
+}
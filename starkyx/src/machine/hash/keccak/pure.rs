@@ -0,0 +1,152 @@
+use super::{
+    lane, pi_index, BLOCK_SIZE, DIGEST_LANES, KECCAK256, NUM_LANES, RATE_LANES, ROTATIONS,
+    ROUND_CONSTANTS,
+};
+use crate::machine::hash::HashPureInteger;
+
+impl HashPureInteger for KECCAK256 {
+    type Integer = u64;
+}
+
+pub trait KeccakPure: HashPureInteger {
+    /// Applies one round of Keccak-f[1600] with round constant `rc` to `state`.
+    fn round(state: &mut [Self::Integer; NUM_LANES], rc: Self::Integer);
+
+    /// Applies the Keccak-f[1600] permutation to `state`.
+    fn keccak_f(state: &mut [Self::Integer; NUM_LANES]);
+}
+
+impl KeccakPure for KECCAK256 {
+    fn round(state: &mut [Self::Integer; NUM_LANES], rc: Self::Integer) {
+        // Theta.
+        let c: [u64; 5] =
+            core::array::from_fn(|x| (0..5).fold(0, |acc, y| acc ^ state[lane(x, y)]));
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                state[lane(x, y)] ^= d;
+            }
+        }
+
+        // Rho and pi.
+        let mut b = [0u64; NUM_LANES];
+        for y in 0..5 {
+            for x in 0..5 {
+                b[pi_index(x, y)] = state[lane(x, y)].rotate_left(ROTATIONS[lane(x, y)] as u32);
+            }
+        }
+
+        // Chi.
+        for y in 0..5 {
+            for x in 0..5 {
+                state[lane(x, y)] = b[lane(x, y)] ^ (!b[lane(x + 1, y)] & b[lane(x + 2, y)]);
+            }
+        }
+
+        // Iota.
+        state[0] ^= rc;
+    }
+
+    fn keccak_f(state: &mut [Self::Integer; NUM_LANES]) {
+        for rc in ROUND_CONSTANTS {
+            Self::round(state, rc);
+        }
+    }
+}
+
+impl KECCAK256 {
+    /// The number of blocks of the padded message of length `msg_len`.
+    pub const fn num_blocks(msg_len: usize) -> usize {
+        msg_len / BLOCK_SIZE + 1
+    }
+
+    /// Pads `msg` to a whole number of blocks. The padding always takes at least one byte, so a
+    /// message whose length is a multiple of the block size gets an extra block.
+    pub fn pad(msg: &[u8]) -> Vec<u8> {
+        let mut padded_msg = msg.to_vec();
+        padded_msg.push(0x01);
+        padded_msg.resize(Self::num_blocks(msg.len()) * BLOCK_SIZE, 0);
+        *padded_msg.last_mut().unwrap() |= 0x80;
+        padded_msg
+    }
+
+    /// The little-endian lanes of a block of the padded message.
+    pub fn block_lanes(block: &[u8]) -> [u64; RATE_LANES] {
+        assert_eq!(
+            block.len(),
+            BLOCK_SIZE,
+            "A block must have {} bytes",
+            BLOCK_SIZE
+        );
+        core::array::from_fn(|i| u64::from_le_bytes(block[8 * i..8 * (i + 1)].try_into().unwrap()))
+    }
+
+    /// Absorbs the padded message `padded_msg` and returns the lanes of the digest.
+    pub fn absorb(padded_msg: &[u8]) -> [u64; DIGEST_LANES] {
+        let mut state = [0u64; NUM_LANES];
+        for block in padded_msg.chunks_exact(BLOCK_SIZE) {
+            for (word, value) in state.iter_mut().zip(Self::block_lanes(block)) {
+                *word ^= value;
+            }
+            Self::keccak_f(&mut state);
+        }
+        core::array::from_fn(|i| state[i])
+    }
+
+    /// The Keccak-256 digest of `msg`.
+    pub fn hash(msg: &[u8]) -> [u8; 32] {
+        let lanes = Self::absorb(&Self::pad(msg));
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(8).zip(lanes) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keccak256_hash() {
+        // Known vectors of Keccak-256, as computed by the `tiny-keccak` crate.
+        let expected = [
+            (
+                b"".to_vec(),
+                "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            ),
+            (
+                b"abc".to_vec(),
+                "4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45",
+            ),
+            (
+                b"The quick brown fox jumps over the lazy dog".to_vec(),
+                "4d741b6f1eb29cb2a9b9911c82f56fa8d73b04959d3d9d222895df6c0b28aa15",
+            ),
+            (
+                (0..136).map(|i| (i * 7 + 3) as u8).collect(),
+                "742061bcad767ed4c4f5883b1dcb1aad11afdcc140dc469d953759b127b9f9ed",
+            ),
+            (
+                (0..200).map(|i| (i * 7 + 3) as u8).collect(),
+                "66d2cdf3ab4c5bd3c75add9b60b14ac5b7789534fa2da3f348853b847359a3a0",
+            ),
+        ];
+        for (msg, digest) in expected {
+            assert_eq!(KECCAK256::hash(&msg).to_vec(), hex::decode(digest).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_keccak256_pad() {
+        for (msg_len, num_blocks) in [(0, 1), (135, 1), (136, 2), (200, 2), (272, 3)] {
+            let padded_msg = KECCAK256::pad(&vec![0xff; msg_len]);
+            assert_eq!(padded_msg.len(), num_blocks * BLOCK_SIZE);
+            assert_eq!(KECCAK256::num_blocks(msg_len), num_blocks);
+            assert_eq!(*padded_msg.last().unwrap() & 0x80, 0x80);
+        }
+        // A single byte of padding holds both the first and the last bit.
+        assert_eq!(KECCAK256::pad(&[0; 135])[135], 0x81);
+    }
+}
@@ -8,6 +8,9 @@ use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::Register;
 
 pub mod blake;
+pub mod hmac;
+pub mod keccak;
+pub mod poseidon;
 pub mod sha;
 
 pub trait HashPureInteger {
@@ -0,0 +1,98 @@
+use super::{
+    is_full_round, mds_entry, round_constants, DIGEST_SIZE, NUM_ROUNDS, POSEIDON, RATE, WIDTH,
+};
+use crate::chip::arithmetic::expression::ArithmeticExpression;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::machine::builder::Builder;
+use crate::math::prelude::*;
+
+pub trait PoseidonAir<B: Builder> {
+    /// Applies the Poseidon permutation to `state`, returning the new state.
+    fn permute(builder: &mut B, state: &[ElementRegister; WIDTH]) -> [ElementRegister; WIDTH];
+
+    /// Hashes `inputs` with the sponge of the permutation, with no padding, returning the digest.
+    ///
+    /// All the permutations are computed in the row of the inputs.
+    fn poseidon_hash(builder: &mut B, inputs: &[ElementRegister])
+        -> [ElementRegister; DIGEST_SIZE];
+}
+
+impl POSEIDON {
+    /// Applies the round `round` to `state`, returning the state after the MDS layer.
+    ///
+    /// The cube of every input of an S-box is allocated so that the constraints are of degree
+    /// three, while the S-box outputs only appear in the expressions of the new state.
+    fn round<B: Builder>(
+        builder: &mut B,
+        state: [ArithmeticExpression<B::Field>; WIDTH],
+        round: usize,
+    ) -> [ElementRegister; WIDTH] {
+        let num_sboxes = if is_full_round(round) { WIDTH } else { 1 };
+        let sbox_outputs = state
+            .into_iter()
+            .zip(round_constants(round))
+            .enumerate()
+            .map(|(i, (x, c))| {
+                let x = x + B::Field::from_canonical_u64(c);
+                if i < num_sboxes {
+                    let cube =
+                        builder.expression::<ElementRegister>(x.clone() * x.clone() * x.clone());
+                    cube.expr() * cube.expr() * x
+                } else {
+                    x
+                }
+            })
+            .collect::<Vec<_>>();
+
+        core::array::from_fn(|i| {
+            let mixed = sbox_outputs
+                .iter()
+                .enumerate()
+                .fold(ArithmeticExpression::zero(), |acc, (j, y)| {
+                    acc + y.clone() * B::Field::from_canonical_u64(mds_entry(i, j))
+                });
+            builder.expression(mixed)
+        })
+    }
+
+    fn permute_expressions<B: Builder>(
+        builder: &mut B,
+        state: [ArithmeticExpression<B::Field>; WIDTH],
+    ) -> [ElementRegister; WIDTH] {
+        let mut state = Self::round(builder, state, 0);
+        for round in 1..NUM_ROUNDS {
+            state = Self::round(builder, state.map(|x| x.expr()), round);
+        }
+        state
+    }
+}
+
+impl<B: Builder> PoseidonAir<B> for POSEIDON {
+    fn permute(builder: &mut B, state: &[ElementRegister; WIDTH]) -> [ElementRegister; WIDTH] {
+        Self::permute_expressions(builder, state.map(|x| x.expr()))
+    }
+
+    fn poseidon_hash(
+        builder: &mut B,
+        inputs: &[ElementRegister],
+    ) -> [ElementRegister; DIGEST_SIZE] {
+        if inputs.is_empty() {
+            return core::array::from_fn(|_| builder.constant(&B::Field::ZERO));
+        }
+
+        let mut state: [ArithmeticExpression<B::Field>; WIDTH] =
+            core::array::from_fn(|_| ArithmeticExpression::zero());
+        let mut output = None;
+        for chunk in inputs.chunks(RATE) {
+            for (x, input) in state.iter_mut().zip(chunk) {
+                *x = input.expr();
+            }
+            let permuted = Self::permute_expressions(builder, state);
+            state = permuted.map(|x| x.expr());
+            output = Some(permuted);
+        }
+        let output = output.unwrap();
+        core::array::from_fn(|i| output[i])
+    }
+}
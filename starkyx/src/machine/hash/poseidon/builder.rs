@@ -0,0 +1,76 @@
+use super::air::PoseidonAir;
+use super::DIGEST_SIZE;
+use crate::chip::register::element::ElementRegister;
+use crate::prelude::Builder;
+
+pub trait PoseidonBuilder: Builder {
+    fn poseidon_hash<P: PoseidonAir<Self>>(
+        &mut self,
+        inputs: &[ElementRegister],
+    ) -> [ElementRegister; DIGEST_SIZE] {
+        P::poseidon_hash(self, inputs)
+    }
+}
+
+impl<B: Builder> PoseidonBuilder for B {}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::hash::poseidon::PoseidonHash;
+    use plonky2::plonk::config::Hasher;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::AirParameters;
+    use crate::machine::hash::poseidon::POSEIDON;
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct PoseidonTest;
+
+    impl AirParameters for PoseidonTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 967;
+    }
+
+    #[test]
+    fn test_poseidon_hash() {
+        type F = GoldilocksField;
+        type L = PoseidonTest;
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        // Eleven inputs take two permutations, of 478 columns each.
+        let num_inputs = 11;
+        let mut builder = AirBuilder::<L>::new();
+        let inputs = builder.alloc_array::<ElementRegister>(num_inputs);
+        let digest = builder.poseidon_hash::<POSEIDON>(&inputs.iter().collect::<Vec<_>>());
+
+        let (air, trace_data) = builder.build();
+
+        let num_rows = 1 << 4;
+        let generator = ArithmeticGenerator::<L>::new(trace_data, num_rows);
+        let writer = generator.new_writer();
+        for i in 0..num_rows {
+            let input_values = F::rand_vec(num_inputs);
+            writer.write_array(&inputs, &input_values, i);
+            writer.write_row_instructions(&generator.air_data, i);
+
+            let expected = POSEIDON::hash_no_pad(&input_values);
+            assert_eq!(expected, PoseidonHash::hash_no_pad(&input_values).elements);
+            for (register, value) in digest.iter().zip(expected) {
+                assert_eq!(writer.read(register, i), value);
+            }
+        }
+
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(num_rows);
+
+        let public = writer.public().unwrap().clone();
+        test_starky(&stark, &config, &generator, &public);
+    }
+}
@@ -0,0 +1,38 @@
+use super::air::PoseidonAir;
+use super::{DIGEST_SIZE, POSEIDON};
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::machine::builder::Builder;
+use crate::machine::merkle::MerkleHash;
+
+/// The nodes are Poseidon digests, and the parent of two nodes is the digest of the eight
+/// elements of the left node followed by the right node, as in `PoseidonHash::two_to_one`.
+impl<B: Builder> MerkleHash<B> for POSEIDON {
+    type Node = [ElementRegister; DIGEST_SIZE];
+    type NodeValue = [B::Field; DIGEST_SIZE];
+
+    fn hash_nodes(builder: &mut B, left: &Self::Node, right: &Self::Node) -> Self::Node {
+        let inputs = left.iter().chain(right.iter()).copied().collect::<Vec<_>>();
+        Self::poseidon_hash(builder, &inputs)
+    }
+
+    fn select_node(
+        builder: &mut B,
+        bit: BitRegister,
+        true_node: &Self::Node,
+        false_node: &Self::Node,
+    ) -> Self::Node {
+        core::array::from_fn(|i| builder.select(bit, &true_node[i], &false_node[i]))
+    }
+
+    fn assert_nodes_equal(builder: &mut B, a: &Self::Node, b: &Self::Node) {
+        for (a, b) in a.iter().zip(b.iter()) {
+            builder.assert_equal(a, b);
+        }
+    }
+
+    fn hash_node_values(left: &Self::NodeValue, right: &Self::NodeValue) -> Self::NodeValue {
+        let inputs = left.iter().chain(right.iter()).copied().collect::<Vec<_>>();
+        Self::hash_no_pad(&inputs)
+    }
+}
@@ -0,0 +1,51 @@
+use plonky2::field::goldilocks_field::GoldilocksField;
+use plonky2::hash::poseidon::{Poseidon, ALL_ROUND_CONSTANTS};
+use serde::{Deserialize, Serialize};
+
+pub mod air;
+pub mod builder;
+pub mod merkle;
+pub mod pure;
+
+/// The Poseidon permutation over the Goldilocks field with a state of 12 elements, four full
+/// rounds on either side of 22 partial rounds and the S-box `x^7`, and its sponge hash with a
+/// rate of 8 elements and a digest of 4.
+///
+/// The round constants and the MDS matrix are those of plonky2, so the digests agree with
+/// `PoseidonHash::hash_no_pad`. plonky2 only fixes the constants of a state of 12 elements.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct POSEIDON;
+
+/// The number of elements of the state.
+pub const WIDTH: usize = 12;
+/// The number of elements of the state overwritten by every chunk of the inputs.
+pub const RATE: usize = 8;
+/// The number of elements of the digest.
+pub const DIGEST_SIZE: usize = 4;
+/// The number of full rounds before, and after, the partial rounds.
+pub const HALF_NUM_FULL_ROUNDS: usize = 4;
+/// The number of partial rounds, which only apply the S-box to the first element.
+pub const NUM_PARTIAL_ROUNDS: usize = 22;
+/// The number of rounds of the permutation.
+pub const NUM_ROUNDS: usize = 2 * HALF_NUM_FULL_ROUNDS + NUM_PARTIAL_ROUNDS;
+
+/// The constants added to the state at the start of the round `round`.
+fn round_constants(round: usize) -> [u64; WIDTH] {
+    core::array::from_fn(|i| ALL_ROUND_CONSTANTS[i + WIDTH * round])
+}
+
+/// Whether the round `round` applies the S-box to the whole state.
+const fn is_full_round(round: usize) -> bool {
+    round < HALF_NUM_FULL_ROUNDS || round >= HALF_NUM_FULL_ROUNDS + NUM_PARTIAL_ROUNDS
+}
+
+/// The coefficient of the `j`-th element of the state in the `i`-th element of the MDS layer,
+/// a circulant matrix plus a diagonal one.
+fn mds_entry(i: usize, j: usize) -> u64 {
+    let circ = <GoldilocksField as Poseidon>::MDS_MATRIX_CIRC[(j + WIDTH - i) % WIDTH];
+    if i == j {
+        circ + <GoldilocksField as Poseidon>::MDS_MATRIX_DIAG[i]
+    } else {
+        circ
+    }
+}
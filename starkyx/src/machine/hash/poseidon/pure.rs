@@ -0,0 +1,69 @@
+use super::{
+    is_full_round, mds_entry, round_constants, DIGEST_SIZE, NUM_ROUNDS, POSEIDON, RATE, WIDTH,
+};
+use crate::math::prelude::*;
+
+impl POSEIDON {
+    /// Applies the Poseidon permutation to `state`.
+    pub fn permute<F: Field>(state: &mut [F; WIDTH]) {
+        for round in 0..NUM_ROUNDS {
+            for (x, c) in state.iter_mut().zip(round_constants(round)) {
+                *x += F::from_canonical_u64(c);
+            }
+            let num_sboxes = if is_full_round(round) { WIDTH } else { 1 };
+            for x in state.iter_mut().take(num_sboxes) {
+                *x = x.pow(7);
+            }
+            let mixed: [F; WIDTH] = core::array::from_fn(|i| {
+                state
+                    .iter()
+                    .enumerate()
+                    .map(|(j, x)| *x * F::from_canonical_u64(mds_entry(i, j)))
+                    .sum()
+            });
+            *state = mixed;
+        }
+    }
+
+    /// Hashes `inputs` with the sponge of the permutation, with no padding.
+    ///
+    /// Every chunk of `RATE` inputs overwrites the first elements of the state before a
+    /// permutation, and the digest is the first `DIGEST_SIZE` elements of the final state.
+    pub fn hash_no_pad<F: Field>(inputs: &[F]) -> [F; DIGEST_SIZE] {
+        let mut state = [F::ZERO; WIDTH];
+        for chunk in inputs.chunks(RATE) {
+            state[..chunk.len()].copy_from_slice(chunk);
+            Self::permute(&mut state);
+        }
+        core::array::from_fn(|i| state[i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::goldilocks_field::GoldilocksField;
+    use plonky2::hash::poseidon::{Poseidon, PoseidonHash};
+    use plonky2::plonk::config::Hasher;
+
+    use super::*;
+
+    #[test]
+    fn test_poseidon_pure() {
+        type F = GoldilocksField;
+
+        for _ in 0..10 {
+            let mut state = F::rand_array::<WIDTH>();
+            let expected = F::poseidon(state);
+            POSEIDON::permute(&mut state);
+            assert_eq!(state, expected);
+        }
+
+        for len in [0, 1, 7, 8, 9, 16, 21] {
+            let inputs = F::rand_vec(len);
+            assert_eq!(
+                POSEIDON::hash_no_pad(&inputs),
+                PoseidonHash::hash_no_pad(&inputs).elements
+            );
+        }
+    }
+}
@@ -35,7 +35,17 @@ pub trait SHAPure<const CYCLE_LENGTH: usize>:
     fn pre_process(chunk: &[Self::Integer]) -> [Self::Integer; CYCLE_LENGTH];
 
     /// Process a chunk of `Self::Integer` values.
-    fn process(hash: [Self::Integer; 8], w: &[Self::Integer; CYCLE_LENGTH]) -> [Self::Integer; 8];
+    fn process(hash: [Self::Integer; 8], w: &[Self::Integer; CYCLE_LENGTH]) -> [Self::Integer; 8] {
+        Self::process_with_round_constants(hash, w, &Self::ROUND_CONSTANTS)
+    }
+
+    /// Process a chunk of `Self::Integer` values using `round_constants` in place of
+    /// `Self::ROUND_CONSTANTS`.
+    fn process_with_round_constants(
+        hash: [Self::Integer; 8],
+        w: &[Self::Integer; CYCLE_LENGTH],
+        round_constants: &[Self::Integer; CYCLE_LENGTH],
+    ) -> [Self::Integer; 8];
 
     /// Decode a digest encoded as a string to a vector of `Self::Integer` values.
     fn decode(digest: &str) -> [Self::Integer; 8];
@@ -102,6 +112,28 @@ pub trait SHAir<B: Builder, const CYCLE_LENGTH: usize>:
         end_bits: &ArrayRegister<BitRegister>,
         digest_bits: &ArrayRegister<BitRegister>,
         digest_indices: ArrayRegister<ElementRegister>,
+    ) -> Vec<Self::StateVariable> {
+        Self::sha_with_round_constants(
+            builder,
+            padded_chunks,
+            end_bits,
+            digest_bits,
+            digest_indices,
+            &Self::ROUND_CONSTANTS,
+        )
+    }
+
+    /// The SHA AIR using `round_constants` in place of `Self::ROUND_CONSTANTS`.
+    ///
+    /// This allows to prove variants of the hash function, such as those of test vectors with
+    /// modified constants.
+    fn sha_with_round_constants(
+        builder: &mut B,
+        padded_chunks: &[ArrayRegister<Self::IntRegister>],
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: ArrayRegister<ElementRegister>,
+        round_constants: &[Self::Integer; CYCLE_LENGTH],
     ) -> Vec<Self::StateVariable> {
         let data = Self::data(
             builder,
@@ -109,6 +141,7 @@ pub trait SHAir<B: Builder, const CYCLE_LENGTH: usize>:
             end_bits,
             digest_bits,
             digest_indices,
+            round_constants,
         );
         let w_i = Self::preprocessing(builder, &data);
         Self::processing(builder, w_i, &data)
@@ -120,6 +153,7 @@ pub trait SHAir<B: Builder, const CYCLE_LENGTH: usize>:
         end_bits: &ArrayRegister<BitRegister>,
         digest_bits: &ArrayRegister<BitRegister>,
         digest_indices: ArrayRegister<ElementRegister>,
+        round_constants: &[Self::Integer; CYCLE_LENGTH],
     ) -> SHAData<Self::IntRegister, CYCLE_LENGTH> {
         assert_eq!(padded_chunks.len(), end_bits.len());
         let num_real_rounds = padded_chunks.len();
@@ -130,6 +164,7 @@ pub trait SHAir<B: Builder, const CYCLE_LENGTH: usize>:
         let degree_log = log2_ceil(num_real_rounds * CYCLE_LENGTH);
         assert!(degree_log < 31, "AIR degree is too large");
         debug!("AIR degree after padding: {}", 1 << degree_log);
+        builder.api().set_trace_length(1 << degree_log);
         let num_dummy_rounds = (1 << degree_log) / CYCLE_LENGTH + 1 - num_real_rounds;
         // Keep track of the last round length to know how many dummy reads to add.
         let length_last_round = (1 << degree_log) % CYCLE_LENGTH;
@@ -144,9 +179,8 @@ pub trait SHAir<B: Builder, const CYCLE_LENGTH: usize>:
             .constant_array::<Self::IntRegister>(&Self::INITIAL_HASH.map(Self::int_to_field_value));
 
         // Initialize the round constants and set them to the constant value.
-        let round_constant_values = builder.constant_array::<Self::IntRegister>(
-            &Self::ROUND_CONSTANTS.map(Self::int_to_field_value),
-        );
+        let round_constant_values = builder
+            .constant_array::<Self::IntRegister>(&round_constants.map(Self::int_to_field_value));
 
         // Store the round constants in a slice to be able to load them in the trace.
         let round_constants = builder.uninit_slice();
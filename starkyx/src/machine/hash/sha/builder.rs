@@ -2,7 +2,10 @@ use super::algorithm::SHAir;
 use crate::chip::register::array::ArrayRegister;
 use crate::chip::register::bit::BitRegister;
 use crate::chip::register::element::ElementRegister;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::{AirParameters, ColumnBudget};
 use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
 
 pub trait SHABuilder: Builder {
     fn sha<S: SHAir<Self, CYCLE_LENGTH>, const CYCLE_LENGTH: usize>(
@@ -14,10 +17,49 @@ pub trait SHABuilder: Builder {
     ) -> Vec<S::StateVariable> {
         S::sha(self, padded_chunks, end_bits, digest_bits, digest_indices)
     }
+
+    /// Hashes with `round_constants` in place of the standard round constants of `S`.
+    fn sha_with_round_constants<S: SHAir<Self, CYCLE_LENGTH>, const CYCLE_LENGTH: usize>(
+        &mut self,
+        padded_chunks: &[ArrayRegister<S::IntRegister>],
+        end_bits: &ArrayRegister<BitRegister>,
+        digest_bits: &ArrayRegister<BitRegister>,
+        digest_indices: ArrayRegister<ElementRegister>,
+        round_constants: &[S::Integer; CYCLE_LENGTH],
+    ) -> Vec<S::StateVariable> {
+        S::sha_with_round_constants(
+            self,
+            padded_chunks,
+            end_bits,
+            digest_bits,
+            digest_indices,
+            round_constants,
+        )
+    }
 }
 
 impl<B: Builder> SHABuilder for B {}
 
+/// The columns needed by the SHA machine `S` hashing `num_rounds` padded chunks.
+pub(crate) fn sha_required_columns<L, S, const CYCLE_LENGTH: usize>(
+    num_rounds: usize,
+) -> ColumnBudget
+where
+    L: AirParameters,
+    L::Instruction: UintInstructions,
+    S: SHAir<BytesBuilder<L>, CYCLE_LENGTH>,
+{
+    let mut builder = BytesBuilder::<L>::new();
+    let padded_chunks = (0..num_rounds)
+        .map(|_| builder.alloc_array_public::<S::IntRegister>(16))
+        .collect::<Vec<_>>();
+    let end_bits = builder.alloc_array_public::<BitRegister>(num_rounds);
+    let digest_indices = builder.alloc_array_public::<ElementRegister>(num_rounds);
+    builder.sha::<S, CYCLE_LENGTH>(&padded_chunks, &end_bits, &end_bits, digest_indices);
+
+    builder.column_budget()
+}
+
 #[cfg(test)]
 pub mod test_utils {
     use core::fmt::Debug;
@@ -58,6 +100,31 @@ pub mod test_utils {
         S: SHAir<BytesBuilder<L>, CYCLE_LENGTH>,
         Chip<L>: Plonky2Air<GoldilocksField, 2>,
         S::Integer: PartialEq + Eq + Debug,
+    {
+        test_sha_with_round_constants::<L, S, _, _, CYCLE_LENGTH>(
+            messages,
+            expected_digests.into_iter().map(S::decode),
+            &S::ROUND_CONSTANTS,
+        )
+    }
+
+    pub fn test_sha_with_round_constants<
+        'a,
+        L,
+        S,
+        I: IntoIterator<Item = &'a [u8]>,
+        J: IntoIterator<Item = [S::Integer; 8]>,
+        const CYCLE_LENGTH: usize,
+    >(
+        messages: I,
+        expected_digests: J,
+        round_constants: &[S::Integer; CYCLE_LENGTH],
+    ) where
+        L: AirParameters<Field = GoldilocksField, CubicParams = GoldilocksCubicParameters>,
+        L::Instruction: UintInstructions,
+        S: SHAir<BytesBuilder<L>, CYCLE_LENGTH>,
+        Chip<L>: Plonky2Air<GoldilocksField, 2>,
+        S::Integer: PartialEq + Eq + Debug,
     {
         type C = CurtaPoseidonGoldilocksConfig;
         type Config = <C as CurtaConfig<2>>::GenericConfig;
@@ -88,8 +155,13 @@ pub mod test_utils {
             .collect::<Vec<_>>();
         let end_bits = builder.alloc_array_public::<BitRegister>(num_rounds);
         let digest_indices = builder.alloc_array_public(num_messages);
-        let hash_state =
-            builder.sha::<S, CYCLE_LENGTH>(&padded_chunks, &end_bits, &end_bits, digest_indices);
+        let hash_state = builder.sha_with_round_constants::<S, CYCLE_LENGTH>(
+            &padded_chunks,
+            &end_bits,
+            &end_bits,
+            digest_indices,
+            round_constants,
+        );
 
         let num_rows_degree = log2_ceil(CYCLE_LENGTH * num_rounds);
         let num_rows = 1 << num_rows_degree;
@@ -122,7 +194,8 @@ pub mod test_utils {
             writer.write_array(register, message.iter().map(|x| S::int_to_field_value(*x)));
 
             let pre_processed = S::pre_process(message);
-            current_state = S::process(current_state, &pre_processed);
+            current_state =
+                S::process_with_round_constants(current_state, &pre_processed, round_constants);
             let state = current_state.map(S::int_to_field_value);
             if *end_bit_value == GoldilocksField::ONE {
                 writer.write(
@@ -156,8 +229,7 @@ pub mod test_utils {
             let digest = writer
                 .read_array::<_, 8>(&array)
                 .map(|x| S::field_value_to_int(&x));
-            let expected_digest = S::decode(expected);
-            assert_eq!(digest, expected_digest);
+            assert_eq!(digest, expected);
         }
 
         let (trace, public) = (writer_data.trace, writer_data.public);
@@ -9,10 +9,11 @@ use crate::chip::register::Register;
 use crate::chip::uint::operations::instruction::UintInstructions;
 use crate::chip::uint::register::{U32Register, U64Register};
 use crate::chip::uint::util::{u32_from_le_field_bytes, u32_to_le_field_bytes};
-use crate::chip::AirParameters;
+use crate::chip::{AirParameters, ColumnBudget};
 use crate::machine::builder::Builder;
 use crate::machine::bytes::builder::BytesBuilder;
 use crate::machine::hash::sha::algorithm::SHAir;
+use crate::machine::hash::sha::builder::sha_required_columns;
 use crate::machine::hash::{HashDigest, HashIntConversion, HashInteger};
 
 impl<B: Builder> HashInteger<B> for SHA256 {
@@ -34,6 +35,21 @@ impl<B: Builder> HashDigest<B> for SHA256 {
     type DigestRegister = SHA256DigestRegister;
 }
 
+impl SHA256 {
+    /// The columns needed by the SHA-256 machine hashing `num_rounds` padded chunks, to be used
+    /// as the column constants of `L`.
+    pub fn required_columns<L: AirParameters>(num_rounds: usize) -> ColumnBudget
+    where
+        L::Instruction: UintInstructions,
+    {
+        sha_required_columns::<L, Self, 64>(num_rounds)
+    }
+}
+
+/// The SHA-256 AIR over `U32Register` words.
+///
+/// The 64 round constants are stored in an `uninit_slice` by `SHAir::data` and loaded on each
+/// row of the processing cycle.
 impl<L: AirParameters> SHAir<BytesBuilder<L>, 64> for SHA256
 where
     L::Instruction: UintInstructions,
@@ -138,10 +154,7 @@ where
         sum_1 = builder.xor(sum_1, e_rotate_25);
 
         // Calculate ch = (e & f) ^ (!e & g).
-        let e_and_f = builder.and(&e, &f);
-        let not_e = builder.not(e);
-        let not_e_and_g = builder.and(&not_e, &g);
-        let ch = builder.xor(&e_and_f, &not_e_and_g);
+        let ch = builder.ch(e, f, g);
 
         // Calculate temp_1 = h + sum_1 + ch + round_constant + w.
         let mut temp_1 = builder.add(h, sum_1);
@@ -156,12 +169,8 @@ where
         let mut sum_0 = builder.xor(a_rotate_2, a_rotate_13);
         sum_0 = builder.xor(sum_0, a_rotate_22);
 
-        // Calculate maj = (a & b) ^ (a & c) ^ (b & c);
-        let a_and_b = builder.and(a, b);
-        let a_and_c = builder.and(a, c);
-        let b_and_c = builder.and(b, c);
-        let mut maj = builder.xor(a_and_b, a_and_c);
-        maj = builder.xor(maj, b_and_c);
+        // Calculate maj = (a & b) ^ (a & c) ^ (b & c).
+        let maj = builder.maj(a, b, c);
 
         // Calculate temp_2 = sum_0 + maj.
         let temp_2 = builder.add(sum_0, maj);
@@ -206,7 +215,8 @@ mod tests {
 
     use super::*;
     use crate::chip::uint::operations::instruction::UintInstruction;
-    use crate::machine::hash::sha::builder::test_utils::test_sha;
+    use crate::machine::hash::sha::algorithm::SHAPure;
+    use crate::machine::hash::sha::builder::test_utils::{test_sha, test_sha_with_round_constants};
     use crate::math::goldilocks::cubic::GoldilocksCubicParameters;
 
     #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -229,6 +239,13 @@ mod tests {
         test_sha::<SHA256Test, SHA256, _, _, 64>(messages, expected_digests)
     }
 
+    #[test]
+    fn test_sha256_required_columns() {
+        let budget = SHA256::required_columns::<SHA256Test>(4);
+        assert!(budget.fits::<SHA256Test>());
+        assert_eq!(budget.num_arithmetic_columns, 0);
+    }
+
     #[test]
     fn test_sha256_short_message() {
         let msg = b"abc";
@@ -272,4 +289,47 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_sha256_sha2_vectors() {
+        use rand::Rng;
+        use sha2::{Digest, Sha256};
+
+        // Messages on both sides of the padding boundaries, hashed by the `sha2` crate.
+        let mut rng = rand::thread_rng();
+        let messages = [0, 1, 55, 56, 63, 64, 119, 120, 200]
+            .map(|len| (0..len).map(|_| rng.gen::<u8>()).collect::<Vec<_>>());
+        let expected_digests = messages
+            .iter()
+            .map(|msg| hex::encode(Sha256::digest(msg)))
+            .collect::<Vec<_>>();
+        test_sha256(
+            messages.iter().map(|msg| msg.as_slice()),
+            expected_digests.iter().map(|digest| digest.as_str()),
+        )
+    }
+
+    #[test]
+    fn test_sha256_custom_round_constants() {
+        let msg = b"abc";
+        let expected_digest = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        let mut round_constants = SHA256::ROUND_CONSTANTS;
+        round_constants[0] ^= 1;
+
+        let pre_processed = SHA256::pre_process(&SHA256::pad(msg));
+        let default_digest = SHA256::process(SHA256::INITIAL_HASH, &pre_processed);
+        let digest = SHA256::process_with_round_constants(
+            SHA256::INITIAL_HASH,
+            &pre_processed,
+            &round_constants,
+        );
+        assert_eq!(default_digest, SHA256::decode(expected_digest));
+        assert_ne!(digest, default_digest);
+
+        test_sha_with_round_constants::<SHA256Test, SHA256, _, _, 64>(
+            iter::repeat(msg.as_slice()).take(2),
+            iter::repeat(digest).take(2),
+            &round_constants,
+        );
+    }
 }
@@ -0,0 +1,149 @@
+use itertools::Itertools;
+
+use super::register::SHA256DigestRegister;
+use super::SHA256;
+use crate::chip::register::array::ArrayRegister;
+use crate::chip::register::bit::BitRegister;
+use crate::chip::register::element::ElementRegister;
+use crate::chip::register::Register;
+use crate::chip::trace::writer::AirWriter;
+use crate::chip::uint::bytes::register::ByteRegister;
+use crate::chip::uint::operations::instruction::UintInstructions;
+use crate::chip::uint::register::U32Register;
+use crate::chip::uint::util::u32_to_le_field_bytes;
+use crate::chip::AirParameters;
+use crate::machine::builder::Builder;
+use crate::machine::bytes::builder::BytesBuilder;
+use crate::machine::hash::hmac::HashGadget;
+use crate::machine::hash::sha::algorithm::SHAPure;
+use crate::machine::hash::sha::builder::SHABuilder;
+use crate::math::prelude::*;
+
+/// The registers of messages hashed by the SHA-256 machine, see `HashGadget::hash_messages`.
+#[derive(Debug, Clone)]
+pub struct SHA256Messages {
+    padded_chunks: Vec<ArrayRegister<U32Register>>,
+    /// The index of the first padded chunk of every message, followed by the number of chunks.
+    chunk_offsets: Vec<usize>,
+    lengths: Vec<usize>,
+    digests: Vec<SHA256DigestRegister>,
+}
+
+impl SHA256Messages {
+    /// The bytes of the padded message of index `index`.
+    ///
+    /// The words of the padded chunks hold the little-endian bytes of big-endian message words.
+    fn padded_bytes(&self, index: usize) -> Vec<ByteRegister> {
+        self.padded_chunks[self.chunk_offsets[index]..self.chunk_offsets[index + 1]]
+            .iter()
+            .flat_map(|chunk| chunk.iter())
+            .flat_map(be_bytes)
+            .collect()
+    }
+}
+
+/// The bytes of `word` from the most significant one.
+fn be_bytes(word: U32Register) -> [ByteRegister; 4] {
+    let bytes = word.to_le_bytes();
+    [3, 2, 1, 0].map(|i| bytes.get(i))
+}
+
+impl<L: AirParameters> HashGadget<BytesBuilder<L>> for SHA256
+where
+    L::Instruction: UintInstructions,
+{
+    const BLOCK_SIZE: usize = 64;
+    const DIGEST_SIZE: usize = 32;
+
+    type Messages = SHA256Messages;
+
+    fn hash_messages(builder: &mut BytesBuilder<L>, lengths: &[usize]) -> SHA256Messages {
+        let mut padded_chunks = Vec::new();
+        let mut chunk_offsets = vec![0];
+        let mut end_bits_values = Vec::new();
+        let mut digest_indices_values = Vec::new();
+        for length in lengths {
+            // The message is followed by the byte `0x80` and its length in bits, on 8 bytes.
+            let num_chunks = (length + 9).div_ceil(64);
+            for _ in 0..num_chunks {
+                padded_chunks.push(builder.alloc_array_public::<U32Register>(16));
+            }
+            end_bits_values.extend(vec![L::Field::ZERO; num_chunks - 1]);
+            end_bits_values.push(L::Field::ONE);
+            digest_indices_values.push(L::Field::from_canonical_usize(padded_chunks.len() - 1));
+            chunk_offsets.push(padded_chunks.len());
+        }
+
+        let mut messages = SHA256Messages {
+            padded_chunks,
+            chunk_offsets,
+            lengths: lengths.to_vec(),
+            digests: Vec::new(),
+        };
+        for (i, length) in lengths.iter().enumerate() {
+            let padded_bytes = messages.padded_bytes(i);
+            let num_bytes = padded_bytes.len();
+            let bit_length = (8 * *length as u64).to_be_bytes();
+            for (j, byte) in padded_bytes.iter().enumerate().skip(*length) {
+                let value = if j == *length {
+                    0x80
+                } else if j >= num_bytes - 8 {
+                    bit_length[j + 8 - num_bytes]
+                } else {
+                    0
+                };
+                builder.assert_expression_zero(byte.expr() - L::Field::from_canonical_u8(value));
+            }
+        }
+
+        let end_bits = builder.constant_array::<BitRegister>(&end_bits_values);
+        let digest_indices = builder.constant_array::<ElementRegister>(&digest_indices_values);
+        messages.digests = builder.sha::<SHA256, 64>(
+            &messages.padded_chunks,
+            &end_bits,
+            &end_bits,
+            digest_indices,
+        );
+        messages
+    }
+
+    fn message_bytes(messages: &SHA256Messages, index: usize) -> Vec<ByteRegister> {
+        let mut bytes = messages.padded_bytes(index);
+        bytes.truncate(messages.lengths[index]);
+        bytes
+    }
+
+    fn digest_bytes(messages: &SHA256Messages, index: usize) -> Vec<ByteRegister> {
+        messages.digests[index].iter().flat_map(be_bytes).collect()
+    }
+
+    fn write_messages(
+        messages: &SHA256Messages,
+        values: &[Vec<u8>],
+        writer: &mut impl AirWriter<Field = L::Field>,
+    ) {
+        for (i, ((value, length), digest)) in values
+            .iter()
+            .zip_eq(messages.lengths.iter())
+            .zip_eq(messages.digests.iter())
+            .enumerate()
+        {
+            assert_eq!(value.len(), *length, "Message {} has the wrong length", i);
+            let chunks =
+                &messages.padded_chunks[messages.chunk_offsets[i]..messages.chunk_offsets[i + 1]];
+            for (register, words) in chunks.iter().zip_eq(Self::pad(value).chunks_exact(16)) {
+                writer.write_array(register, words.iter().map(|w| u32_to_le_field_bytes(*w)));
+            }
+
+            let digest_words = SHA256::hash(value)
+                .chunks_exact(4)
+                .map(|word| u32_to_le_field_bytes(u32::from_be_bytes(word.try_into().unwrap())))
+                .collect::<Vec<_>>();
+            writer.write_array(&digest.as_array(), digest_words);
+        }
+    }
+
+    fn hash(message: &[u8]) -> Vec<u8> {
+        SHA256::hash(message).to_vec()
+    }
+}
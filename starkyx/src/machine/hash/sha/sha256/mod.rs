@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 pub mod air;
+pub mod gadget;
 pub mod pure;
 pub mod register;
 
@@ -48,9 +48,13 @@ impl SHAPure<64> for SHA256 {
         w
     }
 
-    fn process(hash: [Self::Integer; 8], w: &[Self::Integer; 64]) -> [Self::Integer; 8] {
+    fn process_with_round_constants(
+        hash: [Self::Integer; 8],
+        w: &[Self::Integer; 64],
+        round_constants: &[Self::Integer; 64],
+    ) -> [Self::Integer; 8] {
         let mut msg = hash;
-        for (&w, &round_constant) in w.iter().zip(Self::ROUND_CONSTANTS.iter()) {
+        for (&w, &round_constant) in w.iter().zip(round_constants.iter()) {
             msg = step(msg, w, round_constant);
         }
 
@@ -109,3 +113,91 @@ pub fn step(msg: [u32; 8], w_i: u32, round_constant: u32) -> [u32; 8] {
 
     [a, b, c, d, e, f, g, h]
 }
+
+impl SHA256 {
+    /// Compresses a 64-byte `chunk` into `state`, returning the new state.
+    pub fn compress(chunk: &[u8], state: &mut [u32; 8]) -> [u32; 8] {
+        assert_eq!(chunk.len(), 64, "A SHA-256 chunk must have 64 bytes");
+        let words = chunk
+            .chunks_exact(4)
+            .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        *state = Self::process(*state, &Self::pre_process(&words));
+        *state
+    }
+
+    /// The SHA-256 digest of `msg`.
+    pub fn hash(msg: &[u8]) -> [u8; 32] {
+        let padded_msg = Self::pad(msg);
+        let mut state = INITIAL_HASH;
+        for chunk in padded_msg.chunks_exact(16) {
+            state = Self::process(state, &Self::pre_process(chunk));
+        }
+
+        let mut digest = [0u8; 32];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(state) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_sha256_hash() {
+        let expected = [
+            (
+                b"".to_vec(),
+                "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            ),
+            (
+                b"abc".to_vec(),
+                "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+            ),
+            (
+                b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq".to_vec(),
+                "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1",
+            ),
+        ];
+        for (msg, digest) in expected {
+            assert_eq!(SHA256::hash(&msg).to_vec(), hex::decode(digest).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_sha256_hash_sha2() {
+        use sha2::{Digest, Sha256};
+
+        // Every length up to three chunks, across the padding boundaries at 56 and 64 bytes.
+        let mut rng = rand::thread_rng();
+        for len in 0..=192 {
+            let msg = (0..len).map(|_| rng.gen::<u8>()).collect::<Vec<_>>();
+            assert_eq!(SHA256::hash(&msg).to_vec(), Sha256::digest(&msg).to_vec());
+        }
+    }
+
+    #[test]
+    fn test_sha256_compress() {
+        // The padded chunks of a two-chunk message, compressed one at a time.
+        let msg = b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+        let padded_msg = SHA256::pad(msg)
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect::<Vec<_>>();
+        assert_eq!(padded_msg.len(), 128);
+
+        let mut state = INITIAL_HASH;
+        for chunk in padded_msg.chunks_exact(64) {
+            SHA256::compress(chunk, &mut state);
+        }
+        assert_eq!(
+            state,
+            SHA256::decode("248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1")
+        );
+    }
+}
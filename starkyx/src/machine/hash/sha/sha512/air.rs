@@ -9,10 +9,11 @@ use crate::chip::register::Register;
 use crate::chip::uint::operations::instruction::UintInstructions;
 use crate::chip::uint::register::U64Register;
 use crate::chip::uint::util::{u64_from_le_field_bytes, u64_to_le_field_bytes};
-use crate::chip::AirParameters;
+use crate::chip::{AirParameters, ColumnBudget};
 use crate::machine::builder::Builder;
 use crate::machine::bytes::builder::BytesBuilder;
 use crate::machine::hash::sha::algorithm::SHAir;
+use crate::machine::hash::sha::builder::sha_required_columns;
 use crate::machine::hash::{HashDigest, HashIntConversion, HashInteger};
 
 impl<B: Builder> HashInteger<B> for SHA512 {
@@ -34,6 +35,17 @@ impl<B: Builder> HashDigest<B> for SHA512 {
     type DigestRegister = SHA512DigestRegister;
 }
 
+impl SHA512 {
+    /// The columns needed by the SHA-512 machine hashing `num_rounds` padded chunks, to be used
+    /// as the column constants of `L`.
+    pub fn required_columns<L: AirParameters>(num_rounds: usize) -> ColumnBudget
+    where
+        L::Instruction: UintInstructions,
+    {
+        sha_required_columns::<L, Self, 80>(num_rounds)
+    }
+}
+
 impl<L: AirParameters> SHAir<BytesBuilder<L>, 80> for SHA512
 where
     L::Instruction: UintInstructions,
@@ -138,10 +150,7 @@ where
         sum_1 = builder.xor(sum_1, e_rotate_41);
 
         // Calculate ch = (e & f) ^ (!e & g).
-        let e_and_f = builder.and(&e, &f);
-        let not_e = builder.not(e);
-        let not_e_and_g = builder.and(&not_e, &g);
-        let ch = builder.xor(&e_and_f, &not_e_and_g);
+        let ch = builder.ch(e, f, g);
 
         // Calculate temp_1 = h + sum_1 + ch + round_constant + w.
         let mut temp_1 = builder.add(h, sum_1);
@@ -156,12 +165,8 @@ where
         let mut sum_0 = builder.xor(a_rotate_28, a_rotate_34);
         sum_0 = builder.xor(sum_0, a_rotate_39);
 
-        // Calculate maj = (a & b) ^ (a & c) ^ (b & c);
-        let a_and_b = builder.and(a, b);
-        let a_and_c = builder.and(a, c);
-        let b_and_c = builder.and(b, c);
-        let mut maj = builder.xor(a_and_b, a_and_c);
-        maj = builder.xor(maj, b_and_c);
+        // Calculate maj = (a & b) ^ (a & c) ^ (b & c).
+        let maj = builder.maj(a, b, c);
 
         // Calculate temp_2 = sum_0 + maj.
         let temp_2 = builder.add(sum_0, maj);
@@ -242,6 +247,20 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_sha512_fips_vectors() {
+        // The examples of FIPS 180-2, the last of which takes two blocks.
+        let two_block_msg = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+        test_sha512(
+            [b"".as_slice(), b"abc".as_slice(), two_block_msg.as_slice()],
+            [
+                "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e",
+                "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+                "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909",
+            ],
+        );
+    }
+
     #[test]
     fn test_sha512_long_message() {
         let num_messages = 600;
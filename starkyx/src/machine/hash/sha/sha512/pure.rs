@@ -48,9 +48,13 @@ impl SHAPure<80> for SHA512 {
         w
     }
 
-    fn process(hash: [Self::Integer; 8], w: &[Self::Integer; 80]) -> [Self::Integer; 8] {
+    fn process_with_round_constants(
+        hash: [Self::Integer; 8],
+        w: &[Self::Integer; 80],
+        round_constants: &[Self::Integer; 80],
+    ) -> [Self::Integer; 8] {
         let mut msg = hash;
-        for (&w, &round_constant) in w.iter().zip(Self::ROUND_CONSTANTS.iter()) {
+        for (&w, &round_constant) in w.iter().zip(round_constants.iter()) {
             msg = step(msg, w, round_constant);
         }
 
@@ -0,0 +1,295 @@
+//! Merkle paths.
+//!
+//! A path from a leaf to the root of a binary Merkle tree is given by the sibling of the node at
+//! every level, from the leaf up, and by the bits of the index of the leaf, from the least
+//! significant one. The bit of a level is one when the node of that level is the right child of
+//! its parent, which is then the hash of the sibling followed by the node.
+
+use core::fmt::Debug;
+
+use crate::chip::register::bit::BitRegister;
+use crate::machine::builder::Builder;
+
+/// A two-to-one hash function for the nodes of a Merkle tree.
+pub trait MerkleHash<B: Builder> {
+    /// The registers of a node.
+    type Node: Debug + Clone + Copy;
+
+    /// The value of a node.
+    type NodeValue: Debug + Clone + Copy + PartialEq;
+
+    /// Returns the parent of the nodes `left` and `right`.
+    fn hash_nodes(builder: &mut B, left: &Self::Node, right: &Self::Node) -> Self::Node;
+
+    /// Returns the node equal to `true_node` if `bit` is one and to `false_node` otherwise.
+    fn select_node(
+        builder: &mut B,
+        bit: BitRegister,
+        true_node: &Self::Node,
+        false_node: &Self::Node,
+    ) -> Self::Node;
+
+    /// Asserts that the nodes `a` and `b` are equal.
+    fn assert_nodes_equal(builder: &mut B, a: &Self::Node, b: &Self::Node);
+
+    /// The value of the parent of the nodes of values `left` and `right`.
+    fn hash_node_values(left: &Self::NodeValue, right: &Self::NodeValue) -> Self::NodeValue;
+}
+
+pub trait MerkleBuilder: Builder {
+    /// Returns the root of the tree reached from `leaf` by the siblings of `path`, with the
+    /// position of the node at every level given by `index_bits`.
+    ///
+    /// The depth of the tree is the length of `path`, and every level takes one hash of `H`.
+    fn merkle_root<H: MerkleHash<Self>>(
+        &mut self,
+        leaf: &H::Node,
+        path: &[H::Node],
+        index_bits: &[BitRegister],
+    ) -> H::Node {
+        assert_eq!(
+            path.len(),
+            index_bits.len(),
+            "A Merkle path needs one index bit per sibling"
+        );
+        path.iter()
+            .zip(index_bits.iter())
+            .fold(*leaf, |node, (sibling, bit)| {
+                let left = H::select_node(self, *bit, sibling, &node);
+                let right = H::select_node(self, *bit, &node, sibling);
+                H::hash_nodes(self, &left, &right)
+            })
+    }
+
+    /// Asserts that `path` is a Merkle path from `leaf` to `root`, see `merkle_root`.
+    fn verify_merkle_path<H: MerkleHash<Self>>(
+        &mut self,
+        leaf: &H::Node,
+        path: &[H::Node],
+        index_bits: &[BitRegister],
+        root: &H::Node,
+    ) {
+        let computed_root = self.merkle_root::<H>(leaf, path, index_bits);
+        H::assert_nodes_equal(self, &computed_root, root);
+    }
+}
+
+impl<B: Builder> MerkleBuilder for B {}
+
+/// The node values of a binary Merkle tree, computed off-circuit from the values of its leaves.
+#[derive(Debug, Clone)]
+pub struct MerkleTree<V> {
+    /// The levels of the tree, from the leaves to the root.
+    levels: Vec<Vec<V>>,
+}
+
+impl<V: Debug + Clone + Copy + PartialEq> MerkleTree<V> {
+    /// Builds the tree of `leaves` with the hash function `H`. The number of leaves must be a
+    /// power of two.
+    pub fn new<B: Builder, H: MerkleHash<B, NodeValue = V>>(leaves: &[V]) -> Self {
+        assert!(
+            leaves.len().is_power_of_two(),
+            "The number of leaves must be a power of two"
+        );
+        let mut levels = vec![leaves.to_vec()];
+        while levels.last().unwrap().len() > 1 {
+            let level = levels
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .map(|pair| H::hash_node_values(&pair[0], &pair[1]))
+                .collect();
+            levels.push(level);
+        }
+        MerkleTree { levels }
+    }
+
+    /// The depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// The root of the tree.
+    pub fn root(&self) -> V {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// The leaf of index `index`.
+    pub fn leaf(&self, index: usize) -> V {
+        self.levels[0][index]
+    }
+
+    /// The siblings of the path from the leaf of index `index` to the root, and the bits of
+    /// `index`.
+    pub fn path(&self, index: usize) -> (Vec<V>, Vec<bool>) {
+        self.levels[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(level, nodes)| {
+                let position = index >> level;
+                (nodes[position ^ 1], position & 1 == 1)
+            })
+            .unzip()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::hash::hash_types::HashOut;
+    use plonky2::hash::poseidon::PoseidonHash;
+    use plonky2::plonk::config::Hasher;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+    use crate::chip::builder::tests::*;
+    use crate::chip::builder::AirBuilder;
+    use crate::chip::register::array::ArrayRegister;
+    use crate::chip::register::element::ElementRegister;
+    use crate::chip::register::Register;
+    use crate::chip::trace::writer::TraceWriter;
+    use crate::chip::{AirParameters, Chip};
+    use crate::machine::hash::poseidon::{DIGEST_SIZE, POSEIDON};
+    use crate::math::prelude::*;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct MerkleTest;
+
+    /// A path of depth 3: three permutations of 478 columns, the 24 elements of the selected
+    /// nodes, and the 23 columns of the leaf, the siblings, the index bits and the root.
+    impl AirParameters for MerkleTest {
+        type Field = GoldilocksField;
+        type CubicParams = GoldilocksCubicParameters;
+        type Instruction = EmptyInstruction<GoldilocksField>;
+
+        const NUM_FREE_COLUMNS: usize = 1481;
+    }
+
+    type F = GoldilocksField;
+    type L = MerkleTest;
+    type Node = [ElementRegister; DIGEST_SIZE];
+
+    const DEPTH: usize = 3;
+    const NUM_ROWS: usize = 1 << 4;
+    const TAMPERED_ROW: usize = 5;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Tamper {
+        None,
+        Sibling,
+        IndexBit,
+    }
+
+    fn alloc_node(builder: &mut AirBuilder<L>) -> Node {
+        core::array::from_fn(|_| builder.alloc::<ElementRegister>())
+    }
+
+    fn write_node(writer: &TraceWriter<F>, node: &Node, value: &[F; DIGEST_SIZE], row: usize) {
+        for (register, value) in node.iter().zip(value) {
+            writer.write(register, value, row);
+        }
+    }
+
+    /// Verifies in row `i` the path of the leaf of index `i % 8` of a tree of random leaves,
+    /// with the path of `TAMPERED_ROW` altered according to `tamper`.
+    fn write_merkle_paths(tamper: Tamper) -> (Chip<L>, ArithmeticGenerator<L>) {
+        let mut builder = AirBuilder::<L>::new();
+        let leaf = alloc_node(&mut builder);
+        let path = (0..DEPTH)
+            .map(|_| alloc_node(&mut builder))
+            .collect::<Vec<_>>();
+        let index_bits: ArrayRegister<BitRegister> = builder.alloc_array(DEPTH);
+        let root = alloc_node(&mut builder);
+        builder.verify_merkle_path::<POSEIDON>(
+            &leaf,
+            &path,
+            &index_bits.iter().collect::<Vec<_>>(),
+            &root,
+        );
+
+        let (air, trace_data) = builder.build();
+
+        let generator = ArithmeticGenerator::<L>::new(trace_data, NUM_ROWS);
+        let writer = generator.new_writer();
+
+        let leaves = (0..1 << DEPTH)
+            .map(|_| F::rand_array::<DIGEST_SIZE>())
+            .collect::<Vec<_>>();
+        let tree = MerkleTree::new::<AirBuilder<L>, POSEIDON>(&leaves);
+        assert_eq!(tree.depth(), DEPTH);
+        for i in 0..NUM_ROWS {
+            let index = i % (1 << DEPTH);
+            let (mut siblings, mut bits) = tree.path(index);
+            if i == TAMPERED_ROW {
+                match tamper {
+                    Tamper::None => {}
+                    Tamper::Sibling => siblings[1][0] += F::ONE,
+                    Tamper::IndexBit => bits[0] = !bits[0],
+                }
+            }
+
+            write_node(&writer, &leaf, &tree.leaf(index), i);
+            for (node, value) in path.iter().zip(siblings.iter()) {
+                write_node(&writer, node, value, i);
+            }
+            writer.write_array(
+                &index_bits,
+                bits.iter().map(|bit| F::from_canonical_u8(*bit as u8)),
+                i,
+            );
+            write_node(&writer, &root, &tree.root(), i);
+            writer.write_row_instructions(&generator.air_data, i);
+        }
+
+        (air, generator)
+    }
+
+    fn eval_merkle_paths(tamper: Tamper) {
+        let (air, generator) = write_merkle_paths(tamper);
+        let trace = generator.trace_clone();
+        for window in trace.windows() {
+            let mut window_parser = TraceWindowParser::new(window, &[], &[], &[]);
+            air.eval(&mut window_parser);
+        }
+    }
+
+    #[test]
+    fn test_poseidon_merkle_hash() {
+        for _ in 0..10 {
+            let (left, right) = (F::rand_array(), F::rand_array());
+            let expected =
+                PoseidonHash::two_to_one(HashOut { elements: left }, HashOut { elements: right });
+            assert_eq!(
+                <POSEIDON as MerkleHash<AirBuilder<L>>>::hash_node_values(&left, &right),
+                expected.elements
+            );
+        }
+    }
+
+    #[test]
+    fn test_verify_merkle_path() {
+        type SC = PoseidonGoldilocksStarkConfig;
+
+        let (air, generator) = write_merkle_paths(Tamper::None);
+        let stark = Starky::new(air);
+        let config = SC::standard_fast_config(NUM_ROWS);
+
+        test_starky(&stark, &config, &generator, &[]);
+    }
+
+    #[test]
+    fn test_merkle_path_valid() {
+        eval_merkle_paths(Tamper::None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_merkle_path_tampered_sibling() {
+        eval_merkle_paths(Tamper::Sibling);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_merkle_path_tampered_index() {
+        eval_merkle_paths(Tamper::IndexBit);
+    }
+}
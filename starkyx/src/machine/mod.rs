@@ -3,4 +3,5 @@ pub mod bytes;
 pub mod ec;
 pub mod emulated;
 pub mod hash;
+pub mod merkle;
 pub mod stark;
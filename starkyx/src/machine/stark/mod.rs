@@ -106,6 +106,7 @@ where
                     .collect::<Vec<_>>(),
             );
         }
+        self.air_data.write_range_checks(&writer);
 
         writer
     }
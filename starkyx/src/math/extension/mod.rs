@@ -4,8 +4,10 @@ use super::algebra::Algebra;
 use super::field::Field;
 
 pub mod cubic;
+pub mod quintic;
 
 pub use cubic::parameters::CubicParameters;
+pub use quintic::parameters::QuinticParameters;
 /// A ring extension of a field with a fixed basis
 pub trait Extension<F: Field>: Algebra<F> {
     /// The dimension (i.e. degree) of the extension
@@ -0,0 +1,135 @@
+use core::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use crate::math::prelude::*;
+
+/// The coefficients of an element of the quintic extension F[X]/(X^5 - W).
+///
+/// The reduction depends on `W`, so the product is given by `mul_mod` rather than `Mul`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct QuinticElement<T>(pub [T; 5]);
+
+impl<T> QuinticElement<T> {
+    #[inline]
+    pub const fn new(a: T, b: T, c: T, d: T, e: T) -> Self {
+        Self([a, b, c, d, e])
+    }
+
+    #[inline]
+    pub const fn from_base(element: T, zero: T) -> Self
+    where
+        T: Copy,
+    {
+        Self([element, zero, zero, zero, zero])
+    }
+
+    #[inline]
+    pub fn from_slice(slice: &[T]) -> Self
+    where
+        T: Copy,
+    {
+        assert_eq!(slice.len(), 5, "Quintic array slice must have length 5");
+        Self(core::array::from_fn(|i| slice[i]))
+    }
+
+    #[inline]
+    pub const fn as_slice(&self) -> &[T] {
+        &self.0
+    }
+
+    #[inline]
+    pub const fn as_array(&self) -> [T; 5]
+    where
+        T: Copy,
+    {
+        self.0
+    }
+}
+
+impl<R: Ring + Copy> QuinticElement<R> {
+    /// The product of `self` and `rhs` modulo X^5 - `w`.
+    pub fn mul_mod(self, rhs: Self, w: R) -> Self {
+        let (x, y) = (self.0, rhs.0);
+
+        // Using X^5 = W, the coefficient of X^k is the sum of the products `x_i y_j` with
+        // `i + j = k`, plus `W` times the sum of those with `i + j = k + 5`.
+        Self(core::array::from_fn(|k| {
+            let low = (0..=k).map(|i| x[i] * y[k - i]).sum::<R>();
+            let high = (k + 1..5).map(|i| x[i] * y[k + 5 - i]).sum::<R>();
+            low + w * high
+        }))
+    }
+
+    /// The square of `self` modulo X^5 - `w`.
+    pub fn square_mod(self, w: R) -> Self {
+        let x = self.0;
+        let double = |a: R| a + a;
+
+        Self([
+            x[0] * x[0] + w * double(x[1] * x[4] + x[2] * x[3]),
+            double(x[0] * x[1]) + w * (double(x[2] * x[4]) + x[3] * x[3]),
+            double(x[0] * x[2]) + x[1] * x[1] + w * double(x[3] * x[4]),
+            double(x[0] * x[3] + x[1] * x[2]) + w * x[4] * x[4],
+            double(x[0] * x[4] + x[1] * x[3]) + x[2] * x[2],
+        ])
+    }
+}
+
+impl<T: Clone + Add<Output = T>> Add for QuinticElement<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(core::array::from_fn(|i| {
+            self.0[i].clone() + rhs.0[i].clone()
+        }))
+    }
+}
+
+impl<T: Clone + Sub<Output = T>> Sub for QuinticElement<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(core::array::from_fn(|i| {
+            self.0[i].clone() - rhs.0[i].clone()
+        }))
+    }
+}
+
+impl<T: Clone + Neg<Output = T>> Neg for QuinticElement<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(core::array::from_fn(|i| -self.0[i].clone()))
+    }
+}
+
+impl<T: Copy + AddAssign> AddAssign for QuinticElement<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        for (a, b) in self.0.iter_mut().zip(rhs.0) {
+            *a += b;
+        }
+    }
+}
+
+impl<T: Copy + SubAssign> SubAssign for QuinticElement<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        for (a, b) in self.0.iter_mut().zip(rhs.0) {
+            *a -= b;
+        }
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for QuinticElement<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self(self.0.map(|x| x * rhs))
+    }
+}
+
+impl<R: Ring> Default for QuinticElement<R> {
+    fn default() -> Self {
+        Self([R::ZERO, R::ZERO, R::ZERO, R::ZERO, R::ZERO])
+    }
+}
@@ -0,0 +1,303 @@
+use core::hash::{Hash, Hasher};
+use core::iter::{Product, Sum};
+use core::marker::PhantomData;
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::element::QuinticElement;
+use super::parameters::QuinticParameters;
+use crate::math::prelude::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct QuinticExtension<F: Field, P: QuinticParameters<F>>(
+    pub QuinticElement<F>,
+    PhantomData<P>,
+);
+
+impl<F: Field, P: QuinticParameters<F>> QuinticExtension<F, P> {
+    pub const ZERO: Self = Self::from_base_field(F::ZERO);
+    pub const ONE: Self = Self::from_base_field(F::ONE);
+
+    pub const fn new(a: F, b: F, c: F, d: F, e: F) -> Self {
+        Self(QuinticElement::new(a, b, c, d, e), PhantomData)
+    }
+
+    pub const fn from_base_field(a: F) -> Self {
+        Self::new(a, F::ZERO, F::ZERO, F::ZERO, F::ZERO)
+    }
+
+    #[inline]
+    pub fn from_slice(slice: &[F]) -> Self {
+        Self(QuinticElement::from_slice(slice), PhantomData)
+    }
+
+    #[inline]
+    pub const fn from_base_field_array(array: [F; 5]) -> Self {
+        Self(QuinticElement(array), PhantomData)
+    }
+
+    #[inline]
+    pub fn base_field_array(&self) -> [F; 5] {
+        self.0.as_array()
+    }
+
+    #[inline]
+    fn in_base_field(&self) -> bool {
+        self.0.as_slice()[1..].iter().all(|x| *x == F::ZERO)
+    }
+
+    /// The image of `self` under the `count`-th power of the Frobenius automorphism.
+    pub fn repeated_frobenius(&self, count: usize) -> Self {
+        let root = P::DTH_ROOT.pow(count as u64 % 5);
+        let mut power = F::ONE;
+        let mut array = self.base_field_array();
+        for x in array.iter_mut() {
+            *x *= power;
+            power *= root;
+        }
+        Self::from(array)
+    }
+
+    /// The image of `self` under the Frobenius automorphism `x -> x^p`.
+    pub fn frobenius(&self) -> Self {
+        self.repeated_frobenius(1)
+    }
+
+    pub fn try_inverse(&self) -> Option<Self> {
+        if *self == Self::ZERO {
+            return None;
+        }
+
+        // The product of the Galois conjugates `a^p * a^(p^2) * a^(p^3) * a^(p^4)` is the inverse
+        // of `a` up to the norm `a^(1 + p + ... + p^4)`, which is in the base field.
+        let frob_1 = self.frobenius();
+        let frob_12 = frob_1 * frob_1.frobenius();
+        let gal_prod = frob_12 * frob_12.repeated_frobenius(2);
+
+        let norm = *self * gal_prod;
+        debug_assert!(norm.in_base_field());
+
+        let norm_inv = norm.0.as_slice()[0].try_inverse()?;
+        Some(gal_prod * norm_inv)
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("Cannot invert zero")
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> From<[F; 5]> for QuinticExtension<F, P> {
+    fn from(value: [F; 5]) -> Self {
+        Self::from_base_field_array(value)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> From<QuinticElement<F>> for QuinticExtension<F, P> {
+    fn from(value: QuinticElement<F>) -> Self {
+        Self(value, PhantomData)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> From<F> for QuinticExtension<F, P> {
+    fn from(value: F) -> Self {
+        Self::from_base_field(value)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Add for QuinticExtension<F, P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, PhantomData)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Add<F> for QuinticExtension<F, P> {
+    type Output = Self;
+
+    fn add(self, rhs: F) -> Self::Output {
+        self + Self::from_base_field(rhs)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Sub<F> for QuinticExtension<F, P> {
+    type Output = Self;
+
+    fn sub(self, rhs: F) -> Self::Output {
+        self - Self::from_base_field(rhs)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Mul for QuinticExtension<F, P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self(self.0.mul_mod(rhs.0, P::W), PhantomData)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Mul<F> for QuinticExtension<F, P> {
+    type Output = Self;
+
+    fn mul(self, rhs: F) -> Self::Output {
+        Self(self.0 * rhs, PhantomData)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Sub for QuinticExtension<F, P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0, PhantomData)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Neg for QuinticExtension<F, P> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0, PhantomData)
+    }
+}
+
+impl<'a, F: Field, P: QuinticParameters<F>> Sum<&'a Self> for QuinticExtension<F, P> {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + *x)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Sum for QuinticExtension<F, P> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a, F: Field, P: QuinticParameters<F>> Product<&'a Self> for QuinticExtension<F, P> {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * *x)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Product for QuinticExtension<F, P> {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> AddAssign for QuinticExtension<F, P> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> MulAssign for QuinticExtension<F, P> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> MulAssign<F> for QuinticExtension<F, P> {
+    fn mul_assign(&mut self, rhs: F) {
+        *self = *self * rhs;
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> SubAssign for QuinticExtension<F, P> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Div for QuinticExtension<F, P> {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> DivAssign for QuinticExtension<F, P> {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl<F: Field + Sample, P: QuinticParameters<F>> Sample for QuinticExtension<F, P> {
+    fn sample<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Self::from(core::array::from_fn::<_, 5, _>(|_| F::sample(rng)))
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Default for QuinticExtension<F, P> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Hash for QuinticExtension<F, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.as_array().hash(state);
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Ring for QuinticExtension<F, P> {
+    const ONE: Self = Self::ONE;
+    const ZERO: Self = Self::ZERO;
+
+    fn square(&self) -> Self {
+        Self(self.0.square_mod(P::W), PhantomData)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> Algebra<F> for QuinticExtension<F, P> {}
+
+impl<F: Field, P: QuinticParameters<F>> Extension<F> for QuinticExtension<F, P> {
+    const D: usize = 5;
+
+    fn as_base_slice(&self) -> &[F] {
+        self.0.as_slice()
+    }
+
+    fn from_base_slice(elements: &[F]) -> Self {
+        Self::from_slice(elements)
+    }
+}
+
+impl<F: Field, P: QuinticParameters<F>> ExtensionField<F> for QuinticExtension<F, P> {}
+
+impl<F: Field, P: QuinticParameters<F>> Field for QuinticExtension<F, P> {
+    fn try_inverse(&self) -> Option<Self> {
+        self.try_inverse()
+    }
+    fn from_canonical_u8(n: u8) -> Self {
+        Self::from_base_field(F::from_canonical_u8(n))
+    }
+    fn from_canonical_u16(n: u16) -> Self {
+        Self::from_base_field(F::from_canonical_u16(n))
+    }
+    fn from_canonical_u32(n: u32) -> Self {
+        Self::from_base_field(F::from_canonical_u32(n))
+    }
+    fn from_canonical_u64(n: u64) -> Self {
+        Self::from_base_field(F::from_canonical_u64(n))
+    }
+    fn from_canonical_usize(n: usize) -> Self {
+        Self::from_base_field(F::from_canonical_usize(n))
+    }
+
+    fn from_noncanonical_biguint(n: num::BigUint) -> Self {
+        Self::from_base_field(F::from_noncanonical_biguint(n))
+    }
+
+    fn primitive_root_of_unity(_n_log: usize) -> Self {
+        unimplemented!("QuinticExtension::primitive_root_of_unity")
+    }
+
+    fn two_adic_subgroup(_n_log: usize) -> Vec<Self> {
+        unimplemented!("QuinticExtension::two_adic_subgroup")
+    }
+}
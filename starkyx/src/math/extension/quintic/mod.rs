@@ -0,0 +1,5 @@
+//! The quintic extension field F[X]/(X^5 - W).
+
+pub mod element;
+pub mod extension;
+pub mod parameters;
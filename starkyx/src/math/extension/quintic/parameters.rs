@@ -0,0 +1,17 @@
+use core::fmt::Debug;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Parameters for the quintic extension F[X]/(X^5 - W)
+pub trait QuinticParameters<F>:
+    'static + Sized + Copy + Clone + Send + Sync + PartialEq + Eq + Debug + Serialize + DeserializeOwned
+{
+    /// The constant `W` of the irreducible polynomial X^5 - W.
+    const W: F;
+
+    /// The fifth root of unity `W^((p - 1) / 5)`.
+    ///
+    /// The Frobenius automorphism maps X to `DTH_ROOT * X`.
+    const DTH_ROOT: F;
+}
@@ -81,6 +81,40 @@ pub trait Field:
         self.try_inverse().expect("Tried to invert zero")
     }
 
+    /// Inverts every element of `elements` in place.
+    ///
+    /// Uses Montgomery's trick, which computes a single inversion and `3(n - 1)` multiplications
+    /// for `n` elements.
+    ///
+    /// # Panics
+    /// Panics if any of the elements is zero, as `inverse` does.
+    fn batch_inverse(elements: &mut [Self]) {
+        let Some((first, rest)) = elements.split_first() else {
+            return;
+        };
+
+        // The products `e_0 * ... * e_i` of all prefixes of the elements.
+        let mut prefix_products = Vec::with_capacity(elements.len());
+        prefix_products.push(*first);
+        for element in rest {
+            let product = *prefix_products.last().unwrap() * *element;
+            prefix_products.push(product);
+        }
+
+        // Peel off the elements from the inverse of the full product, from the last to the first.
+        let mut inverse = prefix_products
+            .last()
+            .unwrap()
+            .try_inverse()
+            .expect("Tried to batch invert a zero element");
+        for i in (1..elements.len()).rev() {
+            let element_inverse = inverse * prefix_products[i - 1];
+            inverse *= elements[i];
+            elements[i] = element_inverse;
+        }
+        elements[0] = inverse;
+    }
+
     /// Returns `true` if `self` is zero.
     fn is_zero(&self) -> bool {
         *self == Self::ZERO
@@ -258,4 +292,39 @@ pub mod tests {
             assert_eq!(a * a.inverse(), one);
         }
     }
+
+    pub fn batch_inverse_test<F: Field + Sample>() {
+        for n in [0, 1, 2, 3, 16, 33] {
+            let elements = F::rand_vec(n);
+            let mut inverses = elements.clone();
+            F::batch_inverse(&mut inverses);
+
+            assert_eq!(inverses.len(), n);
+            for (element, inverse) in elements.iter().zip(inverses.iter()) {
+                assert_eq!(*inverse, element.inverse());
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_inverse() {
+        use plonky2::field::goldilocks_field::GoldilocksField;
+
+        use crate::math::goldilocks::cubic::GF3;
+        use crate::math::mersenne31::field::Mersenne31;
+
+        batch_inverse_test::<GoldilocksField>();
+        batch_inverse_test::<GF3>();
+        batch_inverse_test::<Mersenne31>();
+    }
+
+    #[test]
+    #[should_panic(expected = "Tried to batch invert a zero element")]
+    fn test_batch_inverse_zero() {
+        use plonky2::field::goldilocks_field::GoldilocksField;
+
+        let mut elements = GoldilocksField::rand_vec(4);
+        elements[2] = GoldilocksField::ZERO;
+        GoldilocksField::batch_inverse(&mut elements);
+    }
 }
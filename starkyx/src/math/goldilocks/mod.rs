@@ -1,4 +1,5 @@
 pub mod cubic;
+pub mod quintic;
 
 // use plonky2::field::goldilocks_field::GoldilocksField;
 // use plonky2::field::types::PrimeField64 as PlonkyPrimeField64;
@@ -0,0 +1,91 @@
+use plonky2::field::goldilocks_field::GoldilocksField;
+use serde::{Deserialize, Serialize};
+
+use crate::math::extension::quintic::extension::QuinticExtension;
+use crate::math::extension::quintic::parameters::QuinticParameters;
+
+pub type GF5 = QuinticExtension<GoldilocksField, GoldilocksQuinticParameters>;
+
+/// Parameters for the quintic Goldilocks extension field F[X]/(X^5 - 3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GoldilocksQuinticParameters;
+
+impl QuinticParameters<GoldilocksField> for GoldilocksQuinticParameters {
+    const W: GoldilocksField = GoldilocksField(3);
+
+    const DTH_ROOT: GoldilocksField = GoldilocksField(1041288259238279555);
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::field::extension::quintic::QuinticExtension as PlonkyQuinticExtension;
+    use plonky2::field::extension::FieldExtension;
+
+    use super::*;
+    use crate::math::field::tests::field_test;
+    use crate::math::prelude::*;
+
+    type F = GoldilocksField;
+
+    /// The reference quintic extension of plonky2, which is also defined by X^5 - 3.
+    type Reference = PlonkyQuinticExtension<F>;
+
+    fn reference(a: GF5) -> Reference {
+        Reference::from_basefield_array(a.base_field_array())
+    }
+
+    #[test]
+    fn test_gf5_field() {
+        for _ in 0..100 {
+            field_test::<GF5>();
+        }
+    }
+
+    #[test]
+    fn test_gf5_arithmetic() {
+        let num_tests = 100;
+
+        for _ in 0..num_tests {
+            let a = GF5::rand();
+            let b = GF5::rand();
+
+            let (a_ref, b_ref) = (reference(a), reference(b));
+
+            assert_eq!(reference(a + b), a_ref + b_ref);
+            assert_eq!(reference(a - b), a_ref - b_ref);
+            assert_eq!(reference(-a), -a_ref);
+            assert_eq!(reference(a * b), a_ref * b_ref);
+            assert_eq!(
+                reference(a.square()),
+                plonky2::field::types::Field::square(&a_ref)
+            );
+            assert_eq!(
+                reference(a.inverse()),
+                plonky2::field::types::Field::inverse(&a_ref)
+            );
+        }
+    }
+
+    #[test]
+    fn test_gf5_frobenius() {
+        assert_eq!(GoldilocksQuinticParameters::W, F::from_canonical_u8(3));
+        let root = GoldilocksQuinticParameters::DTH_ROOT;
+        assert_ne!(root, F::ONE);
+        assert_eq!(root.pow(5), F::ONE);
+
+        // The Frobenius automorphism is the p-th power map.
+        let a = GF5::rand();
+        let order = F::order() as u64;
+        assert_eq!(a.frobenius(), a.pow(order));
+        assert_eq!(a.repeated_frobenius(5), a);
+    }
+
+    #[test]
+    fn test_gf5_inverse() {
+        assert_eq!(GF5::ZERO.try_inverse(), None);
+        for _ in 0..100 {
+            let a = GF5::rand();
+            assert_eq!(a * a.inverse(), GF5::ONE);
+        }
+    }
+}
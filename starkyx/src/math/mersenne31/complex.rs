@@ -0,0 +1,283 @@
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use super::field::Mersenne31;
+use crate::math::prelude::*;
+
+pub type CM31 = Mersenne31Complex;
+
+/// The quadratic extension of Mersenne-31 by a square root `i` of `-1`, F[i]/(i^2 + 1).
+///
+/// Since `2^31 - 1 = 3 mod 4`, the polynomial `X^2 + 1` is irreducible, and the Frobenius
+/// automorphism is the complex conjugation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Mersenne31Complex(pub [Mersenne31; 2]);
+
+impl Mersenne31Complex {
+    pub const ZERO: Self = Self([Mersenne31::ZERO, Mersenne31::ZERO]);
+    pub const ONE: Self = Self([Mersenne31::ONE, Mersenne31::ZERO]);
+
+    pub const fn new(real: Mersenne31, imaginary: Mersenne31) -> Self {
+        Self([real, imaginary])
+    }
+
+    pub const fn from_base_field(a: Mersenne31) -> Self {
+        Self([a, Mersenne31::ZERO])
+    }
+
+    #[inline]
+    pub const fn real(&self) -> Mersenne31 {
+        self.0[0]
+    }
+
+    #[inline]
+    pub const fn imaginary(&self) -> Mersenne31 {
+        self.0[1]
+    }
+
+    /// The complex conjugate `a - bi` of `a + bi`.
+    #[inline]
+    pub fn conjugate(&self) -> Self {
+        Self([self.0[0], -self.0[1]])
+    }
+
+    /// The image of `self` under the Frobenius automorphism `x -> x^p`.
+    #[inline]
+    pub fn frobenius(&self) -> Self {
+        self.conjugate()
+    }
+
+    /// The norm `a^2 + b^2` of `a + bi`, which is in the base field.
+    #[inline]
+    pub fn norm(&self) -> Mersenne31 {
+        self.0[0].square() + self.0[1].square()
+    }
+
+    pub fn try_inverse(&self) -> Option<Self> {
+        let norm_inv = self.norm().try_inverse()?;
+        Some(self.conjugate() * norm_inv)
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("Cannot invert zero")
+    }
+}
+
+impl From<Mersenne31> for Mersenne31Complex {
+    fn from(value: Mersenne31) -> Self {
+        Self::from_base_field(value)
+    }
+}
+
+impl From<[Mersenne31; 2]> for Mersenne31Complex {
+    fn from(value: [Mersenne31; 2]) -> Self {
+        Self(value)
+    }
+}
+
+impl Add for Mersenne31Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self([self.0[0] + rhs.0[0], self.0[1] + rhs.0[1]])
+    }
+}
+
+impl Add<Mersenne31> for Mersenne31Complex {
+    type Output = Self;
+
+    fn add(self, rhs: Mersenne31) -> Self::Output {
+        Self([self.0[0] + rhs, self.0[1]])
+    }
+}
+
+impl Sub for Mersenne31Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self([self.0[0] - rhs.0[0], self.0[1] - rhs.0[1]])
+    }
+}
+
+impl Sub<Mersenne31> for Mersenne31Complex {
+    type Output = Self;
+
+    fn sub(self, rhs: Mersenne31) -> Self::Output {
+        Self([self.0[0] - rhs, self.0[1]])
+    }
+}
+
+impl Neg for Mersenne31Complex {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self([-self.0[0], -self.0[1]])
+    }
+}
+
+impl Mul for Mersenne31Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let [a, b] = self.0;
+        let [c, d] = rhs.0;
+        Self([a * c - b * d, a * d + b * c])
+    }
+}
+
+impl Mul<Mersenne31> for Mersenne31Complex {
+    type Output = Self;
+
+    fn mul(self, rhs: Mersenne31) -> Self::Output {
+        Self([self.0[0] * rhs, self.0[1] * rhs])
+    }
+}
+
+impl Div for Mersenne31Complex {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl AddAssign for Mersenne31Complex {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Mersenne31Complex {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Mersenne31Complex {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Mersenne31Complex {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for Mersenne31Complex {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Product for Mersenne31Complex {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Sample for Mersenne31Complex {
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        Self([Mersenne31::sample(rng), Mersenne31::sample(rng)])
+    }
+}
+
+impl Ring for Mersenne31Complex {
+    const ONE: Self = Self::ONE;
+    const ZERO: Self = Self::ZERO;
+}
+
+impl Algebra<Mersenne31> for Mersenne31Complex {}
+
+impl Extension<Mersenne31> for Mersenne31Complex {
+    const D: usize = 2;
+
+    fn as_base_slice(&self) -> &[Mersenne31] {
+        &self.0
+    }
+
+    fn from_base_slice(elements: &[Mersenne31]) -> Self {
+        assert_eq!(elements.len(), 2, "Complex array slice must have length 2");
+        Self([elements[0], elements[1]])
+    }
+}
+
+impl ExtensionField<Mersenne31> for Mersenne31Complex {}
+
+impl Field for Mersenne31Complex {
+    fn try_inverse(&self) -> Option<Self> {
+        self.try_inverse()
+    }
+    fn from_canonical_u8(n: u8) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_u8(n))
+    }
+    fn from_canonical_u16(n: u16) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_u16(n))
+    }
+    fn from_canonical_u32(n: u32) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_u32(n))
+    }
+    fn from_canonical_u64(n: u64) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_u64(n))
+    }
+    fn from_canonical_usize(n: usize) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_usize(n))
+    }
+
+    fn from_noncanonical_biguint(n: num::BigUint) -> Self {
+        Self::from_base_field(Mersenne31::from_noncanonical_biguint(n))
+    }
+
+    fn primitive_root_of_unity(_n_log: usize) -> Self {
+        unimplemented!("Mersenne31Complex::primitive_root_of_unity")
+    }
+
+    fn two_adic_subgroup(_n_log: usize) -> Vec<Self> {
+        unimplemented!("Mersenne31Complex::two_adic_subgroup")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::field::tests::field_test;
+
+    type F = Mersenne31;
+
+    #[test]
+    fn test_cm31_field() {
+        for _ in 0..100 {
+            field_test::<CM31>();
+        }
+    }
+
+    #[test]
+    fn test_cm31_arithmetic() {
+        let i = CM31::new(F::ZERO, F::ONE);
+        assert_eq!(i * i, -CM31::ONE);
+
+        for _ in 0..100 {
+            let a = CM31::rand();
+            assert_eq!(a * a.conjugate(), CM31::from(a.norm()));
+            if a != CM31::ZERO {
+                assert_eq!(a * a.inverse(), CM31::ONE);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cm31_frobenius() {
+        for _ in 0..10 {
+            let a = CM31::rand();
+            assert_eq!(a.frobenius(), a.pow(F::order()));
+            assert_eq!(a.frobenius().frobenius(), a);
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use core::fmt::{self, Display, Formatter};
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num::BigUint;
+use serde::{Deserialize, Serialize};
+
+use crate::math::prelude::*;
+
+/// The Mersenne-31 field of order `2^31 - 1`.
+///
+/// Elements are kept in canonical form, in the range `0..2^31 - 1`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Mersenne31(u32);
+
+impl Mersenne31 {
+    /// The order of the field, `2^31 - 1`.
+    pub const ORDER: u32 = (1 << 31) - 1;
+
+    /// The element `value mod 2^31 - 1`.
+    #[inline]
+    pub const fn new(value: u32) -> Self {
+        Self::reduce_u32(value)
+    }
+
+    /// Reduces a value in `0..2^32` using `2^31 = 1`.
+    #[inline]
+    const fn reduce_u32(value: u32) -> Self {
+        let folded = (value & Self::ORDER) + (value >> 31);
+        if folded >= Self::ORDER {
+            Self(folded - Self::ORDER)
+        } else {
+            Self(folded)
+        }
+    }
+
+    /// Reduces a value in `0..2^62`, such as the product of two canonical elements.
+    #[inline]
+    const fn reduce_u64(value: u64) -> Self {
+        let folded = (value & Self::ORDER as u64) + (value >> 31);
+        Self::reduce_u32(folded as u32)
+    }
+}
+
+impl Display for Mersenne31 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Add for Mersenne31 {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::reduce_u32(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Mersenne31 {
+    type Output = Self;
+
+    #[inline]
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + (-rhs)
+    }
+}
+
+impl Neg for Mersenne31 {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        if self.0 == 0 {
+            self
+        } else {
+            Self(Self::ORDER - self.0)
+        }
+    }
+}
+
+impl Mul for Mersenne31 {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::reduce_u64(self.0 as u64 * rhs.0 as u64)
+    }
+}
+
+impl Div for Mersenne31 {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl AddAssign for Mersenne31 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Mersenne31 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Mersenne31 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Mersenne31 {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for Mersenne31 {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl<'a> Sum<&'a Self> for Mersenne31 {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + *x)
+    }
+}
+
+impl Product for Mersenne31 {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a Self> for Mersenne31 {
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * *x)
+    }
+}
+
+impl Ring for Mersenne31 {
+    const ONE: Self = Self(1);
+    const ZERO: Self = Self(0);
+}
+
+impl Field for Mersenne31 {
+    fn try_inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        // By Fermat's little theorem, `a^(p - 2)` is the inverse of `a`.
+        Some(self.pow(Self::ORDER as u64 - 2))
+    }
+
+    fn from_canonical_u8(n: u8) -> Self {
+        Self(n as u32)
+    }
+
+    fn from_canonical_u16(n: u16) -> Self {
+        Self(n as u32)
+    }
+
+    fn from_canonical_u32(n: u32) -> Self {
+        debug_assert!(n < Self::ORDER, "{n} is not canonical");
+        Self::new(n)
+    }
+
+    fn from_canonical_u64(n: u64) -> Self {
+        debug_assert!(n < Self::ORDER as u64, "{n} is not canonical");
+        Self::new((n % Self::ORDER as u64) as u32)
+    }
+
+    fn from_canonical_usize(n: usize) -> Self {
+        Self::from_canonical_u64(n as u64)
+    }
+
+    fn from_noncanonical_biguint(n: BigUint) -> Self {
+        let reduced = n % BigUint::from(Self::ORDER);
+        Self(reduced.to_u32_digits().first().copied().unwrap_or(0))
+    }
+
+    /// The multiplicative group has order `2 * (2^30 - 1)`, so its only two-adic subgroups are
+    /// of order one and two.
+    fn primitive_root_of_unity(n_log: usize) -> Self {
+        match n_log {
+            0 => Self::ONE,
+            1 => -Self::ONE,
+            _ => panic!("Mersenne31 has no root of unity of order 2^{n_log}"),
+        }
+    }
+
+    fn two_adic_subgroup(n_log: usize) -> Vec<Self> {
+        let generator = Self::primitive_root_of_unity(n_log);
+        generator.powers().take(1 << n_log).collect()
+    }
+}
+
+impl PrimeField for Mersenne31 {}
+
+impl PrimeField64 for Mersenne31 {
+    fn as_canonical_u64(&self) -> u64 {
+        self.0 as u64
+    }
+}
+
+impl PrimeField32 for Mersenne31 {
+    fn as_canonical_u32(&self) -> u32 {
+        self.0
+    }
+}
+
+impl Sample for Mersenne31 {
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        // Rejection sampling of 31-bit values, which is uniform over the field.
+        loop {
+            let value = rng.next_u32() >> 1;
+            if value < Self::ORDER {
+                return Self(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::field::tests::field_test;
+
+    type F = Mersenne31;
+
+    #[test]
+    fn test_mersenne31_field() {
+        for _ in 0..100 {
+            field_test::<F>();
+        }
+    }
+
+    #[test]
+    fn test_mersenne31_reduction() {
+        let p_minus_one = -F::ONE;
+        assert_eq!(p_minus_one.as_canonical_u32(), F::ORDER - 1);
+        assert_eq!(F::order(), F::ORDER as u64);
+
+        assert_eq!(F::new(F::ORDER), F::ZERO);
+        assert_eq!(F::new(u32::MAX), F::ONE);
+        assert_eq!(p_minus_one + F::ONE, F::ZERO);
+        assert_eq!(F::ZERO - F::ONE, p_minus_one);
+        assert_eq!(p_minus_one * p_minus_one, F::ONE);
+        assert_eq!(
+            F::from_noncanonical_biguint(BigUint::from(u64::MAX)),
+            F::new((u64::MAX % F::ORDER as u64) as u32)
+        );
+
+        // `2^31 = 1` in the field.
+        assert_eq!(F::from_canonical_u32(2).pow(31), F::ONE);
+    }
+
+    #[test]
+    fn test_mersenne31_inverse() {
+        assert_eq!(F::ZERO.try_inverse(), None);
+        for _ in 0..100 {
+            let a = F::rand();
+            if !a.is_zero() {
+                assert_eq!(a * a.inverse(), F::ONE);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mersenne31_roots_of_unity() {
+        assert_eq!(F::two_adic_subgroup(1), vec![F::ONE, -F::ONE]);
+    }
+}
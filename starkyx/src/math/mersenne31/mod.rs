@@ -0,0 +1,3 @@
+pub mod complex;
+pub mod field;
+pub mod quartic;
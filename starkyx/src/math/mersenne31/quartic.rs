@@ -0,0 +1,341 @@
+use core::iter::{Product, Sum};
+use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use super::complex::Mersenne31Complex;
+use super::field::Mersenne31;
+use crate::math::prelude::*;
+
+pub type QM31 = Mersenne31Quartic;
+
+/// The quartic extension of Mersenne-31, CM31[u]/(u^2 - (2 + i)).
+///
+/// The polynomial `X^3 - X - 1` of `CubicParameters` has the root `2045307031` in Mersenne-31,
+/// so the field has no such cubic extension and challenges are drawn from this extension instead.
+///
+/// The element `a + b u` is stored as the coordinates `[a_0, a_1, b_0, b_1]` over Mersenne-31.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Mersenne31Quartic(pub [Mersenne31; 4]);
+
+impl Mersenne31Quartic {
+    pub const ZERO: Self = Self([Mersenne31::ZERO; 4]);
+    pub const ONE: Self = Self([
+        Mersenne31::ONE,
+        Mersenne31::ZERO,
+        Mersenne31::ZERO,
+        Mersenne31::ZERO,
+    ]);
+
+    /// The non-square `2 + i` of CM31 whose square root is `u`.
+    pub const NON_RESIDUE: Mersenne31Complex =
+        Mersenne31Complex::new(Mersenne31::new(2), Mersenne31::new(1));
+
+    /// The constant `(2 + i)^((p - 1) / 2)`, so that `u^p = FROBENIUS_U * u`.
+    const FROBENIUS_U: Mersenne31Complex =
+        Mersenne31Complex::new(Mersenne31::new(21189756), Mersenne31::new(42379512));
+
+    pub const fn new(a: Mersenne31Complex, b: Mersenne31Complex) -> Self {
+        Self([a.0[0], a.0[1], b.0[0], b.0[1]])
+    }
+
+    pub const fn from_base_field(a: Mersenne31) -> Self {
+        Self::new(
+            Mersenne31Complex::from_base_field(a),
+            Mersenne31Complex::ZERO,
+        )
+    }
+
+    /// The element `a + b u` as the pair `(a, b)` of CM31 coefficients.
+    #[inline]
+    pub const fn as_complex_pair(&self) -> (Mersenne31Complex, Mersenne31Complex) {
+        (
+            Mersenne31Complex::new(self.0[0], self.0[1]),
+            Mersenne31Complex::new(self.0[2], self.0[3]),
+        )
+    }
+
+    #[inline]
+    pub const fn base_field_array(&self) -> [Mersenne31; 4] {
+        self.0
+    }
+
+    /// The image of `self` under the Frobenius automorphism `x -> x^p`.
+    ///
+    /// The Frobenius conjugates the CM31 coefficients and maps `u` to `FROBENIUS_U * u`.
+    pub fn frobenius(&self) -> Self {
+        let (a, b) = self.as_complex_pair();
+        Self::new(a.conjugate(), b.conjugate() * Self::FROBENIUS_U)
+    }
+
+    /// The image of `self` under the `count`-th power of the Frobenius automorphism.
+    pub fn repeated_frobenius(&self, count: usize) -> Self {
+        (0..count % 4).fold(*self, |acc, _| acc.frobenius())
+    }
+
+    pub fn try_inverse(&self) -> Option<Self> {
+        // The inverse of `a + b u` is `(a - b u) / (a^2 - (2 + i) b^2)`, where the denominator is
+        // the norm to CM31.
+        let (a, b) = self.as_complex_pair();
+        let norm = a.square() - Self::NON_RESIDUE * b.square();
+        let norm_inv = norm.try_inverse()?;
+        Some(Self::new(a * norm_inv, -b * norm_inv))
+    }
+
+    pub fn inverse(&self) -> Self {
+        self.try_inverse().expect("Cannot invert zero")
+    }
+}
+
+impl From<Mersenne31> for Mersenne31Quartic {
+    fn from(value: Mersenne31) -> Self {
+        Self::from_base_field(value)
+    }
+}
+
+impl From<Mersenne31Complex> for Mersenne31Quartic {
+    fn from(value: Mersenne31Complex) -> Self {
+        Self::new(value, Mersenne31Complex::ZERO)
+    }
+}
+
+impl From<[Mersenne31; 4]> for Mersenne31Quartic {
+    fn from(value: [Mersenne31; 4]) -> Self {
+        Self(value)
+    }
+}
+
+impl Add for Mersenne31Quartic {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(core::array::from_fn(|i| self.0[i] + rhs.0[i]))
+    }
+}
+
+impl Add<Mersenne31> for Mersenne31Quartic {
+    type Output = Self;
+
+    fn add(self, rhs: Mersenne31) -> Self::Output {
+        self + Self::from_base_field(rhs)
+    }
+}
+
+impl Sub for Mersenne31Quartic {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(core::array::from_fn(|i| self.0[i] - rhs.0[i]))
+    }
+}
+
+impl Sub<Mersenne31> for Mersenne31Quartic {
+    type Output = Self;
+
+    fn sub(self, rhs: Mersenne31) -> Self::Output {
+        self - Self::from_base_field(rhs)
+    }
+}
+
+impl Neg for Mersenne31Quartic {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.map(|x| -x))
+    }
+}
+
+impl Mul for Mersenne31Quartic {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        // (a + b u)(c + d u) = (ac + (2 + i) bd) + (ad + bc) u.
+        let (a, b) = self.as_complex_pair();
+        let (c, d) = rhs.as_complex_pair();
+        Self::new(a * c + Self::NON_RESIDUE * b * d, a * d + b * c)
+    }
+}
+
+impl Mul<Mersenne31> for Mersenne31Quartic {
+    type Output = Self;
+
+    fn mul(self, rhs: Mersenne31) -> Self::Output {
+        Self(self.0.map(|x| x * rhs))
+    }
+}
+
+impl Div for Mersenne31Quartic {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self::Output {
+        self * rhs.inverse()
+    }
+}
+
+impl AddAssign for Mersenne31Quartic {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Mersenne31Quartic {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl MulAssign for Mersenne31Quartic {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl DivAssign for Mersenne31Quartic {
+    fn div_assign(&mut self, rhs: Self) {
+        *self = *self / rhs;
+    }
+}
+
+impl Sum for Mersenne31Quartic {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |acc, x| acc + x)
+    }
+}
+
+impl Product for Mersenne31Quartic {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ONE, |acc, x| acc * x)
+    }
+}
+
+impl Sample for Mersenne31Quartic {
+    fn sample<R>(rng: &mut R) -> Self
+    where
+        R: rand::RngCore + ?Sized,
+    {
+        Self(core::array::from_fn(|_| Mersenne31::sample(rng)))
+    }
+}
+
+impl Ring for Mersenne31Quartic {
+    const ONE: Self = Self::ONE;
+    const ZERO: Self = Self::ZERO;
+}
+
+impl Algebra<Mersenne31> for Mersenne31Quartic {}
+
+impl Extension<Mersenne31> for Mersenne31Quartic {
+    const D: usize = 4;
+
+    fn as_base_slice(&self) -> &[Mersenne31] {
+        &self.0
+    }
+
+    fn from_base_slice(elements: &[Mersenne31]) -> Self {
+        assert_eq!(elements.len(), 4, "Quartic array slice must have length 4");
+        Self(core::array::from_fn(|i| elements[i]))
+    }
+}
+
+impl ExtensionField<Mersenne31> for Mersenne31Quartic {}
+
+impl Field for Mersenne31Quartic {
+    fn try_inverse(&self) -> Option<Self> {
+        self.try_inverse()
+    }
+    fn from_canonical_u8(n: u8) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_u8(n))
+    }
+    fn from_canonical_u16(n: u16) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_u16(n))
+    }
+    fn from_canonical_u32(n: u32) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_u32(n))
+    }
+    fn from_canonical_u64(n: u64) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_u64(n))
+    }
+    fn from_canonical_usize(n: usize) -> Self {
+        Self::from_base_field(Mersenne31::from_canonical_usize(n))
+    }
+
+    fn from_noncanonical_biguint(n: num::BigUint) -> Self {
+        Self::from_base_field(Mersenne31::from_noncanonical_biguint(n))
+    }
+
+    fn primitive_root_of_unity(_n_log: usize) -> Self {
+        unimplemented!("Mersenne31Quartic::primitive_root_of_unity")
+    }
+
+    fn two_adic_subgroup(_n_log: usize) -> Vec<Self> {
+        unimplemented!("Mersenne31Quartic::two_adic_subgroup")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::field::tests::field_test;
+
+    type F = Mersenne31;
+
+    #[test]
+    fn test_qm31_field() {
+        for _ in 0..100 {
+            field_test::<QM31>();
+        }
+    }
+
+    #[test]
+    fn test_no_cubic_extension() {
+        // `X^3 - X - 1` has a root in the base field, so it is not irreducible.
+        let root = F::new(2045307031);
+        assert_eq!(root * root * root - root - F::ONE, F::ZERO);
+    }
+
+    #[test]
+    fn test_qm31_arithmetic() {
+        let u = QM31::new(Mersenne31Complex::ZERO, Mersenne31Complex::ONE);
+        assert_eq!(u * u, QM31::from(QM31::NON_RESIDUE));
+
+        for _ in 0..100 {
+            let a = QM31::rand();
+            let b = QM31::rand();
+            let c = QM31::rand();
+
+            assert_eq!(a.square(), a * a);
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * (b + c), a * b + a * c);
+            if b != QM31::ZERO {
+                assert_eq!((a / b) * b, a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_qm31_inverse() {
+        assert_eq!(QM31::ZERO.try_inverse(), None);
+        for _ in 0..100 {
+            let a = QM31::rand();
+            assert_eq!(a * a.inverse(), QM31::ONE);
+        }
+    }
+
+    #[test]
+    fn test_qm31_frobenius() {
+        // The quartic field has order p^4, so `u^(p^2) = -u` and the fourth power of the
+        // Frobenius is the identity.
+        let u = QM31::new(Mersenne31Complex::ZERO, Mersenne31Complex::ONE);
+        assert_ne!(u.frobenius(), u);
+        assert_eq!(u.repeated_frobenius(2), -u);
+
+        for _ in 0..10 {
+            let a = QM31::rand();
+            let b = QM31::rand();
+            assert_eq!(a.frobenius(), a.pow(F::order()));
+            assert_eq!((a * b).frobenius(), a.frobenius() * b.frobenius());
+            assert_eq!(a.repeated_frobenius(4), a);
+        }
+    }
+}
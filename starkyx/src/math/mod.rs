@@ -2,6 +2,7 @@ pub mod algebra;
 pub mod extension;
 pub mod field;
 pub mod goldilocks;
+pub mod mersenne31;
 
 pub mod prelude {
     pub use super::algebra::*;
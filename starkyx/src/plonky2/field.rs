@@ -12,7 +12,7 @@ impl<F: Plonky2Field> Ring for F {
 
 impl<F: Plonky2Field> Field for F {
     fn try_inverse(&self) -> Option<Self> {
-        Some(self.inverse())
+        Plonky2Field::try_inverse(self)
     }
     fn from_canonical_u8(n: u8) -> Self {
         F::from_canonical_u8(n)
@@ -7,8 +7,10 @@ use plonky2::iop::target::Target;
 use plonky2::plonk::circuit_builder::CircuitBuilder;
 
 use crate::air::extension::cubic::CubicParser;
+use crate::air::extension::quintic::QuinticParser;
 use crate::air::parser::AirParser;
 use crate::math::extension::cubic::parameters::CubicParameters;
+use crate::math::extension::quintic::parameters::QuinticParameters;
 use crate::math::prelude::cubic::element::CubicElement;
 use crate::plonky2::cubic::builder::CubicCircuitBuilder;
 use crate::plonky2::cubic::operations::CubicOperation;
@@ -134,6 +136,15 @@ where
 {
 }
 
+impl<'a, F, FE, E: QuinticParameters<F>, P, const D: usize, const D2: usize> QuinticParser<E>
+    for GlobalStarkParser<'a, F, FE, P, D, D2>
+where
+    F: RichField + Extendable<D>,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+}
+
 impl<'a, F: RichField + Extendable<D>, const D: usize> AirParser
     for GlobalRecursiveStarkParser<'a, F, D>
 {
@@ -250,3 +261,8 @@ impl<'a, F: RichField + Extendable<D>, E: CubicParameters<F>, const D: usize> Cu
         self.builder.scalar_mul_cubic(a, scalar, self.cubic_results)
     }
 }
+
+impl<'a, F: RichField + Extendable<D>, E: QuinticParameters<F>, const D: usize> QuinticParser<E>
+    for GlobalRecursiveStarkParser<'a, F, D>
+{
+}
@@ -9,8 +9,10 @@ use plonky2::plonk::circuit_builder::CircuitBuilder;
 
 use self::consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 use crate::air::extension::cubic::CubicParser;
+use crate::air::extension::quintic::QuinticParser;
 use crate::air::parser::AirParser;
 use crate::math::extension::cubic::parameters::CubicParameters;
+use crate::math::extension::quintic::parameters::QuinticParameters;
 use crate::polynomial::parser::PolynomialParser;
 
 pub struct StarkParser<'a, F, FE, P, const D: usize, const D2: usize>
@@ -136,6 +138,15 @@ where
 {
 }
 
+impl<'a, F, FE, E: QuinticParameters<F>, P, const D: usize, const D2: usize> QuinticParser<E>
+    for StarkParser<'a, F, FE, P, D, D2>
+where
+    F: RichField + Extendable<D>,
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+}
+
 impl<'a, F: RichField + Extendable<D>, const D: usize> AirParser
     for RecursiveStarkParser<'a, F, D>
 {
@@ -223,3 +234,8 @@ impl<'a, F: RichField + Extendable<D>, E: CubicParameters<F>, const D: usize> Cu
     for RecursiveStarkParser<'a, F, D>
 {
 }
+
+impl<'a, F: RichField + Extendable<D>, E: QuinticParameters<F>, const D: usize> QuinticParser<E>
+    for RecursiveStarkParser<'a, F, D>
+{
+}
@@ -163,10 +163,12 @@ pub(crate) mod tests {
     use crate::chip::builder::tests::ArithmeticGenerator;
     use crate::chip::{AirParameters, Chip};
     use crate::math::prelude::*;
-    use crate::plonky2::stark::config::PoseidonGoldilocksStarkConfig;
+    use crate::plonky2::stark::config::{
+        CurtaPoseidonGoldilocksConfig, PoseidonGoldilocksStarkConfig,
+    };
     use crate::plonky2::stark::gadget::StarkGadget;
     use crate::plonky2::stark::prover::StarkyProver;
-    use crate::plonky2::stark::verifier::StarkyVerifier;
+    use crate::plonky2::stark::verifier::{set_stark_proof_target, StarkyVerifier};
     use crate::plonky2::{Plonky2Air, StarkyAir};
     use crate::trace::generator::{ConstantGenerator, TraceGenerator};
 
@@ -264,4 +266,48 @@ pub(crate) mod tests {
         // Generate proof and verify as a stark
         test_starky(&stark, &config, &trace_generator, &public_inputs);
     }
+
+    #[test]
+    fn test_plonky2_fibonacci_recursive_stark() {
+        type F = GoldilocksField;
+        type C = CurtaPoseidonGoldilocksConfig;
+        type SC = PoseidonGoldilocksStarkConfig;
+        const D: usize = 2;
+
+        let num_rows = 1 << 5usize;
+        let stark = Starky::<FibonacciAir>::new(FibonacciAir::new());
+
+        let public_inputs = [
+            F::ZERO,
+            F::ONE,
+            FibonacciAir::fibonacci(num_rows - 1, F::ZERO, F::ONE),
+        ];
+
+        let trace = FibonacciAir::generate_trace(F::ZERO, F::ONE, num_rows);
+        let trace_generator = ConstantGenerator::new(trace);
+
+        let config = SC::standard_fast_config(num_rows);
+        let proof =
+            StarkyProver::<F, C, D>::prove(&config, &stark, &trace_generator, &public_inputs)
+                .unwrap();
+
+        // The constraints of the AIR are evaluated in the circuit by the recursive parsers.
+        let mut builder = CircuitBuilder::<F, D>::new(CircuitConfig::standard_recursion_config());
+        let proof_target = builder.add_virtual_stark_proof(&stark, &config);
+        let public_input_targets = builder.add_virtual_targets(public_inputs.len());
+        builder.verify_stark_proof(&config, &stark, &proof_target, &public_input_targets);
+        let data = builder.build::<<C as CurtaConfig<D>>::GenericConfig>();
+
+        let mut pw = PartialWitness::new();
+        for (&pi_t, &pi) in public_input_targets.iter().zip(public_inputs.iter()) {
+            pw.set_target(pi_t, pi);
+        }
+        set_stark_proof_target(&mut pw, &proof_target, &proof);
+
+        let mut timing = TimingTree::new("fibonacci_recursive_proof", log::Level::Debug);
+        let recursive_proof =
+            plonky2::plonk::prover::prove(&data.prover_only, &data.common, pw, &mut timing)
+                .unwrap();
+        data.verify(recursive_proof).unwrap();
+    }
 }
@@ -1,5 +1,6 @@
 use super::window::TraceWindow;
 use crate::air::extension::cubic::CubicParser;
+use crate::air::extension::quintic::QuinticParser;
 use crate::air::parser::AirParser;
 use crate::math::prelude::*;
 use crate::polynomial::parser::PolynomialParser;
@@ -119,3 +120,5 @@ impl<'a, F: Field> AirParser for TraceWindowParser<'a, F> {
 impl<'a, F: Field> PolynomialParser for TraceWindowParser<'a, F> {}
 
 impl<'a, F: Field, E: CubicParameters<F>> CubicParser<E> for TraceWindowParser<'a, F> {}
+
+impl<'a, F: Field, E: QuinticParameters<F>> QuinticParser<E> for TraceWindowParser<'a, F> {}
@@ -0,0 +1,194 @@
+//! The pure BLAKE2b hash function, unkeyed and with a 32-byte output.
+//!
+//! This is the reference used by the BLAKE2b AIR and by the hash of the structure of an AIR.
+
+pub const IV: [u64; 8] = [
+    0x6a09e667f2bdc928,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+// Note that for this blake2b implementation, we don't support a key input and
+// we assume that the output is 32 bytes
+// So that means the initial hash entry to be
+// 0x6a09e667f3bcc908 xor 0x01010020
+pub const COMPRESS_IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+pub(crate) const SIGMA_PERMUTATIONS: [[u8; 16]; 12] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+];
+
+/// Compresses the 128-byte `msg_chunk` into `state`, with `compress_iv` in the second half of
+/// the work vector, returning the new state.
+pub fn compress(
+    msg_chunk: &[u8],
+    state: &mut [u64; 8],
+    bytes_compressed: u64,
+    last_chunk: bool,
+    compress_iv: &[u64; 8],
+) -> [u64; 8] {
+    // Set up the work vector V
+    let mut v: [u64; 16] = [0; 16];
+
+    v[..8].copy_from_slice(&state[..]);
+    v[8..16].copy_from_slice(compress_iv);
+
+    v[12] ^= bytes_compressed;
+    if last_chunk {
+        v[14] ^= 0xFFFFFFFFFFFFFFFF;
+    }
+
+    let msg_u64_chunks = msg_chunk
+        .chunks_exact(8)
+        .map(|x| u64::from_le_bytes(x.try_into().unwrap()))
+        .collect::<Vec<_>>();
+
+    for s in SIGMA_PERMUTATIONS.iter() {
+        mix(
+            &mut v,
+            0,
+            4,
+            8,
+            12,
+            msg_u64_chunks[s[0] as usize],
+            msg_u64_chunks[s[1] as usize],
+        );
+        mix(
+            &mut v,
+            1,
+            5,
+            9,
+            13,
+            msg_u64_chunks[s[2] as usize],
+            msg_u64_chunks[s[3] as usize],
+        );
+        mix(
+            &mut v,
+            2,
+            6,
+            10,
+            14,
+            msg_u64_chunks[s[4] as usize],
+            msg_u64_chunks[s[5] as usize],
+        );
+        mix(
+            &mut v,
+            3,
+            7,
+            11,
+            15,
+            msg_u64_chunks[s[6] as usize],
+            msg_u64_chunks[s[7] as usize],
+        );
+
+        mix(
+            &mut v,
+            0,
+            5,
+            10,
+            15,
+            msg_u64_chunks[s[8] as usize],
+            msg_u64_chunks[s[9] as usize],
+        );
+        mix(
+            &mut v,
+            1,
+            6,
+            11,
+            12,
+            msg_u64_chunks[s[10] as usize],
+            msg_u64_chunks[s[11] as usize],
+        );
+        mix(
+            &mut v,
+            2,
+            7,
+            8,
+            13,
+            msg_u64_chunks[s[12] as usize],
+            msg_u64_chunks[s[13] as usize],
+        );
+        mix(
+            &mut v,
+            3,
+            4,
+            9,
+            14,
+            msg_u64_chunks[s[14] as usize],
+            msg_u64_chunks[s[15] as usize],
+        );
+    }
+
+    for i in 0..8 {
+        state[i] ^= v[i];
+    }
+
+    for i in 0..8 {
+        state[i] ^= v[i + 8];
+    }
+
+    *state
+}
+
+/// The mixing function `G` on the words `a`, `b`, `c` and `d` of the work vector `v`.
+#[allow(clippy::too_many_arguments)]
+pub fn mix(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+/// The BLAKE2b digest of `msg`.
+pub fn hash(msg: &[u8]) -> [u8; 32] {
+    let num_chunks = ((msg.len() + 127) / 128).max(1);
+    let mut padded_msg = msg.to_vec();
+    padded_msg.resize(num_chunks * 128, 0);
+    let mut state = IV;
+    for (i, chunk) in padded_msg.chunks_exact(128).enumerate() {
+        let is_last = i == num_chunks - 1;
+        let bytes_compressed = if is_last { msg.len() } else { 128 * (i + 1) };
+        compress(
+            chunk,
+            &mut state,
+            bytes_compressed as u64,
+            is_last,
+            &COMPRESS_IV,
+        );
+    }
+
+    let mut digest = [0u8; 32];
+    for (bytes, word) in digest.chunks_exact_mut(8).zip(state) {
+        bytes.copy_from_slice(&word.to_le_bytes());
+    }
+    digest
+}
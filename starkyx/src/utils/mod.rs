@@ -1,2 +1,3 @@
+pub mod blake2b;
 pub mod serde;
 pub mod watcher;